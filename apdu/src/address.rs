@@ -0,0 +1,480 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Address verification APDUs, for displaying a subaddress on-device
+//! and returning a signed acknowledgement following user confirmation.
+//!
+//! See [AddressGetReq] for fetching the signed address following approval,
+//! or [PublicAddressReq] for fetching b58 encoded public addresses
+//! without on-device verification.
+
+use encdec::{Decode, DecodeOwned, Encode};
+
+use mc_core::keys::{SubaddressSpendPublic, SubaddressViewPublic};
+
+use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
+use crate::{helpers::*, tx::FogId};
+
+/// Request verification of the address for a given account, subaddress and fog,
+/// returning a state object.
+///
+/// The address is derived and displayed on the device, following approval
+/// the signed address may be fetched with [AddressGetReq].
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                       SUBADDRESS_INDEX                        |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |    FOG_ID     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                           CHALLENGE                           /
+/// /                           (32-byte)                           /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct AddressVerifyReq {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// Subaddress index
+    pub subaddress_index: u64,
+    /// Fog ID for address
+    pub fog_id: FogId,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 3],
+    /// Host provided challenge, included in the signed acknowledgement
+    #[encdec(with = "arr")]
+    pub challenge: [u8; 32],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    AddressVerifyReq {
+        account_index,
+        subaddress_index,
+        fog_id,
+        challenge,
+    } reserved { _reserved }
+);
+
+impl AddressVerifyReq {
+    /// Create a new [AddressVerifyReq] APDU
+    pub fn new(
+        account_index: u32,
+        subaddress_index: u64,
+        fog_id: FogId,
+        challenge: [u8; 32],
+    ) -> Self {
+        Self {
+            account_index,
+            subaddress_index,
+            fog_id,
+            _reserved: [0u8; 3],
+            challenge,
+        }
+    }
+}
+
+impl ApduStatic for AddressVerifyReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::AddressVerify as u8;
+}
+
+/// Fetch a verified address following approval (0 length APDU)
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct AddressGetReq;
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(AddressGetReq {});
+
+impl ApduStatic for AddressGetReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::AddressGet as u8;
+}
+
+/// Verified address response APDU
+///
+/// Contains the derived public address with fog information, and a
+/// schnorrkel signature by the subaddress spend private key over the
+/// address short hash and host challenge.
+///
+/// `FOG_AUTHORITY_SIG` is zeroed where `FOG_ID` is `None`.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                    SUBADDR_VIEW_PUBLIC_KEY                    /
+/// /                 (32-byte Ristretto Public Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                    SUBADDR_SPEND_PUBLIC_KEY                   /
+/// /                 (32-byte Ristretto Public Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |    FOG_ID     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                       FOG_AUTHORITY_SIG                       /
+/// /               (64-byte Fog Authority Signature)               /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                           SIGNATURE                           /
+/// /                 (64-byte Schnorrkel Signature)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct AddressResp {
+    /// Subaddress view public key
+    #[encdec(with = "pub_key")]
+    pub view_public: SubaddressViewPublic,
+    /// Subaddress spend public key
+    #[encdec(with = "pub_key")]
+    pub spend_public: SubaddressSpendPublic,
+    /// Fog ID for address
+    pub fog_id: FogId,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 3],
+    /// Fog authority signature (zeroed if no fog is set)
+    #[encdec(with = "arr")]
+    pub fog_sig: [u8; 64],
+    /// Signature over address short hash and challenge
+    #[encdec(with = "arr")]
+    pub signature: [u8; 64],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    AddressResp {
+        view_public,
+        spend_public,
+        fog_id,
+        fog_sig,
+        signature,
+    } reserved { _reserved }
+);
+
+impl AddressResp {
+    /// Create a new [`AddressResp`] APDU
+    pub fn new(
+        view_public: SubaddressViewPublic,
+        spend_public: SubaddressSpendPublic,
+        fog_id: FogId,
+        fog_sig: [u8; 64],
+        signature: [u8; 64],
+    ) -> Self {
+        Self {
+            view_public,
+            spend_public,
+            fog_id,
+            _reserved: [0u8; 3],
+            fog_sig,
+            signature,
+        }
+    }
+}
+
+/// Maximum length of a b58 encoded public address in a [PublicAddressResp]
+pub const PUBLIC_ADDRESS_MAX: usize = 512;
+
+/// Request the b58 encoded public address for a given account, subaddress and fog,
+/// returning a [PublicAddressResp].
+///
+/// Encoded addresses may exceed the maximum APDU payload, so this
+/// should be issued using the chunked exchange (see [crate::chunk]).
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                       SUBADDRESS_INDEX                        |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |    FOG_ID     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct PublicAddressReq {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// Subaddress index
+    pub subaddress_index: u64,
+    /// Fog ID for address
+    pub fog_id: FogId,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 3],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    PublicAddressReq {
+        account_index,
+        subaddress_index,
+        fog_id,
+    } reserved { _reserved }
+);
+
+impl PublicAddressReq {
+    /// Create a new [PublicAddressReq] APDU
+    pub fn new(account_index: u32, subaddress_index: u64, fog_id: FogId) -> Self {
+        Self {
+            account_index,
+            subaddress_index,
+            fog_id,
+            _reserved: [0u8; 3],
+        }
+    }
+}
+
+impl ApduStatic for PublicAddressReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetPublicAddress as u8;
+}
+
+/// Public address response APDU
+///
+/// Contains the b58 encoded public address (`PrintableWrapper { PublicAddress }`)
+/// with the fog information used in its construction.
+///
+/// `FOG_AUTHORITY_SIG` is zeroed where `FOG_ID` is `None`.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |    FOG_ID     |   RESERVED    |          ADDRESS_LEN          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                       FOG_AUTHORITY_SIG                       /
+/// /               (64-byte Fog Authority Signature)               /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                        B58_ADDRESS                            /
+/// /                      (variable length)                        /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct PublicAddressResp<'a> {
+    /// Fog ID for address
+    pub fog_id: FogId,
+    /// Fog authority signature (zeroed if no fog is set)
+    pub fog_sig: [u8; 64],
+    /// b58 encoded public address
+    pub address: &'a str,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(PublicAddressResp<'a> { fog_id, fog_sig, address });
+
+/// Length of [PublicAddressResp] header and fog signature
+const PUBLIC_ADDRESS_HEADER_LEN: usize = 4 + 64;
+
+impl<'a> PublicAddressResp<'a> {
+    /// Create a new [PublicAddressResp] APDU
+    pub fn new(fog_id: FogId, fog_sig: [u8; 64], address: &'a str) -> Self {
+        Self {
+            fog_id,
+            fog_sig,
+            address,
+        }
+    }
+}
+
+impl<'a> Encode for PublicAddressResp<'a> {
+    type Error = ApduError;
+
+    /// Encode a [PublicAddressResp] APDU into the provided buffer
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        let mut index = 0;
+        let d = self.address.as_bytes();
+
+        // Check address and buffer lengths are valid
+        if d.len() > PUBLIC_ADDRESS_MAX {
+            return Err(ApduError::InvalidLength);
+        }
+        if buff.len() < PUBLIC_ADDRESS_HEADER_LEN + d.len() {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write fog id and padding
+        index += self.fog_id.encode(&mut buff[index..])?;
+        buff[index] = 0;
+        index += 1;
+
+        // Write address length
+        index += (d.len() as u16).encode(&mut buff[index..])?;
+
+        // Write fog authority signature
+        buff[index..][..64].copy_from_slice(&self.fog_sig);
+        index += 64;
+
+        // Write address
+        buff[index..][..d.len()].copy_from_slice(d);
+        index += d.len();
+
+        Ok(index)
+    }
+
+    fn encode_len(&self) -> Result<usize, ApduError> {
+        Ok(PUBLIC_ADDRESS_HEADER_LEN + self.address.len())
+    }
+}
+
+impl<'a> Decode<'a> for PublicAddressResp<'a> {
+    type Output = Self;
+    type Error = ApduError;
+
+    /// Decode a [PublicAddressResp] APDU from the provided buffer
+    fn decode(buff: &'a [u8]) -> Result<(Self, usize), ApduError> {
+        let mut index = 0;
+
+        // Check header length
+        if buff.len() < PUBLIC_ADDRESS_HEADER_LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read fog id and skip padding
+        let (fog_id, n) = FogId::decode_owned(&buff[index..])?;
+        index += n + 1;
+
+        // Read address length
+        let (address_len, n) = u16::decode(&buff[index..])?;
+        let address_len = address_len as usize;
+        index += n;
+
+        // Check address and full buffer lengths
+        if address_len > PUBLIC_ADDRESS_MAX || buff.len() < PUBLIC_ADDRESS_HEADER_LEN + address_len
+        {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read fog authority signature
+        let mut fog_sig = [0u8; 64];
+        fog_sig.copy_from_slice(&buff[index..][..64]);
+        index += 64;
+
+        // Read address
+        let address = core::str::from_utf8(&buff[index..][..address_len])
+            .map_err(|_| ApduError::InvalidUtf8)?;
+        index += address_len;
+
+        Ok((
+            Self {
+                fog_id,
+                fog_sig,
+                address,
+            },
+            index,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use mc_crypto_keys::RistrettoPublic;
+    use mc_util_from_random::FromRandom;
+    use rand::{random, rngs::OsRng};
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn address_verify_req_apdu() {
+        let apdu = AddressVerifyReq::new(random(), random(), FogId::MobMain, random());
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn address_get_req_apdu() {
+        let apdu = AddressGetReq;
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn address_resp_apdu() {
+        let mut fog_sig = [0u8; 64];
+        let mut signature = [0u8; 64];
+        fog_sig.iter_mut().for_each(|b| *b = random());
+        signature.iter_mut().for_each(|b| *b = random());
+
+        let apdu = AddressResp::new(
+            RistrettoPublic::from_random(&mut OsRng {}).into(),
+            RistrettoPublic::from_random(&mut OsRng {}).into(),
+            FogId::SignalTest,
+            fog_sig,
+            signature,
+        );
+
+        let mut buff = [0u8; 256];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn public_address_req_apdu() {
+        let apdu = PublicAddressReq::new(random(), random(), FogId::MobTest);
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn public_address_resp_apdu() {
+        let mut fog_sig = [0u8; 64];
+        fog_sig.iter_mut().for_each(|b| *b = random());
+
+        let apdu = PublicAddressResp::new(FogId::MobMain, fog_sig, "abcdefghijkl1234");
+
+        let mut buff = [0u8; 256];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn public_address_resp_long() {
+        // Encoded addresses with fog exceed a single APDU payload
+        let address: heapless::String<PUBLIC_ADDRESS_MAX> =
+            core::iter::repeat('z').take(300).collect();
+        let apdu = PublicAddressResp::new(FogId::SignalMain, [0xab; 64], &address);
+
+        let mut buff = [0u8; 1024];
+        let n = apdu.encode(&mut buff).unwrap();
+        assert_eq!(n, apdu.encode_len().unwrap());
+
+        let (decoded, m) = PublicAddressResp::decode(&buff[..n]).unwrap();
+        assert_eq!(m, n);
+        assert_eq!(decoded, apdu);
+
+        // Truncated buffers must be rejected
+        assert!(matches!(
+            PublicAddressResp::decode(&buff[..n - 1]),
+            Err(ApduError::InvalidLength)
+        ));
+    }
+}
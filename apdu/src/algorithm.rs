@@ -0,0 +1,68 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Key / signature algorithm identifiers
+//!
+//! Key and identity request APDUs carry an explicit algorithm identifier
+//! so future curve or scheme migrations can be expressed without breaking
+//! the wire format. Each request currently accepts only a single value,
+//! with unsupported identifiers rejected at decode time.
+
+use num_enum::TryFromPrimitive;
+use strum::{Display, EnumIter, EnumString, EnumVariantNames};
+
+use crate::{app_info::AppFlags, ApduError};
+
+/// Key / signature algorithm identifier
+#[derive(
+    Copy, Clone, PartialEq, Debug, EnumString, Display, EnumVariantNames, EnumIter, TryFromPrimitive,
+)]
+#[repr(u8)]
+pub enum Algorithm {
+    /// Ristretto255 keys, used for MobileCoin account and subaddress keys
+    Ristretto255 = 0x01,
+
+    /// Ed25519 keys, used for SLIP-0010 derived identities
+    Ed25519 = 0x02,
+}
+
+impl Algorithm {
+    /// Fetch the [AppFlags] bit indicating support for this algorithm
+    pub const fn flag(&self) -> AppFlags {
+        match self {
+            Algorithm::Ristretto255 => AppFlags::ALG_RISTRETTO255,
+            Algorithm::Ed25519 => AppFlags::ALG_ED25519,
+        }
+    }
+
+    /// Check an algorithm matches the expected value, returning
+    /// [ApduError::InvalidEncoding] on mismatch
+    pub fn expect(self, expected: Algorithm) -> Result<Self, ApduError> {
+        match self == expected {
+            true => Ok(self),
+            false => Err(ApduError::InvalidEncoding),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn algorithm_ids() {
+        for a in Algorithm::iter() {
+            assert_eq!(Algorithm::try_from(a as u8).unwrap(), a);
+            assert!(a.expect(a).is_ok());
+        }
+
+        assert!(Algorithm::try_from(0x00).is_err());
+        assert!(Algorithm::try_from(0xff).is_err());
+
+        assert!(matches!(
+            Algorithm::Ed25519.expect(Algorithm::Ristretto255),
+            Err(ApduError::InvalidEncoding)
+        ));
+    }
+}
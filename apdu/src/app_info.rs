@@ -10,6 +10,9 @@ use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
 #[derive(Copy, Clone, PartialEq, Debug, Default)]
 pub struct AppInfoReq {}
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(AppInfoReq {});
+
 impl ApduStatic for AppInfoReq {
     /// Application Info command APDU is class `0xb0`
     const CLA: u8 = MOB_APDU_CLA;
@@ -42,6 +45,9 @@ impl DecodeOwned for AppInfoReq {
 
 /// Application information response APDU
 ///
+/// Newer firmware appends [AppBuildInfo] following the flags, this is
+/// ignored by earlier hosts and absent (`None`) for earlier firmware.
+///
 /// ## Encoding
 ///
 /// ```text
@@ -56,6 +62,8 @@ impl DecodeOwned for AppInfoReq {
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// /                             FLAGS...                          /
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /                     BUILD_INFO... (optional)                  /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
 ///
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -71,19 +79,65 @@ pub struct AppInfoResp<'a> {
 
     /// Application flags
     pub flags: AppFlags,
+
+    /// Build information (where supported)
+    pub build: Option<AppBuildInfo<'a>>,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(AppInfoResp<'a> { proto, name, version, flags, build });
+
 bitflags::bitflags! {
     /// Application info flags
     pub struct AppFlags: u16 {
-        /// Indicates app is unlocked for key requests
+        /// Indicates app is unlocked for all key requests
         const UNLOCKED = 1 << 0;
 
+        /// Indicates wallet (root) key requests are approved
+        const WALLET_KEYS_APPROVED = 1 << 1;
+
+        /// Indicates subaddress key requests are approved
+        const SUBADDRESS_KEYS_APPROVED = 1 << 2;
+
+        /// Indicates key image requests (scanning) are approved
+        const KEY_IMAGES_APPROVED = 1 << 3;
+
+        /// Indicates key image requests using host-supplied
+        /// onetime private keys are approved
+        const ONETIME_KEY_IMAGES_APPROVED = 1 << 4;
+
         /// Indicates app has tx summary feature
         const HAS_TX_SUMMARY = 1 << 8;
+
+        /// Indicates blind signing is disabled, transactions
+        /// must be loaded via TxSummary
+        const BLIND_SIGNING_DISABLED = 1 << 9;
+
+        /// Indicates host-supplied onetime private keys are rejected,
+        /// rings may only be signed with seed-derived keys
+        const ONETIME_KEYS_DISABLED = 1 << 10;
+
+        /// Indicates settings (fog, lock timeout, etc.) persist
+        /// across app restarts
+        const HAS_PERSISTENT_SETTINGS = 1 << 11;
+
+        /// Indicates app supports Ristretto255 keys
+        /// (see [Algorithm][crate::algorithm::Algorithm])
+        const ALG_RISTRETTO255 = 1 << 12;
+
+        /// Indicates app supports Ed25519 identities
+        /// (see [Algorithm][crate::algorithm::Algorithm])
+        const ALG_ED25519 = 1 << 13;
+
+        /// Indicates a touchscreen device (Stax, Flex), where reviews
+        /// are displayed using NBGL flows rather than button-driven pages
+        const TOUCHSCREEN = 1 << 15;
     }
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_bitflags!(AppFlags, u16);
+
 impl<'a> AppInfoResp<'a> {
     /// Create a new application version APDU
     pub fn new(proto: u8, name: &'a str, version: &'a str, flags: AppFlags) -> Self {
@@ -92,7 +146,191 @@ impl<'a> AppInfoResp<'a> {
             name,
             version,
             flags,
+            build: None,
+        }
+    }
+
+    /// Attach [AppBuildInfo] to an application version APDU
+    pub fn with_build(mut self, build: AppBuildInfo<'a>) -> Self {
+        self.build = Some(build);
+        self
+    }
+}
+
+/// Current [AppBuildInfo] encoding version
+pub const APP_BUILD_INFO_VERSION: u8 = 1;
+
+bitflags::bitflags! {
+    /// Cargo features enabled in the application build
+    pub struct BuildFeatures: u8 {
+        /// TxSummary verification (`summary`)
+        const SUMMARY = 1 << 0;
+
+        /// Ed25519 identities (`ident`)
+        const IDENT = 1 << 1;
+
+        /// Heap allocation (`alloc`)
+        const ALLOC = 1 << 2;
+
+        /// Persistent settings storage (`nvm`)
+        const NVM = 1 << 3;
+    }
+}
+
+crate::encdec_bitflags!(BuildFeatures);
+
+#[cfg(feature = "cbor")]
+crate::cbor_bitflags!(BuildFeatures, u8);
+
+/// Application build information, identifying the exact binary
+/// running on a device for support purposes
+///
+/// Later encoding versions may append fields, these are skipped
+/// by hosts supporting earlier versions.
+///
+/// ## Encoding
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   BUILD_VER   |    INFO_LEN   |   TARGET_LEN  |    HASH_LEN   |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |    FEATURES   | RING_SIZE_MAX |           RESERVED            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /                            TARGET...                          /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /                           GIT_HASH...                         /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+///
+/// `INFO_LEN` is the total encoded length (including the header),
+/// allowing hosts to skip fields appended in later versions.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct AppBuildInfo<'a> {
+    /// SDK build target (eg. `nanosplus`)
+    pub target: &'a str,
+
+    /// Git hash of the application build
+    pub git_hash: &'a str,
+
+    /// Enabled cargo features
+    pub features: BuildFeatures,
+
+    /// Maximum supported ring size
+    pub ring_size_max: u8,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(AppBuildInfo<'a> {
+    target,
+    git_hash,
+    features,
+    ring_size_max
+});
+
+impl<'a> AppBuildInfo<'a> {
+    /// Create a new [AppBuildInfo] object
+    pub fn new(
+        target: &'a str,
+        git_hash: &'a str,
+        features: BuildFeatures,
+        ring_size_max: u8,
+    ) -> Self {
+        Self {
+            target,
+            git_hash,
+            features,
+            ring_size_max,
+        }
+    }
+}
+
+impl<'a> Encode for AppBuildInfo<'a> {
+    type Error = ApduError;
+
+    /// Encode build information into the provided buffer
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        let len = self.encode_len()?;
+
+        // Check total length is encodable and buffer length is viable (MOB-06.6)
+        if len > u8::MAX as usize || buff.len() < len {
+            return Err(ApduError::InvalidLength);
         }
+
+        // Set header
+        buff[0] = APP_BUILD_INFO_VERSION;
+        buff[1] = len as u8;
+        buff[2] = self.target.len() as u8;
+        buff[3] = self.git_hash.len() as u8;
+        buff[4] = self.features.bits();
+        buff[5] = self.ring_size_max;
+        buff[6..8].fill(0);
+        let mut index = 8;
+
+        // Write target
+        buff[index..][..self.target.len()].copy_from_slice(self.target.as_bytes());
+        index += self.target.len();
+
+        // Write git hash
+        buff[index..][..self.git_hash.len()].copy_from_slice(self.git_hash.as_bytes());
+        index += self.git_hash.len();
+
+        Ok(index)
+    }
+
+    /// Compute encoded length
+    fn encode_len(&self) -> Result<usize, ApduError> {
+        Ok(8 + self.target.len() + self.git_hash.len())
+    }
+}
+
+impl<'a> Decode<'a> for AppBuildInfo<'a> {
+    type Output = Self;
+    type Error = ApduError;
+
+    /// Decode build information from the provided buffer
+    fn decode(buff: &'a [u8]) -> Result<(Self, usize), ApduError> {
+        // Check buffer length prior to header parsing (MOB-06.7)
+        if buff.len() < 8 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Fetch headers
+        let version = buff[0];
+        let info_len = buff[1] as usize;
+        let target_len = buff[2] as usize;
+        let hash_len = buff[3] as usize;
+
+        if version < APP_BUILD_INFO_VERSION {
+            return Err(ApduError::InvalidEncoding);
+        }
+
+        // Check full buffer length (MOB-06.7)
+        if info_len < 8 + target_len + hash_len || buff.len() < info_len {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let mut index = 8;
+
+        // Fetch target string
+        let target = core::str::from_utf8(&buff[index..][..target_len])
+            .map_err(|_| ApduError::InvalidUtf8)?;
+        index += target_len;
+
+        // Fetch git hash string
+        let git_hash =
+            core::str::from_utf8(&buff[index..][..hash_len]).map_err(|_| ApduError::InvalidUtf8)?;
+
+        Ok((
+            Self {
+                target,
+                git_hash,
+                features: BuildFeatures::from_bits_truncate(buff[4]),
+                ring_size_max: buff[5],
+            },
+            info_len,
+        ))
     }
 }
 
@@ -126,6 +364,11 @@ impl<'a> Encode for AppInfoResp<'a> {
         // Write flags
         index += self.flags.encode(&mut buff[index..])?;
 
+        // Write build information where provided
+        if let Some(b) = &self.build {
+            index += b.encode(&mut buff[index..])?;
+        }
+
         Ok(index)
     }
 
@@ -137,6 +380,10 @@ impl<'a> Encode for AppInfoResp<'a> {
         len += self.version.len();
         len += self.flags.encode_len()?;
 
+        if let Some(b) = &self.build {
+            len += b.encode_len()?;
+        }
+
         Ok(len)
     }
 }
@@ -180,12 +427,23 @@ impl<'a> Decode<'a> for AppInfoResp<'a> {
         let (flags, n) = AppFlags::decode_owned(&buff[index..][..flags_len])?;
         index += n;
 
+        // Fetch build information, absent for earlier firmware
+        let build = match buff.len() > index {
+            true => {
+                let (b, n) = AppBuildInfo::decode(&buff[index..])?;
+                index += n;
+                Some(b)
+            }
+            false => None,
+        };
+
         Ok((
             Self {
                 proto,
                 name,
                 version,
                 flags,
+                build,
             },
             index,
         ))
@@ -267,4 +525,54 @@ mod test {
         let mut buff = [0u8; 128];
         encode_decode_apdu(&mut buff, &apdu);
     }
+
+    #[test]
+    fn app_info_resp_build_apdu() {
+        let build = AppBuildInfo::new(
+            "nanosplus",
+            "0a1b2c3d",
+            BuildFeatures::SUMMARY | BuildFeatures::IDENT | BuildFeatures::NVM,
+            11,
+        );
+        let apdu =
+            AppInfoResp::new(1, "TEST NAME", "TEST VERSION", AppFlags::UNLOCKED).with_build(build);
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 4 + 9 + 12 + 3 + 8 + 9 + 8);
+
+        // Responses without build info remain decodable
+        let n = AppInfoResp::new(1, "TEST NAME", "TEST VERSION", AppFlags::UNLOCKED)
+            .encode(&mut buff)
+            .unwrap();
+        let (d, _) = AppInfoResp::decode(&buff[..n]).unwrap();
+        assert_eq!(d.build, None);
+    }
+
+    #[test]
+    fn app_build_info_versions() {
+        let build = AppBuildInfo::new("nanox", "0a1b2c3d", BuildFeatures::ALLOC, 11);
+
+        let mut buff = [0u8; 64];
+        let n = build.encode(&mut buff).unwrap();
+
+        // Fields appended by later versions are skipped
+        buff[0] = APP_BUILD_INFO_VERSION + 1;
+        buff[1] = n as u8 + 2;
+        buff[n..][..2].fill(0xff);
+        let (d, m) = AppBuildInfo::decode(&buff[..n + 2]).unwrap();
+        assert_eq!(m, n + 2);
+        assert_eq!(d, build);
+
+        // Truncated and unversioned encodings are rejected
+        assert!(matches!(
+            AppBuildInfo::decode(&buff[..n + 1]),
+            Err(ApduError::InvalidLength)
+        ));
+        buff[0] = 0;
+        assert!(matches!(
+            AppBuildInfo::decode(&buff[..n + 2]),
+            Err(ApduError::InvalidEncoding)
+        ));
+    }
 }
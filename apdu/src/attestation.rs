@@ -0,0 +1,128 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Key attestation objects, for proving exported keys were
+//! returned by a device holding the wallet seed.
+//!
+//! Attested key requests ([WalletKeyAttestReq][crate::wallet_keys::WalletKeyAttestReq],
+//! [SubaddressKeyAttestReq][crate::subaddress_keys::SubaddressKeyAttestReq])
+//! include a host challenge, and the device appends a [KeyAttestation] to the
+//! key response containing an ed25519 signature over a digest of the challenge
+//! and returned keys (see [crate::digest::digest_wallet_key_attestation] and
+//! [crate::digest::digest_subaddress_key_attestation]).
+//!
+//! The attestation key is derived via SLIP-0010 at [KEY_ATTESTATION_PATH],
+//! so is stable for a given seed and may be pinned by custody back-ends.
+
+use encdec::{Decode, Encode};
+
+use super::ApduError;
+use crate::helpers::*;
+
+/// Attestation challenge length
+pub const KEY_ATTESTATION_CHALLENGE_LEN: usize = 32;
+
+/// Encoded [KeyAttestation] length
+pub const KEY_ATTESTATION_LEN: usize = 96;
+
+/// SLIP-0010 ed25519 derivation path for the attestation key
+/// (`m/44'/866'/2147483647'/0'`), a hardened child of the reserved
+/// final account index to remain within the application's permitted paths
+pub const KEY_ATTESTATION_PATH: [u32; 4] = [
+    44 | (1 << 31),
+    866 | (1 << 31),
+    0x7fff_ffff | (1 << 31),
+    (1 << 31),
+];
+
+/// Key attestation, appended to attested key responses
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                          PUBLIC_KEY                           /
+/// /              (32-byte ed25519 attestation public key)         /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                            SIGNATURE                          /
+/// /                   (64-byte ed25519 signature)                 /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct KeyAttestation {
+    /// Attestation public key
+    #[encdec(with = "arr")]
+    pub public_key: [u8; 32],
+    /// Signature over the attestation digest
+    #[encdec(with = "arr")]
+    pub signature: [u8; 64],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(KeyAttestation {
+    public_key,
+    signature
+});
+
+impl KeyAttestation {
+    /// Create a new [KeyAttestation]
+    pub fn new(public_key: [u8; 32], signature: [u8; 64]) -> Self {
+        Self {
+            public_key,
+            signature,
+        }
+    }
+}
+
+/// Helpers for encoding optional trailing attestations in key responses
+pub(crate) mod trailing {
+    use super::*;
+
+    pub fn enc_len(a: &Option<KeyAttestation>) -> usize {
+        match a {
+            Some(_) => KEY_ATTESTATION_LEN,
+            None => 0,
+        }
+    }
+
+    pub fn enc(a: &Option<KeyAttestation>, buff: &mut [u8]) -> Result<usize, ApduError> {
+        match a {
+            Some(a) => a.encode(buff),
+            None => Ok(0),
+        }
+    }
+
+    /// Decode an optional attestation, absent where the buffer is empty
+    pub fn dec(buff: &[u8]) -> Result<(Option<KeyAttestation>, usize), ApduError> {
+        match buff.is_empty() {
+            true => Ok((None, 0)),
+            false => KeyAttestation::decode(buff).map(|(a, n)| (Some(a), n)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn key_attestation_apdu() {
+        let mut signature = [0u8; 64];
+        signature[..32].copy_from_slice(&random::<[u8; 32]>());
+        signature[32..].copy_from_slice(&random::<[u8; 32]>());
+
+        let a = KeyAttestation::new(random(), signature);
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &a);
+        assert_eq!(n, KEY_ATTESTATION_LEN);
+    }
+}
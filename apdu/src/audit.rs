@@ -0,0 +1,346 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Approval audit log APDUs, for fetching a history of operations
+//! approved on the device (most recent first)
+
+use encdec::{Decode, DecodeOwned, Encode};
+use heapless::Vec;
+use num_enum::TryFromPrimitive;
+use strum::{Display, EnumIter};
+
+use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
+use crate::helpers::*;
+
+/// Maximum number of audit log entries per response
+pub const AUDIT_ENTRIES_MAX: usize = 4;
+
+/// Approved operation kind for audit log entries
+#[derive(Copy, Clone, PartialEq, Debug, Display, EnumIter, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum AuditKind {
+    /// Transaction approved, data contains the transaction digest
+    Transaction = 0x01,
+    /// Key export / scanning approved, data contains the approval scope bits
+    KeyExport = 0x02,
+    /// Identity challenge signed, data contains the identity URI
+    Ident = 0x03,
+    /// Address confirmed, data contains the account and subaddress indices
+    Address = 0x04,
+    /// Fog id changed, data contains the new fog id
+    FogId = 0x05,
+}
+
+/// Audit log entry, recording an approved operation
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     KIND      |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                           SEQUENCE                            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                             TICKS                             |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                             DATA                              /
+/// /                           (32-byte)                           /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AuditEntry {
+    /// Approved operation kind
+    pub kind: AuditKind,
+    /// Sequence number, incremented for each recorded approval
+    pub seq: u32,
+    /// Device tick count at approval (reset on app start)
+    pub ticks: u32,
+    /// Operation specific data, see [AuditKind]
+    pub data: [u8; 32],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(AuditEntry {
+    kind,
+    seq,
+    ticks,
+    data
+});
+
+impl AuditEntry {
+    /// Encoded length of an [AuditEntry]
+    pub const LEN: usize = 44;
+}
+
+impl Encode for AuditEntry {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(Self::LEN)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        if buff.len() < Self::LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        buff[0] = self.kind as u8;
+        buff[1..4].fill(0);
+
+        let mut index = 4;
+        index += self.seq.encode(&mut buff[index..])?;
+        index += self.ticks.encode(&mut buff[index..])?;
+        index += arr::enc(&self.data, &mut buff[index..])?;
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for AuditEntry {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        if buff.len() < Self::LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let kind = AuditKind::try_from(buff[0]).map_err(|_| ApduError::InvalidEncoding)?;
+
+        let mut index = 4;
+        let (seq, n) = u32::decode_owned(&buff[index..])?;
+        index += n;
+        let (ticks, n) = u32::decode_owned(&buff[index..])?;
+        index += n;
+        let (data, n) = arr::dec::<32>(&buff[index..])?;
+        index += n;
+
+        Ok((
+            Self {
+                kind,
+                seq,
+                ticks,
+                data,
+            },
+            index,
+        ))
+    }
+}
+
+/// Request audit log entries, starting from `start` entries
+/// prior to the most recent approval
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     START     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct AuditLogReq {
+    /// Offset from most recent entry
+    pub start: u8,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 3],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(AuditLogReq { start } reserved { _reserved });
+
+impl AuditLogReq {
+    /// Create a new [AuditLogReq] APDU
+    pub fn new(start: u8) -> Self {
+        Self {
+            start,
+            _reserved: [0u8; 3],
+        }
+    }
+}
+
+impl ApduStatic for AuditLogReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetAuditLog as u8;
+}
+
+/// Audit log response APDU
+///
+/// Contains up to [AUDIT_ENTRIES_MAX] entries, most recent first,
+/// along with the number of entries currently held by the device.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     START     |   NUM_HELD    |  NUM_ENTRIES  |   RESERVED    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                 AUDIT_ENTRIES (44-byte * N)                   /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct AuditLogResp {
+    /// Offset of the first returned entry from the most recent entry
+    pub start: u8,
+    /// Number of entries held by the device
+    pub num_held: u8,
+    /// Audit log entries, most recent first
+    pub entries: Vec<AuditEntry, AUDIT_ENTRIES_MAX>,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(AuditLogResp {
+    start,
+    num_held,
+    entries
+});
+
+impl AuditLogResp {
+    /// Create a new [AuditLogResp] APDU
+    pub fn new(start: u8, num_held: u8, entries: &[AuditEntry]) -> Self {
+        let n = entries.len().min(AUDIT_ENTRIES_MAX);
+
+        Self {
+            start,
+            num_held,
+            entries: Vec::from_slice(&entries[..n]).unwrap(),
+        }
+    }
+}
+
+impl Encode for AuditLogResp {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(4 + self.entries.len() * AuditEntry::LEN)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < self.encode_len()? {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write header
+        buff[0] = self.start;
+        buff[1] = self.num_held;
+        buff[2] = self.entries.len() as u8;
+        buff[3] = 0;
+        let mut index = 4;
+
+        // Write entries
+        for e in &self.entries {
+            index += e.encode(&mut buff[index..])?;
+        }
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for AuditLogResp {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        // Check header length (MOB-06.7)
+        if buff.len() < 4 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read header
+        let start = buff[0];
+        let num_held = buff[1];
+        let count = buff[2] as usize;
+        let mut index = 4;
+
+        // Check count and full buffer length (MOB-06.7)
+        if count > AUDIT_ENTRIES_MAX || buff.len() < 4 + count * AuditEntry::LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read entries
+        let mut entries = Vec::new();
+        for _i in 0..count {
+            let (e, n) = AuditEntry::decode_owned(&buff[index..])?;
+            index += n;
+
+            let _ = entries.push(e);
+        }
+
+        Ok((
+            Self {
+                start,
+                num_held,
+                entries,
+            },
+            index,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    fn random_entry(kind: AuditKind) -> AuditEntry {
+        AuditEntry {
+            kind,
+            seq: random(),
+            ticks: random(),
+            data: random(),
+        }
+    }
+
+    #[test]
+    fn audit_log_req_apdu() {
+        let apdu = AuditLogReq::new(random());
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn audit_log_resp_apdu() {
+        let entries = [
+            AuditKind::Transaction,
+            AuditKind::KeyExport,
+            AuditKind::Ident,
+            AuditKind::Address,
+            AuditKind::FogId,
+        ]
+        .map(random_entry);
+
+        for n in 0..=AUDIT_ENTRIES_MAX {
+            let apdu = AuditLogResp::new(random(), random(), &entries[..n]);
+
+            let mut buff = [0u8; 256];
+            encode_decode_apdu(&mut buff, &apdu);
+        }
+    }
+
+    #[test]
+    fn audit_kind_invalid() {
+        let mut buff = [0u8; AuditEntry::LEN];
+        random_entry(AuditKind::Transaction)
+            .encode(&mut buff)
+            .unwrap();
+
+        buff[0] = 0xff;
+        assert!(matches!(
+            AuditEntry::decode_owned(&buff),
+            Err(ApduError::InvalidEncoding)
+        ));
+    }
+}
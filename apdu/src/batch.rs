@@ -0,0 +1,426 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Batched APDUs, combining a number of small requests into a single
+//! exchange to reduce the per-exchange latency when streaming rings.
+//!
+//! A [BatchReq] contains a sequence of encoded requests, each of which
+//! is handled by the device as if issued directly, with the encoded
+//! responses returned in order in a [BatchResp]. Batches are built
+//! using a [BatchWriter].
+//!
+//! Entries are handled in order, stopping at the first failed entry
+//! with the status for this entry returned in place of the [BatchResp]
+//! (preceding entries are not reverted).
+//!
+//! Batches are always sent via the [chunked exchange][crate::chunk] and
+//! may not be session wrapped. Entries requiring user approval are rejected,
+//! as are entries carrying sensitive material (see
+//! [requires_session][crate::session::requires_session]) where a session is open.
+
+use encdec::{Decode, Encode};
+
+use super::{ApduError, ApduReq, ApduStatic, Instruction, MOB_APDU_CLA};
+
+/// Maximum number of entries in a batch
+pub const BATCH_MAX: usize = 8;
+
+/// Length of batch headers
+const BATCH_HEADER_LEN: usize = 4;
+
+/// Length of batch entry headers
+const BATCH_ENTRY_HEADER_LEN: usize = 2;
+
+/// Batched request APDU, containing a number of encoded requests
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     COUNT     |                    RESERVED                   |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                      ENTRIES (see below)                      /
+/// /                       (variable length)                       /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+///
+/// ## Entry Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |      INS      |   DATA_LEN    |                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               +
+/// /                             DATA                              /
+/// /                       (variable length)                       /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct BatchReq<'a> {
+    count: u8,
+    entries: &'a [u8],
+}
+
+impl<'a> BatchReq<'a> {
+    /// Fetch the number of entries in the batch
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    /// Check whether the batch is empty
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Iterate over batch entries
+    pub fn entries(&self) -> BatchIter<'a> {
+        BatchIter { buff: self.entries }
+    }
+}
+
+impl<'a> ApduStatic for BatchReq<'a> {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::Batch as u8;
+}
+
+impl<'a> Encode for BatchReq<'a> {
+    type Error = ApduError;
+
+    /// Encode a [`BatchReq`] APDU into the provided buffer
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        encode_batch(self.count, self.entries, buff)
+    }
+
+    fn encode_len(&self) -> Result<usize, ApduError> {
+        Ok(BATCH_HEADER_LEN + self.entries.len())
+    }
+}
+
+impl<'a> Decode<'a> for BatchReq<'a> {
+    type Output = Self;
+    type Error = ApduError;
+
+    /// Decode a [`BatchReq`] APDU from the provided buffer
+    fn decode(buff: &'a [u8]) -> Result<(Self, usize), ApduError> {
+        let (count, entries) = decode_batch(buff)?;
+        Ok((Self { count, entries }, BATCH_HEADER_LEN + entries.len()))
+    }
+}
+
+/// Batched response APDU, containing responses for each entry in the
+/// associated [BatchReq].
+///
+/// Entries are encoded as for [BatchReq], with each entry
+/// containing the instruction and response for the request entry.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BatchResp<'a> {
+    count: u8,
+    entries: &'a [u8],
+}
+
+impl<'a> BatchResp<'a> {
+    /// Fetch the number of entries in the batch
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    /// Check whether the batch is empty
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Iterate over batch entries
+    pub fn entries(&self) -> BatchIter<'a> {
+        BatchIter { buff: self.entries }
+    }
+}
+
+impl<'a> Encode for BatchResp<'a> {
+    type Error = ApduError;
+
+    /// Encode a [`BatchResp`] APDU into the provided buffer
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        encode_batch(self.count, self.entries, buff)
+    }
+
+    fn encode_len(&self) -> Result<usize, ApduError> {
+        Ok(BATCH_HEADER_LEN + self.entries.len())
+    }
+}
+
+impl<'a> Decode<'a> for BatchResp<'a> {
+    type Output = Self;
+    type Error = ApduError;
+
+    /// Decode a [`BatchResp`] APDU from the provided buffer
+    fn decode(buff: &'a [u8]) -> Result<(Self, usize), ApduError> {
+        let (count, entries) = decode_batch(buff)?;
+        Ok((Self { count, entries }, BATCH_HEADER_LEN + entries.len()))
+    }
+}
+
+/// Batch entry, containing an encoded request or response
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct BatchEntry<'a> {
+    /// Request instruction
+    pub ins: u8,
+    /// Encoded request or response
+    pub data: &'a [u8],
+}
+
+/// Iterator over entries in a [BatchReq] or [BatchResp]
+pub struct BatchIter<'a> {
+    buff: &'a [u8],
+}
+
+impl<'a> Iterator for BatchIter<'a> {
+    type Item = BatchEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Entries are validated on decode, short buffers indicate completion
+        if self.buff.len() < BATCH_ENTRY_HEADER_LEN {
+            return None;
+        }
+
+        let ins = self.buff[0];
+        let n = self.buff[1] as usize;
+
+        if self.buff.len() < BATCH_ENTRY_HEADER_LEN + n {
+            return None;
+        }
+
+        let data = &self.buff[BATCH_ENTRY_HEADER_LEN..][..n];
+        self.buff = &self.buff[BATCH_ENTRY_HEADER_LEN + n..];
+
+        Some(BatchEntry { ins, data })
+    }
+}
+
+/// Writer for building batches in place, see [BatchReq] and [BatchResp]
+/// for the encoded layout.
+pub struct BatchWriter<'a> {
+    buff: &'a mut [u8],
+    count: u8,
+    len: usize,
+}
+
+impl<'a> BatchWriter<'a> {
+    /// Create a new batch writer using the provided buffer
+    pub fn new(buff: &'a mut [u8]) -> Self {
+        Self {
+            buff,
+            count: 0,
+            len: BATCH_HEADER_LEN,
+        }
+    }
+
+    /// Fetch the number of entries written
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    /// Check whether no entries have been written
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Append a request to the batch
+    pub fn push<'b>(&mut self, req: &impl ApduReq<'b>) -> Result<(), ApduError> {
+        self.push_with(req.header().ins, |b| req.encode(b))
+    }
+
+    /// Append an entry to the batch, encoded using the provided encoder
+    pub fn push_with(
+        &mut self,
+        ins: u8,
+        encode: impl FnOnce(&mut [u8]) -> Result<usize, ApduError>,
+    ) -> Result<(), ApduError> {
+        if self.count as usize >= BATCH_MAX || self.buff.len() < self.len + BATCH_ENTRY_HEADER_LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Encode entry data, limited to the maximum entry length
+        let max = (self.buff.len() - self.len - BATCH_ENTRY_HEADER_LEN).min(u8::MAX as usize);
+        let n = encode(&mut self.buff[self.len + BATCH_ENTRY_HEADER_LEN..][..max])?;
+
+        // Write entry header
+        self.buff[self.len] = ins;
+        self.buff[self.len + 1] = n as u8;
+
+        self.len += BATCH_ENTRY_HEADER_LEN + n;
+        self.count += 1;
+
+        Ok(())
+    }
+
+    /// Finalise the batch header, returning the encoded batch length
+    pub fn finish(self) -> Result<usize, ApduError> {
+        if self.buff.len() < BATCH_HEADER_LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        self.buff[0] = self.count;
+        self.buff[1..BATCH_HEADER_LEN].fill(0);
+
+        Ok(self.len)
+    }
+}
+
+/// Encode batch header and entries
+fn encode_batch(count: u8, entries: &[u8], buff: &mut [u8]) -> Result<usize, ApduError> {
+    let n = BATCH_HEADER_LEN + entries.len();
+
+    if count as usize > BATCH_MAX || buff.len() < n {
+        return Err(ApduError::InvalidLength);
+    }
+
+    buff[0] = count;
+    buff[1..BATCH_HEADER_LEN].fill(0);
+    buff[BATCH_HEADER_LEN..n].copy_from_slice(entries);
+
+    Ok(n)
+}
+
+/// Decode batch header and validate entries, returning the entry count and data
+fn decode_batch(buff: &[u8]) -> Result<(u8, &[u8]), ApduError> {
+    if buff.len() < BATCH_HEADER_LEN {
+        return Err(ApduError::InvalidLength);
+    }
+
+    let count = buff[0];
+    if count as usize > BATCH_MAX {
+        return Err(ApduError::InvalidLength);
+    }
+
+    // Check each entry is contained within the buffer
+    let mut n = BATCH_HEADER_LEN;
+    for _ in 0..count {
+        if buff.len() < n + BATCH_ENTRY_HEADER_LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        n += BATCH_ENTRY_HEADER_LEN + buff[n + 1] as usize;
+
+        if buff.len() < n {
+            return Err(ApduError::InvalidLength);
+        }
+    }
+
+    Ok((count, &buff[BATCH_HEADER_LEN..n]))
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::{
+        random::{RandomReq, RandomResp},
+        tx::TxGetResponse,
+    };
+
+    #[test]
+    fn batch_req_encode_decode() {
+        let mut buff = [0u8; 256];
+
+        let reqs = [
+            TxGetResponse::new(1),
+            TxGetResponse::new(2),
+            TxGetResponse::prior(3),
+        ];
+
+        // Build batch
+        let mut w = BatchWriter::new(&mut buff);
+        for r in &reqs {
+            w.push(r).unwrap();
+        }
+        assert_eq!(w.len(), reqs.len());
+        let n = w.finish().unwrap();
+
+        // Decode batch and check entries
+        let (b, m) = BatchReq::decode(&buff[..n]).unwrap();
+        assert_eq!(n, m);
+        assert_eq!(b.len(), reqs.len());
+
+        let mut count = 0;
+        for (e, r) in b.entries().zip(reqs.iter()) {
+            assert_eq!(e.ins, TxGetResponse::INS);
+
+            let (d, _) = TxGetResponse::decode(e.data).unwrap();
+            assert_eq!(&d, r);
+
+            count += 1;
+        }
+        assert_eq!(count, reqs.len());
+
+        // Re-encode batch
+        let mut buff2 = [0u8; 256];
+        let n2 = b.encode(&mut buff2).unwrap();
+        assert_eq!(&buff[..n], &buff2[..n2]);
+    }
+
+    #[test]
+    fn batch_resp_encode_decode() {
+        let mut buff = [0u8; 256];
+
+        let resps = [
+            RandomResp::new(heapless::Vec::from_slice(&random::<[u8; 32]>()).unwrap()),
+            RandomResp::new(heapless::Vec::from_slice(&random::<[u8; 32]>()).unwrap()),
+        ];
+
+        let mut w = BatchWriter::new(&mut buff);
+        for r in &resps {
+            w.push_with(RandomReq::INS, |b| r.encode(b)).unwrap();
+        }
+        let n = w.finish().unwrap();
+
+        let (b, _) = BatchResp::decode(&buff[..n]).unwrap();
+        assert_eq!(b.len(), resps.len());
+
+        for (e, r) in b.entries().zip(resps.iter()) {
+            assert_eq!(e.ins, RandomReq::INS);
+
+            let (d, _) = RandomResp::decode(e.data).unwrap();
+            assert_eq!(&d, r);
+        }
+    }
+
+    #[test]
+    fn batch_limits() {
+        let mut buff = [0u8; 256];
+
+        // Entry count is limited
+        let mut w = BatchWriter::new(&mut buff);
+        for _ in 0..BATCH_MAX {
+            w.push(&RandomReq::default()).unwrap();
+        }
+        assert!(matches!(
+            w.push(&RandomReq::default()),
+            Err(ApduError::InvalidLength)
+        ));
+
+        // Entries must be contained within the buffer
+        let mut w = BatchWriter::new(&mut buff[..8]);
+        assert!(matches!(
+            w.push(&TxGetResponse::new(0)),
+            Err(ApduError::InvalidLength)
+        ));
+
+        // Truncated entries are rejected on decode
+        let b = [0x01, 0x00, 0x00, 0x00, TxGetResponse::INS, 0x04, 0x00];
+        assert!(matches!(
+            BatchReq::decode(&b),
+            Err(ApduError::InvalidLength)
+        ));
+
+        // As are batches exceeding the maximum entry count
+        let b = [BATCH_MAX as u8 + 1, 0x00, 0x00, 0x00];
+        assert!(matches!(
+            BatchReq::decode(&b),
+            Err(ApduError::InvalidLength)
+        ));
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Export APDU layouts as JSON for client code generation,
+//! see [ledger_mob_apdu::schema].
+
+use serde_json::{json, Value};
+use strum::IntoEnumIterator;
+
+use ledger_mob_apdu::{
+    schema::{ApduSchema, SCHEMAS},
+    Instruction, MOB_APDU_CLA, MOB_APDU_CLA_CBOR, MOB_PROTO_VERSION, MOB_PROTO_VERSION_MIN,
+};
+
+fn apdu(s: &ApduSchema) -> Value {
+    let fields: Vec<_> = s
+        .fields
+        .iter()
+        .map(|f| {
+            json!({
+                "name": f.name,
+                "offset": f.offset,
+                "len": f.len,
+            })
+        })
+        .collect();
+
+    json!({
+        "name": s.name,
+        "ins": s.ins,
+        "len": s.len(),
+        "header_len": s.header_len(),
+        "fields": fields,
+    })
+}
+
+fn main() {
+    let instructions: Vec<_> = Instruction::iter()
+        .map(|i| {
+            let name: &'static str = i.into();
+            json!({ "name": name, "code": i as u8 })
+        })
+        .collect();
+
+    let apdus: Vec<_> = SCHEMAS.iter().map(apdu).collect();
+
+    let schema = json!({
+        "cla": MOB_APDU_CLA,
+        "cla_cbor": MOB_APDU_CLA_CBOR,
+        "proto_version": MOB_PROTO_VERSION,
+        "proto_version_min": MOB_PROTO_VERSION_MIN,
+        "endianness": "little",
+        "instructions": instructions,
+        "apdus": apdus,
+    });
+
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+}
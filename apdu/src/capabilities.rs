@@ -0,0 +1,334 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Capability APDUs, used to negotiate the protocol version and
+//! discover optional features supported by the device.
+//!
+//! Hosts should select the highest protocol version supported by both
+//! parties (see [CapabilitiesResp::negotiate]), falling back to the
+//! [`AppInfoResp`][crate::app_info::AppInfoResp] protocol version for
+//! devices that do not support [CapabilitiesReq].
+//!
+//! Where [Features::PROTOCOL_PIN] is supported the host must then pin the
+//! negotiated version with a [SetProtocolVersionReq], transactions are
+//! rejected until a supported version is pinned so older hosts cannot
+//! silently mis-decode requests to newer firmware.
+
+use encdec::{Decode, Encode};
+
+use super::{
+    ApduError, ApduStatic, Instruction, MOB_APDU_CLA, MOB_PROTO_VERSION, MOB_PROTO_VERSION_MIN,
+};
+use crate::helpers::*;
+
+/// Request device capabilities (0 length APDU)
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct CapabilitiesReq {}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(CapabilitiesReq {});
+
+impl ApduStatic for CapabilitiesReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetCapabilities as u8;
+}
+
+bitflags::bitflags! {
+    /// Optional features supported by the device
+    pub struct Features: u32 {
+        /// Chunked APDU exchange (see [crate::chunk])
+        const CHUNKED_APDU = 1 << 0;
+
+        /// Transaction summary verification
+        const TX_SUMMARY = 1 << 1;
+
+        /// SLIP-0013/17 identity signing
+        const IDENT = 1 << 2;
+
+        /// Memo signing
+        const MEMO = 1 << 3;
+
+        /// On-device address verification
+        const ADDRESS_VERIFY = 1 << 4;
+
+        /// Subaddress range requests
+        const SUBADDRESS_RANGE = 1 << 5;
+
+        /// Key image computation from host-supplied onetime keys
+        const KEY_IMAGE_FROM_KEY = 1 << 6;
+
+        /// Approval audit log
+        const AUDIT_LOG = 1 << 7;
+
+        /// Engine diagnostic counters
+        const DIAGNOSTICS = 1 << 8;
+
+        /// Session account invalidation
+        const INVALIDATE_ACCOUNTS = 1 << 9;
+
+        /// Encrypted APDU sessions (see [crate::session])
+        const SECURE_SESSION = 1 << 10;
+
+        /// Batched APDUs (see [crate::batch])
+        const BATCH = 1 << 11;
+
+        /// b58 encoded public address requests
+        const PUBLIC_ADDRESS = 1 << 12;
+
+        /// Batched subaddress key requests
+        const SUBADDRESS_KEYS_BATCH = 1 << 13;
+
+        /// Host-initiated fog id changes
+        const SET_FOG_ID = 1 << 14;
+
+        /// Progress requests
+        const PROGRESS = 1 << 15;
+
+        /// Device settings requests
+        const GET_SETTINGS = 1 << 16;
+
+        /// TxSummary report read back
+        const TX_SUMMARY_REPORT = 1 << 17;
+
+        /// Transaction request sequence numbers
+        const TX_SEQUENCE = 1 << 18;
+
+        /// Payload MACs on streamed transaction requests
+        const TX_PAYLOAD_MAC = 1 << 19;
+
+        /// Variable-length random values
+        const RANDOM_LEN = 1 << 20;
+
+        /// SLIP-0013 / SLIP-0017 identity derivation selection and
+        /// chunked identity challenges
+        const IDENT_DERIVATION = 1 << 21;
+
+        /// Host-initiated transaction aborts
+        const TX_ABORT = 1 << 22;
+
+        /// Multi-scalar response fetches (see [crate::tx::TxGetResponses])
+        const TX_GET_RESPONSES = 1 << 23;
+
+        /// Attested wallet / subaddress key responses
+        /// (see [crate::attestation])
+        const KEY_ATTESTATION = 1 << 24;
+
+        /// Protocol version pinning (see [SetProtocolVersionReq]),
+        /// required prior to starting transactions
+        const PROTOCOL_PIN = 1 << 25;
+
+        /// Key requests using explicit derivation paths
+        /// (see [crate::key_path])
+        const KEY_PATH = 1 << 26;
+
+        /// Compact TxOut summary encodings
+        /// (see [crate::tx::TxSummaryAddTxOutCompact])
+        const COMPACT_SUMMARY = 1 << 27;
+
+        /// Expected fee and tombstone block checks
+        /// (see [crate::tx::TxExpected])
+        const TX_EXPECTED = 1 << 28;
+
+        /// Device nonce challenge-response for proving device presence
+        /// (see [crate::device_nonce])
+        const DEVICE_NONCE = 1 << 29;
+
+        /// Subaddress keys with fog information
+        /// (see [crate::subaddress_keys::SubaddressKeyFogReq])
+        const SUBADDRESS_KEYS_FOG = 1 << 30;
+    }
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_bitflags!(Features, u32);
+
+/// Device capabilities response APDU, containing the range of supported
+/// protocol versions and optional features.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   PROTO_MIN   |   PROTO_MAX   |            RESERVED           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                           FEATURES                            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CapabilitiesResp {
+    /// Minimum supported protocol version
+    pub proto_min: u8,
+    /// Maximum supported protocol version
+    pub proto_max: u8,
+    /// Supported features
+    pub features: Features,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(CapabilitiesResp {
+    proto_min,
+    proto_max,
+    features
+});
+
+impl CapabilitiesResp {
+    /// Create a new [`CapabilitiesResp`] APDU
+    pub fn new(proto_min: u8, proto_max: u8, features: Features) -> Self {
+        Self {
+            proto_min,
+            proto_max,
+            features,
+        }
+    }
+
+    /// Select the highest protocol version supported by both the device
+    /// and this implementation, returning `None` if there is no overlap
+    pub fn negotiate(&self) -> Option<u8> {
+        negotiate(
+            (MOB_PROTO_VERSION_MIN, MOB_PROTO_VERSION),
+            (self.proto_min, self.proto_max),
+        )
+    }
+}
+
+/// Select the highest protocol version within both provided (inclusive) ranges
+pub fn negotiate(a: (u8, u8), b: (u8, u8)) -> Option<u8> {
+    let (min, max) = (a.0.max(b.0), a.1.min(b.1));
+
+    match min <= max {
+        true => Some(max),
+        false => None,
+    }
+}
+
+impl Encode for CapabilitiesResp {
+    type Error = ApduError;
+
+    /// Encode a [`CapabilitiesResp`] APDU into the provided buffer
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        if buff.len() < 8 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        buff[0] = self.proto_min;
+        buff[1] = self.proto_max;
+        buff[2..4].fill(0);
+        buff[4..8].copy_from_slice(&self.features.bits().to_le_bytes());
+
+        Ok(8)
+    }
+
+    fn encode_len(&self) -> Result<usize, ApduError> {
+        Ok(8)
+    }
+}
+
+impl<'a> Decode<'a> for CapabilitiesResp {
+    type Output = Self;
+    type Error = ApduError;
+
+    /// Decode a [`CapabilitiesResp`] APDU from the provided buffer
+    fn decode(buff: &'a [u8]) -> Result<(Self, usize), ApduError> {
+        if buff.len() < 8 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let bits = u32::from_le_bytes([buff[4], buff[5], buff[6], buff[7]]);
+
+        Ok((
+            Self {
+                proto_min: buff[0],
+                proto_max: buff[1],
+                // Truncate unknown features for forward compatibility
+                features: Features::from_bits_truncate(bits),
+            },
+            8,
+        ))
+    }
+}
+
+/// Pin the protocol version for subsequent requests, sent following
+/// capability negotiation and returning a [TxInfo][crate::tx::TxInfo] response.
+///
+/// Devices reject versions outside the supported range, and reject
+/// transactions until a version has been pinned.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |    VERSION    |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SetProtocolVersionReq {
+    /// Protocol version selected by the host
+    pub version: u8,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 3],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(SetProtocolVersionReq { version } reserved { _reserved });
+
+impl SetProtocolVersionReq {
+    /// Create a new [SetProtocolVersionReq] APDU
+    pub fn new(version: u8) -> Self {
+        Self {
+            version,
+            _reserved: [0u8; 3],
+        }
+    }
+}
+
+impl ApduStatic for SetProtocolVersionReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::SetProtocolVersion as u8;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn capabilities_req_apdu() {
+        let apdu = CapabilitiesReq::default();
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn capabilities_resp_apdu() {
+        let apdu = CapabilitiesResp::new(1, 3, Features::CHUNKED_APDU | Features::IDENT);
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn set_protocol_version_apdu() {
+        let apdu = SetProtocolVersionReq::new(MOB_PROTO_VERSION);
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 4);
+    }
+
+    #[test]
+    fn negotiate_versions() {
+        // Highest common version is selected
+        assert_eq!(negotiate((1, 1), (1, 1)), Some(1));
+        assert_eq!(negotiate((1, 3), (2, 5)), Some(3));
+        assert_eq!(negotiate((2, 5), (1, 3)), Some(3));
+
+        // Disjoint ranges fail
+        assert_eq!(negotiate((1, 1), (2, 3)), None);
+        assert_eq!(negotiate((3, 4), (1, 2)), None);
+    }
+}
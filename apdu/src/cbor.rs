@@ -0,0 +1,586 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! CBOR encoding for APDU payloads (requires the `cbor` feature).
+//!
+//! This provides an alternative to the packed APDU encoding for integrators
+//! able to use standard CBOR tooling, selected using the
+//! [MOB_APDU_CLA_CBOR][crate::MOB_APDU_CLA_CBOR] class
+//! (see [Encoding][crate::encoding::Encoding]).
+//!
+//! APDU objects are encoded as CBOR arrays containing each field in
+//! declaration order, omitting reserved fields:
+//! - integers are encoded as unsigned integers
+//! - keys, points, scalars and fixed-length arrays as byte strings
+//! - enumerations and flags as unsigned integers (matching the packed values)
+//! - optional values as the value or `null`
+//! - lists as arrays, with tuples as nested arrays
+//!
+//! For example, a [WalletKeyReq][crate::wallet_keys::WalletKeyReq] for account
+//! 1 is encoded as `[1, 1]` (`82 01 01`).
+//!
+//! Framing APDUs ([chunks][crate::chunk], [sessions][crate::session] and
+//! [batches][crate::batch]) always use the packed encoding, with the wrapped
+//! payloads encoded per the APDU class.
+
+use curve25519_dalek::ristretto::CompressedRistretto;
+use heapless::Vec;
+use minicbor::{
+    data::Type,
+    decode::Error as DecodeError,
+    encode::{write::Cursor, Error as EncodeError, Write},
+    Decoder, Encoder,
+};
+
+use mc_core::keys::Key;
+use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate, RistrettoPublic};
+use mc_crypto_ring_signature::{CompressedCommitment, KeyImage, Scalar};
+
+use crate::{
+    algorithm::Algorithm,
+    audit::AuditKind,
+    ident::IdentDerivation,
+    state::TxState,
+    tx::{FogId, ReportEntity},
+    ApduError,
+};
+
+/// Re-export `minicbor` for use by downstream consumers and helper macros
+pub use minicbor;
+
+/// Encode a field or object to CBOR
+pub trait CborEncode {
+    /// Write CBOR encoded object using the provided encoder
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>>;
+}
+
+/// Decode a field or object from CBOR
+pub trait CborDecode<'b>: Sized {
+    /// Read CBOR encoded object using the provided decoder
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError>;
+}
+
+/// Encode an object to CBOR in the provided buffer, returning the encoded length
+pub fn encode<T: CborEncode + ?Sized>(v: &T, buff: &mut [u8]) -> Result<usize, ApduError> {
+    let mut c = Cursor::new(buff);
+
+    let mut e = Encoder::new(&mut c);
+    v.encode_field(&mut e)
+        .map_err(|_| ApduError::InvalidLength)?;
+
+    Ok(c.position())
+}
+
+/// Decode an object from CBOR, returning the object and consumed length
+pub fn decode<'b, T: CborDecode<'b>>(buff: &'b [u8]) -> Result<(T, usize), ApduError> {
+    let mut d = Decoder::new(buff);
+
+    let v = T::decode_field(&mut d).map_err(|_| ApduError::InvalidEncoding)?;
+
+    Ok((v, d.position()))
+}
+
+/// Helper macro implementing CBOR encoding for APDU structs as
+/// arrays of fields, with optional per-field helper modules
+/// (from [crate::cbor]) and reserved fields (omitted when encoding,
+/// defaulted when decoding).
+#[macro_export]
+macro_rules! cbor_struct {
+    ($t:ident<$l:lifetime> { $($f:ident $(: $w:ident)?),* $(,)? } $(reserved { $($r:ident),* $(,)? })?) => {
+        $crate::cbor_struct!(@impl [$l] [$t<$l>] { $($f $(: $w)?),* } { $($($r),*)? });
+    };
+    ($t:ident { $($f:ident $(: $w:ident)?),* $(,)? } $(reserved { $($r:ident),* $(,)? })?) => {
+        $crate::cbor_struct!(@impl ['b] [$t] { $($f $(: $w)?),* } { $($($r),*)? });
+    };
+    (@impl [$lt:lifetime] [$ty:ty] { $($f:ident $(: $w:ident)?),* } { $($r:ident),* }) => {
+        impl<$lt> $crate::cbor::CborEncode for $ty {
+            fn encode_field<W: $crate::cbor::minicbor::encode::Write>(
+                &self,
+                e: &mut $crate::cbor::minicbor::Encoder<W>,
+            ) -> Result<(), $crate::cbor::minicbor::encode::Error<W::Error>> {
+                e.array(0 $(+ $crate::cbor_struct!(@one $f))*)?;
+                $( $crate::cbor_struct!(@enc e, &self.$f $(, $w)?); )*
+                Ok(())
+            }
+        }
+
+        impl<$lt> $crate::cbor::CborDecode<$lt> for $ty {
+            fn decode_field(
+                d: &mut $crate::cbor::minicbor::Decoder<$lt>,
+            ) -> Result<Self, $crate::cbor::minicbor::decode::Error> {
+                if d.array()? != Some(0 $(+ $crate::cbor_struct!(@one $f))*) {
+                    return Err($crate::cbor::minicbor::decode::Error::message("invalid field count"));
+                }
+
+                Ok(Self {
+                    $( $f: $crate::cbor_struct!(@dec d $(, $w)?), )*
+                    $( $r: Default::default(), )*
+                })
+            }
+        }
+
+        impl<$lt, C> $crate::cbor::minicbor::Encode<C> for $ty {
+            fn encode<W: $crate::cbor::minicbor::encode::Write>(
+                &self,
+                e: &mut $crate::cbor::minicbor::Encoder<W>,
+                _ctx: &mut C,
+            ) -> Result<(), $crate::cbor::minicbor::encode::Error<W::Error>> {
+                $crate::cbor::CborEncode::encode_field(self, e)
+            }
+        }
+
+        impl<$lt, C> $crate::cbor::minicbor::Decode<$lt, C> for $ty {
+            fn decode(
+                d: &mut $crate::cbor::minicbor::Decoder<$lt>,
+                _ctx: &mut C,
+            ) -> Result<Self, $crate::cbor::minicbor::decode::Error> {
+                $crate::cbor::CborDecode::decode_field(d)
+            }
+        }
+    };
+    (@one $f:ident) => { 1 };
+    (@enc $e:ident, $v:expr) => { $crate::cbor::CborEncode::encode_field($v, $e)? };
+    (@enc $e:ident, $v:expr, $w:ident) => { $crate::cbor::$w::encode($v, $e)? };
+    (@dec $d:ident) => { $crate::cbor::CborDecode::decode_field($d)? };
+    (@dec $d:ident, $w:ident) => { $crate::cbor::$w::decode($d)? };
+}
+
+/// Helper macro implementing CBOR encoding for `bitflags` types
+#[macro_export]
+macro_rules! cbor_bitflags {
+    ($b:ty, $i:ident) => {
+        impl $crate::cbor::CborEncode for $b {
+            fn encode_field<W: $crate::cbor::minicbor::encode::Write>(
+                &self,
+                e: &mut $crate::cbor::minicbor::Encoder<W>,
+            ) -> Result<(), $crate::cbor::minicbor::encode::Error<W::Error>> {
+                e.$i(self.bits())?;
+                Ok(())
+            }
+        }
+
+        impl<'b> $crate::cbor::CborDecode<'b> for $b {
+            fn decode_field(
+                d: &mut $crate::cbor::minicbor::Decoder<'b>,
+            ) -> Result<Self, $crate::cbor::minicbor::decode::Error> {
+                Ok(<$b>::from_bits_truncate(d.$i()?))
+            }
+        }
+    };
+}
+
+/// Implement CBOR encoding for unsigned integers
+macro_rules! cbor_uint {
+    ($($i:ident),*) => {
+        $(
+            impl CborEncode for $i {
+                fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+                    e.$i(*self)?;
+                    Ok(())
+                }
+            }
+
+            impl<'b> CborDecode<'b> for $i {
+                fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+                    d.$i()
+                }
+            }
+        )*
+    };
+}
+
+cbor_uint!(u8, u16, u32, u64);
+
+/// Implement CBOR encoding for `u8` enumerations
+macro_rules! cbor_enum {
+    ($($t:ty),*) => {
+        $(
+            impl CborEncode for $t {
+                fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+                    e.u8(*self as u8)?;
+                    Ok(())
+                }
+            }
+
+            impl<'b> CborDecode<'b> for $t {
+                fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+                    <$t>::try_from(d.u8()?).map_err(|_| DecodeError::message("invalid value"))
+                }
+            }
+        )*
+    };
+}
+
+cbor_enum!(Algorithm, IdentDerivation, TxState, AuditKind, ReportEntity);
+
+impl CborEncode for FogId {
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+        e.u8(*self as u8)?;
+        Ok(())
+    }
+}
+
+impl<'b> CborDecode<'b> for FogId {
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+        use encdec::DecodeOwned;
+
+        // Match packed decoding (unknown IDs map to [FogId::None])
+        let (id, _) =
+            FogId::decode_owned(&[d.u8()?]).map_err(|_| DecodeError::message("invalid fog id"))?;
+
+        Ok(id)
+    }
+}
+
+impl<const N: usize> CborEncode for [u8; N] {
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+        e.bytes(&self[..])?;
+        Ok(())
+    }
+}
+
+impl<'b, const N: usize> CborDecode<'b> for [u8; N] {
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+        d.bytes()?
+            .try_into()
+            .map_err(|_| DecodeError::message("invalid length"))
+    }
+}
+
+impl<'a> CborEncode for &'a [u8] {
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+        e.bytes(self)?;
+        Ok(())
+    }
+}
+
+impl<'b> CborDecode<'b> for &'b [u8] {
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+        d.bytes()
+    }
+}
+
+impl<'a> CborEncode for &'a str {
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+        e.str(self)?;
+        Ok(())
+    }
+}
+
+impl<'b> CborDecode<'b> for &'b str {
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+        d.str()
+    }
+}
+
+impl<const N: usize> CborEncode for heapless::String<N> {
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+        e.str(self.as_str())?;
+        Ok(())
+    }
+}
+
+impl<'b, const N: usize> CborDecode<'b> for heapless::String<N> {
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+        let mut s = heapless::String::new();
+        s.push_str(d.str()?)
+            .map_err(|_| DecodeError::message("invalid length"))?;
+        Ok(s)
+    }
+}
+
+impl<T: CborEncode> CborEncode for Option<T> {
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+        match self {
+            Some(v) => v.encode_field(e),
+            None => {
+                e.null()?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<'b, T: CborDecode<'b>> CborDecode<'b> for Option<T> {
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+        match d.datatype()? {
+            Type::Null => {
+                d.null()?;
+                Ok(None)
+            }
+            _ => T::decode_field(d).map(Some),
+        }
+    }
+}
+
+impl<A: CborEncode, B: CborEncode> CborEncode for (A, B) {
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+        e.array(2)?;
+        self.0.encode_field(e)?;
+        self.1.encode_field(e)
+    }
+}
+
+impl<'b, A: CborDecode<'b>, B: CborDecode<'b>> CborDecode<'b> for (A, B) {
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+        if d.array()? != Some(2) {
+            return Err(DecodeError::message("invalid tuple length"));
+        }
+
+        Ok((A::decode_field(d)?, B::decode_field(d)?))
+    }
+}
+
+impl<T: CborEncode, const N: usize> CborEncode for Vec<T, N> {
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+        e.array(self.len() as u64)?;
+        for v in self.iter() {
+            v.encode_field(e)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'b, T: CborDecode<'b>, const N: usize> CborDecode<'b> for Vec<T, N> {
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+        let n = match d.array()? {
+            Some(n) if n as usize <= N => n as usize,
+            _ => return Err(DecodeError::message("invalid list length")),
+        };
+
+        let mut v = Vec::new();
+        for _i in 0..n {
+            // Capacity checked above
+            let _ = v.push(T::decode_field(d)?);
+        }
+
+        Ok(v)
+    }
+}
+
+impl<A, B> CborEncode for Key<A, B, RistrettoPublic> {
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+        let k: &RistrettoPublic = self.as_ref();
+        e.bytes(&k.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl<'b, A, B> CborDecode<'b> for Key<A, B, RistrettoPublic> {
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+        let k = RistrettoPublic::try_from(d.bytes()?)
+            .map_err(|_| DecodeError::message("invalid public key"))?;
+        Ok(Self::from(k))
+    }
+}
+
+impl<A, B> CborEncode for Key<A, B, RistrettoPrivate> {
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+        let k: &RistrettoPrivate = self.as_ref();
+        e.bytes(&k.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl<'b, A, B> CborDecode<'b> for Key<A, B, RistrettoPrivate> {
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+        let k = RistrettoPrivate::try_from(d.bytes()?)
+            .map_err(|_| DecodeError::message("invalid private key"))?;
+        Ok(Self::from(k))
+    }
+}
+
+impl CborEncode for Scalar {
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+        e.bytes(self.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl<'b> CborDecode<'b> for Scalar {
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+        // Match packed decoding (reduced modulo the group order)
+        let b = <[u8; 32]>::decode_field(d)?;
+        Ok(Scalar::from_bytes_mod_order(b))
+    }
+}
+
+impl CborEncode for KeyImage {
+    fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+        e.bytes(self.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl<'b> CborDecode<'b> for KeyImage {
+    fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+        KeyImage::try_from(d.bytes()?).map_err(|_| DecodeError::message("invalid key image"))
+    }
+}
+
+/// Implement CBOR encoding for compressed points
+macro_rules! cbor_point {
+    ($($t:ty),*) => {
+        $(
+            impl CborEncode for $t {
+                fn encode_field<W: Write>(&self, e: &mut Encoder<W>) -> Result<(), EncodeError<W::Error>> {
+                    let b: &[u8; 32] = self.as_ref();
+                    e.bytes(&b[..])?;
+                    Ok(())
+                }
+            }
+
+            impl<'b> CborDecode<'b> for $t {
+                fn decode_field(d: &mut Decoder<'b>) -> Result<Self, DecodeError> {
+                    let b = <[u8; 32]>::decode_field(d)?;
+                    Ok(<$t>::from(CompressedRistretto(b)))
+                }
+            }
+        )*
+    };
+}
+
+cbor_point!(CompressedRistrettoPublic, CompressedCommitment);
+
+/// Decode an algorithm identifier, accepting only the expected algorithm
+fn decode_alg(d: &mut Decoder<'_>, expected: Algorithm) -> Result<Algorithm, DecodeError> {
+    Algorithm::decode_field(d)?
+        .expect(expected)
+        .map_err(|_| DecodeError::message("unsupported algorithm"))
+}
+
+/// CBOR helper for Ristretto255 algorithm identifiers (account / subaddress keys),
+/// rejecting other algorithms as with packed decoding
+pub mod ristretto_alg {
+    use super::*;
+
+    /// Encode algorithm identifier
+    pub fn encode<W: Write>(
+        a: &Algorithm,
+        e: &mut Encoder<W>,
+    ) -> Result<(), EncodeError<W::Error>> {
+        a.encode_field(e)
+    }
+
+    /// Decode algorithm identifier, accepting only [Algorithm::Ristretto255]
+    pub fn decode(d: &mut Decoder<'_>) -> Result<Algorithm, DecodeError> {
+        decode_alg(d, Algorithm::Ristretto255)
+    }
+}
+
+/// CBOR helper for Ed25519 algorithm identifiers (identities),
+/// rejecting other algorithms as with packed decoding
+pub mod ed25519_alg {
+    use super::*;
+
+    /// Encode algorithm identifier
+    pub fn encode<W: Write>(
+        a: &Algorithm,
+        e: &mut Encoder<W>,
+    ) -> Result<(), EncodeError<W::Error>> {
+        a.encode_field(e)
+    }
+
+    /// Decode algorithm identifier, accepting only [Algorithm::Ed25519]
+    pub fn decode(d: &mut Decoder<'_>) -> Result<Algorithm, DecodeError> {
+        decode_alg(d, Algorithm::Ed25519)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use mc_crypto_keys::{RistrettoPrivate, RistrettoPublic};
+    use mc_transaction_types::Amount;
+    use mc_util_from_random::FromRandom;
+    use rand::random;
+    use rand_core::OsRng;
+
+    use super::*;
+    use crate::{
+        encoding::{ApduDecode, ApduEncode, Encoding},
+        prelude::*,
+    };
+
+    /// Round-trip an APDU via CBOR encoding
+    fn cbor_encode_decode<'a, A>(buff: &'a mut [u8], apdu: &A) -> usize
+    where
+        A: ApduEncode + ApduDecode<'a> + PartialEq + core::fmt::Debug,
+    {
+        let n = apdu
+            .encode_with(Encoding::Cbor, buff)
+            .expect("encode failed");
+
+        let (decoded, m) = A::decode_with(Encoding::Cbor, &buff[..n]).expect("decode failed");
+
+        assert_eq!(n, m, "decode length mismatch");
+        assert_eq!(apdu, &decoded);
+
+        n
+    }
+
+    #[test]
+    fn cbor_wallet_key_req() {
+        let mut buff = [0u8; 32];
+
+        // Check encoding matches documented example
+        let n = cbor_encode_decode(&mut buff, &WalletKeyReq::new(1));
+        assert_eq!(&buff[..n], &[0x82, 0x01, 0x01]);
+
+        // Non-ristretto algorithms are rejected
+        let r = WalletKeyReq::decode_with(Encoding::Cbor, &[0x82, 0x01, 0x02]);
+        assert!(matches!(r, Err(ApduError::InvalidEncoding)));
+
+        // As are mismatched field counts
+        let r = WalletKeyReq::decode_with(Encoding::Cbor, &[0x81, 0x01]);
+        assert!(matches!(r, Err(ApduError::InvalidEncoding)));
+    }
+
+    #[test]
+    fn cbor_apdus() {
+        let mut buff = [0u8; 512];
+
+        let private_key = RistrettoPrivate::from_random(&mut OsRng {});
+        let public_key = RistrettoPublic::from(&private_key);
+
+        cbor_encode_decode(
+            &mut buff,
+            &KeyImageReq::new(random(), random(), public_key.into()),
+        );
+        cbor_encode_decode(
+            &mut buff,
+            &KeyImageResp::new(
+                random(),
+                random(),
+                KeyImage::from(&private_key),
+                public_key.into(),
+            ),
+        );
+        cbor_encode_decode(&mut buff, &RandomReq::new(random()));
+        cbor_encode_decode(
+            &mut buff,
+            &RandomResp::new(heapless::Vec::from_slice(&random::<[u8; 32]>()).unwrap()),
+        );
+        cbor_encode_decode(&mut buff, &TxInit::new(random(), random()));
+        cbor_encode_decode(&mut buff, &TxSetMessage::new(&[0xab; 64]));
+        cbor_encode_decode(
+            &mut buff,
+            &TxSummaryBuild::new(
+                Amount::new(random(), random::<u64>().into()),
+                random(),
+                Some(random()),
+            ),
+        );
+        cbor_encode_decode(
+            &mut buff,
+            &TxSummaryBuild::new(
+                Amount::new(random(), random::<u64>().into()),
+                random(),
+                None,
+            ),
+        );
+        cbor_encode_decode(
+            &mut buff,
+            &IdentSignReq::new(random(), "test://uri", &[0xcd; 32]),
+        );
+        cbor_encode_decode(&mut buff, &IdentGetReq);
+    }
+}
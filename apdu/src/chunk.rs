@@ -0,0 +1,601 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Chunked APDU exchange, supporting request and response payloads
+//! larger than the maximum APDU payload.
+//!
+//! An encoded request is split into [ChunkReq] APDUs carrying the target
+//! instruction, which are reassembled on the device into a [ChunkBuffer].
+//! Request chunks other than the last are acknowledged with an empty
+//! [ChunkResp] containing the number of bytes received.
+//!
+//! Once the final chunk (with [ChunkFlags::LAST] set) is received the request
+//! is handled as if it were issued directly, with the encoded response written
+//! to the [ChunkBuffer] and returned in [ChunkResp] APDUs. The first response
+//! chunk is returned in reply to the final request chunk, with remaining chunks
+//! fetched using [ChunkGetReq].
+//!
+//! Responses to chunked requests are always chunked, allowing small requests
+//! to be issued as a single chunk where a large response is expected.
+
+use encdec::{Decode, Encode};
+
+use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
+
+/// Maximum data length for a single request or response chunk
+pub const CHUNK_DATA_MAX: usize = 240;
+
+/// Maximum reassembled request or response length
+pub const CHUNK_BUFF_LEN: usize = 1024;
+
+/// Length of chunk APDU headers
+const CHUNK_HEADER_LEN: usize = 8;
+
+bitflags::bitflags! {
+    /// Flags for chunked requests
+    pub struct ChunkFlags: u8 {
+        /// First chunk in a request, resets reassembly state
+        const FIRST = 1 << 0;
+        /// Last chunk in a request, executes the reassembled request
+        const LAST = 1 << 1;
+    }
+}
+
+/// Chunked request APDU, carrying a part of an encoded request
+/// for the instruction `INS`.
+///
+/// Chunks must be sent in order, starting with [ChunkFlags::FIRST]
+/// and ending with [ChunkFlags::LAST].
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |      INS      |     FLAGS     |   DATA_LEN    |    RESERVED   |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |             OFFSET            |           TOTAL_LEN           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                             DATA                              /
+/// /                       (variable length)                       /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct ChunkReq<'a> {
+    /// Instruction for the reassembled request
+    pub ins: u8,
+    /// Chunk flags
+    pub flags: ChunkFlags,
+    /// Offset of this chunk in the encoded request
+    pub offset: u16,
+    /// Total length of the encoded request
+    pub total_len: u16,
+    /// Chunk data
+    pub data: &'a [u8],
+}
+
+impl<'a> ChunkReq<'a> {
+    /// Create a new [`ChunkReq`] APDU
+    pub fn new(ins: u8, flags: ChunkFlags, offset: u16, total_len: u16, data: &'a [u8]) -> Self {
+        Self {
+            ins,
+            flags,
+            offset,
+            total_len,
+            data,
+        }
+    }
+}
+
+impl<'a> ApduStatic for ChunkReq<'a> {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::Chunk as u8;
+}
+
+impl<'a> Encode for ChunkReq<'a> {
+    type Error = ApduError;
+
+    /// Encode a [`ChunkReq`] APDU into the provided buffer
+    #[inline]
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        let n = self.data.len();
+
+        // Check data and buffer lengths
+        if n > CHUNK_DATA_MAX || buff.len() < CHUNK_HEADER_LEN + n {
+            return Err(ApduError::InvalidLength);
+        }
+
+        buff[0] = self.ins;
+        buff[1] = self.flags.bits();
+        buff[2] = n as u8;
+        buff[3] = 0;
+
+        self.offset.encode(&mut buff[4..])?;
+        self.total_len.encode(&mut buff[6..])?;
+
+        buff[CHUNK_HEADER_LEN..][..n].copy_from_slice(self.data);
+
+        Ok(CHUNK_HEADER_LEN + n)
+    }
+
+    #[inline]
+    fn encode_len(&self) -> Result<usize, ApduError> {
+        Ok(CHUNK_HEADER_LEN + self.data.len())
+    }
+}
+
+impl<'a> Decode<'a> for ChunkReq<'a> {
+    type Output = Self;
+    type Error = ApduError;
+
+    /// Decode a [`ChunkReq`] APDU from the provided buffer
+    #[inline]
+    fn decode(buff: &'a [u8]) -> Result<(Self, usize), ApduError> {
+        // Check header length
+        if buff.len() < CHUNK_HEADER_LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let ins = buff[0];
+        let flags = ChunkFlags::from_bits_truncate(buff[1]);
+        let n = buff[2] as usize;
+
+        let (offset, _) = u16::decode(&buff[4..])?;
+        let (total_len, _) = u16::decode(&buff[6..])?;
+
+        // Check data length
+        if n > CHUNK_DATA_MAX || buff.len() < CHUNK_HEADER_LEN + n {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let data = &buff[CHUNK_HEADER_LEN..][..n];
+
+        Ok((
+            Self {
+                ins,
+                flags,
+                offset,
+                total_len,
+                data,
+            },
+            CHUNK_HEADER_LEN + n,
+        ))
+    }
+}
+
+/// Request a response chunk at the provided offset,
+/// following a completed chunked request
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |             OFFSET            |            RESERVED           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct ChunkGetReq {
+    /// Offset of the requested chunk in the encoded response
+    pub offset: u16,
+    _reserved: u16,
+}
+
+impl ChunkGetReq {
+    /// Create a new [`ChunkGetReq`] APDU
+    pub fn new(offset: u16) -> Self {
+        Self {
+            offset,
+            _reserved: 0,
+        }
+    }
+}
+
+impl ApduStatic for ChunkGetReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::ChunkGet as u8;
+}
+
+/// Chunked response APDU, carrying a part of an encoded response.
+///
+/// Acknowledgements for intermediate request chunks contain no data,
+/// with `OFFSET` set to the number of request bytes received and
+/// `TOTAL_LEN` set to zero.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |             OFFSET            |           TOTAL_LEN           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   DATA_LEN    |                    RESERVED                   |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                             DATA                              /
+/// /                       (variable length)                       /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct ChunkResp<'a> {
+    /// Offset of this chunk in the encoded response
+    pub offset: u16,
+    /// Total length of the encoded response
+    pub total_len: u16,
+    /// Chunk data
+    pub data: &'a [u8],
+}
+
+impl<'a> ChunkResp<'a> {
+    /// Create a new [`ChunkResp`] APDU
+    pub fn new(offset: u16, total_len: u16, data: &'a [u8]) -> Self {
+        Self {
+            offset,
+            total_len,
+            data,
+        }
+    }
+}
+
+impl<'a> Encode for ChunkResp<'a> {
+    type Error = ApduError;
+
+    /// Encode a [`ChunkResp`] APDU into the provided buffer
+    #[inline]
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        let n = self.data.len();
+
+        // Check data and buffer lengths
+        if n > CHUNK_DATA_MAX || buff.len() < CHUNK_HEADER_LEN + n {
+            return Err(ApduError::InvalidLength);
+        }
+
+        self.offset.encode(&mut buff[0..])?;
+        self.total_len.encode(&mut buff[2..])?;
+
+        buff[4] = n as u8;
+        buff[5..CHUNK_HEADER_LEN].fill(0);
+
+        buff[CHUNK_HEADER_LEN..][..n].copy_from_slice(self.data);
+
+        Ok(CHUNK_HEADER_LEN + n)
+    }
+
+    #[inline]
+    fn encode_len(&self) -> Result<usize, ApduError> {
+        Ok(CHUNK_HEADER_LEN + self.data.len())
+    }
+}
+
+impl<'a> Decode<'a> for ChunkResp<'a> {
+    type Output = Self;
+    type Error = ApduError;
+
+    /// Decode a [`ChunkResp`] APDU from the provided buffer
+    #[inline]
+    fn decode(buff: &'a [u8]) -> Result<(Self, usize), ApduError> {
+        // Check header length
+        if buff.len() < CHUNK_HEADER_LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let (offset, _) = u16::decode(&buff[0..])?;
+        let (total_len, _) = u16::decode(&buff[2..])?;
+        let n = buff[4] as usize;
+
+        // Check data length
+        if n > CHUNK_DATA_MAX || buff.len() < CHUNK_HEADER_LEN + n {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let data = &buff[CHUNK_HEADER_LEN..][..n];
+
+        Ok((
+            Self {
+                offset,
+                total_len,
+                data,
+            },
+            CHUNK_HEADER_LEN + n,
+        ))
+    }
+}
+
+/// Chunk reassembly state
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum ChunkState {
+    /// No chunked exchange in progress
+    Idle,
+    /// Receiving request chunks
+    Request,
+    /// Returning response chunks
+    Response,
+}
+
+/// Buffer for reassembling chunked requests and returning chunked responses,
+/// used by devices to handle [ChunkReq] and [ChunkGetReq] APDUs.
+pub struct ChunkBuffer<const N: usize = CHUNK_BUFF_LEN> {
+    state: ChunkState,
+    ins: u8,
+    len: usize,
+    total_len: usize,
+    buff: [u8; N],
+}
+
+impl<const N: usize> ChunkBuffer<N> {
+    /// Create a new (empty) [ChunkBuffer]
+    pub const fn new() -> Self {
+        Self {
+            state: ChunkState::Idle,
+            ins: 0,
+            len: 0,
+            total_len: 0,
+            buff: [0u8; N],
+        }
+    }
+
+    /// Initialise a [ChunkBuffer] via pointer, avoiding stack allocation
+    ///
+    /// # Safety
+    /// `p` must be valid for writes
+    pub unsafe fn init(p: *mut Self) {
+        use core::ptr::addr_of_mut;
+
+        addr_of_mut!((*p).state).write(ChunkState::Idle);
+        addr_of_mut!((*p).ins).write(0);
+        addr_of_mut!((*p).len).write(0);
+        addr_of_mut!((*p).total_len).write(0);
+        addr_of_mut!((*p).buff).write_bytes(0, 1);
+    }
+
+    /// Reset chunk state, discarding any buffered data
+    pub fn reset(&mut self) {
+        self.state = ChunkState::Idle;
+        self.ins = 0;
+        self.len = 0;
+        self.total_len = 0;
+        self.buff.fill(0);
+    }
+
+    /// Push a request chunk, returning the instruction and reassembled request
+    /// once the final chunk has been received.
+    ///
+    /// Chunks must be pushed in order, out of order chunks reset reassembly state.
+    pub fn push(&mut self, req: &ChunkReq) -> Result<Option<(u8, &[u8])>, ApduError> {
+        // Reset state on first chunk
+        if req.flags.contains(ChunkFlags::FIRST) {
+            self.reset();
+
+            if req.total_len as usize > N {
+                return Err(ApduError::InvalidLength);
+            }
+
+            self.state = ChunkState::Request;
+            self.ins = req.ins;
+            self.total_len = req.total_len as usize;
+        }
+
+        // Check chunk follows previously received data
+        if self.state != ChunkState::Request
+            || self.ins != req.ins
+            || self.total_len != req.total_len as usize
+            || self.len != req.offset as usize
+            || self.len + req.data.len() > self.total_len
+        {
+            self.reset();
+            return Err(ApduError::InvalidEncoding);
+        }
+
+        // Append chunk data
+        self.buff[self.len..][..req.data.len()].copy_from_slice(req.data);
+        self.len += req.data.len();
+
+        // Return reassembled request on completion
+        match req.flags.contains(ChunkFlags::LAST) {
+            false => Ok(None),
+            true if self.len == self.total_len => Ok(Some((self.ins, &self.buff[..self.len]))),
+            true => {
+                self.reset();
+                Err(ApduError::InvalidLength)
+            }
+        }
+    }
+
+    /// Fetch the instruction and reassembled request following
+    /// the final request chunk, prior to a response being set
+    pub fn request(&self) -> Option<(u8, &[u8])> {
+        match self.state {
+            ChunkState::Request if self.len == self.total_len => {
+                Some((self.ins, &self.buff[..self.len]))
+            }
+            _ => None,
+        }
+    }
+
+    /// Fetch the number of request bytes received
+    pub fn received(&self) -> usize {
+        match self.state {
+            ChunkState::Request => self.len,
+            _ => 0,
+        }
+    }
+
+    /// Write a response to the chunk buffer using the provided encoder,
+    /// for return via [ChunkBuffer::get]
+    pub fn set_response<E>(
+        &mut self,
+        encode: impl FnOnce(&mut [u8]) -> Result<usize, E>,
+    ) -> Result<(), E> {
+        self.buff.fill(0);
+
+        match encode(&mut self.buff) {
+            Ok(n) => {
+                self.state = ChunkState::Response;
+                self.len = n;
+                self.total_len = n;
+                Ok(())
+            }
+            Err(e) => {
+                self.reset();
+                Err(e)
+            }
+        }
+    }
+
+    /// Fetch a response chunk at the provided offset
+    pub fn get(&self, offset: u16) -> Result<ChunkResp, ApduError> {
+        let offset = offset as usize;
+
+        // Check a response is available and offset is within the response
+        if self.state != ChunkState::Response || offset > self.len {
+            return Err(ApduError::InvalidEncoding);
+        }
+
+        let n = (self.len - offset).min(CHUNK_DATA_MAX);
+
+        Ok(ChunkResp::new(
+            offset as u16,
+            self.len as u16,
+            &self.buff[offset..][..n],
+        ))
+    }
+}
+
+impl<const N: usize> Default for ChunkBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn chunk_req_apdu() {
+        let data = [0xabu8; CHUNK_DATA_MAX];
+        let apdu = ChunkReq::new(0x20, ChunkFlags::FIRST, random(), random(), &data);
+
+        let mut buff = [0u8; 256];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn chunk_get_req_apdu() {
+        let apdu = ChunkGetReq::new(random());
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn chunk_resp_apdu() {
+        let data = [0xcdu8; CHUNK_DATA_MAX];
+        let apdu = ChunkResp::new(random(), random(), &data);
+
+        let mut buff = [0u8; 256];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn chunk_reassembly() {
+        let mut c = ChunkBuffer::<CHUNK_BUFF_LEN>::new();
+
+        let req: [u8; 600] = core::array::from_fn(|i| i as u8);
+        let total = req.len() as u16;
+
+        // Push request chunks
+        let mut offset = 0;
+        let mut res = None;
+        for d in req.chunks(CHUNK_DATA_MAX) {
+            let mut flags = ChunkFlags::empty();
+            flags.set(ChunkFlags::FIRST, offset == 0);
+            flags.set(ChunkFlags::LAST, offset + d.len() == req.len());
+
+            let r = c
+                .push(&ChunkReq::new(0x20, flags, offset as u16, total, d))
+                .unwrap();
+
+            offset += d.len();
+
+            match r {
+                Some((ins, v)) => {
+                    res = Some((
+                        ins,
+                        heapless::Vec::<u8, CHUNK_BUFF_LEN>::from_slice(v).unwrap(),
+                    ))
+                }
+                None => {
+                    assert_eq!(c.received(), offset);
+                    assert_eq!(c.request(), None);
+                }
+            }
+        }
+
+        let (ins, v) = res.expect("request not reassembled");
+        assert_eq!(ins, 0x20);
+        assert_eq!(&v[..], &req[..]);
+
+        // Reassembled request is available until a response is set
+        assert_eq!(c.request(), Some((0x20, &req[..])));
+
+        // Set and fetch response chunks
+        let resp: [u8; 300] = core::array::from_fn(|i| (i * 3) as u8);
+        c.set_response::<ApduError>(|b| {
+            b[..resp.len()].copy_from_slice(&resp);
+            Ok(resp.len())
+        })
+        .unwrap();
+        assert_eq!(c.request(), None);
+
+        let r = c.get(0).unwrap();
+        assert_eq!(r.total_len as usize, resp.len());
+        assert_eq!(r.data, &resp[..CHUNK_DATA_MAX]);
+
+        let r = c.get(CHUNK_DATA_MAX as u16).unwrap();
+        assert_eq!(r.data, &resp[CHUNK_DATA_MAX..]);
+
+        assert!(c.get(resp.len() as u16 + 1).is_err());
+    }
+
+    #[test]
+    fn chunk_reassembly_errors() {
+        let mut c = ChunkBuffer::<CHUNK_BUFF_LEN>::new();
+        let d = [0u8; 16];
+
+        // Requests exceeding the buffer length are rejected
+        let r = c.push(&ChunkReq::new(
+            0x20,
+            ChunkFlags::FIRST,
+            0,
+            CHUNK_BUFF_LEN as u16 + 1,
+            &d,
+        ));
+        assert!(matches!(r, Err(ApduError::InvalidLength)));
+
+        // As are chunks without a prior first chunk
+        let r = c.push(&ChunkReq::new(0x20, ChunkFlags::empty(), 0, 32, &d));
+        assert!(matches!(r, Err(ApduError::InvalidEncoding)));
+
+        // Out of order chunks
+        c.push(&ChunkReq::new(0x20, ChunkFlags::FIRST, 0, 48, &d))
+            .unwrap();
+        let r = c.push(&ChunkReq::new(0x20, ChunkFlags::empty(), 32, 48, &d));
+        assert!(matches!(r, Err(ApduError::InvalidEncoding)));
+
+        // Or chunks ending before the total length
+        c.push(&ChunkReq::new(0x20, ChunkFlags::FIRST, 0, 48, &d))
+            .unwrap();
+        let r = c.push(&ChunkReq::new(0x20, ChunkFlags::LAST, 16, 48, &d));
+        assert!(matches!(r, Err(ApduError::InvalidLength)));
+
+        // Responses are not available until set
+        assert!(c.get(0).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Constant-time comparison helpers
+//!
+//! Used in place of [PartialEq] for digests, key images and response
+//! scalars so comparisons on secret-adjacent data do not leak timing
+//! information.
+
+use mc_crypto_ring_signature::{CurveScalar, KeyImage};
+
+pub use subtle::ConstantTimeEq;
+
+/// Compare two byte slices in constant time
+/// (slices of differing lengths always compare unequal)
+#[inline(never)]
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+/// Compare two key images in constant time
+pub fn key_image_eq(a: &KeyImage, b: &KeyImage) -> bool {
+    ct_eq(a.as_bytes(), b.as_bytes())
+}
+
+/// Compare two response scalars in constant time
+pub fn scalar_eq(a: &CurveScalar, b: &CurveScalar) -> bool {
+    ct_eq(a.as_bytes(), b.as_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use mc_crypto_ring_signature::Scalar;
+
+    use super::*;
+
+    #[test]
+    fn ct_eq_bytes() {
+        assert!(ct_eq(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!ct_eq(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!ct_eq(&[1, 2, 3], &[1, 2]));
+    }
+
+    #[test]
+    fn ct_eq_key_material() {
+        let k1 = KeyImage::from(1);
+        let k2 = KeyImage::from(2);
+
+        assert!(key_image_eq(&k1, &k1));
+        assert!(!key_image_eq(&k1, &k2));
+
+        let s1 = CurveScalar::from(Scalar::from(7u64));
+        let s2 = CurveScalar::from(Scalar::from(8u64));
+
+        assert!(scalar_eq(&s1, &s1));
+        assert!(!scalar_eq(&s1, &s2));
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Device nonce challenge-response APDUs, for proving device presence.
+//!
+//! Hosts request a fresh device-generated nonce via [DeviceNonceReq], then
+//! submit a [SessionChallengeReq] containing a host challenge (for example a
+//! hash of the session or transaction being authorised). The device returns an
+//! [IdentResp][crate::ident::IdentResp] containing an ed25519 signature over a
+//! digest of the nonce and challenge (see [crate::digest::digest_session_challenge]).
+//!
+//! Nonces are single use and cleared when the device locks, so a valid
+//! signature proves the unlocked device was present following the nonce request.
+//!
+//! The signing key is derived via SLIP-0010 at [DEVICE_IDENT_PATH], so is
+//! stable for a given seed and may be pinned by custody back-ends.
+
+use encdec::{Decode, Encode};
+
+use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
+use crate::helpers::*;
+
+/// Device nonce length
+pub const DEVICE_NONCE_LEN: usize = 32;
+
+/// Session challenge length
+pub const SESSION_CHALLENGE_LEN: usize = 32;
+
+/// SLIP-0010 ed25519 derivation path for the device identity key
+/// (`m/44'/866'/2147483647'/1'`), a sibling of the
+/// [attestation key][crate::attestation::KEY_ATTESTATION_PATH]
+pub const DEVICE_IDENT_PATH: [u32; 4] = [
+    44 | (1 << 31),
+    866 | (1 << 31),
+    0x7fff_ffff | (1 << 31),
+    1 | (1 << 31),
+];
+
+/// Request a fresh device nonce (0 length APDU), replacing any
+/// previously issued nonce
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct DeviceNonceReq {}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(DeviceNonceReq {});
+
+impl ApduStatic for DeviceNonceReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetDeviceNonce as u8;
+}
+
+/// Device nonce response APDU
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                             NONCE                             /
+/// /                  (32-byte device generated nonce)             /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct DeviceNonceResp {
+    /// Device generated nonce
+    #[encdec(with = "arr")]
+    pub nonce: [u8; DEVICE_NONCE_LEN],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(DeviceNonceResp { nonce });
+
+impl DeviceNonceResp {
+    /// Create a new [DeviceNonceResp] APDU
+    pub fn new(nonce: [u8; DEVICE_NONCE_LEN]) -> Self {
+        Self { nonce }
+    }
+}
+
+/// Request a signature over the outstanding device nonce and provided
+/// host challenge, returning an [IdentResp][crate::ident::IdentResp]
+///
+/// This consumes the device nonce, requests without an outstanding
+/// nonce are rejected.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                           CHALLENGE                           /
+/// /                    (32-byte host challenge)                   /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SessionChallengeReq {
+    /// Host challenge
+    #[encdec(with = "arr")]
+    pub challenge: [u8; SESSION_CHALLENGE_LEN],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(SessionChallengeReq { challenge });
+
+impl SessionChallengeReq {
+    /// Create a new [SessionChallengeReq] APDU
+    pub fn new(challenge: [u8; SESSION_CHALLENGE_LEN]) -> Self {
+        Self { challenge }
+    }
+}
+
+impl ApduStatic for SessionChallengeReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::SignSessionChallenge as u8;
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn device_nonce_req_apdu() {
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &DeviceNonceReq {});
+        assert_eq!(n, 0);
+    }
+
+    #[test]
+    fn device_nonce_resp_apdu() {
+        let apdu = DeviceNonceResp::new(random());
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, DEVICE_NONCE_LEN);
+    }
+
+    #[test]
+    fn session_challenge_req_apdu() {
+        let apdu = SessionChallengeReq::new(random());
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, SESSION_CHALLENGE_LEN);
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Engine diagnostic counter APDUs, to aid debugging of unreliable
+//! transports without attaching a debugger
+
+use encdec::{Decode, Encode};
+
+use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
+
+/// Request engine diagnostic counters (0 length APDU)
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct DiagnosticsReq {}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(DiagnosticsReq {});
+
+impl ApduStatic for DiagnosticsReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetDiagnostics as u8;
+}
+
+/// Engine diagnostic counters response APDU
+///
+/// Contains counts of events processed, events resulting in errors,
+/// engine resets and rings signed since the app was started.
+/// Counters wrap on overflow.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                            EVENTS                             |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                            ERRORS                             |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                            RESETS                             |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         RINGS_SIGNED                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct DiagnosticsResp {
+    /// Events processed by the engine
+    pub events: u32,
+    /// Events resulting in an error
+    pub errors: u32,
+    /// Engine resets
+    pub resets: u32,
+    /// Rings successfully signed
+    pub rings_signed: u32,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(DiagnosticsResp {
+    events,
+    errors,
+    resets,
+    rings_signed
+});
+
+impl DiagnosticsResp {
+    /// Create a new [`DiagnosticsResp`] APDU
+    pub fn new(events: u32, errors: u32, resets: u32, rings_signed: u32) -> Self {
+        Self {
+            events,
+            errors,
+            resets,
+            rings_signed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn diagnostics_req_apdu() {
+        let apdu = DiagnosticsReq::default();
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn diagnostics_resp_apdu() {
+        let apdu = DiagnosticsResp::new(random(), random(), random(), random());
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+}
@@ -1,26 +1,75 @@
 //! Helpers for computing APDU / event digests
 //!
 //! This is used in place of `Digestible` as the same digest must be computed over engine events and APDUs.
+//!
+//! Digests are computed over a domain-separated [merlin::Transcript], binding the
+//! [Instruction] and field names so that distinct events can not produce colliding digests.
 
-use sha2::{Digest as _, Sha512_256};
+use merlin::Transcript;
 
 use mc_core::{
     account::PublicSubaddress,
     keys::{SubaddressViewPublic, TxOutPublic},
 };
-use mc_crypto_keys::CompressedRistrettoPublic;
-use mc_crypto_ring_signature::{CompressedCommitment, ReducedTxOut, Scalar};
+use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate, RistrettoPublic};
+use mc_crypto_ring_signature::{CompressedCommitment, CurveScalar, KeyImage, ReducedTxOut, Scalar};
 use mc_transaction_types::UnmaskedAmount;
 
-use crate::tx::{TxOnetimeKey, TxPrivateKey};
+use crate::{
+    tx::{FogUrl, TxMemoFlags, TxOnetimeKey, TxPrivateKey},
+    Instruction,
+};
+
+/// Domain separator for event digest transcripts
+pub const EVENT_DIGEST_DOMAIN: &[u8] = b"mob-ledger-event-digest-v1";
+
+/// Domain separator for signed transaction digest transcripts
+pub const SIGNED_TX_DIGEST_DOMAIN: &[u8] = b"mob-ledger-signed-tx-digest-v1";
+
+/// Domain separator for request payload MAC transcripts
+pub const PAYLOAD_MAC_DOMAIN: &[u8] = b"mob-ledger-payload-mac-v1";
+
+/// Length of (truncated) request payload MACs
+pub const PAYLOAD_MAC_LEN: usize = 8;
+
+/// Domain separator for key attestation transcripts
+pub const KEY_ATTESTATION_DOMAIN: &[u8] = b"mob-ledger-key-attestation-v1";
+
+/// Domain separator for device nonce session challenge transcripts
+pub const SESSION_CHALLENGE_DOMAIN: &[u8] = b"mob-ledger-session-challenge-v1";
+
+/// Compute a request payload MAC, keyed by the transaction digest prior to
+/// applying the request and binding the request sequence number and event digest
+pub fn payload_mac(digest: &[u8; 32], seq: u8, event: &[u8; 32]) -> [u8; PAYLOAD_MAC_LEN] {
+    let mut t = Transcript::new(PAYLOAD_MAC_DOMAIN);
+    t.append_message(b"key", digest);
+    t.append_message(b"seq", &[seq]);
+    t.append_message(b"event", event);
+
+    let mut b = [0u8; PAYLOAD_MAC_LEN];
+    t.challenge_bytes(b"mac", &mut b);
+    b
+}
+
+/// Create a new event transcript, binding the event instruction
+fn transcript(ins: Instruction) -> Transcript {
+    let mut t = Transcript::new(EVENT_DIGEST_DOMAIN);
+    t.append_message(b"ins", &[ins as u8]);
+    t
+}
+
+/// Finalise an event transcript to a digest
+fn finalise(mut t: Transcript) -> [u8; 32] {
+    let mut b = [0u8; 32];
+    t.challenge_bytes(b"digest", &mut b);
+    b
+}
 
 pub fn digest_tx_init(account_index: &u32, num_rings: u8) -> [u8; 32] {
-    Sha512_256::new()
-        .chain_update("tx_init")
-        .chain_update(account_index.to_le_bytes())
-        .chain_update(num_rings.to_le_bytes())
-        .finalize()
-        .into()
+    let mut t = transcript(Instruction::TxInit);
+    t.append_message(b"account_index", &account_index.to_le_bytes());
+    t.append_message(b"num_rings", &num_rings.to_le_bytes());
+    finalise(t)
 }
 
 pub fn digest_tx_sign_memo(
@@ -28,25 +77,26 @@ pub fn digest_tx_sign_memo(
     tx_public_key: &TxOutPublic,
     receiver_view_public: &SubaddressViewPublic,
     kind: &[u8; 2],
-    payload: &[u8; 48],
+    flags: TxMemoFlags,
+    payload: &[u8],
 ) -> [u8; 32] {
-    Sha512_256::new()
-        .chain_update("sign_memo")
-        .chain_update(subaddress_index.to_le_bytes())
-        .chain_update(tx_public_key.to_bytes())
-        .chain_update(receiver_view_public.to_bytes())
-        .chain_update(kind)
-        .chain_update(payload)
-        .finalize()
-        .into()
+    let mut t = transcript(Instruction::TxMemoSign);
+    t.append_u64(b"subaddress_index", *subaddress_index);
+    t.append_message(b"tx_public_key", &tx_public_key.to_bytes());
+    t.append_message(b"receiver_view_public", &receiver_view_public.to_bytes());
+    t.append_message(b"kind", kind);
+    // Flags are only bound for streamed payloads, matching single-request digests
+    if !flags.is_empty() {
+        t.append_message(b"flags", &[flags.bits()]);
+    }
+    t.append_message(b"payload", payload);
+    finalise(t)
 }
 
 pub fn digest_tx_set_message(m: &[u8]) -> [u8; 32] {
-    Sha512_256::new()
-        .chain_update("set_message")
-        .chain_update(m)
-        .finalize()
-        .into()
+    let mut t = transcript(Instruction::TxSetMessage);
+    t.append_message(b"message", m);
+    finalise(t)
 }
 
 pub fn digest_tx_summary_init(
@@ -55,14 +105,12 @@ pub fn digest_tx_summary_init(
     num_outputs: &u32,
     num_inputs: &u32,
 ) -> [u8; 32] {
-    Sha512_256::new()
-        .chain_update("tx_summary_init")
-        .chain_update(message)
-        .chain_update(block_version.to_le_bytes())
-        .chain_update(num_outputs.to_le_bytes())
-        .chain_update(num_inputs.to_le_bytes())
-        .finalize()
-        .into()
+    let mut t = transcript(Instruction::TxSummaryInit);
+    t.append_message(b"message", message);
+    t.append_message(b"block_version", &block_version.to_le_bytes());
+    t.append_message(b"num_outputs", &num_outputs.to_le_bytes());
+    t.append_message(b"num_inputs", &num_inputs.to_le_bytes());
+    finalise(t)
 }
 
 pub fn digest_tx_summary_add_output(
@@ -71,24 +119,22 @@ pub fn digest_tx_summary_add_output(
     public_key: &CompressedRistrettoPublic,
     associated_to_input_rules: bool,
 ) -> [u8; 32] {
-    let mut d = Sha512_256::new().chain_update("tx_summary_add_output");
+    let mut t = transcript(Instruction::TxSummaryAddTxOut);
 
     if let Some((commitment, value, token_id)) = masked_amount {
-        d = d
-            .chain_update(commitment.point.as_bytes())
-            .chain_update(value.to_le_bytes())
-            .chain_update(token_id);
+        t.append_message(b"commitment", commitment.point.as_bytes());
+        t.append_u64(b"masked_value", *value);
+        t.append_message(b"masked_token_id", token_id);
     }
 
-    d = d
-        .chain_update(target_key.as_bytes())
-        .chain_update(public_key.as_bytes());
-
-    if associated_to_input_rules {
-        d.update("associated_to_input_rules");
-    }
+    t.append_message(b"target_key", target_key.as_bytes());
+    t.append_message(b"public_key", public_key.as_bytes());
+    t.append_u64(
+        b"associated_to_input_rules",
+        associated_to_input_rules as u64,
+    );
 
-    d.finalize().into()
+    finalise(t)
 }
 
 pub fn digest_tx_summary_add_output_unblinding(
@@ -96,28 +142,33 @@ pub fn digest_tx_summary_add_output_unblinding(
     address: Option<&PublicSubaddress>,
     tx_private_key: Option<&TxPrivateKey>,
     fog_sig: Option<&[u8]>,
-    // TODO: add fog info here
+    fog_url: Option<&FogUrl>,
 ) -> [u8; 32] {
-    let mut d = Sha512_256::new()
-        .chain_update("tx_summary_add_output_unblinding")
-        .chain_update(unmasked_amount.value.to_le_bytes())
-        .chain_update(unmasked_amount.token_id.to_le_bytes())
-        .chain_update(unmasked_amount.blinding.as_bytes());
+    let mut t = transcript(Instruction::TxSummaryAddTxOutUnblinding);
+    t.append_u64(b"value", unmasked_amount.value);
+    t.append_u64(b"token_id", unmasked_amount.token_id);
+    t.append_message(b"blinding", unmasked_amount.blinding.as_bytes());
 
     if let Some(a) = address {
-        d.update(a.view_public.to_bytes());
-        d.update(a.spend_public.to_bytes());
+        t.append_message(b"view_public", &a.view_public.to_bytes());
+        t.append_message(b"spend_public", &a.spend_public.to_bytes());
     }
 
     if let Some(k) = tx_private_key {
-        d.update(k.to_bytes());
+        t.append_message(b"tx_private_key", &k.to_bytes());
     }
 
     if let Some(s) = fog_sig {
-        d.update(s);
+        t.append_message(b"fog_sig", s);
     }
 
-    d.finalize().into()
+    // Fog URLs are only bound where set, retaining digests for known fogs
+    if let Some(f) = fog_url {
+        t.append_message(b"fog_url", f.url().as_bytes());
+        t.append_message(b"fog_spki_hash", &f.spki_hash);
+    }
+
+    finalise(t)
 }
 
 pub fn digest_tx_summary_add_input(
@@ -125,17 +176,21 @@ pub fn digest_tx_summary_add_input(
     input_rules_digest: Option<&[u8; 32]>,
     unmasked_amount: &UnmaskedAmount,
 ) -> [u8; 32] {
-    let mut d = Sha512_256::new().chain_update(pseudo_output_commitment.point.as_bytes());
+    let mut t = transcript(Instruction::TxSummaryAddTxIn);
+    t.append_message(
+        b"pseudo_output_commitment",
+        pseudo_output_commitment.point.as_bytes(),
+    );
 
     if let Some(i) = input_rules_digest {
-        d.update(i);
+        t.append_message(b"input_rules_digest", i);
     }
 
-    d.chain_update(unmasked_amount.value.to_le_bytes())
-        .chain_update(unmasked_amount.token_id.to_le_bytes())
-        .chain_update(unmasked_amount.blinding.as_bytes())
-        .finalize()
-        .into()
+    t.append_u64(b"value", unmasked_amount.value);
+    t.append_u64(b"token_id", unmasked_amount.token_id);
+    t.append_message(b"blinding", unmasked_amount.blinding.as_bytes());
+
+    finalise(t)
 }
 
 pub fn digest_tx_summary_build(
@@ -143,13 +198,11 @@ pub fn digest_tx_summary_build(
     fee_token: &u64,
     tombstone_block: &u64,
 ) -> [u8; 32] {
-    Sha512_256::new()
-        .chain_update("tx_summary_build")
-        .chain_update(fee_value.to_le_bytes())
-        .chain_update(fee_token.to_le_bytes())
-        .chain_update(tombstone_block.to_le_bytes())
-        .finalize()
-        .into()
+    let mut t = transcript(Instruction::TxSummaryBuild);
+    t.append_u64(b"fee_value", *fee_value);
+    t.append_u64(b"fee_token", *fee_token);
+    t.append_u64(b"tombstone_block", *tombstone_block);
+    finalise(t)
 }
 
 pub fn digest_ring_init(
@@ -160,41 +213,229 @@ pub fn digest_ring_init(
     token_id: &u64,
     onetime_private_key: Option<&TxOnetimeKey>,
 ) -> [u8; 32] {
-    let mut h = Sha512_256::new()
-        .chain_update("ring_init")
-        .chain_update(ring_size.to_le_bytes())
-        .chain_update(real_index.to_le_bytes())
-        .chain_update(subaddress_index.to_le_bytes())
-        .chain_update(value.to_le_bytes())
-        .chain_update(token_id.to_le_bytes());
+    let mut t = transcript(Instruction::TxRingInit);
+    t.append_message(b"ring_size", &ring_size.to_le_bytes());
+    t.append_message(b"real_index", &real_index.to_le_bytes());
+    t.append_u64(b"subaddress_index", *subaddress_index);
+    t.append_u64(b"value", *value);
+    t.append_u64(b"token_id", *token_id);
 
     if let Some(k) = onetime_private_key {
-        h.update(k.to_bytes());
+        t.append_message(b"onetime_private_key", &k.to_bytes());
     }
 
-    h.finalize().into()
+    finalise(t)
 }
 
 pub fn digest_ring_set_blinding(blinding: &Scalar, output_blinding: &Scalar) -> [u8; 32] {
-    Sha512_256::new()
-        .chain_update("set_blinding")
-        .chain_update(blinding.as_bytes())
-        .chain_update(output_blinding.as_bytes())
-        .finalize()
-        .into()
+    let mut t = transcript(Instruction::TxSetBlinding);
+    t.append_message(b"blinding", blinding.as_bytes());
+    t.append_message(b"output_blinding", output_blinding.as_bytes());
+    finalise(t)
 }
 
 pub fn digest_ring_add_txout(n: u8, tx_out: &ReducedTxOut) -> [u8; 32] {
-    Sha512_256::new()
-        .chain_update("add_txout")
-        .chain_update(n.to_le_bytes())
-        .chain_update(tx_out.public_key.as_bytes())
-        .chain_update(tx_out.target_key.as_bytes())
-        .chain_update(tx_out.commitment.point.as_bytes())
-        .finalize()
-        .into()
+    let mut t = transcript(Instruction::TxAddTxOut);
+    t.append_message(b"index", &n.to_le_bytes());
+    t.append_message(b"public_key", tx_out.public_key.as_bytes());
+    t.append_message(b"target_key", tx_out.target_key.as_bytes());
+    t.append_message(b"commitment", tx_out.commitment.point.as_bytes());
+    finalise(t)
 }
 
 pub fn digest_ring_sign() -> [u8; 32] {
-    Sha512_256::new().chain_update("sign").finalize().into()
+    finalise(transcript(Instruction::TxSign))
+}
+
+/// Create a new key attestation transcript, binding the request
+/// instruction and host challenge
+fn attestation_transcript(ins: Instruction, challenge: &[u8; 32]) -> Transcript {
+    let mut t = Transcript::new(KEY_ATTESTATION_DOMAIN);
+    t.append_message(b"ins", &[ins as u8]);
+    t.append_message(b"challenge", challenge);
+    t
+}
+
+/// Compute the attestation digest for an attested wallet key response
+pub fn digest_wallet_key_attestation(
+    challenge: &[u8; 32],
+    account_index: u32,
+    view_private: &RistrettoPrivate,
+    spend_public: &RistrettoPublic,
+) -> [u8; 32] {
+    let mut t = attestation_transcript(Instruction::GetWalletKeysAttested, challenge);
+    t.append_message(b"account_index", &account_index.to_le_bytes());
+    t.append_message(b"view_private", &view_private.to_bytes());
+    t.append_message(b"spend_public", &spend_public.to_bytes());
+    finalise(t)
+}
+
+/// Compute the attestation digest for an attested subaddress key response
+pub fn digest_subaddress_key_attestation(
+    challenge: &[u8; 32],
+    account_index: u32,
+    subaddress_index: u64,
+    view_private: &RistrettoPrivate,
+    spend_public: &RistrettoPublic,
+) -> [u8; 32] {
+    let mut t = attestation_transcript(Instruction::GetSubaddressKeysAttested, challenge);
+    t.append_message(b"account_index", &account_index.to_le_bytes());
+    t.append_u64(b"subaddress_index", subaddress_index);
+    t.append_message(b"view_private", &view_private.to_bytes());
+    t.append_message(b"spend_public", &spend_public.to_bytes());
+    finalise(t)
+}
+
+/// Compute the digest signed in response to a session challenge, binding
+/// the device nonce and host challenge (see [crate::device_nonce])
+pub fn digest_session_challenge(nonce: &[u8; 32], challenge: &[u8; 32]) -> [u8; 32] {
+    let mut t = Transcript::new(SESSION_CHALLENGE_DOMAIN);
+    t.append_message(b"ins", &[Instruction::SignSessionChallenge as u8]);
+    t.append_message(b"nonce", nonce);
+    t.append_message(b"challenge", challenge);
+    finalise(t)
+}
+
+/// Signed transaction digest, binding the transaction message and the
+/// key images, c_zero and response values for each signed ring.
+///
+/// This is computed by the device and returned on transaction completion,
+/// allowing the host to archive a record of exactly what was signed.
+#[derive(Clone)]
+pub struct SignedTxDigest {
+    t: Transcript,
+    num_rings: u32,
+}
+
+impl SignedTxDigest {
+    /// Create a new (empty) signed transaction digest
+    pub fn new() -> Self {
+        Self {
+            t: Transcript::new(SIGNED_TX_DIGEST_DOMAIN),
+            num_rings: 0,
+        }
+    }
+
+    /// Add a signed ring to the digest
+    pub fn add_ring(
+        &mut self,
+        key_image: &KeyImage,
+        c_zero: &CurveScalar,
+        responses: &[CurveScalar],
+    ) {
+        self.t.append_u64(b"ring", self.num_rings as u64);
+        self.t.append_message(b"key_image", key_image.as_bytes());
+        self.t.append_message(b"c_zero", c_zero.as_bytes());
+
+        self.t.append_u64(b"num_responses", responses.len() as u64);
+        for r in responses {
+            self.t.append_message(b"response", r.as_bytes());
+        }
+
+        self.num_rings = self.num_rings.wrapping_add(1);
+    }
+
+    /// Number of rings added to the digest
+    pub fn num_rings(&self) -> u32 {
+        self.num_rings
+    }
+
+    /// Finalise the digest over the signed transaction message
+    pub fn finalise(mut self, message: &[u8]) -> [u8; 32] {
+        self.t.append_u64(b"num_rings", self.num_rings as u64);
+        self.t.append_message(b"message", message);
+
+        let mut b = [0u8; 32];
+        self.t.challenge_bytes(b"signed_digest", &mut b);
+        b
+    }
+}
+
+impl Default for SignedTxDigest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for SignedTxDigest {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SignedTxDigest")
+            .field("num_rings", &self.num_rings)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use mc_util_from_random::FromRandom;
+    use rand_core::OsRng;
+
+    use super::*;
+
+    #[test]
+    fn digest_domain_separation() {
+        // Events with equivalent encodings must not collide
+        assert_ne!(digest_tx_set_message(&[]), digest_ring_sign());
+        assert_ne!(digest_tx_set_message(&[0u8; 5]), digest_tx_init(&0, 0));
+    }
+
+    #[test]
+    fn signed_tx_digest() {
+        let key_image = KeyImage::from(&RistrettoPrivate::from_random(&mut OsRng {}));
+        let c_zero = CurveScalar::from(Scalar::from(7u64));
+        let responses = [CurveScalar::from(Scalar::from(3u64)); 4];
+
+        let mut a = SignedTxDigest::new();
+        a.add_ring(&key_image, &c_zero, &responses);
+        assert_eq!(a.num_rings(), 1);
+
+        // Digests are deterministic
+        assert_eq!(
+            a.clone().finalise(&[0xab; 32]),
+            a.clone().finalise(&[0xab; 32])
+        );
+
+        // And bind the message and ring values
+        assert_ne!(
+            a.clone().finalise(&[0xab; 32]),
+            a.clone().finalise(&[0xac; 32])
+        );
+
+        let mut b = SignedTxDigest::new();
+        b.add_ring(&key_image, &c_zero, &responses[..3]);
+        assert_ne!(a.finalise(&[0xab; 32]), b.finalise(&[0xab; 32]));
+    }
+
+    #[test]
+    fn key_attestation_digest() {
+        let view_private = RistrettoPrivate::from_random(&mut OsRng {});
+        let spend_public = RistrettoPublic::from(&RistrettoPrivate::from_random(&mut OsRng {}));
+
+        let a = digest_wallet_key_attestation(&[0xab; 32], 0, &view_private, &spend_public);
+
+        // Attestations bind the challenge and key context
+        assert_ne!(
+            a,
+            digest_wallet_key_attestation(&[0xac; 32], 0, &view_private, &spend_public)
+        );
+        assert_ne!(
+            a,
+            digest_wallet_key_attestation(&[0xab; 32], 1, &view_private, &spend_public)
+        );
+        assert_ne!(
+            a,
+            digest_subaddress_key_attestation(&[0xab; 32], 0, 0, &view_private, &spend_public)
+        );
+    }
+
+    #[test]
+    fn session_challenge_digest() {
+        let a = digest_session_challenge(&[0xab; 32], &[0xcd; 32]);
+
+        // Digests bind both the device nonce and host challenge
+        assert_eq!(a, digest_session_challenge(&[0xab; 32], &[0xcd; 32]));
+        assert_ne!(a, digest_session_challenge(&[0xac; 32], &[0xcd; 32]));
+        assert_ne!(a, digest_session_challenge(&[0xab; 32], &[0xce; 32]));
+        assert_ne!(a, digest_session_challenge(&[0xcd; 32], &[0xab; 32]));
+    }
 }
@@ -0,0 +1,111 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! APDU payload encodings, selected by APDU class.
+//!
+//! All APDUs support the packed encoding using the [MOB_APDU_CLA] class,
+//! with CBOR encoding available using the [MOB_APDU_CLA_CBOR] class
+//! where the `cbor` feature is enabled (see [crate::cbor]).
+
+use encdec::{Decode, Encode};
+
+use crate::{ApduError, MOB_APDU_CLA};
+
+#[cfg(feature = "cbor")]
+use crate::cbor::{CborDecode as DecodeExt, CborEncode as EncodeExt};
+
+#[cfg(not(feature = "cbor"))]
+use ext::{DecodeExt, EncodeExt};
+
+/// Placeholder bounds where optional encodings are disabled
+#[cfg(not(feature = "cbor"))]
+mod ext {
+    pub trait EncodeExt {}
+
+    impl<T: ?Sized> EncodeExt for T {}
+
+    pub trait DecodeExt<'a> {}
+
+    impl<'a, T> DecodeExt<'a> for T {}
+}
+
+/// APDU payload encoding
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Encoding {
+    /// Packed binary encoding, per the layout documented for each APDU
+    Packed,
+
+    /// CBOR encoding (see [crate::cbor])
+    #[cfg(feature = "cbor")]
+    Cbor,
+}
+
+impl Encoding {
+    /// Resolve payload encoding from APDU class, returning `None`
+    /// for unrecognised or unsupported classes
+    pub fn from_cla(cla: u8) -> Option<Self> {
+        match cla {
+            MOB_APDU_CLA => Some(Self::Packed),
+            #[cfg(feature = "cbor")]
+            crate::MOB_APDU_CLA_CBOR => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+
+    /// Fetch APDU class for this encoding
+    pub fn cla(&self) -> u8 {
+        match self {
+            Self::Packed => MOB_APDU_CLA,
+            #[cfg(feature = "cbor")]
+            Self::Cbor => crate::MOB_APDU_CLA_CBOR,
+        }
+    }
+}
+
+/// APDU objects supporting encoding with each enabled [Encoding]
+pub trait ApduEncode: Encode<Error = ApduError> + EncodeExt {
+    /// Encode object using the provided [Encoding], returning the encoded length
+    fn encode_with(&self, enc: Encoding, buff: &mut [u8]) -> Result<usize, ApduError> {
+        match enc {
+            Encoding::Packed => self.encode(buff),
+            #[cfg(feature = "cbor")]
+            Encoding::Cbor => crate::cbor::encode(self, buff),
+        }
+    }
+}
+
+impl<T: Encode<Error = ApduError> + EncodeExt> ApduEncode for T {}
+
+/// APDU objects supporting decoding with each enabled [Encoding]
+pub trait ApduDecode<'a>:
+    Decode<'a, Output = Self, Error = ApduError> + DecodeExt<'a> + Sized
+{
+    /// Decode object using the provided [Encoding], returning the object and consumed length
+    fn decode_with(enc: Encoding, buff: &'a [u8]) -> Result<(Self, usize), ApduError> {
+        match enc {
+            Encoding::Packed => Self::decode(buff),
+            #[cfg(feature = "cbor")]
+            Encoding::Cbor => crate::cbor::decode(buff),
+        }
+    }
+}
+
+impl<'a, T: Decode<'a, Output = T, Error = ApduError> + DecodeExt<'a>> ApduDecode<'a> for T {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MOB_APDU_CLA_CBOR;
+
+    #[test]
+    fn encoding_cla() {
+        assert_eq!(Encoding::from_cla(MOB_APDU_CLA), Some(Encoding::Packed));
+        assert_eq!(Encoding::Packed.cla(), MOB_APDU_CLA);
+
+        #[cfg(feature = "cbor")]
+        assert_eq!(Encoding::from_cla(MOB_APDU_CLA_CBOR), Some(Encoding::Cbor));
+        #[cfg(not(feature = "cbor"))]
+        assert_eq!(Encoding::from_cla(MOB_APDU_CLA_CBOR), None);
+
+        assert_eq!(Encoding::from_cla(0x00), None);
+    }
+}
@@ -227,3 +227,114 @@ pub(crate) mod arr {
         Ok((d, N))
     }
 }
+
+/// encdec helpers for algorithm identifiers, encoded as a single byte
+/// followed by three reserved bytes to maintain 32-bit alignment.
+///
+/// Decoding is strict, only the single algorithm supported by each
+/// request is accepted.
+pub(crate) mod alg {
+    use ledger_proto::ApduError;
+
+    use crate::algorithm::Algorithm;
+
+    pub fn enc(a: &Algorithm, buff: &mut [u8]) -> Result<usize, ApduError> {
+        if buff.len() < 4 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        buff[0] = *a as u8;
+        buff[1..4].fill(0);
+
+        Ok(4)
+    }
+
+    pub fn enc_len(_a: &Algorithm) -> Result<usize, ApduError> {
+        Ok(4)
+    }
+
+    pub fn dec_expected(buff: &[u8], expected: Algorithm) -> Result<(Algorithm, usize), ApduError> {
+        if buff.len() < 4 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let a = Algorithm::try_from(buff[0]).map_err(|_| ApduError::InvalidEncoding)?;
+
+        Ok((a.expect(expected)?, 4))
+    }
+}
+
+/// encdec helper module for Ristretto255 algorithm identifiers (account / subaddress keys)
+pub(crate) mod ristretto_alg {
+    use ledger_proto::ApduError;
+
+    use crate::algorithm::Algorithm;
+
+    pub use super::alg::{enc, enc_len};
+
+    pub fn dec(buff: &[u8]) -> Result<(Algorithm, usize), ApduError> {
+        super::alg::dec_expected(buff, Algorithm::Ristretto255)
+    }
+}
+
+/// encdec helper module for LEB128 (variable-length) encoded integers,
+/// used by compact encodings (see [crate::tx::TxSummaryAddTxOutUnblindingCompact])
+pub(crate) mod leb128 {
+    use ledger_proto::ApduError;
+
+    /// Maximum encoded length for a u64
+    pub const LEB128_MAX: usize = 10;
+
+    pub fn enc(v: &u64, buff: &mut [u8]) -> Result<usize, ApduError> {
+        let mut v = *v;
+        let mut i = 0;
+
+        loop {
+            if i >= buff.len() {
+                return Err(ApduError::InvalidLength);
+            }
+
+            let b = (v & 0x7f) as u8;
+            v >>= 7;
+
+            if v == 0 {
+                buff[i] = b;
+                return Ok(i + 1);
+            }
+
+            buff[i] = b | 0x80;
+            i += 1;
+        }
+    }
+
+    pub fn enc_len(v: &u64) -> Result<usize, ApduError> {
+        let bits = 64 - v.leading_zeros() as usize;
+        Ok((bits.max(1) + 6) / 7)
+    }
+
+    pub fn dec(buff: &[u8]) -> Result<(u64, usize), ApduError> {
+        let mut v = 0u64;
+
+        for (i, b) in buff.iter().take(LEB128_MAX).enumerate() {
+            let d = (b & 0x7f) as u64;
+
+            // Reject values overflowing a u64
+            if i == LEB128_MAX - 1 && d > 1 {
+                return Err(ApduError::InvalidEncoding);
+            }
+
+            v |= d << (i * 7);
+
+            if b & 0x80 == 0 {
+                // Reject non-canonical (zero padded) encodings
+                if i > 0 && *b == 0 {
+                    return Err(ApduError::InvalidEncoding);
+                }
+
+                return Ok((v, i + 1));
+            }
+        }
+
+        Err(ApduError::InvalidLength)
+    }
+}
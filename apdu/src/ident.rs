@@ -3,9 +3,57 @@
 //! Ed25519 Identity APDUs for SLIP-0013/17 signing support
 
 use encdec::{Decode, Encode};
+use num_enum::TryFromPrimitive;
+use strum::{Display, EnumIter, EnumString, EnumVariantNames, IntoStaticStr};
 
 use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
-use crate::helpers::arr;
+use crate::{algorithm::Algorithm, helpers::arr};
+
+/// Maximum identity URI length
+pub const IDENT_URI_MAX: usize = 64;
+
+/// Maximum identity challenge length, challenges exceeding the APDU
+/// payload must be sent using the chunked exchange (see [crate::chunk])
+pub const IDENT_CHALLENGE_MAX: usize = 255;
+
+/// Identity key derivation scheme, selecting the SLIP-0010 purpose
+/// used when deriving identity keys from a URI and index
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Debug,
+    Default,
+    EnumString,
+    Display,
+    EnumVariantNames,
+    EnumIter,
+    IntoStaticStr,
+    TryFromPrimitive,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum IdentDerivation {
+    /// SLIP-0013 hierarchical deterministic identities (purpose 13)
+    ///
+    /// https://github.com/satoshilabs/slips/blob/master/slip-0013.md
+    #[default]
+    #[strum(serialize = "slip13", to_string = "SLIP-0013")]
+    Slip13 = 13,
+
+    /// SLIP-0017 ECDH / signing identities (purpose 17)
+    ///
+    /// https://github.com/satoshilabs/slips/blob/master/slip-0017.md
+    #[strum(serialize = "slip17", to_string = "SLIP-0017")]
+    Slip17 = 17,
+}
+
+impl IdentDerivation {
+    /// Fetch the (unhardened) SLIP-0010 purpose for this derivation
+    pub const fn purpose(&self) -> u32 {
+        *self as u32
+    }
+}
 
 /// Request an ed25519 identity for provided URI and index,
 /// returning a state object.
@@ -13,6 +61,13 @@ use crate::helpers::arr;
 /// See [IdentGetReq] for fetching the identity and challenge following
 /// user approval.
 ///
+/// `ALGORITHM` must be [Algorithm::Ed25519], `DERIVATION` selects the
+/// [IdentDerivation] with `0` retained as [IdentDerivation::Slip13]
+/// for compatibility with earlier hosts.
+///
+/// `IDENTITY_LEN` must not exceed [IDENT_URI_MAX], requests where the
+/// challenge would exceed the APDU payload must be chunked.
+///
 /// ## Encoding:
 /// ```text
 ///  0                   1                   2                   3
@@ -20,7 +75,7 @@ use crate::helpers::arr;
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                       IDENTITY_INDEX                          |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |  IDENTITY_LEN | CHALLENGE_LEN |           RESERVED            |
+/// |  IDENTITY_LEN | CHALLENGE_LEN |   ALGORITHM   |   DERIVATION  |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                                                               |
 /// /                         IDENTITY_URI                          /
@@ -41,15 +96,33 @@ pub struct IdentSignReq<'a> {
     pub identity_uri: &'a str,
     /// Challenge to be signed
     pub challenge: &'a [u8],
+    /// Identity key algorithm
+    pub algorithm: Algorithm,
+    /// Identity key derivation
+    pub derivation: IdentDerivation,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    IdentSignReq<'a> {
+        identity_index,
+        identity_uri,
+        challenge,
+        algorithm: ed25519_alg,
+        derivation,
+    }
+);
+
 impl<'a> IdentSignReq<'a> {
-    /// Create a new application version APDU
+    /// Create a new identity signing APDU using the default
+    /// [IdentDerivation::Slip13] derivation
     pub fn new(identity_index: u32, identity_uri: &'a str, challenge: &'a [u8]) -> Self {
         Self {
             identity_index,
             identity_uri,
             challenge,
+            algorithm: Algorithm::Ed25519,
+            derivation: IdentDerivation::Slip13,
         }
     }
 }
@@ -68,6 +141,11 @@ impl<'a> Encode for IdentSignReq<'a> {
         let mut index = 0;
         let d = self.identity_uri.as_bytes();
 
+        // Check field lengths are encodable
+        if d.len() > IDENT_URI_MAX || self.challenge.len() > IDENT_CHALLENGE_MAX {
+            return Err(ApduError::InvalidLength);
+        }
+
         // Check buffer length is valid
         if buff.len() < d.len() + self.challenge.len() + 8 {
             return Err(ApduError::InvalidLength);
@@ -84,8 +162,13 @@ impl<'a> Encode for IdentSignReq<'a> {
         buff[index] = self.challenge.len() as u8;
         index += 1;
 
-        // Write padding
-        index += 2;
+        // Write algorithm
+        buff[index] = self.algorithm as u8;
+        index += 1;
+
+        // Write derivation
+        buff[index] = self.derivation as u8;
+        index += 1;
 
         // Write uri
         buff[index..][..d.len()].copy_from_slice(d);
@@ -130,11 +213,21 @@ impl<'a> Decode<'a> for IdentSignReq<'a> {
         let challenge_len = buff[index] as usize;
         index += 1;
 
-        // Skip padding
-        index += 2;
+        // Read algorithm, only ed25519 identities are supported
+        let algorithm = Algorithm::try_from(buff[index])
+            .map_err(|_| ApduError::InvalidEncoding)?
+            .expect(Algorithm::Ed25519)?;
+        index += 1;
+
+        // Read derivation, zero maps to SLIP-0013 for compatibility
+        let derivation = match buff[index] {
+            0 => IdentDerivation::Slip13,
+            v => IdentDerivation::try_from(v).map_err(|_| ApduError::InvalidEncoding)?,
+        };
+        index += 1;
 
-        // Check full buffer length (MOB-06.8)
-        if buff.len() < 8 + uri_len + challenge_len {
+        // Check URI and full buffer length (MOB-06.8)
+        if uri_len > IDENT_URI_MAX || buff.len() < 8 + uri_len + challenge_len {
             return Err(ApduError::InvalidLength);
         }
 
@@ -151,6 +244,8 @@ impl<'a> Decode<'a> for IdentSignReq<'a> {
                 identity_index,
                 identity_uri,
                 challenge,
+                algorithm,
+                derivation,
             },
             index,
         ))
@@ -163,6 +258,9 @@ impl<'a> Decode<'a> for IdentSignReq<'a> {
 #[encdec(error = "ApduError")]
 pub struct IdentGetReq;
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(IdentGetReq {});
+
 impl ApduStatic for IdentGetReq {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::IdentGetReq as u8;
@@ -197,6 +295,12 @@ pub struct IdentResp {
     pub signature: [u8; 64],
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(IdentResp {
+    public_key,
+    signature
+});
+
 impl IdentResp {
     /// Create a new [`IdentResp`] APDU
     pub fn new(public_key: [u8; 32], signature: [u8; 64]) -> Self {
@@ -210,6 +314,7 @@ impl IdentResp {
 #[cfg(test)]
 mod test {
     use rand::random;
+    use strum::IntoEnumIterator;
 
     use super::*;
     use crate::test::encode_decode_apdu;
@@ -226,6 +331,60 @@ mod test {
         encode_decode_apdu(&mut buff, &apdu);
     }
 
+    #[test]
+    fn identity_key_req_apdu_algorithm() {
+        let mut apdu = IdentSignReq::new(random(), "ssh://someone@something.com", &[1, 2, 3]);
+        apdu.algorithm = Algorithm::Ristretto255;
+
+        // Unsupported algorithms are rejected on decode
+        let mut buff = [0u8; 256];
+        let n = apdu.encode(&mut buff).unwrap();
+        assert!(IdentSignReq::decode(&buff[..n]).is_err());
+    }
+
+    #[test]
+    fn identity_key_req_apdu_derivation() {
+        let mut buff = [0u8; 512];
+
+        for d in IdentDerivation::iter() {
+            let mut apdu = IdentSignReq::new(random(), "ssh://someone@something.com", &[1, 2]);
+            apdu.derivation = d;
+
+            encode_decode_apdu(&mut buff, &apdu);
+        }
+
+        // Zero derivation bytes decode as SLIP-0013
+        let apdu = IdentSignReq::new(random(), "ssh://someone@something.com", &[1, 2, 3]);
+        let n = apdu.encode(&mut buff).unwrap();
+        buff[7] = 0;
+
+        let (decoded, _) = IdentSignReq::decode(&buff[..n]).unwrap();
+        assert_eq!(decoded.derivation, IdentDerivation::Slip13);
+
+        // Unknown derivations are rejected
+        buff[7] = 0x20;
+        assert!(IdentSignReq::decode(&buff[..n]).is_err());
+    }
+
+    #[test]
+    fn identity_key_req_apdu_long_challenge() {
+        let challenge = [0xab; IDENT_CHALLENGE_MAX];
+        let apdu = IdentSignReq::new(random(), "ssh://someone@something.com", &challenge);
+
+        // Long challenges exceed the APDU payload, so check the packed encoding directly
+        let mut buff = [0u8; 512];
+        let n = apdu.encode(&mut buff).unwrap();
+        assert_eq!(n, apdu.encode_len().unwrap());
+
+        let (decoded, _) = IdentSignReq::decode(&buff[..n]).unwrap();
+        assert_eq!(decoded, apdu);
+
+        // Oversized challenges are rejected on encode
+        let challenge = [0xab; IDENT_CHALLENGE_MAX + 1];
+        let apdu = IdentSignReq::new(random(), "ssh://someone@something.com", &challenge);
+        assert!(apdu.encode(&mut buff).is_err());
+    }
+
     #[test]
     fn identity_key_resp_apdu() {
         let mut sig = [0u8; 64];
@@ -0,0 +1,92 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Key export counter APDUs, for monitoring how often view keys
+//! have been exported from the device since the last unlock
+
+use encdec::{Decode, Encode};
+
+use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
+
+/// Request key export counters (0 length APDU)
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct KeyCountersReq {}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(KeyCountersReq {});
+
+impl ApduStatic for KeyCountersReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetKeyCounters as u8;
+}
+
+/// Key export counter response APDU
+///
+/// Contains the number of wallet and subaddress key exports since the
+/// device was last unlocked, and the number of exports permitted for each
+/// before re-approval of that key type is required (zero if unlimited).
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                       WALLET_KEY_COUNT                        |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                     SUBADDRESS_KEY_COUNT                      |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         REQUEST_LIMIT                         |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct KeyCountersResp {
+    /// Wallet (root) key exports since last unlock
+    pub wallet_keys: u32,
+    /// Subaddress key exports since last unlock
+    pub subaddress_keys: u32,
+    /// Exports permitted before re-approval is required (0 for unlimited)
+    pub limit: u32,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(KeyCountersResp {
+    wallet_keys,
+    subaddress_keys,
+    limit
+});
+
+impl KeyCountersResp {
+    /// Create a new [`KeyCountersResp`] APDU
+    pub fn new(wallet_keys: u32, subaddress_keys: u32, limit: u32) -> Self {
+        Self {
+            wallet_keys,
+            subaddress_keys,
+            limit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn key_counters_req_apdu() {
+        let apdu = KeyCountersReq::default();
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn key_counters_resp_apdu() {
+        let apdu = KeyCountersResp::new(random(), random(), random());
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+}
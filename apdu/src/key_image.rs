@@ -2,15 +2,22 @@
 
 //! Key Image APDUs, used for key matching
 
-use encdec::{Decode, Encode};
-use mc_core::keys::TxOutPublic;
+use encdec::{Decode, DecodeOwned, Encode};
+use heapless::Vec;
+use mc_core::keys::{SubaddressSpendPublic, TxOutPublic};
 use mc_crypto_ring_signature::KeyImage;
 
 use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
-use crate::helpers::{ki, pub_key};
+use crate::{
+    algorithm::Algorithm,
+    helpers::{ki, pri_key, pub_key, ristretto_alg},
+    tx::TxOnetimeKey,
+};
 
 /// Resolve a key image for a specific subaddress and `txout_public_key`
 ///
+/// `ALGORITHM` must be [Algorithm::Ristretto255].
+///
 /// ## Encoding:
 /// ```text
 ///  0                   1                   2                   3
@@ -26,6 +33,8 @@ use crate::helpers::{ki, pub_key};
 /// /                 (32-byte Ristretto Public Key)                /
 /// |                                                               |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   ALGORITHM   |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
 #[derive(Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
@@ -37,8 +46,19 @@ pub struct KeyImageReq {
     /// TX_OUT public key
     #[encdec(with = "pub_key")]
     pub txout_public_key: TxOutPublic,
+    /// Key algorithm
+    #[encdec(with = "ristretto_alg")]
+    pub algorithm: Algorithm,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(KeyImageReq {
+    account_index,
+    subaddress_index,
+    txout_public_key,
+    algorithm: ristretto_alg,
+});
+
 impl KeyImageReq {
     /// Create a new application version APDU
     pub fn new(account_index: u32, subaddress_index: u64, txout_public_key: TxOutPublic) -> Self {
@@ -46,6 +66,7 @@ impl KeyImageReq {
             account_index,
             subaddress_index,
             txout_public_key,
+            algorithm: Algorithm::Ristretto255,
         }
     }
 }
@@ -55,8 +76,73 @@ impl ApduStatic for KeyImageReq {
     const INS: u8 = Instruction::GetKeyImage as u8;
 }
 
+/// Compute a key image from a host-supplied onetime private key,
+/// for view-only-signer workflows where the host has recovered the key.
+///
+/// Account and subaddress indices are informational only and returned
+/// in the [KeyImageResp].
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                       SUBADDRESS_INDEX                        |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                      ONETIME_PRIVATE_KEY                      /
+/// /                (32-byte Ristretto Private Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct KeyImageFromKeyReq {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// Subkey index
+    pub subaddress_index: u64,
+    /// Onetime private key for the TxOut
+    #[encdec(with = "pri_key")]
+    pub onetime_private_key: TxOnetimeKey,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(KeyImageFromKeyReq {
+    account_index,
+    subaddress_index,
+    onetime_private_key
+});
+
+impl KeyImageFromKeyReq {
+    /// Create a new [KeyImageFromKeyReq] APDU
+    pub fn new(
+        account_index: u32,
+        subaddress_index: u64,
+        onetime_private_key: TxOnetimeKey,
+    ) -> Self {
+        Self {
+            account_index,
+            subaddress_index,
+            onetime_private_key,
+        }
+    }
+}
+
+impl ApduStatic for KeyImageFromKeyReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetKeyImageFromKey as u8;
+}
+
 /// Key image response APDU
 ///
+/// Includes the spend public key for the subaddress derived by the device,
+/// allowing hosts to check this matches the expected subaddress prior to
+/// trusting the returned key image.
+///
 /// ## Encoding:
 /// ```text
 ///  0                   1                   2                   3
@@ -72,8 +158,13 @@ impl ApduStatic for KeyImageReq {
 /// /               (32-byte compressed Ristretto point)            /
 /// |                                                               |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                         SPEND_PUBLIC                          /
+/// /                 (32-byte Ristretto Public Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
-#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
 pub struct KeyImageResp {
     /// SLIP-0010 account index
@@ -83,16 +174,339 @@ pub struct KeyImageResp {
     /// Key Image (compressed point)
     #[encdec(with = "ki")]
     pub key_image: KeyImage,
+    /// Subaddress spend public key
+    #[encdec(with = "pub_key")]
+    pub spend_public: SubaddressSpendPublic,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(KeyImageResp {
+    account_index,
+    subaddress_index,
+    key_image,
+    spend_public
+});
+
 impl KeyImageResp {
     /// Create a new [`KeyImage`] APDU
-    pub fn new(account_index: u32, subaddress_index: u64, key_image: KeyImage) -> Self {
+    pub fn new(
+        account_index: u32,
+        subaddress_index: u64,
+        key_image: KeyImage,
+        spend_public: SubaddressSpendPublic,
+    ) -> Self {
         Self {
             account_index,
             subaddress_index,
             key_image,
+            spend_public,
+        }
+    }
+}
+
+/// Maximum number of TxOuts in a single [KeyImageBatchReq]
+pub const KEY_IMAGE_BATCH_MAX: usize = 6;
+
+/// Maximum number of key images returned in a single [KeyImageBatchResp] page
+pub const KEY_IMAGE_BATCH_PAGE: usize = 3;
+
+/// Resolve key images for a batch of `txout_public_key`s owned by
+/// subaddresses of a single account, for scanning
+///
+/// `COUNT` must be between 1 and [KEY_IMAGE_BATCH_MAX]. Responses are paged,
+/// with `OFFSET` selecting the first entry returned in the
+/// [KeyImageBatchResp], hosts should repeat the request with the next offset
+/// until all entries have been returned.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     COUNT     |    OFFSET     |           RESERVED            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                       SUBADDRESS_INDEX                        |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                        TXOUT_PUBLIC_KEY                       /
+/// /                 (32-byte Ristretto Public Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /               ... repeated for COUNT TxOuts ...               /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct KeyImageBatchReq {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// Index of the first entry to be returned in the response page
+    pub offset: u8,
+    /// Subaddress indices and TX_OUT public keys
+    pub entries: Vec<(u64, TxOutPublic), KEY_IMAGE_BATCH_MAX>,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(KeyImageBatchReq {
+    account_index,
+    offset,
+    entries
+});
+
+impl KeyImageBatchReq {
+    /// Create a new [KeyImageBatchReq] APDU
+    pub fn new(
+        account_index: u32,
+        offset: u8,
+        entries: Vec<(u64, TxOutPublic), KEY_IMAGE_BATCH_MAX>,
+    ) -> Self {
+        Self {
+            account_index,
+            offset,
+            entries,
+        }
+    }
+}
+
+impl ApduStatic for KeyImageBatchReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetKeyImageBatch as u8;
+}
+
+impl Encode for KeyImageBatchReq {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(8 + self.entries.len() * 40)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut index = 0;
+
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < self.encode_len()? {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write header
+        index += self.account_index.encode(&mut buff[index..])?;
+        buff[index] = self.entries.len() as u8;
+        buff[index + 1] = self.offset;
+        buff[index + 2..][..2].fill(0);
+        index += 4;
+
+        // Write entries
+        for (subaddress_index, txout_public_key) in &self.entries {
+            index += subaddress_index.encode(&mut buff[index..])?;
+            index += pub_key::enc(txout_public_key, &mut buff[index..])?;
+        }
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for KeyImageBatchReq {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        let mut index = 0;
+
+        // Check header length (MOB-06.7)
+        if buff.len() < 8 {
+            return Err(ApduError::InvalidLength);
         }
+
+        // Read header
+        let (account_index, n) = u32::decode_owned(&buff[index..])?;
+        index += n;
+        let count = buff[index] as usize;
+        let offset = buff[index + 1];
+        index += 4;
+
+        // Check count and full buffer length (MOB-06.7)
+        if count > KEY_IMAGE_BATCH_MAX || buff.len() < 8 + count * 40 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read entries
+        let mut entries = Vec::new();
+        for _i in 0..count {
+            let (subaddress_index, n) = u64::decode_owned(&buff[index..])?;
+            index += n;
+            let (txout_public_key, n) = pub_key::dec(&buff[index..])?;
+            index += n;
+
+            let _ = entries.push((subaddress_index, txout_public_key));
+        }
+
+        Ok((
+            Self {
+                account_index,
+                offset,
+                entries,
+            },
+            index,
+        ))
+    }
+}
+
+/// Key image batch response APDU, containing a page of key images and
+/// subaddress spend public keys for entries in a [KeyImageBatchReq]
+///
+/// `OFFSET` is the index of the first returned entry in the request, and
+/// `TOTAL` the number of entries in the request, further pages remain
+/// where `OFFSET + COUNT < TOTAL`.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     COUNT     |    OFFSET     |     TOTAL     |   RESERVED    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                           KEY_IMAGE                           /
+/// /               (32-byte compressed Ristretto point)            /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                         SPEND_PUBLIC                          /
+/// /                 (32-byte Ristretto Public Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /              ... repeated for COUNT entries ...               /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct KeyImageBatchResp {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// Index of the first returned entry in the request
+    pub offset: u8,
+    /// Total number of entries in the request
+    pub total: u8,
+    /// Key images and subaddress spend public keys
+    pub entries: Vec<(KeyImage, SubaddressSpendPublic), KEY_IMAGE_BATCH_PAGE>,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(KeyImageBatchResp {
+    account_index,
+    offset,
+    total,
+    entries
+});
+
+impl KeyImageBatchResp {
+    /// Create a new [KeyImageBatchResp] APDU
+    pub fn new(
+        account_index: u32,
+        offset: u8,
+        total: u8,
+        entries: Vec<(KeyImage, SubaddressSpendPublic), KEY_IMAGE_BATCH_PAGE>,
+    ) -> Self {
+        Self {
+            account_index,
+            offset,
+            total,
+            entries,
+        }
+    }
+
+    /// Check whether further pages remain following this response
+    pub fn more(&self) -> bool {
+        (self.offset as usize + self.entries.len()) < self.total as usize
+    }
+}
+
+impl Encode for KeyImageBatchResp {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(8 + self.entries.len() * 64)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut index = 0;
+
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < self.encode_len()? {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write header
+        index += self.account_index.encode(&mut buff[index..])?;
+        buff[index] = self.entries.len() as u8;
+        buff[index + 1] = self.offset;
+        buff[index + 2] = self.total;
+        buff[index + 3] = 0;
+        index += 4;
+
+        // Write entries
+        for (key_image, spend_public) in &self.entries {
+            index += ki::enc(key_image, &mut buff[index..])?;
+            index += pub_key::enc(spend_public, &mut buff[index..])?;
+        }
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for KeyImageBatchResp {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        let mut index = 0;
+
+        // Check header length (MOB-06.7)
+        if buff.len() < 8 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read header
+        let (account_index, n) = u32::decode_owned(&buff[index..])?;
+        index += n;
+        let count = buff[index] as usize;
+        let offset = buff[index + 1];
+        let total = buff[index + 2];
+        index += 4;
+
+        // Check count and full buffer length (MOB-06.7)
+        if count > KEY_IMAGE_BATCH_PAGE || buff.len() < 8 + count * 64 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Page must lie within the request
+        if total as usize > KEY_IMAGE_BATCH_MAX || offset as usize + count > total as usize {
+            return Err(ApduError::InvalidEncoding);
+        }
+
+        // Read entries
+        let mut entries = Vec::new();
+        for _i in 0..count {
+            let (key_image, n) = ki::dec(&buff[index..])?;
+            index += n;
+            let (spend_public, n) = pub_key::dec(&buff[index..])?;
+            index += n;
+
+            let _ = entries.push((key_image, spend_public));
+        }
+
+        Ok((
+            Self {
+                account_index,
+                offset,
+                total,
+                entries,
+            },
+            index,
+        ))
     }
 }
 
@@ -117,13 +531,95 @@ mod test {
         encode_decode_apdu(&mut buff, &apdu);
     }
 
+    #[test]
+    fn key_image_from_key_apdu() {
+        let onetime_private_key = RistrettoPrivate::from_random(&mut OsRng {});
+
+        let apdu = KeyImageFromKeyReq::new(random(), random(), onetime_private_key.into());
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
     #[test]
     fn key_image_ans_apdu() {
         let key_image = KeyImage::from(&RistrettoPrivate::from_random(&mut OsRng {}));
+        let spend_public = RistrettoPublic::from(&RistrettoPrivate::from_random(&mut OsRng {}));
+
+        let apdu = KeyImageResp::new(random(), random(), key_image, spend_public.into());
+
+        let mut buff = [0u8; 256];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 76);
+    }
+
+    #[test]
+    fn key_image_batch_req_apdu() {
+        let mut entries = Vec::new();
+        for _i in 0..KEY_IMAGE_BATCH_MAX {
+            let pub_key = RistrettoPublic::from(&RistrettoPrivate::from_random(&mut OsRng {}));
+            entries.push((random(), pub_key.into())).unwrap();
+        }
 
-        let apdu = KeyImageResp::new(random(), random(), key_image);
+        // Maximum length requests fit within a single APDU
+        let apdu = KeyImageBatchReq::new(random(), 3, entries);
 
         let mut buff = [0u8; 256];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 8 + KEY_IMAGE_BATCH_MAX * 40);
+
+        // Counts exceeding the batch limit are rejected
+        buff[4] = KEY_IMAGE_BATCH_MAX as u8 + 1;
+        assert!(matches!(
+            KeyImageBatchReq::decode_owned(&buff),
+            Err(ApduError::InvalidLength)
+        ));
+
+        // As are truncated requests
+        buff[4] = KEY_IMAGE_BATCH_MAX as u8;
+        assert!(matches!(
+            KeyImageBatchReq::decode_owned(&buff[..n - 1]),
+            Err(ApduError::InvalidLength)
+        ));
+    }
+
+    #[test]
+    fn key_image_batch_resp_apdu() {
+        let mut entries = Vec::new();
+        for _i in 0..KEY_IMAGE_BATCH_PAGE {
+            let key_image = KeyImage::from(&RistrettoPrivate::from_random(&mut OsRng {}));
+            let spend_public = RistrettoPublic::from(&RistrettoPrivate::from_random(&mut OsRng {}));
+            entries.push((key_image, spend_public.into())).unwrap();
+        }
+
+        // Maximum length responses fit within a single APDU
+        let apdu = KeyImageBatchResp::new(random(), 0, KEY_IMAGE_BATCH_MAX as u8, entries);
+        assert!(apdu.more());
+
+        let mut buff = [0u8; 256];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 8 + KEY_IMAGE_BATCH_PAGE * 64);
+
+        // Final page
+        let apdu = KeyImageBatchResp {
+            offset: KEY_IMAGE_BATCH_PAGE as u8,
+            ..apdu
+        };
+        assert!(!apdu.more());
         encode_decode_apdu(&mut buff, &apdu);
+
+        // Pages must lie within the request
+        buff[5] = KEY_IMAGE_BATCH_PAGE as u8 + 1;
+        assert!(matches!(
+            KeyImageBatchResp::decode_owned(&buff),
+            Err(ApduError::InvalidEncoding)
+        ));
+
+        // Counts exceeding the page limit are rejected
+        buff[4] = KEY_IMAGE_BATCH_PAGE as u8 + 1;
+        assert!(matches!(
+            KeyImageBatchResp::decode_owned(&buff),
+            Err(ApduError::InvalidLength)
+        ));
     }
 }
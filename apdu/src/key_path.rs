@@ -0,0 +1,258 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Explicit SLIP-0010 key derivation paths, for custody integrations
+//! mandating path schemes other than the default `m/44'/866'/account'`.
+//!
+//! Paths are used by [WalletKeyPathReq][crate::wallet_keys::WalletKeyPathReq]
+//! and [SubaddressKeyPathReq][crate::subaddress_keys::SubaddressKeyPathReq]
+//! in place of an account index. SLIP-0010 ed25519 derivation only supports
+//! hardened children, and the device limits paths to the application's
+//! [KEY_PATH_PREFIX].
+
+use encdec::{DecodeOwned, Encode};
+
+use super::ApduError;
+
+/// Maximum number of path levels in a [KeyPath]
+pub const KEY_PATH_MAX: usize = 5;
+
+/// Encoded [KeyPath] length
+pub const KEY_PATH_LEN: usize = 4 + KEY_PATH_MAX * 4;
+
+/// Hardened derivation flag for path elements
+pub const KEY_PATH_HARDENED: u32 = 1 << 31;
+
+/// Path prefix permitted for application key derivation (`m/44'/866'`)
+pub const KEY_PATH_PREFIX: [u32; 2] = [44 | KEY_PATH_HARDENED, 866 | KEY_PATH_HARDENED];
+
+/// SLIP-0010 derivation path of up to [KEY_PATH_MAX] levels
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   PATH_LEN    |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /                             PATH                              /
+/// /         (KEY_PATH_MAX u32 elements, unused elements zero)     /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyPath {
+    len: u8,
+    path: [u32; KEY_PATH_MAX],
+}
+
+impl KeyPath {
+    /// Create a new [KeyPath], failing for empty paths
+    /// or paths exceeding [KEY_PATH_MAX] levels
+    pub fn new(path: &[u32]) -> Result<Self, ApduError> {
+        if path.is_empty() || path.len() > KEY_PATH_MAX {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let mut p = [0u32; KEY_PATH_MAX];
+        p[..path.len()].copy_from_slice(path);
+
+        Ok(Self {
+            len: path.len() as u8,
+            path: p,
+        })
+    }
+
+    /// Fetch path elements
+    pub fn as_slice(&self) -> &[u32] {
+        &self.path[..self.len as usize]
+    }
+
+    /// Fetch the number of path levels
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Check whether the path is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check all path elements are hardened
+    pub fn is_hardened(&self) -> bool {
+        self.as_slice().iter().all(|p| p & KEY_PATH_HARDENED != 0)
+    }
+
+    /// Check the path is hardened and within [KEY_PATH_PREFIX]
+    pub fn is_permitted(&self) -> bool {
+        self.len() > KEY_PATH_PREFIX.len()
+            && self.as_slice().starts_with(&KEY_PATH_PREFIX)
+            && self.is_hardened()
+    }
+
+    /// Fetch the final path element (without the hardened flag),
+    /// used in place of the account index in key responses
+    pub fn account_index(&self) -> u32 {
+        self.as_slice()
+            .last()
+            .map(|p| p & !KEY_PATH_HARDENED)
+            .unwrap_or(0)
+    }
+}
+
+impl Encode for KeyPath {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, ApduError> {
+        Ok(KEY_PATH_LEN)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        if buff.len() < KEY_PATH_LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        buff[0] = self.len;
+        buff[1..4].fill(0);
+
+        for (i, p) in self.path.iter().enumerate() {
+            buff[4 + i * 4..][..4].copy_from_slice(&p.to_le_bytes());
+        }
+
+        Ok(KEY_PATH_LEN)
+    }
+}
+
+impl DecodeOwned for KeyPath {
+    type Output = Self;
+
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), ApduError> {
+        if buff.len() < KEY_PATH_LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let len = buff[0] as usize;
+        if len == 0 || len > KEY_PATH_MAX {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let mut path = [0u32; KEY_PATH_MAX];
+        for (i, p) in path.iter_mut().enumerate() {
+            let mut b = [0u8; 4];
+            b.copy_from_slice(&buff[4 + i * 4..][..4]);
+            *p = u32::from_le_bytes(b);
+        }
+
+        // Unused path elements must be zero
+        if path[len..].iter().any(|p| *p != 0) {
+            return Err(ApduError::InvalidEncoding);
+        }
+
+        Ok((
+            Self {
+                len: len as u8,
+                path,
+            },
+            KEY_PATH_LEN,
+        ))
+    }
+}
+
+/// CBOR encoding for [KeyPath], as an array of path elements
+#[cfg(feature = "cbor")]
+impl crate::cbor::CborEncode for KeyPath {
+    fn encode_field<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(self.len() as u64)?;
+        for p in self.as_slice() {
+            e.u32(*p)?;
+        }
+        Ok(())
+    }
+}
+
+/// CBOR decoding for [KeyPath], from an array of path elements
+#[cfg(feature = "cbor")]
+impl<'b> crate::cbor::CborDecode<'b> for KeyPath {
+    fn decode_field(d: &mut minicbor::Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        let n = match d.array()? {
+            Some(n) if n > 0 && n <= KEY_PATH_MAX as u64 => n as usize,
+            _ => return Err(minicbor::decode::Error::message("invalid path length")),
+        };
+
+        let mut path = [0u32; KEY_PATH_MAX];
+        for p in path.iter_mut().take(n) {
+            *p = d.u32()?;
+        }
+
+        Ok(Self { len: n as u8, path })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn key_path_encode_decode() {
+        let p = KeyPath::new(&[44 | KEY_PATH_HARDENED, 866 | KEY_PATH_HARDENED, 3, 4]).unwrap();
+
+        let mut buff = [0u8; KEY_PATH_LEN];
+        assert_eq!(p.encode(&mut buff), Ok(KEY_PATH_LEN));
+
+        let (d, n) = KeyPath::decode_owned(&buff).unwrap();
+        assert_eq!(n, KEY_PATH_LEN);
+        assert_eq!(d, p);
+        assert_eq!(d.as_slice(), p.as_slice());
+
+        // Unused path elements must be zero
+        buff[4 + 4 * 4] = 1;
+        assert!(matches!(
+            KeyPath::decode_owned(&buff),
+            Err(ApduError::InvalidEncoding)
+        ));
+
+        // Path length must be within limits
+        buff[0] = KEY_PATH_MAX as u8 + 1;
+        assert!(matches!(
+            KeyPath::decode_owned(&buff),
+            Err(ApduError::InvalidLength)
+        ));
+    }
+
+    #[test]
+    fn key_path_permitted() {
+        let h = KEY_PATH_HARDENED;
+
+        let tests: &[(&[u32], bool)] = &[
+            (&[44 | h, 866 | h, h], true),
+            (&[44 | h, 866 | h, 7 | h, 1 | h, 2 | h], true),
+            // Must extend the application prefix
+            (&[44 | h, 866 | h], false),
+            (&[44 | h, 1 | h, h], false),
+            // Must be fully hardened
+            (&[44 | h, 866 | h, 7], false),
+        ];
+
+        for (p, permitted) in tests {
+            let p = KeyPath::new(p).unwrap();
+            assert_eq!(p.is_permitted(), *permitted, "{p:?}");
+        }
+
+        assert_eq!(
+            KeyPath::new(&[44 | h, 866 | h, 9 | h])
+                .unwrap()
+                .account_index(),
+            9
+        );
+
+        assert!(matches!(KeyPath::new(&[]), Err(ApduError::InvalidLength)));
+        assert!(matches!(
+            KeyPath::new(&[h; KEY_PATH_MAX + 1]),
+            Err(ApduError::InvalidLength)
+        ));
+    }
+}
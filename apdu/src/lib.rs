@@ -15,6 +15,12 @@
 //!
 //! See [Instruction] for APDU instruction codes.
 //!
+//! A CBOR encoding is also available with the `cbor` feature, see [cbor] and [encoding].
+//!
+//! Machine-readable layouts for each APDU are provided in [schema].
+//!
+//! Failure status words are grouped by class, see [status].
+//!
 
 #![no_std]
 
@@ -22,15 +28,37 @@ use core::fmt::Debug;
 
 pub use ledger_proto::{ApduError, ApduReq, ApduStatic};
 
+pub mod address;
+pub mod algorithm;
 pub mod app_info;
+pub mod attestation;
+pub mod audit;
+pub mod batch;
+pub mod capabilities;
+#[cfg(feature = "cbor")]
+pub mod cbor;
+pub mod chunk;
+pub mod ct;
+pub mod device_nonce;
+pub mod diagnostics;
 pub mod digest;
+pub mod encoding;
 pub mod ident;
+pub mod key_counters;
 pub mod key_image;
+pub mod key_path;
+pub mod memory;
 pub mod prelude;
+pub mod progress;
 pub mod random;
+pub mod schema;
+pub mod session;
+pub mod settings;
 pub mod state;
+pub mod status;
 pub mod subaddress_keys;
 pub mod tx;
+pub mod ui_echo;
 pub mod wallet_keys;
 
 mod helpers;
@@ -38,10 +66,17 @@ mod helpers;
 /// MobileCoin APDU Class
 pub const MOB_APDU_CLA: u8 = 0xab;
 
+/// MobileCoin APDU class for CBOR encoded payloads (see [cbor])
+pub const MOB_APDU_CLA_CBOR: u8 = 0xac;
+
+/// Maximum (current) supported protocol version
 pub const MOB_PROTO_VERSION: u8 = 0x01;
 
+/// Minimum supported protocol version
+pub const MOB_PROTO_VERSION_MIN: u8 = 0x01;
+
 /// MobileCoin APDU instruction codes
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, strum::EnumIter, strum::IntoStaticStr)]
 #[repr(u8)]
 pub enum Instruction {
     // General instructions
@@ -66,6 +101,36 @@ pub enum Instruction {
     /// Fetched signed identity following approval
     IdentGetReq = 0x15,
 
+    /// Fetch key export counters
+    GetKeyCounters = 0x16,
+
+    /// Fetch public keys for a range of subaddresses
+    GetSubaddressRange = 0x17,
+
+    /// Request on-device address verification
+    AddressVerify = 0x18,
+
+    /// Fetch signed address following approval
+    AddressGet = 0x19,
+
+    /// Fetch approval audit log entries
+    GetAuditLog = 0x1a,
+
+    /// Fetch engine diagnostic counters
+    GetDiagnostics = 0x1b,
+
+    /// Compute key image from a host-supplied onetime private key
+    GetKeyImageFromKey = 0x1c,
+
+    /// Invalidate account roots cached during the current session
+    InvalidateAccounts = 0x1d,
+
+    /// Fetch supported protocol versions and features
+    GetCapabilities = 0x1e,
+
+    /// Fetch b58 encoded public address for a subaddress
+    GetPublicAddress = 0x1f,
+
     /// Initialise a transaction
     TxInit = 0x20,
 
@@ -90,6 +155,15 @@ pub enum Instruction {
     /// Build Tx summary
     TxSummaryBuild = 0x34,
 
+    /// Fetch TxSummary report entries following build
+    TxSummaryReport = 0x35,
+
+    /// Add TxOut to summary (compact encoding)
+    TxSummaryAddTxOutCompact = 0x36,
+
+    /// Add TxOut unblinding to summary (compact encoding)
+    TxSummaryAddTxOutUnblindingCompact = 0x37,
+
     /// Start a ring signing operation
     TxRingInit = 0x40,
 
@@ -108,11 +182,79 @@ pub enum Instruction {
     /// Fetch a response for a given ring entry in the signed ring
     TxGetResponse = 0x45,
 
+    /// Fetch a range of responses from the signed ring
+    TxGetResponses = 0x46,
+
     /// Complete a transaction
     TxComplete = 0x50,
 
     /// Fetch transaction state
     TxGetInfo = 0x51,
+
+    /// Abort the in-progress transaction
+    TxAbort = 0x52,
+
+    /// Send a chunk of a request exceeding the maximum APDU payload
+    Chunk = 0x60,
+
+    /// Fetch a chunk of a response following a chunked request
+    ChunkGet = 0x61,
+
+    /// Send a batch of requests
+    Batch = 0x62,
+
+    /// Establish an encrypted session
+    SessionInit = 0x70,
+
+    /// Send an encrypted (session wrapped) request
+    SessionWrap = 0x71,
+
+    /// Fetch keys for a contiguous batch of subaddresses
+    GetSubaddressKeysBatch = 0x80,
+
+    /// Request a change of the active fog id
+    SetFogId = 0x81,
+
+    /// Fetch summary loading / ring signing progress
+    GetProgress = 0x82,
+
+    /// Fetch current device settings
+    GetSettings = 0x83,
+
+    /// Fetch wallet keys with a device attestation
+    GetWalletKeysAttested = 0x84,
+
+    /// Fetch subaddress keys with a device attestation
+    GetSubaddressKeysAttested = 0x85,
+
+    /// Pin the protocol version used for subsequent requests
+    SetProtocolVersion = 0x86,
+
+    /// Fetch wallet keys using an explicit derivation path
+    GetWalletKeysPath = 0x87,
+
+    /// Fetch subaddress keys using an explicit derivation path
+    GetSubaddressKeysPath = 0x88,
+
+    /// Fetch a fresh device nonce for session challenges
+    GetDeviceNonce = 0x89,
+
+    /// Sign a host challenge bound to the outstanding device nonce
+    SignSessionChallenge = 0x8a,
+
+    /// Resolve key images for a batch of TxOuts
+    GetKeyImageBatch = 0x8b,
+
+    /// Fetch subaddress keys with fog authority signature
+    GetSubaddressKeysFog = 0x8c,
+
+    // Test-only instructions (`speculos` firmware builds)
+    /// Fetch text currently rendered on screen
+    UiEcho = 0xf0,
+
+    // Debug-only instructions (debug and `pre-release` firmware builds)
+    /// Fetch heap, stack and function context memory usage
+    GetMemoryInfo = 0xf1,
 }
 
 /// Helper macro for encoding `bitflags` types
@@ -138,6 +280,10 @@ macro_rules! encdec_bitflags {
             type Error = ApduError;
 
             fn decode_owned(buff: &[u8]) -> Result<(Self, usize), Self::Error> {
+                if buff.is_empty() {
+                    return Err(ApduError::InvalidLength);
+                }
+
                 let v = <$b>::from_bits_truncate(buff[0]);
                 Ok((v, 1))
             }
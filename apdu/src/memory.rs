@@ -0,0 +1,112 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Debug-only memory instrumentation APDUs, reporting heap and stack use
+//! and the size of the active engine function context
+//!
+//! These are only handled by debug and `pre-release` firmware builds, so
+//! memory regressions can be caught on hardware. Other builds return
+//! `NotSupported`.
+
+use encdec::{Decode, Encode};
+
+use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
+
+/// Request memory usage information (0 length APDU)
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct MemoryInfoReq {}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(MemoryInfoReq {});
+
+impl ApduStatic for MemoryInfoReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetMemoryInfo as u8;
+}
+
+/// Memory usage response APDU
+///
+/// Heap values are zero for builds without an instrumented allocator.
+/// Stack use is measured via a canary fill of unused stack at app start,
+/// so reports the high-water mark since the app was started.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                           HEAP_USED                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                           HEAP_FREE                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          STACK_USED                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          STACK_FREE                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         FUNCTION_SIZE                         |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         FUNCTION_MAX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct MemoryInfoResp {
+    /// Heap bytes in use
+    pub heap_used: u32,
+    /// Heap bytes free
+    pub heap_free: u32,
+    /// Stack high-water mark, in bytes used since app start
+    pub stack_used: u32,
+    /// Stack bytes never used since app start
+    pub stack_free: u32,
+    /// Size of the active engine function context (0 where idle)
+    pub function_size: u32,
+    /// Size of the engine function container (largest function context)
+    pub function_max: u32,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(MemoryInfoResp {
+    heap_used,
+    heap_free,
+    stack_used,
+    stack_free,
+    function_size,
+    function_max
+});
+
+impl ApduStatic for MemoryInfoResp {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetMemoryInfo as u8;
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn memory_info_req_apdu() {
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &MemoryInfoReq {});
+        assert_eq!(n, 0);
+    }
+
+    #[test]
+    fn memory_info_resp_apdu() {
+        let apdu = MemoryInfoResp {
+            heap_used: random(),
+            heap_free: random(),
+            stack_used: random(),
+            stack_free: random(),
+            function_size: random(),
+            function_max: random(),
+        };
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 24);
+    }
+}
@@ -2,16 +2,53 @@
 //!
 
 pub use crate::{
-    app_info::{AppFlags, AppInfoReq, AppInfoResp},
+    address::{
+        AddressGetReq, AddressResp, AddressVerifyReq, PublicAddressReq, PublicAddressResp,
+        PUBLIC_ADDRESS_MAX,
+    },
+    algorithm::Algorithm,
+    app_info::{AppBuildInfo, AppFlags, AppInfoReq, AppInfoResp, BuildFeatures},
+    attestation::{KeyAttestation, KEY_ATTESTATION_CHALLENGE_LEN},
+    audit::{AuditEntry, AuditKind, AuditLogReq, AuditLogResp, AUDIT_ENTRIES_MAX},
+    batch::{BatchReq, BatchResp, BatchWriter, BATCH_MAX},
+    capabilities::{CapabilitiesReq, CapabilitiesResp, Features, SetProtocolVersionReq},
+    chunk::{ChunkFlags, ChunkGetReq, ChunkReq, ChunkResp, CHUNK_DATA_MAX},
+    device_nonce::{
+        DeviceNonceReq, DeviceNonceResp, SessionChallengeReq, DEVICE_NONCE_LEN,
+        SESSION_CHALLENGE_LEN,
+    },
+    diagnostics::{DiagnosticsReq, DiagnosticsResp},
     ident::{IdentGetReq, IdentResp, IdentSignReq},
-    key_image::{KeyImageReq, KeyImageResp},
+    key_counters::{KeyCountersReq, KeyCountersResp},
+    key_image::{
+        KeyImageBatchReq, KeyImageBatchResp, KeyImageFromKeyReq, KeyImageReq, KeyImageResp,
+        KEY_IMAGE_BATCH_MAX, KEY_IMAGE_BATCH_PAGE,
+    },
+    key_path::{KeyPath, KEY_PATH_HARDENED, KEY_PATH_MAX, KEY_PATH_PREFIX},
+    memory::{MemoryInfoReq, MemoryInfoResp},
+    progress::{ProgressReq, ProgressResp},
     random::{RandomReq, RandomResp},
-    subaddress_keys::{SubaddressKeyReq, SubaddressKeyResp},
+    session::{
+        requires_session, DeviceSession, HostHandshake, HostSession, SessionInitReq,
+        SessionInitResp, SessionReq, SessionResp,
+    },
+    settings::{SetFogIdReq, SettingsFlags, SettingsReq, SettingsResp},
+    status::{StatusClass, StatusWord, SW_OK},
+    subaddress_keys::{
+        SubaddressKeyAttestReq, SubaddressKeyFogReq, SubaddressKeyFogResp, SubaddressKeyPathReq,
+        SubaddressKeyReq, SubaddressKeyResp, SubaddressKeysBatchReq, SubaddressKeysBatchResp,
+        SubaddressRangeReq, SubaddressRangeResp, SUBADDRESS_KEYS_BATCH_MAX, SUBADDRESS_RANGE_MAX,
+    },
     tx::{
-        AddTxOutFlags, TxAddTxOut, TxComplete, TxGetKeyImage, TxGetResponse, TxInfo, TxInfoReq,
-        TxInit, TxKeyImage, TxMemoSign, TxPrivateKey, TxRingInit, TxRingSign, TxSetBlinding,
-        TxSetMessage, TxSummaryAddTxIn, TxSummaryAddTxOut, TxSummaryAddTxOutUnblinding,
-        TxSummaryBuild, TxSummaryInit,
+        AddTxOutFlags, TxAbort, TxAddTxOut, TxAuthenticated, TxComplete, TxCompleteResp,
+        TxExpected, TxGetKeyImage, TxGetResponse, TxGetResponses, TxInfo, TxInfoReq, TxInit,
+        TxKeyImage, TxMemoFlags, TxMemoSign, TxPrivateKey, TxRingInit, TxRingSign, TxSequenced,
+        TxSetBlinding, TxSetMessage, TxSummaryAddTxIn, TxSummaryAddTxOut, TxSummaryAddTxOutCompact,
+        TxSummaryAddTxOutUnblinding, TxSummaryAddTxOutUnblindingCompact, TxSummaryBuild,
+        TxSummaryInit, TxSummaryReportReq, TxSummaryReportResp,
+    },
+    ui_echo::{UiEchoReq, UiEchoResp},
+    wallet_keys::{
+        InvalidateAccountsReq, WalletKeyAttestReq, WalletKeyPathReq, WalletKeyReq, WalletKeyResp,
     },
-    wallet_keys::{WalletKeyReq, WalletKeyResp},
 };
@@ -0,0 +1,106 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Progress APDUs, for reporting summary loading and ring signing
+//! progress to host UIs
+
+use encdec::{Decode, Encode};
+
+use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
+use crate::{helpers::*, state::TxState};
+
+/// Request engine progress (0 length APDU)
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct ProgressReq {}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(ProgressReq {});
+
+impl ApduStatic for ProgressReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetProgress as u8;
+}
+
+/// Progress response APDU
+///
+/// Contains the current engine state with the percentage complete
+/// and step counts for the current operation. For summary loading
+/// steps count the loaded transaction entries, for ring signing
+/// steps count the rings signed.
+///
+/// `PERCENT`, `STEP` and `TOTAL` are zero where the engine is not
+/// loading a summary or signing rings.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   TX_STATE    |    PERCENT    |             STEP              |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |             TOTAL             |           RESERVED            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct ProgressResp {
+    /// Current transaction engine state
+    pub state: TxState,
+    /// Percentage complete for the current operation (0..=100)
+    pub percent: u8,
+    /// Current step in the operation
+    pub step: u16,
+    /// Total steps in the operation
+    pub total: u16,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 2],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    ProgressResp {
+        state,
+        percent,
+        step,
+        total,
+    } reserved { _reserved }
+);
+
+impl ProgressResp {
+    /// Create a new [ProgressResp] APDU
+    pub fn new(state: TxState, percent: u8, step: u16, total: u16) -> Self {
+        Self {
+            state,
+            percent,
+            step,
+            total,
+            _reserved: [0u8; 2],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn progress_req_apdu() {
+        let apdu = ProgressReq::default();
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn progress_resp_apdu() {
+        let apdu = ProgressResp::new(TxState::RingSign, 42, random(), random());
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 8);
+    }
+}
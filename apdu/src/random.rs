@@ -1,23 +1,187 @@
 // Copyright (c) 2022-2023 The MobileCoin Foundation
 
-use encdec::{Decode, Encode};
+//! Random value APDUs, for sourcing entropy from the device
+
+use encdec::{Decode, DecodeOwned, Encode};
+use heapless::Vec;
 
 use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
 use crate::helpers::arr;
 
+/// Default random value length, used where [RandomReq::len] is zero
+pub const RANDOM_LEN_DEFAULT: usize = 32;
+
+/// Maximum random value length per request, bounded by the APDU payload limit
+pub const RANDOM_LEN_MAX: usize = 240;
+
+/// Request a random value from the device, returns a [RandomResp]
+///
+/// `LEN` must not exceed [RANDOM_LEN_MAX], a `LEN` of zero requests
+/// [RANDOM_LEN_DEFAULT] bytes.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |      LEN      |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
 #[derive(Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
-pub struct RandomReq {}
+pub struct RandomReq {
+    /// Requested random value length in bytes
+    pub len: u8,
+
+    /// Reserved for future use (maintains field alignment)
+    #[encdec(with = "arr")]
+    reserved: [u8; 3],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(RandomReq { len } reserved { reserved });
 
 impl ApduStatic for RandomReq {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::GetRandom as u8;
 }
 
-#[derive(Clone, PartialEq, Debug, Encode, Decode)]
-#[encdec(error = "ApduError")]
+impl RandomReq {
+    /// Create a new [RandomReq] for `len` bytes
+    pub fn new(len: u8) -> Self {
+        Self {
+            len,
+            reserved: [0u8; 3],
+        }
+    }
+
+    /// Fetch the requested length, resolving the default where zero
+    pub fn value_len(&self) -> usize {
+        match self.len {
+            0 => RANDOM_LEN_DEFAULT,
+            n => n as usize,
+        }
+    }
+}
+
+impl Default for RandomReq {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Random value response
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |      LEN      |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /                             VALUE                             /
+/// /                          (LEN bytes)                          /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
 pub struct RandomResp {
     /// Random value
-    #[encdec(with = "arr")]
-    pub value: [u8; 32],
+    pub value: Vec<u8, RANDOM_LEN_MAX>,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(RandomResp { value });
+
+impl RandomResp {
+    /// Create a new [RandomResp] APDU
+    pub fn new(value: Vec<u8, RANDOM_LEN_MAX>) -> Self {
+        Self { value }
+    }
+}
+
+impl Encode for RandomResp {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(4 + self.value.len())
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        let n = self.value.len();
+
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < 4 + n {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write header
+        buff[0] = n as u8;
+        buff[1..4].fill(0);
+
+        // Write value
+        buff[4..][..n].copy_from_slice(&self.value);
+
+        Ok(4 + n)
+    }
+}
+
+impl DecodeOwned for RandomResp {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        // Check header length (MOB-06.7)
+        if buff.len() < 4 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let n = buff[0] as usize;
+
+        // Check value and full buffer length (MOB-06.7)
+        if n > RANDOM_LEN_MAX || buff.len() < 4 + n {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read value (capacity checked above)
+        let value = Vec::from_slice(&buff[4..][..n]).map_err(|_| ApduError::InvalidLength)?;
+
+        Ok((Self { value }, 4 + n))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn encode_decode_random_req() {
+        let mut buff = [0u8; 256];
+
+        let n = encode_decode_apdu(&mut buff, &RandomReq::new(random()));
+        assert_eq!(n, 4);
+    }
+
+    #[test]
+    fn encode_decode_random_resp() {
+        let mut buff = [0u8; 256];
+
+        for len in [0, RANDOM_LEN_DEFAULT, RANDOM_LEN_MAX] {
+            let mut value = Vec::new();
+            for _i in 0..len {
+                let _ = value.push(random());
+            }
+
+            let n = encode_decode_apdu(&mut buff, &RandomResp::new(value));
+            assert_eq!(n, 4 + len);
+        }
+    }
+
+    #[test]
+    fn random_req_len() {
+        assert_eq!(RandomReq::default().value_len(), RANDOM_LEN_DEFAULT);
+        assert_eq!(RandomReq::new(64).value_len(), 64);
+    }
 }
@@ -0,0 +1,799 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Machine-readable APDU layout descriptions
+//!
+//! [SCHEMAS] describes the packed encoding of each request and response
+//! APDU, including instruction codes, field names, offsets and lengths, to
+//! allow clients in other languages to generate encoders rather than
+//! hand-porting the layouts in this crate.
+//!
+//! Layouts are checked against the encoded lengths of the Rust types in
+//! tests, and may be exported as JSON via the `ledger-mob-apdu-schema`
+//! binary (requires the `schema` feature).
+
+use crate::Instruction;
+
+/// Field description for a packed APDU encoding
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Field {
+    /// Field name
+    pub name: &'static str,
+    /// Field offset in bytes, `None` where following a variable-length field
+    pub offset: Option<usize>,
+    /// Field length in bytes, `None` for variable-length fields
+    pub len: Option<usize>,
+}
+
+/// Layout description for a request or response APDU
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ApduSchema {
+    /// APDU type name
+    pub name: &'static str,
+    /// Instruction code for requests, `None` for responses
+    pub ins: Option<u8>,
+    /// Fields in encoding order
+    pub fields: &'static [Field],
+}
+
+impl ApduSchema {
+    /// Fetch the encoded length for fixed-length APDUs,
+    /// `None` where the APDU contains variable-length fields
+    pub const fn len(&self) -> Option<usize> {
+        let mut len = 0;
+        let mut i = 0;
+
+        while i < self.fields.len() {
+            match self.fields[i].len {
+                Some(n) => len += n,
+                None => return None,
+            }
+            i += 1;
+        }
+
+        Some(len)
+    }
+
+    /// Fetch the length of the fixed-length header preceding
+    /// any variable-length fields
+    pub const fn header_len(&self) -> usize {
+        let mut len = 0;
+        let mut i = 0;
+
+        while i < self.fields.len() {
+            match self.fields[i].len {
+                Some(n) => len += n,
+                None => break,
+            }
+            i += 1;
+        }
+
+        len
+    }
+
+    /// Check whether the APDU has no fields
+    pub const fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+/// Compute field offsets from a list of field names and lengths
+const fn layout<const N: usize>(fields: [(&'static str, Option<usize>); N]) -> [Field; N] {
+    let mut out = [Field {
+        name: "",
+        offset: None,
+        len: None,
+    }; N];
+    let mut offset = Some(0);
+    let mut i = 0;
+
+    while i < N {
+        let (name, len) = fields[i];
+        out[i] = Field { name, offset, len };
+
+        offset = match (offset, len) {
+            (Some(o), Some(n)) => Some(o + n),
+            _ => None,
+        };
+        i += 1;
+    }
+
+    out
+}
+
+/// Helper macro for field lengths, `..` indicates a variable-length field
+macro_rules! field_len {
+    (..) => {
+        None
+    };
+    ($n:expr) => {
+        Some($n)
+    };
+}
+
+/// Helper macro for building [ApduSchema] objects, requests
+/// are annotated with their [Instruction]
+macro_rules! schema {
+    ($t:ident => $ins:ident { $($f:ident: $n:tt),* $(,)? }) => {
+        ApduSchema {
+            name: stringify!($t),
+            ins: Some(Instruction::$ins as u8),
+            fields: &layout([$((stringify!($f), field_len!($n))),*]),
+        }
+    };
+    ($t:ident { $($f:ident: $n:tt),* $(,)? }) => {
+        ApduSchema {
+            name: stringify!($t),
+            ins: None,
+            fields: &layout([$((stringify!($f), field_len!($n))),*]),
+        }
+    };
+}
+
+/// Layouts for all request and response APDUs
+pub const SCHEMAS: &[ApduSchema] = &[
+    // General requests
+    schema!(AppInfoReq => GetAppInfo {}),
+    schema!(CapabilitiesReq => GetCapabilities {}),
+    schema!(SetProtocolVersionReq => SetProtocolVersion { version: 1, reserved: 3 }),
+    schema!(WalletKeyReq => GetWalletKeys { account_index: 4, algorithm: 4 }),
+    schema!(InvalidateAccountsReq => InvalidateAccounts {}),
+    schema!(SubaddressKeyReq => GetSubaddressKeys {
+        account_index: 4,
+        subaddress_index: 8,
+        algorithm: 4,
+    }),
+    schema!(WalletKeyAttestReq => GetWalletKeysAttested {
+        account_index: 4,
+        algorithm: 4,
+        challenge: 32,
+    }),
+    schema!(SubaddressKeyAttestReq => GetSubaddressKeysAttested {
+        account_index: 4,
+        subaddress_index: 8,
+        algorithm: 4,
+        challenge: 32,
+    }),
+    schema!(WalletKeyPathReq => GetWalletKeysPath { path: 24, algorithm: 4 }),
+    schema!(SubaddressKeyPathReq => GetSubaddressKeysPath {
+        path: 24,
+        subaddress_index: 8,
+        algorithm: 4,
+    }),
+    schema!(SubaddressKeyFogReq => GetSubaddressKeysFog {
+        account_index: 4,
+        subaddress_index: 8,
+        algorithm: 4,
+        fog_id: 1,
+        reserved: 3,
+    }),
+    schema!(SubaddressRangeReq => GetSubaddressRange {
+        account_index: 4,
+        start: 8,
+        count: 1,
+        reserved: 3,
+    }),
+    schema!(SubaddressKeysBatchReq => GetSubaddressKeysBatch {
+        account_index: 4,
+        start: 8,
+        count: 1,
+        reserved: 3,
+    }),
+    schema!(KeyImageReq => GetKeyImage {
+        account_index: 4,
+        subaddress_index: 8,
+        txout_public_key: 32,
+        algorithm: 4,
+    }),
+    schema!(KeyImageFromKeyReq => GetKeyImageFromKey {
+        account_index: 4,
+        subaddress_index: 8,
+        onetime_private_key: 32,
+    }),
+    schema!(KeyImageBatchReq => GetKeyImageBatch {
+        account_index: 4,
+        count: 1,
+        offset: 1,
+        reserved: 2,
+        entries: ..,
+    }),
+    schema!(RandomReq => GetRandom { len: 1, reserved: 3 }),
+    schema!(KeyCountersReq => GetKeyCounters {}),
+    schema!(DeviceNonceReq => GetDeviceNonce {}),
+    schema!(SessionChallengeReq => SignSessionChallenge { challenge: 32 }),
+    schema!(IdentSignReq => IdentSignReq {
+        identity_index: 4,
+        identity_uri_len: 1,
+        challenge_len: 1,
+        algorithm: 1,
+        derivation: 1,
+        identity_uri: ..,
+        challenge: ..,
+    }),
+    schema!(IdentGetReq => IdentGetReq {}),
+    schema!(AddressVerifyReq => AddressVerify {
+        account_index: 4,
+        subaddress_index: 8,
+        fog_id: 1,
+        reserved: 3,
+        challenge: 32,
+    }),
+    schema!(AddressGetReq => AddressGet {}),
+    schema!(PublicAddressReq => GetPublicAddress {
+        account_index: 4,
+        subaddress_index: 8,
+        fog_id: 1,
+        reserved: 3,
+    }),
+    schema!(AuditLogReq => GetAuditLog { start: 1, reserved: 3 }),
+    schema!(DiagnosticsReq => GetDiagnostics {}),
+    schema!(SetFogIdReq => SetFogId { fog_id: 1, reserved: 3 }),
+    schema!(ProgressReq => GetProgress {}),
+    schema!(SettingsReq => GetSettings {}),
+    // Transaction requests
+    schema!(TxInit => TxInit {
+        num_rings: 1,
+        seq: 1,
+        reserved: 2,
+        account_index: 4,
+        expected: ..,
+    }),
+    schema!(TxMemoSign => TxMemoSign {
+        kind: 2,
+        payload_len: 1,
+        seq: 1,
+        subaddress_index: 8,
+        tx_public_key: 32,
+        target_view_public: 32,
+        payload: 48,
+        flags: ..,
+    }),
+    schema!(TxSetMessage => TxSetMessage {
+        message_len: 1,
+        seq: 1,
+        reserved: 2,
+        message: ..,
+    }),
+    schema!(TxSummaryInit => TxSummaryInit {
+        seq: 1,
+        reserved: 3,
+        message: 32,
+        block_version: 4,
+        num_inputs: 4,
+        num_outputs: 4,
+    }),
+    schema!(TxSummaryAddTxOut => TxSummaryAddTxOut {
+        flags: 1,
+        index: 1,
+        seq: 1,
+        reserved: 1,
+        masked_value: 8,
+        masked_token_id: 8,
+        commitment: 32,
+        target_key: 32,
+        public_key: 32,
+        mac: 8,
+    }),
+    schema!(TxSummaryAddTxOutUnblinding => TxSummaryAddTxOutUnblinding {
+        flags: 1,
+        index: 1,
+        fog_id: 1,
+        seq: 1,
+        unmasked_value: 8,
+        token_id: 8,
+        blinding: 32,
+        address_spend_public: 32,
+        address_view_public: 32,
+        tx_private_key: 32,
+        fog_authority_sig: 64,
+        mac: 8,
+        fog_url: ..,
+    }),
+    schema!(TxSummaryAddTxOutCompact => TxSummaryAddTxOutCompact {
+        flags: 1,
+        index: 1,
+        seq: 1,
+        reserved: 1,
+        masked_amount: ..,
+        target_key: 32,
+        public_key: 32,
+        mac: 8,
+    }),
+    schema!(TxSummaryAddTxOutUnblindingCompact => TxSummaryAddTxOutUnblindingCompact {
+        flags: 1,
+        index: 1,
+        fog_id: 1,
+        seq: 1,
+        unmasked_value: ..,
+        token_id: ..,
+        blinding: 32,
+        address: ..,
+        tx_private_key: ..,
+        fog_authority_sig: ..,
+        mac: 8,
+        fog_url: ..,
+    }),
+    schema!(TxSummaryAddTxIn => TxSummaryAddTxIn {
+        flags: 1,
+        index: 1,
+        seq: 1,
+        reserved: 1,
+        pseudo_output_commitment: 32,
+        unmasked_value: 8,
+        token_id: 8,
+        blinding: 32,
+        input_rules_digest: 32,
+        mac: 8,
+    }),
+    schema!(TxSummaryBuild => TxSummaryBuild {
+        seq: 1,
+        reserved: 3,
+        fee_value: 8,
+        fee_token_id: 8,
+        tombstone_block: 8,
+        current_block: ..,
+    }),
+    schema!(TxSummaryReportReq => TxSummaryReport { start: 1, reserved: 3 }),
+    schema!(TxRingInit => TxRingInit {
+        ring_size: 1,
+        real_index: 1,
+        flags: 1,
+        seq: 1,
+        subaddress_index: 8,
+        value: 8,
+        token_id: 8,
+        onetime_private_key: 32,
+    }),
+    schema!(TxSetBlinding => TxSetBlinding {
+        seq: 1,
+        reserved: 3,
+        blinding: 32,
+        output_blinding: 32,
+        mac: 8,
+    }),
+    schema!(TxAddTxOut => TxAddTxOut {
+        ring_index: 1,
+        seq: 1,
+        reserved: 2,
+        public_key: 32,
+        target_key: 32,
+        commitment: 32,
+        mac: 8,
+    }),
+    schema!(TxRingSign => TxSign { seq: 1, reserved: 3 }),
+    schema!(TxGetKeyImage => TxGetKeyImage {}),
+    schema!(TxGetResponse => TxGetResponse {
+        ring_index: 1,
+        flags: 1,
+        reserved: 2,
+    }),
+    schema!(TxGetResponses => TxGetResponses {
+        start: 1,
+        count: 1,
+        flags: 1,
+        reserved: 1,
+    }),
+    schema!(TxComplete => TxComplete {}),
+    schema!(TxInfoReq => TxGetInfo {}),
+    schema!(TxAbort => TxAbort {}),
+    // Transport requests
+    schema!(ChunkReq => Chunk {
+        ins: 1,
+        flags: 1,
+        data_len: 1,
+        reserved: 1,
+        offset: 2,
+        total_len: 2,
+        data: ..,
+    }),
+    schema!(ChunkGetReq => ChunkGet { offset: 2, reserved: 2 }),
+    schema!(BatchReq => Batch { count: 1, reserved: 3, entries: .. }),
+    schema!(SessionInitReq => SessionInit { public: 32 }),
+    schema!(SessionReq => SessionWrap {
+        ins: 1,
+        reserved: 1,
+        data_len: 2,
+        counter: 4,
+        data: ..,
+    }),
+    // Test-only requests
+    schema!(UiEchoReq => UiEcho {}),
+    schema!(MemoryInfoReq => GetMemoryInfo {}),
+    // General responses
+    schema!(AppInfoResp {
+        proto: 1,
+        name_len: 1,
+        version_len: 1,
+        flags_len: 1,
+        name: ..,
+        version: ..,
+        flags: ..,
+        build: ..,
+    }),
+    schema!(CapabilitiesResp {
+        proto_min: 1,
+        proto_max: 1,
+        reserved: 2,
+        features: 4,
+    }),
+    schema!(WalletKeyResp {
+        account_index: 4,
+        view_private: 32,
+        spend_public: 32,
+        attestation: ..,
+    }),
+    schema!(SubaddressKeyResp {
+        account_index: 4,
+        subaddress_index: 8,
+        view_private: 32,
+        spend_public: 32,
+        attestation: ..,
+    }),
+    schema!(SubaddressKeyFogResp {
+        account_index: 4,
+        subaddress_index: 8,
+        view_private: 32,
+        spend_public: 32,
+        fog_id: 1,
+        reserved: 3,
+        fog_sig: 64,
+    }),
+    schema!(SubaddressRangeResp {
+        account_index: 4,
+        start: 8,
+        count: 1,
+        reserved: 3,
+        keys: ..,
+    }),
+    schema!(SubaddressKeysBatchResp {
+        account_index: 4,
+        start: 8,
+        count: 1,
+        reserved: 3,
+        keys: ..,
+    }),
+    schema!(KeyImageResp {
+        account_index: 4,
+        subaddress_index: 8,
+        key_image: 32,
+        spend_public: 32,
+    }),
+    schema!(KeyImageBatchResp {
+        account_index: 4,
+        count: 1,
+        offset: 1,
+        total: 1,
+        reserved: 1,
+        entries: ..,
+    }),
+    schema!(DeviceNonceResp { nonce: 32 }),
+    schema!(RandomResp {
+        len: 1,
+        reserved: 3,
+        value: ..
+    }),
+    schema!(KeyCountersResp {
+        wallet_keys: 4,
+        subaddress_keys: 4,
+        limit: 4,
+    }),
+    schema!(IdentResp {
+        public_key: 32,
+        signature: 64
+    }),
+    schema!(AddressResp {
+        view_public: 32,
+        spend_public: 32,
+        fog_id: 1,
+        reserved: 3,
+        fog_sig: 64,
+        signature: 64,
+    }),
+    schema!(PublicAddressResp {
+        fog_id: 1,
+        reserved: 1,
+        address_len: 2,
+        fog_sig: 64,
+        address: ..,
+    }),
+    schema!(AuditLogResp {
+        start: 1,
+        num_held: 1,
+        num_entries: 1,
+        reserved: 1,
+        entries: ..,
+    }),
+    schema!(DiagnosticsResp {
+        events: 4,
+        errors: 4,
+        resets: 4,
+        rings_signed: 4,
+    }),
+    schema!(ProgressResp {
+        state: 1,
+        percent: 1,
+        step: 2,
+        total: 2,
+        reserved: 2,
+    }),
+    schema!(SettingsResp {
+        fog_id: 1,
+        flags: 1,
+        reserved: 2,
+        lock_timeout_s: 4,
+        account_index: 4,
+    }),
+    // Transaction responses
+    schema!(TxInfo {
+        state: 1,
+        seq: 1,
+        value: 2,
+        digest: 32,
+        timeout_s: 4,
+    }),
+    schema!(TxMemoSig {
+        state: 1,
+        value: 2,
+        digest: 32,
+        hmac: 16,
+    }),
+    schema!(TxSummaryReportResp {
+        start: 1,
+        num_held: 1,
+        num_entries: 1,
+        reserved: 1,
+        entries: ..,
+    }),
+    schema!(TxKeyImage {
+        key_image: 32,
+        c_zero: 32
+    }),
+    schema!(TxResponse {
+        ring_index: 1,
+        reserved: 3,
+        scalar: 32,
+    }),
+    schema!(TxResponses {
+        start: 1,
+        count: 1,
+        reserved: 2,
+        scalars: ..,
+    }),
+    schema!(TxCompleteResp {
+        state: 1,
+        seq: 1,
+        value: 2,
+        digest: 32,
+        signed_digest: 32,
+    }),
+    // Transport responses
+    schema!(ChunkResp {
+        offset: 2,
+        total_len: 2,
+        data_len: 1,
+        reserved: 3,
+        data: ..,
+    }),
+    schema!(BatchResp {
+        count: 1,
+        reserved: 3,
+        entries: ..
+    }),
+    schema!(SessionInitResp { public: 32 }),
+    schema!(SessionResp {
+        counter: 4,
+        data_len: 2,
+        reserved: 2,
+        data: ..,
+    }),
+    // Test-only responses
+    schema!(UiEchoResp {
+        num_lines: 1,
+        reserved: 3,
+        lines: ..,
+    }),
+    schema!(MemoryInfoResp {
+        heap_used: 4,
+        heap_free: 4,
+        stack_used: 4,
+        stack_free: 4,
+        function_size: 4,
+        function_max: 4,
+    }),
+];
+
+/// Fetch the [ApduSchema] for a given APDU type name
+pub fn schema(name: &str) -> Option<&'static ApduSchema> {
+    SCHEMAS.iter().find(|s| s.name == name)
+}
+
+#[cfg(test)]
+mod test {
+    use encdec::Encode;
+    use heapless::Vec;
+    use mc_crypto_keys::{RistrettoPrivate, RistrettoPublic};
+    use mc_crypto_ring_signature::{KeyImage, Scalar};
+    use mc_util_from_random::FromRandom;
+    use rand_core::OsRng;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::{
+        prelude::*,
+        state::{Digest, TxState},
+        tx::{FogId, TxMemoSig, TxResponse, TxResponses},
+    };
+
+    /// Check schema lengths match the encoded length of the provided object,
+    /// excluding any variable-length fields
+    fn check<T: Encode>(name: &str, v: &T)
+    where
+        <T as Encode>::Error: core::fmt::Debug,
+    {
+        let s = schema(name).unwrap_or_else(|| panic!("missing schema for {name}"));
+        let n = v.encode_len().unwrap();
+
+        assert_eq!(s.len().unwrap_or(s.header_len()), n, "{name}");
+    }
+
+    #[test]
+    fn schema_lengths() {
+        let private_key = RistrettoPrivate::from_random(&mut OsRng {});
+        let public_key = RistrettoPublic::from(&private_key);
+        let key_image = KeyImage::from(&private_key);
+
+        check("AppInfoReq", &AppInfoReq {});
+        check("CapabilitiesReq", &CapabilitiesReq {});
+        check("SetProtocolVersionReq", &SetProtocolVersionReq::new(1));
+        check("WalletKeyReq", &WalletKeyReq::new(1));
+        check("SubaddressKeyReq", &SubaddressKeyReq::new(1, 2));
+        check("WalletKeyAttestReq", &WalletKeyAttestReq::new(1, [0u8; 32]));
+        check(
+            "SubaddressKeyAttestReq",
+            &SubaddressKeyAttestReq::new(1, 2, [0u8; 32]),
+        );
+        let path = KeyPath::new(&[KEY_PATH_PREFIX[0], KEY_PATH_PREFIX[1], 1]).unwrap();
+        check("WalletKeyPathReq", &WalletKeyPathReq::new(path));
+        check("SubaddressKeyPathReq", &SubaddressKeyPathReq::new(path, 2));
+        check(
+            "SubaddressKeyFogReq",
+            &SubaddressKeyFogReq::new(1, 2, FogId::MobMain),
+        );
+        check("SubaddressRangeReq", &SubaddressRangeReq::new(1, 2, 3));
+        check(
+            "SubaddressKeysBatchReq",
+            &SubaddressKeysBatchReq::new(1, 2, 3),
+        );
+        check("KeyImageReq", &KeyImageReq::new(1, 2, public_key.into()));
+        check(
+            "KeyImageFromKeyReq",
+            &KeyImageFromKeyReq::new(1, 2, private_key.clone().into()),
+        );
+        check("KeyImageBatchReq", &KeyImageBatchReq::new(1, 0, Vec::new()));
+        check("RandomReq", &RandomReq::new(32));
+        check("DeviceNonceReq", &DeviceNonceReq {});
+        check("SessionChallengeReq", &SessionChallengeReq::new([0u8; 32]));
+        check("IdentSignReq", &IdentSignReq::new(1, "", &[]));
+        check("IdentGetReq", &IdentGetReq);
+        check("AddressGetReq", &AddressGetReq);
+        check(
+            "PublicAddressReq",
+            &PublicAddressReq::new(1, 2, FogId::MobMain),
+        );
+        check("AuditLogReq", &AuditLogReq::new(0));
+        check("SetFogIdReq", &SetFogIdReq::new(FogId::MobMain));
+
+        check("TxInit", &TxInit::new(1, 2));
+        check("TxSetMessage", &TxSetMessage::new(&[]));
+        check("TxSummaryInit", &TxSummaryInit::new([0u8; 32], 1, 2, 3));
+        check("TxSummaryReportReq", &TxSummaryReportReq::new(0));
+        check(
+            "TxSetBlinding",
+            &TxSetBlinding::new(Scalar::from(1u64), Scalar::from(2u64)),
+        );
+        check("TxRingSign", &TxRingSign::default());
+        check("TxGetKeyImage", &TxGetKeyImage {});
+        check("TxGetResponse", &TxGetResponse::new(1));
+        check("TxGetResponses", &TxGetResponses::new(1, 2));
+        check("TxComplete", &TxComplete);
+        check("TxInfoReq", &TxInfoReq);
+        check("TxAbort", &TxAbort);
+
+        check("ChunkGetReq", &ChunkGetReq::new(0));
+        check(
+            "ChunkReq",
+            &ChunkReq::new(1, ChunkFlags::empty(), 0, 0, &[]),
+        );
+
+        check(
+            "CapabilitiesResp",
+            &CapabilitiesResp::new(1, 1, Features::empty()),
+        );
+        check(
+            "KeyImageResp",
+            &KeyImageResp::new(1, 2, key_image, public_key.into()),
+        );
+        check(
+            "SubaddressKeyFogResp",
+            &SubaddressKeyFogResp::new(
+                1,
+                2,
+                private_key.clone().into(),
+                public_key.into(),
+                FogId::MobMain,
+                [0u8; 64],
+            ),
+        );
+        check(
+            "KeyImageBatchResp",
+            &KeyImageBatchResp::new(1, 0, 0, Vec::new()),
+        );
+        check("RandomResp", &RandomResp::new(Vec::new()));
+        check("DeviceNonceResp", &DeviceNonceResp::new([0u8; 32]));
+        check("KeyCountersResp", &KeyCountersResp::new(1, 2, 3));
+        check("IdentResp", &IdentResp::new([0u8; 32], [0u8; 64]));
+        check(
+            "PublicAddressResp",
+            &PublicAddressResp::new(FogId::MobMain, [0u8; 64], ""),
+        );
+        check("AuditLogResp", &AuditLogResp::new(0, 0, &[]));
+        check("DiagnosticsResp", &DiagnosticsResp::new(1, 2, 3, 4));
+        check("ProgressResp", &ProgressResp::new(TxState::Init, 0, 1, 2));
+        check(
+            "TxMemoSig",
+            &TxMemoSig::new(TxState::Init, 1, Digest::new(), [0u8; 16]),
+        );
+        check("TxSummaryReportResp", &TxSummaryReportResp::new(0, 0, &[]));
+        check(
+            "TxKeyImage",
+            &TxKeyImage {
+                key_image,
+                c_zero: Scalar::from(1u64),
+            },
+        );
+        check("TxResponse", &TxResponse::new(1, Scalar::from(1u64)));
+        check("TxResponses", &TxResponses::new(1, Vec::new()));
+        check("ChunkResp", &ChunkResp::new(0, 0, &[]));
+        check("UiEchoReq", &UiEchoReq {});
+        check("UiEchoResp", &UiEchoResp::new());
+        check("MemoryInfoReq", &MemoryInfoReq {});
+        check("MemoryInfoResp", &MemoryInfoResp::default());
+    }
+
+    #[test]
+    fn schema_offsets() {
+        let s = schema("KeyImageReq").unwrap();
+
+        let offsets = [Some(0), Some(4), Some(12), Some(44)];
+        for (f, o) in s.fields.iter().zip(offsets) {
+            assert_eq!(f.offset, o, "{}", f.name);
+        }
+        assert_eq!(s.len(), Some(48));
+
+        // Offsets following variable-length fields are unknown
+        let s = schema("IdentSignReq").unwrap();
+        assert_eq!(s.header_len(), 8);
+        assert_eq!(s.fields[5].offset, Some(8));
+        assert_eq!(s.fields[6].offset, None);
+        assert_eq!(s.len(), None);
+    }
+
+    #[test]
+    fn schema_instructions() {
+        // Request instruction codes are unique
+        for (i, a) in SCHEMAS.iter().enumerate() {
+            let Some(ins) = a.ins else { continue };
+
+            for b in &SCHEMAS[i + 1..] {
+                assert_ne!(Some(ins), b.ins, "{} / {}", a.name, b.name);
+            }
+        }
+
+        // Every instruction has a request schema
+        for i in Instruction::iter() {
+            assert!(
+                SCHEMAS.iter().any(|s| s.ins == Some(i as u8)),
+                "missing schema for {i:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,739 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Secure channel APDUs, protecting sensitive request and response
+//! payloads (view keys, blindings) from transport sniffers.
+//!
+//! A session is established via an ephemeral X25519 handshake
+//! ([SessionInitReq] / [SessionInitResp]), with directional AES-256-GCM keys
+//! derived from the shared secret and handshake public keys using HKDF-SHA256.
+//!
+//! Requests are then wrapped in [SessionReq] APDUs carrying the inner
+//! instruction and a strictly increasing counter (used as the nonce and
+//! bound to the ciphertext with the instruction), with responses returned
+//! as [SessionResp] APDUs under the same counter.
+//!
+//! Once a session is open, devices require instructions carrying sensitive
+//! material (see [requires_session]) to be wrapped. Wrapped requests exceeding
+//! the maximum APDU payload may be sent via the [chunked exchange][crate::chunk].
+//!
+//! ## Limitations
+//!
+//! The handshake is unauthenticated: neither ephemeral key is signed, so
+//! sessions protect against passive observers of the transport only. An
+//! active attacker able to intercept and modify APDUs may complete separate
+//! handshakes with the host and device and relay requests between them.
+//! Requiring sessions does not authenticate the device to the host (or the
+//! host to the device), and should not be relied upon in place of approval
+//! on the device.
+
+use encdec::{Decode, Encode};
+use hkdf::Hkdf;
+use mc_crypto_keys::{KexEphemeralPrivate, X25519EphemeralPrivate, X25519Public};
+use mc_oblivious_aes_gcm::{
+    aead::{AeadInPlace, KeyInit},
+    Aes256Gcm, Nonce, Tag,
+};
+use mc_util_from_random::FromRandom;
+use rand_core::{CryptoRng, RngCore};
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
+use crate::{ct::ct_eq, helpers::arr};
+
+/// AEAD tag length, appended to encrypted payloads
+pub const SESSION_TAG_LEN: usize = 16;
+
+/// Maximum encrypted payload length (including tag)
+pub const SESSION_BUFF_LEN: usize = 512;
+
+/// Length of wrapped request / response headers
+const SESSION_HEADER_LEN: usize = 8;
+
+/// HKDF info for session key derivation
+const SESSION_KDF_INFO: &[u8] = b"ledger-mob session v1";
+
+/// Check whether an instruction carries sensitive material and must be
+/// wrapped in a [SessionReq] where a session is open (or required).
+pub fn requires_session(ins: u8) -> bool {
+    const SENSITIVE: &[Instruction] = &[
+        // Responses contain view private keys
+        Instruction::GetWalletKeys,
+        Instruction::GetSubaddressKeys,
+        Instruction::GetSubaddressRange,
+        Instruction::GetSubaddressKeysBatch,
+        Instruction::GetWalletKeysPath,
+        Instruction::GetSubaddressKeysPath,
+        Instruction::GetSubaddressKeysFog,
+        // Requests contain onetime private keys
+        Instruction::GetKeyImageFromKey,
+        // Requests contain blindings
+        Instruction::TxSetBlinding,
+        Instruction::TxSummaryAddTxOutUnblinding,
+        Instruction::TxSummaryAddTxOutUnblindingCompact,
+    ];
+
+    SENSITIVE.iter().any(|i| *i as u8 == ins)
+}
+
+/// Session handshake request APDU, containing the host ephemeral
+/// X25519 public key
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                       HOST_PUBLIC_KEY                         /
+/// /                   (32-byte X25519 Public Key)                 /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SessionInitReq {
+    /// Host ephemeral public key
+    #[encdec(with = "arr")]
+    pub public: [u8; 32],
+}
+
+impl ApduStatic for SessionInitReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::SessionInit as u8;
+}
+
+/// Session handshake response APDU, containing the device ephemeral
+/// X25519 public key
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                      DEVICE_PUBLIC_KEY                        /
+/// /                   (32-byte X25519 Public Key)                 /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SessionInitResp {
+    /// Device ephemeral public key
+    #[encdec(with = "arr")]
+    pub public: [u8; 32],
+}
+
+/// Wrapped (encrypted) request APDU, carrying an encoded request for
+/// the instruction `INS`
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |      INS      |    RESERVED   |            DATA_LEN           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                            COUNTER                            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                      DATA (CIPHERTEXT | TAG)                  /
+/// /                       (variable length)                       /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct SessionReq<'a> {
+    /// Instruction for the wrapped request
+    pub ins: u8,
+    /// Request counter
+    pub counter: u32,
+    /// Encrypted request and tag
+    pub data: &'a [u8],
+}
+
+impl<'a> ApduStatic for SessionReq<'a> {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::SessionWrap as u8;
+}
+
+impl<'a> Encode for SessionReq<'a> {
+    type Error = ApduError;
+
+    /// Encode a [`SessionReq`] APDU into the provided buffer
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        let n = self.data.len();
+
+        if n > SESSION_BUFF_LEN || buff.len() < SESSION_HEADER_LEN + n {
+            return Err(ApduError::InvalidLength);
+        }
+
+        buff[0] = self.ins;
+        buff[1] = 0;
+        (n as u16).encode(&mut buff[2..])?;
+        self.counter.encode(&mut buff[4..])?;
+
+        buff[SESSION_HEADER_LEN..][..n].copy_from_slice(self.data);
+
+        Ok(SESSION_HEADER_LEN + n)
+    }
+
+    fn encode_len(&self) -> Result<usize, ApduError> {
+        Ok(SESSION_HEADER_LEN + self.data.len())
+    }
+}
+
+impl<'a> Decode<'a> for SessionReq<'a> {
+    type Output = Self;
+    type Error = ApduError;
+
+    /// Decode a [`SessionReq`] APDU from the provided buffer
+    fn decode(buff: &'a [u8]) -> Result<(Self, usize), ApduError> {
+        if buff.len() < SESSION_HEADER_LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let ins = buff[0];
+        let (n, _) = u16::decode(&buff[2..])?;
+        let (counter, _) = u32::decode(&buff[4..])?;
+
+        let n = n as usize;
+        if n > SESSION_BUFF_LEN || buff.len() < SESSION_HEADER_LEN + n {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let data = &buff[SESSION_HEADER_LEN..][..n];
+
+        Ok((Self { ins, counter, data }, SESSION_HEADER_LEN + n))
+    }
+}
+
+/// Wrapped (encrypted) response APDU
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                            COUNTER                            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |            DATA_LEN           |            RESERVED           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                      DATA (CIPHERTEXT | TAG)                  /
+/// /                       (variable length)                       /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct SessionResp<'a> {
+    /// Counter for the associated request
+    pub counter: u32,
+    /// Encrypted response and tag
+    pub data: &'a [u8],
+}
+
+impl<'a> Encode for SessionResp<'a> {
+    type Error = ApduError;
+
+    /// Encode a [`SessionResp`] APDU into the provided buffer
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        let n = self.data.len();
+
+        if n > SESSION_BUFF_LEN || buff.len() < SESSION_HEADER_LEN + n {
+            return Err(ApduError::InvalidLength);
+        }
+
+        self.counter.encode(&mut buff[0..])?;
+        (n as u16).encode(&mut buff[4..])?;
+        buff[6..SESSION_HEADER_LEN].fill(0);
+
+        buff[SESSION_HEADER_LEN..][..n].copy_from_slice(self.data);
+
+        Ok(SESSION_HEADER_LEN + n)
+    }
+
+    fn encode_len(&self) -> Result<usize, ApduError> {
+        Ok(SESSION_HEADER_LEN + self.data.len())
+    }
+}
+
+impl<'a> Decode<'a> for SessionResp<'a> {
+    type Output = Self;
+    type Error = ApduError;
+
+    /// Decode a [`SessionResp`] APDU from the provided buffer
+    fn decode(buff: &'a [u8]) -> Result<(Self, usize), ApduError> {
+        if buff.len() < SESSION_HEADER_LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let (counter, _) = u32::decode(&buff[0..])?;
+        let (n, _) = u16::decode(&buff[4..])?;
+
+        let n = n as usize;
+        if n > SESSION_BUFF_LEN || buff.len() < SESSION_HEADER_LEN + n {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let data = &buff[SESSION_HEADER_LEN..][..n];
+
+        Ok((Self { counter, data }, SESSION_HEADER_LEN + n))
+    }
+}
+
+/// Directional session keys, zeroized on drop
+struct SessionKeys {
+    host_to_device: [u8; 32],
+    device_to_host: [u8; 32],
+}
+
+impl SessionKeys {
+    /// Derive session keys from the handshake shared secret and public keys
+    fn derive(
+        shared: &[u8],
+        host_public: &[u8; 32],
+        device_public: &[u8; 32],
+    ) -> Result<Self, ApduError> {
+        // Reject low-order points resulting in an all-zero shared secret
+        if ct_eq(shared, &[0u8; 32]) {
+            return Err(ApduError::InvalidEncoding);
+        }
+
+        // Bind keys to handshake transcript
+        let mut salt = [0u8; 64];
+        salt[..32].copy_from_slice(host_public);
+        salt[32..].copy_from_slice(device_public);
+
+        let mut okm = [0u8; 64];
+        Hkdf::<Sha256>::new(Some(&salt), shared)
+            .expand(SESSION_KDF_INFO, &mut okm)
+            .map_err(|_| ApduError::InvalidLength)?;
+
+        let mut keys = Self {
+            host_to_device: [0u8; 32],
+            device_to_host: [0u8; 32],
+        };
+        keys.host_to_device.copy_from_slice(&okm[..32]);
+        keys.device_to_host.copy_from_slice(&okm[32..]);
+
+        okm.zeroize();
+
+        Ok(keys)
+    }
+}
+
+impl Drop for SessionKeys {
+    fn drop(&mut self) {
+        self.host_to_device.zeroize();
+        self.device_to_host.zeroize();
+    }
+}
+
+/// Build nonce for the provided counter
+fn nonce(counter: u32) -> [u8; 12] {
+    let mut n = [0u8; 12];
+    n[..4].copy_from_slice(&counter.to_le_bytes());
+    n
+}
+
+/// Build additional data binding ciphertext to instruction and counter
+fn aad(ins: u8, counter: u32) -> [u8; 5] {
+    let mut a = [0u8; 5];
+    a[0] = ins;
+    a[1..].copy_from_slice(&counter.to_le_bytes());
+    a
+}
+
+/// Encrypt `buff[..n]` in place, appending the tag and returning the total length
+fn seal(
+    key: &[u8; 32],
+    ins: u8,
+    counter: u32,
+    buff: &mut [u8],
+    n: usize,
+) -> Result<usize, ApduError> {
+    if buff.len() < n + SESSION_TAG_LEN {
+        return Err(ApduError::InvalidLength);
+    }
+
+    let c = Aes256Gcm::new_from_slice(key).map_err(|_| ApduError::InvalidLength)?;
+    let tag = c
+        .encrypt_in_place_detached(
+            Nonce::from_slice(&nonce(counter)),
+            &aad(ins, counter),
+            &mut buff[..n],
+        )
+        .map_err(|_| ApduError::InvalidEncoding)?;
+
+    buff[n..][..SESSION_TAG_LEN].copy_from_slice(tag.as_slice());
+
+    Ok(n + SESSION_TAG_LEN)
+}
+
+/// Decrypt and authenticate `data` into `buff`, returning the plaintext length
+fn open(
+    key: &[u8; 32],
+    ins: u8,
+    counter: u32,
+    data: &[u8],
+    buff: &mut [u8],
+) -> Result<usize, ApduError> {
+    if data.len() < SESSION_TAG_LEN {
+        return Err(ApduError::InvalidLength);
+    }
+
+    let n = data.len() - SESSION_TAG_LEN;
+    if buff.len() < n {
+        return Err(ApduError::InvalidLength);
+    }
+
+    buff[..n].copy_from_slice(&data[..n]);
+
+    let c = Aes256Gcm::new_from_slice(key).map_err(|_| ApduError::InvalidLength)?;
+    let r = c.decrypt_in_place_detached(
+        Nonce::from_slice(&nonce(counter)),
+        &aad(ins, counter),
+        &mut buff[..n],
+        Tag::from_slice(&data[n..]),
+    );
+
+    // Clear unauthenticated plaintext on failure
+    if r.is_err() {
+        buff[..n].zeroize();
+        return Err(ApduError::InvalidEncoding);
+    }
+
+    Ok(n)
+}
+
+/// Device session state, used by devices to handle [SessionInitReq]
+/// and [SessionReq] APDUs
+pub struct DeviceSession {
+    keys: Option<SessionKeys>,
+    ins: u8,
+    counter: u32,
+    buff: [u8; SESSION_BUFF_LEN],
+}
+
+impl DeviceSession {
+    /// Create a new (closed) [DeviceSession]
+    pub const fn new() -> Self {
+        Self {
+            keys: None,
+            ins: 0,
+            counter: 0,
+            buff: [0u8; SESSION_BUFF_LEN],
+        }
+    }
+
+    /// Initialise a [DeviceSession] via pointer, avoiding stack allocation
+    ///
+    /// # Safety
+    /// `p` must be valid for writes
+    pub unsafe fn init(p: *mut Self) {
+        use core::ptr::addr_of_mut;
+
+        addr_of_mut!((*p).keys).write(None);
+        addr_of_mut!((*p).ins).write(0);
+        addr_of_mut!((*p).counter).write(0);
+        addr_of_mut!((*p).buff).write_bytes(0, 1);
+    }
+
+    /// Close the session, zeroizing keys and buffered data
+    pub fn reset(&mut self) {
+        self.keys = None;
+        self.ins = 0;
+        self.counter = 0;
+        self.buff.zeroize();
+    }
+
+    /// Check whether a session is open
+    pub fn is_open(&self) -> bool {
+        self.keys.is_some()
+    }
+
+    /// Handle a session handshake request, replacing any existing session
+    pub fn handshake<R: RngCore + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        req: &SessionInitReq,
+    ) -> Result<SessionInitResp, ApduError> {
+        self.reset();
+
+        let host_public =
+            X25519Public::try_from(&req.public[..]).map_err(|_| ApduError::InvalidEncoding)?;
+
+        // Generate device ephemeral key
+        let private = X25519EphemeralPrivate::from_random(rng);
+        let mut public = [0u8; 32];
+        public.copy_from_slice(X25519Public::from(&private).as_ref());
+
+        // Derive session keys
+        let shared = private.key_exchange(&host_public);
+        let keys = SessionKeys::derive(shared.as_ref(), &req.public, &public)?;
+
+        self.keys = Some(keys);
+
+        Ok(SessionInitResp { public })
+    }
+
+    /// Decrypt a wrapped request, returning the inner instruction and
+    /// encoded request.
+    ///
+    /// Counters must strictly increase within a session to prevent replay.
+    pub fn open(&mut self, req: &SessionReq) -> Result<(u8, &[u8]), ApduError> {
+        let keys = self.keys.as_ref().ok_or(ApduError::InvalidEncoding)?;
+
+        if req.counter <= self.counter {
+            return Err(ApduError::InvalidEncoding);
+        }
+
+        let n = open(
+            &keys.host_to_device,
+            req.ins,
+            req.counter,
+            req.data,
+            &mut self.buff,
+        )?;
+
+        self.ins = req.ins;
+        self.counter = req.counter;
+
+        Ok((req.ins, &self.buff[..n]))
+    }
+
+    /// Encrypt a response for the last opened request,
+    /// written to the session buffer using the provided encoder
+    pub fn seal(
+        &mut self,
+        encode: impl FnOnce(&mut [u8]) -> Result<usize, ApduError>,
+    ) -> Result<SessionResp, ApduError> {
+        let keys = self.keys.as_ref().ok_or(ApduError::InvalidEncoding)?;
+
+        self.buff.zeroize();
+        let n = encode(&mut self.buff[..SESSION_BUFF_LEN - SESSION_TAG_LEN])?;
+        let n = seal(
+            &keys.device_to_host,
+            self.ins,
+            self.counter,
+            &mut self.buff,
+            n,
+        )?;
+
+        Ok(SessionResp {
+            counter: self.counter,
+            data: &self.buff[..n],
+        })
+    }
+}
+
+impl Default for DeviceSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Host session handshake, see [HostSession]
+pub struct HostHandshake {
+    private: X25519EphemeralPrivate,
+    public: [u8; 32],
+}
+
+impl HostHandshake {
+    /// Create a new handshake with a random ephemeral key
+    pub fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let private = X25519EphemeralPrivate::from_random(rng);
+
+        let mut public = [0u8; 32];
+        public.copy_from_slice(X25519Public::from(&private).as_ref());
+
+        Self { private, public }
+    }
+
+    /// Fetch the handshake request APDU
+    pub fn request(&self) -> SessionInitReq {
+        SessionInitReq {
+            public: self.public,
+        }
+    }
+
+    /// Complete the handshake using the device response
+    pub fn finish(self, resp: &SessionInitResp) -> Result<HostSession, ApduError> {
+        let device_public =
+            X25519Public::try_from(&resp.public[..]).map_err(|_| ApduError::InvalidEncoding)?;
+
+        let shared = self.private.key_exchange(&device_public);
+        let keys = SessionKeys::derive(shared.as_ref(), &self.public, &resp.public)?;
+
+        Ok(HostSession { keys, counter: 0 })
+    }
+}
+
+/// Host session state, used to wrap requests and unwrap responses
+pub struct HostSession {
+    keys: SessionKeys,
+    counter: u32,
+}
+
+impl HostSession {
+    /// Encrypt the encoded request in `buff[..n]` in place, returning
+    /// a wrapped request for the provided instruction.
+    ///
+    /// `buff` must have space for the appended tag.
+    pub fn seal<'a>(
+        &mut self,
+        ins: u8,
+        buff: &'a mut [u8],
+        n: usize,
+    ) -> Result<SessionReq<'a>, ApduError> {
+        // Sessions must be re-established on counter exhaustion
+        let counter = self
+            .counter
+            .checked_add(1)
+            .ok_or(ApduError::InvalidEncoding)?;
+
+        let n = seal(&self.keys.host_to_device, ins, counter, buff, n)?;
+        self.counter = counter;
+
+        Ok(SessionReq {
+            ins,
+            counter,
+            data: &buff[..n],
+        })
+    }
+
+    /// Decrypt a wrapped response to the last sealed request into `buff`,
+    /// returning the encoded response length
+    pub fn open(&self, ins: u8, resp: &SessionResp, buff: &mut [u8]) -> Result<usize, ApduError> {
+        if resp.counter != self.counter {
+            return Err(ApduError::InvalidEncoding);
+        }
+
+        open(
+            &self.keys.device_to_host,
+            ins,
+            self.counter,
+            resp.data,
+            buff,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{random, rngs::OsRng};
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn session_init_apdus() {
+        let mut buff = [0u8; 128];
+
+        encode_decode_apdu(&mut buff, &SessionInitReq { public: random() });
+        encode_decode_apdu(&mut buff, &SessionInitResp { public: random() });
+    }
+
+    #[test]
+    fn session_wrap_apdus() {
+        let data = [0xabu8; 200];
+        let mut buff = [0u8; 256];
+
+        encode_decode_apdu(
+            &mut buff,
+            &SessionReq {
+                ins: 0x10,
+                counter: random(),
+                data: &data,
+            },
+        );
+        encode_decode_apdu(
+            &mut buff,
+            &SessionResp {
+                counter: random(),
+                data: &data,
+            },
+        );
+    }
+
+    #[test]
+    fn session_exchange() {
+        let mut device = DeviceSession::new();
+
+        // Requests are rejected prior to handshake
+        let r = device.open(&SessionReq {
+            ins: 0x10,
+            counter: 1,
+            data: &[0u8; 32],
+        });
+        assert!(r.is_err());
+
+        // Establish session
+        let h = HostHandshake::new(&mut OsRng);
+        let resp = device.handshake(&mut OsRng, &h.request()).unwrap();
+        let mut host = h.finish(&resp).unwrap();
+        assert!(device.is_open());
+
+        for i in 0..3u8 {
+            // Wrap request
+            let req = [i; 40];
+            let mut buff = [0u8; 64];
+            buff[..req.len()].copy_from_slice(&req);
+
+            let wrapped = host.seal(0x10, &mut buff, req.len()).unwrap();
+            assert_ne!(&wrapped.data[..req.len()], &req[..]);
+
+            // Unwrap on device
+            let (ins, data) = device.open(&wrapped).unwrap();
+            assert_eq!(ins, 0x10);
+            assert_eq!(data, &req[..]);
+
+            // Replayed requests are rejected
+            assert!(device.open(&wrapped).is_err());
+
+            // Wrap response
+            let r = device
+                .seal(|b| {
+                    b[..8].copy_from_slice(&[i; 8]);
+                    Ok(8)
+                })
+                .unwrap();
+
+            // Unwrap on host
+            let mut out = [0u8; 64];
+            let n = host.open(0x10, &r, &mut out).unwrap();
+            assert_eq!(&out[..n], &[i; 8]);
+
+            // Responses bound to other instructions are rejected
+            assert!(host.open(0x11, &r, &mut out).is_err());
+        }
+
+        // Tampered requests are rejected
+        let mut buff = [0u8; 64];
+        let wrapped = host.seal(0x10, &mut buff, 16).unwrap();
+        let mut data = [0u8; 32];
+        data.copy_from_slice(wrapped.data);
+        data[0] ^= 0x01;
+
+        let r = device.open(&SessionReq {
+            ins: 0x10,
+            counter: wrapped.counter,
+            data: &data,
+        });
+        assert!(r.is_err());
+
+        // Closed sessions reject requests
+        device.reset();
+        assert!(!device.is_open());
+    }
+
+    #[test]
+    fn sensitive_instructions() {
+        assert!(requires_session(Instruction::GetWalletKeys as u8));
+        assert!(requires_session(Instruction::TxSetBlinding as u8));
+        assert!(!requires_session(Instruction::GetAppInfo as u8));
+        assert!(!requires_session(Instruction::TxGetInfo as u8));
+    }
+}
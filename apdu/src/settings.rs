@@ -0,0 +1,197 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Device settings APDUs, for fetching the device configuration and
+//! host-initiated configuration changes requiring on-device confirmation.
+
+use encdec::{Decode, Encode};
+
+use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
+use crate::{helpers::*, tx::FogId};
+
+/// Request a change of the device's active fog id, returning a state object.
+///
+/// The requested fog is displayed in the device settings page, following
+/// confirmation the state moves to `FogApproved` (or `FogDenied` where
+/// the user selects a different fog).
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |    FOG_ID     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SetFogIdReq {
+    /// Requested fog ID
+    pub fog_id: FogId,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 3],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    SetFogIdReq {
+        fog_id,
+    } reserved { _reserved }
+);
+
+impl SetFogIdReq {
+    /// Create a new [SetFogIdReq] APDU
+    pub fn new(fog_id: FogId) -> Self {
+        Self {
+            fog_id,
+            _reserved: [0u8; 3],
+        }
+    }
+}
+
+impl ApduStatic for SetFogIdReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::SetFogId as u8;
+}
+
+/// Request current device settings (0 length APDU)
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SettingsReq {}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(SettingsReq {});
+
+impl ApduStatic for SettingsReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetSettings as u8;
+}
+
+bitflags::bitflags! {
+    /// Device settings flags
+    pub struct SettingsFlags: u8 {
+        /// Blind signing (direct message setting) permitted
+        const BLIND_SIGNING = 1 << 0;
+        /// Host-supplied onetime private keys permitted for ring signing
+        const ONETIME_KEYS = 1 << 1;
+    }
+}
+
+crate::encdec_bitflags!(SettingsFlags);
+
+#[cfg(feature = "cbor")]
+crate::cbor_bitflags!(SettingsFlags, u8);
+
+/// Device settings response APDU
+///
+/// Contains the current device configuration, allowing hosts to
+/// detect settings that conflict with a transaction prior to signing.
+///
+/// `LOCK_TIMEOUT_S` is zero where the device does not lock on inactivity.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |    FOG_ID     |     FLAGS     |           RESERVED            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                        LOCK_TIMEOUT_S                         |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                     DEFAULT_ACCOUNT_INDEX                     |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SettingsResp {
+    /// Active fog ID
+    pub fog_id: FogId,
+    /// Settings flags
+    pub flags: SettingsFlags,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 2],
+    /// Inactivity timeout before the device locks
+    pub lock_timeout_s: u32,
+    /// SLIP-0010 account index used for on-device operations
+    pub account_index: u32,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    SettingsResp {
+        fog_id,
+        flags,
+        lock_timeout_s,
+        account_index,
+    } reserved { _reserved }
+);
+
+impl SettingsResp {
+    /// Create a new [SettingsResp] APDU
+    pub fn new(
+        fog_id: FogId,
+        flags: SettingsFlags,
+        lock_timeout_s: u32,
+        account_index: u32,
+    ) -> Self {
+        Self {
+            fog_id,
+            flags,
+            _reserved: [0u8; 2],
+            lock_timeout_s,
+            account_index,
+        }
+    }
+
+    /// Check whether blind signing is permitted
+    pub fn blind_signing(&self) -> bool {
+        self.flags.contains(SettingsFlags::BLIND_SIGNING)
+    }
+
+    /// Check whether host-supplied onetime private keys are permitted
+    pub fn onetime_keys(&self) -> bool {
+        self.flags.contains(SettingsFlags::ONETIME_KEYS)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn set_fog_id_req_apdu() {
+        let apdu = SetFogIdReq::new(FogId::SignalMain);
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 4);
+    }
+
+    #[test]
+    fn settings_req_apdu() {
+        let apdu = SettingsReq::default();
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn settings_resp_apdu() {
+        let apdu = SettingsResp::new(
+            FogId::MobTest,
+            SettingsFlags::BLIND_SIGNING | SettingsFlags::ONETIME_KEYS,
+            random(),
+            random(),
+        );
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 12);
+        assert!(apdu.blind_signing());
+        assert!(apdu.onetime_keys());
+    }
+}
@@ -9,6 +9,7 @@ use num_enum::TryFromPrimitive;
 use rand_core::{CryptoRng, RngCore};
 use sha2::{Digest as _, Sha512_256};
 use strum::{Display, EnumIter, EnumString, EnumVariantNames};
+use subtle::{Choice, ConstantTimeEq};
 
 /// Engine state enumeration
 /// used in [crate::tx::TxInfo] to communicate transaction progress
@@ -25,17 +26,31 @@ pub enum TxState {
     SummaryAddTxIn = 0x05,
     SummaryReady = 0x06,
     SummaryComplete = 0x07,
+    /// First memo held for on-device review prior to signing
+    MemoPending = 0x08,
     Pending = 0x10,
     Ready = 0x20,
     RingInit = 0x30,
     RingBuild = 0x31,
     RingSign = 0x32,
     RingComplete = 0x33,
+    /// Ring signing failed, the ring may be restarted via `TxRingInit`
+    RingError = 0x34,
     TxComplete = 0x40,
     TxDenied = 0x41,
+    /// Transaction aborted by the host (see [crate::tx::TxAbort])
+    TxAborted = 0x42,
+    /// Transaction cancelled on-device during signing
+    TxCancelled = 0x43,
     IdentPending = 0x50,
     IdentApproved = 0x51,
     IdentDenied = 0x52,
+    AddressPending = 0x60,
+    AddressApproved = 0x61,
+    AddressDenied = 0x62,
+    FogPending = 0x70,
+    FogApproved = 0x71,
+    FogDenied = 0x72,
     Error = 0xFF,
 }
 
@@ -72,7 +87,8 @@ impl DecodeOwned for TxState {
 /// Transaction digest, used to keep a running digest of inputs to
 /// the transaction engine to ensure sync between the host and hardware
 /// wallet.
-#[derive(Clone, PartialEq, Encode)]
+#[derive(Clone, Encode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Digest([u8; 32]);
 
 impl Digest {
@@ -81,6 +97,11 @@ impl Digest {
         Self([0u8; 32])
     }
 
+    /// Fetch digest bytes
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
     /// Reset state digest from random seed
     #[inline(never)]
     pub fn from_random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
@@ -115,6 +136,39 @@ impl Digest {
     }
 }
 
+/// CBOR encoding for [Digest], as a 32-byte string
+#[cfg(feature = "cbor")]
+impl crate::cbor::CborEncode for Digest {
+    fn encode_field<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        crate::cbor::CborEncode::encode_field(&self.0, e)
+    }
+}
+
+/// CBOR decoding for [Digest], from a 32-byte string
+#[cfg(feature = "cbor")]
+impl<'b> crate::cbor::CborDecode<'b> for Digest {
+    fn decode_field(d: &mut minicbor::Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        <[u8; 32] as crate::cbor::CborDecode>::decode_field(d).map(Self)
+    }
+}
+
+/// Constant-time comparison for [Digest]
+impl ConstantTimeEq for Digest {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+/// [PartialEq] for [Digest], using a constant-time comparison
+impl PartialEq for Digest {
+    fn eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
 /// Debug format [Digest] as hex
 impl core::fmt::Debug for Digest {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -0,0 +1,174 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Status words returned for failed requests
+//!
+//! Engine failures are returned as `CLASS << 8 | CODE`, where `CLASS` is a
+//! [StatusClass] indicating how the failure should be handled and `CODE`
+//! is the engine-specific error code, allowing clients to branch on the
+//! class of failure without matching every error code.
+//!
+//! | Range             | Class                      |
+//! | ----------------- | -------------------------- |
+//! | `0x9000`          | Success ([SW_OK])          |
+//! | `0xB000..=0xB0FF` | [StatusClass::State]       |
+//! | `0xB100..=0xB1FF` | [StatusClass::Decode]      |
+//! | `0xB200..=0xB2FF` | [StatusClass::Approval]    |
+//! | `0xB300..=0xB3FF` | [StatusClass::Crypto]      |
+//! | `0xB400..=0xB4FF` | [StatusClass::Other]       |
+//!
+//! Other status words (for example unsupported instructions or malformed
+//! APDUs) are returned using standard platform values.
+//!
+//! Debug and `pre-release` firmware builds may include a short UTF-8 detail
+//! string (engine state, failing field) as reply data preceding failure
+//! status words, see [error_detail]. Production builds return status-only
+//! failure replies.
+
+use num_enum::TryFromPrimitive;
+use strum::{Display, EnumIter};
+
+/// Status word for successful requests
+pub const SW_OK: u16 = 0x9000;
+
+/// Standard status word for unsupported instructions within a supported class
+pub const SW_INS_NOT_SUPPORTED: u16 = 0x6d00;
+
+/// Maximum length of UTF-8 error detail strings preceding failure status words
+pub const ERROR_DETAIL_MAX: usize = 48;
+
+/// Status word classes, used as the upper byte of engine failure status words
+#[derive(Copy, Clone, PartialEq, Debug, Display, EnumIter, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum StatusClass {
+    /// Request not valid in the current engine state (unexpected event,
+    /// out-of-sequence request, missing prior data), the host should
+    /// re-synchronise or restart the operation
+    State = 0xb0,
+
+    /// Request contents are invalid or unsupported (lengths, keys,
+    /// addresses, memos, ring indices), the host should not retry
+    Decode = 0xb1,
+
+    /// User approval pending or rejected, the host may wait and retry
+    /// pending requests
+    Approval = 0xb2,
+
+    /// Cryptographic operation or verification failed
+    /// (signing, key recovery, value conservation, payload MACs)
+    Crypto = 0xb3,
+
+    /// Unclassified failures
+    Other = 0xb4,
+}
+
+/// Engine failure status word, see [module][self] documentation
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct StatusWord(pub u16);
+
+impl StatusWord {
+    /// Create a status word for a given class and error code
+    pub const fn new(class: StatusClass, code: u8) -> Self {
+        Self((class as u16) << 8 | code as u16)
+    }
+
+    /// Fetch the [StatusClass] for a status word,
+    /// `None` for status words outside the engine failure ranges
+    pub fn class(&self) -> Option<StatusClass> {
+        StatusClass::try_from((self.0 >> 8) as u8).ok()
+    }
+
+    /// Fetch the engine error code for a status word
+    pub const fn code(&self) -> u8 {
+        self.0 as u8
+    }
+
+    /// Check whether the status word indicates success
+    pub const fn is_ok(&self) -> bool {
+        self.0 == SW_OK
+    }
+}
+
+impl From<StatusWord> for u16 {
+    fn from(sw: StatusWord) -> Self {
+        sw.0
+    }
+}
+
+impl From<u16> for StatusWord {
+    fn from(v: u16) -> Self {
+        Self(v)
+    }
+}
+
+/// Split a raw reply into data and [StatusWord],
+/// `None` for replies without a status word
+pub fn split_reply(resp: &[u8]) -> Option<(&[u8], StatusWord)> {
+    if resp.len() < 2 {
+        return None;
+    }
+
+    let (data, sw) = resp.split_at(resp.len() - 2);
+    Some((data, StatusWord(u16::from_be_bytes([sw[0], sw[1]]))))
+}
+
+/// Fetch the UTF-8 error detail from a raw failure reply (data and status word),
+/// `None` for successful replies or where no valid detail string is present
+pub fn error_detail(resp: &[u8]) -> Option<&str> {
+    let (data, sw) = split_reply(resp)?;
+
+    // Detail strings are only attached to failure replies (MOB-06.7)
+    if sw.is_ok() || data.is_empty() || data.len() > ERROR_DETAIL_MAX {
+        return None;
+    }
+
+    core::str::from_utf8(data).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn status_word_class() {
+        let sw = StatusWord::new(StatusClass::Approval, 0x0c);
+        assert_eq!(u16::from(sw), 0xb20c);
+        assert_eq!(sw.class(), Some(StatusClass::Approval));
+        assert_eq!(sw.code(), 0x0c);
+
+        assert_eq!(StatusWord(SW_OK).class(), None);
+        assert!(StatusWord(SW_OK).is_ok());
+
+        // Platform status words are not engine failures
+        assert_eq!(StatusWord(0x6a80).class(), None);
+    }
+
+    #[test]
+    fn status_error_detail() {
+        // Status-only failure replies
+        assert_eq!(
+            split_reply(&[0xb0, 0x02]),
+            Some((&[][..], StatusWord(0xb002)))
+        );
+        assert_eq!(error_detail(&[0xb0, 0x02]), None);
+        assert_eq!(split_reply(&[0x90]), None);
+
+        // Failure replies with detail
+        let r = b"UnexpectedEvent (Init)\xb0\x02";
+        assert_eq!(split_reply(r).map(|(_, sw)| sw), Some(StatusWord(0xb002)));
+        assert_eq!(error_detail(r), Some("UnexpectedEvent (Init)"));
+
+        // Successful replies never carry details
+        assert_eq!(error_detail(b"abcd\x90\x00"), None);
+
+        // Invalid or oversized details are ignored
+        assert_eq!(error_detail(&[0xff, 0xfe, 0xb0, 0x02]), None);
+        let mut r = [b'a'; ERROR_DETAIL_MAX + 3];
+        r[ERROR_DETAIL_MAX + 1..].copy_from_slice(&[0xb0, 0x02]);
+        assert_eq!(error_detail(&r), None);
+        assert_eq!(
+            error_detail(&r[1..]).map(|s| s.len()),
+            Some(ERROR_DETAIL_MAX)
+        );
+    }
+}
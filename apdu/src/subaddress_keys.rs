@@ -3,15 +3,24 @@
 //! Subaddress related APDUs, for fetching / checking wallet subaddresses
 //!
 
-use encdec::{Decode, Encode};
+use encdec::{Decode, DecodeOwned, Encode};
+use heapless::Vec;
 
-use mc_core::keys::{SubaddressSpendPublic, SubaddressViewPrivate};
+use mc_core::keys::{SubaddressSpendPublic, SubaddressViewPrivate, SubaddressViewPublic};
 
 use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
-use crate::helpers::*;
+use crate::{
+    algorithm::Algorithm,
+    attestation::{trailing, KeyAttestation, KEY_ATTESTATION_CHALLENGE_LEN},
+    helpers::*,
+    key_path::KeyPath,
+    tx::FogId,
+};
 
 /// Request keys for a given account index and subaddress
 ///
+/// `ALGORITHM` must be [Algorithm::Ristretto255].
+///
 /// ## Encoding:
 /// ```text
 ///  0                   1                   2                   3
@@ -22,6 +31,8 @@ use crate::helpers::*;
 /// |                       SUBADDRESS_INDEX                        |
 /// |                           (8-bytes)                           |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   ALGORITHM   |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
 #[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
@@ -30,14 +41,25 @@ pub struct SubaddressKeyReq {
     pub account_index: u32,
     /// Subkey index
     pub subaddress_index: u64,
+    /// Key algorithm
+    #[encdec(with = "ristretto_alg")]
+    pub algorithm: Algorithm,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(SubaddressKeyReq {
+    account_index,
+    subaddress_index,
+    algorithm: ristretto_alg
+});
+
 impl SubaddressKeyReq {
     /// Create a new [SubaddressKeyReq] APDU
     pub fn new(account_index: u32, subaddress_index: u64) -> Self {
         Self {
             account_index,
             subaddress_index,
+            algorithm: Algorithm::Ristretto255,
         }
     }
 }
@@ -47,7 +69,132 @@ impl ApduStatic for SubaddressKeyReq {
     const INS: u8 = Instruction::GetSubaddressKeys as u8;
 }
 
-/// Subaddress key response
+/// Request attested keys for a given account index and subaddress,
+/// returning a [SubaddressKeyResp] including a [KeyAttestation] over
+/// the returned keys and provided `CHALLENGE` (see [crate::attestation]).
+///
+/// `ALGORITHM` must be [Algorithm::Ristretto255].
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                       SUBADDRESS_INDEX                        |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   ALGORITHM   |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                           CHALLENGE                           /
+/// /                     (32-byte host challenge)                  /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SubaddressKeyAttestReq {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// Subkey index
+    pub subaddress_index: u64,
+    /// Key algorithm
+    #[encdec(with = "ristretto_alg")]
+    pub algorithm: Algorithm,
+    /// Host challenge, bound by the attestation signature
+    #[encdec(with = "arr")]
+    pub challenge: [u8; KEY_ATTESTATION_CHALLENGE_LEN],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(SubaddressKeyAttestReq {
+    account_index,
+    subaddress_index,
+    algorithm: ristretto_alg,
+    challenge
+});
+
+impl SubaddressKeyAttestReq {
+    /// Create a new [SubaddressKeyAttestReq] APDU
+    pub fn new(
+        account_index: u32,
+        subaddress_index: u64,
+        challenge: [u8; KEY_ATTESTATION_CHALLENGE_LEN],
+    ) -> Self {
+        Self {
+            account_index,
+            subaddress_index,
+            algorithm: Algorithm::Ristretto255,
+            challenge,
+        }
+    }
+}
+
+impl ApduStatic for SubaddressKeyAttestReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetSubaddressKeysAttested as u8;
+}
+
+/// Request subaddress keys for the account at an explicit derivation path
+/// (see [crate::key_path]), in place of an account index. The returned
+/// [SubaddressKeyResp] `account_index` is set to the final (unhardened)
+/// path element.
+///
+/// `ALGORITHM` must be [Algorithm::Ristretto255].
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /                             PATH                              /
+/// /                  (24-byte KeyPath encoding)                   /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                       SUBADDRESS_INDEX                        |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   ALGORITHM   |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SubaddressKeyPathReq {
+    /// SLIP-0010 derivation path
+    pub path: KeyPath,
+    /// Subkey index
+    pub subaddress_index: u64,
+    /// Key algorithm
+    #[encdec(with = "ristretto_alg")]
+    pub algorithm: Algorithm,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(SubaddressKeyPathReq {
+    path,
+    subaddress_index,
+    algorithm: ristretto_alg
+});
+
+impl SubaddressKeyPathReq {
+    /// Create a new [SubaddressKeyPathReq] APDU
+    pub fn new(path: KeyPath, subaddress_index: u64) -> Self {
+        Self {
+            path,
+            subaddress_index,
+            algorithm: Algorithm::Ristretto255,
+        }
+    }
+}
+
+impl ApduStatic for SubaddressKeyPathReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetSubaddressKeysPath as u8;
+}
+
+/// Subaddress key response, followed by a [KeyAttestation]
+/// for [SubaddressKeyAttestReq] requests
 ///
 /// ## Encoding:
 /// ```text
@@ -69,22 +216,33 @@ impl ApduStatic for SubaddressKeyReq {
 /// /                 (32-byte Ristretto Public Key)                /
 /// |                                                               |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /                    ATTESTATION (OPTIONAL)                     /
+/// /                (96-byte KeyAttestation, if present)           /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
-#[derive(Clone, PartialEq, Debug, Encode, Decode)]
-#[encdec(error = "ApduError")]
+#[derive(Clone, PartialEq, Debug)]
 pub struct SubaddressKeyResp {
     /// SLIP-0010 account index
     pub account_index: u32,
     /// Subaddress index
     pub subaddress_index: u64,
     /// Vew Private Key
-    #[encdec(with = "pri_key")]
     pub view_private: SubaddressViewPrivate,
     /// Spend public key
-    #[encdec(with = "pub_key")]
     pub spend_public: SubaddressSpendPublic,
+    /// Key attestation, for [SubaddressKeyAttestReq] requests
+    pub attestation: Option<KeyAttestation>,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(SubaddressKeyResp {
+    account_index,
+    subaddress_index,
+    view_private,
+    spend_public,
+    attestation,
+});
+
 impl SubaddressKeyResp {
     /// Create a new [`SubaddressKeyResp`] APDU
     pub fn new(
@@ -98,7 +256,627 @@ impl SubaddressKeyResp {
             subaddress_index,
             view_private,
             spend_public,
+            attestation: None,
+        }
+    }
+
+    /// Attach a [KeyAttestation] to a [`SubaddressKeyResp`]
+    pub fn with_attestation(mut self, attestation: KeyAttestation) -> Self {
+        self.attestation = Some(attestation);
+        self
+    }
+}
+
+impl Encode for SubaddressKeyResp {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(76 + trailing::enc_len(&self.attestation))
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut index = 0;
+
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < self.encode_len()? {
+            return Err(ApduError::InvalidLength);
         }
+
+        // Write keys
+        index += self.account_index.encode(&mut buff[index..])?;
+        index += self.subaddress_index.encode(&mut buff[index..])?;
+        index += pri_key::enc(&self.view_private, &mut buff[index..])?;
+        index += pub_key::enc(&self.spend_public, &mut buff[index..])?;
+
+        // Write attestation if present
+        index += trailing::enc(&self.attestation, &mut buff[index..])?;
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for SubaddressKeyResp {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        let mut index = 0;
+
+        // Check minimum length (MOB-06.7)
+        if buff.len() < 76 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read keys
+        let (account_index, n) = u32::decode_owned(&buff[index..])?;
+        index += n;
+        let (subaddress_index, n) = u64::decode_owned(&buff[index..])?;
+        index += n;
+        let (view_private, n) = pri_key::dec(&buff[index..])?;
+        index += n;
+        let (spend_public, n) = pub_key::dec(&buff[index..])?;
+        index += n;
+
+        // Read attestation if present
+        let (attestation, n) = trailing::dec(&buff[index..])?;
+        index += n;
+
+        Ok((
+            Self {
+                account_index,
+                subaddress_index,
+                view_private,
+                spend_public,
+                attestation,
+            },
+            index,
+        ))
+    }
+}
+
+/// Request keys for a given account index and subaddress along with the fog
+/// authority signature for the provided `FOG_ID`, returning a
+/// [SubaddressKeyFogResp] so hosts may construct fog-enabled public
+/// addresses without a further [PublicAddressReq][crate::address::PublicAddressReq].
+///
+/// `ALGORITHM` must be [Algorithm::Ristretto255].
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                       SUBADDRESS_INDEX                        |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   ALGORITHM   |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |    FOG_ID     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SubaddressKeyFogReq {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// Subkey index
+    pub subaddress_index: u64,
+    /// Key algorithm
+    #[encdec(with = "ristretto_alg")]
+    pub algorithm: Algorithm,
+    /// Fog ID for address
+    pub fog_id: FogId,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 3],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    SubaddressKeyFogReq {
+        account_index,
+        subaddress_index,
+        algorithm: ristretto_alg,
+        fog_id,
+    } reserved { _reserved }
+);
+
+impl SubaddressKeyFogReq {
+    /// Create a new [SubaddressKeyFogReq] APDU
+    pub fn new(account_index: u32, subaddress_index: u64, fog_id: FogId) -> Self {
+        Self {
+            account_index,
+            subaddress_index,
+            algorithm: Algorithm::Ristretto255,
+            fog_id,
+            _reserved: [0u8; 3],
+        }
+    }
+}
+
+impl ApduStatic for SubaddressKeyFogReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetSubaddressKeysFog as u8;
+}
+
+/// Subaddress key response with fog information, for [SubaddressKeyFogReq]
+/// requests
+///
+/// The fog report URL is resolved from `FOG_ID` (see [FogId::url]),
+/// `FOG_AUTHORITY_SIG` is zeroed where `FOG_ID` is `None`.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                       SUBADDRESS_INDEX                        |
+/// |                         (8-byte u64)                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                    SUBADDR_VIEW_PRIVATE_KEY                   /
+/// /                (32-byte Ristretto Private Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                    SUBADDR_SPEND_PUBLIC_KEY                   /
+/// /                 (32-byte Ristretto Public Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |    FOG_ID     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                       FOG_AUTHORITY_SIG                       /
+/// /               (64-byte Fog Authority Signature)               /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SubaddressKeyFogResp {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// Subaddress index
+    pub subaddress_index: u64,
+    /// View private key
+    #[encdec(with = "pri_key")]
+    pub view_private: SubaddressViewPrivate,
+    /// Spend public key
+    #[encdec(with = "pub_key")]
+    pub spend_public: SubaddressSpendPublic,
+    /// Fog ID for address
+    pub fog_id: FogId,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 3],
+    /// Fog authority signature (zeroed if no fog is set)
+    #[encdec(with = "arr")]
+    pub fog_sig: [u8; 64],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    SubaddressKeyFogResp {
+        account_index,
+        subaddress_index,
+        view_private,
+        spend_public,
+        fog_id,
+        fog_sig,
+    } reserved { _reserved }
+);
+
+impl SubaddressKeyFogResp {
+    /// Create a new [SubaddressKeyFogResp] APDU
+    pub fn new(
+        account_index: u32,
+        subaddress_index: u64,
+        view_private: SubaddressViewPrivate,
+        spend_public: SubaddressSpendPublic,
+        fog_id: FogId,
+        fog_sig: [u8; 64],
+    ) -> Self {
+        Self {
+            account_index,
+            subaddress_index,
+            view_private,
+            spend_public,
+            fog_id,
+            _reserved: [0u8; 3],
+            fog_sig,
+        }
+    }
+}
+
+/// Maximum number of subaddresses returned in a single [SubaddressRangeResp]
+pub const SUBADDRESS_RANGE_MAX: usize = 3;
+
+/// Request public keys for a contiguous range of subaddresses
+///
+/// `COUNT` must be between 1 and [SUBADDRESS_RANGE_MAX], larger ranges
+/// should be split across multiple requests.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                    FIRST_SUBADDRESS_INDEX                     |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     COUNT     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SubaddressRangeReq {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// First subaddress index in range
+    pub start: u64,
+    /// Number of subaddresses in range
+    pub count: u8,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 3],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(SubaddressRangeReq { account_index, start, count } reserved { _reserved });
+
+impl SubaddressRangeReq {
+    /// Create a new [SubaddressRangeReq] APDU
+    pub fn new(account_index: u32, start: u64, count: u8) -> Self {
+        Self {
+            account_index,
+            start,
+            count,
+            _reserved: [0u8; 3],
+        }
+    }
+}
+
+impl ApduStatic for SubaddressRangeReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetSubaddressRange as u8;
+}
+
+/// Subaddress range response, containing view and spend public keys
+/// for each subaddress in the requested range
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                    FIRST_SUBADDRESS_INDEX                     |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     COUNT     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                    SUBADDR_VIEW_PUBLIC_KEY                    /
+/// /                 (32-byte Ristretto Public Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                    SUBADDR_SPEND_PUBLIC_KEY                   /
+/// /                 (32-byte Ristretto Public Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /            ... repeated for COUNT subaddresses ...            /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct SubaddressRangeResp {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// First subaddress index in range
+    pub start: u64,
+    /// Subaddress public keys
+    pub keys: Vec<(SubaddressViewPublic, SubaddressSpendPublic), SUBADDRESS_RANGE_MAX>,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(SubaddressRangeResp {
+    account_index,
+    start,
+    keys
+});
+
+impl SubaddressRangeResp {
+    /// Create a new [`SubaddressRangeResp`] APDU
+    pub fn new(
+        account_index: u32,
+        start: u64,
+        keys: Vec<(SubaddressViewPublic, SubaddressSpendPublic), SUBADDRESS_RANGE_MAX>,
+    ) -> Self {
+        Self {
+            account_index,
+            start,
+            keys,
+        }
+    }
+}
+
+impl Encode for SubaddressRangeResp {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(16 + self.keys.len() * 64)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut index = 0;
+
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < self.encode_len()? {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write header
+        index += self.account_index.encode(&mut buff[index..])?;
+        index += self.start.encode(&mut buff[index..])?;
+        buff[index] = self.keys.len() as u8;
+        buff[index + 1..][..3].fill(0);
+        index += 4;
+
+        // Write keys
+        for (view_public, spend_public) in &self.keys {
+            index += pub_key::enc(view_public, &mut buff[index..])?;
+            index += pub_key::enc(spend_public, &mut buff[index..])?;
+        }
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for SubaddressRangeResp {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        let mut index = 0;
+
+        // Check header length (MOB-06.7)
+        if buff.len() < 16 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read header
+        let (account_index, n) = u32::decode_owned(&buff[index..])?;
+        index += n;
+        let (start, n) = u64::decode_owned(&buff[index..])?;
+        index += n;
+        let count = buff[index] as usize;
+        index += 4;
+
+        // Check count and full buffer length (MOB-06.7)
+        if count > SUBADDRESS_RANGE_MAX || buff.len() < 16 + count * 64 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read keys
+        let mut keys = Vec::new();
+        for _i in 0..count {
+            let (view_public, n) = pub_key::dec(&buff[index..])?;
+            index += n;
+            let (spend_public, n) = pub_key::dec(&buff[index..])?;
+            index += n;
+
+            let _ = keys.push((view_public, spend_public));
+        }
+
+        Ok((
+            Self {
+                account_index,
+                start,
+                keys,
+            },
+            index,
+        ))
+    }
+}
+
+/// Maximum number of subaddresses returned in a single [SubaddressKeysBatchResp]
+pub const SUBADDRESS_KEYS_BATCH_MAX: usize = 3;
+
+/// Request keys for a contiguous range of subaddresses, for scanning
+///
+/// `COUNT` must be between 1 and [SUBADDRESS_KEYS_BATCH_MAX], larger ranges
+/// should be split across multiple requests.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                    FIRST_SUBADDRESS_INDEX                     |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     COUNT     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct SubaddressKeysBatchReq {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// First subaddress index in batch
+    pub start: u64,
+    /// Number of subaddresses in batch
+    pub count: u8,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 3],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(SubaddressKeysBatchReq { account_index, start, count } reserved { _reserved });
+
+impl SubaddressKeysBatchReq {
+    /// Create a new [SubaddressKeysBatchReq] APDU
+    pub fn new(account_index: u32, start: u64, count: u8) -> Self {
+        Self {
+            account_index,
+            start,
+            count,
+            _reserved: [0u8; 3],
+        }
+    }
+}
+
+impl ApduStatic for SubaddressKeysBatchReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetSubaddressKeysBatch as u8;
+}
+
+/// Subaddress key batch response, containing spend public and view private
+/// keys for each subaddress in the requested batch
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                    FIRST_SUBADDRESS_INDEX                     |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     COUNT     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                    SUBADDR_SPEND_PUBLIC_KEY                   /
+/// /                 (32-byte Ristretto Public Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                    SUBADDR_VIEW_PRIVATE_KEY                   /
+/// /                (32-byte Ristretto Private Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /            ... repeated for COUNT subaddresses ...            /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct SubaddressKeysBatchResp {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// First subaddress index in batch
+    pub start: u64,
+    /// Subaddress spend public and view private keys
+    pub keys: Vec<(SubaddressSpendPublic, SubaddressViewPrivate), SUBADDRESS_KEYS_BATCH_MAX>,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(SubaddressKeysBatchResp {
+    account_index,
+    start,
+    keys
+});
+
+impl SubaddressKeysBatchResp {
+    /// Create a new [`SubaddressKeysBatchResp`] APDU
+    pub fn new(
+        account_index: u32,
+        start: u64,
+        keys: Vec<(SubaddressSpendPublic, SubaddressViewPrivate), SUBADDRESS_KEYS_BATCH_MAX>,
+    ) -> Self {
+        Self {
+            account_index,
+            start,
+            keys,
+        }
+    }
+}
+
+impl Encode for SubaddressKeysBatchResp {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(16 + self.keys.len() * 64)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut index = 0;
+
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < self.encode_len()? {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write header
+        index += self.account_index.encode(&mut buff[index..])?;
+        index += self.start.encode(&mut buff[index..])?;
+        buff[index] = self.keys.len() as u8;
+        buff[index + 1..][..3].fill(0);
+        index += 4;
+
+        // Write keys
+        for (spend_public, view_private) in &self.keys {
+            index += pub_key::enc(spend_public, &mut buff[index..])?;
+            index += pri_key::enc(view_private, &mut buff[index..])?;
+        }
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for SubaddressKeysBatchResp {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        let mut index = 0;
+
+        // Check header length (MOB-06.7)
+        if buff.len() < 16 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read header
+        let (account_index, n) = u32::decode_owned(&buff[index..])?;
+        index += n;
+        let (start, n) = u64::decode_owned(&buff[index..])?;
+        index += n;
+        let count = buff[index] as usize;
+        index += 4;
+
+        // Check count and full buffer length (MOB-06.7)
+        if count > SUBADDRESS_KEYS_BATCH_MAX || buff.len() < 16 + count * 64 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read keys
+        let mut keys = Vec::new();
+        for _i in 0..count {
+            let (spend_public, n) = pub_key::dec(&buff[index..])?;
+            index += n;
+            let (view_private, n) = pri_key::dec(&buff[index..])?;
+            index += n;
+
+            let _ = keys.push((spend_public, view_private));
+        }
+
+        Ok((
+            Self {
+                account_index,
+                start,
+                keys,
+            },
+            index,
+        ))
     }
 }
 
@@ -111,7 +889,11 @@ mod test {
     use mc_util_from_random::FromRandom;
 
     use super::*;
-    use crate::test::encode_decode_apdu;
+    use crate::{
+        attestation::KEY_ATTESTATION_LEN,
+        key_path::{KEY_PATH_HARDENED, KEY_PATH_PREFIX},
+        test::encode_decode_apdu,
+    };
 
     #[test]
     fn subaddress_keys_get_apdu() {
@@ -133,6 +915,117 @@ mod test {
             SubaddressSpendPublic::from(&spend_private),
         );
 
+        let mut buff = [0u8; 256];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 76);
+
+        // With attestation
+        let apdu = apdu.with_attestation(KeyAttestation::new(random(), [0xab; 64]));
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 76 + KEY_ATTESTATION_LEN);
+    }
+
+    #[test]
+    fn subaddress_keys_fog_apdu() {
+        let apdu = SubaddressKeyFogReq::new(random(), random(), FogId::MobMain);
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 20);
+
+        let view_private = SubaddressViewPrivate::from(RistrettoPrivate::from_random(&mut OsRng));
+        let spend_private = SubaddressSpendPrivate::from(RistrettoPrivate::from_random(&mut OsRng));
+
+        let apdu = SubaddressKeyFogResp::new(
+            random(),
+            random(),
+            view_private,
+            SubaddressSpendPublic::from(&spend_private),
+            FogId::MobMain,
+            [0xab; 64],
+        );
+
+        let mut buff = [0u8; 256];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 76 + 4 + 64);
+    }
+
+    #[test]
+    fn subaddress_keys_attest_apdu() {
+        let apdu = SubaddressKeyAttestReq::new(random(), random(), random());
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 48);
+    }
+
+    #[test]
+    fn subaddress_keys_path_apdu() {
+        let path = [
+            KEY_PATH_PREFIX[0],
+            KEY_PATH_PREFIX[1],
+            random::<u32>() | KEY_PATH_HARDENED,
+        ];
+        let apdu = SubaddressKeyPathReq::new(KeyPath::new(&path).unwrap(), random());
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 36);
+    }
+
+    #[test]
+    fn subaddress_range_req_apdu() {
+        let apdu = SubaddressRangeReq::new(random(), random(), SUBADDRESS_RANGE_MAX as u8);
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn subaddress_range_resp_apdu() {
+        let mut keys = Vec::new();
+        for _i in 0..SUBADDRESS_RANGE_MAX {
+            let view_private =
+                SubaddressViewPrivate::from(RistrettoPrivate::from_random(&mut OsRng));
+            let spend_private =
+                SubaddressSpendPrivate::from(RistrettoPrivate::from_random(&mut OsRng));
+
+            keys.push((
+                SubaddressViewPublic::from(&view_private),
+                SubaddressSpendPublic::from(&spend_private),
+            ))
+            .unwrap();
+        }
+
+        let apdu = SubaddressRangeResp::new(random(), random(), keys);
+
+        let mut buff = [0u8; 256];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn subaddress_keys_batch_req_apdu() {
+        let apdu = SubaddressKeysBatchReq::new(random(), random(), SUBADDRESS_KEYS_BATCH_MAX as u8);
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn subaddress_keys_batch_resp_apdu() {
+        let mut keys = Vec::new();
+        for _i in 0..SUBADDRESS_KEYS_BATCH_MAX {
+            let view_private =
+                SubaddressViewPrivate::from(RistrettoPrivate::from_random(&mut OsRng));
+            let spend_private =
+                SubaddressSpendPrivate::from(RistrettoPrivate::from_random(&mut OsRng));
+
+            keys.push((SubaddressSpendPublic::from(&spend_private), view_private))
+                .unwrap();
+        }
+
+        let apdu = SubaddressKeysBatchResp::new(random(), random(), keys);
+
         let mut buff = [0u8; 256];
         encode_decode_apdu(&mut buff, &apdu);
     }
@@ -0,0 +1,669 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Compact encodings for TxOut summary APDUs.
+//!
+//! [TxSummaryAddTxOutCompact] and [TxSummaryAddTxOutUnblindingCompact] carry
+//! the same contents as [TxSummaryAddTxOut] and [TxSummaryAddTxOutUnblinding],
+//! omitting fields not indicated by the request flags and using LEB128
+//! encoded amounts, keeping these requests within the maximum APDU payload
+//! as fields are added. Omitted fields are zeroed when decoding.
+//!
+//! Compact requests share request digests and payload MACs with the standard
+//! encodings, and are only supported by devices reporting
+//! [Features::COMPACT_SUMMARY][crate::capabilities::Features::COMPACT_SUMMARY].
+
+use encdec::{DecodeOwned, Encode};
+use ledger_proto::ApduStatic;
+
+use super::{
+    summary::fog_url, AddTxOutFlags, AddTxOutUnblindingFlags, FogId, TxAuthenticated, TxSequenced,
+    TxSummaryAddTxOut, TxSummaryAddTxOutUnblinding,
+};
+use crate::{
+    digest::PAYLOAD_MAC_LEN,
+    helpers::{leb128, pri_key, pt, pub_key, scalar},
+    ApduError, Instruction, MOB_APDU_CLA,
+};
+
+/// Compact encoding for [TxSummaryAddTxOut], omitting the masked amount
+/// where `HAS_MASKED_AMOUNT` is not set.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     FLAGS     |    INDEX      |      SEQ      |   RESERVED    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |          MASKED_VALUE (optional, see HAS_MASKED_AMOUNT)       |
+/// |                        (u64, 8-byte)                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |        MASKED_TOKEN_ID (optional, see HAS_MASKED_AMOUNT)      |
+/// |                        (u64, 8-byte)                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /          COMMITMENT (optional, see HAS_MASKED_AMOUNT)         /
+/// /                 (32-byte Compressed Ristretto Point)          /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                        TXOUT_TARGET_KEY                       /
+/// /           (32-byte Compressed Ristretto Public Key)           /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                           PUBLIC_KEY                          /
+/// /           (32-byte Compressed Ristretto Public Key)           /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          PAYLOAD_MAC                          |
+/// |                            (8-byte)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+///
+/// Masked values are random so are not LEB128 encoded.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TxSummaryAddTxOutCompact(pub TxSummaryAddTxOut);
+
+impl ApduStatic for TxSummaryAddTxOutCompact {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::TxSummaryAddTxOutCompact as u8;
+}
+
+impl From<TxSummaryAddTxOut> for TxSummaryAddTxOutCompact {
+    fn from(a: TxSummaryAddTxOut) -> Self {
+        Self(a)
+    }
+}
+
+impl From<TxSummaryAddTxOutCompact> for TxSummaryAddTxOut {
+    fn from(a: TxSummaryAddTxOutCompact) -> Self {
+        a.0
+    }
+}
+
+impl TxSequenced for TxSummaryAddTxOutCompact {
+    fn seq(&self) -> u8 {
+        self.0.seq
+    }
+
+    fn with_seq(self, seq: u8) -> Self {
+        Self(self.0.with_seq(seq))
+    }
+}
+
+impl TxAuthenticated for TxSummaryAddTxOutCompact {
+    fn mac(&self) -> [u8; PAYLOAD_MAC_LEN] {
+        self.0.mac
+    }
+
+    fn with_mac(self, mac: [u8; PAYLOAD_MAC_LEN]) -> Self {
+        Self(self.0.with_mac(mac))
+    }
+
+    fn event_hash(&self) -> [u8; 32] {
+        self.0.hash()
+    }
+}
+
+impl Encode for TxSummaryAddTxOutCompact {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, ApduError> {
+        let masked_amount = match self.0.flags.contains(AddTxOutFlags::HAS_MASKED_AMOUNT) {
+            true => 8 + 8 + 32,
+            false => 0,
+        };
+
+        Ok(4 + masked_amount + 32 + 32 + PAYLOAD_MAC_LEN)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        let a = &self.0;
+
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < self.encode_len()? {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write header
+        buff[0] = a.flags.bits();
+        buff[1] = a.index;
+        buff[2] = a.seq;
+        buff[3] = a.reserved[0];
+        let mut index = 4;
+
+        // Write masked amount if available
+        if a.flags.contains(AddTxOutFlags::HAS_MASKED_AMOUNT) {
+            index += put(&mut buff[index..], &a.masked_value.to_le_bytes());
+            index += put(&mut buff[index..], &a.masked_token_id);
+            index += pt::enc(&a.commitment, &mut buff[index..])?;
+        }
+
+        // Write keys and MAC
+        index += pt::enc(&a.target_key, &mut buff[index..])?;
+        index += pt::enc(&a.public_key, &mut buff[index..])?;
+        index += put(&mut buff[index..], &a.mac);
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for TxSummaryAddTxOutCompact {
+    type Output = Self;
+
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), ApduError> {
+        // Check header length (MOB-06.7)
+        if buff.len() < 4 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let flags = AddTxOutFlags::from_bits_truncate(buff[0]);
+        let mut index = 4;
+
+        // Read masked amount if available, zeroed otherwise
+        let (masked_value, masked_token_id, commitment) =
+            match flags.contains(AddTxOutFlags::HAS_MASKED_AMOUNT) {
+                true => {
+                    let masked_value = u64::from_le_bytes(take(buff, &mut index)?);
+                    let masked_token_id = take(buff, &mut index)?;
+                    let (commitment, n) = pt::dec(&buff[index..])?;
+                    index += n;
+
+                    (masked_value, masked_token_id, commitment)
+                }
+                false => (0, [0u8; 8], Default::default()),
+            };
+
+        // Read keys and MAC
+        let (target_key, n) = pt::dec(&buff[index..])?;
+        index += n;
+        let (public_key, n) = pt::dec(&buff[index..])?;
+        index += n;
+        let mac = take(buff, &mut index)?;
+
+        let a = TxSummaryAddTxOut {
+            flags,
+            index: buff[1],
+            seq: buff[2],
+            reserved: [buff[3]],
+            masked_value,
+            masked_token_id,
+            commitment,
+            target_key,
+            public_key,
+            mac,
+        };
+
+        Ok((Self(a), index))
+    }
+}
+
+/// Compact encoding for [TxSummaryAddTxOutUnblinding], with LEB128 encoded
+/// amounts and optional fields included only where indicated by the
+/// request flags.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     FLAGS     |    INDEX      |    FOG_ID     |      SEQ      |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /              UNMASKED_VALUE (LEB128, 1 to 10 bytes)           /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /                 TOKEN_ID (LEB128, 1 to 10 bytes)              /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                            BLINDING                           /
+/// /                   (32-byte Ristretto Scalar)                  /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /      TARGET_SPEND_PUBLIC_KEY (optional, see HAS_ADDRESS)      /
+/// /                (32-byte Ristretto Public Key)                 /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /      TARGET_VIEW_PUBLIC_KEY (optional, see HAS_ADDRESS)       /
+/// /                 (32-byte Ristretto Public Key)                /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /       TXOUT_PRIVATE_KEY (optional, see HAS_PRIVATE_KEY)       /
+/// /          (32-byte Compressed Ristretto Private Key)           /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /    FOG_AUTHORITY_SIG (optional, see HAS_FOG_AUTHORITY_SIG)    /
+/// /               (64-byte Fog Authority Signature)               /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          PAYLOAD_MAC                          |
+/// |                            (8-byte)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /        FOG_URL (optional, see HAS_FOG_URL, as per             /
+/// /                 [TxSummaryAddTxOutUnblinding])                /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct TxSummaryAddTxOutUnblindingCompact(pub TxSummaryAddTxOutUnblinding);
+
+impl ApduStatic for TxSummaryAddTxOutUnblindingCompact {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::TxSummaryAddTxOutUnblindingCompact as u8;
+}
+
+impl From<TxSummaryAddTxOutUnblinding> for TxSummaryAddTxOutUnblindingCompact {
+    fn from(a: TxSummaryAddTxOutUnblinding) -> Self {
+        Self(a)
+    }
+}
+
+impl From<TxSummaryAddTxOutUnblindingCompact> for TxSummaryAddTxOutUnblinding {
+    fn from(a: TxSummaryAddTxOutUnblindingCompact) -> Self {
+        a.0
+    }
+}
+
+impl TxSequenced for TxSummaryAddTxOutUnblindingCompact {
+    fn seq(&self) -> u8 {
+        self.0.seq
+    }
+
+    fn with_seq(self, seq: u8) -> Self {
+        Self(self.0.with_seq(seq))
+    }
+}
+
+impl TxAuthenticated for TxSummaryAddTxOutUnblindingCompact {
+    fn mac(&self) -> [u8; PAYLOAD_MAC_LEN] {
+        self.0.mac
+    }
+
+    fn with_mac(self, mac: [u8; PAYLOAD_MAC_LEN]) -> Self {
+        Self(self.0.with_mac(mac))
+    }
+
+    fn event_hash(&self) -> [u8; 32] {
+        self.0.hash()
+    }
+}
+
+impl Encode for TxSummaryAddTxOutUnblindingCompact {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, ApduError> {
+        let a = &self.0;
+        let f = a.flags;
+
+        let mut n = 4 + leb128::enc_len(&a.unmasked_value)? + leb128::enc_len(&a.token_id)? + 32;
+
+        if f.contains(AddTxOutUnblindingFlags::HAS_ADDRESS) {
+            n += 32 + 32;
+        }
+        if f.contains(AddTxOutUnblindingFlags::HAS_PRIVATE_KEY) {
+            n += 32;
+        }
+        if f.contains(AddTxOutUnblindingFlags::HAS_FOG_AUTHORITY_SIG) {
+            n += 64;
+        }
+        n += PAYLOAD_MAC_LEN;
+        if f.contains(AddTxOutUnblindingFlags::HAS_FOG_URL) {
+            n += fog_url::enc_len(&a.fog_url)?;
+        }
+
+        Ok(n)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        let a = &self.0;
+        let f = a.flags;
+
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < self.encode_len()? {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write header
+        buff[0] = f.bits();
+        buff[1] = a.index;
+        buff[2] = a.fog_id as u8;
+        buff[3] = a.seq;
+        let mut index = 4;
+
+        // Write unmasked amount
+        index += leb128::enc(&a.unmasked_value, &mut buff[index..])?;
+        index += leb128::enc(&a.token_id, &mut buff[index..])?;
+        index += scalar::enc(&a.blinding, &mut buff[index..])?;
+
+        // Write optional fields
+        if f.contains(AddTxOutUnblindingFlags::HAS_ADDRESS) {
+            index += pub_key::enc(&a.address_spend_public, &mut buff[index..])?;
+            index += pub_key::enc(&a.address_view_public, &mut buff[index..])?;
+        }
+        if f.contains(AddTxOutUnblindingFlags::HAS_PRIVATE_KEY) {
+            index += pri_key::enc(&a.tx_private_key, &mut buff[index..])?;
+        }
+        if f.contains(AddTxOutUnblindingFlags::HAS_FOG_AUTHORITY_SIG) {
+            index += put(&mut buff[index..], &a.fog_authority_sig);
+        }
+
+        // Write MAC and trailing fog URL
+        index += put(&mut buff[index..], &a.mac);
+        if f.contains(AddTxOutUnblindingFlags::HAS_FOG_URL) {
+            index += fog_url::enc(&a.fog_url, &mut buff[index..])?;
+        }
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for TxSummaryAddTxOutUnblindingCompact {
+    type Output = Self;
+
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), ApduError> {
+        // Check header length (MOB-06.7)
+        if buff.len() < 4 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let flags = AddTxOutUnblindingFlags::from_bits_truncate(buff[0]);
+        let (fog_id, _) = FogId::decode_owned(&buff[2..])?;
+        let mut index = 4;
+
+        // Read unmasked amount
+        let (unmasked_value, n) = leb128::dec(&buff[index..])?;
+        index += n;
+        let (token_id, n) = leb128::dec(&buff[index..])?;
+        index += n;
+        let (blinding, n) = scalar::dec(&buff[index..])?;
+        index += n;
+
+        // Read optional fields, defaulting where omitted
+        let (address_spend_public, address_view_public) =
+            match flags.contains(AddTxOutUnblindingFlags::HAS_ADDRESS) {
+                true => {
+                    let (spend, n) = pub_key::dec(&buff[index..])?;
+                    index += n;
+                    let (view, n) = pub_key::dec(&buff[index..])?;
+                    index += n;
+                    (spend, view)
+                }
+                false => (Default::default(), Default::default()),
+            };
+
+        let tx_private_key = match flags.contains(AddTxOutUnblindingFlags::HAS_PRIVATE_KEY) {
+            true => {
+                let (k, n) = pri_key::dec(&buff[index..])?;
+                index += n;
+                k
+            }
+            false => Default::default(),
+        };
+
+        let fog_authority_sig = match flags.contains(AddTxOutUnblindingFlags::HAS_FOG_AUTHORITY_SIG)
+        {
+            true => take(buff, &mut index)?,
+            false => [0u8; 64],
+        };
+
+        // Read MAC and trailing fog URL
+        let mac = take(buff, &mut index)?;
+
+        let fog_url = match flags.contains(AddTxOutUnblindingFlags::HAS_FOG_URL) {
+            true => {
+                let (f, n) = fog_url::dec(&buff[index..])?;
+                index += n;
+                f
+            }
+            false => None,
+        };
+
+        let a = TxSummaryAddTxOutUnblinding {
+            flags,
+            index: buff[1],
+            fog_id,
+            seq: buff[3],
+            unmasked_value,
+            token_id,
+            blinding,
+            address_spend_public,
+            address_view_public,
+            tx_private_key,
+            fog_authority_sig,
+            mac,
+            fog_url,
+        };
+
+        Ok((Self(a), index))
+    }
+}
+
+/// Write bytes to a buffer (length checked by the caller), returning the written length
+fn put(buff: &mut [u8], d: &[u8]) -> usize {
+    buff[..d.len()].copy_from_slice(d);
+    d.len()
+}
+
+/// Read a fixed length array at the provided index, advancing the index
+fn take<const N: usize>(buff: &[u8], index: &mut usize) -> Result<[u8; N], ApduError> {
+    if buff.len() < *index + N {
+        return Err(ApduError::InvalidLength);
+    }
+
+    let mut d = [0u8; N];
+    d.copy_from_slice(&buff[*index..][..N]);
+    *index += N;
+
+    Ok(d)
+}
+
+/// CBOR encodings match the standard requests, as CBOR omits unset fields
+#[cfg(feature = "cbor")]
+mod cbor {
+    use super::*;
+    use crate::cbor::{
+        minicbor::{decode, encode, Decoder, Encoder},
+        CborDecode, CborEncode,
+    };
+
+    impl CborEncode for TxSummaryAddTxOutCompact {
+        fn encode_field<W: encode::Write>(
+            &self,
+            e: &mut Encoder<W>,
+        ) -> Result<(), encode::Error<W::Error>> {
+            self.0.encode_field(e)
+        }
+    }
+
+    impl<'b> CborDecode<'b> for TxSummaryAddTxOutCompact {
+        fn decode_field(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+            TxSummaryAddTxOut::decode_field(d).map(Self)
+        }
+    }
+
+    impl CborEncode for TxSummaryAddTxOutUnblindingCompact {
+        fn encode_field<W: encode::Write>(
+            &self,
+            e: &mut Encoder<W>,
+        ) -> Result<(), encode::Error<W::Error>> {
+            self.0.encode_field(e)
+        }
+    }
+
+    impl<'b> CborDecode<'b> for TxSummaryAddTxOutUnblindingCompact {
+        fn decode_field(d: &mut Decoder<'b>) -> Result<Self, decode::Error> {
+            TxSummaryAddTxOutUnblinding::decode_field(d).map(Self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use curve25519_dalek::ristretto::RistrettoPoint;
+    use mc_core::account::{PublicSubaddress, ShortAddressHash};
+    use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate, RistrettoPublic};
+    use mc_crypto_ring_signature::Scalar;
+    use mc_transaction_types::UnmaskedAmount;
+    use mc_util_from_random::FromRandom;
+    use rand::random;
+    use rand_core::OsRng;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    fn unblinding(
+        value: u64,
+        address: bool,
+        fog: Option<&str>,
+        tx_private_key: bool,
+    ) -> TxSummaryAddTxOutUnblinding {
+        let subaddress = PublicSubaddress {
+            view_public: RistrettoPublic::from_random(&mut OsRng {}).into(),
+            spend_public: RistrettoPublic::from_random(&mut OsRng {}).into(),
+        };
+
+        TxSummaryAddTxOutUnblinding::new(
+            random(),
+            &UnmaskedAmount {
+                value,
+                token_id: 0,
+                blinding: Scalar::random(&mut OsRng {}).into(),
+            },
+            match address {
+                true => Some((subaddress, ShortAddressHash::from(random::<[u8; 16]>()))),
+                false => None,
+            },
+            fog.map(|f| (f, [0xab; 64])),
+            match tx_private_key {
+                true => Some(RistrettoPrivate::from_random(&mut OsRng {}).into()),
+                false => None,
+            },
+        )
+        .unwrap()
+        .with_seq(random())
+        .with_mac(random())
+    }
+
+    #[test]
+    fn tx_summary_add_tx_out_compact() {
+        let target_key = RistrettoPublic::from_random(&mut OsRng {});
+        let public_key = RistrettoPublic::from_random(&mut OsRng {});
+        let commitment = RistrettoPoint::random(&mut OsRng {});
+
+        let mut apdu = TxSummaryAddTxOut {
+            flags: AddTxOutFlags::HAS_MASKED_AMOUNT,
+            index: random(),
+            seq: random(),
+            reserved: [0u8; 1],
+            masked_value: random(),
+            masked_token_id: random(),
+            commitment: commitment.compress().into(),
+            target_key: CompressedRistrettoPublic::from(&target_key),
+            public_key: CompressedRistrettoPublic::from(&public_key),
+            mac: random(),
+        };
+
+        let mut buff = [0u8; 256];
+
+        // Masked amounts are retained where present
+        let n = encode_decode_apdu(&mut buff, &TxSummaryAddTxOutCompact(apdu.clone()));
+        assert_eq!(n, apdu.encode_len().unwrap());
+
+        // And omitted otherwise
+        apdu.flags = AddTxOutFlags::ASSOC_INPUT_RULES;
+        apdu.masked_value = 0;
+        apdu.masked_token_id = [0u8; 8];
+        apdu.commitment = Default::default();
+
+        let c = TxSummaryAddTxOutCompact(apdu.clone());
+        let n = encode_decode_apdu(&mut buff, &c);
+        assert_eq!(n, apdu.encode_len().unwrap() - 48);
+
+        // Compact requests share request digests and MACs
+        assert_eq!(c.event_hash(), apdu.hash());
+        assert_eq!(c.mac(), apdu.mac);
+        assert_eq!(c.seq(), apdu.seq);
+    }
+
+    #[test]
+    fn tx_summary_add_tx_out_unblinding_compact() {
+        let tests = [
+            unblinding(0, false, None, false),
+            unblinding(1_000_000_000_000, true, None, false),
+            unblinding(u64::MAX, true, Some(FogId::MobMain.url()), true),
+            unblinding(1_000, true, Some("fog://fog.example.com:443"), false),
+        ];
+
+        for apdu in tests {
+            let c = TxSummaryAddTxOutUnblindingCompact(apdu.clone());
+
+            // Compact encodings are never larger than standard encodings
+            let n = c.encode_len().unwrap();
+            assert!(n <= apdu.encode_len().unwrap(), "{apdu:?}");
+
+            // Check round-trip (not via `encode_decode_apdu` as requests
+            // with fog URLs may exceed the maximum APDU payload)
+            let mut buff = [0u8; 512];
+            assert_eq!(c.encode(&mut buff).unwrap(), n);
+
+            let (d, m) = TxSummaryAddTxOutUnblindingCompact::decode_owned(&buff[..n]).unwrap();
+            assert_eq!(m, n);
+            assert_eq!(d, c);
+            assert_eq!(d.event_hash(), apdu.hash());
+
+            // Truncated requests are rejected
+            assert!(TxSummaryAddTxOutUnblindingCompact::decode_owned(&buff[..n - 1]).is_err());
+        }
+
+        // Fully populated requests without fog URLs fit within a single APDU
+        let apdu = unblinding(u64::MAX, true, Some(FogId::MobMain.url()), true);
+        let mut buff = [0u8; 256];
+        encode_decode_apdu(&mut buff, &TxSummaryAddTxOutUnblindingCompact(apdu));
+    }
+
+    #[test]
+    fn leb128_encode_decode() {
+        let tests: &[(u64, &[u8])] = &[
+            (0, &[0x00]),
+            (1, &[0x01]),
+            (127, &[0x7f]),
+            (128, &[0x80, 0x01]),
+            (300, &[0xac, 0x02]),
+            (
+                u64::MAX,
+                &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+            ),
+        ];
+
+        for (v, e) in tests {
+            let mut buff = [0u8; leb128::LEB128_MAX];
+
+            assert_eq!(leb128::enc_len(v).unwrap(), e.len());
+            assert_eq!(leb128::enc(v, &mut buff).unwrap(), e.len());
+            assert_eq!(&buff[..e.len()], *e);
+
+            assert_eq!(leb128::dec(e).unwrap(), (*v, e.len()));
+        }
+
+        // Non-canonical encodings are rejected
+        let r = leb128::dec(&[0x80, 0x00]);
+        assert!(matches!(r, Err(ApduError::InvalidEncoding)));
+
+        // Overflowing encodings are rejected
+        let r = leb128::dec(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]);
+        assert!(matches!(r, Err(ApduError::InvalidEncoding)));
+
+        // Truncated encodings are rejected
+        assert!(matches!(
+            leb128::dec(&[0x80]),
+            Err(ApduError::InvalidLength)
+        ));
+        assert!(matches!(leb128::dec(&[]), Err(ApduError::InvalidLength)));
+    }
+}
@@ -15,14 +15,41 @@ use crate::{
 const MEMO_PAYLOAD_NO_HMAC: usize = 48;
 const MEMO_HMAC_LEN: usize = 16;
 
+/// Memo payload length per [TxMemoSign] request
+pub const MEMO_CHUNK_LEN: usize = MEMO_PAYLOAD_NO_HMAC;
+
+/// Maximum memo payload length, streamed over multiple [TxMemoSign] requests
+pub const MEMO_PAYLOAD_MAX: usize = 5 * MEMO_CHUNK_LEN;
+
+bitflags::bitflags! {
+    /// Memo signing flags, used to stream payloads exceeding [MEMO_CHUNK_LEN]
+    pub struct TxMemoFlags: u8 {
+        /// Request continues a payload started by a prior request
+        const CONTINUATION = 1 << 0;
+        /// Further payload chunks follow this request
+        const MORE = 1 << 1;
+    }
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_bitflags!(TxMemoFlags, u8);
+
 /// Memo HMAC signing request
 ///
+/// Payloads exceeding [MEMO_CHUNK_LEN] bytes are streamed over a sequence of
+/// requests with matching headers, setting [TxMemoFlags::MORE] on all but the
+/// last chunk and [TxMemoFlags::CONTINUATION] on all but the first. All chunks
+/// other than the last must be full, and the device returns a [TxInfo][super::TxInfo]
+/// for each chunk until the final [TxMemoSig].
+///
+/// Flags are omitted from the encoding for single-request payloads.
+///
 /// ## Encoding:
 /// ```text
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |          MEMO_KIND            |  PAYLOAD_LEN  |   RESERVED    |
+/// |          MEMO_KIND            |  PAYLOAD_LEN  |      SEQ      |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                                                               |
 /// /                        SUBADDRESS_INDEX                       /
@@ -44,6 +71,8 @@ const MEMO_HMAC_LEN: usize = 16;
 /// /                      (48-byte memo body)                      /
 /// |                                                               |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  FLAGS (opt)  |                RESERVED (opt)                 |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
 #[derive(Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
@@ -52,11 +81,11 @@ pub struct TxMemoSign {
     #[encdec(with = "arr")]
     pub kind: [u8; 2],
 
-    /// Payload length, always 48 bytes (included for future expansion)
+    /// Payload length in this request, 48 bytes for all but the last chunk
     pub payload_len: u8,
 
-    /// Reserved for future use, must be 0 (ensures 32-bit alignment)
-    pub reserved: u8,
+    /// Request sequence number (see [TxSequenced][super::TxSequenced])
+    pub seq: u8,
 
     /// Signing subaddress index
     pub subaddress_index: u64,
@@ -72,8 +101,26 @@ pub struct TxMemoSign {
     /// Memo payload
     #[encdec(with = "arr")]
     pub payload: [u8; MEMO_PAYLOAD_NO_HMAC],
+
+    /// Payload chunking flags
+    #[encdec(with = "memo_flags")]
+    pub flags: TxMemoFlags,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxMemoSign {
+    kind,
+    payload_len,
+    seq,
+    subaddress_index,
+    tx_public_key,
+    target_view_public,
+    payload,
+    flags
+});
+
+crate::tx::tx_sequenced!(TxMemoSign);
+
 impl ApduStatic for TxMemoSign {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxMemoSign as u8;
@@ -92,13 +139,46 @@ impl TxMemoSign {
             subaddress_index,
             kind,
             payload_len: MEMO_PAYLOAD_NO_HMAC as u8,
-            reserved: 0,
+            seq: 0,
+            tx_public_key,
+            target_view_public: target.view_public_key(),
+            payload,
+            flags: TxMemoFlags::empty(),
+        }
+    }
+
+    /// Create a new memo signing request for a chunk of a streamed payload,
+    /// chunks are truncated to [MEMO_CHUNK_LEN]
+    pub fn new_chunk<T: RingCtAddress>(
+        subaddress_index: u64,
+        tx_public_key: TxOutPublic,
+        target: &T,
+        kind: [u8; 2],
+        chunk: &[u8],
+        flags: TxMemoFlags,
+    ) -> Self {
+        let n = chunk.len().min(MEMO_CHUNK_LEN);
+
+        let mut payload = [0u8; MEMO_CHUNK_LEN];
+        payload[..n].copy_from_slice(&chunk[..n]);
+
+        Self {
+            subaddress_index,
+            kind,
+            payload_len: n as u8,
+            seq: 0,
             tx_public_key,
             target_view_public: target.view_public_key(),
             payload,
+            flags,
         }
     }
 
+    /// Fetch the payload bytes carried by this request
+    pub fn payload(&self) -> &[u8] {
+        &self.payload[..(self.payload_len as usize).min(MEMO_CHUNK_LEN)]
+    }
+
     /// Compute hash for [TxMemoSign] object
     pub fn hash(&self) -> [u8; 32] {
         digest_tx_sign_memo(
@@ -106,11 +186,54 @@ impl TxMemoSign {
             &self.tx_public_key,
             &self.target_view_public,
             &self.kind,
-            &self.payload,
+            self.flags,
+            self.payload(),
         )
     }
 }
 
+/// encdec helper for trailing [TxMemoFlags], omitted where empty
+/// for compatibility with single-request memo payloads
+mod memo_flags {
+    use super::*;
+
+    pub fn enc(f: &TxMemoFlags, buff: &mut [u8]) -> Result<usize, ApduError> {
+        if f.is_empty() {
+            return Ok(0);
+        }
+
+        if buff.len() < 4 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        buff[0] = f.bits();
+        buff[1..4].fill(0);
+
+        Ok(4)
+    }
+
+    pub fn enc_len(f: &TxMemoFlags) -> Result<usize, ApduError> {
+        match f.is_empty() {
+            true => Ok(0),
+            false => Ok(4),
+        }
+    }
+
+    pub fn dec(buff: &[u8]) -> Result<(TxMemoFlags, usize), ApduError> {
+        if buff.is_empty() {
+            return Ok((TxMemoFlags::empty(), 0));
+        }
+
+        if buff.len() < 4 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let f = TxMemoFlags::from_bits(buff[0]).ok_or(ApduError::InvalidEncoding)?;
+
+        Ok((f, 4))
+    }
+}
+
 /// Memo signature response
 ///
 /// ## Encoding:
@@ -144,6 +267,14 @@ pub struct TxMemoSig {
     pub hmac: [u8; MEMO_HMAC_LEN],
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxMemoSig {
+    state,
+    value,
+    digest,
+    hmac
+});
+
 impl TxMemoSig {
     /// Create a new memo signature response
     pub fn new(state: TxState, value: u16, digest: Digest, hmac: [u8; MEMO_HMAC_LEN]) -> Self {
@@ -192,11 +323,47 @@ mod test {
         );
 
         let mut buff = [0u8; 256];
-        encode_decode_apdu(&mut buff, &apdu);
+        let n = encode_decode_apdu(&mut buff, &apdu);
+
+        // Flags are omitted for single-request payloads
+        assert_eq!(n, 124);
 
         // TODO: check values in buffer
     }
 
+    #[test]
+    fn encode_decode_memo_sign_chunk() {
+        let target = Account::new(
+            RistrettoPrivate::from_random(&mut OsRng {}).into(),
+            RistrettoPrivate::from_random(&mut OsRng {}).into(),
+        );
+        let tx_public_key = RistrettoPublic::from(&RistrettoPrivate::from_random(&mut OsRng {}));
+
+        let mut payload = [0u8; 2 * MEMO_CHUNK_LEN - 8];
+        OsRng {}.fill_bytes(&mut payload);
+
+        let chunks = [
+            (&payload[..MEMO_CHUNK_LEN], TxMemoFlags::MORE),
+            (&payload[MEMO_CHUNK_LEN..], TxMemoFlags::CONTINUATION),
+        ];
+
+        for (chunk, flags) in chunks {
+            let apdu = TxMemoSign::new_chunk(
+                DEFAULT_SUBADDRESS_INDEX,
+                tx_public_key.into(),
+                &target.subaddress(0),
+                [0, 1],
+                chunk,
+                flags,
+            );
+            assert_eq!(apdu.payload(), chunk);
+
+            let mut buff = [0u8; 256];
+            let n = encode_decode_apdu(&mut buff, &apdu);
+            assert_eq!(n, 128);
+        }
+    }
+
     #[test]
     fn encode_decode_memo_sig_resp() {
         let mut hmac = [0u8; MEMO_HMAC_LEN];
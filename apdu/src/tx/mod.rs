@@ -8,6 +8,8 @@ use encdec::{Decode, Encode};
 use ledger_proto::ApduStatic;
 
 use crate::{
+    digest::{payload_mac, PAYLOAD_MAC_LEN},
+    helpers::*,
     state::{Digest, TxState},
     ApduError, Instruction, MOB_APDU_CLA,
 };
@@ -24,11 +26,105 @@ pub use memo::*;
 mod summary;
 pub use summary::*;
 
+mod compact;
+pub use compact::*;
+
+mod report;
+pub use report::*;
+
+/// Sequence numbers for state-mutating transaction requests, allowing
+/// dropped or duplicated requests to be detected prior to the digest
+/// diverging.
+///
+/// Sequences start at 1 with [TxInit] and increment with each mutating
+/// request, wrapping from 255 to 1. A sequence of 0 marks an unsequenced
+/// request, these are not checked by the device.
+/// The last accepted sequence is echoed in [TxInfo] responses.
+pub trait TxSequenced: Sized {
+    /// Fetch the request sequence number
+    fn seq(&self) -> u8;
+
+    /// Set the request sequence number
+    fn with_seq(self, seq: u8) -> Self;
+}
+
+/// Compute the sequence number following `seq`, wrapping from 255 to 1
+pub const fn next_seq(seq: u8) -> u8 {
+    match seq {
+        u8::MAX => 1,
+        v => v + 1,
+    }
+}
+
+/// Helper to implement [TxSequenced] for requests with a `seq` field
+macro_rules! tx_sequenced {
+    ($t:ty) => {
+        impl crate::tx::TxSequenced for $t {
+            fn seq(&self) -> u8 {
+                self.seq
+            }
+
+            fn with_seq(mut self, seq: u8) -> Self {
+                self.seq = seq;
+                self
+            }
+        }
+    };
+}
+pub(crate) use tx_sequenced;
+
+/// Streamed transaction requests carrying a payload MAC
+/// (see [payload_mac][crate::digest::payload_mac]).
+///
+/// The MAC is keyed by the transaction digest prior to applying the request,
+/// allowing the device to reject corrupted or tampered payloads on receipt
+/// rather than at completion. MACs are only checked for sequenced requests.
+pub trait TxAuthenticated: TxSequenced {
+    /// Fetch the request payload MAC
+    fn mac(&self) -> [u8; PAYLOAD_MAC_LEN];
+
+    /// Set the request payload MAC
+    fn with_mac(self, mac: [u8; PAYLOAD_MAC_LEN]) -> Self;
+
+    /// Compute the event digest for the request
+    fn event_hash(&self) -> [u8; 32];
+
+    /// Compute and set the payload MAC using the current transaction digest
+    fn authenticate(self, digest: &Digest) -> Self {
+        let mac = payload_mac(digest.as_bytes(), self.seq(), &self.event_hash());
+        self.with_mac(mac)
+    }
+}
+
+/// Helper to implement [TxAuthenticated] for requests with a `mac` field
+macro_rules! tx_authenticated {
+    ($t:ty) => {
+        impl crate::tx::TxAuthenticated for $t {
+            fn mac(&self) -> [u8; crate::digest::PAYLOAD_MAC_LEN] {
+                self.mac
+            }
+
+            fn with_mac(mut self, mac: [u8; crate::digest::PAYLOAD_MAC_LEN]) -> Self {
+                self.mac = mac;
+                self
+            }
+
+            fn event_hash(&self) -> [u8; 32] {
+                self.hash()
+            }
+        }
+    };
+}
+pub(crate) use tx_authenticated;
+
 /// Transaction information request APDU
 #[derive(Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
 pub struct TxInfoReq;
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxInfoReq {});
+
 impl ApduStatic for TxInfoReq {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxGetInfo as u8;
@@ -40,36 +136,116 @@ impl ApduStatic for TxInfoReq {
 
 pub struct TxComplete;
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxComplete {});
+
 impl ApduStatic for TxComplete {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxComplete as u8;
 }
 
+/// Abort transaction operation (0 length APDU)
+///
+/// Cancels the in-progress transaction, clearing signing contexts and
+/// returning a [TxInfo] containing the final transaction digest with
+/// state [TxState::TxAborted] (distinct from on-device denial).
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct TxAbort;
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxAbort {});
+
+impl ApduStatic for TxAbort {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::TxAbort as u8;
+}
+
+/// Transaction completion response APDU.
+///
+/// Received in response to [TxComplete], contains the final transaction engine state and digest, along with a signed transaction digest binding the message and the key image, c_zero and response values for each ring signed (see [SignedTxDigest][crate::digest::SignedTxDigest]).
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   TX_STATE    |      SEQ      |             VALUE             |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                           TX_DIGEST                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                         SIGNED_DIGEST                         /
+/// /                           (32-byte)                           /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct TxCompleteResp {
+    /// Current transaction engine state
+    pub state: TxState,
+    /// Last accepted request sequence number (see [TxSequenced])
+    pub seq: u8,
+    /// Value associated with current state (zero otherwise)
+    pub value: u16,
+    /// Transaction state digest
+    pub digest: Digest,
+    /// Signed transaction digest
+    #[encdec(with = "arr")]
+    pub signed_digest: [u8; 32],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxCompleteResp {
+    state,
+    seq,
+    value,
+    digest,
+    signed_digest
+});
+
 /// Transaction information response APDU.
 ///
-/// Received in response to TX commands, contains the current transaction engine state, a value where relevant (ie. ring index when streaming rings), and a digest over operations in the transaction to mitigate state errors.
+/// Received in response to TX commands, contains the current transaction engine state, the last accepted request sequence number, a value where relevant (ie. ring index when streaming rings), a digest over operations in the transaction to mitigate state errors, and the time remaining before the transaction is aborted by the device watchdog.
 ///
 /// ## Encoding:
 /// ```text
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |            TX_STATE           |             VALUE             |
+/// |   TX_STATE    |      SEQ      |             VALUE             |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                           TX_DIGEST                           |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                           TIMEOUT_S                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
 #[derive(Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
 pub struct TxInfo {
     /// Current transaction engine state
     pub state: TxState,
+    /// Last accepted request sequence number (see [TxSequenced])
+    pub seq: u8,
     /// Value associated with current state (zero otherwise)
     pub value: u16,
     /// Transaction state digest
     pub digest: Digest,
+    /// Seconds remaining before the in-progress transaction is aborted
+    /// (zero where no transaction watchdog is running)
+    pub timeout_s: u32,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxInfo {
+    state,
+    seq,
+    value,
+    digest,
+    timeout_s
+});
+
 /// Header shared between TX response APDUs
 ///
 /// ## Encoding:
@@ -77,7 +253,7 @@ pub struct TxInfo {
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |            TX_STATE           |             VALUE             |
+/// |   TX_STATE    |      SEQ      |             VALUE             |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                                                               |
 /// |                           TX_DIGEST                           |
@@ -90,6 +266,8 @@ pub struct TxInfo {
 pub struct TxHeader {
     /// Current transaction engine state
     pub state: TxState,
+    /// Last accepted request sequence number (see [TxSequenced])
+    pub seq: u8,
     /// Value associated with current state (zero otherwise)
     pub value: u16,
     /// Transaction state digest
@@ -98,5 +276,44 @@ pub struct TxHeader {
 
 #[cfg(test)]
 mod test {
-    // TODO: test encode / decode
+    use rand::random;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn tx_info_apdu() {
+        let apdu = TxInfo {
+            state: TxState::Pending,
+            seq: random(),
+            value: random(),
+            digest: Digest::new(),
+            timeout_s: random(),
+        };
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn tx_complete_resp_apdu() {
+        let apdu = TxCompleteResp {
+            state: TxState::TxComplete,
+            seq: random(),
+            value: 0,
+            digest: Digest::new(),
+            signed_digest: random(),
+        };
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn tx_next_seq() {
+        assert_eq!(next_seq(0), 1);
+        assert_eq!(next_seq(1), 2);
+        assert_eq!(next_seq(254), 255);
+        assert_eq!(next_seq(255), 1);
+    }
 }
@@ -0,0 +1,332 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! TxSummary report APDUs, for reading back the unblinding report
+//! computed by the device following [TxSummaryBuild][super::TxSummaryBuild]
+
+use encdec::{Decode, DecodeOwned, Encode};
+use heapless::Vec;
+use num_enum::TryFromPrimitive;
+use strum::{Display, EnumIter};
+
+use crate::{helpers::*, ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
+
+/// Maximum number of report entries per response
+pub const REPORT_ENTRIES_MAX: usize = 4;
+
+/// Entity kind for report entries
+#[derive(Copy, Clone, PartialEq, Debug, Display, EnumIter, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum ReportEntity {
+    /// Balance change to an address we control
+    OurAddress = 0x01,
+    /// Balance change to another address
+    OtherAddress = 0x02,
+    /// Balance change to a swap counterparty (no address hash)
+    Swap = 0x03,
+}
+
+/// TxSummary report entry, describing a balance change
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |    ENTITY     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                           TOKEN_ID                            |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                             VALUE                             |
+/// |                           (8-bytes)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                         ADDRESS_HASH                          /
+/// /                           (16-byte)                           /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+///
+/// `ADDRESS_HASH` contains the `ShortAddressHash` for address entities,
+/// and is zeroed for [ReportEntity::Swap] entries.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReportEntry {
+    /// Entity kind
+    pub entity: ReportEntity,
+    /// Token ID
+    pub token_id: u64,
+    /// Value
+    pub value: u64,
+    /// Short address hash (zeroed for swaps)
+    pub address_hash: [u8; 16],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(ReportEntry {
+    entity,
+    token_id,
+    value,
+    address_hash
+});
+
+impl ReportEntry {
+    /// Encoded length of a [ReportEntry]
+    pub const LEN: usize = 36;
+}
+
+impl Encode for ReportEntry {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(Self::LEN)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        if buff.len() < Self::LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        buff[0] = self.entity as u8;
+        buff[1..4].fill(0);
+
+        let mut index = 4;
+        index += self.token_id.encode(&mut buff[index..])?;
+        index += self.value.encode(&mut buff[index..])?;
+        index += arr::enc(&self.address_hash, &mut buff[index..])?;
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for ReportEntry {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        if buff.len() < Self::LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let entity = ReportEntity::try_from(buff[0]).map_err(|_| ApduError::InvalidEncoding)?;
+
+        let mut index = 4;
+        let (token_id, n) = u64::decode_owned(&buff[index..])?;
+        index += n;
+        let (value, n) = u64::decode_owned(&buff[index..])?;
+        index += n;
+        let (address_hash, n) = arr::dec::<16>(&buff[index..])?;
+        index += n;
+
+        Ok((
+            Self {
+                entity,
+                token_id,
+                value,
+                address_hash,
+            },
+            index,
+        ))
+    }
+}
+
+/// Request TxSummary report entries, starting from entry `start`
+///
+/// Valid once the summary has been built, prior to ring signing.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     START     |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct TxSummaryReportReq {
+    /// Index of the first entry to return
+    pub start: u8,
+    /// Reserved for alignment
+    #[encdec(with = "arr")]
+    _reserved: [u8; 3],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxSummaryReportReq { start } reserved { _reserved });
+
+impl TxSummaryReportReq {
+    /// Create a new [TxSummaryReportReq] APDU
+    pub fn new(start: u8) -> Self {
+        Self {
+            start,
+            _reserved: [0u8; 3],
+        }
+    }
+}
+
+impl ApduStatic for TxSummaryReportReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::TxSummaryReport as u8;
+}
+
+/// TxSummary report response APDU
+///
+/// Contains up to [REPORT_ENTRIES_MAX] entries from `START`, along
+/// with the total number of entries held in the report.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     START     |   NUM_HELD    |  NUM_ENTRIES  |   RESERVED    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                REPORT_ENTRIES (36-byte * N)                   /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct TxSummaryReportResp {
+    /// Index of the first returned entry
+    pub start: u8,
+    /// Number of entries held in the report
+    pub num_held: u8,
+    /// Report entries
+    pub entries: Vec<ReportEntry, REPORT_ENTRIES_MAX>,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxSummaryReportResp {
+    start,
+    num_held,
+    entries
+});
+
+impl TxSummaryReportResp {
+    /// Create a new [TxSummaryReportResp] APDU
+    pub fn new(start: u8, num_held: u8, entries: &[ReportEntry]) -> Self {
+        let n = entries.len().min(REPORT_ENTRIES_MAX);
+
+        Self {
+            start,
+            num_held,
+            entries: Vec::from_slice(&entries[..n]).unwrap(),
+        }
+    }
+}
+
+impl Encode for TxSummaryReportResp {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(4 + self.entries.len() * ReportEntry::LEN)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < self.encode_len()? {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write header
+        buff[0] = self.start;
+        buff[1] = self.num_held;
+        buff[2] = self.entries.len() as u8;
+        buff[3] = 0;
+        let mut index = 4;
+
+        // Write entries
+        for e in &self.entries {
+            index += e.encode(&mut buff[index..])?;
+        }
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for TxSummaryReportResp {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        // Check header length (MOB-06.7)
+        if buff.len() < 4 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read header
+        let start = buff[0];
+        let num_held = buff[1];
+        let count = buff[2] as usize;
+        let mut index = 4;
+
+        // Check count and full buffer length (MOB-06.7)
+        if count > REPORT_ENTRIES_MAX || buff.len() < 4 + count * ReportEntry::LEN {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read entries
+        let mut entries = Vec::new();
+        for _i in 0..count {
+            let (e, n) = ReportEntry::decode_owned(&buff[index..])?;
+            index += n;
+
+            let _ = entries.push(e);
+        }
+
+        Ok((
+            Self {
+                start,
+                num_held,
+                entries,
+            },
+            index,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    fn random_entry(entity: ReportEntity) -> ReportEntry {
+        ReportEntry {
+            entity,
+            token_id: random(),
+            value: random(),
+            address_hash: random(),
+        }
+    }
+
+    #[test]
+    fn tx_summary_report_req_apdu() {
+        let apdu = TxSummaryReportReq::new(random());
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn tx_summary_report_resp_apdu() {
+        let entries = [
+            ReportEntity::OurAddress,
+            ReportEntity::OtherAddress,
+            ReportEntity::Swap,
+            ReportEntity::OtherAddress,
+        ]
+        .map(random_entry);
+
+        for n in 0..=REPORT_ENTRIES_MAX {
+            let apdu = TxSummaryReportResp::new(random(), random(), &entries[..n]);
+
+            let mut buff = [0u8; 256];
+            encode_decode_apdu(&mut buff, &apdu);
+        }
+    }
+}
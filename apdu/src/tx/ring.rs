@@ -1,7 +1,8 @@
 // Copyright (c) 2022-2023 The MobileCoin Foundation
 
 use curve25519_dalek::ristretto::CompressedRistretto;
-use encdec::{Decode, Encode};
+use encdec::{Decode, DecodeOwned, Encode};
+use heapless::Vec;
 
 use ledger_proto::ApduStatic;
 
@@ -9,7 +10,10 @@ use mc_crypto_keys::CompressedRistrettoPublic;
 use mc_crypto_ring_signature::{CompressedCommitment, KeyImage, ReducedTxOut, Scalar};
 
 use crate::{
-    digest::{digest_ring_add_txout, digest_ring_init, digest_ring_set_blinding, digest_ring_sign},
+    digest::{
+        digest_ring_add_txout, digest_ring_init, digest_ring_set_blinding, digest_ring_sign,
+        PAYLOAD_MAC_LEN,
+    },
     helpers::*,
     ApduError, Instruction, MOB_APDU_CLA,
 };
@@ -23,7 +27,7 @@ use super::TxOnetimeKey;
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |   RING_SIZE   |  REAL_INDEX   |           RESERVED            |
+/// |   RING_SIZE   |  REAL_INDEX   |     FLAGS     |      SEQ      |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                       SUBADDRESS_INDEX                        |
 /// |                                                               |
@@ -45,9 +49,8 @@ pub struct TxRingInit {
     /// Flags for ring init message
     pub flags: TxRingInitFlags,
 
-    /// Reserved for future use (ensures next field alignment)
-    #[encdec(with = "arr")]
-    reserved: [u8; 1],
+    /// Request sequence number (see [TxSequenced][super::TxSequenced])
+    pub seq: u8,
 
     /// Subaddress of real tx_in, used for onetime_private_key recovery
     pub subaddress_index: u64,
@@ -64,6 +67,20 @@ pub struct TxRingInit {
     pub onetime_private_key: TxOnetimeKey,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxRingInit {
+    ring_size,
+    real_index,
+    flags,
+    seq,
+    subaddress_index,
+    value,
+    token_id,
+    onetime_private_key,
+});
+
+crate::tx::tx_sequenced!(TxRingInit);
+
 impl ApduStatic for TxRingInit {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxRingInit as u8;
@@ -79,6 +96,9 @@ bitflags::bitflags! {
 
 crate::encdec_bitflags!(TxRingInitFlags);
 
+#[cfg(feature = "cbor")]
+crate::cbor_bitflags!(TxRingInitFlags, u8);
+
 impl TxRingInit {
     /// Create a new ring initialisation request
     pub fn new(
@@ -104,7 +124,7 @@ impl TxRingInit {
             ring_size,
             real_index,
             flags,
-            reserved: [0u8; 1],
+            seq: 0,
             subaddress_index,
             value,
             token_id,
@@ -140,6 +160,8 @@ impl TxRingInit {
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |      SEQ      |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                                                               |
 /// /                            BLINDING                           /
 /// /                   (32-byte Ristretto Scalar)                  /
@@ -150,17 +172,44 @@ impl TxRingInit {
 /// /                   (32-byte Ristretto Scalar)                  /
 /// |                                                               |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          PAYLOAD_MAC                          |
+/// |                            (8-byte)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
 #[derive(Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
 pub struct TxSetBlinding {
+    /// Request sequence number (see [TxSequenced][super::TxSequenced])
+    pub seq: u8,
+
+    /// Reserved for future use (maintains field alignment)
+    #[encdec(with = "arr")]
+    reserved: [u8; 3],
+
     #[encdec(with = "scalar")]
     pub blinding: Scalar,
 
     #[encdec(with = "scalar")]
     pub output_blinding: Scalar,
+
+    /// Request payload MAC (see [TxAuthenticated][super::TxAuthenticated])
+    #[encdec(with = "arr")]
+    pub mac: [u8; PAYLOAD_MAC_LEN],
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    TxSetBlinding {
+        seq,
+        blinding,
+        output_blinding,
+        mac,
+    } reserved { reserved }
+);
+
+crate::tx::tx_sequenced!(TxSetBlinding);
+crate::tx::tx_authenticated!(TxSetBlinding);
+
 impl ApduStatic for TxSetBlinding {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxSetBlinding as u8;
@@ -170,8 +219,11 @@ impl TxSetBlinding {
     /// Crete a new [TxSetBlinding] object
     pub fn new(blinding: Scalar, output_blinding: Scalar) -> Self {
         Self {
+            seq: 0,
+            reserved: [0u8; 3],
             blinding,
             output_blinding,
+            mac: [0u8; PAYLOAD_MAC_LEN],
         }
     }
 
@@ -188,7 +240,7 @@ impl TxSetBlinding {
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |   RING_INDEX  |                  RESERVED                     |
+/// |   RING_INDEX  |      SEQ      |           RESERVED            |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                                                               |
 /// /                        TXOUT_PUBLIC_KEY                       /
@@ -205,6 +257,9 @@ impl TxSetBlinding {
 /// /             (32-byte Compressed Ristretto Point)              /
 /// |                                                               |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          PAYLOAD_MAC                          |
+/// |                            (8-byte)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
 #[derive(Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
@@ -212,9 +267,12 @@ pub struct TxAddTxOut {
     /// The tx_out index in the ring
     pub ring_index: u8,
 
+    /// Request sequence number (see [TxSequenced][super::TxSequenced])
+    pub seq: u8,
+
     /// Reserved for future use (maintains field alignment)
     #[encdec(with = "arr")]
-    reserved: [u8; 3],
+    reserved: [u8; 2],
 
     /// The tx_out.public_key field
     #[encdec(with = "pt")]
@@ -227,8 +285,27 @@ pub struct TxAddTxOut {
     /// The tx_out.masked_amount.commitment field
     #[encdec(with = "pt")]
     pub commitment: CompressedRistrettoPublic,
+
+    /// Request payload MAC (see [TxAuthenticated][super::TxAuthenticated])
+    #[encdec(with = "arr")]
+    pub mac: [u8; PAYLOAD_MAC_LEN],
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    TxAddTxOut {
+        ring_index,
+        seq,
+        public_key,
+        target_key,
+        commitment,
+        mac,
+    } reserved { reserved }
+);
+
+crate::tx::tx_sequenced!(TxAddTxOut);
+crate::tx::tx_authenticated!(TxAddTxOut);
+
 impl ApduStatic for TxAddTxOut {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxAddTxOut as u8;
@@ -244,10 +321,12 @@ impl TxAddTxOut {
     ) -> Self {
         Self {
             ring_index,
-            reserved: [0u8; 3],
+            seq: 0,
+            reserved: [0u8; 2],
             public_key,
             target_key,
             commitment,
+            mac: [0u8; PAYLOAD_MAC_LEN],
         }
     }
 
@@ -268,10 +347,31 @@ impl TxAddTxOut {
     }
 }
 
-/// Execute signing operation for a completed ring (0 length APDU)
-#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+/// Execute signing operation for a completed ring
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |      SEQ      |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug, Default, Encode, Decode)]
 #[encdec(error = "ApduError")]
-pub struct TxRingSign;
+pub struct TxRingSign {
+    /// Request sequence number (see [TxSequenced][super::TxSequenced])
+    pub seq: u8,
+
+    /// Reserved for future use
+    #[encdec(with = "arr")]
+    reserved: [u8; 3],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxRingSign { seq } reserved { reserved });
+
+crate::tx::tx_sequenced!(TxRingSign);
 
 impl ApduStatic for TxRingSign {
     const CLA: u8 = MOB_APDU_CLA;
@@ -290,6 +390,9 @@ impl TxRingSign {
 #[encdec(error = "ApduError")]
 pub struct TxGetKeyImage {}
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxGetKeyImage {});
+
 impl ApduStatic for TxGetKeyImage {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxGetKeyImage as u8;
@@ -325,14 +428,21 @@ pub struct TxKeyImage {
     pub c_zero: Scalar,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxKeyImage { key_image, c_zero });
+
 /// Fetch a response scalar for a ring entry in a signed ring, returns [`TxResponse`] on success
 ///
+/// Setting [TxGetResponseFlags::PRIOR_RING] fetches responses for the
+/// previously completed ring, which are retained by the device on
+/// [TxRingInit] so these may be fetched while the next ring is loaded.
+///
 /// ## Encoding:
 /// ```text
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |   RING_INDEX  |                   RESERVED                    |
+/// |   RING_INDEX  |     FLAGS     |           RESERVED            |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
 #[derive(Clone, PartialEq, Debug, Encode, Decode)]
@@ -341,21 +451,50 @@ pub struct TxGetResponse {
     /// Index of response to be fetched
     pub ring_index: u8,
 
+    /// Response request flags
+    pub flags: TxGetResponseFlags,
+
     /// Reserved for future use
     #[encdec(with = "arr")]
-    reserved: [u8; 3],
+    reserved: [u8; 2],
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxGetResponse { ring_index, flags } reserved { reserved });
+
 impl TxGetResponse {
     /// Create a new TX response request
     pub fn new(ring_index: u8) -> Self {
         Self {
             ring_index,
-            reserved: [0u8; 3],
+            flags: TxGetResponseFlags::empty(),
+            reserved: [0u8; 2],
+        }
+    }
+
+    /// Create a new TX response request for the previously completed ring
+    pub fn prior(ring_index: u8) -> Self {
+        Self {
+            ring_index,
+            flags: TxGetResponseFlags::PRIOR_RING,
+            reserved: [0u8; 2],
         }
     }
 }
 
+bitflags::bitflags! {
+    /// TxGetResponse flags
+    pub struct TxGetResponseFlags: u8 {
+        /// Fetch response from the previously completed ring
+        const PRIOR_RING = 1 << 0;
+    }
+}
+
+crate::encdec_bitflags!(TxGetResponseFlags);
+
+#[cfg(feature = "cbor")]
+crate::cbor_bitflags!(TxGetResponseFlags, u8);
+
 impl ApduStatic for TxGetResponse {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxGetResponse as u8;
@@ -392,6 +531,9 @@ pub struct TxResponse {
     pub scalar: Scalar,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxResponse { ring_index, scalar } reserved { reserved });
+
 impl TxResponse {
     /// Create a new tx response response message
     pub fn new(ring_index: u8, scalar: Scalar) -> Self {
@@ -403,6 +545,167 @@ impl TxResponse {
     }
 }
 
+/// Maximum number of response scalars returned per [TxResponses] APDU
+pub const TX_RESPONSES_MAX: usize = 6;
+
+/// Fetch a range of response scalars from the signed ring, returns a
+/// [TxResponses] APDU.
+///
+/// `COUNT` must be between 1 and [TX_RESPONSES_MAX], flags are as
+/// per [TxGetResponse].
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     START     |     COUNT     |     FLAGS     |   RESERVED    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct TxGetResponses {
+    /// Index of the first response to be fetched
+    pub start: u8,
+
+    /// Number of responses to be fetched
+    pub count: u8,
+
+    /// Response request flags
+    pub flags: TxGetResponseFlags,
+
+    /// Reserved for future use
+    #[encdec(with = "arr")]
+    reserved: [u8; 1],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxGetResponses { start, count, flags } reserved { reserved });
+
+impl TxGetResponses {
+    /// Create a new request for `count` responses from `start`
+    pub fn new(start: u8, count: u8) -> Self {
+        Self {
+            start,
+            count,
+            flags: TxGetResponseFlags::empty(),
+            reserved: [0u8; 1],
+        }
+    }
+
+    /// Create a new request for `count` responses from `start` for the
+    /// previously completed ring
+    pub fn prior(start: u8, count: u8) -> Self {
+        Self {
+            start,
+            count,
+            flags: TxGetResponseFlags::PRIOR_RING,
+            reserved: [0u8; 1],
+        }
+    }
+}
+
+impl ApduStatic for TxGetResponses {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::TxGetResponses as u8;
+}
+
+/// TX Responses APDU, contains a contiguous range of response scalars
+/// from the signed ring, returned for [TxGetResponses] requests.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     START     |     COUNT     |           RESERVED            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                           RESPONSES                           /
+/// /              (COUNT x 32-byte Ristretto Scalars)              /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct TxResponses {
+    /// Index of the first returned response
+    pub start: u8,
+
+    /// Response scalars
+    pub scalars: Vec<Scalar, TX_RESPONSES_MAX>,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxResponses { start, scalars });
+
+impl TxResponses {
+    /// Create a new tx responses message
+    pub fn new(start: u8, scalars: Vec<Scalar, TX_RESPONSES_MAX>) -> Self {
+        Self { start, scalars }
+    }
+}
+
+impl Encode for TxResponses {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(4 + self.scalars.len() * 32)
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        let n = self.scalars.len();
+
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < 4 + n * 32 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write header
+        buff[0] = self.start;
+        buff[1] = n as u8;
+        buff[2..4].fill(0);
+
+        // Write response scalars
+        let mut index = 4;
+        for s in self.scalars.iter() {
+            index += scalar::enc(s, &mut buff[index..])?;
+        }
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for TxResponses {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        // Check header length (MOB-06.7)
+        if buff.len() < 4 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let start = buff[0];
+        let n = buff[1] as usize;
+
+        // Check count and full buffer length (MOB-06.7)
+        if n > TX_RESPONSES_MAX || buff.len() < 4 + n * 32 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Read response scalars (capacity checked above)
+        let mut index = 4;
+        let mut scalars = Vec::new();
+        for _i in 0..n {
+            let (s, l) = scalar::dec(&buff[index..])?;
+            let _ = scalars.push(s);
+            index += l;
+        }
+
+        Ok((Self { start, scalars }, index))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use mc_crypto_ring_signature::Scalar;
@@ -411,8 +714,16 @@ mod test {
     use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate, RistrettoPublic};
     use mc_util_from_random::FromRandom;
 
-    use super::{TxAddTxOut, TxRingInit, TxSetBlinding};
-    use crate::test::encode_decode_apdu;
+    use super::{
+        TxAddTxOut, TxGetResponse, TxGetResponses, TxResponses, TxRingInit, TxRingSign,
+        TxSetBlinding, TX_RESPONSES_MAX,
+    };
+    use crate::{
+        digest::payload_mac,
+        state::Digest,
+        test::encode_decode_apdu,
+        tx::{TxAuthenticated, TxSequenced},
+    };
 
     #[test]
     fn encode_decode_tx_ring_init() {
@@ -425,7 +736,8 @@ mod test {
             random(),
             random(),
             Some(onetime_private_key.into()),
-        );
+        )
+        .with_seq(random());
 
         let mut buff = [0u8; 256];
         encode_decode_apdu(&mut buff, &apdu);
@@ -435,12 +747,60 @@ mod test {
     fn encode_decode_tx_set_blinding() {
         let mut b = [0u8; 256];
 
-        let t = TxSetBlinding {
-            blinding: Scalar::random(&mut OsRng {}),
-            output_blinding: Scalar::random(&mut OsRng {}),
-        };
+        let t = TxSetBlinding::new(Scalar::random(&mut OsRng {}), Scalar::random(&mut OsRng {}))
+            .with_seq(random())
+            .with_mac(random());
+
+        let n = encode_decode_apdu(&mut b, &t);
+        assert_eq!(n, 76);
+    }
+
+    #[test]
+    fn encode_decode_tx_ring_sign() {
+        let mut b = [0u8; 256];
+
+        let apdu = TxRingSign::default().with_seq(random());
+
+        let n = encode_decode_apdu(&mut b, &apdu);
+        assert_eq!(n, 4);
+    }
+
+    #[test]
+    fn encode_decode_tx_get_response() {
+        let mut b = [0u8; 256];
+
+        for apdu in [TxGetResponse::new(random()), TxGetResponse::prior(random())] {
+            let n = encode_decode_apdu(&mut b, &apdu);
+            assert_eq!(n, 4);
+        }
+    }
+
+    #[test]
+    fn encode_decode_tx_get_responses() {
+        let mut b = [0u8; 256];
+
+        for apdu in [
+            TxGetResponses::new(random(), random()),
+            TxGetResponses::prior(random(), random()),
+        ] {
+            let n = encode_decode_apdu(&mut b, &apdu);
+            assert_eq!(n, 4);
+        }
+    }
 
-        encode_decode_apdu(&mut b, &t);
+    #[test]
+    fn encode_decode_tx_responses() {
+        let mut b = [0u8; 256];
+
+        for count in 0..=TX_RESPONSES_MAX {
+            let mut scalars = heapless::Vec::new();
+            for _i in 0..count {
+                let _ = scalars.push(Scalar::random(&mut OsRng {}));
+            }
+
+            let n = encode_decode_apdu(&mut b, &TxResponses::new(random(), scalars));
+            assert_eq!(n, 4 + count * 32);
+        }
     }
 
     #[test]
@@ -456,10 +816,45 @@ mod test {
             CompressedRistrettoPublic::from(RistrettoPublic::from(&public_key)),
             CompressedRistrettoPublic::from(RistrettoPublic::from(&target_key)),
             CompressedRistrettoPublic::from(RistrettoPublic::from(&commitment)),
-        );
+        )
+        .with_seq(random())
+        .with_mac(random());
 
         let n = encode_decode_apdu(&mut b, &apdu);
 
-        assert_eq!(n, 100);
+        assert_eq!(n, 108);
+    }
+
+    #[test]
+    fn add_txout_mac() {
+        let digest = Digest::from_random(&mut OsRng {});
+
+        let public_key = RistrettoPrivate::from_random(&mut OsRng {});
+        let target_key = RistrettoPrivate::from_random(&mut OsRng {});
+        let commitment = RistrettoPrivate::from_random(&mut OsRng {});
+
+        let apdu = TxAddTxOut::new(
+            1,
+            CompressedRistrettoPublic::from(RistrettoPublic::from(&public_key)),
+            CompressedRistrettoPublic::from(RistrettoPublic::from(&target_key)),
+            CompressedRistrettoPublic::from(RistrettoPublic::from(&commitment)),
+        )
+        .with_seq(3)
+        .authenticate(&digest);
+
+        // MAC binds digest, sequence and payload
+        assert_eq!(
+            apdu.mac,
+            payload_mac(digest.as_bytes(), 3, &apdu.hash()),
+            "payload MAC mismatch"
+        );
+
+        // Modified payloads produce distinct MACs
+        let mut tampered = apdu.clone();
+        tampered.ring_index = 2;
+        assert_ne!(apdu.mac, tampered.authenticate(&digest).mac);
+
+        let resequenced = apdu.clone().with_seq(4);
+        assert_ne!(apdu.mac, resequenced.authenticate(&digest).mac);
     }
 }
@@ -17,7 +17,7 @@ use mc_transaction_types::{Amount, UnmaskedAmount};
 use mc_transaction_types::{MaskedAmount, MaskedAmountV2};
 
 use crate::{
-    digest::{digest_tx_summary_add_output, digest_tx_summary_init},
+    digest::{digest_tx_summary_add_output, digest_tx_summary_init, PAYLOAD_MAC_LEN},
     helpers::*,
     tx::TxPrivateKey,
     ApduError, Instruction, MOB_APDU_CLA,
@@ -25,10 +25,35 @@ use crate::{
 
 /// Set TxSummary for a transaction
 ///
-///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |      SEQ      |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                            MESSAGE                            /
+/// /                           (32-byte)                           /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         BLOCK_VERSION                         |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          NUM_INPUTS                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          NUM_OUTPUTS                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
 #[derive(Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
 pub struct TxSummaryInit {
+    /// Request sequence number (see [TxSequenced][super::TxSequenced])
+    pub seq: u8,
+
+    /// Reserved for future use (maintains field alignment)
+    #[encdec(with = "arr")]
+    pub reserved: [u8; 3],
+
     #[encdec(with = "arr")]
     pub message: [u8; 32],
 
@@ -38,6 +63,19 @@ pub struct TxSummaryInit {
     pub num_outputs: u32,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    TxSummaryInit {
+        seq,
+        message,
+        block_version,
+        num_inputs,
+        num_outputs,
+    } reserved { reserved }
+);
+
+crate::tx::tx_sequenced!(TxSummaryInit);
+
 impl ApduStatic for TxSummaryInit {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxSummaryInit as u8;
@@ -47,6 +85,8 @@ impl TxSummaryInit {
     /// Create a new [TxSummaryInit] object
     pub fn new(message: [u8; 32], block_version: u32, num_inputs: u32, num_outputs: u32) -> Self {
         Self {
+            seq: 0,
+            reserved: [0u8; 3],
             message,
             block_version,
             num_inputs,
@@ -74,7 +114,7 @@ impl TxSummaryInit {
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |     FLAGS     |    INDEX      |            RESERVED           |
+/// |     FLAGS     |    INDEX      |      SEQ      |   RESERVED    |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                         MASKED_VALUE                          |
 /// |                        (u64, 8-byte)                          |
@@ -97,6 +137,9 @@ impl TxSummaryInit {
 /// /           (32-byte Compressed Ristretto Public Key)           /
 /// |                                                               |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          PAYLOAD_MAC                          |
+/// |                            (8-byte)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
 #[derive(Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
@@ -107,7 +150,10 @@ pub struct TxSummaryAddTxOut {
     /// TxOut index for idempotency
     pub index: u8,
 
-    pub reserved: [u8; 2],
+    /// Request sequence number (see [TxSequenced][super::TxSequenced])
+    pub seq: u8,
+
+    pub reserved: [u8; 1],
 
     /// MaskedAmountV2.masked_value
     pub masked_value: u64,
@@ -127,8 +173,30 @@ pub struct TxSummaryAddTxOut {
     /// The per output tx public key
     #[encdec(with = "pt")]
     pub public_key: CompressedRistrettoPublic,
+
+    /// Request payload MAC (see [TxAuthenticated][super::TxAuthenticated])
+    #[encdec(with = "arr")]
+    pub mac: [u8; PAYLOAD_MAC_LEN],
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    TxSummaryAddTxOut {
+        flags,
+        index,
+        seq,
+        masked_value,
+        masked_token_id,
+        commitment,
+        target_key,
+        public_key,
+        mac,
+    } reserved { reserved }
+);
+
+crate::tx::tx_sequenced!(TxSummaryAddTxOut);
+crate::tx::tx_authenticated!(TxSummaryAddTxOut);
+
 bitflags::bitflags! {
     /// TxSummaryAddTxOut flags
     pub struct AddTxOutFlags: u8 {
@@ -141,6 +209,9 @@ bitflags::bitflags! {
 
 crate::encdec_bitflags!(AddTxOutFlags);
 
+#[cfg(feature = "cbor")]
+crate::cbor_bitflags!(AddTxOutFlags, u8);
+
 impl ApduStatic for TxSummaryAddTxOut {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxSummaryAddTxOut as u8;
@@ -165,12 +236,14 @@ impl TxSummaryAddTxOut {
         let mut s = Self {
             flags,
             index,
-            reserved: [0u8; 2],
+            seq: 0,
+            reserved: [0u8; 1],
             masked_value: 0,
             masked_token_id: [0u8; 8],
             commitment: CompressedCommitment::default(),
             target_key,
             public_key,
+            mac: [0u8; PAYLOAD_MAC_LEN],
         };
 
         if let Some(m) = masked_amount {
@@ -227,7 +300,7 @@ impl TxSummaryAddTxOut {
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |     FLAGS     |    INDEX      |    FOG_ID     |   RESERVED    |
+/// |     FLAGS     |    INDEX      |    FOG_ID     |      SEQ      |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                        UNMASKED_VALUE                         |
 /// |                        (u64, 8-byte)                          |
@@ -265,7 +338,25 @@ impl TxSummaryAddTxOut {
 /// /               (64-byte Fog Authority Signature)               /
 /// |                                                               |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          PAYLOAD_MAC                          |
+/// |                            (8-byte)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  FOG_URL_LEN  |                                               |
+/// +-+-+-+-+-+-+-+-+                                               +
+/// /            FOG_SPKI_HASH (optional, see HAS_FOG_URL)          /
+/// /                      (32-byte SHA-256)                        /
+/// +                               +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                               |                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               +
+/// /              FOG_URL (optional, see HAS_FOG_URL)              /
+/// /                 (FOG_URL_LEN byte UTF-8 string)               /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
+///
+/// Fog deployments without a [FogId] are sent as a trailing [FogUrl], with
+/// `FOG_ID` set to [FogId::None]. As this may exceed the maximum APDU
+/// payload, these requests should be sent using the chunked exchange
+/// (see [crate::chunk]).
 #[derive(Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
 pub struct TxSummaryAddTxOutUnblinding {
@@ -278,7 +369,8 @@ pub struct TxSummaryAddTxOutUnblinding {
     /// Fog ID for address if available
     pub fog_id: FogId,
 
-    pub reserved: u8,
+    /// Request sequence number (see [TxSequenced][super::TxSequenced])
+    pub seq: u8,
 
     /// UnmaskedAmount.value
     pub unmasked_value: u64,
@@ -304,8 +396,36 @@ pub struct TxSummaryAddTxOutUnblinding {
 
     #[encdec(with = "arr")]
     pub fog_authority_sig: [u8; 64],
+
+    /// Request payload MAC (see [TxAuthenticated][super::TxAuthenticated])
+    #[encdec(with = "arr")]
+    pub mac: [u8; PAYLOAD_MAC_LEN],
+
+    /// Fog report URL for fog deployments without a [FogId]
+    #[encdec(with = "fog_url")]
+    pub fog_url: Option<FogUrl>,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxSummaryAddTxOutUnblinding {
+    flags,
+    index,
+    fog_id,
+    seq,
+    unmasked_value,
+    token_id,
+    blinding,
+    address_spend_public,
+    address_view_public,
+    tx_private_key,
+    fog_authority_sig,
+    mac,
+    fog_url,
+});
+
+crate::tx::tx_sequenced!(TxSummaryAddTxOutUnblinding);
+crate::tx::tx_authenticated!(TxSummaryAddTxOutUnblinding);
+
 bitflags::bitflags! {
     /// TxSummaryAddTxOut flags
     pub struct AddTxOutUnblindingFlags: u8 {
@@ -315,13 +435,100 @@ bitflags::bitflags! {
         const HAS_ADDRESS = 1 << 1;
         /// TxSummaryAddTxOutUnblinding contains fog authority signature
         const HAS_FOG_AUTHORITY_SIG = 1 << 2;
+        /// TxSummaryAddTxOutUnblinding contains a fog report URL
+        const HAS_FOG_URL = 1 << 3;
     }
 }
 
 crate::encdec_bitflags!(AddTxOutUnblindingFlags);
 
+#[cfg(feature = "cbor")]
+crate::cbor_bitflags!(AddTxOutUnblindingFlags, u8);
+
+/// Maximum fog report URL length for [FogUrl]
+pub const FOG_URL_MAX_LEN: usize = 64;
+
+/// Fog report URL and authority SPKI hash,
+/// for recipients using fog deployments without a [FogId]
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FogUrl {
+    /// SHA-256 hash of the fog authority SPKI (zeroed where unknown)
+    pub spki_hash: [u8; 32],
+    /// Fog report URL
+    pub url: heapless::String<FOG_URL_MAX_LEN>,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(FogUrl { spki_hash, url });
+
+impl FogUrl {
+    /// Create a new [FogUrl], failing for URLs exceeding [FOG_URL_MAX_LEN]
+    pub fn new(url: &str, spki_hash: [u8; 32]) -> Result<Self, ApduError> {
+        let mut s = heapless::String::new();
+        s.push_str(url).map_err(|_| ApduError::InvalidLength)?;
+
+        Ok(Self { spki_hash, url: s })
+    }
+
+    /// Fetch fog report URL
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+}
+
+/// Helpers for encoding optional trailing [FogUrl]s
+pub(super) mod fog_url {
+    use super::*;
+
+    pub fn enc_len(f: &Option<FogUrl>) -> Result<usize, ApduError> {
+        match f {
+            Some(f) => Ok(1 + 32 + f.url.len()),
+            None => Ok(0),
+        }
+    }
+
+    pub fn enc(f: &Option<FogUrl>, buff: &mut [u8]) -> Result<usize, ApduError> {
+        let f = match f {
+            Some(f) => f,
+            None => return Ok(0),
+        };
+
+        let n = f.url.len();
+        if buff.len() < 1 + 32 + n {
+            return Err(ApduError::InvalidLength);
+        }
+
+        buff[0] = n as u8;
+        buff[1..][..32].copy_from_slice(&f.spki_hash);
+        buff[33..][..n].copy_from_slice(f.url.as_bytes());
+
+        Ok(1 + 32 + n)
+    }
+
+    /// Decode an optional fog URL, absent where the buffer is empty
+    pub fn dec(buff: &[u8]) -> Result<(Option<FogUrl>, usize), ApduError> {
+        if buff.is_empty() {
+            return Ok((None, 0));
+        }
+
+        let n = buff[0] as usize;
+        if n > FOG_URL_MAX_LEN || buff.len() < 1 + 32 + n {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let mut spki_hash = [0u8; 32];
+        spki_hash.copy_from_slice(&buff[1..][..32]);
+
+        let url = core::str::from_utf8(&buff[33..][..n]).map_err(|_| ApduError::InvalidUtf8)?;
+
+        Ok((Some(FogUrl::new(url, spki_hash)?), 1 + 32 + n))
+    }
+}
+
 /// Fog identifier for resolving account information
 #[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum FogId {
     /// No fog associated with account
@@ -457,12 +664,16 @@ impl TxSummaryAddTxOutUnblinding {
 
         // Parse fog information
 
-        let (fog_id, fog_authority_sig) = match fog_info {
+        let (fog_id, fog_url, fog_authority_sig) = match fog_info {
             Some((url, sig)) => {
-                // Parse fog url
-                let fog_id = match FogId::from_str(url) {
-                    Ok(v) => v,
-                    Err(_) => return Err(ApduError::InvalidEncoding),
+                // Parse fog url, falling back to the full URL
+                // for fog deployments without a [FogId]
+                let (fog_id, fog_url) = match FogId::from_str(url) {
+                    Ok(v) => (v, None),
+                    Err(_) => {
+                        flags.insert(AddTxOutUnblindingFlags::HAS_FOG_URL);
+                        (FogId::None, Some(FogUrl::new(url, [0u8; 32])?))
+                    }
                 };
 
                 let mut fog_authority_sig = [0u8; 64];
@@ -470,9 +681,9 @@ impl TxSummaryAddTxOutUnblinding {
 
                 flags.insert(AddTxOutUnblindingFlags::HAS_FOG_AUTHORITY_SIG);
 
-                (fog_id, fog_authority_sig)
+                (fog_id, fog_url, fog_authority_sig)
             }
-            _ => (FogId::None, [0u8; 64]),
+            _ => (FogId::None, None, [0u8; 64]),
         };
 
         // Return object
@@ -480,7 +691,7 @@ impl TxSummaryAddTxOutUnblinding {
             flags,
             index,
             fog_id,
-            reserved: 0,
+            seq: 0,
             unmasked_value: unmasked_amount.value,
             token_id: unmasked_amount.token_id,
             blinding: unmasked_amount.blinding.into(),
@@ -488,9 +699,19 @@ impl TxSummaryAddTxOutUnblinding {
             address_view_public,
             tx_private_key: tx_private_key.map(Key::from).unwrap_or_default(),
             fog_authority_sig,
+            mac: [0u8; PAYLOAD_MAC_LEN],
+            fog_url,
         })
     }
 
+    /// Set the fog authority SPKI hash for requests containing a [FogUrl]
+    pub fn with_fog_spki_hash(mut self, spki_hash: [u8; 32]) -> Self {
+        if let Some(f) = &mut self.fog_url {
+            f.spki_hash = spki_hash;
+        }
+        self
+    }
+
     pub fn flags(&self) -> AddTxOutUnblindingFlags {
         self.flags
     }
@@ -534,11 +755,23 @@ impl TxSummaryAddTxOutUnblinding {
         Some((self.fog_id, self.fog_authority_sig))
     }
 
+    /// Fetch fog report URL for fog deployments without a [FogId]
+    pub fn fog_url(&self) -> Option<&FogUrl> {
+        match self.fog_info() {
+            Some(_) if self.flags.contains(AddTxOutUnblindingFlags::HAS_FOG_URL) => {
+                self.fog_url.as_ref()
+            }
+            _ => None,
+        }
+    }
+
     /// Compute hash for [TxSummaryAddTxOutUnblinding]
     pub fn hash(&self) -> [u8; 32] {
-        let fog_authority_sig = match self.fog_id {
-            FogId::None => None,
-            _ => Some(&self.fog_authority_sig[..]),
+        // Matches the engine event digest, which binds the fog authority
+        // signature where HAS_FOG_AUTHORITY_SIG is set
+        let fog_authority_sig = match self.fog_info() {
+            Some(_) => Some(&self.fog_authority_sig[..]),
+            None => None,
         };
 
         crate::digest::digest_tx_summary_add_output_unblinding(
@@ -546,6 +779,7 @@ impl TxSummaryAddTxOutUnblinding {
             self.address().as_ref(),
             self.tx_private_key(),
             fog_authority_sig,
+            self.fog_url(),
         )
     }
 }
@@ -557,7 +791,7 @@ impl TxSummaryAddTxOutUnblinding {
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |     FLAGS     |    INDEX      |            RESERVED           |
+/// |     FLAGS     |    INDEX      |      SEQ      |   RESERVED    |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                                                               |
 /// /                    PSEUDO_OUTPUT_COMMITMENT                   /
@@ -580,6 +814,9 @@ impl TxSummaryAddTxOutUnblinding {
 /// /                 (32-byte Input Rules Digest)                  /
 /// |                                                               |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                          PAYLOAD_MAC                          |
+/// |                            (8-byte)                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
 #[derive(Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
@@ -590,7 +827,10 @@ pub struct TxSummaryAddTxIn {
     /// TxIn index for idempotency
     pub index: u8,
 
-    pub reserved: [u8; 2],
+    /// Request sequence number (see [TxSequenced][super::TxSequenced])
+    pub seq: u8,
+
+    pub reserved: [u8; 1],
 
     #[encdec(with = "pt")]
     pub pseudo_output_commitment: CompressedCommitment,
@@ -608,8 +848,30 @@ pub struct TxSummaryAddTxIn {
     /// Digest of input rules per MCIP 52 if has_input_rules is set
     #[encdec(with = "arr")]
     pub input_rules_digest: [u8; 32],
+
+    /// Request payload MAC (see [TxAuthenticated][super::TxAuthenticated])
+    #[encdec(with = "arr")]
+    pub mac: [u8; PAYLOAD_MAC_LEN],
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(
+    TxSummaryAddTxIn {
+        flags,
+        index,
+        seq,
+        pseudo_output_commitment,
+        unmasked_value,
+        token_id,
+        blinding,
+        input_rules_digest,
+        mac,
+    } reserved { reserved }
+);
+
+crate::tx::tx_sequenced!(TxSummaryAddTxIn);
+crate::tx::tx_authenticated!(TxSummaryAddTxIn);
+
 bitflags::bitflags! {
     /// TxSummaryAddTxOut flags
     pub struct AddTxInFlags: u8 {
@@ -620,6 +882,9 @@ bitflags::bitflags! {
 
 crate::encdec_bitflags!(AddTxInFlags);
 
+#[cfg(feature = "cbor")]
+crate::cbor_bitflags!(AddTxInFlags, u8);
+
 impl ApduStatic for TxSummaryAddTxIn {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxSummaryAddTxIn as u8;
@@ -639,12 +904,14 @@ impl TxSummaryAddTxIn {
         Self {
             flags,
             index,
-            reserved: [0u8; 2],
+            seq: 0,
+            reserved: [0u8; 1],
             pseudo_output_commitment,
             unmasked_value: unmasked_amount.value,
             token_id: unmasked_amount.token_id,
             blinding: unmasked_amount.blinding.into(),
             input_rules_digest: input_rules_digest.copied().unwrap_or_default(),
+            mac: [0u8; PAYLOAD_MAC_LEN],
         }
     }
 
@@ -679,6 +946,8 @@ impl TxSummaryAddTxIn {
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |      SEQ      |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                           FEE_VALUE                           |
 /// |                         (u64, 8-byte)                         |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
@@ -688,13 +957,109 @@ impl TxSummaryAddTxIn {
 /// |                        TOMBSTONE_BLOCK                        |
 /// |                         (u64, 8-byte)                         |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                    CURRENT_BLOCK (optional)                   |
+/// |                         (u64, 8-byte)                         |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
-#[derive(Clone, PartialEq, Debug, Encode, Decode)]
-#[encdec(error = "ApduError")]
+///
+/// `CURRENT_BLOCK` allows the device to check the tombstone block is
+/// plausible, this is omitted where the current block index is unknown
+/// and is not included in the transaction digest.
+#[derive(Clone, PartialEq, Debug)]
 pub struct TxSummaryBuild {
+    /// Request sequence number (see [TxSequenced][super::TxSequenced])
+    pub seq: u8,
     pub fee_value: u64,
     pub fee_token_id: u64,
     pub tombstone_block: u64,
+    pub current_block: Option<u64>,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxSummaryBuild {
+    seq,
+    fee_value,
+    fee_token_id,
+    tombstone_block,
+    current_block
+});
+
+crate::tx::tx_sequenced!(TxSummaryBuild);
+
+impl Encode for TxSummaryBuild {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        match self.current_block {
+            Some(_) => Ok(36),
+            None => Ok(28),
+        }
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        // Check buffer length is viable
+        if buff.len() < self.encode_len()? {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write header
+        buff[0] = self.seq;
+        buff[1..4].fill(0);
+
+        let mut index = 4;
+        index += self.fee_value.encode(&mut buff[index..])?;
+        index += self.fee_token_id.encode(&mut buff[index..])?;
+        index += self.tombstone_block.encode(&mut buff[index..])?;
+
+        if let Some(b) = &self.current_block {
+            index += b.encode(&mut buff[index..])?;
+        }
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for TxSummaryBuild {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        // Check header length (MOB-06.7)
+        if buff.len() < 4 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let seq = buff[0];
+        let mut index = 4;
+
+        let (fee_value, n) = u64::decode_owned(&buff[index..])?;
+        index += n;
+        let (fee_token_id, n) = u64::decode_owned(&buff[index..])?;
+        index += n;
+        let (tombstone_block, n) = u64::decode_owned(&buff[index..])?;
+        index += n;
+
+        // Current block is optional for compatibility with earlier hosts
+        let current_block = match buff.len() >= index + 8 {
+            true => {
+                let (b, n) = u64::decode_owned(&buff[index..])?;
+                index += n;
+                Some(b)
+            }
+            false => None,
+        };
+
+        Ok((
+            Self {
+                seq,
+                fee_value,
+                fee_token_id,
+                tombstone_block,
+                current_block,
+            },
+            index,
+        ))
+    }
 }
 
 impl ApduStatic for TxSummaryBuild {
@@ -704,11 +1069,13 @@ impl ApduStatic for TxSummaryBuild {
 
 impl TxSummaryBuild {
     /// Create a new [TxSummaryBuild] APDU
-    pub fn new(fee: Amount, tombstone_block: u64) -> Self {
+    pub fn new(fee: Amount, tombstone_block: u64, current_block: Option<u64>) -> Self {
         Self {
+            seq: 0,
             fee_value: fee.value,
             fee_token_id: *fee.token_id,
             tombstone_block,
+            current_block,
         }
     }
 
@@ -733,19 +1100,19 @@ mod test {
     use rand_core::OsRng;
 
     use super::*;
-    use crate::test::encode_decode_apdu;
+    use crate::{
+        test::encode_decode_apdu,
+        tx::{TxAuthenticated, TxSequenced},
+    };
 
     #[test]
     fn tx_summary_init() {
-        let apdu = TxSummaryInit {
-            message: [0xfau8; 32],
-            block_version: random(),
-            num_inputs: random(),
-            num_outputs: random(),
-        };
+        let apdu =
+            TxSummaryInit::new([0xfau8; 32], random(), random(), random()).with_seq(random());
 
         let mut buff = [0u8; 256];
-        encode_decode_apdu(&mut buff, &apdu);
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 48);
     }
 
     #[test]
@@ -757,12 +1124,14 @@ mod test {
         let apdu = TxSummaryAddTxOut {
             flags: AddTxOutFlags::ASSOC_INPUT_RULES,
             index: random(),
-            reserved: [0u8; 2],
+            seq: random(),
+            reserved: [0u8; 1],
             masked_value: random(),
             masked_token_id: random(),
             commitment: commitment.compress().into(),
             target_key: CompressedRistrettoPublic::from(&public_key),
             public_key: CompressedRistrettoPublic::from(&target_key),
+            mac: random(),
         };
 
         let mut buff = [0u8; 256];
@@ -794,12 +1163,92 @@ mod test {
             Some((FogId::MobMain.url(), [0xab; 64])),
             Some(tx_private_key.into()),
         )
-        .unwrap();
+        .unwrap()
+        .with_seq(random())
+        .with_mac(random());
 
         let mut buff = [0u8; 256];
         encode_decode_apdu(&mut buff, &apdu);
     }
 
+    #[test]
+    fn tx_summary_add_tx_out_unblinding_fog_url() {
+        let view_public = RistrettoPublic::from_random(&mut OsRng {});
+        let spend_public = RistrettoPublic::from_random(&mut OsRng {});
+        let url = "fog://fog.example.com:443";
+
+        let amount = UnmaskedAmount {
+            value: random(),
+            token_id: random(),
+            blinding: Scalar::random(&mut OsRng {}).into(),
+        };
+        let address = PublicSubaddress {
+            view_public: view_public.into(),
+            spend_public: spend_public.into(),
+        };
+
+        let apdu = TxSummaryAddTxOutUnblinding::new(
+            random(),
+            &amount,
+            Some((
+                address.clone(),
+                ShortAddressHash::from(random::<[u8; 16]>()),
+            )),
+            Some((url, [0xab; 64])),
+            None,
+        )
+        .unwrap()
+        .with_fog_spki_hash([0xcd; 32])
+        .with_seq(random())
+        .with_mac(random());
+
+        // Unrecognised fogs are sent as a trailing URL
+        assert_eq!(apdu.fog_id, FogId::None);
+        assert!(apdu.flags().contains(AddTxOutUnblindingFlags::HAS_FOG_URL));
+        assert_eq!(apdu.fog_url().map(|f| f.url()), Some(url));
+        assert_eq!(apdu.fog_url().map(|f| f.spki_hash), Some([0xcd; 32]));
+
+        // Encoded requests exceed the maximum APDU payload so are
+        // not checked via `encode_decode_apdu`
+        let mut buff = [0u8; 512];
+        let n = apdu.encode(&mut buff).unwrap();
+        assert_eq!(n, 220 + 1 + 32 + url.len());
+        assert_eq!(apdu.encode_len().unwrap(), n);
+
+        let (decoded, m) = TxSummaryAddTxOutUnblinding::decode(&buff[..n]).unwrap();
+        assert_eq!(decoded, apdu);
+        assert_eq!(m, n);
+
+        // The fog URL is bound by the request hash
+        let known = TxSummaryAddTxOutUnblinding::new(
+            0,
+            &amount,
+            Some((
+                address.clone(),
+                ShortAddressHash::from(random::<[u8; 16]>()),
+            )),
+            Some((FogId::MobMain.url(), [0xab; 64])),
+            None,
+        )
+        .unwrap();
+        assert!(known.fog_url().is_none());
+        assert_ne!(apdu.hash(), known.hash());
+
+        // URLs exceeding the maximum length are rejected
+        let long = [b'a'; FOG_URL_MAX_LEN + 1];
+        let r = TxSummaryAddTxOutUnblinding::new(
+            0,
+            &amount,
+            Some((
+                address.clone(),
+                ShortAddressHash::from(random::<[u8; 16]>()),
+            )),
+            Some((core::str::from_utf8(&long).unwrap(), [0xab; 64])),
+            None,
+        );
+        assert!(matches!(r, Err(ApduError::InvalidLength)));
+    }
+
     #[test]
     fn tx_summary_add_tx_in() {
         let commitment = RistrettoPoint::random(&mut OsRng {});
@@ -807,12 +1256,14 @@ mod test {
         let apdu = TxSummaryAddTxIn {
             flags: AddTxInFlags::HAS_INPUT_RULES,
             index: random(),
-            reserved: [0u8; 2],
+            seq: random(),
+            reserved: [0u8; 1],
             unmasked_value: random(),
             token_id: random(),
             pseudo_output_commitment: commitment.compress().into(),
             blinding: Scalar::random(&mut OsRng),
             input_rules_digest: [0xfa; 32],
+            mac: random(),
         };
 
         let mut buff = [0u8; 256];
@@ -821,14 +1272,18 @@ mod test {
 
     #[test]
     fn tx_summary_build() {
-        let apdu = TxSummaryBuild {
-            fee_value: random(),
-            fee_token_id: random(),
-            tombstone_block: random(),
-        };
-
-        let mut buff = [0u8; 256];
-        encode_decode_apdu(&mut buff, &apdu);
+        for current_block in [None, Some(random())] {
+            let apdu = TxSummaryBuild {
+                seq: random(),
+                fee_value: random(),
+                fee_token_id: random(),
+                tombstone_block: random(),
+                current_block,
+            };
+
+            let mut buff = [0u8; 256];
+            encode_decode_apdu(&mut buff, &apdu);
+        }
     }
 
     /// Ensure [FogId] enum values match order of [FOG_ID] list
@@ -5,6 +5,7 @@ use ledger_proto::ApduStatic;
 
 use mc_core::keys::Key;
 use mc_crypto_keys::{RistrettoPrivate, RistrettoPublic};
+use mc_transaction_types::Amount;
 
 use crate::{helpers::*, ApduError, Instruction, MOB_APDU_CLA};
 
@@ -32,29 +33,51 @@ pub type TxPrivateKey = Key<Tx, Tx, RistrettoPrivate>;
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |   NUM_RINGS   |                    RESERVED                   |
+/// |   NUM_RINGS   |      SEQ      |           RESERVED            |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                        ACCOUNT_INDEX                          |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |                           TOKEN_ID                            |
+/// |                     FEE_VALUE (optional)                      |
+/// |                        (u64, 8-byte)                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                    FEE_TOKEN_ID (optional)                    |
+/// |                        (u64, 8-byte)                          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                   TOMBSTONE_BLOCK (optional)                  |
 /// |                        (u64, 8-byte)                          |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
 ///
+/// The optional [TxExpected] fee and tombstone block are those displayed
+/// to the user by the wallet, and are checked by the device against the
+/// values subsequently provided via [TxSummaryBuild][super::TxSummaryBuild].
+/// These are omitted by earlier hosts.
 #[derive(Clone, Debug, PartialEq, Encode, Decode)]
 #[encdec(error = "ApduError")]
 pub struct TxInit {
     /// Number of rings to be signed
     pub num_rings: u8,
 
+    /// Request sequence number (see [TxSequenced][super::TxSequenced])
+    pub seq: u8,
+
     /// Reserved for future use (maintains 32-bit field alignment)
     #[encdec(with = "arr")]
-    reserved: [u8; 3],
+    reserved: [u8; 2],
 
     /// Account index for SLIP-010 derivation
     pub account_index: u32,
+
+    /// Expected fee and tombstone block (optional)
+    #[encdec(with = "tx_expected")]
+    pub expected: Option<TxExpected>,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxInit { num_rings, seq, account_index, expected } reserved { reserved });
+
+crate::tx::tx_sequenced!(TxInit);
+
 impl ApduStatic for TxInit {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxInit as u8;
@@ -65,20 +88,108 @@ impl TxInit {
     pub fn new(account_index: u32, num_rings: u8) -> Self {
         Self {
             num_rings,
-            reserved: [0u8; 3],
+            seq: 0,
+            reserved: [0u8; 2],
             account_index,
+            expected: None,
+        }
+    }
+
+    /// Set the expected transaction fee and tombstone block,
+    /// checked by the device when the summary is built
+    pub fn with_expected(mut self, fee: Amount, tombstone_block: u64) -> Self {
+        self.expected = Some(TxExpected::new(fee, tombstone_block));
+        self
+    }
+}
+
+/// Expected transaction fee and tombstone block, provided with [TxInit]
+#[derive(Copy, Clone, Debug, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[encdec(error = "ApduError")]
+pub struct TxExpected {
+    /// Transaction fee value
+    pub fee_value: u64,
+
+    /// Transaction fee token id
+    pub fee_token_id: u64,
+
+    /// Transaction tombstone block
+    pub tombstone_block: u64,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxExpected {
+    fee_value,
+    fee_token_id,
+    tombstone_block
+});
+
+impl TxExpected {
+    /// Create a new [TxExpected] from a fee [Amount] and tombstone block
+    pub fn new(fee: Amount, tombstone_block: u64) -> Self {
+        Self {
+            fee_value: fee.value,
+            fee_token_id: *fee.token_id,
+            tombstone_block,
+        }
+    }
+
+    /// Fetch the expected fee [Amount]
+    pub fn fee(&self) -> Amount {
+        Amount {
+            value: self.fee_value,
+            token_id: self.fee_token_id.into(),
+        }
+    }
+}
+
+/// Helpers for encoding optional trailing [TxExpected] values
+mod tx_expected {
+    use encdec::{DecodeOwned, Encode};
+
+    use super::TxExpected;
+    use crate::ApduError;
+
+    pub fn enc_len(e: &Option<TxExpected>) -> Result<usize, ApduError> {
+        match e {
+            Some(e) => e.encode_len(),
+            None => Ok(0),
+        }
+    }
+
+    pub fn enc(e: &Option<TxExpected>, buff: &mut [u8]) -> Result<usize, ApduError> {
+        match e {
+            Some(e) => e.encode(buff),
+            None => Ok(0),
+        }
+    }
+
+    /// Decode optional expected values, absent where the buffer is empty
+    /// for compatibility with earlier hosts
+    pub fn dec(buff: &[u8]) -> Result<(Option<TxExpected>, usize), ApduError> {
+        if buff.is_empty() {
+            return Ok((None, 0));
         }
+
+        let (e, n) = TxExpected::decode_owned(buff)?;
+        Ok((Some(e), n))
     }
 }
 
+/// Maximum transaction message length accepted by the device
+pub const TX_MESSAGE_MAX: usize = 64;
+
 /// Set the message for the transaction
 ///
+/// `MESSAGE_LEN` must not exceed [TX_MESSAGE_MAX].
+///
 /// ## Encoding:
 /// ```text
 ///  0                   1                   2                   3
 ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |  MESSAGE_LEN  |                    RESERVED                   |
+/// |  MESSAGE_LEN  |      SEQ      |           RESERVED            |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                                                               |
 /// /                            MESSAGE                            /
@@ -88,10 +199,18 @@ impl TxInit {
 /// ```
 #[derive(Clone, PartialEq, Debug)]
 pub struct TxSetMessage<'a> {
+    /// Request sequence number (see [TxSequenced][super::TxSequenced])
+    pub seq: u8,
+
     /// `Message` for transaction, derived from prefix
     pub message: &'a [u8],
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(TxSetMessage<'a> { seq, message });
+
+crate::tx::tx_sequenced!(TxSetMessage<'_>);
+
 impl<'a> ApduStatic for TxSetMessage<'a> {
     const CLA: u8 = MOB_APDU_CLA;
     const INS: u8 = Instruction::TxSetMessage as u8;
@@ -99,7 +218,7 @@ impl<'a> ApduStatic for TxSetMessage<'a> {
 
 impl<'a> TxSetMessage<'a> {
     pub fn new(message: &'a [u8]) -> Self {
-        Self { message }
+        Self { seq: 0, message }
     }
 
     /// Compute hash of [TxSetMessage] object
@@ -116,12 +235,14 @@ impl<'a> Encode for TxSetMessage<'a> {
     fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
         let d = self.message;
 
-        if buff.len() < d.len() + 1 {
+        if buff.len() < d.len() + 4 {
             return Err(ApduError::InvalidLength);
         }
 
         let mut index = 0;
-        buff[index] = d.len() as u8;
+        buff[0] = d.len() as u8;
+        buff[1] = self.seq;
+        buff[2..4].fill(0);
         index += 4;
 
         buff[index..][..d.len()].copy_from_slice(d);
@@ -132,7 +253,7 @@ impl<'a> Encode for TxSetMessage<'a> {
 
     #[inline]
     fn encode_len(&self) -> Result<usize, ApduError> {
-        Ok(1 + self.message.len())
+        Ok(4 + self.message.len())
     }
 }
 
@@ -145,13 +266,19 @@ impl<'a> Decode<'a> for TxSetMessage<'a> {
     fn decode(buff: &'a [u8]) -> Result<(Self, usize), ApduError> {
         let mut index = 0;
 
+        // Check header and message lengths (MOB-06.7)
+        if buff.len() < 4 || buff.len() < 4 + buff[0] as usize {
+            return Err(ApduError::InvalidLength);
+        }
+
         let l = buff[index] as usize;
+        let seq = buff[1];
         index += 4;
 
         let message = &buff[index..][..l];
         index += l;
 
-        Ok((Self { message }, index))
+        Ok((Self { seq, message }, index))
     }
 }
 
@@ -159,16 +286,43 @@ impl<'a> Decode<'a> for TxSetMessage<'a> {
 mod test {
     use rand::random;
 
-    use super::TxInit;
-    use crate::test::encode_decode_apdu;
+    use encdec::Decode;
+    use mc_transaction_types::{Amount, TokenId};
+
+    use super::{TxInit, TxSetMessage};
+    use crate::{test::encode_decode_apdu, tx::TxSequenced};
 
     #[test]
     fn encode_decode_txinit() {
-        let apdu = TxInit::new(random(), random());
+        let apdu = TxInit::new(random(), random()).with_seq(random());
+
+        let mut buff = [0u8; 256];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 8);
+    }
+
+    #[test]
+    fn encode_decode_txinit_expected() {
+        let fee = Amount::new(400_000_000, TokenId::from(0));
+        let apdu = TxInit::new(random(), random())
+            .with_expected(fee, random())
+            .with_seq(random());
 
         let mut buff = [0u8; 256];
-        let _n = encode_decode_apdu(&mut buff, &apdu);
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 32);
+        assert_eq!(apdu.expected.map(|e| e.fee()), Some(fee));
+
+        // Truncated expected values are rejected
+        assert!(TxInit::decode(&buff[..n - 1]).is_err());
+    }
 
-        //assert_eq!(n, 48);
+    #[test]
+    fn encode_decode_tx_set_message() {
+        let apdu = TxSetMessage::new(&[0xab; 32]).with_seq(random());
+
+        let mut buff = [0u8; 256];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 36);
     }
 }
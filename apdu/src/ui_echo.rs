@@ -0,0 +1,199 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Test-only UI echo APDUs, returning the text currently rendered on screen
+//!
+//! These are only handled by firmware built with the `speculos` feature,
+//! allowing automated tests to assert approvers show the intended values
+//! without screenshot comparisons. Other builds return `NotSupported`.
+
+use encdec::{Decode, DecodeOwned, Encode};
+use heapless::{String, Vec};
+
+use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
+
+/// Maximum number of rendered lines returned in a [UiEchoResp]
+pub const UI_ECHO_LINES_MAX: usize = 8;
+
+/// Maximum length of each rendered line, longer lines are truncated
+pub const UI_ECHO_LINE_MAX: usize = 24;
+
+/// Request the text currently rendered on screen (0 length APDU)
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct UiEchoReq {}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(UiEchoReq {});
+
+impl ApduStatic for UiEchoReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::UiEcho as u8;
+}
+
+/// UI echo response, containing lines of text in the order they were
+/// rendered, the first line being the page title
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   NUM_LINES   |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   LINE_LEN    |             LINE (LINE_LEN bytes)             /
+/// +-+-+-+-+-+-+-+-+                                               /
+/// /            (repeated NUM_LINES times, UTF8 encoded)           /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct UiEchoResp {
+    /// Rendered lines, in draw order
+    pub lines: Vec<String<UI_ECHO_LINE_MAX>, UI_ECHO_LINES_MAX>,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(UiEchoResp { lines });
+
+impl UiEchoResp {
+    /// Create a new (empty) [UiEchoResp]
+    pub const fn new() -> Self {
+        Self { lines: Vec::new() }
+    }
+
+    /// Append a rendered line, truncating to [UI_ECHO_LINE_MAX] bytes
+    /// (on a character boundary) and ignoring lines beyond [UI_ECHO_LINES_MAX]
+    pub fn push(&mut self, line: &str) {
+        let mut n = line.len().min(UI_ECHO_LINE_MAX);
+        while !line.is_char_boundary(n) {
+            n -= 1;
+        }
+
+        let mut s = String::new();
+        let _ = s.push_str(&line[..n]);
+        let _ = self.lines.push(s);
+    }
+
+    /// Clear rendered lines
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Fetch the page title (first rendered line)
+    pub fn title(&self) -> Option<&str> {
+        self.lines.first().map(|l| l.as_str())
+    }
+
+    /// Check whether any rendered line contains the provided text
+    pub fn contains(&self, text: &str) -> bool {
+        self.lines.iter().any(|l| l.contains(text))
+    }
+}
+
+impl Encode for UiEchoResp {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(4 + self.lines.iter().map(|l| 1 + l.len()).sum::<usize>())
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < self.encode_len()? {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write header
+        buff[0] = self.lines.len() as u8;
+        buff[1..4].fill(0);
+        let mut index = 4;
+
+        // Write length-prefixed lines
+        for l in &self.lines {
+            buff[index] = l.len() as u8;
+            buff[index + 1..][..l.len()].copy_from_slice(l.as_bytes());
+            index += 1 + l.len();
+        }
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for UiEchoResp {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        // Check header length (MOB-06.7)
+        if buff.len() < 4 {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let num_lines = buff[0] as usize;
+        if num_lines > UI_ECHO_LINES_MAX {
+            return Err(ApduError::InvalidLength);
+        }
+
+        let mut lines = Vec::new();
+        let mut index = 4;
+
+        for _i in 0..num_lines {
+            // Check line header and length (MOB-06.7)
+            if buff.len() < index + 1 {
+                return Err(ApduError::InvalidLength);
+            }
+            let n = buff[index] as usize;
+            if n > UI_ECHO_LINE_MAX || buff.len() < index + 1 + n {
+                return Err(ApduError::InvalidLength);
+            }
+
+            let s = core::str::from_utf8(&buff[index + 1..][..n])
+                .map_err(|_| ApduError::InvalidUtf8)?;
+
+            let mut l = String::new();
+            l.push_str(s).map_err(|_| ApduError::InvalidLength)?;
+            lines.push(l).map_err(|_| ApduError::InvalidLength)?;
+
+            index += 1 + n;
+        }
+
+        Ok((Self { lines }, index))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::encode_decode_apdu;
+
+    #[test]
+    fn ui_echo_req_apdu() {
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &UiEchoReq {});
+        assert_eq!(n, 0);
+    }
+
+    #[test]
+    fn ui_echo_resp_apdu() {
+        let mut r = UiEchoResp::new();
+        r.push("Send  (1/2)");
+        r.push("1.5 MOB");
+        r.push("abcdefgh...01234567");
+
+        let mut buff = [0u8; 256];
+        let n = encode_decode_apdu(&mut buff, &r);
+        assert_eq!(n, 4 + 12 + 8 + 20);
+
+        assert_eq!(r.title(), Some("Send  (1/2)"));
+        assert!(r.contains("1.5 MOB"));
+
+        // Maximum length responses fit within a single APDU
+        let mut r = UiEchoResp::new();
+        for _i in 0..UI_ECHO_LINES_MAX + 1 {
+            r.push("Allow the connected application to retrieve");
+        }
+        assert_eq!(r.lines.len(), UI_ECHO_LINES_MAX);
+        assert_eq!(r.lines[0].len(), UI_ECHO_LINE_MAX);
+
+        encode_decode_apdu(&mut buff, &r);
+    }
+}
@@ -2,17 +2,24 @@
 
 //! Wallet key APDUs, for fetching root account keys
 
-use encdec::{Decode, Encode};
+use encdec::{Decode, DecodeOwned, Encode};
 
 use mc_core::keys::{RootSpendPublic, RootViewPrivate};
 
 use super::{ApduError, ApduStatic, Instruction, MOB_APDU_CLA};
-use crate::helpers::*;
+use crate::{
+    algorithm::Algorithm,
+    attestation::{trailing, KeyAttestation, KEY_ATTESTATION_CHALLENGE_LEN},
+    helpers::*,
+    key_path::KeyPath,
+};
 
 /// Wallet key request APDU.
 ///
 /// Requests root / account keys for SLIP-0010 derived account.
 ///
+/// `ALGORITHM` must be [Algorithm::Ristretto255].
+///
 /// ## Encoding:
 /// ```text
 ///  0                   1                   2                   3
@@ -20,18 +27,32 @@ use crate::helpers::*;
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                         WALLET_INDEX                          |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   ALGORITHM   |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
 #[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
 #[encdec(error = "ApduError")]
 pub struct WalletKeyReq {
     /// SLIP-0010 account index
     pub account_index: u32,
+    /// Key algorithm
+    #[encdec(with = "ristretto_alg")]
+    pub algorithm: Algorithm,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(WalletKeyReq {
+    account_index,
+    algorithm: ristretto_alg
+});
+
 impl WalletKeyReq {
     /// Create a new [WalletKeyReq] APDU
     pub fn new(account_index: u32) -> Self {
-        Self { account_index }
+        Self {
+            account_index,
+            algorithm: Algorithm::Ristretto255,
+        }
     }
 }
 
@@ -40,9 +61,13 @@ impl ApduStatic for WalletKeyReq {
     const INS: u8 = Instruction::GetWalletKeys as u8;
 }
 
-/// Wallet key response APDU
+/// Attested wallet key request APDU.
+///
+/// Requests root / account keys for SLIP-0010 derived account, returning
+/// a [WalletKeyResp] including a [KeyAttestation] over the returned keys
+/// and provided `CHALLENGE` (see [crate::attestation]).
 ///
-/// Contains root view private and spend public keys for application use.
+/// `ALGORITHM` must be [Algorithm::Ristretto255].
 ///
 /// ## Encoding:
 /// ```text
@@ -51,8 +76,128 @@ impl ApduStatic for WalletKeyReq {
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                         WALLET_INDEX                          |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |                       SUBADDRESS_INDEX                        |
-/// |                         (8-byte u64)                          |
+/// |   ALGORITHM   |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// /                           CHALLENGE                           /
+/// /                     (32-byte host challenge)                  /
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct WalletKeyAttestReq {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// Key algorithm
+    #[encdec(with = "ristretto_alg")]
+    pub algorithm: Algorithm,
+    /// Host challenge, bound by the attestation signature
+    #[encdec(with = "arr")]
+    pub challenge: [u8; KEY_ATTESTATION_CHALLENGE_LEN],
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(WalletKeyAttestReq {
+    account_index,
+    algorithm: ristretto_alg,
+    challenge
+});
+
+impl WalletKeyAttestReq {
+    /// Create a new [WalletKeyAttestReq] APDU
+    pub fn new(account_index: u32, challenge: [u8; KEY_ATTESTATION_CHALLENGE_LEN]) -> Self {
+        Self {
+            account_index,
+            algorithm: Algorithm::Ristretto255,
+            challenge,
+        }
+    }
+}
+
+impl ApduStatic for WalletKeyAttestReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetWalletKeysAttested as u8;
+}
+
+/// Wallet key request APDU using an explicit derivation path.
+///
+/// Requests root / account keys for the account at the provided SLIP-0010
+/// `PATH` (see [crate::key_path]), in place of the default
+/// `m/44'/866'/account'` derivation. The returned [WalletKeyResp]
+/// `account_index` is set to the final (unhardened) path element.
+///
+/// `ALGORITHM` must be [Algorithm::Ristretto255].
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /                             PATH                              /
+/// /                  (24-byte KeyPath encoding)                   /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   ALGORITHM   |                   RESERVED                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct WalletKeyPathReq {
+    /// SLIP-0010 derivation path
+    pub path: KeyPath,
+    /// Key algorithm
+    #[encdec(with = "ristretto_alg")]
+    pub algorithm: Algorithm,
+}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(WalletKeyPathReq {
+    path,
+    algorithm: ristretto_alg
+});
+
+impl WalletKeyPathReq {
+    /// Create a new [WalletKeyPathReq] APDU
+    pub fn new(path: KeyPath) -> Self {
+        Self {
+            path,
+            algorithm: Algorithm::Ristretto255,
+        }
+    }
+}
+
+impl ApduStatic for WalletKeyPathReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::GetWalletKeysPath as u8;
+}
+
+/// Invalidate accounts request APDU (0 length APDU)
+///
+/// Requests the device zeroize account roots cached during
+/// the current session, returns a [TxInfo][crate::tx::TxInfo] response.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Encode, Decode)]
+#[encdec(error = "ApduError")]
+pub struct InvalidateAccountsReq {}
+
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(InvalidateAccountsReq {});
+
+impl ApduStatic for InvalidateAccountsReq {
+    const CLA: u8 = MOB_APDU_CLA;
+    const INS: u8 = Instruction::InvalidateAccounts as u8;
+}
+
+/// Wallet key response APDU
+///
+/// Contains root view private and spend public keys for application use,
+/// followed by a [KeyAttestation] for [WalletKeyAttestReq] requests.
+///
+/// ## Encoding:
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                         WALLET_INDEX                          |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// |                                                               |
 /// /                      ROOT_VIEW_PRIVATE_KEY                    /
@@ -64,20 +209,30 @@ impl ApduStatic for WalletKeyReq {
 /// /                 (32-byte Ristretto Public Key)                /
 /// |                                                               |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// /                    ATTESTATION (OPTIONAL)                     /
+/// /                (96-byte KeyAttestation, if present)           /
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
-#[derive(Clone, PartialEq, Debug, Encode, Decode)]
-#[encdec(error = "ApduError")]
+#[derive(Clone, PartialEq, Debug)]
 pub struct WalletKeyResp {
     /// SLIP-0010 account index
     pub account_index: u32,
     /// View Private Key
-    #[encdec(with = "pri_key")]
     pub view_private: RootViewPrivate,
     /// Spend public key
-    #[encdec(with = "pub_key")]
     pub spend_public: RootSpendPublic,
+    /// Key attestation, for [WalletKeyAttestReq] requests
+    pub attestation: Option<KeyAttestation>,
 }
 
+#[cfg(feature = "cbor")]
+crate::cbor_struct!(WalletKeyResp {
+    account_index,
+    view_private,
+    spend_public,
+    attestation
+});
+
 impl WalletKeyResp {
     /// Create a new [`WalletKeyResp`] APDU
     pub fn new(
@@ -89,7 +244,77 @@ impl WalletKeyResp {
             account_index,
             view_private,
             spend_public,
+            attestation: None,
+        }
+    }
+
+    /// Attach a [KeyAttestation] to a [`WalletKeyResp`]
+    pub fn with_attestation(mut self, attestation: KeyAttestation) -> Self {
+        self.attestation = Some(attestation);
+        self
+    }
+}
+
+impl Encode for WalletKeyResp {
+    type Error = ApduError;
+
+    fn encode_len(&self) -> Result<usize, Self::Error> {
+        Ok(68 + trailing::enc_len(&self.attestation))
+    }
+
+    fn encode(&self, buff: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut index = 0;
+
+        // Check buffer length is viable (MOB-06.6)
+        if buff.len() < self.encode_len()? {
+            return Err(ApduError::InvalidLength);
+        }
+
+        // Write keys
+        index += self.account_index.encode(&mut buff[index..])?;
+        index += pri_key::enc(&self.view_private, &mut buff[index..])?;
+        index += pub_key::enc(&self.spend_public, &mut buff[index..])?;
+
+        // Write attestation if present
+        index += trailing::enc(&self.attestation, &mut buff[index..])?;
+
+        Ok(index)
+    }
+}
+
+impl DecodeOwned for WalletKeyResp {
+    type Output = Self;
+    type Error = ApduError;
+
+    fn decode_owned(buff: &[u8]) -> Result<(Self::Output, usize), Self::Error> {
+        let mut index = 0;
+
+        // Check minimum length (MOB-06.7)
+        if buff.len() < 68 {
+            return Err(ApduError::InvalidLength);
         }
+
+        // Read keys
+        let (account_index, n) = u32::decode_owned(&buff[index..])?;
+        index += n;
+        let (view_private, n) = pri_key::dec(&buff[index..])?;
+        index += n;
+        let (spend_public, n) = pub_key::dec(&buff[index..])?;
+        index += n;
+
+        // Read attestation if present
+        let (attestation, n) = trailing::dec(&buff[index..])?;
+        index += n;
+
+        Ok((
+            Self {
+                account_index,
+                view_private,
+                spend_public,
+                attestation,
+            },
+            index,
+        ))
     }
 }
 
@@ -104,7 +329,11 @@ mod test {
     use rand_core::OsRng;
 
     use super::*;
-    use crate::test::encode_decode_apdu;
+    use crate::{
+        attestation::KEY_ATTESTATION_LEN,
+        key_path::{KEY_PATH_HARDENED, KEY_PATH_PREFIX},
+        test::encode_decode_apdu,
+    };
 
     #[test]
     fn wallet_keys_get_apdu() {
@@ -114,6 +343,27 @@ mod test {
         encode_decode_apdu(&mut buff, &apdu);
     }
 
+    #[test]
+    fn invalidate_accounts_apdu() {
+        let apdu = InvalidateAccountsReq {};
+
+        let mut buff = [0u8; 128];
+        encode_decode_apdu(&mut buff, &apdu);
+    }
+
+    #[test]
+    fn wallet_keys_get_apdu_algorithm() {
+        let apdu = WalletKeyReq {
+            account_index: random(),
+            algorithm: Algorithm::Ed25519,
+        };
+
+        // Unsupported algorithms are rejected on decode
+        let mut buff = [0u8; 128];
+        let n = apdu.encode(&mut buff).unwrap();
+        assert!(WalletKeyReq::decode(&buff[..n]).is_err());
+    }
+
     #[test]
     fn wallet_keys_ans_apdu() {
         let (view_private, spend_private) = (
@@ -128,6 +378,36 @@ mod test {
         );
 
         let mut buff = [0u8; 256];
-        encode_decode_apdu(&mut buff, &apdu);
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 68);
+
+        // With attestation
+        let apdu = apdu.with_attestation(KeyAttestation::new(random(), [0xab; 64]));
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 68 + KEY_ATTESTATION_LEN);
+    }
+
+    #[test]
+    fn wallet_keys_attest_apdu() {
+        let apdu = WalletKeyAttestReq::new(random(), random());
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 40);
+    }
+
+    #[test]
+    fn wallet_keys_path_apdu() {
+        let path = [
+            KEY_PATH_PREFIX[0],
+            KEY_PATH_PREFIX[1],
+            random::<u32>() | KEY_PATH_HARDENED,
+            random::<u32>() | KEY_PATH_HARDENED,
+        ];
+        let apdu = WalletKeyPathReq::new(KeyPath::new(&path).unwrap());
+
+        let mut buff = [0u8; 128];
+        let n = encode_decode_apdu(&mut buff, &apdu);
+        assert_eq!(n, 28);
     }
 }
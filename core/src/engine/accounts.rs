@@ -0,0 +1,139 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Derived account cache, retaining account roots for recently used
+//! account indices during an approved session to avoid repeated
+//! SLIP-0010 derivations (for example, when syncing multiple accounts).
+
+use zeroize::Zeroize;
+
+use mc_core::account::Account;
+
+/// Number of account roots retained in the [AccountCache]
+#[cfg(not(feature = "constrained"))]
+pub const ACCOUNT_CACHE_LEN: usize = 4;
+#[cfg(feature = "constrained")]
+pub const ACCOUNT_CACHE_LEN: usize = 2;
+
+/// Derived account cache, retaining up to [ACCOUNT_CACHE_LEN] accounts
+///
+/// Cached accounts are zeroized on [AccountCache::clear] and on drop.
+pub struct AccountCache {
+    entries: [Option<(u32, Account)>; ACCOUNT_CACHE_LEN],
+    next: usize,
+}
+
+impl AccountCache {
+    /// Create a new (empty) account cache
+    pub const fn new() -> Self {
+        const NONE: Option<(u32, Account)> = None;
+
+        Self {
+            entries: [NONE; ACCOUNT_CACHE_LEN],
+            next: 0,
+        }
+    }
+
+    /// Fetch a cached account by account index
+    pub fn get(&self, account_index: u32) -> Option<&Account> {
+        self.entries
+            .iter()
+            .flatten()
+            .find(|(i, _)| *i == account_index)
+            .map(|(_, a)| a)
+    }
+
+    /// Insert an account, evicting (and zeroizing) the oldest entry when full
+    pub fn insert(&mut self, account_index: u32, account: Account) {
+        if let Some(e) = self
+            .entries
+            .iter_mut()
+            .flatten()
+            .find(|(i, _)| *i == account_index)
+        {
+            e.1.zeroize();
+            e.1 = account;
+            return;
+        }
+
+        if let Some((_, mut a)) = self.entries[self.next].take() {
+            a.zeroize();
+        }
+
+        self.entries[self.next] = Some((account_index, account));
+        self.next = (self.next + 1) % ACCOUNT_CACHE_LEN;
+    }
+
+    /// Number of accounts currently cached
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|e| e.is_some()).count()
+    }
+
+    /// Check whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Zeroize and remove all cached accounts
+    pub fn clear(&mut self) {
+        for e in self.entries.iter_mut() {
+            if let Some((_, mut a)) = e.take() {
+                a.zeroize();
+            }
+        }
+
+        self.next = 0;
+    }
+}
+
+impl Default for AccountCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Zeroize cached accounts on drop
+impl Drop for AccountCache {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use mc_core::{account::RingCtAddress, slip10::Slip10Key, subaddress::Subaddress};
+
+    use super::*;
+
+    fn account(i: u8) -> Account {
+        Account::from(&Slip10Key::from_raw([i; 32]))
+    }
+
+    #[test]
+    fn account_cache_eviction() {
+        let mut c = AccountCache::new();
+        assert!(c.is_empty());
+        assert!(c.get(0).is_none());
+
+        // Fill cache
+        for i in 0..ACCOUNT_CACHE_LEN as u8 {
+            c.insert(i as u32, account(i));
+        }
+        assert_eq!(c.len(), ACCOUNT_CACHE_LEN);
+
+        let expected = account(1).subaddress(0);
+        let a = c.get(1).unwrap().subaddress(0);
+        assert_eq!(a.spend_public_key(), expected.spend_public_key());
+
+        // Overflowing the cache evicts the oldest entry
+        c.insert(0xff, account(0xff));
+        assert_eq!(c.len(), ACCOUNT_CACHE_LEN);
+        assert!(c.get(0).is_none());
+        assert!(c.get(1).is_some());
+        assert!(c.get(0xff).is_some());
+
+        // Clearing the cache removes all entries
+        c.clear();
+        assert!(c.is_empty());
+        assert!(c.get(1).is_none());
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+use strum::{EnumIter, EnumString};
+
+use super::FogId;
+
+/// Address verification state
+#[derive(Copy, Clone, Debug, PartialEq, Default, EnumString, EnumIter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AddressState {
+    /// Address loaded, pending user confirmation
+    #[default]
+    Pending,
+    /// Address confirmed, signing allowed
+    Approved,
+    /// Address rejected, return error
+    Denied,
+}
+
+/// Address verification request
+#[derive(Clone, Debug, PartialEq)]
+pub struct AddressVerify {
+    /// SLIP-0010 account index
+    pub account_index: u32,
+    /// Subaddress index
+    pub subaddress_index: u64,
+    /// Fog ID for address
+    pub fog_id: FogId,
+    /// Host challenge, included in the signed acknowledgement
+    pub challenge: [u8; 32],
+}
+
+impl AddressVerify {
+    /// Create a new address verification context
+    pub fn new(
+        account_index: u32,
+        subaddress_index: u64,
+        fog_id: FogId,
+        challenge: [u8; 32],
+    ) -> Self {
+        Self {
+            account_index,
+            subaddress_index,
+            fog_id,
+            challenge,
+        }
+    }
+
+    /// Build the message signed to acknowledge an address,
+    /// consisting of the address short hash and host challenge
+    pub fn message(&self, short_hash: &[u8]) -> [u8; 48] {
+        let mut m = [0u8; 48];
+        m[..16].copy_from_slice(&short_hash[..16]);
+        m[16..].copy_from_slice(&self.challenge);
+        m
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Approval audit log, a fixed-size ring buffer recording operations
+//! approved on the device for retrieval by the host.
+
+pub use ledger_mob_apdu::audit::{AuditEntry, AuditKind};
+
+/// Number of entries retained in the [AuditLog]
+#[cfg(not(feature = "constrained"))]
+pub const AUDIT_LOG_LEN: usize = 8;
+#[cfg(feature = "constrained")]
+pub const AUDIT_LOG_LEN: usize = 4;
+
+/// Approval audit log, retaining the most recent [AUDIT_LOG_LEN] approvals
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditLog {
+    entries: [Option<AuditEntry>; AUDIT_LOG_LEN],
+    seq: u32,
+}
+
+impl AuditLog {
+    /// Create a new (empty) audit log
+    pub const fn new() -> Self {
+        Self {
+            entries: [None; AUDIT_LOG_LEN],
+            seq: 0,
+        }
+    }
+
+    /// Record an approval, overwriting the oldest entry when full
+    pub fn push(&mut self, kind: AuditKind, ticks: u32, data: [u8; 32]) {
+        let i = self.seq as usize % AUDIT_LOG_LEN;
+
+        self.entries[i] = Some(AuditEntry {
+            kind,
+            seq: self.seq,
+            ticks,
+            data,
+        });
+
+        self.seq = self.seq.wrapping_add(1);
+    }
+
+    /// Number of entries currently held
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|e| e.is_some()).count()
+    }
+
+    /// Check whether the audit log is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Fetch an entry by offset from the most recent approval
+    pub fn get(&self, offset: usize) -> Option<&AuditEntry> {
+        if offset >= AUDIT_LOG_LEN {
+            return None;
+        }
+
+        let i = (self.seq as usize).wrapping_sub(offset + 1) % AUDIT_LOG_LEN;
+
+        self.entries[i].as_ref()
+    }
+
+    /// Iterate over entries, most recent first
+    pub fn iter(&self) -> impl Iterator<Item = &AuditEntry> {
+        (0..AUDIT_LOG_LEN).map_while(|i| self.get(i))
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn audit_log_ordering() {
+        let mut l = AuditLog::new();
+        assert!(l.is_empty());
+        assert_eq!(l.get(0), None);
+
+        // Partially filled log returns entries most recent first
+        for i in 0..3u8 {
+            l.push(AuditKind::Transaction, i as u32, [i; 32]);
+        }
+        assert_eq!(l.len(), 3);
+        assert_eq!(
+            l.iter().map(|e| e.seq).collect::<heapless::Vec<_, 8>>(),
+            &[2, 1, 0]
+        );
+
+        // Overflowing the log drops the oldest entries
+        for i in 3..(AUDIT_LOG_LEN as u8 + 2) {
+            l.push(AuditKind::KeyExport, i as u32, [i; 32]);
+        }
+        assert_eq!(l.len(), AUDIT_LOG_LEN);
+        assert_eq!(l.get(0).map(|e| e.seq), Some(AUDIT_LOG_LEN as u32 + 1));
+        assert_eq!(l.get(AUDIT_LOG_LEN - 1).map(|e| e.seq), Some(2));
+        assert_eq!(l.get(AUDIT_LOG_LEN), None);
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Engine diagnostic counters, to aid debugging of unreliable transports
+
+/// Engine diagnostic counters, retained for the lifetime of the app.
+/// Counters wrap on overflow.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostics {
+    /// Events processed by the engine
+    pub events: u32,
+    /// Events resulting in an error
+    pub errors: u32,
+    /// Engine resets
+    pub resets: u32,
+    /// Rings successfully signed
+    pub rings_signed: u32,
+}
+
+impl Diagnostics {
+    /// Create a new (zeroed) diagnostics instance
+    pub const fn new() -> Self {
+        Self {
+            events: 0,
+            errors: 0,
+            resets: 0,
+            rings_signed: 0,
+        }
+    }
+}
@@ -1,5 +1,7 @@
 // Copyright (c) 2022-2023 The MobileCoin Foundation
 
+use ledger_mob_apdu::status::{StatusClass, StatusWord};
+
 /// [super::Engine] errors
 #[derive(Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
@@ -69,7 +71,180 @@ pub enum Error {
     #[cfg_attr(feature = "thiserror", error("identity request rejected"))]
     IdentRejected = 0x0f,
 
+    /// Real input commitment does not match the provided value and blindings
+    #[cfg_attr(feature = "thiserror", error("value not conserved"))]
+    ValueNotConserved = 0x10,
+
+    /// Memo kind is not supported for signing
+    #[cfg_attr(feature = "thiserror", error("unsupported memo kind"))]
+    UnsupportedMemo = 0x11,
+
+    /// Address verification rejected by user
+    #[cfg_attr(feature = "thiserror", error("address verification rejected"))]
+    AddressRejected = 0x12,
+
+    /// Block version outside the range supported by the engine
+    #[cfg_attr(feature = "thiserror", error("unsupported block version"))]
+    UnsupportedBlockVersion = 0x13,
+
+    /// Output to our own account targets neither the change nor default subaddress
+    #[cfg_attr(feature = "thiserror", error("output to unexpected own subaddress"))]
+    UnexpectedOwnAddress = 0x14,
+
+    /// Transaction totals exceed the range that can be displayed
+    #[cfg_attr(feature = "thiserror", error("transaction totals overflow"))]
+    TotalsOverflow = 0x15,
+
+    /// Blind signing (direct message set) is disabled, use TxSummary
+    #[cfg_attr(feature = "thiserror", error("blind signing disabled"))]
+    BlindSigningDisabled = 0x16,
+
+    /// Memo payload is malformed (invalid payment id or non-zero unused bytes)
+    #[cfg_attr(feature = "thiserror", error("invalid memo payload"))]
+    InvalidMemo = 0x17,
+
+    /// Ring entry index outside the ring, or real entry not provided first
+    #[cfg_attr(feature = "thiserror", error("invalid ring index"))]
+    InvalidRingIndex = 0x18,
+
+    /// Ring entry index already added
+    #[cfg_attr(feature = "thiserror", error("duplicate ring index"))]
+    DuplicateRingIndex = 0x19,
+
+    /// Ring value or token does not match an unsigned input from the approved summary
+    #[cfg_attr(feature = "thiserror", error("ring does not match approved input"))]
+    RingInputMismatch = 0x1a,
+
+    /// Invalid b58 address encoding
+    #[cfg_attr(feature = "thiserror", error("invalid address"))]
+    InvalidAddress = 0x1b,
+
+    /// Transaction request sequence number does not follow the last accepted request
+    #[cfg_attr(feature = "thiserror", error("unexpected request sequence"))]
+    UnexpectedSequence = 0x1c,
+
+    /// Transaction request payload MAC does not match the request payload
+    #[cfg_attr(feature = "thiserror", error("invalid request payload MAC"))]
+    InvalidMac = 0x1d,
+
+    /// Protocol version outside the range supported by the engine
+    #[cfg_attr(feature = "thiserror", error("unsupported protocol version"))]
+    UnsupportedProtocol = 0x1e,
+
+    /// Transaction requested prior to the host pinning a protocol version
+    #[cfg_attr(feature = "thiserror", error("protocol version not pinned"))]
+    ProtocolNotPinned = 0x1f,
+
+    /// Fog report URL provided without a fog authority signature
+    #[cfg_attr(feature = "thiserror", error("invalid fog report URL"))]
+    InvalidFogUrl = 0x20,
+
+    /// Key derivation path not hardened or outside the application prefix
+    #[cfg_attr(feature = "thiserror", error("invalid key derivation path"))]
+    InvalidPath = 0x21,
+
+    /// Summary fee or tombstone block does not match the values provided with TxInit
+    #[cfg_attr(feature = "thiserror", error("expected fee or tombstone mismatch"))]
+    ExpectedMismatch = 0x22,
+
+    /// Host-supplied onetime private keys are disabled, use seed-derived keys
+    #[cfg_attr(feature = "thiserror", error("onetime private keys disabled"))]
+    OnetimeKeysDisabled = 0x23,
+
+    /// Transaction cancelled on-device during signing
+    #[cfg_attr(feature = "thiserror", error("transaction cancelled"))]
+    TxCancelled = 0x24,
+
+    /// Approved summary inputs could not be recorded for signing checks
+    #[cfg_attr(feature = "thiserror", error("failed to record summary inputs"))]
+    SummaryFailed = 0x25,
+
+    /// Request exceeds the key exports remaining before re-approval
+    #[cfg_attr(feature = "thiserror", error("key export limit exceeded"))]
+    KeyLimitExceeded = 0x26,
+
     /// Unknown / not-yet defined error (placeholder)
     #[cfg_attr(feature = "thiserror", error("unknown"))]
     Unknown = 0xf0,
 }
+
+impl Error {
+    /// Fetch the [StatusClass] for an engine error,
+    /// used to group errors into status word ranges
+    pub fn class(&self) -> StatusClass {
+        use Error::*;
+
+        match self {
+            UnexpectedEvent
+            | RingFull
+            | MissingBlindings
+            | MissingOnetimePrivateKey
+            | InvalidState
+            | SummaryInitFailed
+            | SummaryMissingOutput
+            | SummaryFailed
+            | UnexpectedSequence
+            | ProtocolNotPinned => StatusClass::State,
+
+            InvalidLength
+            | InvalidKey
+            | EncodingFailed
+            | UnsupportedMemo
+            | UnsupportedBlockVersion
+            | InvalidMemo
+            | InvalidRingIndex
+            | DuplicateRingIndex
+            | InvalidAddress
+            | UnexpectedOwnAddress
+            | TotalsOverflow
+            | RingInputMismatch
+            | UnsupportedProtocol
+            | InvalidFogUrl
+            | InvalidPath
+            | ExpectedMismatch => StatusClass::Decode,
+
+            ApprovalPending | IdentRejected | AddressRejected | BlindSigningDisabled
+            | OnetimeKeysDisabled | TxCancelled | KeyLimitExceeded => StatusClass::Approval,
+
+            SignError
+            | OnetimeKeyRecoveryFailed
+            | RingInitFailed
+            | RingUpdateFailed
+            | ValueNotConserved
+            | InvalidMac => StatusClass::Crypto,
+
+            Unknown => StatusClass::Other,
+        }
+    }
+}
+
+/// Map engine errors to status words (`CLASS << 8 | CODE`)
+impl From<Error> for StatusWord {
+    fn from(e: Error) -> Self {
+        StatusWord::new(e.class(), e as u8)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn error_status_words() {
+        let tests = [
+            (Error::UnexpectedEvent, 0xb001),
+            (Error::InvalidLength, 0xb100),
+            (Error::ApprovalPending, 0xb20c),
+            (Error::InvalidMac, 0xb31d),
+            (Error::Unknown, 0xb4f0),
+        ];
+
+        for (e, v) in tests {
+            let class = e.class();
+            let sw = StatusWord::from(e);
+
+            assert_eq!(u16::from(sw), v);
+            assert_eq!(sw.class(), Some(class));
+        }
+    }
+}
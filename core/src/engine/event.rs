@@ -15,25 +15,63 @@ use mc_crypto_ring_signature::{CompressedCommitment, CurveScalar, ReducedTxOut,
 use mc_transaction_types::{Amount, MaskedAmount, UnmaskedAmount};
 
 use ledger_mob_apdu::{
+    digest::PAYLOAD_MAC_LEN,
+    encoding::{ApduDecode, Encoding},
+    ident::{IdentDerivation, IDENT_CHALLENGE_MAX, IDENT_URI_MAX},
     prelude::*,
-    tx::{AddTxInFlags, FogId, TxOnetimeKey, TxRingInitFlags},
+    tx::{
+        AddTxInFlags, FogId, FogUrl, TxAuthenticated, TxGetResponseFlags, TxOnetimeKey,
+        TxRingInitFlags, TxSequenced, TX_MESSAGE_MAX,
+    },
 };
 use ledger_proto::{ApduError, ApduStatic};
 
 /// [`Engine`][super::Engine] input events, typically decoded from request [APDUs][crate::apdu]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event {
     None,
 
-    /// Fetch wallet keys
+    /// Fetch wallet keys, with an attestation where a challenge is provided,
+    /// using an explicit derivation path in place of `account_index` where provided.
+    ///
+    /// Attestations bind `account_index` only, so `challenge` and `path` are exclusive.
     GetWalletKeys {
         account_index: u32,
+        challenge: Option<[u8; KEY_ATTESTATION_CHALLENGE_LEN]>,
+        path: Option<KeyPath>,
     },
 
-    /// Fetch subaddress keys
+    /// Fetch subaddress keys, with an attestation where a challenge is provided,
+    /// using an explicit derivation path in place of `account_index` where provided.
+    ///
+    /// Attestations bind `account_index` only, so `challenge` and `path` are exclusive.
     GetSubaddressKeys {
         account_index: u32,
         subaddress_index: u64,
+        challenge: Option<[u8; KEY_ATTESTATION_CHALLENGE_LEN]>,
+        path: Option<KeyPath>,
+    },
+
+    /// Fetch subaddress keys with fog authority signature
+    GetSubaddressKeysFog {
+        account_index: u32,
+        subaddress_index: u64,
+        fog_id: FogId,
+    },
+
+    /// Fetch public keys for a range of subaddresses
+    GetSubaddressRange {
+        account_index: u32,
+        start: u64,
+        count: u8,
+    },
+
+    /// Fetch keys for a contiguous batch of subaddresses
+    GetSubaddressKeysBatch {
+        account_index: u32,
+        start: u64,
+        count: u8,
     },
 
     /// Fetch key image
@@ -43,36 +81,116 @@ pub enum Event {
         txout_public_key: TxOutPublic,
     },
 
-    /// Fetch random value via RNG
-    GetRandom,
+    /// Fetch key image for a host-supplied onetime private key
+    GetKeyImageFromKey {
+        account_index: u32,
+        subaddress_index: u64,
+        onetime_private_key: TxOnetimeKey,
+    },
+
+    /// Fetch random value of `len` bytes via RNG
+    GetRandom {
+        len: u8,
+    },
+
+    /// Generate a fresh device nonce for session challenges
+    GetDeviceNonce,
+
+    /// Sign a host challenge bound to the outstanding device nonce
+    SignSessionChallenge {
+        challenge: [u8; SESSION_CHALLENGE_LEN],
+    },
+
+    /// Fetch key export counters
+    GetKeyCounters,
+
+    /// Fetch summary loading / ring signing progress
+    GetProgress,
+
+    /// Fetch device settings
+    GetSettings,
+
+    /// Fetch approval audit log entries
+    GetAuditLog {
+        start: u8,
+    },
+
+    /// Fetch engine diagnostic counters
+    GetDiagnostics,
+
+    /// Fetch supported protocol versions and features
+    GetCapabilities,
+
+    /// Pin the protocol version for subsequent requests
+    SetProtocolVersion {
+        version: u8,
+    },
 
-    /// Request BIP-0017 derived ed25519 identity
+    /// Zeroize account roots cached during the current session
+    InvalidateAccounts,
+
+    /// Request SLIP-0013 or SLIP-0017 derived ed25519 identity
     IdentSign {
         ident_index: u32,
-        ident_uri: heapless::String<32>,
-        challenge: heapless::Vec<u8, 64>,
+        ident_uri: heapless::String<IDENT_URI_MAX>,
+        challenge: heapless::Vec<u8, IDENT_CHALLENGE_MAX>,
+        derivation: IdentDerivation,
     },
 
     /// Fetch signed identity
     IdentGet,
 
+    /// Request on-device address verification
+    AddressVerify {
+        account_index: u32,
+        subaddress_index: u64,
+        fog_id: FogId,
+        challenge: [u8; 32],
+    },
+
+    /// Fetch signed address following verification
+    AddressGet,
+
+    /// Fetch b58 encoded public address
+    GetPublicAddress {
+        account_index: u32,
+        subaddress_index: u64,
+        fog_id: FogId,
+    },
+
+    /// Request a change of the active fog id
+    SetFogId {
+        fog_id: FogId,
+    },
+
     /// Initialise transaction
     TxInit {
         account_index: u32,
         num_rings: u8,
+        /// Expected fee and tombstone block (where provided by the host),
+        /// checked on [Event::TxSummaryBuild]
+        expected: Option<TxExpected>,
     },
 
-    /// Sign transaction memos
+    /// Sign transaction memos, payloads exceeding a single request
+    /// are streamed as a sequence of chunks (see [TxMemoFlags])
     TxSignMemo {
         subaddress_index: u64,
         tx_public_key: TxOutPublic,
         receiver_view_public: SubaddressViewPublic,
         kind: [u8; 2],
+        #[cfg_attr(feature = "serde", serde(with = "serde_big_array::BigArray"))]
         payload: [u8; 48],
+        /// Payload bytes in this chunk
+        payload_len: u8,
+        /// Chunk continues a payload started by a prior event
+        continuation: bool,
+        /// Further payload chunks follow this event
+        more: bool,
     },
 
     /// Set transaction message
-    TxSetMessage(heapless::Vec<u8, 64>),
+    TxSetMessage(heapless::Vec<u8, TX_MESSAGE_MAX>),
 
     /// Set transaction summary
     /// (replaces TxSetMessage where streaming verification is supported)
@@ -95,7 +213,10 @@ pub enum Event {
     TxSummaryAddOutputUnblinding {
         unmasked_amount: UnmaskedAmount,
         address: Option<PublicSubaddress>,
+        #[cfg_attr(feature = "serde", serde(with = "serde_fog_info"))]
         fog_info: Option<(FogId, [u8; 64])>,
+        /// Fog report URL, for fog deployments without a [FogId]
+        fog_url: Option<FogUrl>,
         tx_private_key: Option<TxPrivateKey>,
     },
 
@@ -111,6 +232,14 @@ pub enum Event {
     TxSummaryBuild {
         fee: Amount,
         tombstone_block: u64,
+        /// Current block index (where provided by the host),
+        /// not included in the transaction digest
+        current_block: Option<u64>,
+    },
+
+    /// Fetch TxSummary report entries following build
+    TxSummaryReport {
+        start: u8,
     },
 
     /// Initialise ring signing
@@ -144,24 +273,139 @@ pub enum Event {
         index: u8,
     },
 
+    /// Fetch responses for the previously completed ring
+    TxGetPriorResponse {
+        /// Index into ring
+        index: u8,
+    },
+
+    /// Fetch a range of responses
+    TxGetResponses {
+        /// Index of first response
+        start: u8,
+        /// Number of responses
+        count: u8,
+    },
+
+    /// Fetch a range of responses for the previously completed ring
+    TxGetPriorResponses {
+        /// Index of first response
+        start: u8,
+        /// Number of responses
+        count: u8,
+    },
+
     /// Complete transaction
     TxComplete,
 
+    /// Abort transaction at host request
+    TxAbort,
+
     /// Fetch TX info / state
     TxGetInfo,
 }
 
+/// Serde helper for optional fog info, as arrays longer than 32 bytes
+/// are not natively supported
+#[cfg(feature = "serde")]
+mod serde_fog_info {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_big_array::BigArray;
+
+    use super::FogId;
+
+    #[derive(Serialize, Deserialize)]
+    struct FogInfo(FogId, #[serde(with = "BigArray")] [u8; 64]);
+
+    pub fn serialize<S: Serializer>(
+        v: &Option<(FogId, [u8; 64])>,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        v.map(|(f, sig)| FogInfo(f, sig)).serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        d: D,
+    ) -> Result<Option<(FogId, [u8; 64])>, D::Error> {
+        Option::<FogInfo>::deserialize(d).map(|v| v.map(|FogInfo(f, sig)| (f, sig)))
+    }
+}
+
+/// Errors returned when parsing APDUs to [Event]s
+#[derive(Clone, Debug)]
+pub enum ParseError {
+    /// Instruction not supported by the engine
+    NotSupported(u8),
+    /// APDU decoding failed (or decoded fields exceed event capacity)
+    Apdu(ApduError),
+}
+
+impl From<ApduError> for ParseError {
+    fn from(e: ApduError) -> Self {
+        Self::Apdu(e)
+    }
+}
+
+/// Request metadata decoded alongside engine events, used to check
+/// transaction requests prior to applying them to the engine
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct ReqMeta {
+    /// Request sequence number, zero for unsequenced requests (see [TxSequenced])
+    pub seq: u8,
+
+    /// Request payload MAC where provided (see [TxAuthenticated])
+    pub mac: Option<[u8; PAYLOAD_MAC_LEN]>,
+}
+
+impl ReqMeta {
+    /// Create request metadata with the provided sequence number
+    pub const fn seq(seq: u8) -> Self {
+        Self { seq, mac: None }
+    }
+}
+
 /// Helper for decoding APDUs to events
 ///
 /// NOTE: forced-inlining collects the stack into a single frame in [Event::parse]
 /// which makes analysis tidier and is a non-critical frame (outside of [Engine::update] path)
 #[inline(always)]
-fn decode_event<'a, T>(buff: &'a [u8]) -> Result<Event, ApduError>
+fn decode_event<'a, T>(enc: Encoding, buff: &'a [u8]) -> Result<(Event, ReqMeta), ApduError>
 where
-    T: Decode<'a, Error = ApduError>,
-    Event: From<T::Output>,
+    T: ApduDecode<'a>,
+    Event: From<T>,
 {
-    T::decode(buff).map(|(v, _n)| Event::from(v))
+    T::decode_with(enc, buff).map(|(v, _n)| (Event::from(v), ReqMeta::default()))
+}
+
+/// Helper for decoding sequenced APDUs to events, returning the
+/// request sequence number alongside the event
+#[inline(always)]
+fn decode_seq_event<'a, T>(enc: Encoding, buff: &'a [u8]) -> Result<(Event, ReqMeta), ApduError>
+where
+    T: ApduDecode<'a> + TxSequenced,
+    Event: From<T>,
+{
+    T::decode_with(enc, buff).map(|(v, _n)| {
+        let meta = ReqMeta::seq(v.seq());
+        (Event::from(v), meta)
+    })
+}
+
+/// Helper for decoding authenticated APDUs to events, returning the
+/// request sequence number and payload MAC alongside the event
+#[inline(always)]
+fn decode_mac_event<'a, T>(enc: Encoding, buff: &'a [u8]) -> Result<(Event, ReqMeta), ApduError>
+where
+    T: ApduDecode<'a> + TxAuthenticated,
+    Event: From<T>,
+{
+    T::decode_with(enc, buff).map(|(v, _n)| {
+        let meta = ReqMeta {
+            seq: v.seq(),
+            mac: Some(v.mac()),
+        };
+        (Event::from(v), meta)
+    })
 }
 
 impl Event {
@@ -172,52 +416,170 @@ impl Event {
         p.write(Self::None);
     }
 
-    /// Parse an incoming APDU to engine event
-    #[cfg_attr(feature = "noinline", inline(never))]
-    pub fn parse(ins: u8, buff: &[u8]) -> Result<Self, ApduError> {
-        match ins {
-            WalletKeyReq::INS => decode_event::<WalletKeyReq>(buff),
-            SubaddressKeyReq::INS => decode_event::<SubaddressKeyReq>(buff),
-            KeyImageReq::INS => decode_event::<KeyImageReq>(buff),
-            RandomReq::INS => decode_event::<RandomReq>(buff),
+    /// Parse an incoming (packed) APDU to engine event
+    pub fn parse(ins: u8, buff: &[u8]) -> Result<Self, ParseError> {
+        Self::parse_with(Encoding::Packed, ins, buff)
+    }
+
+    /// Parse an incoming (packed) APDU to engine event and request
+    /// metadata (see [Event::parse_meta_with])
+    pub fn parse_meta(ins: u8, buff: &[u8]) -> Result<(Self, ReqMeta), ParseError> {
+        Self::parse_meta_with(Encoding::Packed, ins, buff)
+    }
 
-            IdentSignReq::INS => decode_event::<IdentSignReq>(buff),
-            IdentGetReq::INS => decode_event::<IdentGetReq>(buff),
+    /// Parse an incoming APDU to engine event using the provided [Encoding]
+    pub fn parse_with(enc: Encoding, ins: u8, buff: &[u8]) -> Result<Self, ParseError> {
+        Self::parse_meta_with(enc, ins, buff).map(|(evt, _meta)| evt)
+    }
 
-            TxInit::INS => decode_event::<TxInit>(buff),
-            TxMemoSign::INS => decode_event::<TxMemoSign>(buff),
+    /// Parse an incoming APDU to engine event using the provided [Encoding],
+    /// returning the request sequence number and payload MAC for transaction
+    /// requests (see [ReqMeta])
+    ///
+    /// Malformed APDUs and fields exceeding event capacity return
+    /// [ParseError::Apdu], unrecognised instructions [ParseError::NotSupported].
+    #[cfg_attr(feature = "noinline", inline(never))]
+    pub fn parse_meta_with(
+        enc: Encoding,
+        ins: u8,
+        buff: &[u8],
+    ) -> Result<(Self, ReqMeta), ParseError> {
+        let r = match ins {
+            WalletKeyReq::INS => decode_event::<WalletKeyReq>(enc, buff),
+            SubaddressKeyReq::INS => decode_event::<SubaddressKeyReq>(enc, buff),
+            WalletKeyAttestReq::INS => decode_event::<WalletKeyAttestReq>(enc, buff),
+            SubaddressKeyAttestReq::INS => decode_event::<SubaddressKeyAttestReq>(enc, buff),
+            WalletKeyPathReq::INS => decode_event::<WalletKeyPathReq>(enc, buff),
+            SubaddressKeyPathReq::INS => decode_event::<SubaddressKeyPathReq>(enc, buff),
+            SubaddressKeyFogReq::INS => decode_event::<SubaddressKeyFogReq>(enc, buff),
+            SubaddressRangeReq::INS => decode_event::<SubaddressRangeReq>(enc, buff),
+            SubaddressKeysBatchReq::INS => decode_event::<SubaddressKeysBatchReq>(enc, buff),
+            KeyImageReq::INS => decode_event::<KeyImageReq>(enc, buff),
+            KeyImageFromKeyReq::INS => decode_event::<KeyImageFromKeyReq>(enc, buff),
+            RandomReq::INS => decode_event::<RandomReq>(enc, buff),
+            DeviceNonceReq::INS => decode_event::<DeviceNonceReq>(enc, buff),
+            SessionChallengeReq::INS => decode_event::<SessionChallengeReq>(enc, buff),
+            KeyCountersReq::INS => decode_event::<KeyCountersReq>(enc, buff),
+            ProgressReq::INS => decode_event::<ProgressReq>(enc, buff),
+            AuditLogReq::INS => decode_event::<AuditLogReq>(enc, buff),
+            DiagnosticsReq::INS => decode_event::<DiagnosticsReq>(enc, buff),
+            CapabilitiesReq::INS => decode_event::<CapabilitiesReq>(enc, buff),
+            SetProtocolVersionReq::INS => decode_event::<SetProtocolVersionReq>(enc, buff),
+            InvalidateAccountsReq::INS => decode_event::<InvalidateAccountsReq>(enc, buff),
+
+            IdentSignReq::INS => IdentSignReq::decode_with(enc, buff)
+                .and_then(|(v, _n)| Event::try_from(v))
+                .map(|evt| (evt, ReqMeta::default())),
+            IdentGetReq::INS => decode_event::<IdentGetReq>(enc, buff),
+
+            AddressVerifyReq::INS => decode_event::<AddressVerifyReq>(enc, buff),
+            AddressGetReq::INS => decode_event::<AddressGetReq>(enc, buff),
+            PublicAddressReq::INS => decode_event::<PublicAddressReq>(enc, buff),
+
+            SetFogIdReq::INS => decode_event::<SetFogIdReq>(enc, buff),
+            SettingsReq::INS => decode_event::<SettingsReq>(enc, buff),
+
+            TxInit::INS => decode_seq_event::<TxInit>(enc, buff),
+            TxMemoSign::INS => decode_seq_event::<TxMemoSign>(enc, buff),
 
             #[cfg(feature = "summary")]
-            TxSummaryInit::INS => decode_event::<TxSummaryInit>(buff),
+            TxSummaryInit::INS => decode_seq_event::<TxSummaryInit>(enc, buff),
+            #[cfg(feature = "summary")]
+            TxSummaryAddTxIn::INS => decode_mac_event::<TxSummaryAddTxIn>(enc, buff),
             #[cfg(feature = "summary")]
-            TxSummaryAddTxIn::INS => decode_event::<TxSummaryAddTxIn>(buff),
+            TxSummaryAddTxOut::INS => decode_mac_event::<TxSummaryAddTxOut>(enc, buff),
             #[cfg(feature = "summary")]
-            TxSummaryAddTxOut::INS => decode_event::<TxSummaryAddTxOut>(buff),
+            TxSummaryAddTxOutUnblinding::INS => {
+                decode_mac_event::<TxSummaryAddTxOutUnblinding>(enc, buff)
+            }
             #[cfg(feature = "summary")]
-            TxSummaryAddTxOutUnblinding::INS => decode_event::<TxSummaryAddTxOutUnblinding>(buff),
+            TxSummaryAddTxOutCompact::INS => {
+                decode_mac_event::<TxSummaryAddTxOutCompact>(enc, buff)
+            }
             #[cfg(feature = "summary")]
-            TxSummaryBuild::INS => decode_event::<TxSummaryBuild>(buff),
+            TxSummaryAddTxOutUnblindingCompact::INS => {
+                decode_mac_event::<TxSummaryAddTxOutUnblindingCompact>(enc, buff)
+            }
+            #[cfg(feature = "summary")]
+            TxSummaryBuild::INS => decode_seq_event::<TxSummaryBuild>(enc, buff),
+            #[cfg(feature = "summary")]
+            TxSummaryReportReq::INS => decode_event::<TxSummaryReportReq>(enc, buff),
 
-            TxSetMessage::INS => decode_event::<TxSetMessage>(buff),
+            TxSetMessage::INS => TxSetMessage::decode_with(enc, buff).and_then(|(v, _n)| {
+                let meta = ReqMeta::seq(v.seq());
+                Event::try_from(v).map(|evt| (evt, meta))
+            }),
 
-            TxRingInit::INS => decode_event::<TxRingInit>(buff),
-            TxSetBlinding::INS => decode_event::<TxSetBlinding>(buff),
-            TxAddTxOut::INS => decode_event::<TxAddTxOut>(buff),
-            TxRingSign::INS => decode_event::<TxRingSign>(buff),
-            TxGetKeyImage::INS => decode_event::<TxGetKeyImage>(buff),
-            TxGetResponse::INS => decode_event::<TxGetResponse>(buff),
+            TxRingInit::INS => decode_seq_event::<TxRingInit>(enc, buff),
+            TxSetBlinding::INS => decode_mac_event::<TxSetBlinding>(enc, buff),
+            TxAddTxOut::INS => decode_mac_event::<TxAddTxOut>(enc, buff),
+            TxRingSign::INS => decode_seq_event::<TxRingSign>(enc, buff),
+            TxGetKeyImage::INS => decode_event::<TxGetKeyImage>(enc, buff),
+            TxGetResponse::INS => decode_event::<TxGetResponse>(enc, buff),
+            TxGetResponses::INS => decode_event::<TxGetResponses>(enc, buff),
 
-            TxComplete::INS => decode_event::<TxComplete>(buff),
+            TxComplete::INS => decode_event::<TxComplete>(enc, buff),
+            TxAbort::INS => decode_event::<TxAbort>(enc, buff),
 
-            TxInfoReq::INS => decode_event::<TxInfoReq>(buff),
-            _ => unimplemented!(),
+            TxInfoReq::INS => decode_event::<TxInfoReq>(enc, buff),
+
+            // Reject unrecognised instructions
+            _ => return Err(ParseError::NotSupported(ins)),
+        };
+
+        r.map_err(ParseError::from)
+    }
+
+    /// Fetch the [Approvals][super::Approvals] required to handle an event,
+    /// if any
+    pub fn required_approval(&self) -> Option<super::Approvals> {
+        use super::Approvals;
+
+        match self {
+            Event::GetWalletKeys { .. } => Some(Approvals::WALLET_KEYS),
+            Event::GetSubaddressKeys { .. } => Some(Approvals::SUBADDRESS_KEYS),
+            Event::GetSubaddressKeysFog { .. } => Some(Approvals::SUBADDRESS_KEYS),
+            Event::GetSubaddressRange { .. } => Some(Approvals::SUBADDRESS_KEYS),
+            Event::GetSubaddressKeysBatch { .. } => Some(Approvals::SUBADDRESS_KEYS),
+            Event::GetPublicAddress { .. } => Some(Approvals::SUBADDRESS_KEYS),
+            Event::GetKeyImage { .. } => Some(Approvals::KEY_IMAGES),
+            Event::GetKeyImageFromKey { .. } => Some(Approvals::ONETIME_KEY_IMAGES),
+            _ => None,
         }
     }
 
-    /// Compute a SHA512_256 hash for state-mutating events,
-    /// used in the construction of the streaming digest.
+    /// Fetch the SLIP-0010 derivation path for key requests, for display
+    /// on approval, using the default `m/44'/866'/account'` path where
+    /// no explicit path is provided
+    pub fn key_path(&self) -> Option<KeyPath> {
+        let account_index = match self {
+            Event::GetWalletKeys { path: Some(p), .. }
+            | Event::GetSubaddressKeys { path: Some(p), .. } => return Some(*p),
+            Event::GetWalletKeys { account_index, .. }
+            | Event::GetSubaddressKeys { account_index, .. }
+            | Event::GetSubaddressKeysFog { account_index, .. }
+            | Event::GetSubaddressRange { account_index, .. }
+            | Event::GetSubaddressKeysBatch { account_index, .. }
+            | Event::GetPublicAddress { account_index, .. }
+            | Event::GetKeyImage { account_index, .. }
+            | Event::GetKeyImageFromKey { account_index, .. } => *account_index,
+            _ => return None,
+        };
+
+        KeyPath::new(&[
+            KEY_PATH_PREFIX[0],
+            KEY_PATH_PREFIX[1],
+            account_index | KEY_PATH_HARDENED,
+        ])
+        .ok()
+    }
+
+    /// Compute a digest for state-mutating events, used in the
+    /// construction of the streaming digest.
     ///
-    /// This calls out to [ledger_mob_apdu::digest] methods for
+    /// Digests are taken over a [merlin::Transcript] under the
+    /// `mob-ledger-event-digest-v1` domain, binding the instruction and
+    /// field names. This calls out to [ledger_mob_apdu::digest] methods for
     /// consistency between events and APDUs.
     #[cfg_attr(feature = "noinline", inline(never))]
     pub fn hash(&self) -> Option<[u8; 32]> {
@@ -228,6 +590,7 @@ impl Event {
             Event::TxInit {
                 account_index,
                 num_rings,
+                ..
             } => digest_tx_init(account_index, *num_rings),
             Event::TxSignMemo {
                 subaddress_index,
@@ -235,13 +598,23 @@ impl Event {
                 receiver_view_public,
                 kind,
                 payload,
-            } => digest_tx_sign_memo(
-                subaddress_index,
-                tx_public_key,
-                receiver_view_public,
-                kind,
-                payload,
-            ),
+                payload_len,
+                continuation,
+                more,
+            } => {
+                let mut flags = TxMemoFlags::empty();
+                flags.set(TxMemoFlags::CONTINUATION, *continuation);
+                flags.set(TxMemoFlags::MORE, *more);
+
+                digest_tx_sign_memo(
+                    subaddress_index,
+                    tx_public_key,
+                    receiver_view_public,
+                    kind,
+                    flags,
+                    &payload[..(*payload_len as usize).min(payload.len())],
+                )
+            }
 
             // Set message (blind transactions)
             Event::TxSetMessage(m) => digest_tx_set_message(m),
@@ -270,12 +643,14 @@ impl Event {
                 unmasked_amount,
                 address,
                 fog_info,
+                fog_url,
                 tx_private_key,
             } => digest_tx_summary_add_output_unblinding(
                 unmasked_amount,
                 address.as_ref(),
                 tx_private_key.as_ref(),
                 fog_info.as_ref().map(|(_id, sig)| &sig[..]),
+                fog_url.as_ref(),
             ),
             Event::TxSummaryAddInput {
                 pseudo_output_commitment,
@@ -289,6 +664,7 @@ impl Event {
             Event::TxSummaryBuild {
                 fee,
                 tombstone_block,
+                ..
             } => digest_tx_summary_build(&fee.value, &fee.token_id, tombstone_block),
 
             // Ring updates
@@ -325,6 +701,28 @@ impl From<WalletKeyReq> for Event {
     fn from(a: WalletKeyReq) -> Self {
         Event::GetWalletKeys {
             account_index: a.account_index,
+            challenge: None,
+            path: None,
+        }
+    }
+}
+
+impl From<WalletKeyAttestReq> for Event {
+    fn from(a: WalletKeyAttestReq) -> Self {
+        Event::GetWalletKeys {
+            account_index: a.account_index,
+            challenge: Some(a.challenge),
+            path: None,
+        }
+    }
+}
+
+impl From<WalletKeyPathReq> for Event {
+    fn from(a: WalletKeyPathReq) -> Self {
+        Event::GetWalletKeys {
+            account_index: a.path.account_index(),
+            challenge: None,
+            path: Some(a.path),
         }
     }
 }
@@ -334,6 +732,60 @@ impl From<SubaddressKeyReq> for Event {
         Event::GetSubaddressKeys {
             account_index: a.account_index,
             subaddress_index: a.subaddress_index,
+            challenge: None,
+            path: None,
+        }
+    }
+}
+
+impl From<SubaddressKeyAttestReq> for Event {
+    fn from(a: SubaddressKeyAttestReq) -> Self {
+        Event::GetSubaddressKeys {
+            account_index: a.account_index,
+            subaddress_index: a.subaddress_index,
+            challenge: Some(a.challenge),
+            path: None,
+        }
+    }
+}
+
+impl From<SubaddressKeyPathReq> for Event {
+    fn from(a: SubaddressKeyPathReq) -> Self {
+        Event::GetSubaddressKeys {
+            account_index: a.path.account_index(),
+            subaddress_index: a.subaddress_index,
+            challenge: None,
+            path: Some(a.path),
+        }
+    }
+}
+
+impl From<SubaddressKeyFogReq> for Event {
+    fn from(a: SubaddressKeyFogReq) -> Self {
+        Event::GetSubaddressKeysFog {
+            account_index: a.account_index,
+            subaddress_index: a.subaddress_index,
+            fog_id: a.fog_id,
+        }
+    }
+}
+
+impl From<SubaddressRangeReq> for Event {
+    fn from(a: SubaddressRangeReq) -> Self {
+        Event::GetSubaddressRange {
+            account_index: a.account_index,
+            start: a.start,
+            count: a.count,
+        }
+    }
+}
+
+impl From<SubaddressKeysBatchReq> for Event {
+    fn from(a: SubaddressKeysBatchReq) -> Self {
+        Event::GetSubaddressKeysBatch {
+            account_index: a.account_index,
+            start: a.start,
+            count: a.count,
         }
     }
 }
@@ -348,33 +800,153 @@ impl From<KeyImageReq> for Event {
     }
 }
 
+impl From<KeyImageFromKeyReq> for Event {
+    fn from(a: KeyImageFromKeyReq) -> Self {
+        Event::GetKeyImageFromKey {
+            account_index: a.account_index,
+            subaddress_index: a.subaddress_index,
+            onetime_private_key: a.onetime_private_key,
+        }
+    }
+}
+
 impl From<RandomReq> for Event {
-    fn from(_: RandomReq) -> Self {
-        Event::GetRandom
+    fn from(a: RandomReq) -> Self {
+        Event::GetRandom {
+            len: a.value_len() as u8,
+        }
     }
 }
 
-impl<'a> From<IdentSignReq<'a>> for Event {
-    fn from(i: IdentSignReq<'a>) -> Self {
-        Event::IdentSign {
-            ident_index: i.identity_index,
-            ident_uri: heapless::String::from(i.identity_uri),
-            challenge: heapless::Vec::from_slice(i.challenge).unwrap(),
+impl From<DeviceNonceReq> for Event {
+    fn from(_: DeviceNonceReq) -> Self {
+        Event::GetDeviceNonce
+    }
+}
+
+impl From<SessionChallengeReq> for Event {
+    fn from(a: SessionChallengeReq) -> Self {
+        Event::SignSessionChallenge {
+            challenge: a.challenge,
         }
     }
 }
 
+impl From<KeyCountersReq> for Event {
+    fn from(_: KeyCountersReq) -> Self {
+        Event::GetKeyCounters
+    }
+}
+
+impl From<ProgressReq> for Event {
+    fn from(_: ProgressReq) -> Self {
+        Event::GetProgress
+    }
+}
+
+impl From<AuditLogReq> for Event {
+    fn from(a: AuditLogReq) -> Self {
+        Event::GetAuditLog { start: a.start }
+    }
+}
+
+impl From<DiagnosticsReq> for Event {
+    fn from(_: DiagnosticsReq) -> Self {
+        Event::GetDiagnostics
+    }
+}
+
+impl From<CapabilitiesReq> for Event {
+    fn from(_: CapabilitiesReq) -> Self {
+        Event::GetCapabilities
+    }
+}
+
+impl From<SetProtocolVersionReq> for Event {
+    fn from(r: SetProtocolVersionReq) -> Self {
+        Event::SetProtocolVersion { version: r.version }
+    }
+}
+
+impl From<InvalidateAccountsReq> for Event {
+    fn from(_: InvalidateAccountsReq) -> Self {
+        Event::InvalidateAccounts
+    }
+}
+
+/// Fallible as URI and challenge lengths are not bounded by CBOR decoding
+impl<'a> TryFrom<IdentSignReq<'a>> for Event {
+    type Error = ApduError;
+
+    fn try_from(i: IdentSignReq<'a>) -> Result<Self, Self::Error> {
+        let mut ident_uri = heapless::String::new();
+        ident_uri
+            .push_str(i.identity_uri)
+            .map_err(|_| ApduError::InvalidLength)?;
+
+        let challenge =
+            heapless::Vec::from_slice(i.challenge).map_err(|_| ApduError::InvalidLength)?;
+
+        Ok(Event::IdentSign {
+            ident_index: i.identity_index,
+            ident_uri,
+            challenge,
+            derivation: i.derivation,
+        })
+    }
+}
+
 impl From<IdentGetReq> for Event {
     fn from(_i: IdentGetReq) -> Self {
         Event::IdentGet
     }
 }
 
+impl From<AddressVerifyReq> for Event {
+    fn from(a: AddressVerifyReq) -> Self {
+        Event::AddressVerify {
+            account_index: a.account_index,
+            subaddress_index: a.subaddress_index,
+            fog_id: a.fog_id,
+            challenge: a.challenge,
+        }
+    }
+}
+
+impl From<AddressGetReq> for Event {
+    fn from(_a: AddressGetReq) -> Self {
+        Event::AddressGet
+    }
+}
+
+impl From<PublicAddressReq> for Event {
+    fn from(a: PublicAddressReq) -> Self {
+        Event::GetPublicAddress {
+            account_index: a.account_index,
+            subaddress_index: a.subaddress_index,
+            fog_id: a.fog_id,
+        }
+    }
+}
+
+impl From<SetFogIdReq> for Event {
+    fn from(a: SetFogIdReq) -> Self {
+        Event::SetFogId { fog_id: a.fog_id }
+    }
+}
+
+impl From<SettingsReq> for Event {
+    fn from(_: SettingsReq) -> Self {
+        Event::GetSettings
+    }
+}
+
 impl From<TxInit> for Event {
     fn from(a: TxInit) -> Self {
         Event::TxInit {
             account_index: a.account_index,
             num_rings: a.num_rings,
+            expected: a.expected,
         }
     }
 }
@@ -387,6 +959,9 @@ impl From<TxMemoSign> for Event {
             receiver_view_public: a.target_view_public,
             kind: a.kind,
             payload: a.payload,
+            payload_len: a.payload_len,
+            continuation: a.flags.contains(TxMemoFlags::CONTINUATION),
+            more: a.flags.contains(TxMemoFlags::MORE),
         }
     }
 }
@@ -426,11 +1001,26 @@ impl From<TxSummaryAddTxOutUnblinding> for Event {
             },
             address: a.address(),
             fog_info: a.fog_info(),
+            fog_url: a.fog_url().cloned(),
             tx_private_key: a.tx_private_key().cloned(),
         }
     }
 }
 
+#[cfg(feature = "summary")]
+impl From<TxSummaryAddTxOutCompact> for Event {
+    fn from(a: TxSummaryAddTxOutCompact) -> Self {
+        Event::from(a.0)
+    }
+}
+
+#[cfg(feature = "summary")]
+impl From<TxSummaryAddTxOutUnblindingCompact> for Event {
+    fn from(a: TxSummaryAddTxOutUnblindingCompact) -> Self {
+        Event::from(a.0)
+    }
+}
+
 #[cfg(feature = "summary")]
 impl From<TxSummaryAddTxIn> for Event {
     fn from(a: TxSummaryAddTxIn) -> Self {
@@ -456,10 +1046,18 @@ impl From<TxSummaryBuild> for Event {
                 token_id: a.fee_token_id.into(),
             },
             tombstone_block: a.tombstone_block,
+            current_block: a.current_block,
         }
     }
 }
 
+#[cfg(feature = "summary")]
+impl From<TxSummaryReportReq> for Event {
+    fn from(a: TxSummaryReportReq) -> Self {
+        Event::TxSummaryReport { start: a.start }
+    }
+}
+
 impl From<TxRingInit> for Event {
     fn from(a: TxRingInit) -> Self {
         let onetime_private_key = match a.flags.contains(TxRingInitFlags::HAS_ONETIME_PRIVATE_KEY) {
@@ -487,9 +1085,13 @@ impl From<TxSetBlinding> for Event {
     }
 }
 
-impl<'a> From<TxSetMessage<'a>> for Event {
-    fn from(a: TxSetMessage<'a>) -> Self {
-        Event::TxSetMessage(heapless::Vec::from_slice(a.message).unwrap())
+/// Fallible as message lengths are not bounded by APDU decoding
+impl<'a> TryFrom<TxSetMessage<'a>> for Event {
+    type Error = ApduError;
+
+    fn try_from(a: TxSetMessage<'a>) -> Result<Self, Self::Error> {
+        let m = heapless::Vec::from_slice(a.message).map_err(|_| ApduError::InvalidLength)?;
+        Ok(Event::TxSetMessage(m))
     }
 }
 
@@ -522,8 +1124,28 @@ impl From<TxGetKeyImage> for Event {
 
 impl From<TxGetResponse> for Event {
     fn from(a: TxGetResponse) -> Self {
-        Event::TxGetResponse {
-            index: a.ring_index,
+        match a.flags.contains(TxGetResponseFlags::PRIOR_RING) {
+            true => Event::TxGetPriorResponse {
+                index: a.ring_index,
+            },
+            false => Event::TxGetResponse {
+                index: a.ring_index,
+            },
+        }
+    }
+}
+
+impl From<TxGetResponses> for Event {
+    fn from(a: TxGetResponses) -> Self {
+        match a.flags.contains(TxGetResponseFlags::PRIOR_RING) {
+            true => Event::TxGetPriorResponses {
+                start: a.start,
+                count: a.count,
+            },
+            false => Event::TxGetResponses {
+                start: a.start,
+                count: a.count,
+            },
         }
     }
 }
@@ -534,6 +1156,12 @@ impl From<TxComplete> for Event {
     }
 }
 
+impl From<TxAbort> for Event {
+    fn from(_: TxAbort) -> Self {
+        Event::TxAbort
+    }
+}
+
 impl From<TxInfoReq> for Event {
     fn from(_: TxInfoReq) -> Self {
         Event::TxGetInfo
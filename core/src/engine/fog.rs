@@ -1,5 +1,20 @@
+use strum::{EnumIter, EnumString};
+
 pub use ledger_mob_apdu::tx::FogId;
 
+/// Fog id change state, for host-initiated fog changes
+#[derive(Copy, Clone, Debug, PartialEq, Default, EnumString, EnumIter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FogState {
+    /// Fog change requested, pending user confirmation
+    #[default]
+    Pending,
+    /// Fog change confirmed
+    Approved,
+    /// Fog change rejected
+    Denied,
+}
+
 /// Fog certificate information for [FogId] types
 pub trait FogCert {
     fn spki(&self) -> &[u8];
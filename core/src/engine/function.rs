@@ -1,16 +1,17 @@
 // Copyright (c) 2022-2023 The MobileCoin Foundation
 
-use core::mem::MaybeUninit;
+use core::mem::{size_of, MaybeUninit};
 
 use mc_core::{
     account::PublicSubaddress,
     keys::{RootViewPrivate, SubaddressSpendPrivate},
 };
 use mc_transaction_types::BlockVersion;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use ledger_mob_apdu::tx::TxOnetimeKey;
 
-use super::Error;
+use super::{address::AddressVerify, Error};
 
 #[cfg(feature = "mlsag")]
 use super::ring::RingSigner;
@@ -21,6 +22,11 @@ use super::{summary::Summarizer, MAX_RECORDS};
 #[cfg(feature = "ident")]
 use super::ident::Ident;
 
+#[cfg(feature = "memo")]
+use super::memo::MemoMac;
+#[cfg(feature = "ident")]
+use ledger_mob_apdu::ident::IdentDerivation;
+
 pub struct Function {
     inner: FunctionType,
 }
@@ -47,8 +53,28 @@ enum FunctionType {
 
     #[cfg(feature = "ident")]
     Ident(Ident),
+
+    VerifyAddress(AddressVerify),
+
+    #[cfg(feature = "memo")]
+    MemoMac(MemoMac),
+}
+
+/// Clear the active function, zeroizing contexts on drop
+impl Zeroize for Function {
+    fn zeroize(&mut self) {
+        self.clear();
+    }
+}
+
+impl Drop for Function {
+    fn drop(&mut self) {
+        self.clear();
+    }
 }
 
+impl ZeroizeOnDrop for Function {}
+
 impl Default for FunctionType {
     fn default() -> Self {
         Self::None
@@ -149,6 +175,8 @@ impl Function {
         num_inputs: usize,
         view_private_key: &RootViewPrivate,
         change_subaddress: &PublicSubaddress,
+        default_subaddress: &PublicSubaddress,
+        gift_code_subaddress: &PublicSubaddress,
     ) -> Result<&mut Summarizer<MAX_RECORDS>, Error> {
         // Clear function prior to init (executes drop)
 
@@ -173,6 +201,8 @@ impl Function {
                 num_inputs,
                 view_private_key,
                 change_subaddress,
+                default_subaddress,
+                gift_code_subaddress,
             )
         } {
             // Clear context and return error
@@ -211,12 +241,13 @@ impl Function {
         identity_index: u32,
         uri: &str,
         challenge: &[u8],
+        derivation: IdentDerivation,
     ) -> Result<&mut Ident, Error> {
         // Clear function prior to init (executes drop)
         self.clear();
 
         // Setup ident context
-        self.inner = FunctionType::Ident(Ident::new(identity_index, uri, challenge)?);
+        self.inner = FunctionType::Ident(Ident::new(identity_index, uri, challenge, derivation)?);
 
         // Return ident context
         match &mut self.inner {
@@ -234,6 +265,48 @@ impl Function {
         }
     }
 
+    /// Initialise address verification function
+    #[cfg_attr(feature = "noinline", inline(never))]
+    pub fn address_verify_init(&mut self, v: AddressVerify) -> &mut AddressVerify {
+        // Clear function prior to init (executes drop)
+        self.clear();
+
+        // Setup address verification context
+        self.inner = FunctionType::VerifyAddress(v);
+
+        // Return address verification context
+        match &mut self.inner {
+            FunctionType::VerifyAddress(s) => s,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Fetch address verification context
+    pub fn address_verify_ref(&self) -> Option<&AddressVerify> {
+        match &self.inner {
+            FunctionType::VerifyAddress(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Start streamed memo HMAC function
+    #[cfg(feature = "memo")]
+    pub(crate) fn memo_mac_init(&mut self, m: MemoMac) {
+        // Clear function prior to init (executes drop)
+        self.clear();
+
+        self.inner = FunctionType::MemoMac(m);
+    }
+
+    /// Fetch streamed memo HMAC context
+    #[cfg(feature = "memo")]
+    pub(crate) fn memo_mac(&mut self) -> Option<&mut MemoMac> {
+        match &mut self.inner {
+            FunctionType::MemoMac(m) => Some(m),
+            _ => None,
+        }
+    }
+
     /// Clear context, executing drop if required
     #[cfg_attr(feature = "noinline", inline(never))]
     pub fn clear(&mut self) {
@@ -249,10 +322,35 @@ impl Function {
 
         self.inner = FunctionType::None;
     }
+
+    /// Fetch the size of the active function context in bytes
+    /// (0 where no function is active), for memory instrumentation
+    pub fn size(&self) -> usize {
+        match &self.inner {
+            FunctionType::None => 0,
+            #[cfg(feature = "summary")]
+            FunctionType::Summarize(_) => size_of::<Summarizer<MAX_RECORDS>>(),
+            #[cfg(feature = "mlsag")]
+            FunctionType::RingSign(_) => size_of::<RingSigner>(),
+            #[cfg(feature = "ident")]
+            FunctionType::Ident(_) => size_of::<Ident>(),
+            FunctionType::VerifyAddress(_) => size_of::<AddressVerify>(),
+            #[cfg(feature = "memo")]
+            FunctionType::MemoMac(_) => size_of::<MemoMac>(),
+        }
+    }
+
+    /// Fetch the size of the function container in bytes, bounded by the
+    /// largest function context
+    pub const fn max_size() -> usize {
+        size_of::<Self>()
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use core::mem::size_of;
+
     use mc_core::account::{Account, PublicSubaddress};
     use mc_crypto_keys::{RistrettoPrivate, RistrettoPublic};
     use mc_transaction_types::BlockVersion;
@@ -260,11 +358,25 @@ mod test {
     use rand::random;
     use rand_core::OsRng;
 
-    use super::Function;
+    use ledger_mob_apdu::ident::IdentDerivation;
+
+    use super::{
+        super::{
+            memo::{MemoInfo, MemoKind, MemoMac},
+            FogId,
+        },
+        Function, RingSigner, Summarizer, MAX_RECORDS,
+    };
 
     // Set function container to ident mode
     fn ident_init(f: &mut Function) {
-        f.ident_init(0, "test.lol", &random::<[u8; 32]>()).unwrap();
+        f.ident_init(
+            0,
+            "test.lol",
+            &random::<[u8; 32]>(),
+            IdentDerivation::Slip13,
+        )
+        .unwrap();
     }
 
     // Set function container to summary generator mode
@@ -288,6 +400,8 @@ mod test {
             2,
             account.view_private_key(),
             &change,
+            &change,
+            &change,
         )
         .unwrap();
     }
@@ -316,6 +430,31 @@ mod test {
         .unwrap();
     }
 
+    // Set function container to address verification mode
+    fn address_init(f: &mut Function) {
+        f.address_verify_init(super::AddressVerify::new(
+            random(),
+            random(),
+            FogId::MobMain,
+            random(),
+        ));
+    }
+
+    // Set function container to memo HMAC mode
+    fn memo_init(f: &mut Function) {
+        let kind = MemoKind::AuthenticatedSender.to_bytes();
+        let info = MemoInfo::decode(&kind, &[0u8; 48]).unwrap();
+
+        f.memo_mac_init(MemoMac::new(
+            &random(),
+            random(),
+            &RistrettoPublic::from(&RistrettoPrivate::from_random(&mut OsRng {})).into(),
+            &RistrettoPublic::from(&RistrettoPrivate::from_random(&mut OsRng {})).into(),
+            kind,
+            info,
+        ));
+    }
+
     fn clear(f: &mut Function) {
         f.clear();
     }
@@ -326,7 +465,14 @@ mod test {
         let mut f = Function::new();
 
         // Collect state transition functions
-        let states = &[ident_init, summary_init, ring_init, clear];
+        let states = &[
+            ident_init,
+            summary_init,
+            ring_init,
+            address_init,
+            memo_init,
+            clear,
+        ];
 
         // Iterate through possible state transitions
         for i in 0..states.len() {
@@ -338,4 +484,22 @@ mod test {
             }
         }
     }
+
+    /// Active function size is reported per-variant and bounded by the container
+    #[test]
+    fn function_size() {
+        let mut f = Function::new();
+        assert_eq!(f.size(), 0);
+
+        ring_init(&mut f);
+        assert_eq!(f.size(), size_of::<RingSigner>());
+        assert!(f.size() <= Function::max_size());
+
+        summary_init(&mut f);
+        assert_eq!(f.size(), size_of::<Summarizer<MAX_RECORDS>>());
+        assert!(f.size() <= Function::max_size());
+
+        clear(&mut f);
+        assert_eq!(f.size(), 0);
+    }
 }
@@ -3,6 +3,7 @@
 use heapless::{String, Vec};
 
 use byteorder::{ByteOrder, LittleEndian};
+use ledger_mob_apdu::ident::{IdentDerivation, IDENT_CHALLENGE_MAX, IDENT_URI_MAX};
 use mc_core::slip10::Slip10Key;
 use sha2::{Digest, Sha256};
 use strum::{EnumIter, EnumString};
@@ -11,6 +12,7 @@ use super::{Error, Output};
 
 /// Identity challenge state
 #[derive(Copy, Clone, Debug, PartialEq, Default, EnumString, EnumIter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IdentState {
     /// Challenge loaded, pending approval
     #[default]
@@ -26,14 +28,21 @@ pub struct Ident {
     /// SLIP-0017 account index (note this differs from SLIP-0010)
     pub identity_index: u32,
     /// Identity URI
-    pub identity_uri: String<64>,
+    pub identity_uri: String<IDENT_URI_MAX>,
     /// Challenge to be signed
-    pub challenge: Vec<u8, 64>,
+    pub challenge: Vec<u8, IDENT_CHALLENGE_MAX>,
+    /// Identity key derivation
+    pub derivation: IdentDerivation,
 }
 
 impl Ident {
     /// Create a new ident context
-    pub fn new(identity_index: u32, uri: &str, challenge: &[u8]) -> Result<Self, Error> {
+    pub fn new(
+        identity_index: u32,
+        uri: &str,
+        challenge: &[u8],
+        derivation: IdentDerivation,
+    ) -> Result<Self, Error> {
         let identity_uri = String::try_from(uri).map_err(|_| Error::InvalidLength)?;
         let challenge = Vec::try_from(challenge).map_err(|_| Error::InvalidLength)?;
 
@@ -41,6 +50,7 @@ impl Ident {
             identity_index,
             identity_uri,
             challenge,
+            derivation,
         })
     }
 
@@ -52,9 +62,13 @@ impl Ident {
         &self.challenge
     }
 
+    pub fn derivation(&self) -> IdentDerivation {
+        self.derivation
+    }
+
     /// Compute path for identity key derivation
     pub fn path(&self) -> [u32; 5] {
-        derive_bip32(&self.identity_uri, self.identity_index)
+        derive_bip32(&self.identity_uri, self.identity_index, self.derivation)
     }
 
     /// Compute identity challenge signature using the provide private key
@@ -77,10 +91,11 @@ impl Ident {
     }
 }
 
-/// SLIP-0013 path derivation
+/// SLIP-0013 path derivation, with the purpose set by the provided
+/// [IdentDerivation] (SLIP-0017 shares the SLIP-0013 URI hashing)
 ///
 /// https://github.com/satoshilabs/slips/blob/master/slip-0013.md
-pub(crate) fn derive_bip32(uri: &str, index: u32) -> [u32; 5] {
+pub(crate) fn derive_bip32(uri: &str, index: u32, derivation: IdentDerivation) -> [u32; 5] {
     // Build URI/index hash
     let mut hasher = Sha256::new();
 
@@ -93,7 +108,7 @@ pub(crate) fn derive_bip32(uri: &str, index: u32) -> [u32; 5] {
 
     // Setup derivation path
     let mut p = [0u32; 5];
-    p[0] = 13 | (1 << 31);
+    p[0] = derivation.purpose() | (1 << 31);
     for i in 0..4 {
         p[i + 1] = LittleEndian::read_u32(&b[i * 4..]) | (1 << 31);
     }
@@ -105,7 +120,7 @@ pub(crate) fn derive_bip32(uri: &str, index: u32) -> [u32; 5] {
 mod test {
     use ed25519_dalek::{SigningKey, VerifyingKey};
 
-    use super::derive_bip32;
+    use super::{derive_bip32, IdentDerivation};
     use ledger_mob_tests::ident::{Vector, VECTORS};
 
     #[test]
@@ -114,11 +129,24 @@ mod test {
             uri, index, path, ..
         } in VECTORS
         {
-            let p = derive_bip32(uri, *index);
+            let p = derive_bip32(uri, *index, IdentDerivation::Slip13);
             assert_eq!(&p, path, "derivation path mismatch");
         }
     }
 
+    #[test]
+    fn slip0017_derive_path() {
+        for Vector {
+            uri, index, path, ..
+        } in VECTORS
+        {
+            // SLIP-0017 differs from SLIP-0013 only in the purpose
+            let p = derive_bip32(uri, *index, IdentDerivation::Slip17);
+            assert_eq!(p[0], 17 | (1 << 31), "purpose mismatch");
+            assert_eq!(&p[1..], &path[1..], "derivation path mismatch");
+        }
+    }
+
     #[test]
     fn slip0013_derive_full() {
         for v in VECTORS {
@@ -126,7 +154,7 @@ mod test {
             let seed = v.seed();
 
             // Generate path
-            let p = derive_bip32(v.uri, v.index);
+            let p = derive_bip32(v.uri, v.index, IdentDerivation::Slip13);
             assert_eq!(&p, &v.path, "derivation path mismatch");
 
             // Derive private key
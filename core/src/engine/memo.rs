@@ -0,0 +1,430 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Memo type table and payload decoding, used to validate memo signing
+//! requests and to display memo intent during transaction approval.
+//!
+//! See [MCIP-0004](https://github.com/mobilecoinfoundation/mcips/blob/main/text/0004-recoverable-transaction-history.md)
+//! for memo type definitions and layouts.
+
+#[cfg(feature = "memo")]
+use hkdf::hmac::{Hmac, Mac};
+use num_enum::TryFromPrimitive;
+#[cfg(feature = "memo")]
+use sha2::Sha512;
+use strum::{Display, EnumIter};
+
+use mc_core::account::ShortAddressHash;
+#[cfg(feature = "memo")]
+use mc_core::keys::{SubaddressViewPublic, TxOutPublic};
+#[cfg(feature = "memo")]
+use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPublic};
+
+#[cfg(feature = "memo")]
+use ledger_mob_apdu::tx::MEMO_PAYLOAD_MAX;
+
+use super::Error;
+
+/// Standard memo types, big-endian encoded as two-byte `kind` values
+#[derive(Copy, Clone, PartialEq, Debug, Display, EnumIter, TryFromPrimitive)]
+#[repr(u16)]
+pub enum MemoKind {
+    /// Authenticated sender memo
+    AuthenticatedSender = 0x0100,
+    /// Authenticated sender memo with payment request id
+    AuthenticatedSenderWithPaymentRequestId = 0x0101,
+    /// Authenticated sender memo with payment intent id
+    AuthenticatedSenderWithPaymentIntentId = 0x0102,
+    /// Destination memo
+    Destination = 0x0200,
+    /// Destination memo with payment request id
+    DestinationWithPaymentRequestId = 0x0203,
+    /// Destination memo with payment intent id
+    DestinationWithPaymentIntentId = 0x0204,
+}
+
+impl MemoKind {
+    /// Resolve a [MemoKind] from the two-byte memo type
+    pub fn from_bytes(kind: &[u8; 2]) -> Option<Self> {
+        Self::try_from(u16::from_be_bytes(*kind)).ok()
+    }
+
+    /// Fetch the two-byte memo type for a [MemoKind]
+    pub fn to_bytes(&self) -> [u8; 2] {
+        (*self as u16).to_be_bytes()
+    }
+
+    /// Check whether a memo kind is authenticated via a category 1 HMAC
+    /// (and thus may be signed by the device)
+    pub fn is_authenticated(&self) -> bool {
+        matches!(
+            self,
+            MemoKind::AuthenticatedSender
+                | MemoKind::AuthenticatedSenderWithPaymentRequestId
+                | MemoKind::AuthenticatedSenderWithPaymentIntentId
+        )
+    }
+
+    /// Short label for display
+    pub fn label(&self) -> &'static str {
+        match self {
+            MemoKind::AuthenticatedSender => "Sender",
+            MemoKind::AuthenticatedSenderWithPaymentRequestId => "Payment request",
+            MemoKind::AuthenticatedSenderWithPaymentIntentId => "Payment intent",
+            MemoKind::Destination => "Destination",
+            MemoKind::DestinationWithPaymentRequestId => "Dest. request",
+            MemoKind::DestinationWithPaymentIntentId => "Dest. intent",
+        }
+    }
+}
+
+/// Decoded memo information for display
+#[derive(Clone, PartialEq, Debug)]
+pub struct MemoInfo {
+    /// Memo kind
+    pub kind: MemoKind,
+    /// Short hash of the sender (authenticated sender memos)
+    /// or recipient (destination memos) address
+    pub address_hash: ShortAddressHash,
+    /// Payment request or intent id, where present
+    pub payment_id: Option<u64>,
+    /// Destination details, for destination memos
+    pub destination: Option<DestinationInfo>,
+}
+
+/// Destination memo details
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct DestinationInfo {
+    /// Number of recipients
+    pub num_recipients: u8,
+    /// Transaction fee
+    pub fee: u64,
+    /// Total outlay (including fee)
+    pub total_outlay: u64,
+}
+
+impl MemoInfo {
+    /// Decode a memo payload for a given `kind`
+    ///
+    /// Authenticated sender memos require the 48-byte payload (excluding HMAC),
+    /// destination memos require the first 40 bytes of the memo data.
+    ///
+    /// Authenticated sender memos are additionally validated, payment
+    /// request and intent ids must be non-zero and unused bytes must be
+    /// zeroed so the signed payload matches what is displayed.
+    pub fn decode(kind: &[u8; 2], payload: &[u8]) -> Result<Self, Error> {
+        let kind = MemoKind::from_bytes(kind).ok_or(Error::UnsupportedMemo)?;
+
+        // All known memos lead with an address hash
+        if payload.len() < 48 {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut h = [0u8; 16];
+        h.copy_from_slice(&payload[..16]);
+        let address_hash = ShortAddressHash::from(h);
+
+        let read_u64 = |offset: usize| {
+            let mut b = [0u8; 8];
+            b.copy_from_slice(&payload[offset..][..8]);
+            u64::from_be_bytes(b)
+        };
+
+        let (payment_id, destination) = match kind {
+            MemoKind::AuthenticatedSender => {
+                check_unused(&payload[16..48])?;
+                (None, None)
+            }
+            MemoKind::AuthenticatedSenderWithPaymentRequestId
+            | MemoKind::AuthenticatedSenderWithPaymentIntentId => {
+                check_unused(&payload[24..48])?;
+
+                // Zero ids are indistinguishable from an unset field
+                let id = read_u64(16);
+                if id == 0 {
+                    return Err(Error::InvalidMemo);
+                }
+
+                (Some(id), None)
+            }
+            MemoKind::Destination
+            | MemoKind::DestinationWithPaymentRequestId
+            | MemoKind::DestinationWithPaymentIntentId => {
+                // Fee is encoded as a 56-bit big-endian value following num_recipients
+                let mut fee = [0u8; 8];
+                fee[1..].copy_from_slice(&payload[17..24]);
+
+                let destination = DestinationInfo {
+                    num_recipients: payload[16],
+                    fee: u64::from_be_bytes(fee),
+                    total_outlay: read_u64(24),
+                };
+
+                let payment_id = match kind {
+                    MemoKind::Destination => None,
+                    _ => Some(read_u64(32)),
+                };
+
+                (payment_id, Some(destination))
+            }
+        };
+
+        Ok(Self {
+            kind,
+            address_hash,
+            payment_id,
+            destination,
+        })
+    }
+}
+
+/// Category 1 memo HMAC, computed over a payload streamed across one or more
+/// memo signing events (see [TxMemoFlags][ledger_mob_apdu::tx::TxMemoFlags]).
+///
+/// This matches `mc_crypto_memo_mac::compute_category1_hmac` for single-request
+/// payloads, binding the shared secret, TxOut public key, memo kind, and payload.
+#[cfg(feature = "memo")]
+#[derive(Clone)]
+pub(crate) struct MemoMac {
+    mac: Hmac<Sha512>,
+    subaddress_index: u64,
+    tx_public_key: TxOutPublic,
+    receiver_view_public: SubaddressViewPublic,
+    kind: [u8; 2],
+    info: MemoInfo,
+    len: usize,
+}
+
+#[cfg(feature = "memo")]
+impl MemoMac {
+    /// Start a memo HMAC using the sender / receiver shared secret,
+    /// retaining the request header and decoded memo for later chunks
+    pub fn new(
+        shared_secret: &[u8; 32],
+        subaddress_index: u64,
+        tx_public_key: &TxOutPublic,
+        receiver_view_public: &SubaddressViewPublic,
+        kind: [u8; 2],
+        info: MemoInfo,
+    ) -> Self {
+        // HMAC accepts keys of any length
+        let mut mac = <Hmac<Sha512> as Mac>::new_from_slice(shared_secret).unwrap();
+
+        let tx_public: &RistrettoPublic = tx_public_key.as_ref();
+        let tx_public = CompressedRistrettoPublic::from(tx_public);
+
+        mac.update(b"mc-memo-mac");
+        mac.update(tx_public.as_ref());
+        mac.update(&kind);
+
+        Self {
+            mac,
+            subaddress_index,
+            tx_public_key: tx_public_key.clone(),
+            receiver_view_public: receiver_view_public.clone(),
+            kind,
+            info,
+            len: 0,
+        }
+    }
+
+    /// Check a continuation chunk header matches the memo being signed
+    pub fn matches(
+        &self,
+        subaddress_index: u64,
+        tx_public_key: &TxOutPublic,
+        receiver_view_public: &SubaddressViewPublic,
+        kind: &[u8; 2],
+    ) -> bool {
+        self.subaddress_index == subaddress_index
+            && &self.tx_public_key == tx_public_key
+            && &self.receiver_view_public == receiver_view_public
+            && &self.kind == kind
+    }
+
+    /// Update the HMAC with a payload chunk,
+    /// failing where the payload exceeds [MEMO_PAYLOAD_MAX]
+    pub fn update(&mut self, chunk: &[u8]) -> Result<(), Error> {
+        if self.len + chunk.len() > MEMO_PAYLOAD_MAX {
+            return Err(Error::InvalidLength);
+        }
+
+        self.mac.update(chunk);
+        self.len += chunk.len();
+
+        Ok(())
+    }
+
+    /// Finalise the HMAC, returning the decoded memo and truncated HMAC
+    pub fn finalize(&self) -> (MemoInfo, [u8; 16]) {
+        let mut hmac = [0u8; 16];
+        hmac.copy_from_slice(&self.mac.clone().finalize().into_bytes()[..16]);
+
+        (self.info.clone(), hmac)
+    }
+}
+
+/// Check unused memo bytes are zeroed
+fn check_unused(b: &[u8]) -> Result<(), Error> {
+    match b.iter().all(|v| *v == 0) {
+        true => Ok(()),
+        false => Err(Error::InvalidMemo),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn memo_kinds() {
+        for k in MemoKind::iter() {
+            assert_eq!(MemoKind::from_bytes(&k.to_bytes()), Some(k));
+        }
+
+        assert_eq!(MemoKind::from_bytes(&[0x00, 0x00]), None);
+        assert_eq!(MemoKind::from_bytes(&[0x01, 0xff]), None);
+    }
+
+    #[test]
+    fn decode_sender_payment_request() {
+        let mut payload = [0u8; 48];
+        payload[..16].copy_from_slice(&[0xab; 16]);
+        payload[16..24].copy_from_slice(&1234u64.to_be_bytes());
+
+        let m = MemoInfo::decode(
+            &MemoKind::AuthenticatedSenderWithPaymentRequestId.to_bytes(),
+            &payload,
+        )
+        .unwrap();
+
+        assert_eq!(m.address_hash, ShortAddressHash::from([0xab; 16]));
+        assert_eq!(m.payment_id, Some(1234));
+        assert_eq!(m.destination, None);
+    }
+
+    #[test]
+    fn decode_sender_invalid() {
+        let mut payload = [0u8; 48];
+        payload[16..24].copy_from_slice(&1234u64.to_be_bytes());
+
+        for kind in [
+            MemoKind::AuthenticatedSenderWithPaymentRequestId,
+            MemoKind::AuthenticatedSenderWithPaymentIntentId,
+        ] {
+            // Zero payment ids are rejected
+            assert_eq!(
+                MemoInfo::decode(&kind.to_bytes(), &[0u8; 48]),
+                Err(Error::InvalidMemo)
+            );
+
+            // As are non-zero unused bytes
+            let mut p = payload;
+            p[47] = 0x01;
+            assert_eq!(
+                MemoInfo::decode(&kind.to_bytes(), &p),
+                Err(Error::InvalidMemo)
+            );
+        }
+
+        // Plain sender memos must not carry an id
+        assert_eq!(
+            MemoInfo::decode(&MemoKind::AuthenticatedSender.to_bytes(), &payload),
+            Err(Error::InvalidMemo)
+        );
+    }
+
+    #[test]
+    fn decode_destination() {
+        let mut payload = [0u8; 48];
+        payload[..16].copy_from_slice(&[0xcd; 16]);
+        payload[16] = 2;
+        payload[17..24].copy_from_slice(&400_000_000u64.to_be_bytes()[1..]);
+        payload[24..32].copy_from_slice(&10_400_000_000u64.to_be_bytes());
+
+        let m = MemoInfo::decode(&MemoKind::Destination.to_bytes(), &payload).unwrap();
+
+        assert_eq!(m.address_hash, ShortAddressHash::from([0xcd; 16]));
+        assert_eq!(m.payment_id, None);
+        assert_eq!(
+            m.destination,
+            Some(DestinationInfo {
+                num_recipients: 2,
+                fee: 400_000_000,
+                total_outlay: 10_400_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn decode_unsupported() {
+        assert_eq!(
+            MemoInfo::decode(&[0x00, 0x00], &[0u8; 48]),
+            Err(Error::UnsupportedMemo)
+        );
+        assert_eq!(
+            MemoInfo::decode(&MemoKind::AuthenticatedSender.to_bytes(), &[0u8; 16]),
+            Err(Error::InvalidLength)
+        );
+    }
+
+    #[cfg(feature = "memo")]
+    #[test]
+    fn memo_mac_streaming() {
+        use mc_crypto_keys::RistrettoPrivate;
+        use mc_crypto_memo_mac::compute_category1_hmac;
+        use mc_util_from_random::FromRandom;
+        use rand_core::OsRng;
+
+        let shared_secret = [0x5a; 32];
+        let tx_public = RistrettoPublic::from(&RistrettoPrivate::from_random(&mut OsRng {}));
+        let view_public = RistrettoPublic::from(&RistrettoPrivate::from_random(&mut OsRng {}));
+        let kind = MemoKind::AuthenticatedSender.to_bytes();
+
+        let mut payload = [0u8; 48];
+        payload[..16].copy_from_slice(&[0xab; 16]);
+        let info = MemoInfo::decode(&kind, &payload).unwrap();
+
+        let mac = MemoMac::new(
+            &shared_secret,
+            7,
+            &tx_public.into(),
+            &view_public.into(),
+            kind,
+            info,
+        );
+        assert!(mac.matches(7, &tx_public.into(), &view_public.into(), &kind));
+        assert!(!mac.matches(8, &tx_public.into(), &view_public.into(), &kind));
+
+        // Single-request payloads match the standard category 1 HMAC
+        let mut m = mac.clone();
+        m.update(&payload).unwrap();
+        let expected = compute_category1_hmac(
+            &shared_secret,
+            &CompressedRistrettoPublic::from(&tx_public),
+            kind,
+            &payload,
+        );
+        assert_eq!(m.finalize().1, expected);
+
+        // Chunked updates match a single update over the full payload
+        let mut long = [0u8; MEMO_PAYLOAD_MAX];
+        long[..16].copy_from_slice(&[0xab; 16]);
+        long[48..].fill(0xcd);
+
+        let mut a = mac.clone();
+        a.update(&long).unwrap();
+
+        let mut b = mac.clone();
+        for c in long.chunks(48) {
+            b.update(c).unwrap();
+        }
+        assert_eq!(a.finalize().1, b.finalize().1);
+
+        // Payloads are limited to MEMO_PAYLOAD_MAX
+        let mut c = mac;
+        c.update(&long).unwrap();
+        assert_eq!(c.update(&[0u8; 1]), Err(Error::InvalidLength));
+    }
+}
@@ -8,7 +8,21 @@
 use core::ptr::addr_of_mut;
 
 use heapless::Vec;
-use ledger_mob_apdu::tx::TxOnetimeKey;
+use ledger_mob_apdu::{
+    attestation::{KeyAttestation, KEY_ATTESTATION_PATH},
+    audit::AUDIT_ENTRIES_MAX,
+    capabilities::Features,
+    ct::ct_eq,
+    device_nonce::{DEVICE_IDENT_PATH, DEVICE_NONCE_LEN},
+    digest::{
+        digest_session_challenge, digest_subaddress_key_attestation, digest_wallet_key_attestation,
+        payload_mac, SignedTxDigest,
+    },
+    key_path::KeyPath,
+    subaddress_keys::{SUBADDRESS_KEYS_BATCH_MAX, SUBADDRESS_RANGE_MAX},
+    tx::{next_seq, TxExpected, TxOnetimeKey},
+    MOB_PROTO_VERSION, MOB_PROTO_VERSION_MIN,
+};
 use rand_core::{CryptoRngCore, OsRng};
 use strum::{Display, EnumIter, EnumString, EnumVariantNames};
 use zeroize::Zeroize;
@@ -19,25 +33,21 @@ use mc_core::{
     slip10::{wallet_path, Slip10Key},
     subaddress::Subaddress,
 };
-use mc_crypto_keys::{
-    CompressedRistrettoPublic, KexReusablePrivate, RistrettoPrivate, RistrettoPublic,
-};
-#[cfg(feature = "memo")]
-use mc_crypto_memo_mac::compute_category1_hmac;
-use mc_crypto_ring_signature::{onetime_keys::recover_onetime_private_key, KeyImage};
-#[cfg(feature = "summary")]
-pub use mc_transaction_summary::TransactionEntity;
+use mc_crypto_keys::{KexReusablePrivate, RistrettoPrivate, RistrettoPublic};
+use mc_crypto_ring_signature::{onetime_keys::recover_onetime_private_key, CurveScalar, KeyImage};
 #[cfg(feature = "summary")]
 use mc_transaction_summary::TxSummaryUnblindingReport;
+#[cfg(feature = "summary")]
+pub use mc_transaction_summary::{TotalKind, TransactionEntity};
 pub use mc_transaction_types::{BlockVersion, TokenId};
 
-use crate::helpers::sign_authority;
+use crate::helpers::{sign_address, sign_authority};
 
 mod function;
 pub use function::Function;
 
 mod event;
-pub use event::Event;
+pub use event::{Event, ParseError, ReqMeta};
 
 //mod digest;
 pub use ledger_mob_apdu::state::Digest as TxDigest;
@@ -49,10 +59,36 @@ mod error;
 pub use error::Error;
 
 mod ring;
-pub use ring::{RingState, RESP_SIZE, RING_SIZE};
+use ring::ResponseBufferPtr;
+pub use ring::{ResponseBuffer, RingState, RESP_SIZE, RING_SIZE};
 
 mod fog;
-pub use fog::{FogCert, FogId};
+pub use fog::{FogCert, FogId, FogState};
+
+mod memo;
+#[cfg(feature = "memo")]
+use memo::MemoMac;
+pub use memo::{DestinationInfo, MemoInfo, MemoKind};
+
+mod address;
+pub use address::{AddressState, AddressVerify};
+
+mod audit;
+pub use audit::{AuditEntry, AuditKind, AuditLog, AUDIT_LOG_LEN};
+
+mod diagnostics;
+pub use diagnostics::Diagnostics;
+
+mod accounts;
+pub use accounts::{AccountCache, ACCOUNT_CACHE_LEN};
+
+mod watchdog;
+pub use watchdog::TxWatchdog;
+
+#[cfg(feature = "software")]
+mod software;
+#[cfg(feature = "software")]
+pub use software::SoftwareDriver;
 
 #[cfg(feature = "ident")]
 mod ident;
@@ -64,21 +100,62 @@ pub use ident::IdentState;
 #[cfg(feature = "summary")]
 mod summary;
 #[cfg(feature = "summary")]
+use crate::helpers::is_high_value;
+#[cfg(feature = "summary")]
 use summary::OutputAddress;
 #[cfg(feature = "summary")]
-pub use summary::SummaryState;
+pub use summary::{
+    DustTotal, ReservedSubaddress, SummaryState, TombstoneStatus, MAX_TOMBSTONE_BLOCKS,
+};
 
-use crate::helpers::digest_public_address;
+use crate::helpers::{digest_public_address, zeroize_vec};
 
 /// Maximum ring message size
 const MSG_SIZE: usize = 32;
 
+/// Minimum block version supported for transaction signing
+pub const BLOCK_VERSION_MIN: BlockVersion = BlockVersion::THREE;
+
+/// Maximum block version supported for transaction signing
+pub const BLOCK_VERSION_MAX: BlockVersion = BlockVersion::MAX;
+
+/// Maximum number of signed memos per transaction
+#[cfg(not(feature = "constrained"))]
+const MAX_MEMOS: usize = 8;
+#[cfg(feature = "constrained")]
+const MAX_MEMOS: usize = 4;
+
 /// Maximum number of records per summary
-#[cfg(feature = "summary")]
+///
+/// Constrained builds reduce this to shrink the summarizer, the largest
+/// [Function] context
+#[cfg(all(feature = "summary", not(feature = "constrained")))]
 const MAX_RECORDS: usize = 16;
+#[cfg(all(feature = "summary", feature = "constrained"))]
+const MAX_RECORDS: usize = 4;
+
+/// Maximum number of inputs approved via TxSummary
+#[cfg(not(feature = "constrained"))]
+const MAX_INPUTS: usize = 16;
+#[cfg(feature = "constrained")]
+const MAX_INPUTS: usize = 4;
+
+// Every input in a summary must be recorded for checks on signing
+#[cfg(feature = "summary")]
+const _: () = assert!(MAX_INPUTS >= MAX_RECORDS);
+
+/// Input approved via TxSummary, checked against rings on signing
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(not(feature = "summary"), allow(dead_code))]
+struct ApprovedInput {
+    value: u64,
+    token_id: u64,
+    signed: bool,
+}
 
 /// Engine internal state enumeration
 #[derive(Copy, Clone, PartialEq, Debug, EnumString, Display, EnumVariantNames, EnumIter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum State {
     /// Idle state, no transaction running
     Init,
@@ -86,8 +163,16 @@ pub enum State {
     /// Identity request pending approval
     Ident(IdentState),
 
+    /// Address verification pending approval
+    Address(AddressState),
+
+    /// Fog id change pending approval
+    Fog(FogState),
+
     /// Transaction init, building memos
     BuildMemos(usize),
+    /// First memo pending user review (see [Engine::set_memo_review])
+    MemoReview,
     /// Ready to set transaction message
     SetMessage,
     /// Loading TxSummary for verification
@@ -99,35 +184,153 @@ pub enum State {
     Ready,
     /// Signing ring(s)
     SignRing(RingState),
-    /// Transaction denied / aborted
+    /// Transaction denied on-device
     Deny,
+    /// Transaction cancelled on-device following approval (during signing)
+    Cancelled,
+    /// Transaction aborted by the host
+    Aborted,
     /// Transaction failed
     Error,
     /// Transaction complete
     Complete,
 }
 
+bitflags::bitflags! {
+    /// Per-capability approvals for key requests and scanning,
+    /// allowing scanning to be approved without exporting view keys
+    pub struct Approvals: u8 {
+        /// Export of root (wallet) view private keys
+        const WALLET_KEYS = 1 << 0;
+        /// Export of subaddress view private keys
+        const SUBADDRESS_KEYS = 1 << 1;
+        /// Key image computation for scanning
+        const KEY_IMAGES = 1 << 2;
+        /// Key image computation from host-supplied onetime private keys
+        const ONETIME_KEY_IMAGES = 1 << 3;
+    }
+}
+
 /// [Engine] provides hardware-independent support for MobileCoin wallet operations
 ///
 pub struct Engine<DRV: Driver, RNG: CryptoRngCore = OsRng> {
     state: State,
-    unlocked: bool,
+    approvals: Approvals,
+
+    /// Wallet key exports since last unlock
+    wallet_key_count: u32,
+    /// Subaddress key exports since last unlock
+    subaddress_key_count: u32,
+    /// Key exports permitted per scope (wallet keys, subaddress keys) before
+    /// re-approval of that scope is required (0 for unlimited)
+    key_request_limit: u32,
+    /// Key and key image requests serviced since unlock, cleared on lock
+    request_count: u32,
+
+    /// Allow direct message setting (blind signing), bypassing TxSummary verification
+    blind_signing: bool,
+
+    /// Allow host-supplied onetime private keys for ring signing
+    onetime_keys: bool,
+
+    /// Display payment request / intent ids when reviewing signed memos
+    show_payment_ids: bool,
+
+    /// Hold the first memo of each transaction for on-device review prior to signing
+    memo_review: bool,
+
+    /// Fog id requested by the host, pending user confirmation
+    fog_request: FogId,
+
+    /// Active fog id, mirrored from platform storage
+    fog_id: FogId,
+
+    /// Platform inactivity timeout before locking, reported to the host
+    lock_timeout_s: u32,
+
+    /// Account index used for on-device operations
+    default_account: u32,
+
+    /// Protocol version pinned by the host, required prior to transactions
+    proto_version: Option<u8>,
+
+    /// Outstanding device nonce for session challenges (single use)
+    device_nonce: Option<[u8; DEVICE_NONCE_LEN]>,
 
     account_index: u32,
     num_rings: usize,
 
+    /// Expected fee and tombstone block provided with TxInit,
+    /// checked when the summary is built
+    tx_expected: Option<TxExpected>,
+
     digest: TxDigest,
 
+    /// Last accepted transaction request sequence number
+    tx_seq: u8,
+
     message: Vec<u8, MSG_SIZE>,
 
+    /// Decoded memos signed for the current transaction, for display
+    memos: Vec<MemoInfo, MAX_MEMOS>,
+
+    /// First memo of the current transaction, pending user review
+    memo_request: Option<MemoInfo>,
+
+    /// Set once the first memo of the current transaction has been reviewed
+    memo_reviewed: bool,
+
+    /// Digest over signed rings for the current transaction, returned on completion
+    signed_digest: Option<SignedTxDigest>,
+
+    /// Responses retained from the previously completed ring, allowing
+    /// these to be fetched while the next ring is loaded
+    prior_responses: Vec<CurveScalar, RESP_SIZE>,
+
+    /// Inputs from the approved summary (empty for blind signing),
+    /// rings must match an unsigned input
+    approved_inputs: Vec<ApprovedInput, MAX_INPUTS>,
+
+    /// Set on summary completion, requiring rings to match approved inputs
+    summary_approved: bool,
+
+    /// Index of the approved input matched by the current ring
+    ring_input: Option<usize>,
+
+    /// Log of recent approvals, for retrieval by the host
+    audit: AuditLog,
+
+    /// Platform tick count, used to timestamp audit log entries
+    /// and drive the transaction watchdog
+    ticks: u32,
+
+    /// Watchdog aborting transactions not completed within a timeout
+    watchdog: TxWatchdog,
+
+    /// Diagnostic counters, for debugging transport issues
+    diagnostics: Diagnostics,
+
+    /// Account roots derived during the current approved session
+    accounts: AccountCache,
+
     ring_count: usize,
 
+    /// Platform ring response buffer (see [Engine::set_response_buffer])
+    response_buff: Option<ResponseBufferPtr>,
+
     function: Function,
 
     drv: DRV,
     rng: RNG,
 }
 
+/// Clear transaction message on drop (function contexts zeroize on drop)
+impl<DRV: Driver, RNG: CryptoRngCore> Drop for Engine<DRV, RNG> {
+    fn drop(&mut self) {
+        zeroize_vec(&mut self.message);
+    }
+}
+
 /// [`Driver`] trait provides platform support for [`Engine`] instances
 pub trait Driver {
     /// SLIP-0010 derivation for ed25519 keys
@@ -153,13 +356,43 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
     pub const fn new_with_rng(drv: DRV, rng: RNG) -> Self {
         Self {
             state: State::Init,
-            unlocked: false,
+            approvals: Approvals::empty(),
+            wallet_key_count: 0,
+            subaddress_key_count: 0,
+            request_count: 0,
+            key_request_limit: 0,
+            blind_signing: true,
+            onetime_keys: true,
+            show_payment_ids: true,
+            memo_review: false,
+            fog_request: FogId::None,
+            fog_id: FogId::None,
+            lock_timeout_s: 0,
+            default_account: 0,
+            proto_version: None,
             message: Vec::new(),
+            memos: Vec::new(),
+            memo_request: None,
+            memo_reviewed: false,
+            signed_digest: None,
+            prior_responses: Vec::new(),
+            approved_inputs: Vec::new(),
+            summary_approved: false,
+            ring_input: None,
+            audit: AuditLog::new(),
+            ticks: 0,
+            watchdog: TxWatchdog::new(),
+            diagnostics: Diagnostics::new(),
+            accounts: AccountCache::new(),
+            device_nonce: None,
             account_index: 0,
+            tx_expected: None,
             digest: TxDigest::new(),
+            tx_seq: 0,
             num_rings: 0,
             function: Function::new(),
             ring_count: 0,
+            response_buff: None,
             rng,
             drv,
         }
@@ -172,29 +405,125 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
     /// per-field init is okay so long as we init _all_ fields
     pub unsafe fn init(p: *mut Self, drv: DRV, rng: RNG) {
         addr_of_mut!((*p).state).write(State::Init);
-        addr_of_mut!((*p).unlocked).write(false);
+        addr_of_mut!((*p).approvals).write(Approvals::empty());
+        addr_of_mut!((*p).wallet_key_count).write(0);
+        addr_of_mut!((*p).subaddress_key_count).write(0);
+        addr_of_mut!((*p).request_count).write(0);
+        addr_of_mut!((*p).key_request_limit).write(0);
+        addr_of_mut!((*p).blind_signing).write(true);
+        addr_of_mut!((*p).onetime_keys).write(true);
+        addr_of_mut!((*p).show_payment_ids).write(true);
+        addr_of_mut!((*p).memo_review).write(false);
+        addr_of_mut!((*p).fog_request).write(FogId::None);
+        addr_of_mut!((*p).fog_id).write(FogId::None);
+        addr_of_mut!((*p).lock_timeout_s).write(0);
+        addr_of_mut!((*p).default_account).write(0);
+        addr_of_mut!((*p).proto_version).write(None);
         addr_of_mut!((*p).message).write(Vec::new());
+        addr_of_mut!((*p).memos).write(Vec::new());
+        addr_of_mut!((*p).memo_request).write(None);
+        addr_of_mut!((*p).memo_reviewed).write(false);
+        addr_of_mut!((*p).signed_digest).write(None);
+        addr_of_mut!((*p).prior_responses).write(Vec::new());
+        addr_of_mut!((*p).approved_inputs).write(Vec::new());
+        addr_of_mut!((*p).summary_approved).write(false);
+        addr_of_mut!((*p).ring_input).write(None);
+        addr_of_mut!((*p).audit).write(AuditLog::new());
+        addr_of_mut!((*p).ticks).write(0);
+        addr_of_mut!((*p).watchdog).write(TxWatchdog::new());
+        addr_of_mut!((*p).diagnostics).write(Diagnostics::new());
+        addr_of_mut!((*p).accounts).write(AccountCache::new());
+        addr_of_mut!((*p).device_nonce).write(None);
         addr_of_mut!((*p).account_index).write(0);
+        addr_of_mut!((*p).tx_expected).write(None);
         addr_of_mut!((*p).digest).write(TxDigest::new());
+        addr_of_mut!((*p).tx_seq).write(0);
         addr_of_mut!((*p).num_rings).write(0);
         addr_of_mut!((*p).function).write(Function::new());
         addr_of_mut!((*p).ring_count).write(0);
+        addr_of_mut!((*p).response_buff).write(None);
         addr_of_mut!((*p).rng).write(rng);
         addr_of_mut!((*p).drv).write(drv);
     }
 
     /// Handle incoming transaction events
+    pub fn update(&mut self, evt: &Event) -> Result<Output, Error> {
+        self.update_meta(ReqMeta::default(), evt)
+    }
+
+    /// Handle incoming transaction events with the request sequence number
+    /// (see [TxSequenced][ledger_mob_apdu::tx::TxSequenced]), rejecting
+    /// dropped or duplicated state-mutating requests
+    pub fn update_seq(&mut self, seq: u8, evt: &Event) -> Result<Output, Error> {
+        self.update_meta(ReqMeta::seq(seq), evt)
+    }
+
+    /// Handle incoming transaction events with request metadata (see [ReqMeta]),
+    /// rejecting out-of-sequence requests and requests with invalid payload MACs
+    #[cfg_attr(feature = "noinline", inline(never))]
+    pub fn update_meta(&mut self, meta: ReqMeta, evt: &Event) -> Result<Output, Error> {
+        self.diagnostics.events = self.diagnostics.events.wrapping_add(1);
+
+        let r = self.handle_event(meta, evt);
+
+        if r.is_err() {
+            self.diagnostics.errors = self.diagnostics.errors.wrapping_add(1);
+        }
+
+        r
+    }
+
+    /// Handle engine events (via [Engine::update])
     // TODO: rejections / timeouts / failure case for transaction aborted half way through?
     #[cfg_attr(feature = "noinline", inline(never))]
-    pub fn update(&mut self, evt: &Event) -> Result<Output, Error> {
+    fn handle_event(&mut self, meta: ReqMeta, evt: &Event) -> Result<Output, Error> {
         #[cfg(feature = "log")]
         log::debug!("event: {:02x?}", evt);
 
+        // Reject transactions until a protocol version is pinned,
+        // prior to applying the event
+        if let Event::TxInit { .. } = evt {
+            self.check_protocol_version()?;
+        }
+
         // Update state digest (only applied for mutating events)
-        if let Some(h) = evt.hash() {
-            self.digest.update(&h);
+        let staged = match evt.hash() {
+            Some(h) => {
+                // Check payload MAC and sequence prior to applying the event
+                self.check_mac(&meta, &h)?;
+
+                // Hold the first memo for review prior to consuming the sequence number,
+                // the host re-issues the request once approved
+                #[cfg(feature = "memo")]
+                self.check_memo_review(evt)?;
+
+                let prev = (self.tx_seq, self.digest.clone());
+
+                self.check_seq(meta.seq, evt)?;
+
+                self.digest.update(&h);
+
+                Some(prev)
+            }
+            None => None,
+        };
+
+        let r = self.apply_event(evt);
+
+        // Restore the sequence and digest where the event is rejected,
+        // so these are only committed once the event is applied
+        if let (Err(_), Some((seq, digest))) = (&r, staged) {
+            self.tx_seq = seq;
+            self.digest = digest;
         }
 
+        r
+    }
+
+    /// Apply engine events following sequence and digest updates
+    /// (via [Engine::handle_event])
+    #[cfg_attr(feature = "noinline", inline(never))]
+    fn apply_event(&mut self, evt: &Event) -> Result<Output, Error> {
         // Handle events
         // TODO: handle repeated events in expected state to mitigate retransmission
         // due to loss of ACK/responses via unreliable channels.
@@ -203,22 +532,57 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
             (_, Event::None) => (),
 
             // Fetch wallet keys
-            (_, Event::GetWalletKeys { account_index }) => {
-                // Check for unlock state
-                if !self.unlocked {
+            (
+                _,
+                Event::GetWalletKeys {
+                    account_index,
+                    challenge,
+                    path,
+                },
+            ) => {
+                // Check for wallet key approval and remaining exports
+                if !self.is_approved(Approvals::WALLET_KEYS) {
                     return Err(Error::ApprovalPending);
                 }
+                self.check_key_request_limit(Approvals::WALLET_KEYS, 1)?;
 
-                let mut account = self.get_account(*account_index);
+                // Attestations bind the account index only, so may not be
+                // requested for explicit derivation paths
+                if path.is_some() && challenge.is_some() {
+                    return Err(Error::InvalidPath);
+                }
+
+                let mut account = match path {
+                    Some(p) => self.get_account_path(p)?,
+                    None => {
+                        self.cache_account(*account_index);
+                        self.get_account(*account_index)
+                    }
+                };
 
                 let spend_public = account.spend_public_key();
                 let view_private = account.view_private_key().clone();
                 account.zeroize();
 
+                // Attest returned keys where a challenge is provided
+                let attestation = match challenge {
+                    Some(c) => Some(self.key_attestation(&digest_wallet_key_attestation(
+                        c,
+                        *account_index,
+                        view_private.as_ref(),
+                        spend_public.as_ref(),
+                    ))?),
+                    None => None,
+                };
+
+                // Update export counter
+                self.record_key_exports(Approvals::WALLET_KEYS, 1);
+
                 return Ok(Output::WalletKeys {
                     account_index: *account_index,
                     spend_public,
                     view_private,
+                    attestation,
                 });
             }
 
@@ -228,14 +592,29 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
                 Event::GetSubaddressKeys {
                     account_index,
                     subaddress_index,
+                    challenge,
+                    path,
                 },
             ) => {
-                // Check for unlock state
-                if !self.unlocked {
+                // Check for subaddress key approval and remaining exports
+                if !self.is_approved(Approvals::SUBADDRESS_KEYS) {
                     return Err(Error::ApprovalPending);
                 }
+                self.check_key_request_limit(Approvals::SUBADDRESS_KEYS, 1)?;
 
-                let mut account = self.get_account(*account_index);
+                // Attestations bind the account index only, so may not be
+                // requested for explicit derivation paths
+                if path.is_some() && challenge.is_some() {
+                    return Err(Error::InvalidPath);
+                }
+
+                let mut account = match path {
+                    Some(p) => self.get_account_path(p)?,
+                    None => {
+                        self.cache_account(*account_index);
+                        self.get_account(*account_index)
+                    }
+                };
                 let mut subaddress = account.subaddress(*subaddress_index);
                 account.zeroize();
 
@@ -244,161 +623,634 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
                 subaddress.view_private.zeroize();
                 subaddress.spend_private.zeroize();
 
+                // Attest returned keys where a challenge is provided
+                let attestation = match challenge {
+                    Some(c) => Some(self.key_attestation(&digest_subaddress_key_attestation(
+                        c,
+                        *account_index,
+                        *subaddress_index,
+                        view_private.as_ref(),
+                        spend_public.as_ref(),
+                    ))?),
+                    None => None,
+                };
+
+                // Update export counter
+                self.record_key_exports(Approvals::SUBADDRESS_KEYS, 1);
+
                 return Ok(Output::SubaddressKeys {
                     account_index: *account_index,
                     subaddress_index: *subaddress_index,
                     spend_public,
                     view_private,
+                    attestation,
                 });
             }
 
-            // Compute key image for a given subaddress and tx_public_key
+            // Fetch subaddress keys with fog authority signature
             (
                 _,
-                Event::GetKeyImage {
+                Event::GetSubaddressKeysFog {
                     account_index,
                     subaddress_index,
-                    txout_public_key,
+                    fog_id,
                 },
             ) => {
-                // Check for unlock state
-                if !self.unlocked {
+                // Check for subaddress key approval and remaining exports
+                if !self.is_approved(Approvals::SUBADDRESS_KEYS) {
                     return Err(Error::ApprovalPending);
                 }
+                self.check_key_request_limit(Approvals::SUBADDRESS_KEYS, 1)?;
 
-                let r = self.get_key_image(
-                    *account_index,
-                    *subaddress_index,
-                    txout_public_key.as_ref(),
-                );
+                self.cache_account(*account_index);
+                let mut account = self.get_account(*account_index);
+                let mut subaddress = account.subaddress(*subaddress_index);
+                account.zeroize();
 
-                return Ok(r);
-            }
+                let fog_sig: [u8; 64] = match fog_id {
+                    FogId::None => [0u8; 64],
+                    _ => sign_authority(&subaddress.view_private, fog_id.spki()).into(),
+                };
 
-            // Fetch a random value
-            (_, Event::GetRandom) => {
-                let mut value = [0xab; 32];
-                self.rng.fill_bytes(&mut value);
+                let spend_public = subaddress.spend_public_key();
+                let view_private = subaddress.view_private_key().clone();
+                subaddress.view_private.zeroize();
+                subaddress.spend_private.zeroize();
 
-                return Ok(Output::RandomValue { value });
+                // Update export counter
+                self.record_key_exports(Approvals::SUBADDRESS_KEYS, 1);
+
+                return Ok(Output::SubaddressKeysFog {
+                    account_index: *account_index,
+                    subaddress_index: *subaddress_index,
+                    spend_public,
+                    view_private,
+                    fog_id: *fog_id,
+                    fog_sig,
+                });
             }
 
-            // Request identity proof
-            #[cfg(feature = "ident")]
+            // Fetch public keys for a range of subaddresses
             (
-                State::Init | State::Ident(_),
-                Event::IdentSign {
-                    ident_index,
-                    ident_uri,
-                    challenge,
+                _,
+                Event::GetSubaddressRange {
+                    account_index,
+                    start,
+                    count,
                 },
             ) => {
-                // Setup identity context
-                if self
-                    .function
-                    .ident_init(*ident_index, ident_uri, challenge)
-                    .is_err()
+                // Check for subaddress key approval
+                if !self.is_approved(Approvals::SUBADDRESS_KEYS) {
+                    return Err(Error::ApprovalPending);
+                }
+
+                // Check range is valid
+                let count = *count as u64;
+                if count == 0
+                    || count > SUBADDRESS_RANGE_MAX as u64
+                    || start.checked_add(count).is_none()
                 {
-                    self.state = State::Error;
-                    return Err(Error::Unknown);
+                    return Err(Error::InvalidLength);
                 }
 
-                // Move to pending state
-                self.state = State::Ident(IdentState::Pending);
+                self.cache_account(*account_index);
+                let mut account = self.get_account(*account_index);
+                let mut keys = Vec::new();
+
+                for i in *start..*start + count {
+                    let mut subaddress = account.subaddress(i);
+
+                    let view_public = subaddress.view_public_key();
+                    let spend_public = subaddress.spend_public_key();
+                    subaddress.view_private.zeroize();
+                    subaddress.spend_private.zeroize();
+
+                    let _ = keys.push((view_public, spend_public));
+                }
+
+                account.zeroize();
+
+                self.request_count = self.request_count.saturating_add(1);
+
+                return Ok(Output::SubaddressRange {
+                    account_index: *account_index,
+                    start: *start,
+                    keys,
+                });
             }
 
-            // Derive identity and sign the provided challenge
-            #[cfg(feature = "ident")]
-            (State::Ident(s), Event::IdentGet) => {
-                // Check approval state (MOB-02, MOB-05)
-                match s {
-                    IdentState::Pending => return Err(Error::ApprovalPending),
-                    IdentState::Denied => return Err(Error::IdentRejected),
-                    IdentState::Approved => (),
+            // Fetch keys for a batch of subaddresses
+            (
+                _,
+                Event::GetSubaddressKeysBatch {
+                    account_index,
+                    start,
+                    count,
+                },
+            ) => {
+                // Check for subaddress key approval
+                if !self.is_approved(Approvals::SUBADDRESS_KEYS) {
+                    return Err(Error::ApprovalPending);
                 }
 
-                // Fetch signed identity
-                let r = self.get_signed_ident(s);
+                // Check batch is valid
+                let count = *count as u64;
+                if count == 0
+                    || count > SUBADDRESS_KEYS_BATCH_MAX as u64
+                    || start.checked_add(count).is_none()
+                {
+                    return Err(Error::InvalidLength);
+                }
 
-                // Reset engine state
-                self.function.clear();
-                self.state = State::Init;
+                // Reject batches exceeding the remaining exports before deriving keys
+                self.check_key_request_limit(Approvals::SUBADDRESS_KEYS, count as u32)?;
 
-                // Return result
-                return r;
+                self.cache_account(*account_index);
+                let mut account = self.get_account(*account_index);
+                let mut keys = Vec::new();
+
+                for i in *start..*start + count {
+                    let mut subaddress = account.subaddress(i);
+
+                    let spend_public = subaddress.spend_public_key();
+                    let view_private = subaddress.view_private_key().clone();
+                    subaddress.view_private.zeroize();
+                    subaddress.spend_private.zeroize();
+
+                    // Capacity checked above
+                    let _ = keys.push((spend_public, view_private));
+                }
+
+                account.zeroize();
+
+                // Update export counter
+                self.record_key_exports(Approvals::SUBADDRESS_KEYS, count as u32);
+
+                return Ok(Output::SubaddressKeysBatch {
+                    account_index: *account_index,
+                    start: *start,
+                    keys,
+                });
             }
 
-            // Initialise transaction with private key and value
+            // Fetch public address with fog information
             (
                 _,
-                Event::TxInit {
+                Event::GetPublicAddress {
                     account_index,
-                    num_rings,
+                    subaddress_index,
+                    fog_id,
                 },
             ) => {
-                // Set common transaction information
-                self.account_index = *account_index;
-                self.num_rings = *num_rings as usize;
-                self.ring_count = 0;
-                self.digest = TxDigest::from_random(&mut self.rng);
+                // Check for subaddress key approval
+                if !self.is_approved(Approvals::SUBADDRESS_KEYS) {
+                    return Err(Error::ApprovalPending);
+                }
+
+                self.cache_account(*account_index);
+                let mut account = self.get_account(*account_index);
+                let mut subaddress = account.subaddress(*subaddress_index);
+                account.zeroize();
 
-                // TODO: start timeout for transaction completion
+                let fog_sig: [u8; 64] = match fog_id {
+                    FogId::None => [0u8; 64],
+                    _ => sign_authority(&subaddress.view_private, fog_id.spki()).into(),
+                };
 
-                // Set initial tx state and ensure function is
-                // clear so prior report cannot be reused.
-                self.state = State::BuildMemos(0);
-                self.function.clear();
-                self.message.clear();
+                let p = PublicSubaddress::from(&subaddress);
+                subaddress.view_private.zeroize();
+                subaddress.spend_private.zeroize();
+
+                self.request_count = self.request_count.saturating_add(1);
+
+                return Ok(Output::PublicAddress {
+                    view_public: p.view_public,
+                    spend_public: p.spend_public,
+                    fog_id: *fog_id,
+                    fog_sig,
+                });
             }
 
-            // Sign memos for the transaction
-            #[cfg(feature = "memo")]
+            // Compute key image for a given subaddress and tx_public_key
             (
-                State::BuildMemos(n),
-                Event::TxSignMemo {
+                _,
+                Event::GetKeyImage {
+                    account_index,
                     subaddress_index,
-                    tx_public_key,
-                    receiver_view_public,
-                    kind,
-                    payload,
+                    txout_public_key,
                 },
             ) => {
-                // NOTE: these must be signed prior to the summary construction,
-                // so must be allowed prior to transaction approval.
-                // this has been deemed non-critical as signed memos are not
-                // _useable_ until included in a transaction (and the device
-                // must already be unlocked to be interactive).
+                // Check for key image approval
+                if !self.is_approved(Approvals::KEY_IMAGES) {
+                    return Err(Error::ApprovalPending);
+                }
 
-                // Perform memo signing
-                let r = self.memo_sign(
+                self.cache_account(*account_index);
+                let r = self.get_key_image(
+                    *account_index,
                     *subaddress_index,
-                    tx_public_key,
-                    receiver_view_public,
-                    kind,
-                    payload,
+                    txout_public_key.as_ref(),
                 );
 
-                // Update memo counter
-                self.state = State::BuildMemos(n + 1);
+                self.request_count = self.request_count.saturating_add(1);
 
-                // There is no condition under which the application can
-                // sign enough memos for this to overflow (MOB-06.10)
-                assert_ne!(n + 1, usize::MAX);
-
-                // Return HMAC
                 return Ok(r);
             }
 
-            // Set transaction message (direct, bypasses TxSummary verification)
-            (State::SetMessage | State::BuildMemos(..), Event::TxSetMessage(m)) => {
-                // Check message length
-                if m.len() > self.message.capacity() {
-                    return Err(Error::InvalidLength);
-                }
-                // Set message value
-                self.message.clear();
-                self.message
-                    .extend_from_slice(m)
+            // Fetch key image for a host-supplied onetime private key
+            (
+                _,
+                Event::GetKeyImageFromKey {
+                    account_index,
+                    subaddress_index,
+                    onetime_private_key,
+                },
+            ) => {
+                // Check for explicit approval, this is not implied by KEY_IMAGES
+                if !self.is_approved(Approvals::ONETIME_KEY_IMAGES) {
+                    return Err(Error::ApprovalPending);
+                }
+
+                // Derive the subaddress spend public key for host checks
+                self.cache_account(*account_index);
+                let mut account = self.get_account(*account_index);
+                let mut subaddress = account.subaddress(*subaddress_index);
+                let spend_public = subaddress.spend_public_key();
+
+                account.zeroize();
+                subaddress.view_private.zeroize();
+                subaddress.spend_private.zeroize();
+
+                self.request_count = self.request_count.saturating_add(1);
+
+                return Ok(Output::KeyImage {
+                    account_index: *account_index,
+                    subaddress_index: *subaddress_index,
+                    key_image: KeyImage::from(onetime_private_key.as_ref()),
+                    spend_public,
+                });
+            }
+
+            // Fetch a random value
+            (_, Event::GetRandom { len }) => {
+                let mut value = Vec::new();
+                if value.resize(*len as usize, 0xab).is_err() {
+                    return Err(Error::InvalidLength);
+                }
+                self.rng.fill_bytes(&mut value);
+
+                return Ok(Output::RandomValue { value });
+            }
+
+            // Generate a device nonce, replacing any outstanding nonce
+            (_, Event::GetDeviceNonce) => {
+                let mut nonce = [0u8; DEVICE_NONCE_LEN];
+                self.rng.fill_bytes(&mut nonce);
+                self.device_nonce = Some(nonce);
+
+                return Ok(Output::DeviceNonce { nonce });
+            }
+
+            // Sign a host challenge bound to the outstanding device nonce
+            (_, Event::SignSessionChallenge { challenge }) => {
+                // Nonces are single use, consume prior to signing
+                let nonce = match self.device_nonce.take() {
+                    Some(v) => v,
+                    None => return Err(Error::InvalidState),
+                };
+
+                return self.session_challenge(&digest_session_challenge(&nonce, challenge));
+            }
+
+            // Fetch key export counters
+            (_, Event::GetKeyCounters) => {
+                return Ok(Output::KeyCounters {
+                    wallet_keys: self.wallet_key_count,
+                    subaddress_keys: self.subaddress_key_count,
+                    limit: self.key_request_limit,
+                });
+            }
+
+            // Fetch device settings
+            (_, Event::GetSettings) => {
+                return Ok(Output::Settings {
+                    fog_id: self.fog_id,
+                    blind_signing: self.blind_signing(),
+                    onetime_keys: self.onetime_keys,
+                    lock_timeout_s: self.lock_timeout_s,
+                    account_index: self.default_account,
+                });
+            }
+
+            // Fetch summary loading / ring signing progress
+            (_, Event::GetProgress) => {
+                let (step, total) = self.progress_steps().unwrap_or((0, 0));
+
+                return Ok(Output::Progress {
+                    state: self.state,
+                    percent: self.progress().unwrap_or(0) as u8,
+                    step: step.min(u16::MAX as usize) as u16,
+                    total: total.min(u16::MAX as usize) as u16,
+                });
+            }
+
+            // Zeroize cached account roots
+            (_, Event::InvalidateAccounts) => self.invalidate_accounts(),
+
+            // Fetch engine diagnostic counters
+            (_, Event::GetDiagnostics) => {
+                let d = self.diagnostics;
+
+                return Ok(Output::Diagnostics {
+                    events: d.events,
+                    errors: d.errors,
+                    resets: d.resets,
+                    rings_signed: d.rings_signed,
+                });
+            }
+
+            // Fetch supported protocol versions and features
+            (_, Event::GetCapabilities) => {
+                return Ok(Output::Capabilities {
+                    proto_min: MOB_PROTO_VERSION_MIN,
+                    proto_max: MOB_PROTO_VERSION,
+                    features: Self::features().bits(),
+                });
+            }
+
+            // Pin protocol version for subsequent requests
+            (_, Event::SetProtocolVersion { version }) => {
+                if !(MOB_PROTO_VERSION_MIN..=MOB_PROTO_VERSION).contains(version) {
+                    return Err(Error::UnsupportedProtocol);
+                }
+
+                self.proto_version = Some(*version);
+            }
+
+            // Fetch approval audit log entries
+            (_, Event::GetAuditLog { start }) => {
+                let entries = self
+                    .audit
+                    .iter()
+                    .skip(*start as usize)
+                    .take(AUDIT_ENTRIES_MAX)
+                    .copied()
+                    .collect();
+
+                return Ok(Output::AuditLog {
+                    start: *start,
+                    num_held: self.audit.len() as u8,
+                    entries,
+                });
+            }
+
+            // Request identity proof
+            #[cfg(feature = "ident")]
+            (
+                State::Init | State::Ident(_),
+                Event::IdentSign {
+                    ident_index,
+                    ident_uri,
+                    challenge,
+                    derivation,
+                },
+            ) => {
+                // Setup identity context
+                if self
+                    .function
+                    .ident_init(*ident_index, ident_uri, challenge, *derivation)
+                    .is_err()
+                {
+                    self.state = State::Error;
+                    return Err(Error::Unknown);
+                }
+
+                // Move to pending state
+                self.state = State::Ident(IdentState::Pending);
+            }
+
+            // Derive identity and sign the provided challenge
+            #[cfg(feature = "ident")]
+            (State::Ident(s), Event::IdentGet) => {
+                // Check approval state (MOB-02, MOB-05)
+                match s {
+                    IdentState::Pending => return Err(Error::ApprovalPending),
+                    IdentState::Denied => return Err(Error::IdentRejected),
+                    IdentState::Approved => (),
+                }
+
+                // Fetch signed identity
+                let r = self.get_signed_ident(s);
+
+                // Reset engine state
+                self.function.clear();
+                self.state = State::Init;
+
+                // Return result
+                return r;
+            }
+
+            // Request on-device address verification
+            (
+                State::Init | State::Address(_),
+                Event::AddressVerify {
+                    account_index,
+                    subaddress_index,
+                    fog_id,
+                    challenge,
+                },
+            ) => {
+                // Setup address verification context
+                self.function.address_verify_init(AddressVerify::new(
+                    *account_index,
+                    *subaddress_index,
+                    *fog_id,
+                    *challenge,
+                ));
+
+                // Move to pending state
+                self.state = State::Address(AddressState::Pending);
+            }
+
+            // Fetch signed address following approval
+            (State::Address(s), Event::AddressGet) => {
+                // Check approval state
+                match s {
+                    AddressState::Pending => return Err(Error::ApprovalPending),
+                    AddressState::Denied => return Err(Error::AddressRejected),
+                    AddressState::Approved => (),
+                }
+
+                // Fetch signed address
+                let r = self.get_signed_address();
+
+                // Reset engine state
+                self.function.clear();
+                self.state = State::Init;
+
+                // Return result
+                return r;
+            }
+
+            // Request on-device confirmation of a fog id change
+            (State::Init | State::Fog(_), Event::SetFogId { fog_id }) => {
+                self.fog_request = *fog_id;
+
+                // Move to pending state
+                self.state = State::Fog(FogState::Pending);
+            }
+
+            // Initialise transaction with private key and value
+            (
+                _,
+                Event::TxInit {
+                    account_index,
+                    num_rings,
+                    expected,
+                },
+            ) => {
+                // Set common transaction information
+                self.account_index = *account_index;
+                self.num_rings = *num_rings as usize;
+                self.tx_expected = *expected;
+                self.ring_count = 0;
+                self.digest = TxDigest::from_random(&mut self.rng);
+
+                // Start watchdog for transaction completion
+                self.watchdog.start(self.ticks);
+
+                // Set initial tx state and ensure function is
+                // clear so prior report cannot be reused.
+                self.state = State::BuildMemos(0);
+                self.function.clear();
+                zeroize_vec(&mut self.message);
+                zeroize_vec(&mut self.prior_responses);
+                self.memos.clear();
+                self.memo_request = None;
+                self.memo_reviewed = false;
+                self.signed_digest = Some(SignedTxDigest::new());
+                self.clear_approved_inputs();
+            }
+
+            // Sign memos for the transaction
+            #[cfg(feature = "memo")]
+            (
+                State::BuildMemos(n),
+                Event::TxSignMemo {
+                    subaddress_index,
+                    tx_public_key,
+                    receiver_view_public,
+                    kind,
+                    payload,
+                    payload_len,
+                    continuation,
+                    more,
+                },
+            ) => {
+                // NOTE: these must be signed prior to the summary construction,
+                // so must be allowed prior to transaction approval.
+                // this has been deemed non-critical as signed memos are not
+                // _useable_ until included in a transaction (and the device
+                // must already be unlocked to be interactive).
+
+                // Check chunk length, all but the last chunk must be full
+                // and the first chunk contains the standard memo body
+                let len = *payload_len as usize;
+                if len == 0 || len > payload.len() {
+                    return Err(Error::InvalidLength);
+                }
+                if (*more || !*continuation) && len != payload.len() {
+                    return Err(Error::InvalidLength);
+                }
+                let payload = &payload[..len];
+
+                let r = match continuation {
+                    // Start signing a new memo
+                    false => {
+                        // Check memo kind is supported and decode for display
+                        let memo = MemoInfo::decode(kind, payload)?;
+                        if !memo.kind.is_authenticated() {
+                            return Err(Error::UnsupportedMemo);
+                        }
+                        if self.memos.is_full() {
+                            return Err(Error::InvalidLength);
+                        }
+
+                        let mut m = self.memo_mac(
+                            *subaddress_index,
+                            tx_public_key,
+                            receiver_view_public,
+                            *kind,
+                            memo,
+                        );
+                        m.update(payload)?;
+
+                        // Retain context where further chunks follow
+                        match more {
+                            true => {
+                                self.function.memo_mac_init(m);
+                                None
+                            }
+                            false => Some(m.finalize()),
+                        }
+                    }
+                    // Continue signing a streamed memo
+                    true => {
+                        let m = self.function.memo_mac().ok_or(Error::UnexpectedEvent)?;
+
+                        // Chunks must match the memo being signed
+                        if !m.matches(*subaddress_index, tx_public_key, receiver_view_public, kind)
+                        {
+                            return Err(Error::InvalidMemo);
+                        }
+                        m.update(payload)?;
+
+                        match more {
+                            true => None,
+                            false => {
+                                let r = m.finalize();
+                                self.function.clear();
+                                Some(r)
+                            }
+                        }
+                    }
+                };
+
+                // Return HMAC once the memo is complete
+                if let Some((memo, hmac)) = r {
+                    self.memos.push(memo).map_err(|_| Error::InvalidLength)?;
+
+                    // Update memo counter
+                    self.state = State::BuildMemos(n + 1);
+
+                    // There is no condition under which the application can
+                    // sign enough memos for this to overflow (MOB-06.10)
+                    assert_ne!(n + 1, usize::MAX);
+
+                    return Ok(Output::MemoHmac {
+                        state: self.state,
+                        digest: self.digest.clone(),
+                        hmac,
+                    });
+                }
+            }
+
+            // Set transaction message (direct, bypasses TxSummary verification)
+            (State::SetMessage | State::BuildMemos(..), Event::TxSetMessage(m)) => {
+                // Reject blind signing where disabled, forcing TxSummary verification
+                #[cfg(feature = "summary")]
+                if !self.blind_signing {
+                    return Err(Error::BlindSigningDisabled);
+                }
+
+                // Check message length
+                if m.len() > self.message.capacity() {
+                    return Err(Error::InvalidLength);
+                }
+                // Set message value
+                zeroize_vec(&mut self.message);
+                self.message
+                    .extend_from_slice(m)
                     .map_err(|_| Error::InvalidLength)?;
 
                 self.state = State::Pending;
@@ -429,6 +1281,21 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
                 return self.tx_summary_update(evt);
             }
 
+            // Read back TxSummary report prior to approval / signing
+            #[cfg(feature = "summary")]
+            (State::Pending | State::Ready, Event::TxSummaryReport { start }) => {
+                return self.get_report_entries(*start);
+            }
+
+            // Abort transaction at host request, returning the final digest
+            (State::Init | State::Ident(_) | State::Address(_) | State::Fog(_), Event::TxAbort) => {
+                return Err(Error::UnexpectedEvent);
+            }
+            (_, Event::TxAbort) => {
+                self.tx_teardown();
+                self.state = State::Aborted;
+            }
+
             // Pending user approval (tbd, expect changes when TxSummary lands)
             (State::Pending, _) => {
                 // No change, reply with pending state
@@ -446,6 +1313,12 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
                     onetime_private_key,
                 },
             ) => {
+                // Reject host-supplied keys where disabled, ensuring only
+                // seed-derived keys are used for signing
+                if onetime_private_key.is_some() && !self.onetime_keys {
+                    return Err(Error::OnetimeKeysDisabled);
+                }
+
                 return self.ring_init(
                     *ring_size,
                     *value,
@@ -463,18 +1336,73 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
                 | Event::TxAddTxout(..)
                 | Event::TxSign
                 | Event::TxGetKeyImage { .. }
-                | Event::TxGetResponse { .. },
+                | Event::TxGetResponse { .. }
+                | Event::TxGetResponses { .. },
             ) => {
                 return self.ring_update(evt);
             }
 
+            // Fetch responses retained from the previously completed ring
+            (State::SignRing(..), Event::TxGetPriorResponse { index }) => {
+                let scalar = match self.prior_responses.get(*index as usize) {
+                    Some(v) => (*v).into(),
+                    None => return Err(Error::UnexpectedEvent),
+                };
+
+                return Ok(Output::TxResponse {
+                    ring_index: *index,
+                    scalar,
+                });
+            }
+            (State::SignRing(..), Event::TxGetPriorResponses { start, count }) => {
+                let scalars = ring::get_responses(&self.prior_responses, *start, *count)?;
+
+                return Ok(Output::TxResponses {
+                    start: *start,
+                    scalars,
+                });
+            }
+
+            // Reject further signing requests once cancelled on-device
+            (
+                State::Cancelled,
+                Event::TxRingInit { .. }
+                | Event::TxSetBlinding { .. }
+                | Event::TxAddTxout(..)
+                | Event::TxSign
+                | Event::TxGetKeyImage { .. }
+                | Event::TxGetResponse { .. }
+                | Event::TxGetResponses { .. }
+                | Event::TxGetPriorResponse { .. }
+                | Event::TxGetPriorResponses { .. }
+                | Event::TxComplete,
+            ) => {
+                return Err(Error::TxCancelled);
+            }
+
             // Complete transaction
             (_, Event::TxComplete) => {
-                // Clear sign context
+                // Finalise digest over the signed transaction
+                let signed_digest = self
+                    .signed_digest
+                    .take()
+                    .map(|d| d.finalise(&self.message))
+                    .unwrap_or_default();
+
+                // Clear sign context and message
                 self.function.clear();
+                zeroize_vec(&mut self.message);
+                zeroize_vec(&mut self.prior_responses);
 
                 // Return to init state
                 self.state = State::Complete;
+
+                return Ok(Output::TxComplete {
+                    state: self.state,
+                    seq: self.tx_seq,
+                    digest: self.digest.clone(),
+                    signed_digest,
+                });
             }
 
             // Fetch transaction state / information
@@ -492,7 +1420,9 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         // Default to returning updated state
         Ok(Output::State {
             state: self.state,
+            seq: self.tx_seq,
             digest: self.digest.clone(),
+            timeout_s: self.tx_timeout_remaining().unwrap_or(0),
         })
     }
 
@@ -501,9 +1431,14 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         self.state
     }
 
-    /// Fetch an [`Account`] instance for a given wallet index
+    /// Fetch an [`Account`] instance for a given wallet index,
+    /// using the session [`AccountCache`] where available
     #[cfg_attr(feature = "noinline", inline(never))]
     pub fn get_account(&self, account_index: u32) -> Account {
+        if let Some(a) = self.accounts.get(account_index) {
+            return a.clone();
+        }
+
         let path = wallet_path(account_index);
         let seed = self.drv.slip10_derive_ed25519(&path);
         let a = Account::from(&seed);
@@ -514,78 +1449,558 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         a
     }
 
-    /// Fetch a Subaddress instance for a given wallet and subaddress index
-    #[cfg_attr(feature = "noinline", inline(never))]
-    pub fn get_subaddress(
-        &self,
-        account_index: u32,
-        subaddress_index: u64,
-        fog_id: FogId,
-    ) -> OutputAddress {
-        let mut account = self.get_account(account_index);
-        let mut subaddress = account.subaddress(subaddress_index);
+    /// Fetch the [`Account`] for an explicit derivation path
+    ///
+    /// Paths must be fully hardened and within the application prefix,
+    /// accounts derived this way are not cached.
+    fn get_account_path(&self, path: &KeyPath) -> Result<Account, Error> {
+        // Reject paths outside the application prefix, including the
+        // attestation and device identity key paths (these must never be exported)
+        if !path.is_permitted()
+            || path.as_slice() == KEY_ATTESTATION_PATH.as_slice()
+            || path.as_slice() == DEVICE_IDENT_PATH.as_slice()
+        {
+            return Err(Error::InvalidPath);
+        }
 
-        // Zeroize private account keys (MOB-01.x)
-        account.zeroize();
+        let seed = self.drv.slip10_derive_ed25519(path.as_slice());
+        let a = Account::from(&seed);
 
-        let sig: Option<[u8; 64]> = match fog_id {
-            FogId::None => None,
-            _ => Some(sign_authority(&subaddress.view_private, fog_id.spki())).map(|v| v.into()),
-        };
+        // Clear seed following use (MOB-01.4)
+        drop(seed);
 
-        let p = PublicSubaddress::from(&subaddress);
+        Ok(a)
+    }
 
-        // Zeroize private subaddress keys (MOB-01.x)
-        subaddress.view_private.zeroize();
-        subaddress.spend_private.zeroize();
+    /// Sign a key attestation digest using the device attestation key
+    /// (see [ledger_mob_apdu::attestation])
+    fn key_attestation(&self, digest: &[u8; 32]) -> Result<KeyAttestation, Error> {
+        let mut private_key = self.drv.slip10_derive_ed25519(&KEY_ATTESTATION_PATH);
 
-        let short_hash = digest_public_address(
-            &p,
-            fog_id.url(),
-            sig.as_ref().map(|v| &v[..]).unwrap_or(&[]),
-        );
+        let keys =
+            ed25519_dalek::SigningKey::try_from(private_key.as_ref()).map_err(|_| Error::SignError);
 
-        OutputAddress {
-            short_hash,
-            address: p,
-            fog_id,
-            fog_sig: sig,
+        // Zeroize derived key (MOB-01.5)
+        private_key.zeroize();
+
+        let keys = keys?;
+        let signature = ed25519_dalek::Signer::sign(&keys, digest);
+        let public_key = keys.verifying_key().to_bytes();
+
+        // Force drop and zeroize of private keys (MOB-01.1)
+        drop(keys);
+
+        Ok(KeyAttestation::new(public_key, signature.to_bytes()))
+    }
+
+    /// Sign a session challenge digest using the device identity key
+    /// (see [ledger_mob_apdu::device_nonce])
+    fn session_challenge(&self, digest: &[u8; 32]) -> Result<Output, Error> {
+        let mut private_key = self.drv.slip10_derive_ed25519(&DEVICE_IDENT_PATH);
+
+        let keys =
+            ed25519_dalek::SigningKey::try_from(private_key.as_ref()).map_err(|_| Error::SignError);
+
+        // Zeroize derived key (MOB-01.5)
+        private_key.zeroize();
+
+        let keys = keys?;
+        let signature = ed25519_dalek::Signer::sign(&keys, digest);
+        let public_key = keys.verifying_key().to_bytes();
+
+        // Force drop and zeroize of private keys (MOB-01.1)
+        drop(keys);
+
+        Ok(Output::Identity {
+            public_key,
+            signature: signature.to_bytes(),
+        })
+    }
+
+    /// Cache the [`Account`] for a given wallet index, only while
+    /// key requests or scanning are approved
+    fn cache_account(&mut self, account_index: u32) {
+        if self.approvals.is_empty() || self.accounts.get(account_index).is_some() {
+            return;
+        }
+
+        let a = self.get_account(account_index);
+        self.accounts.insert(account_index, a);
+    }
+
+    /// Zeroize account roots cached during the current session
+    pub fn invalidate_accounts(&mut self) {
+        self.accounts.clear();
+    }
+
+    /// Fetch the number of account roots cached during the current session
+    pub fn cached_accounts(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Fetch the protocol version pinned by the host, if set
+    pub fn protocol_version(&self) -> Option<u8> {
+        self.proto_version
+    }
+
+    /// Check the host has pinned a protocol version supported by the engine
+    fn check_protocol_version(&self) -> Result<(), Error> {
+        match self.proto_version {
+            Some(v) if (MOB_PROTO_VERSION_MIN..=MOB_PROTO_VERSION).contains(&v) => Ok(()),
+            _ => Err(Error::ProtocolNotPinned),
+        }
+    }
+
+    /// Fetch a Subaddress instance for a given wallet and subaddress index
+    #[cfg_attr(feature = "noinline", inline(never))]
+    pub fn get_subaddress(
+        &self,
+        account_index: u32,
+        subaddress_index: u64,
+        fog_id: FogId,
+    ) -> OutputAddress {
+        let mut account = self.get_account(account_index);
+        let mut subaddress = account.subaddress(subaddress_index);
+
+        // Zeroize private account keys (MOB-01.x)
+        account.zeroize();
+
+        let sig: Option<[u8; 64]> = match fog_id {
+            FogId::None => None,
+            _ => Some(sign_authority(&subaddress.view_private, fog_id.spki())).map(|v| v.into()),
+        };
+
+        let p = PublicSubaddress::from(&subaddress);
+
+        // Zeroize private subaddress keys (MOB-01.x)
+        subaddress.view_private.zeroize();
+        subaddress.spend_private.zeroize();
+
+        let short_hash = digest_public_address(
+            &p,
+            fog_id.url(),
+            sig.as_ref().map(|v| &v[..]).unwrap_or(&[]),
+        );
+
+        OutputAddress {
+            short_hash,
+            address: p,
+            fog_id,
+            fog_sig: sig,
         }
     }
 
-    /// Check whether engine is unlocked (ie. key requests and scanning have been approved)
+    /// Check whether engine is unlocked (ie. all key requests and scanning have been approved)
     pub fn is_unlocked(&self) -> bool {
-        self.unlocked
+        self.approvals.is_all()
     }
 
-    /// Unlock the engine (allowing key requests and scanning)
+    /// Check whether the provided capabilities have been approved
+    pub fn is_approved(&self, approvals: Approvals) -> bool {
+        self.approvals.contains(approvals)
+    }
+
+    /// Fetch current capability approvals
+    pub fn approvals(&self) -> Approvals {
+        self.approvals
+    }
+
+    /// Unlock the engine (allowing all key requests and scanning)
     pub fn unlock(&mut self) {
-        self.unlocked = true;
+        self.approve_scope(Approvals::all());
+    }
+
+    /// Approve a subset of capabilities (key requests and / or scanning)
+    pub fn approve_scope(&mut self, approvals: Approvals) {
+        self.approvals.insert(approvals);
+
+        let mut data = [0u8; 32];
+        data[0] = approvals.bits();
+        self.audit.push(AuditKind::KeyExport, self.ticks, data);
+
+        // Reset key export counters for the approved capabilities
+        if approvals.contains(Approvals::WALLET_KEYS) {
+            self.wallet_key_count = 0;
+        }
+        if approvals.contains(Approvals::SUBADDRESS_KEYS) {
+            self.subaddress_key_count = 0;
+        }
     }
 
     /// Lock the engine (requires approval for key requests and scanning)
     pub fn lock(&mut self) {
         // MOB-04 - lock engine on timeout
-        self.unlocked = false;
+        self.approvals = Approvals::empty();
+
+        // Clear cached account roots with the session
+        self.accounts.clear();
+
+        // Clear outstanding device nonce, presence must be re-proven
+        self.device_nonce = None;
+
+        // Clear serviced request counter
+        self.request_count = 0;
+    }
+
+    /// Set the number of key exports permitted per scope (wallet keys,
+    /// subaddress keys) before re-approval of that scope is required
+    /// (0 for unlimited)
+    pub fn set_key_request_limit(&mut self, limit: u32) {
+        self.key_request_limit = limit;
+    }
+
+    /// Store ring responses in a statically allocated platform buffer for
+    /// subsequent rings, avoiding per-ring stack copies of the responses
+    pub fn set_response_buffer(&mut self, buff: &'static mut ResponseBuffer) {
+        self.response_buff = Some(ResponseBufferPtr::new(buff));
+    }
+
+    /// Enable or disable blind signing (direct message setting), when disabled
+    /// transactions must be verified via TxSummary
+    ///
+    /// This has no effect where the `summary` feature is disabled
+    pub fn set_blind_signing(&mut self, enabled: bool) {
+        self.blind_signing = enabled;
+    }
+
+    /// Check whether blind signing is permitted
+    pub fn blind_signing(&self) -> bool {
+        !cfg!(feature = "summary") || self.blind_signing
+    }
+
+    /// Enable or disable signing with host-supplied onetime private keys
+    /// (`TxRingInit` with `HAS_ONETIME_PRIVATE_KEY`), enabled by default
+    pub fn set_onetime_keys(&mut self, enabled: bool) {
+        self.onetime_keys = enabled;
+    }
+
+    /// Check whether host-supplied onetime private keys are permitted
+    pub fn onetime_keys(&self) -> bool {
+        self.onetime_keys
+    }
+
+    /// Enable or disable display of payment request / intent ids
+    /// when reviewing signed memos (enabled by default)
+    pub fn set_show_payment_ids(&mut self, enabled: bool) {
+        self.show_payment_ids = enabled;
+    }
+
+    /// Check whether payment request / intent ids should be displayed
+    pub fn show_payment_ids(&self) -> bool {
+        self.show_payment_ids
+    }
+
+    /// Enable or disable on-device review of the first memo of each transaction
+    /// (disabled by default), memo signing is held in [State::MemoReview] until
+    /// approved via [Engine::memo_approve]
+    pub fn set_memo_review(&mut self, enabled: bool) {
+        self.memo_review = enabled;
+    }
+
+    /// Check whether the first memo of each transaction is held for review
+    pub fn memo_review(&self) -> bool {
+        self.memo_review
+    }
+
+    /// Set the active fog id, mirroring platform storage for reporting
+    /// via [`SettingsResp`][ledger_mob_apdu::settings::SettingsResp]
+    pub fn set_fog_id(&mut self, fog_id: FogId) {
+        self.fog_id = fog_id;
+    }
+
+    /// Fetch the active fog id
+    pub fn fog_id(&self) -> FogId {
+        self.fog_id
+    }
+
+    /// Set the platform inactivity lock timeout, reported to the host
+    /// (the engine does not enforce this)
+    pub fn set_lock_timeout(&mut self, timeout_s: u32) {
+        self.lock_timeout_s = timeout_s;
+    }
+
+    /// Fetch the platform inactivity lock timeout (0 where locking is disabled)
+    pub fn lock_timeout(&self) -> u32 {
+        self.lock_timeout_s
+    }
+
+    /// Set the account index used for on-device operations
+    pub fn set_default_account(&mut self, account_index: u32) {
+        self.default_account = account_index;
+    }
+
+    /// Fetch the account index used for on-device operations
+    pub fn default_account(&self) -> u32 {
+        self.default_account
+    }
+
+    /// Update the platform tick count, used to timestamp audit log entries
+    /// and abort transactions on watchdog expiry, returning `true` where a
+    /// transaction was aborted
+    pub fn set_ticks(&mut self, ticks: u32) -> bool {
+        self.ticks = ticks;
+
+        if self.tx_in_progress() && self.watchdog.expired(ticks) {
+            return self.tx_expire();
+        }
+
+        false
+    }
+
+    /// Abort the in-progress transaction on timeout, for platform watchdogs
+    /// (for example where the host stops sending requests), returning `true`
+    /// where a transaction was aborted
+    pub fn tx_expire(&mut self) -> bool {
+        if !self.tx_in_progress() {
+            return false;
+        }
+
+        self.tx_teardown();
+        self.state = State::Error;
+
+        true
+    }
+
+    /// Clear transaction context (sign context, summary, approvals and
+    /// message) on abort or expiry
+    fn tx_teardown(&mut self) {
+        self.function.clear();
+        self.signed_digest = None;
+        self.clear_approved_inputs();
+        zeroize_vec(&mut self.message);
+        zeroize_vec(&mut self.prior_responses);
+        self.watchdog.stop();
+    }
+
+    /// Configure the transaction watchdog timeout in seconds (0 to disable),
+    /// with the platform tick rate used by [Engine::set_ticks]
+    ///
+    /// This applies to transactions started after configuration.
+    pub fn set_tx_timeout(&mut self, timeout_s: u32, ticks_per_s: u32) {
+        self.watchdog.configure(timeout_s, ticks_per_s);
+    }
+
+    /// Fetch seconds remaining before the in-progress transaction is
+    /// aborted by the watchdog, `None` where no watchdog is running
+    pub fn tx_timeout_remaining(&self) -> Option<u32> {
+        match self.tx_in_progress() {
+            true => self.watchdog.remaining_s(self.ticks),
+            false => None,
+        }
+    }
+
+    /// Check whether a transaction is in progress (between
+    /// initialisation and completion / denial)
+    fn tx_in_progress(&self) -> bool {
+        match self.state {
+            State::BuildMemos(_)
+            | State::MemoReview
+            | State::SetMessage
+            | State::Pending
+            | State::Ready
+            | State::SignRing(_) => true,
+            #[cfg(feature = "summary")]
+            State::Summary(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Clear inputs recorded from an approved summary
+    fn clear_approved_inputs(&mut self) {
+        self.approved_inputs.clear();
+        self.summary_approved = false;
+        self.ring_input = None;
+    }
+
+    /// Fetch engine diagnostic counters
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    /// Fetch the size of the active [`Function`] context in bytes,
+    /// with the container size available via [`Function::max_size`]
+    pub fn function_size(&self) -> usize {
+        self.function.size()
+    }
+
+    /// Fetch features supported by the engine, reported to the host
+    /// via [`CapabilitiesResp`][ledger_mob_apdu::capabilities::CapabilitiesResp]
+    pub fn features() -> Features {
+        // Chunked exchange, sessions and batches are handled by the APDU dispatcher
+        let mut f = Features::CHUNKED_APDU
+            | Features::SECURE_SESSION
+            | Features::BATCH
+            | Features::ADDRESS_VERIFY
+            | Features::SUBADDRESS_RANGE
+            | Features::KEY_IMAGE_FROM_KEY
+            | Features::AUDIT_LOG
+            | Features::DIAGNOSTICS
+            | Features::INVALIDATE_ACCOUNTS
+            | Features::PUBLIC_ADDRESS
+            | Features::SUBADDRESS_KEYS_BATCH
+            | Features::SET_FOG_ID
+            | Features::PROGRESS
+            | Features::GET_SETTINGS
+            | Features::TX_SEQUENCE
+            | Features::TX_PAYLOAD_MAC
+            | Features::RANDOM_LEN
+            | Features::TX_ABORT
+            | Features::TX_GET_RESPONSES
+            | Features::KEY_ATTESTATION
+            | Features::PROTOCOL_PIN
+            | Features::KEY_PATH
+            | Features::DEVICE_NONCE
+            | Features::SUBADDRESS_KEYS_FOG;
+
+        f.set(Features::TX_SUMMARY, cfg!(feature = "summary"));
+        f.set(Features::TX_SUMMARY_REPORT, cfg!(feature = "summary"));
+        f.set(Features::COMPACT_SUMMARY, cfg!(feature = "summary"));
+        f.set(Features::TX_EXPECTED, cfg!(feature = "summary"));
+        f.set(Features::IDENT, cfg!(feature = "ident"));
+        f.set(Features::IDENT_DERIVATION, cfg!(feature = "ident"));
+        f.set(Features::MEMO, cfg!(feature = "memo"));
+
+        f
+    }
+
+    /// Fetch the approval audit log
+    pub fn audit_log(&self) -> &AuditLog {
+        &self.audit
+    }
+
+    /// Fetch key export counters since last unlock (wallet keys, subaddress keys)
+    pub fn key_counters(&self) -> (u32, u32) {
+        (self.wallet_key_count, self.subaddress_key_count)
+    }
+
+    /// Fetch the number of key and key image requests serviced since unlock
+    pub fn request_count(&self) -> u32 {
+        self.request_count
+    }
+
+    /// Fetch the key export counter for a scope
+    fn key_count(&mut self, scope: Approvals) -> &mut u32 {
+        if scope == Approvals::WALLET_KEYS {
+            &mut self.wallet_key_count
+        } else {
+            &mut self.subaddress_key_count
+        }
+    }
+
+    /// Check `n` further key exports are permitted within the scope limit
+    fn check_key_request_limit(&mut self, scope: Approvals, n: u32) -> Result<(), Error> {
+        let limit = self.key_request_limit;
+        let count = *self.key_count(scope);
+
+        if limit != 0 && count.saturating_add(n) > limit {
+            return Err(Error::KeyLimitExceeded);
+        }
+
+        Ok(())
+    }
+
+    /// Record key exports for a scope, revoking approval for that scope
+    /// (only) once the limit is reached
+    fn record_key_exports(&mut self, scope: Approvals, n: u32) {
+        let limit = self.key_request_limit;
+        let count = self.key_count(scope);
+        *count = count.saturating_add(n);
+
+        if limit != 0 && *count >= limit {
+            self.approvals.remove(scope);
+        }
+
+        self.request_count = self.request_count.saturating_add(1);
     }
 
     /// Approve a pending transaction (advances state to `State::Ready`)
     pub fn approve(&mut self) {
         if let State::Pending = self.state {
             self.state = State::Ready;
+
+            let digest = *self.digest.as_bytes();
+            self.audit.push(AuditKind::Transaction, self.ticks, digest);
         }
     }
 
-    /// Deny a pending transaction
+    /// Deny a pending transaction, or cancel an approved transaction
+    /// during signing
     pub fn deny(&mut self) {
+        // Cancellation following approval is reported distinctly from denial
+        let cancelled = matches!(self.state, State::Ready | State::SignRing(_));
+
         self.function.clear();
-        self.state = State::Deny;
+        self.memo_request = None;
+        self.signed_digest = None;
+        self.clear_approved_inputs();
+        zeroize_vec(&mut self.message);
+        zeroize_vec(&mut self.prior_responses);
+        self.state = match cancelled {
+            true => State::Cancelled,
+            false => State::Deny,
+        };
+    }
+
+    /// Check the request sequence number for state-mutating events,
+    /// updating the last accepted sequence on success
+    fn check_seq(&mut self, seq: u8, evt: &Event) -> Result<(), Error> {
+        // Sequences restart with each transaction
+        let expected = match evt {
+            Event::TxInit { .. } => 1,
+            _ => next_seq(self.tx_seq),
+        };
+
+        match (seq, evt) {
+            // Unsequenced requests are not checked
+            (0, Event::TxInit { .. }) => self.tx_seq = 0,
+            (0, _) => (),
+            (s, _) if s == expected => self.tx_seq = s,
+            _ => {
+                #[cfg(feature = "log")]
+                log::warn!("unexpected sequence {} (expected {})", seq, expected);
+
+                return Err(Error::UnexpectedSequence);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the payload MAC for sequenced requests, keyed by the
+    /// transaction digest prior to applying the event
+    fn check_mac(&self, meta: &ReqMeta, h: &[u8; 32]) -> Result<(), Error> {
+        // Unsequenced requests and requests without MACs are not checked
+        let mac = match meta.mac {
+            Some(v) if meta.seq != 0 => v,
+            _ => return Ok(()),
+        };
+
+        let expected = payload_mac(self.digest.as_bytes(), meta.seq, h);
+        if !ct_eq(&mac, &expected) {
+            #[cfg(feature = "log")]
+            log::warn!("payload MAC mismatch (seq {})", meta.seq);
+
+            return Err(Error::InvalidMac);
+        }
+
+        Ok(())
+    }
+
+    /// Fetch the last accepted request sequence number
+    pub fn tx_seq(&self) -> u8 {
+        self.tx_seq
     }
 
     /// Reset engine state
     pub fn reset(&mut self) {
         self.function.clear();
+        self.signed_digest = None;
+        self.clear_approved_inputs();
+        zeroize_vec(&mut self.message);
+        zeroize_vec(&mut self.prior_responses);
+        self.tx_seq = 0;
         self.state = State::Init;
+        self.diagnostics.resets = self.diagnostics.resets.wrapping_add(1);
     }
 
     /// Fetch progress for non-interactive states (summary, ring signing)
@@ -601,6 +2016,17 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         }
     }
 
+    /// Fetch step counts (current, total) for non-interactive states,
+    /// summary entries loaded or rings signed
+    pub fn progress_steps(&self) -> Option<(usize, usize)> {
+        match self.state {
+            #[cfg(feature = "summary")]
+            State::Summary(_) => self.function.summarizer_ref().map(|v| v.steps()),
+            State::SignRing(_) => Some((self.ring_count, self.num_rings)),
+            _ => None,
+        }
+    }
+
     /// Fetch message for transactions in progress
     pub fn message(&self) -> Option<&[u8]> {
         if self.message.len() == 32 {
@@ -610,6 +2036,81 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         }
     }
 
+    /// Fetch the number of rings for the current transaction
+    pub fn num_rings(&self) -> usize {
+        self.num_rings
+    }
+
+    /// Fetch the expected fee and tombstone block provided with [Event::TxInit],
+    /// these are only checked against the transaction where summaries are used
+    pub fn tx_expected(&self) -> Option<TxExpected> {
+        self.tx_expected
+    }
+
+    /// Fetch decoded memos signed for the current transaction
+    pub fn memos(&self) -> &[MemoInfo] {
+        &self.memos
+    }
+
+    /// Return the first memo of the current transaction if pending review
+    pub fn memo_request(&self) -> Option<&MemoInfo> {
+        match self.state {
+            State::MemoReview => self.memo_request.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Approve or deny the first memo of the current transaction,
+    /// denial rejects the transaction
+    pub fn memo_approve(&mut self, approve: bool) {
+        if let State::MemoReview = self.state {
+            match approve {
+                true => {
+                    self.memo_request = None;
+                    self.memo_reviewed = true;
+                    self.state = State::BuildMemos(0);
+                }
+                false => self.deny(),
+            }
+        }
+    }
+
+    /// Hold the first memo of a transaction for review where enabled,
+    /// called prior to applying memo signing events
+    #[cfg(feature = "memo")]
+    fn check_memo_review(&mut self, evt: &Event) -> Result<(), Error> {
+        let (kind, payload, payload_len) = match evt {
+            Event::TxSignMemo {
+                kind,
+                payload,
+                payload_len,
+                continuation: false,
+                ..
+            } => (kind, payload, *payload_len as usize),
+            _ => return Ok(()),
+        };
+
+        match self.state {
+            // Memos are rejected until the pending memo is reviewed
+            State::MemoReview => return Err(Error::ApprovalPending),
+            // Only the first memo of each transaction is reviewed
+            State::BuildMemos(0) if self.memo_review && !self.memo_reviewed => (),
+            _ => return Ok(()),
+        }
+
+        // Decode memo for display, lengths are checked again on signing
+        let len = payload_len.min(payload.len());
+        let memo = MemoInfo::decode(kind, &payload[..len])?;
+        if !memo.kind.is_authenticated() {
+            return Err(Error::UnsupportedMemo);
+        }
+
+        self.memo_request = Some(memo);
+        self.state = State::MemoReview;
+
+        Err(Error::ApprovalPending)
+    }
+
     /// Return report if available
     #[cfg(feature = "summary")]
     pub fn report(&self) -> Option<&TxSummaryUnblindingReport<MAX_RECORDS>> {
@@ -622,6 +2123,88 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         None
     }
 
+    /// Check whether any of our report totals meet the per-token high-value
+    /// threshold, requiring additional confirmation to approve
+    #[cfg(feature = "summary")]
+    pub fn is_high_value(&self) -> bool {
+        let report = match self.report() {
+            Some(r) => r,
+            None => return false,
+        };
+
+        report
+            .totals
+            .iter()
+            .filter(|(_, kind, _)| matches!(kind, TotalKind::Ours))
+            .any(|(token_id, _, value)| is_high_value(*value, *token_id))
+    }
+
+    /// Noop high-value check if summary feature is disabled
+    #[cfg(not(feature = "summary"))]
+    pub fn is_high_value(&self) -> bool {
+        false
+    }
+
+    /// Fetch report entries from `start` for read back by the host
+    #[cfg(feature = "summary")]
+    #[cfg_attr(feature = "noinline", inline(never))]
+    fn get_report_entries(&self, start: u8) -> Result<Output, Error> {
+        use core::ops::Deref;
+        use ledger_mob_apdu::tx::{ReportEntity, ReportEntry};
+
+        let report = match self.report() {
+            Some(r) => r,
+            None => return Err(Error::InvalidState),
+        };
+
+        let mut entries = Vec::new();
+        for (entity, token_id, value) in report.outputs.iter().skip(start as usize) {
+            let (entity, address_hash) = match entity {
+                TransactionEntity::OurAddress(h) => (ReportEntity::OurAddress, Some(h)),
+                TransactionEntity::OtherAddress(h) => (ReportEntity::OtherAddress, Some(h)),
+                TransactionEntity::Swap => (ReportEntity::Swap, None),
+            };
+
+            let mut hash = [0u8; 16];
+            if let Some(h) = address_hash {
+                let h: &[u8] = h.as_ref();
+                hash.copy_from_slice(&h[..16]);
+            }
+
+            let e = ReportEntry {
+                entity,
+                token_id: *token_id.deref(),
+                value: *value,
+                address_hash: hash,
+            };
+
+            if entries.push(e).is_err() {
+                break;
+            }
+        }
+
+        Ok(Output::TxSummaryReport {
+            start,
+            num_held: report.outputs.len() as u8,
+            entries,
+        })
+    }
+
+    /// Return tombstone block status if available
+    #[cfg(feature = "summary")]
+    pub fn tombstone_status(&self) -> Option<TombstoneStatus> {
+        self.function.summarizer_ref().map(|v| v.tombstone_status())
+    }
+
+    /// Fetch dust outputs folded from the transaction report
+    #[cfg(feature = "summary")]
+    pub fn dust(&self) -> &[DustTotal] {
+        self.function
+            .summarizer_ref()
+            .map(|v| v.dust())
+            .unwrap_or(&[])
+    }
+
     /// Resolve address if available
     #[cfg(feature = "summary")]
     pub fn address(&self, h: &ShortAddressHash) -> Option<&OutputAddress> {
@@ -634,6 +2217,15 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         None
     }
 
+    /// Resolve a reserved subaddress (change, gift code etc.) of the
+    /// signing account by short hash, for naming report entries
+    #[cfg(feature = "summary")]
+    pub fn reserved_subaddress(&self, h: &ShortAddressHash) -> Option<ReservedSubaddress> {
+        self.function
+            .summarizer_ref()
+            .and_then(|v| v.reserved_subaddress(h))
+    }
+
     #[cfg_attr(feature = "noinline", inline(never))]
     fn get_key_image(
         &self,
@@ -650,6 +2242,8 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
             subaddress.spend_private_key().as_ref(),
         );
 
+        let spend_public = subaddress.spend_public_key();
+
         // Zeroize keys
         account.zeroize();
         subaddress.view_private.zeroize();
@@ -659,6 +2253,7 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
             account_index,
             subaddress_index,
             key_image: KeyImage::from(&onetime_private_key),
+            spend_public,
         }
     }
 
@@ -678,6 +2273,15 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
     pub fn ident_approve(&mut self, approve: bool) {
         if let State::Ident(IdentState::Pending) = self.state {
             if approve {
+                // Record identity URI (truncated) for audit
+                let mut data = [0u8; 32];
+                if let Some(i) = self.function.ident_ref() {
+                    let uri = i.uri().as_bytes();
+                    let n = uri.len().min(data.len());
+                    data[..n].copy_from_slice(&uri[..n]);
+                }
+                self.audit.push(AuditKind::Ident, self.ticks, data);
+
                 self.state = State::Ident(IdentState::Approved);
             } else {
                 self.function.clear();
@@ -716,16 +2320,120 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         Ok(resp)
     }
 
-    // Sign the provided memo, returning an `Output::MemoHmac` on success
-    #[cfg_attr(feature = "noinline", inline(never))]
-    fn memo_sign(
-        &mut self,
-        subaddress_index: u64,
-        tx_out_public_key: &TxOutPublic,
-        receiver_view_public: &SubaddressViewPublic,
-        kind: &[u8; 2],
-        payload: &[u8; 48],
-    ) -> Output {
+    /// Return address verification info if pending approval
+    pub fn address_verify(&self) -> Option<&AddressVerify> {
+        match self.state {
+            State::Address(AddressState::Pending) => (),
+            _ => return None,
+        }
+
+        self.function.address_verify_ref()
+    }
+
+    /// Approve or deny a pending address verification, updating the [AddressState]
+    pub fn address_approve(&mut self, approve: bool) {
+        if let State::Address(AddressState::Pending) = self.state {
+            if approve {
+                // Record account and subaddress indices for audit
+                let mut data = [0u8; 32];
+                if let Some(v) = self.function.address_verify_ref() {
+                    data[..4].copy_from_slice(&v.account_index.to_le_bytes());
+                    data[4..12].copy_from_slice(&v.subaddress_index.to_le_bytes());
+                }
+                self.audit.push(AuditKind::Address, self.ticks, data);
+
+                self.state = State::Address(AddressState::Approved);
+            } else {
+                self.function.clear();
+                self.state = State::Address(AddressState::Denied);
+            }
+        }
+    }
+
+    /// Return requested fog id if pending approval
+    pub fn fog_request(&self) -> Option<FogId> {
+        match self.state {
+            State::Fog(FogState::Pending) => Some(self.fog_request),
+            _ => None,
+        }
+    }
+
+    /// Approve or deny a pending fog id change, updating the [FogState]
+    pub fn fog_approve(&mut self, approve: bool) {
+        if let State::Fog(FogState::Pending) = self.state {
+            if approve {
+                // Record requested fog id for audit
+                let mut data = [0u8; 32];
+                data[0] = self.fog_request as u8;
+                self.audit.push(AuditKind::FogId, self.ticks, data);
+
+                self.fog_id = self.fog_request;
+                self.state = State::Fog(FogState::Approved);
+            } else {
+                self.state = State::Fog(FogState::Denied);
+            }
+        }
+    }
+
+    #[cfg_attr(feature = "noinline", inline(never))]
+    fn get_signed_address(&mut self) -> Result<Output, Error> {
+        // Retrieve address verification context
+        let v = match self.function.address_verify_ref() {
+            Some(v) => v,
+            None => {
+                return Err(Error::InvalidState);
+            }
+        };
+
+        let mut account = self.get_account(v.account_index);
+        let mut subaddress = account.subaddress(v.subaddress_index);
+
+        // Zeroize private account keys (MOB-01.x)
+        account.zeroize();
+
+        let fog_sig: [u8; 64] = match v.fog_id {
+            FogId::None => [0u8; 64],
+            _ => sign_authority(&subaddress.view_private, v.fog_id.spki()).into(),
+        };
+
+        let p = PublicSubaddress::from(&subaddress);
+
+        // Compute address hash and sign acknowledgement
+        let short_hash = digest_public_address(
+            &p,
+            v.fog_id.url(),
+            match v.fog_id {
+                FogId::None => &[],
+                _ => &fog_sig[..],
+            },
+        );
+        let signature = sign_address(&subaddress.spend_private, &v.message(short_hash.as_ref()));
+
+        // Zeroize private subaddress keys (MOB-01.x)
+        subaddress.view_private.zeroize();
+        subaddress.spend_private.zeroize();
+
+        Ok(Output::Address {
+            view_public: p.view_public,
+            spend_public: p.spend_public,
+            fog_id: v.fog_id,
+            fog_sig,
+            signature: signature.into(),
+        })
+    }
+
+    // Start a memo HMAC for the provided memo,
+    // payload chunks are then added via [MemoMac::update]
+    #[cfg(feature = "memo")]
+    #[cfg_attr(feature = "noinline", inline(never))]
+    fn memo_mac(
+        &self,
+        subaddress_index: u64,
+        tx_out_public_key: &TxOutPublic,
+        receiver_view_public: &SubaddressViewPublic,
+        kind: [u8; 2],
+        info: MemoInfo,
+    ) -> MemoMac {
         // Fetch default subaddress
         let mut account = self.get_account(self.account_index);
         let mut sender_subaddr = account.subaddress(subaddress_index);
@@ -742,27 +2450,19 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         sender_subaddr.view_private.zeroize();
         sender_subaddr.spend_private.zeroize();
 
-        // TODO: check memo is supported type (no MEMO_TYPE_BYTES exported from core just yet)
-
         // Build HMAC
         // - this is a deterministic value so it can be generated by the sender with knowledge of the
         //   receiver, and the receiver with knowledge of the sender, but not by other parties.
         // - `tx_out_public_key` is unique to a given TxOut avoiding collisions for a transaction
         //   between the same parties with the same memo kind and payload.
-        let tx_out_public_key: &RistrettoPublic = tx_out_public_key.as_ref();
-        let hmac_value = compute_category1_hmac(
+        MemoMac::new(
             shared_secret.as_ref(),
-            &CompressedRistrettoPublic::from(tx_out_public_key),
-            *kind,
-            payload,
-        );
-
-        // Return HMAC
-        Output::MemoHmac {
-            state: self.state,
-            digest: self.digest.clone(),
-            hmac: hmac_value,
-        }
+            subaddress_index,
+            tx_out_public_key,
+            receiver_view_public,
+            kind,
+            info,
+        )
     }
 
     /// Initialise ring signing context
@@ -783,8 +2483,36 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         #[cfg(feature = "log")]
         log::info!("using subaddress {}: {:#?}", subaddress_index, subaddress);
 
+        // Restarting a failed ring retains the ring count and prior
+        // responses, preserving rings already signed
+        let retry = self.state == State::SignRing(RingState::Error);
+
+        // Check the ring matches an unsigned input from the approved summary,
+        // so rings can not be signed with values other than those reviewed
+        if self.summary_approved {
+            match self
+                .approved_inputs
+                .iter()
+                .position(|i| !i.signed && i.value == value && i.token_id == token_id)
+            {
+                Some(i) => self.ring_input = Some(i),
+                None => {
+                    #[cfg(feature = "log")]
+                    log::error!("ring (value: {}, token: {}) not approved", value, token_id);
+
+                    account.zeroize();
+                    subaddress.view_private.zeroize();
+                    subaddress.spend_private.zeroize();
+
+                    self.function.clear();
+                    self.state = State::Error;
+                    return Err(Error::RingInputMismatch);
+                }
+            }
+        }
+
         // Count signed rings
-        if self.function.ring_signer_ref().is_some() {
+        if !retry && self.function.ring_signer_ref().is_some() {
             // If we're already in ring signing mode, increment counter
             self.ring_count += 1;
 
@@ -793,6 +2521,15 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
             assert!(self.ring_count < usize::MAX);
         }
 
+        // Retain responses from a completed ring so these may be fetched
+        // while the next ring is loaded
+        if !retry {
+            zeroize_vec(&mut self.prior_responses);
+            if let Some(r) = self.function.ring_signer_ref().and_then(|s| s.responses()) {
+                let _ = self.prior_responses.extend_from_slice(r);
+            }
+        }
+
         // Setup ring signer context
         let ctx = self.function.ring_signer_init(
             ring_size as usize,
@@ -812,13 +2549,18 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
 
         // Handle errors
         match ctx {
-            Ok(v) => v,
+            Ok(v) => {
+                if let Some(b) = self.response_buff {
+                    v.set_response_buffer(b);
+                }
+            }
             Err(e) => {
                 #[cfg(feature = "log")]
                 log::error!("ring init failed: {:?}", e);
 
+                // Fail the ring, this may be restarted via `TxRingInit`
                 self.function.clear();
-                self.state = State::Error;
+                self.state = State::SignRing(RingState::Error);
                 return Err(e);
             }
         };
@@ -827,7 +2569,9 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
 
         Ok(Output::State {
             state: self.state,
+            seq: self.tx_seq,
             digest: self.digest.clone(),
+            timeout_s: self.tx_timeout_remaining().unwrap_or(0),
         })
     }
 
@@ -837,7 +2581,7 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         let ring_signer = match self.function.ring_signer() {
             Some(s) => s,
             _ => {
-                self.state = State::Error;
+                self.state = State::SignRing(RingState::Error);
                 return Err(Error::UnexpectedEvent);
             }
         };
@@ -848,11 +2592,27 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
                 #[cfg(feature = "log")]
                 log::warn!("ring update failed: {:?}", e);
 
-                self.state = State::Error;
+                // Fail the ring without aborting the transaction,
+                // allowing this to be restarted via `TxRingInit`
+                self.state = State::SignRing(RingState::Error);
                 return Err(e);
             }
         };
 
+        // Track completed rings for diagnostics and the signed transaction digest
+        if let (Event::TxSign, RingState::Complete { key_image, c_zero }) = (evt, &state) {
+            self.diagnostics.rings_signed = self.diagnostics.rings_signed.wrapping_add(1);
+
+            // Mark the matched input as signed so this may not be reused
+            if let Some(i) = self.ring_input.take() {
+                self.approved_inputs[i].signed = true;
+            }
+
+            if let (Some(d), Some(r)) = (&mut self.signed_digest, ring_signer.responses()) {
+                d.add_ring(key_image, &CurveScalar::from(*c_zero), r);
+            }
+        }
+
         // Update engine with new ring signing state
         self.state = State::SignRing(state);
 
@@ -860,7 +2620,9 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         match output {
             Output::None => Ok(Output::State {
                 state: self.state,
+                seq: self.tx_seq,
                 digest: self.digest.clone(),
+                timeout_s: self.tx_timeout_remaining().unwrap_or(0),
             }),
             _ => Ok(output),
         }
@@ -880,24 +2642,46 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         // (note transaction message hash is set later, incoming
         // message is only used for txsummary generation)
 
-        use mc_core::consts::CHANGE_SUBADDRESS_INDEX;
+        use mc_core::consts::{
+            CHANGE_SUBADDRESS_INDEX, DEFAULT_SUBADDRESS_INDEX, GIFT_CODE_SUBADDRESS_INDEX,
+        };
 
         if message.len() > self.message.capacity() {
             return Err(Error::InvalidLength);
         }
 
+        // Reject block versions outside the supported range, signatures
+        // for these would be rejected by the network
+        let block_version = match BlockVersion::try_from(block_version) {
+            Ok(v) if (BLOCK_VERSION_MIN..=BLOCK_VERSION_MAX).contains(&v) => v,
+            _ => {
+                #[cfg(feature = "log")]
+                log::error!("unsupported block version: {}", block_version);
+
+                self.function.clear();
+                self.state = State::Error;
+                return Err(Error::UnsupportedBlockVersion);
+            }
+        };
+
         let mut account = self.get_account(self.account_index);
         let change_subaddress =
             PublicSubaddress::from(&account.subaddress(CHANGE_SUBADDRESS_INDEX));
+        let default_subaddress =
+            PublicSubaddress::from(&account.subaddress(DEFAULT_SUBADDRESS_INDEX));
+        let gift_code_subaddress =
+            PublicSubaddress::from(&account.subaddress(GIFT_CODE_SUBADDRESS_INDEX));
 
         // Setup summarizer context
         if let Err(e) = self.function.summarizer_init(
             message,
-            BlockVersion::try_from(block_version).unwrap(),
+            block_version,
             num_outputs as usize,
             num_inputs as usize,
             account.view_private_key(),
             &change_subaddress,
+            &default_subaddress,
+            &gift_code_subaddress,
         ) {
             #[cfg(feature = "log")]
             log::error!("summarizer init failed: {:?}", e);
@@ -914,7 +2698,9 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
 
         Ok(Output::State {
             state: self.state,
+            seq: self.tx_seq,
             digest: self.digest.clone(),
+            timeout_s: self.tx_timeout_remaining().unwrap_or(0),
         })
     }
 
@@ -922,6 +2708,8 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
     #[cfg(feature = "summary")]
     #[cfg_attr(feature = "noinline", inline(never))]
     fn tx_summary_update(&mut self, evt: &Event) -> Result<Output, Error> {
+        let expected = self.tx_expected;
+
         // Fetch summarizer context
         let summarizer = match self.function.summarizer() {
             Some(s) => s,
@@ -948,11 +2736,13 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
                 unmasked_amount,
                 address,
                 fog_info,
+                fog_url,
                 tx_private_key,
             } => summarizer.add_output_unblinding(
                 unmasked_amount,
                 address.as_ref(),
                 fog_info.as_ref().map(|(id, sig)| (*id, sig)),
+                fog_url.as_ref(),
                 tx_private_key.as_ref(),
             ),
             Event::TxSummaryAddInput {
@@ -967,16 +2757,24 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
             Event::TxSummaryBuild {
                 fee,
                 tombstone_block,
-            } => {
-                let mut message = [0u8; 32];
-                let r = summarizer.finalize(*fee, *tombstone_block, &mut message);
+                current_block,
+            } => match expected {
+                // Fee and tombstone block must match those displayed by the host wallet
+                Some(e) if e.fee() != *fee || e.tombstone_block != *tombstone_block => {
+                    Err(Error::ExpectedMismatch)
+                }
+                _ => {
+                    let mut message = [0u8; 32];
+                    let r =
+                        summarizer.finalize(*fee, *tombstone_block, *current_block, &mut message);
 
-                // Write message
-                self.message.clear();
-                let _ = self.message.extend_from_slice(&message);
+                    // Write message
+                    zeroize_vec(&mut self.message);
+                    let _ = self.message.extend_from_slice(&message);
 
-                r
-            }
+                    r
+                }
+            },
             // Unhandled event
             _ => {
                 self.state = State::Error;
@@ -986,8 +2784,31 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
 
         // Check results
         match r {
-            // On complete, move to tx pending state
-            Ok(SummaryState::Complete) => self.state = State::Pending,
+            // On complete, record inputs and move to tx pending state
+            Ok(SummaryState::Complete) => {
+                self.approved_inputs.clear();
+                self.summary_approved = false;
+                self.ring_input = None;
+                for (value, token_id) in summarizer.inputs() {
+                    let input = ApprovedInput {
+                        value: *value,
+                        token_id: *token_id,
+                        signed: false,
+                    };
+
+                    if self.approved_inputs.push(input).is_err() {
+                        #[cfg(feature = "log")]
+                        log::error!("too many summary inputs");
+
+                        self.approved_inputs.clear();
+                        self.state = State::Error;
+                        return Err(Error::SummaryFailed);
+                    }
+                }
+                self.summary_approved = true;
+
+                self.state = State::Pending;
+            }
             // Otherwise, update summary state
             Ok(state) => self.state = State::Summary(state),
             // Or handle errors
@@ -1003,7 +2824,9 @@ impl<DRV: Driver, RNG: CryptoRngCore> Engine<DRV, RNG> {
         // Return state information
         Ok(Output::State {
             state: self.state,
+            seq: self.tx_seq,
             digest: self.digest.clone(),
+            timeout_s: self.tx_timeout_remaining().unwrap_or(0),
         })
     }
 }
@@ -1029,168 +2852,1992 @@ fn compute_ring_progress(current: usize, ring: usize, total_rings: usize) -> usi
 mod test {
     extern crate std;
 
-    use core::mem::MaybeUninit;
+    use core::mem::MaybeUninit;
+
+    use rand_core::OsRng;
+    use strum::IntoEnumIterator;
+
+    use mc_core::{account::RingCtAddress, subaddress::Subaddress};
+    use mc_crypto_keys::{CompressedRistrettoPublic, RistrettoPrivate, RistrettoPublic};
+    use mc_crypto_ring_signature::{
+        onetime_keys::{recover_onetime_private_key, recover_public_subaddress_spend_key},
+        CompressedCommitment, CurveScalar, Scalar,
+    };
+    use mc_util_from_random::FromRandom;
+
+    use ledger_mob_apdu::random::RANDOM_LEN_MAX;
+
+    use super::*;
+    use crate::verifier::verify_ring;
+
+    use ledger_mob_tests::mlsag::RingMLSAGParameters;
+
+    lazy_static::lazy_static! {
+        pub static ref PRIVATE_KEY: RistrettoPrivate = RistrettoPrivate::from_random(&mut OsRng{});
+
+        /// Mocked out test values, only for state tests
+        pub static ref TESTS: [(State, Event); 4] = [
+            (State::Init, Event::TxInit{ account_index: 0, num_rings: 13, expected: None }),
+
+            (State::SetMessage, Event::TxSetMessage(heapless::Vec::from_slice(&[0xaa, 0xbb, 0xcc]).unwrap())),
+
+            (State::Ready, Event::TxRingInit{ ring_size: RING_SIZE as u8, value: 100, token_id: 10, real_index: 3, subaddress_index: 8, onetime_private_key: None }),
+
+            (State::SignRing(RingState::Init), Event::TxSetBlinding{ blinding: Scalar::random(&mut OsRng{}), output_blinding: Scalar::random(&mut OsRng{})}),
+
+            #[cfg(nyet)]
+            (State::BuildRing, Event::TxAddTxout(ReducedTxOut{
+                public_key: CompressedRistrettoPublic::from(&PRIVATE_KEY),
+                target_key: CompressedRistrettoPublic::from(&PRIVATE_KEY),
+                commitment: CompressedCommitment::default(),
+            })),
+        ];
+    }
+
+    /// Pin the current protocol version, required prior to transactions
+    fn pin_protocol<DRV: Driver, RNG: CryptoRngCore>(e: &mut Engine<DRV, RNG>) {
+        e.update(&Event::SetProtocolVersion {
+            version: MOB_PROTO_VERSION,
+        })
+        .expect("Pin protocol version");
+    }
+
+    /// Step through valid events and states
+    #[test]
+    fn valid_events() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+
+        for (_state, evt) in &*TESTS {
+            // Fire expected event
+            let r = e.update(evt);
+
+            // Check response is okay
+            assert!(r.is_ok(), "event {evt:?} failed, error response: {r:?}");
+        }
+    }
+
+    /// Ensure we're handling unexpected events
+    #[test]
+    fn invalid_events() {
+        for (okay_state, evt) in &*TESTS {
+            let mut e = Engine::new(SoftwareDriver::random());
+
+            // Fire the same event to every other state
+            for state in State::iter() {
+                if state == *okay_state {
+                    continue;
+                }
+
+                match (state, evt) {
+                    // Only init once (because this causes a state reset)
+                    (State::Init, Event::TxInit { .. }) => (),
+                    (_, Event::TxInit { .. }) => continue,
+                    // Skip correct state
+                    (state, _) if state == *okay_state => continue,
+                    _ => (),
+                }
+
+                // Fire incorrect event
+                let r = e.update(evt);
+
+                // Check engine reports event error
+                assert_eq!(r, Err(Error::UnexpectedEvent));
+            }
+        }
+    }
+
+    /// Ensure block versions outside the supported range are rejected
+    #[cfg(feature = "summary")]
+    #[test]
+    fn block_version_gating() {
+        let tests = &[
+            (0, Err(Error::UnsupportedBlockVersion)),
+            (*BLOCK_VERSION_MIN - 1, Err(Error::UnsupportedBlockVersion)),
+            (*BLOCK_VERSION_MIN, Ok(State::Summary(SummaryState::Init))),
+            (*BLOCK_VERSION_MAX, Ok(State::Summary(SummaryState::Init))),
+            (*BLOCK_VERSION_MAX + 1, Err(Error::UnsupportedBlockVersion)),
+            (u32::MAX, Err(Error::UnsupportedBlockVersion)),
+        ];
+
+        for (block_version, expected) in tests {
+            let mut e = Engine::new(SoftwareDriver::random());
+            pin_protocol(&mut e);
+
+            e.update(&Event::TxInit {
+                account_index: 0,
+                num_rings: 1,
+                expected: None,
+            })
+            .expect("Init transaction");
+
+            let r = e.update(&Event::TxSummaryInit {
+                message: [0u8; 32],
+                block_version: *block_version,
+                num_outputs: 1,
+                num_inputs: 1,
+            });
+
+            assert_eq!(
+                r.map(|o| o.state()),
+                expected.clone().map(Some),
+                "block version {block_version}"
+            );
+        }
+    }
+
+    /// Ensure progress is reported for summary loading
+    #[cfg(feature = "summary")]
+    #[test]
+    fn progress() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+
+        // No progress reported while idle
+        let r = e.update(&Event::GetProgress).unwrap();
+        assert_eq!(
+            r,
+            Output::Progress {
+                state: State::Init,
+                percent: 0,
+                step: 0,
+                total: 0
+            }
+        );
+
+        e.update(&Event::TxInit {
+            account_index: 0,
+            num_rings: 1,
+            expected: None,
+        })
+        .expect("Init transaction");
+
+        e.update(&Event::TxSummaryInit {
+            message: [0u8; 32],
+            block_version: *BLOCK_VERSION_MIN,
+            num_outputs: 2,
+            num_inputs: 1,
+        })
+        .expect("Init summary");
+
+        // Summary steps include outputs, inputs and finalisation
+        let r = e.update(&Event::GetProgress).unwrap();
+        assert_eq!(
+            r,
+            Output::Progress {
+                state: State::Summary(SummaryState::Init),
+                percent: 0,
+                step: 0,
+                total: 4
+            }
+        );
+    }
+
+    /// Ensure summary fees and tombstone blocks are checked against expected values
+    #[cfg(feature = "summary")]
+    #[test]
+    fn tx_expected_mismatch() {
+        use mc_transaction_types::Amount;
+
+        let fee = Amount::new(400_000_000, TokenId::from(0));
+        let expected = TxExpected::new(fee, 1_000);
+
+        let tests = [
+            (Amount::new(500_000_000, TokenId::from(0)), 1_000),
+            (Amount::new(400_000_000, TokenId::from(1)), 1_000),
+            (fee, 1_001),
+        ];
+
+        for (fee, tombstone_block) in tests {
+            let mut e = Engine::new(SoftwareDriver::random());
+            pin_protocol(&mut e);
+
+            e.update(&Event::TxInit {
+                account_index: 0,
+                num_rings: 1,
+                expected: Some(expected),
+            })
+            .expect("Init transaction");
+
+            e.update(&Event::TxSummaryInit {
+                message: [0u8; 32],
+                block_version: *BLOCK_VERSION_MIN,
+                num_outputs: 1,
+                num_inputs: 1,
+            })
+            .expect("Init summary");
+
+            let r = e.update(&Event::TxSummaryBuild {
+                fee,
+                tombstone_block,
+                current_block: None,
+            });
+            assert_eq!(r, Err(Error::ExpectedMismatch));
+            assert_eq!(e.state(), State::Error);
+        }
+    }
+
+    /// Ensure direct message setting is rejected when blind signing is disabled
+    #[cfg(feature = "summary")]
+    #[test]
+    fn blind_signing_disabled() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+        e.set_blind_signing(false);
+        assert!(!e.blind_signing());
+
+        e.update(&Event::TxInit {
+            account_index: 0,
+            num_rings: 1,
+            expected: None,
+        })
+        .expect("Init transaction");
+
+        let r = e.update(&Event::TxSetMessage(
+            heapless::Vec::from_slice(&[0xaa, 0xbb, 0xcc]).unwrap(),
+        ));
+        assert_eq!(r, Err(Error::BlindSigningDisabled));
+
+        // TxSummary path remains available
+        let r = e.update(&Event::TxSummaryInit {
+            message: [0u8; 32],
+            block_version: *BLOCK_VERSION_MIN,
+            num_outputs: 1,
+            num_inputs: 1,
+        });
+        assert_eq!(
+            r.map(|o| o.state()),
+            Ok(Some(State::Summary(SummaryState::Init)))
+        );
+    }
+
+    /// Ensure host-supplied onetime private keys are rejected where disabled
+    #[test]
+    fn onetime_keys_disabled() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+        e.set_onetime_keys(false);
+        assert!(!e.onetime_keys());
+
+        e.update(&Event::TxInit {
+            account_index: 0,
+            num_rings: 1,
+            expected: None,
+        })
+        .expect("Init transaction");
+        e.update(&Event::TxSetMessage(
+            heapless::Vec::from_slice(&[0xaa, 0xbb, 0xcc]).unwrap(),
+        ))
+        .expect("Set message");
+        e.approve();
+
+        let ring_init = |onetime_private_key| Event::TxRingInit {
+            ring_size: RING_SIZE as u8,
+            value: 100,
+            token_id: 10,
+            real_index: 3,
+            subaddress_index: 8,
+            onetime_private_key,
+        };
+
+        let r = e.update(&ring_init(Some(
+            RistrettoPrivate::from_random(&mut OsRng {}).into(),
+        )));
+        assert_eq!(r, Err(Error::OnetimeKeysDisabled));
+
+        // Seed-derived keys remain available
+        let r = e.update(&ring_init(None));
+        assert_eq!(
+            r.map(|o| o.state()),
+            Ok(Some(State::SignRing(RingState::Init)))
+        );
+    }
+
+    /// Ensure dropped or duplicated sequenced requests are rejected
+    #[test]
+    fn tx_sequence() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+
+        let init = Event::TxInit {
+            account_index: 0,
+            num_rings: 1,
+            expected: None,
+        };
+        let message = Event::TxSetMessage(heapless::Vec::from_slice(&[0xaa, 0xbb, 0xcc]).unwrap());
+
+        // Transactions start at sequence 1
+        assert_eq!(e.update_seq(2, &init), Err(Error::UnexpectedSequence));
+        let r = e.update_seq(1, &init).expect("Init transaction");
+        assert!(matches!(r, Output::State { seq: 1, .. }));
+
+        // Duplicated and skipped requests are rejected without updating the digest
+        let digest = e.digest.clone();
+        assert_eq!(e.update_seq(1, &message), Err(Error::UnexpectedSequence));
+        assert_eq!(e.update_seq(3, &message), Err(Error::UnexpectedSequence));
+        assert_eq!(e.digest, digest);
+
+        let r = e.update_seq(2, &message).expect("Set message");
+        assert!(matches!(r, Output::State { seq: 2, .. }));
+
+        // Requests rejected in the current state do not consume the sequence or digest
+        let digest = e.digest.clone();
+        for _ in 0..2 {
+            assert_eq!(e.update_seq(3, &message), Err(Error::UnexpectedEvent));
+        }
+        assert_eq!(e.digest, digest);
+        assert_eq!(e.tx_seq(), 2);
+
+        // Non-mutating requests are not sequenced
+        let r = e.update(&Event::TxGetInfo).expect("Fetch info");
+        assert!(matches!(r, Output::State { seq: 2, .. }));
+    }
+
+    /// Ensure requests with corrupted payloads are rejected on receipt
+    #[test]
+    fn tx_payload_mac() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+
+        e.update_seq(
+            1,
+            &Event::TxInit {
+                account_index: 0,
+                num_rings: 1,
+                expected: None,
+            },
+        )
+        .expect("Init transaction");
+
+        let message = Event::TxSetMessage(heapless::Vec::from_slice(&[0xaa, 0xbb, 0xcc]).unwrap());
+        let h = message.hash().unwrap();
+        let mac = payload_mac(e.digest.as_bytes(), 2, &h);
+
+        // Mismatched MACs are rejected without updating the digest or sequence
+        let digest = e.digest.clone();
+        for (seq, mut m) in [(2, mac), (3, mac)] {
+            m[0] ^= 0x01;
+            let meta = ReqMeta { seq, mac: Some(m) };
+            assert_eq!(e.update_meta(meta, &message), Err(Error::InvalidMac));
+        }
+        assert_eq!(e.digest, digest);
+        assert_eq!(e.tx_seq(), 1);
+
+        let meta = ReqMeta {
+            seq: 2,
+            mac: Some(mac),
+        };
+        let r = e.update_meta(meta, &message).expect("Set message");
+        assert!(matches!(r, Output::State { seq: 2, .. }));
+    }
+
+    #[test]
+    fn tx_abort() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+
+        // Aborts require a transaction
+        assert_eq!(e.update(&Event::TxAbort), Err(Error::UnexpectedEvent));
+
+        e.update(&Event::TxInit {
+            account_index: 0,
+            num_rings: 1,
+            expected: None,
+        })
+        .expect("Init transaction");
+
+        let message = Event::TxSetMessage(heapless::Vec::from_slice(&[0xaa, 0xbb, 0xcc]).unwrap());
+        e.update(&message).expect("Set message");
+        assert_eq!(e.state(), State::Pending);
+
+        // Aborting returns the final digest and clears transaction context
+        let digest = e.digest.clone();
+        let r = e.update(&Event::TxAbort).expect("Abort transaction");
+        assert_eq!(
+            r,
+            Output::State {
+                state: State::Aborted,
+                seq: 0,
+                digest,
+                timeout_s: 0,
+            }
+        );
+        assert!(e.message.is_empty());
+        assert!(e.signed_digest.is_none());
+
+        // Aborted transactions may not be resumed
+        e.approve();
+        assert_eq!(e.state(), State::Aborted);
+        assert!(e
+            .update(&Event::TxRingInit {
+                ring_size: RING_SIZE as u8,
+                value: 100,
+                token_id: 10,
+                real_index: 3,
+                subaddress_index: 8,
+                onetime_private_key: None,
+            })
+            .is_err());
+
+        // New transactions may be started following an abort
+        e.update(&Event::TxInit {
+            account_index: 0,
+            num_rings: 1,
+            expected: None,
+        })
+        .expect("Init transaction");
+        assert_eq!(e.state(), State::BuildMemos(0));
+    }
+
+    #[test]
+    fn ring_progress() {
+        let tests = &[
+            (0, 0, 2, 0),
+            (50, 0, 2, 25),
+            (100, 0, 2, 50),
+            (0, 1, 2, 50),
+            (50, 1, 2, 75),
+            (100, 1, 2, 100),
+            (0, 2, 2, 100),
+        ];
+
+        for (c, r, t, e) in tests {
+            let p = compute_ring_progress(*c, *r, *t);
+            assert_eq!(p, *e, "progress error ({}.{}/{})", *r, *c, *t);
+        }
+    }
+
+    /// Check engine rejects key requests while locked
+    #[test]
+    fn lock_unlock() {
+        let mut e = Engine::new(SoftwareDriver::random());
+
+        // Locked, return pending message
+        let r = e.update(&Event::GetWalletKeys {
+            account_index: 0,
+            challenge: None,
+            path: None,
+        });
+        assert_eq!(r, Err(Error::ApprovalPending));
+
+        let r = e.update(&Event::GetSubaddressKeys {
+            account_index: 0,
+            subaddress_index: 1,
+            challenge: None,
+            path: None,
+        });
+        assert_eq!(r, Err(Error::ApprovalPending));
+
+        // Unlock engine
+        e.unlock();
+
+        // Unlocked, return view account and subaddress keys
+        let r = e
+            .update(&Event::GetWalletKeys {
+                account_index: 0,
+                challenge: None,
+                path: None,
+            })
+            .unwrap();
+        assert!(matches!(r, Output::WalletKeys { .. }));
+
+        let r = e
+            .update(&Event::GetSubaddressKeys {
+                account_index: 0,
+                subaddress_index: 1,
+                challenge: None,
+                path: None,
+            })
+            .unwrap();
+        assert!(matches!(r, Output::SubaddressKeys { .. }));
+    }
+
+    /// Check attested key requests return a valid attestation
+    #[test]
+    fn key_attestation() {
+        use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+        let mut e = Engine::new(SoftwareDriver::random());
+        e.unlock();
+
+        let challenge = [0xab; 32];
+
+        // Attestations are only returned where a challenge is provided
+        let r = e
+            .update(&Event::GetWalletKeys {
+                account_index: 0,
+                challenge: None,
+                path: None,
+            })
+            .unwrap();
+        assert!(matches!(
+            r,
+            Output::WalletKeys {
+                attestation: None,
+                ..
+            }
+        ));
+
+        let r = e
+            .update(&Event::GetWalletKeys {
+                account_index: 0,
+                challenge: Some(challenge),
+                path: None,
+            })
+            .unwrap();
+        let (view_private, spend_public, a) = match r {
+            Output::WalletKeys {
+                view_private,
+                spend_public,
+                attestation: Some(a),
+                ..
+            } => (view_private, spend_public, a),
+            r => panic!("Unexpected output: {r:?}"),
+        };
+
+        let d = digest_wallet_key_attestation(
+            &challenge,
+            0,
+            view_private.as_ref(),
+            spend_public.as_ref(),
+        );
+        let k = VerifyingKey::from_bytes(&a.public_key).unwrap();
+        k.verify(&d, &Signature::from_bytes(&a.signature)).unwrap();
+
+        // Subaddress attestations use the same key and bind the subaddress index
+        let r = e
+            .update(&Event::GetSubaddressKeys {
+                account_index: 0,
+                subaddress_index: 1,
+                challenge: Some(challenge),
+                path: None,
+            })
+            .unwrap();
+        let (view_private, spend_public, b) = match r {
+            Output::SubaddressKeys {
+                view_private,
+                spend_public,
+                attestation: Some(a),
+                ..
+            } => (view_private, spend_public, a),
+            r => panic!("Unexpected output: {r:?}"),
+        };
+        assert_eq!(a.public_key, b.public_key);
+
+        let d = digest_subaddress_key_attestation(
+            &challenge,
+            0,
+            1,
+            view_private.as_ref(),
+            spend_public.as_ref(),
+        );
+        k.verify(&d, &Signature::from_bytes(&b.signature)).unwrap();
+
+        let d = digest_subaddress_key_attestation(
+            &challenge,
+            0,
+            2,
+            view_private.as_ref(),
+            spend_public.as_ref(),
+        );
+        assert!(k.verify(&d, &Signature::from_bytes(&b.signature)).is_err());
+    }
+
+    /// Check explicit derivation path key requests
+    #[test]
+    fn key_path() {
+        use ledger_mob_apdu::key_path::{KEY_PATH_HARDENED, KEY_PATH_PREFIX};
+
+        let mut e = Engine::new(SoftwareDriver::random());
+        e.unlock();
+
+        // Default account paths match account index requests
+        let path = KeyPath::new(&[
+            KEY_PATH_PREFIX[0],
+            KEY_PATH_PREFIX[1],
+            2 | KEY_PATH_HARDENED,
+        ])
+        .unwrap();
+
+        let w = e.update(&Event::GetWalletKeys {
+            account_index: 2,
+            challenge: None,
+            path: None,
+        });
+        let r = e.update(&Event::GetWalletKeys {
+            account_index: path.account_index(),
+            challenge: None,
+            path: Some(path),
+        });
+        assert!(matches!(w, Ok(Output::WalletKeys { .. })));
+        assert_eq!(w, r);
+
+        let a = e.update(&Event::GetSubaddressKeys {
+            account_index: 2,
+            subaddress_index: 3,
+            challenge: None,
+            path: None,
+        });
+        let b = e.update(&Event::GetSubaddressKeys {
+            account_index: path.account_index(),
+            subaddress_index: 3,
+            challenge: None,
+            path: Some(path),
+        });
+        assert!(matches!(a, Ok(Output::SubaddressKeys { .. })));
+        assert_eq!(a, b);
+
+        // Custody paths derive distinct accounts
+        let custody = KeyPath::new(&[
+            KEY_PATH_PREFIX[0],
+            KEY_PATH_PREFIX[1],
+            7 | KEY_PATH_HARDENED,
+            2 | KEY_PATH_HARDENED,
+        ])
+        .unwrap();
+        let c = e.update(&Event::GetWalletKeys {
+            account_index: custody.account_index(),
+            challenge: None,
+            path: Some(custody),
+        });
+        assert!(matches!(c, Ok(Output::WalletKeys { .. })));
+        assert_ne!(w, c);
+
+        // Request paths are resolved for display on approval
+        let default = Event::GetSubaddressRange {
+            account_index: 2,
+            start: 0,
+            count: 4,
+        };
+        assert_eq!(default.key_path(), Some(path));
+        let explicit = Event::GetWalletKeys {
+            account_index: 0,
+            challenge: None,
+            path: Some(custody),
+        };
+        assert_eq!(explicit.key_path(), Some(custody));
+        assert_eq!(Event::GetRandom { len: 32 }.key_path(), None);
+
+        // Unhardened, foreign, attestation and device identity paths are rejected
+        let h = KEY_PATH_HARDENED;
+        let invalid: &[&[u32]] = &[
+            &[KEY_PATH_PREFIX[0], KEY_PATH_PREFIX[1], 2],
+            &[KEY_PATH_PREFIX[0], h, 2 | h],
+            &[KEY_PATH_PREFIX[0], KEY_PATH_PREFIX[1]],
+            &KEY_ATTESTATION_PATH,
+            &DEVICE_IDENT_PATH,
+        ];
+        for p in invalid {
+            let r = e.update(&Event::GetWalletKeys {
+                account_index: 0,
+                challenge: None,
+                path: Some(KeyPath::new(p).unwrap()),
+            });
+            assert_eq!(r, Err(Error::InvalidPath), "{p:x?}");
+        }
+
+        // Attestations are not available for explicit paths
+        let r = e.update(&Event::GetWalletKeys {
+            account_index: custody.account_index(),
+            challenge: Some([0xab; 32]),
+            path: Some(custody),
+        });
+        assert_eq!(r, Err(Error::InvalidPath));
+        let r = e.update(&Event::GetSubaddressKeys {
+            account_index: custody.account_index(),
+            subaddress_index: 3,
+            challenge: Some([0xab; 32]),
+            path: Some(custody),
+        });
+        assert_eq!(r, Err(Error::InvalidPath));
+    }
+
+    /// Check session challenges are bound to a single-use device nonce
+    #[test]
+    fn device_nonce() {
+        use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+        let mut e = Engine::new(SoftwareDriver::random());
+        e.unlock();
+
+        let challenge = [0xab; 32];
+
+        // Challenges are rejected without an outstanding nonce
+        let r = e.update(&Event::SignSessionChallenge { challenge });
+        assert_eq!(r, Err(Error::InvalidState));
+
+        let nonce = match e.update(&Event::GetDeviceNonce).unwrap() {
+            Output::DeviceNonce { nonce } => nonce,
+            r => panic!("Unexpected output: {r:?}"),
+        };
+
+        let (public_key, signature) = match e.update(&Event::SignSessionChallenge { challenge }) {
+            Ok(Output::Identity {
+                public_key,
+                signature,
+            }) => (public_key, signature),
+            r => panic!("Unexpected output: {r:?}"),
+        };
+
+        let k = VerifyingKey::from_bytes(&public_key).unwrap();
+        let d = digest_session_challenge(&nonce, &challenge);
+        k.verify(&d, &Signature::from_bytes(&signature)).unwrap();
+
+        // Nonces are single use
+        let r = e.update(&Event::SignSessionChallenge { challenge });
+        assert_eq!(r, Err(Error::InvalidState));
+
+        // And cleared on lock
+        e.update(&Event::GetDeviceNonce).unwrap();
+        e.lock();
+        let r = e.update(&Event::SignSessionChallenge { challenge });
+        assert_eq!(r, Err(Error::InvalidState));
+
+        // Fresh nonces bind distinct signatures under the same key
+        e.unlock();
+        e.update(&Event::GetDeviceNonce).unwrap();
+        match e.update(&Event::SignSessionChallenge { challenge }) {
+            Ok(Output::Identity {
+                public_key: p,
+                signature: s,
+            }) => {
+                assert_eq!(p, public_key);
+                assert_ne!(s, signature);
+            }
+            r => panic!("Unexpected output: {r:?}"),
+        }
+    }
+
+    /// Check memo signing is limited to supported kinds
+    #[cfg(feature = "memo")]
+    #[test]
+    fn memo_kinds() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+        let public_key = RistrettoPublic::from(&*PRIVATE_KEY);
+
+        e.update(&Event::TxInit {
+            account_index: 0,
+            num_rings: 1,
+            expected: None,
+        })
+        .unwrap();
+
+        let mut payload = [0u8; 48];
+        payload[16..24].copy_from_slice(&1234u64.to_be_bytes());
+
+        for (kind, payload, expected) in [
+            ([0x00, 0x01], payload, Err(Error::UnsupportedMemo)),
+            (
+                MemoKind::Destination.to_bytes(),
+                payload,
+                Err(Error::UnsupportedMemo),
+            ),
+            (
+                MemoKind::AuthenticatedSenderWithPaymentRequestId.to_bytes(),
+                [0u8; 48],
+                Err(Error::InvalidMemo),
+            ),
+            (
+                MemoKind::AuthenticatedSenderWithPaymentRequestId.to_bytes(),
+                payload,
+                Ok(()),
+            ),
+            (
+                MemoKind::AuthenticatedSenderWithPaymentIntentId.to_bytes(),
+                payload,
+                Ok(()),
+            ),
+        ] {
+            let r = e.update(&Event::TxSignMemo {
+                subaddress_index: 0,
+                tx_public_key: public_key.into(),
+                receiver_view_public: public_key.into(),
+                kind,
+                payload,
+                payload_len: 48,
+                continuation: false,
+                more: false,
+            });
+
+            match expected {
+                Ok(_) => assert!(matches!(r, Ok(Output::MemoHmac { .. }))),
+                Err(err) => assert_eq!(r, Err(err)),
+            }
+        }
+
+        // Signed memos are decoded for display
+        assert_eq!(e.memos().len(), 2);
+        assert_eq!(
+            e.memos()[0].kind,
+            MemoKind::AuthenticatedSenderWithPaymentRequestId
+        );
+        assert_eq!(
+            e.memos()[1].kind,
+            MemoKind::AuthenticatedSenderWithPaymentIntentId
+        );
+        assert!(e.memos().iter().all(|m| m.payment_id == Some(1234)));
+    }
+
+    /// Check memo payloads may be streamed across chunked requests
+    #[cfg(feature = "memo")]
+    #[test]
+    fn memo_chunks() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+        let public_key = RistrettoPublic::from(&*PRIVATE_KEY);
+        let kind = MemoKind::AuthenticatedSender.to_bytes();
+
+        e.update(&Event::TxInit {
+            account_index: 0,
+            num_rings: 1,
+            expected: None,
+        })
+        .unwrap();
+
+        let chunk = |payload_len, continuation, more| Event::TxSignMemo {
+            subaddress_index: 0,
+            tx_public_key: public_key.into(),
+            receiver_view_public: public_key.into(),
+            kind,
+            payload: [0u8; 48],
+            payload_len,
+            continuation,
+            more,
+        };
+
+        // Continuations require a prior chunk
+        assert_eq!(
+            e.update(&chunk(16, true, false)),
+            Err(Error::UnexpectedEvent)
+        );
+
+        // Leading chunks must be full
+        assert_eq!(e.update(&chunk(16, false, true)), Err(Error::InvalidLength));
+
+        // Intermediate chunks return state only
+        let r = e.update(&chunk(48, false, true)).unwrap();
+        assert!(matches!(r, Output::State { .. }));
+        assert_eq!(e.state(), State::BuildMemos(0));
+
+        let r = e.update(&chunk(48, true, true)).unwrap();
+        assert!(matches!(r, Output::State { .. }));
+
+        // Continuations must match the memo being signed
+        let mut mismatch = chunk(16, true, false);
+        if let Event::TxSignMemo { kind, .. } = &mut mismatch {
+            *kind = MemoKind::AuthenticatedSenderWithPaymentIntentId.to_bytes();
+        }
+        assert_eq!(e.update(&mismatch), Err(Error::InvalidMemo));
+
+        // Final chunk returns the memo HMAC
+        let r = e.update(&chunk(16, true, false)).unwrap();
+        assert!(matches!(r, Output::MemoHmac { .. }));
+        assert_eq!(e.state(), State::BuildMemos(1));
+        assert_eq!(e.memos().len(), 1);
+
+        // Signing context is cleared on completion
+        assert_eq!(
+            e.update(&chunk(16, true, false)),
+            Err(Error::UnexpectedEvent)
+        );
+    }
+
+    /// Check the first memo is held for review where enabled
+    #[cfg(feature = "memo")]
+    #[test]
+    fn memo_review() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+        e.set_memo_review(true);
+        let public_key = RistrettoPublic::from(&*PRIVATE_KEY);
+
+        let mut payload = [0u8; 48];
+        payload[16..24].copy_from_slice(&1234u64.to_be_bytes());
+
+        let memo = Event::TxSignMemo {
+            subaddress_index: 0,
+            tx_public_key: public_key.into(),
+            receiver_view_public: public_key.into(),
+            kind: MemoKind::AuthenticatedSenderWithPaymentRequestId.to_bytes(),
+            payload,
+            payload_len: 48,
+            continuation: false,
+            more: false,
+        };
+
+        for approve in [true, false] {
+            e.update(&Event::TxInit {
+                account_index: 0,
+                num_rings: 1,
+                expected: None,
+            })
+            .unwrap();
+
+            // First memo is held pending review
+            assert_eq!(e.update(&memo), Err(Error::ApprovalPending));
+            assert_eq!(e.state(), State::MemoReview);
+
+            let m = e.memo_request().expect("pending memo");
+            assert_eq!(m.kind, MemoKind::AuthenticatedSenderWithPaymentRequestId);
+            assert_eq!(m.payment_id, Some(1234));
+
+            // Re-issued requests remain pending until reviewed
+            assert_eq!(e.update(&memo), Err(Error::ApprovalPending));
+
+            e.memo_approve(approve);
+            assert_eq!(e.memo_request(), None);
+
+            if !approve {
+                assert_eq!(e.state(), State::Deny);
+                assert_eq!(e.update(&memo), Err(Error::UnexpectedEvent));
+                continue;
+            }
+
+            // Approved memo and subsequent memos are signed without review
+            assert_eq!(e.state(), State::BuildMemos(0));
+            for n in 1..=2 {
+                let r = e.update(&memo);
+                assert!(matches!(r, Ok(Output::MemoHmac { .. })));
+                assert_eq!(e.state(), State::BuildMemos(n));
+            }
+        }
+    }
+
+    /// Check subaddress range requests match individual derivations
+    #[test]
+    fn subaddress_range() {
+        let drv = SoftwareDriver::random();
+        let account = drv.account(0);
+        let mut e = Engine::new(drv);
+
+        let r = e.update(&Event::GetSubaddressRange {
+            account_index: 0,
+            start: 4,
+            count: SUBADDRESS_RANGE_MAX as u8,
+        });
+        assert_eq!(r, Err(Error::ApprovalPending));
+
+        e.approve_scope(Approvals::SUBADDRESS_KEYS);
+
+        let r = e
+            .update(&Event::GetSubaddressRange {
+                account_index: 0,
+                start: 4,
+                count: SUBADDRESS_RANGE_MAX as u8,
+            })
+            .unwrap();
+
+        let keys = match r {
+            Output::SubaddressRange {
+                account_index: 0,
+                start: 4,
+                keys,
+            } => keys,
+            _ => panic!("Unexpected output: {r:?}"),
+        };
+        assert_eq!(keys.len(), SUBADDRESS_RANGE_MAX);
+
+        for (i, (view_public, spend_public)) in keys.iter().enumerate() {
+            let subaddress = account.subaddress(4 + i as u64);
+            assert_eq!(view_public, &subaddress.view_public_key());
+            assert_eq!(spend_public, &subaddress.spend_public_key());
+        }
+
+        // Empty, oversized, and overflowing ranges are rejected
+        for (start, count) in [(0, 0), (0, SUBADDRESS_RANGE_MAX as u8 + 1), (u64::MAX, 1)] {
+            let r = e.update(&Event::GetSubaddressRange {
+                account_index: 0,
+                start,
+                count,
+            });
+            assert_eq!(r, Err(Error::InvalidLength));
+        }
+    }
+
+    #[test]
+    fn subaddress_keys_batch() {
+        let drv = SoftwareDriver::random();
+        let account = drv.account(0);
+        let mut e = Engine::new(drv);
+
+        let evt = Event::GetSubaddressKeysBatch {
+            account_index: 0,
+            start: 7,
+            count: SUBADDRESS_KEYS_BATCH_MAX as u8,
+        };
+
+        let r = e.update(&evt);
+        assert_eq!(r, Err(Error::ApprovalPending));
+
+        e.approve_scope(Approvals::SUBADDRESS_KEYS);
+
+        let r = e.update(&evt).unwrap();
+
+        let keys = match r {
+            Output::SubaddressKeysBatch {
+                account_index: 0,
+                start: 7,
+                keys,
+            } => keys,
+            _ => panic!("Unexpected output: {r:?}"),
+        };
+        assert_eq!(keys.len(), SUBADDRESS_KEYS_BATCH_MAX);
+
+        for (i, (spend_public, view_private)) in keys.iter().enumerate() {
+            let subaddress = account.subaddress(7 + i as u64);
+            assert_eq!(spend_public, &subaddress.spend_public_key());
+            assert_eq!(view_private, subaddress.view_private_key());
+        }
+
+        // Batches count towards the key export limit
+        assert_eq!(e.key_counters().1, SUBADDRESS_KEYS_BATCH_MAX as u32);
+
+        // Empty, oversized, and overflowing batches are rejected
+        for (start, count) in [
+            (0, 0),
+            (0, SUBADDRESS_KEYS_BATCH_MAX as u8 + 1),
+            (u64::MAX, 1),
+        ] {
+            let r = e.update(&Event::GetSubaddressKeysBatch {
+                account_index: 0,
+                start,
+                count,
+            });
+            assert_eq!(r, Err(Error::InvalidLength));
+        }
+    }
+
+    #[test]
+    fn public_address() {
+        use encdec::Decode;
+        use ledger_mob_apdu::address::{PublicAddressResp, PUBLIC_ADDRESS_MAX};
+
+        use crate::helpers::{b58_decode_public_address, b58_encode_public_address};
+
+        let drv = SoftwareDriver::random();
+        let account = drv.account(0);
+        let mut e = Engine::new(drv);
+
+        let evt = Event::GetPublicAddress {
+            account_index: 0,
+            subaddress_index: 2,
+            fog_id: FogId::MobTest,
+        };
+
+        // Public addresses require subaddress key approval
+        assert_eq!(e.update(&evt), Err(Error::ApprovalPending));
+
+        e.approve_scope(Approvals::SUBADDRESS_KEYS);
+
+        let r = e.update(&evt).unwrap();
+        let (view_public, spend_public, fog_sig) = match r {
+            Output::PublicAddress {
+                view_public,
+                spend_public,
+                fog_id: FogId::MobTest,
+                fog_sig,
+            } => (view_public, spend_public, fog_sig),
+            _ => panic!("Unexpected output: {r:?}"),
+        };
+
+        let subaddress = account.subaddress(2);
+        assert_eq!(view_public, subaddress.view_public_key());
+        assert_eq!(spend_public, subaddress.spend_public_key());
+        assert_ne!(fog_sig, [0u8; 64]);
+
+        // Encoded responses contain the b58 address
+        let mut buff = [0u8; 1024];
+        let n = r.encode(&mut buff).unwrap();
+        let (resp, _) = PublicAddressResp::decode(&buff[..n]).unwrap();
+
+        let expected = b58_encode_public_address::<PUBLIC_ADDRESS_MAX>(
+            &PublicSubaddress::from(&subaddress),
+            FogId::MobTest.url(),
+            &fog_sig,
+        )
+        .unwrap();
+        assert_eq!(resp.address, expected.as_str());
+        assert_eq!(resp.fog_sig, fog_sig);
+
+        let decoded = b58_decode_public_address(resp.address).unwrap();
+        assert_eq!(decoded.fog_report_url.as_str(), FogId::MobTest.url());
+    }
+
+    #[test]
+    fn subaddress_keys_fog() {
+        use encdec::Decode;
+        use ledger_mob_apdu::subaddress_keys::SubaddressKeyFogResp;
+
+        let drv = SoftwareDriver::random();
+        let account = drv.account(0);
+        let mut e = Engine::new(drv);
+
+        let evt = Event::GetSubaddressKeysFog {
+            account_index: 0,
+            subaddress_index: 3,
+            fog_id: FogId::MobTest,
+        };
+
+        // Requires subaddress key approval
+        assert_eq!(e.update(&evt), Err(Error::ApprovalPending));
+
+        e.approve_scope(Approvals::SUBADDRESS_KEYS);
+
+        let r = e.update(&evt).unwrap();
+        let subaddress = account.subaddress(3);
+
+        let mut buff = [0u8; 256];
+        let n = r.encode(&mut buff).unwrap();
+        let (resp, _) = SubaddressKeyFogResp::decode(&buff[..n]).unwrap();
+
+        assert_eq!(resp.account_index, 0);
+        assert_eq!(resp.subaddress_index, 3);
+        assert_eq!(&resp.view_private, subaddress.view_private_key());
+        assert_eq!(resp.spend_public, subaddress.spend_public_key());
+        assert_eq!(resp.fog_id, FogId::MobTest);
+        assert_ne!(resp.fog_sig, [0u8; 64]);
+
+        // Fog signatures are zeroed where no fog is set
+        let r = e
+            .update(&Event::GetSubaddressKeysFog {
+                account_index: 0,
+                subaddress_index: 3,
+                fog_id: FogId::None,
+            })
+            .unwrap();
+        assert!(matches!(
+            r,
+            Output::SubaddressKeysFog {
+                fog_id: FogId::None,
+                fog_sig,
+                ..
+            } if fog_sig == [0u8; 64]
+        ));
+
+        // Requests count towards the key export limit
+        assert_eq!(e.key_counters().1, 2);
+    }
+
+    #[test]
+    fn address_verify() {
+        use mc_crypto_keys::RistrettoSignature;
+
+        use crate::helpers::ADDRESS_CONTEXT;
+
+        let drv = SoftwareDriver::random();
+        let account = drv.account(0);
+        let mut e = Engine::new(drv);
+
+        let challenge: [u8; 32] = rand::random();
+        let evt = Event::AddressVerify {
+            account_index: 0,
+            subaddress_index: 3,
+            fog_id: FogId::None,
+            challenge,
+        };
+
+        // Address fetch requires approval
+        e.update(&evt).unwrap();
+        assert_eq!(e.state(), State::Address(AddressState::Pending));
+        assert!(e.address_verify().is_some());
+        assert_eq!(e.update(&Event::AddressGet), Err(Error::ApprovalPending));
+
+        // Rejected addresses return an error
+        e.address_approve(false);
+        assert_eq!(e.update(&Event::AddressGet), Err(Error::AddressRejected));
+
+        // Approved addresses return a signed acknowledgement
+        e.update(&evt).unwrap();
+        e.address_approve(true);
+
+        let r = e.update(&Event::AddressGet).unwrap();
+        assert_eq!(e.state(), State::Init);
+
+        let (view_public, spend_public, signature) = match r {
+            Output::Address {
+                view_public,
+                spend_public,
+                fog_id: FogId::None,
+                fog_sig,
+                signature,
+            } if fog_sig == [0u8; 64] => (view_public, spend_public, signature),
+            _ => panic!("Unexpected output: {r:?}"),
+        };
+
+        let subaddress = account.subaddress(3);
+        assert_eq!(view_public, subaddress.view_public_key());
+        assert_eq!(spend_public, subaddress.spend_public_key());
+
+        let short_hash = digest_public_address(&PublicSubaddress::from(&subaddress), "", &[]);
+        let message = AddressVerify::new(0, 3, FogId::None, challenge).message(short_hash.as_ref());
+
+        let signature = RistrettoSignature::try_from(&signature[..]).unwrap();
+        let spend_public: &RistrettoPublic = spend_public.as_ref();
+        spend_public
+            .verify_schnorrkel(ADDRESS_CONTEXT, &message, &signature)
+            .unwrap();
+    }
+
+    #[cfg(feature = "ident")]
+    #[test]
+    fn ident_derivation() {
+        use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+        use encdec::Encode;
+        use ledger_mob_apdu::ident::{IdentDerivation, IdentSignReq, IDENT_CHALLENGE_MAX};
+        use ledger_proto::ApduStatic;
+
+        let mut e = Engine::new(SoftwareDriver::random());
+
+        // Long challenges are decoded from reassembled (chunked) requests
+        let challenge = [0xab; IDENT_CHALLENGE_MAX];
+        let mut buff = [0u8; 512];
+        let mut keys = std::vec::Vec::new();
+
+        for derivation in IdentDerivation::iter() {
+            let mut req = IdentSignReq::new(0, "ssh://someone@something.com", &challenge);
+            req.derivation = derivation;
+
+            let n = req.encode(&mut buff).unwrap();
+            let evt = Event::parse(IdentSignReq::INS, &buff[..n]).unwrap();
+
+            e.update(&evt).unwrap();
+            assert_eq!(e.ident().map(|i| i.derivation()), Some(derivation));
+
+            e.ident_approve(true);
+
+            let (public_key, signature) = match e.update(&Event::IdentGet).unwrap() {
+                Output::Identity {
+                    public_key,
+                    signature,
+                } => (public_key, signature),
+                r => panic!("Unexpected output: {r:?}"),
+            };
+
+            // Check signature over the full challenge
+            let public_key = VerifyingKey::from_bytes(&public_key).unwrap();
+            public_key
+                .verify(&challenge, &Signature::from_bytes(&signature))
+                .unwrap();
+
+            keys.push(public_key);
+        }
+
+        // Derivations produce distinct identities
+        assert_ne!(keys[0], keys[1]);
+    }
+
+    #[test]
+    fn set_fog_id() {
+        let mut e = Engine::new(SoftwareDriver::random());
+
+        let evt = Event::SetFogId {
+            fog_id: FogId::SignalMain,
+        };
+
+        // Fog changes require approval
+        assert_eq!(e.fog_request(), None);
+        e.update(&evt).unwrap();
+        assert_eq!(e.state(), State::Fog(FogState::Pending));
+        assert_eq!(e.fog_request(), Some(FogId::SignalMain));
+
+        // Rejected changes are not recorded
+        e.fog_approve(false);
+        assert_eq!(e.state(), State::Fog(FogState::Denied));
+        assert_eq!(e.fog_request(), None);
+        assert_eq!(e.audit_log().len(), 0);
+
+        // Approved changes are recorded in the audit log
+        e.update(&evt).unwrap();
+        e.fog_approve(true);
+        assert_eq!(e.state(), State::Fog(FogState::Approved));
+
+        let entry = e.audit_log().get(0).unwrap();
+        assert_eq!(entry.kind, AuditKind::FogId);
+        assert_eq!(entry.data[0], FogId::SignalMain as u8);
+        assert_eq!(e.fog_id(), FogId::SignalMain);
+    }
+
+    #[test]
+    fn settings() {
+        let mut e = Engine::new(SoftwareDriver::random());
+
+        e.set_fog_id(FogId::MobTest);
+        e.set_lock_timeout(300);
+        e.set_default_account(2);
+        e.set_blind_signing(false);
+        e.set_onetime_keys(false);
+
+        let r = e.update(&Event::GetSettings).unwrap();
+        assert_eq!(
+            r,
+            Output::Settings {
+                fog_id: FogId::MobTest,
+                blind_signing: !cfg!(feature = "summary"),
+                onetime_keys: false,
+                lock_timeout_s: 300,
+                account_index: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn audit_log() {
+        let mut e = Engine::new(SoftwareDriver::random());
+
+        let r = e.update(&Event::GetAuditLog { start: 0 }).unwrap();
+        assert!(
+            matches!(r, Output::AuditLog { num_held: 0, ref entries, .. } if entries.is_empty())
+        );
+
+        // Record key export and transaction approvals
+        e.set_ticks(10);
+        e.approve_scope(Approvals::KEY_IMAGES);
+
+        e.set_ticks(20);
+        e.state = State::Pending;
+        e.approve();
+        let digest = *e.digest.as_bytes();
+
+        let r = e.update(&Event::GetAuditLog { start: 0 }).unwrap();
+        let entries = match r {
+            Output::AuditLog {
+                start: 0,
+                num_held: 2,
+                entries,
+            } => entries,
+            _ => panic!("Unexpected output: {r:?}"),
+        };
+
+        // Entries are returned most recent first
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].kind, AuditKind::Transaction);
+        assert_eq!(entries[0].ticks, 20);
+        assert_eq!(entries[0].data, digest);
+        assert_eq!(entries[1].kind, AuditKind::KeyExport);
+        assert_eq!(entries[1].ticks, 10);
+        assert_eq!(entries[1].data[0], Approvals::KEY_IMAGES.bits());
+
+        // Offsets skip recent entries
+        let r = e.update(&Event::GetAuditLog { start: 1 }).unwrap();
+        assert!(matches!(r, Output::AuditLog { ref entries, .. } if entries.len() == 1));
+    }
+
+    /// Check scoped approvals only unlock the approved capabilities
+    #[test]
+    fn approval_scopes() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        let txout_public_key = RistrettoPublic::from(&*PRIVATE_KEY).into();
+
+        // Approve scanning only
+        e.approve_scope(Approvals::KEY_IMAGES);
+        assert!(!e.is_unlocked());
+
+        let r = e.update(&Event::GetKeyImage {
+            account_index: 0,
+            subaddress_index: 0,
+            txout_public_key,
+        });
+        assert!(matches!(r, Ok(Output::KeyImage { .. })));
+
+        // View key exports remain locked
+        let r = e.update(&Event::GetWalletKeys {
+            account_index: 0,
+            challenge: None,
+            path: None,
+        });
+        assert_eq!(r, Err(Error::ApprovalPending));
+
+        let r = e.update(&Event::GetSubaddressKeys {
+            account_index: 0,
+            subaddress_index: 1,
+            challenge: None,
+            path: None,
+        });
+        assert_eq!(r, Err(Error::ApprovalPending));
+
+        // Approving subaddress keys leaves root keys locked
+        e.approve_scope(Approvals::SUBADDRESS_KEYS);
+
+        let r = e.update(&Event::GetSubaddressKeys {
+            account_index: 0,
+            subaddress_index: 1,
+            challenge: None,
+            path: None,
+        });
+        assert!(matches!(r, Ok(Output::SubaddressKeys { .. })));
+
+        let r = e.update(&Event::GetWalletKeys {
+            account_index: 0,
+            challenge: None,
+            path: None,
+        });
+        assert_eq!(r, Err(Error::ApprovalPending));
+
+        // Locking clears all approvals
+        e.lock();
+        assert_eq!(e.approvals(), Approvals::empty());
+    }
+
+    /// Check malformed or unsupported APDUs return errors rather than panicking
+    #[test]
+    fn parse_errors() {
+        use encdec::Encode;
+        use ledger_mob_apdu::tx::{TxSetMessage, TX_MESSAGE_MAX};
+        use ledger_proto::{ApduError, ApduStatic};
+
+        // Truncated APDUs return errors for all instructions
+        let buff = [0xffu8; 8];
+        for ins in 0..=u8::MAX {
+            for n in 0..buff.len() {
+                let _ = Event::parse(ins, &buff[..n]);
+            }
+        }
+
+        // Unknown instructions are not supported
+        assert!(matches!(
+            Event::parse(0xff, &[]),
+            Err(ParseError::NotSupported(0xff))
+        ));
+
+        // Over-length messages are rejected
+        let mut buff = [0u8; 128];
+        let message = [0xab; TX_MESSAGE_MAX + 1];
+
+        let n = TxSetMessage::new(&message).encode(&mut buff).unwrap();
+        assert!(matches!(
+            Event::parse(TxSetMessage::INS, &buff[..n]),
+            Err(ParseError::Apdu(ApduError::InvalidLength))
+        ));
+
+        let n = TxSetMessage::new(&message[..TX_MESSAGE_MAX])
+            .encode(&mut buff)
+            .unwrap();
+        assert!(matches!(
+            Event::parse(TxSetMessage::INS, &buff[..n]),
+            Ok(Event::TxSetMessage(m)) if m.len() == TX_MESSAGE_MAX
+        ));
+    }
+
+    /// Check compact summary encodings parse to the same events as standard encodings
+    #[cfg(feature = "summary")]
+    #[test]
+    fn compact_summary_parse() {
+        use encdec::Encode;
+        use ledger_mob_apdu::tx::{
+            TxAuthenticated, TxSequenced, TxSummaryAddTxOutUnblinding,
+            TxSummaryAddTxOutUnblindingCompact,
+        };
+        use ledger_proto::ApduStatic;
+        use mc_core::account::{PublicSubaddress, ShortAddressHash};
+        use mc_transaction_types::UnmaskedAmount;
+
+        let address = PublicSubaddress {
+            view_public: RistrettoPublic::from_random(&mut OsRng {}).into(),
+            spend_public: RistrettoPublic::from_random(&mut OsRng {}).into(),
+        };
+
+        let apdu = TxSummaryAddTxOutUnblinding::new(
+            1,
+            &UnmaskedAmount {
+                value: 10_000,
+                token_id: 0,
+                blinding: Scalar::random(&mut OsRng {}).into(),
+            },
+            Some((address, ShortAddressHash::from([0xab; 16]))),
+            None,
+            Some(RistrettoPrivate::from_random(&mut OsRng {}).into()),
+        )
+        .unwrap()
+        .with_seq(3)
+        .with_mac([0xcd; 8]);
+
+        let mut buff = [0u8; 256];
+
+        let n = apdu.encode(&mut buff).unwrap();
+        let (a, a_meta) = Event::parse_meta(TxSummaryAddTxOutUnblinding::INS, &buff[..n]).unwrap();
+
+        let c = TxSummaryAddTxOutUnblindingCompact::from(apdu.clone());
+        let m = c.encode(&mut buff).unwrap();
+        assert!(m < n);
+        let (b, b_meta) =
+            Event::parse_meta(TxSummaryAddTxOutUnblindingCompact::INS, &buff[..m]).unwrap();
+
+        assert_eq!(a_meta, b_meta);
+        assert_eq!(std::format!("{a:?}"), std::format!("{b:?}"));
+        assert_eq!(c.event_hash(), apdu.event_hash());
+    }
+
+    /// Check key images from host-supplied onetime keys require explicit approval
+    #[test]
+    fn key_image_from_key() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        let evt = Event::GetKeyImageFromKey {
+            account_index: 0,
+            subaddress_index: 2,
+            onetime_private_key: PRIVATE_KEY.clone().into(),
+        };
+
+        // Scanning approval does not permit host-supplied keys
+        e.approve_scope(Approvals::KEY_IMAGES);
+        assert_eq!(e.update(&evt), Err(Error::ApprovalPending));
+        assert_eq!(evt.required_approval(), Some(Approvals::ONETIME_KEY_IMAGES));
+
+        e.approve_scope(Approvals::ONETIME_KEY_IMAGES);
+        let r = e.update(&evt).unwrap();
+        assert_eq!(
+            r,
+            Output::KeyImage {
+                account_index: 0,
+                subaddress_index: 2,
+                key_image: KeyImage::from(&*PRIVATE_KEY),
+                spend_public: e.get_account(0).subaddress(2).spend_public_key(),
+            }
+        );
+    }
+
+    /// Check serviced key and key image requests are counted until lock
+    #[test]
+    fn request_count() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        e.unlock();
+        assert_eq!(e.request_count(), 0);
+
+        e.update(&Event::GetWalletKeys {
+            account_index: 0,
+            challenge: None,
+            path: None,
+        })
+        .unwrap();
+
+        e.update(&Event::GetKeyImage {
+            account_index: 0,
+            subaddress_index: 0,
+            txout_public_key: RistrettoPublic::from(&*PRIVATE_KEY).into(),
+        })
+        .unwrap();
+
+        assert_eq!(e.request_count(), 2);
+
+        // Public key and address requests are counted
+        e.update(&Event::GetSubaddressRange {
+            account_index: 0,
+            start: 0,
+            count: 2,
+        })
+        .unwrap();
+
+        e.update(&Event::GetPublicAddress {
+            account_index: 0,
+            subaddress_index: 0,
+            fog_id: FogId::None,
+        })
+        .unwrap();
+
+        assert_eq!(e.request_count(), 4);
+
+        // Counter is cleared on lock, locked requests are not counted
+        e.lock();
+        assert_eq!(e.request_count(), 0);
+
+        let r = e.update(&Event::GetKeyImage {
+            account_index: 0,
+            subaddress_index: 0,
+            txout_public_key: RistrettoPublic::from(&*PRIVATE_KEY).into(),
+        });
+        assert_eq!(r, Err(Error::ApprovalPending));
+        assert_eq!(e.request_count(), 0);
+    }
+
+    /// Check key export counters and re-approval limit
+    #[test]
+    fn key_request_limit() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        e.set_key_request_limit(3);
+        e.unlock();
+
+        // Export keys up to the limit
+        for i in 0..3 {
+            assert!(e.is_unlocked());
+
+            let evt = match i {
+                0 => Event::GetWalletKeys {
+                    account_index: 0,
+                    challenge: None,
+                    path: None,
+                },
+                _ => Event::GetSubaddressKeys {
+                    account_index: 0,
+                    subaddress_index: i,
+                    challenge: None,
+                    path: None,
+                },
+            };
+            e.update(&evt).unwrap();
+        }
+
+        // Counters are exposed to the host
+        let r = e.update(&Event::GetKeyCounters).unwrap();
+        assert_eq!(
+            r,
+            Output::KeyCounters {
+                wallet_keys: 1,
+                subaddress_keys: 2,
+                limit: 3
+            }
+        );
 
-    use rand_core::OsRng;
-    use strum::IntoEnumIterator;
+        // Limits apply per scope, so both scopes remain approved
+        assert!(e.is_unlocked());
 
-    use mc_core::{account::RingCtAddress, subaddress::Subaddress};
-    use mc_crypto_keys::{RistrettoPrivate, RistrettoPublic};
-    use mc_crypto_ring_signature::{
-        onetime_keys::{recover_onetime_private_key, recover_public_subaddress_spend_key},
-        CompressedCommitment, CurveScalar, MlsagVerify, Scalar,
-    };
-    use mc_util_from_random::FromRandom;
+        // Reaching the limit revokes only the exhausted scope
+        e.update(&Event::GetSubaddressKeys {
+            account_index: 0,
+            subaddress_index: 3,
+            challenge: None,
+            path: None,
+        })
+        .unwrap();
+        assert!(!e.is_approved(Approvals::SUBADDRESS_KEYS));
+        assert!(e.is_approved(Approvals::WALLET_KEYS));
+        assert!(e.is_approved(Approvals::KEY_IMAGES | Approvals::ONETIME_KEY_IMAGES));
 
-    use super::*;
+        let r = e.update(&Event::GetSubaddressKeys {
+            account_index: 0,
+            subaddress_index: 4,
+            challenge: None,
+            path: None,
+        });
+        assert_eq!(r, Err(Error::ApprovalPending));
 
-    use ledger_mob_tests::mlsag::RingMLSAGParameters;
+        // Other scopes are unaffected
+        e.update(&Event::GetWalletKeys {
+            account_index: 0,
+            challenge: None,
+            path: None,
+        })
+        .unwrap();
+        e.update(&Event::GetKeyImage {
+            account_index: 0,
+            subaddress_index: 0,
+            txout_public_key: RistrettoPublic::from(&*PRIVATE_KEY).into(),
+        })
+        .unwrap();
 
-    lazy_static::lazy_static! {
-        pub static ref PRIVATE_KEY: RistrettoPrivate = RistrettoPrivate::from_random(&mut OsRng{});
+        // Counters only reset for the capabilities approved
+        e.approve_scope(Approvals::KEY_IMAGES);
+        assert_eq!(e.key_counters(), (2, 3));
 
-        /// Mocked out test values, only for state tests
-        pub static ref TESTS: [(State, Event); 4] = [
-            (State::Init, Event::TxInit{ account_index: 0, num_rings: 13 }),
+        e.approve_scope(Approvals::SUBADDRESS_KEYS);
+        assert_eq!(e.key_counters(), (2, 0));
 
-            (State::SetMessage, Event::TxSetMessage(heapless::Vec::from_slice(&[0xaa, 0xbb, 0xcc]).unwrap())),
+        // Batches exceeding the remaining exports are rejected without export
+        e.update(&Event::GetSubaddressKeys {
+            account_index: 0,
+            subaddress_index: 5,
+            challenge: None,
+            path: None,
+        })
+        .unwrap();
 
-            (State::Ready, Event::TxRingInit{ ring_size: RING_SIZE as u8, value: 100, token_id: 10, real_index: 3, subaddress_index: 8, onetime_private_key: None }),
+        let r = e.update(&Event::GetSubaddressKeysBatch {
+            account_index: 0,
+            start: 0,
+            count: 3,
+        });
+        assert_eq!(r, Err(Error::KeyLimitExceeded));
+        assert_eq!(e.key_counters(), (2, 1));
+        assert!(e.is_approved(Approvals::SUBADDRESS_KEYS));
 
-            (State::SignRing(RingState::Init), Event::TxSetBlinding{ blinding: Scalar::random(&mut OsRng{}), output_blinding: Scalar::random(&mut OsRng{})}),
+        e.update(&Event::GetSubaddressKeysBatch {
+            account_index: 0,
+            start: 0,
+            count: 2,
+        })
+        .unwrap();
+        assert_eq!(e.key_counters(), (2, 3));
+        assert!(!e.is_approved(Approvals::SUBADDRESS_KEYS));
 
-            #[cfg(nyet)]
-            (State::BuildRing, Event::TxAddTxout(ReducedTxOut{
-                public_key: CompressedRistrettoPublic::from(&PRIVATE_KEY),
-                target_key: CompressedRistrettoPublic::from(&PRIVATE_KEY),
-                commitment: CompressedCommitment::default(),
-            })),
-        ];
+        // Counters reset on re-approval
+        e.unlock();
+        assert_eq!(e.key_counters(), (0, 0));
     }
 
-    /// Driver implementation for test use
-    pub struct TestDriver {
-        /// BIP39 Mnemonic derived seed
-        pub seed: [u8; 32],
-    }
+    #[test]
+    fn random_len() {
+        let mut e = Engine::new(SoftwareDriver::random());
 
-    impl TestDriver {
-        /// Create a new test driver with a random seed
-        pub fn new() -> Self {
-            Self {
-                seed: rand::random(),
-            }
+        for len in [1, 32, RANDOM_LEN_MAX as u8] {
+            let r = e.update(&Event::GetRandom { len }).unwrap();
+            assert!(matches!(r, Output::RandomValue { value } if value.len() == len as usize));
         }
 
-        /// Fetch an account from the seeded test driver
-        pub fn account(&self) -> Account {
-            let path = wallet_path(0);
-            let key = slip10_ed25519::derive_ed25519_private_key(&self.seed, &path);
-            Account::from(&Slip10Key::from_raw(key))
-        }
+        // Requests exceeding the APDU payload limit are rejected
+        let r = e.update(&Event::GetRandom {
+            len: RANDOM_LEN_MAX as u8 + 1,
+        });
+        assert_eq!(r, Err(Error::InvalidLength));
     }
 
-    /// Driver impl for test use
-    impl Driver for TestDriver {
-        fn slip10_derive_ed25519(&self, path: &[u32]) -> Slip10Key {
-            let d = slip10_ed25519::derive_ed25519_private_key(&self.seed, path);
-            Slip10Key::from_raw(d)
-        }
+    #[test]
+    fn diagnostics_counters() {
+        let mut e = Engine::new(SoftwareDriver::random());
+
+        // Successful and failed events are counted
+        e.update(&Event::GetRandom { len: 32 }).unwrap();
+        let r = e.update(&Event::GetWalletKeys {
+            account_index: 0,
+            challenge: None,
+            path: None,
+        });
+        assert_eq!(r, Err(Error::ApprovalPending));
+        e.reset();
+
+        // Counters are exposed to the host
+        let r = e.update(&Event::GetDiagnostics).unwrap();
+        assert_eq!(
+            r,
+            Output::Diagnostics {
+                events: 3,
+                errors: 1,
+                resets: 1,
+                rings_signed: 0,
+            }
+        );
     }
 
-    /// Step through valid events and states
     #[test]
-    fn valid_events() {
-        let mut e = Engine::new(TestDriver::new());
+    fn capabilities() {
+        let mut e = Engine::new(SoftwareDriver::random());
 
-        for (_state, evt) in &*TESTS {
-            // Fire expected event
-            let r = e.update(evt);
+        let r = e.update(&Event::GetCapabilities).unwrap();
+        assert_eq!(
+            r,
+            Output::Capabilities {
+                proto_min: MOB_PROTO_VERSION_MIN,
+                proto_max: MOB_PROTO_VERSION,
+                features: Engine::<SoftwareDriver>::features().bits(),
+            }
+        );
 
-            // Check response is okay
-            assert!(r.is_ok(), "event {evt:?} failed, error response: {r:?}");
-        }
+        // Capabilities are available prior to unlock
+        assert!(!e.is_unlocked());
     }
 
-    /// Ensure we're handling unexpected events
+    /// Check transactions are rejected until a supported protocol version is pinned
     #[test]
-    fn invalid_events() {
-        for (okay_state, evt) in &*TESTS {
-            let mut e = Engine::new(TestDriver::new());
-
-            // Fire the same event to every other state
-            for state in State::iter() {
-                if state == *okay_state {
-                    continue;
-                }
+    fn protocol_pinning() {
+        let mut e = Engine::new(SoftwareDriver::random());
 
-                match (state, evt) {
-                    // Only init once (because this causes a state reset)
-                    (State::Init, Event::TxInit { .. }) => (),
-                    (_, Event::TxInit { .. }) => continue,
-                    // Skip correct state
-                    (state, _) if state == *okay_state => continue,
-                    _ => (),
-                }
+        let init = Event::TxInit {
+            account_index: 0,
+            num_rings: 1,
+            expected: None,
+        };
 
-                // Fire incorrect event
-                let r = e.update(evt);
+        // Transactions require a pinned protocol version
+        assert_eq!(e.update(&init), Err(Error::ProtocolNotPinned));
+        assert_eq!(e.state(), State::Init);
 
-                // Check engine reports event error
-                assert_eq!(r, Err(Error::UnexpectedEvent));
-            }
+        // Unsupported versions are rejected
+        for version in [MOB_PROTO_VERSION_MIN - 1, MOB_PROTO_VERSION + 1] {
+            let r = e.update(&Event::SetProtocolVersion { version });
+            assert_eq!(r, Err(Error::UnsupportedProtocol));
         }
-    }
+        assert_eq!(e.protocol_version(), None);
 
-    #[test]
-    fn ring_progress() {
-        let tests = &[
-            (0, 0, 2, 0),
-            (50, 0, 2, 25),
-            (100, 0, 2, 50),
-            (0, 1, 2, 50),
-            (50, 1, 2, 75),
-            (100, 1, 2, 100),
-            (0, 2, 2, 100),
-        ];
+        // Pinning a supported version allows transactions
+        e.update(&Event::SetProtocolVersion {
+            version: MOB_PROTO_VERSION,
+        })
+        .expect("Pin protocol version");
+        assert_eq!(e.protocol_version(), Some(MOB_PROTO_VERSION));
 
-        for (c, r, t, e) in tests {
-            let p = compute_ring_progress(*c, *r, *t);
-            assert_eq!(p, *e, "progress error ({}.{}/{})", *r, *c, *t);
-        }
+        e.update(&init).expect("Init transaction");
+        assert_eq!(e.state(), State::BuildMemos(0));
     }
 
-    /// Check engine rejects key requests while locked
+    /// Check account roots are cached during approved sessions
+    /// and cleared on invalidation or lock
     #[test]
-    fn lock_unlock() {
-        let mut e = Engine::new(TestDriver::new());
-
-        // Locked, return pending message
-        let r = e.update(&Event::GetWalletKeys { account_index: 0 });
-        assert_eq!(r, Err(Error::ApprovalPending));
+    fn account_cache() {
+        let drv = SoftwareDriver::random();
+        let expected = drv.account(1).subaddress(0).view_public_key();
+        let mut e = Engine::new(drv);
 
-        let r = e.update(&Event::GetSubaddressKeys {
+        // Accounts are not cached prior to approval
+        let _ = e.update(&Event::GetWalletKeys {
             account_index: 0,
-            subaddress_index: 1,
+            challenge: None,
+            path: None,
         });
-        assert_eq!(r, Err(Error::ApprovalPending));
+        assert_eq!(e.cached_accounts(), 0);
 
-        // Unlock engine
         e.unlock();
 
-        // Unlocked, return view account and subaddress keys
-        let r = e
-            .update(&Event::GetWalletKeys { account_index: 0 })
+        // Key requests cache account roots
+        for i in 0..2 {
+            e.update(&Event::GetWalletKeys {
+                account_index: i,
+                challenge: None,
+                path: None,
+            })
             .unwrap();
-        assert!(matches!(r, Output::WalletKeys { .. }));
+            e.update(&Event::GetWalletKeys {
+                account_index: i,
+                challenge: None,
+                path: None,
+            })
+            .unwrap();
+        }
+        assert_eq!(e.cached_accounts(), 2);
+
+        // Cached accounts match derived accounts
+        let a = e.get_account(1).subaddress(0).view_public_key();
+        assert_eq!(a, expected);
+
+        // Invalidation clears the cache
+        e.update(&Event::InvalidateAccounts).unwrap();
+        assert_eq!(e.cached_accounts(), 0);
+
+        // As does locking the engine
+        e.update(&Event::GetWalletKeys {
+            account_index: 3,
+            challenge: None,
+            path: None,
+        })
+        .unwrap();
+        assert_eq!(e.cached_accounts(), 1);
+        e.lock();
+        assert_eq!(e.cached_accounts(), 0);
+    }
+
+    /// Check in-progress transactions are aborted on watchdog expiry
+    #[test]
+    fn tx_watchdog() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+        e.set_tx_timeout(60, 10);
+        e.set_ticks(100);
+
+        // No timeout is reported outside of transactions
+        assert_eq!(e.tx_timeout_remaining(), None);
 
         let r = e
-            .update(&Event::GetSubaddressKeys {
+            .update(&Event::TxInit {
                 account_index: 0,
-                subaddress_index: 1,
+                num_rings: 1,
+                expected: None,
             })
             .unwrap();
-        assert!(matches!(r, Output::SubaddressKeys { .. }));
+        assert!(matches!(r, Output::State { timeout_s: 60, .. }));
+
+        // Remaining time is reported while the transaction is running
+        e.set_ticks(395);
+        assert_eq!(e.tx_timeout_remaining(), Some(31));
+        let r = e.update(&Event::TxGetInfo).unwrap();
+        assert!(matches!(r, Output::State { timeout_s: 31, .. }));
+
+        // Transactions are aborted on expiry
+        assert!(e.set_ticks(700));
+        assert_eq!(e.state(), State::Error);
+        assert_eq!(e.tx_timeout_remaining(), None);
+
+        // Platform watchdogs may only expire in-progress transactions
+        e.reset();
+        assert!(!e.tx_expire());
+        assert_eq!(e.state(), State::Init);
+
+        e.update(&Event::TxInit {
+            account_index: 0,
+            num_rings: 1,
+            expected: None,
+        })
+        .unwrap();
+        assert!(e.tx_expire());
+        assert_eq!(e.state(), State::Error);
+        assert_eq!(e.tx_timeout_remaining(), None);
+
+        // Expiry clears transaction context as for abort
+        assert!(e.message.is_empty());
+        assert!(e.signed_digest.is_none());
+        assert!(e.approved_inputs.is_empty());
+    }
+
+    #[test]
+    fn ring_input_check() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+
+        let ring_init = |value, token_id| Event::TxRingInit {
+            ring_size: RING_SIZE as u8,
+            value,
+            token_id,
+            real_index: 0,
+            subaddress_index: 0,
+            onetime_private_key: None,
+        };
+
+        // Setup an approved transaction with inputs as recorded from a summary
+        let setup = |e: &mut Engine<SoftwareDriver>| {
+            e.update(&Event::TxInit {
+                account_index: 0,
+                num_rings: 2,
+                expected: None,
+            })
+            .unwrap();
+            e.update(&Event::TxSetMessage(
+                heapless::Vec::from_slice(&[0xab; 32]).unwrap(),
+            ))
+            .unwrap();
+            e.approve();
+            assert_eq!(e.state(), State::Ready);
+
+            for (value, token_id) in [(100, 0), (100, 0)] {
+                e.approved_inputs
+                    .push(ApprovedInput {
+                        value,
+                        token_id,
+                        signed: false,
+                    })
+                    .unwrap();
+            }
+            e.summary_approved = true;
+        };
+
+        // Rings with values or tokens not approved abort the transaction
+        for (value, token_id) in [(101, 0), (100, 1)] {
+            setup(&mut e);
+            let r = e.update(&ring_init(value, token_id));
+            assert_eq!(r, Err(Error::RingInputMismatch));
+            assert_eq!(e.state(), State::Error);
+        }
+
+        // Rings matching an approved input may be signed
+        setup(&mut e);
+        e.update(&ring_init(100, 0)).unwrap();
+        assert_eq!(e.state(), State::SignRing(RingState::Init));
+        assert_eq!(e.ring_input, Some(0));
+
+        // Signed inputs may not be reused
+        e.approved_inputs[0].signed = true;
+        e.update(&ring_init(100, 0)).unwrap();
+        assert_eq!(e.ring_input, Some(1));
+
+        e.approved_inputs[1].signed = true;
+        let r = e.update(&ring_init(100, 0));
+        assert_eq!(r, Err(Error::RingInputMismatch));
+
+        // Inputs are cleared on reset
+        e.reset();
+        assert!(e.approved_inputs.is_empty());
+        assert!(!e.summary_approved);
+
+        // Approved summaries without remaining inputs reject all rings
+        setup(&mut e);
+        e.approved_inputs.clear();
+        let r = e.update(&ring_init(100, 0));
+        assert_eq!(r, Err(Error::RingInputMismatch));
     }
 
     use mc_util_test_helper::{RngType, SeedableRng};
@@ -1210,8 +4857,8 @@ mod test {
         let mut rng: RngType = SeedableRng::from_seed(seed);
         let pseudo_output_blinding = Scalar::random(&mut rng);
 
-        let drv = TestDriver::new();
-        let account = drv.account();
+        let drv = SoftwareDriver::random();
+        let account = drv.account(0);
 
         let params =
             RingMLSAGParameters::random(&account, RING_SIZE - 1, pseudo_output_blinding, &mut rng);
@@ -1222,12 +4869,14 @@ mod test {
             Engine::init(e.as_mut_ptr(), drv, rng);
             e.assume_init()
         };
+        pin_protocol(&mut engine);
 
         // Initialise new transaction
         let r = engine
             .update(&Event::TxInit {
                 account_index: 0,
                 num_rings: 1,
+                expected: None,
             })
             .expect("Init transaction");
 
@@ -1328,6 +4977,7 @@ mod test {
             }
             _ => panic!("unexpected state: {state:?}"),
         };
+        assert_eq!(engine.diagnostics().rings_signed, 1);
 
         // Fetch responses
         let responses: Vec<CurveScalar, RESP_SIZE> = (0..RESP_SIZE)
@@ -1388,18 +5038,233 @@ mod test {
         let output_commitment =
             CompressedCommitment::new(params.value, pseudo_output_blinding, &params.generator);
 
-        let verifier = MlsagVerify {
-            message: &params.message,
-            c_zero: &CurveScalar::from(c_zero),
-            responses: responses.as_slice(),
-            key_image: &key_image,
-            ring: params.ring.as_slice(),
-            output_commitment: &output_commitment,
+        verify_ring(
+            &params.message,
+            params.ring.as_slice(),
+            &output_commitment,
+            &key_image,
+            &CurveScalar::from(c_zero),
+            responses.as_slice(),
+        )
+        .expect("Failed to verify ring");
+
+        // Prior responses are unavailable until a subsequent ring is started
+        assert_eq!(
+            engine.update(&Event::TxGetPriorResponse { index: 0 }),
+            Err(Error::UnexpectedEvent)
+        );
+
+        // Start next ring, retaining responses for the completed ring
+        engine.update(&evt).expect("Init next ring");
+        assert_eq!(engine.state, State::SignRing(RingState::Init));
+
+        // Fetch prior responses while the next ring is loaded
+        for (i, r) in responses.iter().enumerate() {
+            let resp = engine
+                .update(&Event::TxGetPriorResponse { index: i as u8 })
+                .expect("Fetch prior response");
+
+            match resp {
+                Output::TxResponse { ring_index, scalar } => {
+                    assert_eq!(ring_index, i as u8);
+                    assert_eq!(&CurveScalar::from(scalar), r);
+                }
+                _ => panic!("Unexpected response: {resp:?}"),
+            }
+        }
+
+        // Fetch prior responses in ranges
+        let resp = engine
+            .update(&Event::TxGetPriorResponses { start: 2, count: 4 })
+            .expect("Fetch prior responses");
+
+        match resp {
+            Output::TxResponses { start, scalars } => {
+                assert_eq!(start, 2);
+                assert_eq!(scalars.len(), 4);
+                for (i, s) in scalars.iter().enumerate() {
+                    assert_eq!(&CurveScalar::from(*s), &responses[2 + i]);
+                }
+            }
+            _ => panic!("Unexpected response: {resp:?}"),
+        }
+
+        // Completing the transaction discards prior responses
+        // and returns a digest over the signed ring and message
+        let r = engine
+            .update(&Event::TxComplete)
+            .expect("Complete transaction");
+        assert!(engine.prior_responses.is_empty());
+
+        let mut expected = SignedTxDigest::new();
+        expected.add_ring(&key_image, &CurveScalar::from(c_zero), &responses);
+
+        assert_eq!(
+            r,
+            Output::TxComplete {
+                state: State::Complete,
+                seq: 0,
+                digest: engine.digest.clone(),
+                signed_digest: expected.finalise(&params.message),
+            }
+        );
+    }
+
+    /// Check failed rings may be restarted without aborting the transaction
+    #[test]
+    fn ring_retry() {
+        let seed = [1u8; 32];
+        let mut rng: RngType = SeedableRng::from_seed(seed);
+        let pseudo_output_blinding = Scalar::random(&mut rng);
+
+        let drv = SoftwareDriver::random();
+        let account = drv.account(0);
+
+        let params =
+            RingMLSAGParameters::random(&account, RING_SIZE - 1, pseudo_output_blinding, &mut rng);
+
+        let mut engine = Engine::new_with_rng(drv, rng);
+        pin_protocol(&mut engine);
+
+        // Setup and approve transaction
+        engine
+            .update(&Event::TxInit {
+                account_index: 0,
+                num_rings: 1,
+                expected: None,
+            })
+            .expect("Init transaction");
+        engine
+            .update(&Event::TxSetMessage(
+                heapless::Vec::from_slice(&params.message).unwrap(),
+            ))
+            .expect("Set message");
+        engine.approve();
+
+        let ring_init = Event::TxRingInit {
+            ring_size: RING_SIZE as u8,
+            value: params.value,
+            token_id: params.token_id,
+            real_index: params.real_index as u8,
+            subaddress_index: params.target_subaddress_index,
+            onetime_private_key: None,
         };
 
-        let mut recomputed_c = [Scalar::default(); RING_SIZE];
-        verifier
-            .verify(&mut recomputed_c[..])
-            .expect("Failed to verify ring");
+        // Attempt to sign with the wrong subaddress, failing
+        // onetime private key recovery for the real input
+        engine
+            .update(&Event::TxRingInit {
+                ring_size: RING_SIZE as u8,
+                value: params.value,
+                token_id: params.token_id,
+                real_index: params.real_index as u8,
+                subaddress_index: params.target_subaddress_index + 1,
+                onetime_private_key: None,
+            })
+            .expect("Init ring");
+        engine
+            .update(&Event::TxSetBlinding {
+                blinding: params.blinding,
+                output_blinding: pseudo_output_blinding,
+            })
+            .expect("Set blinding");
+
+        let tx_out = &params.ring[params.real_index];
+        let r = engine.update(&Event::TxAddTxout(params.real_index as u8, tx_out.clone()));
+        assert_eq!(r, Err(Error::OnetimeKeyRecoveryFailed));
+        assert_eq!(engine.state(), State::SignRing(RingState::Error));
+
+        // Restart the ring with valid values
+        engine.update(&ring_init).expect("Restart ring");
+        assert_eq!(engine.state(), State::SignRing(RingState::Init));
+        assert_eq!(engine.ring_count, 0);
+
+        engine
+            .update(&Event::TxSetBlinding {
+                blinding: params.blinding,
+                output_blinding: pseudo_output_blinding,
+            })
+            .expect("Set blinding");
+
+        for n in 0..RING_SIZE {
+            let i = (params.real_index + n) % RING_SIZE;
+            engine
+                .update(&Event::TxAddTxout(i as u8, params.ring[i].clone()))
+                .expect("Add txout");
+        }
+
+        let r = engine.update(&Event::TxSign).expect("Execute sign");
+        match r {
+            Output::State {
+                state: State::SignRing(RingState::Complete { key_image, .. }),
+                ..
+            } => assert_eq!(key_image, KeyImage::from(&params.onetime_private_key)),
+            _ => panic!("unexpected state: {r:?}"),
+        }
+    }
+
+    /// Check transactions may be cancelled during ring signing
+    #[test]
+    fn cancel_signing() {
+        let mut e = Engine::new(SoftwareDriver::random());
+        pin_protocol(&mut e);
+
+        // Denial prior to approval is not a cancellation
+        e.update(&Event::TxInit {
+            account_index: 0,
+            num_rings: 1,
+            expected: None,
+        })
+        .unwrap();
+        e.update(&Event::TxSetMessage(
+            heapless::Vec::from_slice(&[0xab; 32]).unwrap(),
+        ))
+        .unwrap();
+        e.deny();
+        assert_eq!(e.state(), State::Deny);
+
+        // Setup, approve, and start signing a transaction
+        e.update(&Event::TxInit {
+            account_index: 0,
+            num_rings: 1,
+            expected: None,
+        })
+        .unwrap();
+        e.update(&Event::TxSetMessage(
+            heapless::Vec::from_slice(&[0xab; 32]).unwrap(),
+        ))
+        .unwrap();
+        e.approve();
+        e.update(&Event::TxRingInit {
+            ring_size: RING_SIZE as u8,
+            value: 100,
+            token_id: 0,
+            real_index: 0,
+            subaddress_index: 0,
+            onetime_private_key: None,
+        })
+        .unwrap();
+        assert_eq!(e.state(), State::SignRing(RingState::Init));
+
+        // Cancel mid-ring
+        e.deny();
+        assert_eq!(e.state(), State::Cancelled);
+        assert_eq!(e.tx_timeout_remaining(), None);
+
+        // Further signing requests and completion are rejected
+        assert_eq!(e.update(&Event::TxSign), Err(Error::TxCancelled));
+        assert_eq!(
+            e.update(&Event::TxGetResponse { index: 0 }),
+            Err(Error::TxCancelled)
+        );
+        assert_eq!(e.update(&Event::TxComplete), Err(Error::TxCancelled));
+
+        // Cancellation is reported to the host
+        let r = e.update(&Event::TxGetInfo).unwrap();
+        assert_eq!(r.state(), Some(State::Cancelled));
+        assert_eq!(
+            State::Cancelled.state(),
+            ledger_mob_apdu::state::TxState::TxCancelled
+        );
     }
 }
@@ -1,16 +1,26 @@
 // Copyright (c) 2022-2023 The MobileCoin Foundation
 
-use encdec::Encode;
-
+use ledger_mob_apdu::{
+    attestation::KeyAttestation,
+    encoding::{ApduEncode, Encoding},
+};
 use ledger_proto::ApduError;
-use mc_core::keys::{
-    RootSpendPublic, RootViewPrivate, SubaddressSpendPublic, SubaddressViewPrivate,
+use mc_core::{
+    account::PublicSubaddress,
+    keys::{
+        RootSpendPublic, RootViewPrivate, SubaddressSpendPublic, SubaddressViewPrivate,
+        SubaddressViewPublic,
+    },
 };
 use mc_crypto_ring_signature::{KeyImage, Scalar};
 
 pub use ledger_mob_apdu::state::Digest as TxDigest;
 
-use crate::{apdu, engine::ring::RingState};
+use crate::{
+    apdu,
+    engine::{ring::RingState, AddressState, FogId, FogState},
+    helpers::b58_encode_public_address,
+};
 
 #[cfg(feature = "summary")]
 use super::summary::SummaryState;
@@ -20,13 +30,18 @@ use super::ident::IdentState;
 
 /// [`Engine`][super::Engine] outputs (in response to events), typically encoded to response [APDUs][ledger_mob_apdu]
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Output {
     None,
 
     /// Engine state
     State {
         state: super::State,
+        /// Last accepted request sequence number
+        seq: u8,
         digest: TxDigest,
+        /// Seconds remaining before the transaction watchdog aborts (0 if not running)
+        timeout_s: u32,
     },
 
     /// Wallet keys
@@ -34,6 +49,9 @@ pub enum Output {
         account_index: u32,
         spend_public: RootSpendPublic,
         view_private: RootViewPrivate,
+        /// Key attestation for attested requests
+        #[cfg_attr(feature = "serde", serde(with = "serde_attestation"))]
+        attestation: Option<KeyAttestation>,
     },
 
     /// Subaddress keys
@@ -42,6 +60,40 @@ pub enum Output {
         subaddress_index: u64,
         spend_public: SubaddressSpendPublic,
         view_private: SubaddressViewPrivate,
+        /// Key attestation for attested requests
+        #[cfg_attr(feature = "serde", serde(with = "serde_attestation"))]
+        attestation: Option<KeyAttestation>,
+    },
+
+    /// Subaddress keys with fog authority signature
+    SubaddressKeysFog {
+        account_index: u32,
+        subaddress_index: u64,
+        spend_public: SubaddressSpendPublic,
+        view_private: SubaddressViewPrivate,
+        fog_id: FogId,
+        #[cfg_attr(feature = "serde", serde(with = "serde_big_array::BigArray"))]
+        fog_sig: [u8; 64],
+    },
+
+    /// Subaddress public keys for a contiguous range of subaddresses
+    SubaddressRange {
+        account_index: u32,
+        start: u64,
+        keys: heapless::Vec<
+            (SubaddressViewPublic, SubaddressSpendPublic),
+            { apdu::subaddress_keys::SUBADDRESS_RANGE_MAX },
+        >,
+    },
+
+    /// Subaddress keys for a contiguous batch of subaddresses
+    SubaddressKeysBatch {
+        account_index: u32,
+        start: u64,
+        keys: heapless::Vec<
+            (SubaddressSpendPublic, SubaddressViewPrivate),
+            { apdu::subaddress_keys::SUBADDRESS_KEYS_BATCH_MAX },
+        >,
     },
 
     /// Computed key image
@@ -49,19 +101,101 @@ pub enum Output {
         account_index: u32,
         subaddress_index: u64,
         key_image: KeyImage,
+        spend_public: SubaddressSpendPublic,
     },
 
     /// Random value
     RandomValue {
-        value: [u8; 32],
+        value: heapless::Vec<u8, { apdu::random::RANDOM_LEN_MAX }>,
+    },
+
+    /// Device nonce for session challenges
+    DeviceNonce {
+        nonce: [u8; apdu::device_nonce::DEVICE_NONCE_LEN],
+    },
+
+    /// Key export counters
+    KeyCounters {
+        wallet_keys: u32,
+        subaddress_keys: u32,
+        limit: u32,
+    },
+
+    /// Device settings
+    Settings {
+        fog_id: FogId,
+        blind_signing: bool,
+        onetime_keys: bool,
+        lock_timeout_s: u32,
+        account_index: u32,
+    },
+
+    /// Summary loading / ring signing progress
+    Progress {
+        state: super::State,
+        percent: u8,
+        step: u16,
+        total: u16,
+    },
+
+    /// Engine diagnostic counters
+    Diagnostics {
+        events: u32,
+        errors: u32,
+        resets: u32,
+        rings_signed: u32,
+    },
+
+    /// Supported protocol versions and features
+    /// (see [Features][apdu::capabilities::Features])
+    Capabilities {
+        proto_min: u8,
+        proto_max: u8,
+        features: u32,
+    },
+
+    /// Approval audit log entries, most recent first
+    AuditLog {
+        start: u8,
+        num_held: u8,
+        entries: heapless::Vec<apdu::audit::AuditEntry, { apdu::audit::AUDIT_ENTRIES_MAX }>,
+    },
+
+    /// TxSummary report entries
+    TxSummaryReport {
+        start: u8,
+        num_held: u8,
+        entries: heapless::Vec<apdu::tx::ReportEntry, { apdu::tx::REPORT_ENTRIES_MAX }>,
     },
 
     /// BIP-0017 derived identity and challenge
     Identity {
         public_key: [u8; 32],
+        #[cfg_attr(feature = "serde", serde(with = "serde_big_array::BigArray"))]
+        signature: [u8; 64],
+    },
+
+    /// Verified address with fog authority signature and acknowledgement
+    Address {
+        view_public: SubaddressViewPublic,
+        spend_public: SubaddressSpendPublic,
+        fog_id: FogId,
+        #[cfg_attr(feature = "serde", serde(with = "serde_big_array::BigArray"))]
+        fog_sig: [u8; 64],
+        #[cfg_attr(feature = "serde", serde(with = "serde_big_array::BigArray"))]
         signature: [u8; 64],
     },
 
+    /// Public address with fog information, b58 encoded on output
+    /// (see [b58_encode_public_address])
+    PublicAddress {
+        view_public: SubaddressViewPublic,
+        spend_public: SubaddressSpendPublic,
+        fog_id: FogId,
+        #[cfg_attr(feature = "serde", serde(with = "serde_big_array::BigArray"))]
+        fog_sig: [u8; 64],
+    },
+
     /// HMAC for signed memo
     MemoHmac {
         state: super::State,
@@ -69,6 +203,15 @@ pub enum Output {
         hmac: [u8; 16],
     },
 
+    /// Transaction complete, with digest over the signed transaction
+    TxComplete {
+        state: super::State,
+        /// Last accepted request sequence number
+        seq: u8,
+        digest: TxDigest,
+        signed_digest: [u8; 32],
+    },
+
     /// Key image (and c_zero) from signed ring
     TxKeyImage {
         key_image: KeyImage,
@@ -81,6 +224,12 @@ pub enum Output {
         scalar: Scalar,
     },
 
+    /// Range of response entries from a signed ring
+    TxResponses {
+        start: u8,
+        scalars: heapless::Vec<Scalar, { apdu::tx::TX_RESPONSES_MAX }>,
+    },
+
     /// Indicate the device is waiting for user input
     Pending,
 }
@@ -93,50 +242,183 @@ impl Output {
         ptr.write(Output::None);
     }
 
+    /// Encode an [`Output`] object to a (packed) response [APDU][ledger_mob_apdu]
+    pub fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+        self.encode_with(Encoding::Packed, buff)
+    }
+
     /// Encode an [`Output`] object to a response [APDU][ledger_mob_apdu]
+    /// using the provided [Encoding]
     #[cfg_attr(feature = "noinline", inline(never))]
-    pub fn encode(&self, buff: &mut [u8]) -> Result<usize, ApduError> {
+    pub fn encode_with(&self, enc: Encoding, buff: &mut [u8]) -> Result<usize, ApduError> {
         match self.clone() {
             Output::None => Ok(0),
-            Output::State { state, digest } => apdu::tx::TxInfo {
+            Output::State {
+                state,
+                seq,
+                digest,
+                timeout_s,
+            } => apdu::tx::TxInfo {
                 state: state.state(),
+                seq,
                 value: state.value(),
                 digest,
+                timeout_s,
             }
-            .encode(buff),
+            .encode_with(enc, buff),
             Output::WalletKeys {
                 account_index,
                 spend_public,
                 view_private,
+                attestation,
             } => apdu::wallet_keys::WalletKeyResp {
                 account_index,
                 spend_public,
                 view_private,
+                attestation,
             }
-            .encode(buff),
+            .encode_with(enc, buff),
             Output::SubaddressKeys {
                 account_index,
                 subaddress_index,
                 spend_public,
                 view_private,
+                attestation,
             } => apdu::subaddress_keys::SubaddressKeyResp {
                 account_index,
                 subaddress_index,
                 spend_public,
                 view_private,
+                attestation,
+            }
+            .encode_with(enc, buff),
+            Output::SubaddressKeysFog {
+                account_index,
+                subaddress_index,
+                spend_public,
+                view_private,
+                fog_id,
+                fog_sig,
+            } => apdu::subaddress_keys::SubaddressKeyFogResp::new(
+                account_index,
+                subaddress_index,
+                view_private,
+                spend_public,
+                fog_id,
+                fog_sig,
+            )
+            .encode_with(enc, buff),
+            Output::SubaddressRange {
+                account_index,
+                start,
+                keys,
+            } => apdu::subaddress_keys::SubaddressRangeResp {
+                account_index,
+                start,
+                keys,
+            }
+            .encode_with(enc, buff),
+            Output::SubaddressKeysBatch {
+                account_index,
+                start,
+                keys,
+            } => apdu::subaddress_keys::SubaddressKeysBatchResp {
+                account_index,
+                start,
+                keys,
             }
-            .encode(buff),
+            .encode_with(enc, buff),
             Output::KeyImage {
                 account_index,
                 subaddress_index,
                 key_image,
+                spend_public,
             } => apdu::key_image::KeyImageResp {
                 account_index,
                 subaddress_index,
                 key_image,
+                spend_public,
+            }
+            .encode_with(enc, buff),
+            Output::RandomValue { value } => {
+                apdu::random::RandomResp { value }.encode_with(enc, buff)
+            }
+            Output::DeviceNonce { nonce } => {
+                apdu::device_nonce::DeviceNonceResp::new(nonce).encode_with(enc, buff)
+            }
+            Output::KeyCounters {
+                wallet_keys,
+                subaddress_keys,
+                limit,
+            } => apdu::key_counters::KeyCountersResp {
+                wallet_keys,
+                subaddress_keys,
+                limit,
+            }
+            .encode_with(enc, buff),
+            Output::Settings {
+                fog_id,
+                blind_signing,
+                onetime_keys,
+                lock_timeout_s,
+                account_index,
+            } => {
+                let mut flags = apdu::settings::SettingsFlags::empty();
+                flags.set(apdu::settings::SettingsFlags::BLIND_SIGNING, blind_signing);
+                flags.set(apdu::settings::SettingsFlags::ONETIME_KEYS, onetime_keys);
+
+                apdu::settings::SettingsResp::new(fog_id, flags, lock_timeout_s, account_index)
+                    .encode_with(enc, buff)
+            }
+            Output::Progress {
+                state,
+                percent,
+                step,
+                total,
+            } => apdu::progress::ProgressResp::new(state.state(), percent, step, total)
+                .encode_with(enc, buff),
+            Output::Diagnostics {
+                events,
+                errors,
+                resets,
+                rings_signed,
+            } => apdu::diagnostics::DiagnosticsResp {
+                events,
+                errors,
+                resets,
+                rings_signed,
+            }
+            .encode_with(enc, buff),
+            Output::Capabilities {
+                proto_min,
+                proto_max,
+                features,
+            } => apdu::capabilities::CapabilitiesResp::new(
+                proto_min,
+                proto_max,
+                apdu::capabilities::Features::from_bits_truncate(features),
+            )
+            .encode_with(enc, buff),
+            Output::AuditLog {
+                start,
+                num_held,
+                entries,
+            } => apdu::audit::AuditLogResp {
+                start,
+                num_held,
+                entries,
+            }
+            .encode_with(enc, buff),
+            Output::TxSummaryReport {
+                start,
+                num_held,
+                entries,
+            } => apdu::tx::TxSummaryReportResp {
+                start,
+                num_held,
+                entries,
             }
-            .encode(buff),
-            Output::RandomValue { value } => apdu::random::RandomResp { value }.encode(buff),
+            .encode_with(enc, buff),
             Output::Identity {
                 public_key,
                 signature,
@@ -144,7 +426,45 @@ impl Output {
                 public_key,
                 signature,
             }
-            .encode(buff),
+            .encode_with(enc, buff),
+            Output::Address {
+                view_public,
+                spend_public,
+                fog_id,
+                fog_sig,
+                signature,
+            } => apdu::address::AddressResp::new(
+                view_public,
+                spend_public,
+                fog_id,
+                fog_sig,
+                signature,
+            )
+            .encode_with(enc, buff),
+            Output::PublicAddress {
+                view_public,
+                spend_public,
+                fog_id,
+                fog_sig,
+            } => {
+                let address = PublicSubaddress {
+                    view_public,
+                    spend_public,
+                };
+                let sig = match fog_id {
+                    FogId::None => &[][..],
+                    _ => &fog_sig[..],
+                };
+
+                let b58 = b58_encode_public_address::<{ apdu::address::PUBLIC_ADDRESS_MAX }>(
+                    &address,
+                    fog_id.url(),
+                    sig,
+                )
+                .map_err(|_| ApduError::InvalidEncoding)?;
+
+                apdu::address::PublicAddressResp::new(fog_id, fog_sig, &b58).encode_with(enc, buff)
+            }
             Output::MemoHmac {
                 state,
                 digest,
@@ -155,12 +475,28 @@ impl Output {
                 value: state.value(),
                 hmac,
             }
-            .encode(buff),
+            .encode_with(enc, buff),
+            Output::TxComplete {
+                state,
+                seq,
+                digest,
+                signed_digest,
+            } => apdu::tx::TxCompleteResp {
+                state: state.state(),
+                seq,
+                value: state.value(),
+                digest,
+                signed_digest,
+            }
+            .encode_with(enc, buff),
             Output::TxKeyImage { key_image, c_zero } => {
-                apdu::tx::TxKeyImage { key_image, c_zero }.encode(buff)
+                apdu::tx::TxKeyImage { key_image, c_zero }.encode_with(enc, buff)
             }
             Output::TxResponse { ring_index, scalar } => {
-                apdu::tx::TxResponse::new(ring_index, scalar).encode(buff)
+                apdu::tx::TxResponse::new(ring_index, scalar).encode_with(enc, buff)
+            }
+            Output::TxResponses { start, scalars } => {
+                apdu::tx::TxResponses::new(start, scalars).encode_with(enc, buff)
             }
             Output::Pending => Ok(0),
         }
@@ -170,6 +506,7 @@ impl Output {
     pub fn state(&self) -> Option<super::State> {
         match &self {
             Output::State { state, .. } => Some(*state),
+            Output::TxComplete { state, .. } => Some(*state),
             _ => None,
         }
     }
@@ -178,6 +515,7 @@ impl Output {
     pub fn digest(&self) -> Option<&TxDigest> {
         match &self {
             Output::State { digest, .. } => Some(digest),
+            Output::TxComplete { digest, .. } => Some(digest),
             _ => None,
         }
     }
@@ -186,7 +524,7 @@ impl Output {
 impl PartialEq<super::State> for Output {
     fn eq(&self, other: &super::State) -> bool {
         match self {
-            Output::State { state, .. } => state == other,
+            Output::State { state, .. } | Output::TxComplete { state, .. } => state == other,
             _ => false,
         }
     }
@@ -197,8 +535,10 @@ impl From<(crate::engine::State, TxDigest)> for apdu::tx::TxInfo {
     fn from(s: (crate::engine::State, TxDigest)) -> Self {
         apdu::tx::TxInfo {
             state: s.0.state(),
+            seq: 0,
             value: s.0.value(),
             digest: s.1,
+            timeout_s: 0,
         }
     }
 }
@@ -216,8 +556,19 @@ impl crate::engine::State {
                 IdentState::Approved => TxState::IdentApproved,
                 IdentState::Denied => TxState::IdentDenied,
             },
+            State::Address(s) => match s {
+                AddressState::Pending => TxState::AddressPending,
+                AddressState::Approved => TxState::AddressApproved,
+                AddressState::Denied => TxState::AddressDenied,
+            },
+            State::Fog(s) => match s {
+                FogState::Pending => TxState::FogPending,
+                FogState::Approved => TxState::FogApproved,
+                FogState::Denied => TxState::FogDenied,
+            },
             State::Ready => TxState::Ready,
             State::BuildMemos(_n) => TxState::SignMemos,
+            State::MemoReview => TxState::MemoPending,
             State::SetMessage => TxState::SetMessage,
             #[cfg(feature = "summary")]
             State::Summary(s) => match s {
@@ -233,7 +584,10 @@ impl crate::engine::State {
             State::SignRing(RingState::Execute) => TxState::RingSign,
             State::SignRing(RingState::Complete { .. }) => TxState::RingComplete,
             State::Deny => TxState::TxDenied,
-            State::Error | State::SignRing(RingState::Error) => TxState::Error,
+            State::Cancelled => TxState::TxCancelled,
+            State::Aborted => TxState::TxAborted,
+            State::SignRing(RingState::Error) => TxState::RingError,
+            State::Error => TxState::Error,
             State::Complete => TxState::TxComplete,
         }
     }
@@ -253,10 +607,35 @@ impl crate::engine::State {
     }
 }
 
+/// Serde helper for optional key attestations, as arrays longer than 32 bytes
+/// are not natively supported
+#[cfg(feature = "serde")]
+mod serde_attestation {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_big_array::BigArray;
+
+    use super::KeyAttestation;
+
+    #[derive(Serialize, Deserialize)]
+    struct Attestation([u8; 32], #[serde(with = "BigArray")] [u8; 64]);
+
+    pub fn serialize<S: Serializer>(v: &Option<KeyAttestation>, s: S) -> Result<S::Ok, S::Error> {
+        v.map(|a| Attestation(a.public_key, a.signature))
+            .serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        d: D,
+    ) -> Result<Option<KeyAttestation>, D::Error> {
+        Option::<Attestation>::deserialize(d)
+            .map(|v| v.map(|Attestation(k, sig)| KeyAttestation::new(k, sig)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::apdu::state::TxState;
-    use crate::engine::{RingState, State};
+    use crate::engine::{AddressState, FogState, RingState, State};
 
     // Ensure state mappings match
     #[test]
@@ -265,6 +644,7 @@ mod tests {
             (State::Init, TxState::Init),
             (State::Ready, TxState::Ready),
             (State::BuildMemos(0), TxState::SignMemos),
+            (State::MemoReview, TxState::MemoPending),
             (State::SetMessage, TxState::SetMessage),
             (State::Pending, TxState::Pending),
             (State::SignRing(RingState::Init), TxState::RingInit),
@@ -277,12 +657,73 @@ mod tests {
                 }),
                 TxState::RingComplete,
             ),
+            (
+                State::Address(AddressState::Pending),
+                TxState::AddressPending,
+            ),
+            (
+                State::Address(AddressState::Approved),
+                TxState::AddressApproved,
+            ),
+            (State::Address(AddressState::Denied), TxState::AddressDenied),
+            (State::Fog(FogState::Pending), TxState::FogPending),
+            (State::Fog(FogState::Approved), TxState::FogApproved),
+            (State::Fog(FogState::Denied), TxState::FogDenied),
             (State::Error, TxState::Error),
-            (State::SignRing(RingState::Error), TxState::Error),
+            (State::SignRing(RingState::Error), TxState::RingError),
         ];
 
         for (a, b) in tests {
             assert_eq!(a.state(), *b);
         }
     }
+
+    // Ensure outputs can be recorded and replayed via JSON
+    #[cfg(feature = "serde")]
+    #[test]
+    fn output_serde() {
+        use mc_crypto_keys::{RistrettoPrivate, RistrettoPublic};
+        use rand_core::OsRng;
+
+        use super::{FogId, KeyAttestation, Output, TxDigest};
+
+        let view_private = RistrettoPrivate::from_random(&mut OsRng {});
+        let spend_private = RistrettoPrivate::from_random(&mut OsRng {});
+
+        let tests = &[
+            Output::State {
+                state: State::SignRing(RingState::Complete {
+                    key_image: Default::default(),
+                    c_zero: Default::default(),
+                }),
+                seq: 1,
+                digest: TxDigest::new(),
+                timeout_s: 120,
+            },
+            Output::Identity {
+                public_key: [0xab; 32],
+                signature: [0xcd; 64],
+            },
+            Output::WalletKeys {
+                account_index: 1,
+                spend_public: RistrettoPublic::from(&spend_private).into(),
+                view_private: view_private.clone().into(),
+                attestation: Some(KeyAttestation::new([0xab; 32], [0xcd; 64])),
+            },
+            Output::Address {
+                view_public: RistrettoPublic::from(&view_private).into(),
+                spend_public: RistrettoPublic::from(&spend_private).into(),
+                fog_id: FogId::MobMain,
+                fog_sig: [0x12; 64],
+                signature: [0x34; 64],
+            },
+            Output::Pending,
+        ];
+
+        for o in tests {
+            let s = serde_json::to_string(o).unwrap();
+            let d: Output = serde_json::from_str(&s).unwrap();
+            assert_eq!(o, &d, "round-trip failed for: {s}");
+        }
+    }
 }
@@ -1,35 +1,171 @@
 // Copyright (c) 2022-2023 The MobileCoin Foundation
 
-use core::ptr::addr_of_mut;
+use core::{
+    ops::{Deref, DerefMut},
+    ptr::{addr_of_mut, NonNull},
+};
 
 use heapless::Vec;
-use ledger_mob_apdu::tx::TxOnetimeKey;
+use ledger_mob_apdu::{
+    ct::ct_eq,
+    tx::{TxOnetimeKey, TX_RESPONSES_MAX},
+};
 use strum::{Display, EnumIter, EnumString, EnumVariantNames};
-use zeroize::Zeroize;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use super::{Error, Event, Output};
+use crate::helpers::{zeroize_option, zeroize_vec};
 use mc_core::keys::{RootViewPrivate, SubaddressSpendPrivate};
 use mc_crypto_keys::{RistrettoPrivate, RistrettoPublic};
 use mc_crypto_ring_signature::{
-    generators, onetime_keys::recover_onetime_private_key, CurveScalar, KeyImage, MlsagSignCtx,
-    MlsagSignParams, PedersenGens, ReducedTxOut, Scalar,
+    generators, onetime_keys::recover_onetime_private_key, CurveScalar, Error as RingError,
+    KeyImage, MlsagSignCtx, MlsagSignParams, PedersenGens, ReducedTxOut, Scalar,
 };
 use rand_core::{CryptoRng, RngCore};
 
 /// Maximum ring size
 pub const RING_SIZE: usize = 11;
 
+// Ring indices are tracked in a u16 bitmap
+const _: () = assert!(RING_SIZE <= u16::BITS as usize);
+
 /// Ring response size (2 * RING_SIZE)
 pub const RESP_SIZE: usize = RING_SIZE * 2;
 
 /// Maximum message size
 const MESSAGE_MAX: usize = 66;
 
-/// Heapless based MlsagSignCtx
-pub type SignCtx = MlsagSignCtx<Vec<CurveScalar, RESP_SIZE>>;
+/// Check the real input commitment matches the provided `value` and
+/// blindings when building and finalising rings.
+///
+/// This is always enabled so a host submitting a `value` or `blinding`
+/// that does not correspond to the real input is rejected with
+/// [Error::ValueNotConserved] rather than producing a signature that
+/// fails verification. The cost is a single commitment computation per ring.
+pub const CHECK_VALUE_IS_PRESERVED: bool = true;
+
+/// MlsagSignCtx using [Responses] storage
+pub type SignCtx = MlsagSignCtx<Responses>;
+
+/// Ring response buffer, held in statically allocated platform memory
+/// (see [Engine::set_response_buffer][super::Engine::set_response_buffer])
+/// to avoid moving ~1.5k of responses through the stack on ring init
+#[repr(transparent)]
+pub struct ResponseBuffer([CurveScalar; RESP_SIZE]);
+
+impl ResponseBuffer {
+    /// Initialise a [ResponseBuffer] in place, avoiding stack allocation
+    ///
+    /// # Safety
+    /// `p` must be valid for writes
+    pub unsafe fn init(p: *mut Self) {
+        let r = p as *mut CurveScalar;
+        for i in 0..RESP_SIZE {
+            r.add(i).write(CurveScalar::default());
+        }
+    }
+}
+
+/// Pointer to a platform [ResponseBuffer], shared between the engine and
+/// the active ring signing context
+#[derive(Copy, Clone, Debug)]
+pub struct ResponseBufferPtr(NonNull<ResponseBuffer>);
+
+// SAFETY: buffers are exclusively borrowed for the lifetime of the program
+// and referenced by at most one signing context at a time
+unsafe impl Send for ResponseBufferPtr {}
+
+impl ResponseBufferPtr {
+    /// Create a pointer to a statically allocated [ResponseBuffer]
+    pub(crate) fn new(buff: &'static mut ResponseBuffer) -> Self {
+        Self(NonNull::from(buff))
+    }
+}
+
+/// Response storage for ring signing contexts
+pub enum Responses {
+    /// Platform [ResponseBuffer] with the number of responses in use
+    Static(ResponseBufferPtr, usize),
+
+    /// Owned storage, where no platform buffer is configured
+    #[cfg(feature = "std")]
+    Owned(Vec<CurveScalar, RESP_SIZE>),
+}
+
+impl Responses {
+    /// Reset storage to `len` (`ring_size * 2`) default responses
+    fn reset(&mut self, len: usize) -> Result<(), Error> {
+        if len > RESP_SIZE {
+            return Err(Error::RingInitFailed);
+        }
+
+        match self {
+            Responses::Static(_, n) => *n = len,
+            #[cfg(feature = "std")]
+            Responses::Owned(v) => {
+                v.clear();
+                let _ = v.resize(len, CurveScalar::default());
+            }
+        }
+
+        self.iter_mut().for_each(|r| *r = CurveScalar::default());
+
+        Ok(())
+    }
+}
+
+impl Deref for Responses {
+    type Target = [CurveScalar];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            // SAFETY: see [ResponseBufferPtr], length is bounded by `reset`
+            Responses::Static(p, n) => unsafe { &(*p.0.as_ptr()).0[..*n] },
+            #[cfg(feature = "std")]
+            Responses::Owned(v) => v,
+        }
+    }
+}
+
+impl DerefMut for Responses {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            // SAFETY: see [ResponseBufferPtr], length is bounded by `reset`
+            Responses::Static(p, n) => unsafe { &mut (*p.0.as_ptr()).0[..*n] },
+            #[cfg(feature = "std")]
+            Responses::Owned(v) => v,
+        }
+    }
+}
+
+impl AsRef<[CurveScalar]> for Responses {
+    fn as_ref(&self) -> &[CurveScalar] {
+        self
+    }
+}
+
+impl AsMut<[CurveScalar]> for Responses {
+    fn as_mut(&mut self) -> &mut [CurveScalar] {
+        self
+    }
+}
+
+impl Zeroize for Responses {
+    fn zeroize(&mut self) {
+        self.iter_mut().for_each(Zeroize::zeroize);
+    }
+}
+
+/// Clear responses on drop, as these are held outside the signing context
+impl Drop for Responses {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
 
 /// Ring signing states
 #[derive(Copy, Clone, PartialEq, Debug, EnumString, Display, EnumVariantNames, EnumIter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RingState {
     Init,
     BuildRing(u8),
@@ -79,8 +215,14 @@ pub struct RingSigner {
     /// MLSAG context
     ring_ctx: Option<SignCtx>,
 
+    /// Response storage, moved into the MLSAG context on ring init
+    responses: Option<Responses>,
+
     /// Counter for fetched responses (used for progress tracking)
     fetch_count: usize,
+
+    /// Bitmap of ring indices added (MOB-06.4)
+    added: u16,
 }
 
 /// Ring blindings container
@@ -89,6 +231,36 @@ struct Blindings {
     output_blinding: Scalar,
 }
 
+impl Zeroize for Blindings {
+    fn zeroize(&mut self) {
+        self.blinding.zeroize();
+        self.output_blinding.zeroize();
+    }
+}
+
+/// Clear private keys, blindings and signing context (MOB-01.x)
+impl Zeroize for RingSigner {
+    fn zeroize(&mut self) {
+        self.onetime_private_key.zeroize();
+        self.root_view_private.zeroize();
+        self.subaddress_spend_private.zeroize();
+        self.value.zeroize();
+        self.real_index.zeroize();
+        zeroize_vec(&mut self.message);
+        self.blindings.zeroize();
+        zeroize_option(&mut self.ring_ctx);
+        zeroize_option(&mut self.responses);
+    }
+}
+
+impl Drop for RingSigner {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for RingSigner {}
+
 impl RingSigner {
     /// Create new RingSigner instance with provided params
     #[allow(clippy::too_many_arguments)]
@@ -123,7 +295,9 @@ impl RingSigner {
             generator: generators(token_id),
             blindings: None,
             ring_ctx: None,
+            responses: None,
             fetch_count: 0,
+            added: 0,
         })
     }
 
@@ -171,11 +345,20 @@ impl RingSigner {
         addr_of_mut!((*p).generator).write(generators(token_id));
         addr_of_mut!((*p).blindings).write(None);
         addr_of_mut!((*p).ring_ctx).write(None);
+        addr_of_mut!((*p).responses).write(None);
         addr_of_mut!((*p).fetch_count).write(0);
+        addr_of_mut!((*p).added).write(0);
 
         Ok(())
     }
 
+    /// Store ring responses in a platform [ResponseBuffer] rather than
+    /// in the signing context. This must be set prior to adding the first
+    /// ring entry.
+    pub fn set_response_buffer(&mut self, buff: ResponseBufferPtr) {
+        self.responses = Some(Responses::Static(buff, 0));
+    }
+
     /// Update RingSigner with the provided event
     #[cfg_attr(feature = "noinline", inline(never))]
     pub fn update(
@@ -205,6 +388,15 @@ impl RingSigner {
 
             // Add txouts to ring
             (RingState::BuildRing(n), Event::TxAddTxout(index, txout)) => {
+                // Check the index is viable prior to touching the ring context
+                if let Err(e) = self.check_index(n as usize, *index as usize) {
+                    #[cfg(feature = "log")]
+                    log::error!("invalid ring index {}: {:?}", index, e);
+
+                    self.state = RingState::Error;
+                    return Err(e);
+                }
+
                 // On the first entry (ie. the real one),
                 if n == 0 {
                     // Initialise the ring signing context and recover the onetime_private_key
@@ -226,6 +418,8 @@ impl RingSigner {
                     return Err(e);
                 };
 
+                self.added |= 1 << *index;
+
                 // Move on when we have enough ring entries (MOB-06.4)
                 if (n + 1) as usize == self.ring_size {
                     self.state = RingState::Execute;
@@ -285,12 +479,46 @@ impl RingSigner {
                     },
                 ));
             }
+
+            // Fetch a range of responses to reduce round-trips
+            (RingState::Complete { .. }, Event::TxGetResponses { start, count }) => {
+                let responses = match self.ring_ctx.as_ref().and_then(|c| c.responses()) {
+                    Some(v) => v,
+                    None => return Err(Error::UnexpectedEvent),
+                };
+
+                let scalars = get_responses(responses, *start, *count)?;
+
+                // Update last index for progress indication (MOB-06.9)
+                self.fetch_count = (self.fetch_count + scalars.len()).min(self.ring_size * 2);
+
+                return Ok((
+                    self.state,
+                    Output::TxResponses {
+                        start: *start,
+                        scalars,
+                    },
+                ));
+            }
             _ => (),
         }
 
         Ok((self.state, Output::None))
     }
 
+    /// Fetch responses for a completed ring
+    pub fn responses(&self) -> Option<&[CurveScalar]> {
+        match self.state {
+            RingState::Complete { .. } => (),
+            _ => return None,
+        }
+
+        self.ring_ctx
+            .as_ref()
+            .and_then(|c| c.responses())
+            .map(|r| &r[..])
+    }
+
     /// Fetch ring progress (n / 100)
     pub fn progress(&self) -> usize {
         let ring_size = self.ring_size;
@@ -344,7 +572,10 @@ impl RingSigner {
         );
 
         // Check this is the correct onetime private key for the txout
-        if RistrettoPublic::from(&onetime_private_key) != tx_out_target_key {
+        if !ct_eq(
+            &RistrettoPublic::from(&onetime_private_key).to_bytes(),
+            &tx_out_target_key.to_bytes(),
+        ) {
             // Zeroize recovered key on failure (MOB-01.3)
             onetime_private_key.zeroize();
             return Err(Error::OnetimeKeyRecoveryFailed);
@@ -360,16 +591,20 @@ impl RingSigner {
             blinding: &blindings.blinding,
             output_blinding: &blindings.output_blinding,
             generator: &self.generator,
-            check_value_is_preserved: false,
+            check_value_is_preserved: CHECK_VALUE_IS_PRESERVED,
         };
 
-        // Setup response storage, this _must_ be ring_size * 2 or init will fail
-        // NOTE: switched to this approach to avoid miri complaints, costs us ~1.5k of stack but, we have the headroom on the nanosplus and nanox support requires the stack fix anyway.
-        // TODO: work out whether we can push storage of this up to the global context to reduce stack use.
-        let mut responses = heapless::Vec::<_, RESP_SIZE>::new();
-        responses
-            .resize(self.ring_size * 2, CurveScalar::default())
-            .unwrap();
+        // Setup response storage, this _must_ be ring_size * 2 or init will fail.
+        // Platforms provide a statically allocated buffer to avoid ~1.5k of stack use,
+        // with owned storage used for hosts without a configured buffer
+        let mut responses = match self.responses.take() {
+            Some(r) => r,
+            #[cfg(feature = "std")]
+            None => Responses::Owned(Vec::new()),
+            #[cfg(not(feature = "std"))]
+            None => return Err(Error::RingInitFailed),
+        };
+        responses.reset(self.ring_size * 2)?;
 
         match MlsagSignCtx::init(&sign_params, rng, responses) {
             Ok(ctx) => {
@@ -377,11 +612,14 @@ impl RingSigner {
                 self.onetime_private_key = Some(onetime_private_key);
                 self.ring_ctx = Some(ctx);
             }
-            Err(_e) => {
+            Err(e) => {
                 // Clear onetime private key
                 onetime_private_key.zeroize();
                 // Fail out
-                return Err(Error::RingInitFailed);
+                return match e {
+                    RingError::ValueNotConserved => Err(Error::ValueNotConserved),
+                    _ => Err(Error::RingInitFailed),
+                };
             }
         }
 
@@ -416,7 +654,7 @@ impl RingSigner {
             blinding: &blindings.blinding,
             output_blinding: &blindings.output_blinding,
             generator: &self.generator,
-            check_value_is_preserved: false,
+            check_value_is_preserved: CHECK_VALUE_IS_PRESERVED,
         };
 
         // Decompress txout
@@ -425,7 +663,24 @@ impl RingSigner {
         // Add txout to ring
         ring_ctx
             .update(&sign_params, index, &tx_out)
-            .map_err(|_e| Error::RingUpdateFailed)?;
+            .map_err(|e| match e {
+                RingError::ValueNotConserved => Error::ValueNotConserved,
+                _ => Error::RingUpdateFailed,
+            })?;
+
+        Ok(())
+    }
+
+    /// Internal helper to check ring entry indices are in range,
+    /// not yet added, and that the real entry is provided first
+    fn check_index(&self, n: usize, index: usize) -> Result<(), Error> {
+        if index >= self.ring_size || (n == 0 && index != self.real_index) {
+            return Err(Error::InvalidRingIndex);
+        }
+
+        if self.added & (1 << index) != 0 {
+            return Err(Error::DuplicateRingIndex);
+        }
 
         Ok(())
     }
@@ -457,11 +712,17 @@ impl RingSigner {
             blinding: &blindings.blinding,
             output_blinding: &blindings.output_blinding,
             generator: &self.generator,
-            check_value_is_preserved: false,
+            check_value_is_preserved: CHECK_VALUE_IS_PRESERVED,
         };
 
         let (key_image, c_zero) = match ring_ctx.finalise(&sign_params) {
             Ok(v) => v,
+            // Real input commitment does not match value / blindings
+            Err(RingError::ValueNotConserved) => {
+                #[cfg(feature = "log")]
+                log::error!("Ring signing failed: value not conserved");
+                return Err(Error::ValueNotConserved);
+            }
             Err(_e) => {
                 #[cfg(feature = "log")]
                 log::error!("Ring signing failed: {:?}", _e);
@@ -473,6 +734,29 @@ impl RingSigner {
     }
 }
 
+/// Fetch `count` response scalars from `start`, for multi-scalar response
+/// requests (see [TxGetResponses][ledger_mob_apdu::tx::TxGetResponses])
+pub(crate) fn get_responses(
+    responses: &[CurveScalar],
+    start: u8,
+    count: u8,
+) -> Result<Vec<Scalar, TX_RESPONSES_MAX>, Error> {
+    let (start, count) = (start as usize, count as usize);
+
+    // Check requested count fits a single response
+    if count == 0 || count > TX_RESPONSES_MAX {
+        return Err(Error::InvalidLength);
+    }
+
+    let r = match responses.get(start..start + count) {
+        Some(v) => v,
+        None => return Err(Error::UnexpectedEvent),
+    };
+
+    // Capacity checked above
+    Ok(r.iter().map(|v| (*v).into()).collect())
+}
+
 #[cfg(test)]
 mod test {
     use core::mem::MaybeUninit;
@@ -481,11 +765,11 @@ mod test {
 
     use mc_core::{account::RingCtAddress, subaddress::Subaddress};
     use mc_crypto_ring_signature::{
-        onetime_keys::recover_public_subaddress_spend_key, CompressedCommitment, MlsagVerify,
+        onetime_keys::recover_public_subaddress_spend_key, CompressedCommitment,
     };
     use mc_util_test_helper::{RngType, SeedableRng};
 
-    use crate::engine::test::TestDriver;
+    use crate::{engine::SoftwareDriver, verifier::verify_ring};
     use ledger_mob_tests::mlsag::RingMLSAGParameters;
 
     use super::*;
@@ -505,8 +789,8 @@ mod test {
         let mut rng: RngType = SeedableRng::from_seed(seed);
         let pseudo_output_blinding = Scalar::random(&mut rng);
 
-        let drv = TestDriver::new();
-        let account = drv.account();
+        let drv = SoftwareDriver::random();
+        let account = drv.account(0);
 
         let params =
             RingMLSAGParameters::random(&account, RING_SIZE - 1, pseudo_output_blinding, &mut rng);
@@ -634,6 +918,40 @@ mod test {
 
         assert_eq!(ring_signer.progress(), 100);
 
+        // Fetch responses in ranges, matching individually fetched responses
+        for start in (0..RESP_SIZE).step_by(TX_RESPONSES_MAX) {
+            let count = TX_RESPONSES_MAX.min(RESP_SIZE - start);
+            let evt = Event::TxGetResponses {
+                start: start as u8,
+                count: count as u8,
+            };
+
+            let (_state, output) = ring_signer.update(&evt, OsRng {}).expect("Fetch responses");
+
+            match output {
+                Output::TxResponses { start: s, scalars } => {
+                    assert_eq!(s as usize, start);
+                    for (i, scalar) in scalars.iter().enumerate() {
+                        assert_eq!(CurveScalar::from(*scalar), responses[start + i]);
+                    }
+                }
+                _ => panic!("Unexpected output: {output:?}"),
+            }
+        }
+
+        // Reject empty, oversized and out of range requests
+        for (start, count, err) in [
+            (0, 0, Error::InvalidLength),
+            (0, TX_RESPONSES_MAX + 1, Error::InvalidLength),
+            (RESP_SIZE - 1, 2, Error::UnexpectedEvent),
+        ] {
+            let evt = Event::TxGetResponses {
+                start: start as u8,
+                count: count as u8,
+            };
+            assert_eq!(ring_signer.update(&evt, OsRng {}).map(|_| ()), Err(err));
+        }
+
         #[cfg(feature = "log")]
         {
             log::debug!("c_zero: {}", CurveScalar::from(c_zero));
@@ -676,18 +994,257 @@ mod test {
         let output_commitment =
             CompressedCommitment::new(params.value, pseudo_output_blinding, &params.generator);
 
-        let verifier = MlsagVerify {
-            message: &params.message,
-            c_zero: &CurveScalar::from(c_zero),
-            responses: responses.as_slice(),
-            key_image: &key_image,
-            ring: params.ring.as_slice(),
-            output_commitment: &output_commitment,
+        verify_ring(
+            &params.message,
+            params.ring.as_slice(),
+            &output_commitment,
+            &key_image,
+            &CurveScalar::from(c_zero),
+            responses.as_slice(),
+        )
+        .expect("Failed to verify ring");
+    }
+
+    /// Rings with a `value` not matching the real input commitment must be
+    /// rejected with [Error::ValueNotConserved] (see [CHECK_VALUE_IS_PRESERVED])
+    #[test]
+    fn ring_sign_value_not_conserved() {
+        let seed = [0u8; 32];
+        let mut rng: RngType = SeedableRng::from_seed(seed);
+        let pseudo_output_blinding = Scalar::random(&mut rng);
+
+        let drv = SoftwareDriver::random();
+        let account = drv.account(0);
+
+        let params =
+            RingMLSAGParameters::random(&account, RING_SIZE - 1, pseudo_output_blinding, &mut rng);
+
+        // Setup ring signer with a value that does not match the real input
+        let mut r = MaybeUninit::uninit();
+        let mut ring_signer = unsafe {
+            RingSigner::init(
+                r.as_mut_ptr(),
+                RING_SIZE,
+                params.real_index,
+                account.view_private_key(),
+                account
+                    .subaddress(params.target_subaddress_index)
+                    .spend_private_key(),
+                params.value + 1,
+                &params.message,
+                params.token_id,
+                None,
+            )
+            .unwrap();
+            r.assume_init()
+        };
+
+        ring_signer
+            .update(
+                &Event::TxSetBlinding {
+                    blinding: params.blinding,
+                    output_blinding: pseudo_output_blinding,
+                },
+                OsRng {},
+            )
+            .expect("Set blinding");
+
+        // Load txouts and sign, capturing the first failure
+        let mut err = None;
+        for n in 0..RING_SIZE {
+            let i = (params.real_index + n) % RING_SIZE;
+            let evt = Event::TxAddTxout(i as u8, params.ring[i].clone());
+
+            if let Err(e) = ring_signer.update(&evt, OsRng {}) {
+                err = Some(e);
+                break;
+            }
+        }
+        if err.is_none() {
+            err = ring_signer.update(&Event::TxSign, OsRng {}).err();
+        }
+
+        assert_eq!(err, Some(Error::ValueNotConserved));
+        assert_eq!(ring_signer.state, RingState::Error);
+    }
+
+    /// Sign a ring end-to-end using an RNG from the provided seed,
+    /// returning c_zero and responses
+    fn sign_ring(
+        account: &mc_core::account::Account,
+        params: &RingMLSAGParameters,
+        output_blinding: Scalar,
+        rng_seed: [u8; 32],
+        responses: Option<ResponseBufferPtr>,
+    ) -> (Scalar, alloc::vec::Vec<Scalar>) {
+        let mut rng: RngType = SeedableRng::from_seed(rng_seed);
+
+        let mut r = MaybeUninit::uninit();
+        let mut ring_signer = unsafe {
+            RingSigner::init(
+                r.as_mut_ptr(),
+                RING_SIZE,
+                params.real_index,
+                account.view_private_key(),
+                account
+                    .subaddress(params.target_subaddress_index)
+                    .spend_private_key(),
+                params.value,
+                &params.message,
+                params.token_id,
+                None,
+            )
+            .unwrap();
+            r.assume_init()
+        };
+        if let Some(b) = responses {
+            ring_signer.set_response_buffer(b);
+        }
+
+        ring_signer
+            .update(
+                &Event::TxSetBlinding {
+                    blinding: params.blinding,
+                    output_blinding,
+                },
+                &mut rng,
+            )
+            .expect("Set blinding");
+
+        for n in 0..RING_SIZE {
+            let i = (params.real_index + n) % RING_SIZE;
+            ring_signer
+                .update(
+                    &Event::TxAddTxout(i as u8, params.ring[i].clone()),
+                    &mut rng,
+                )
+                .expect("Failed to add txout");
+        }
+
+        ring_signer
+            .update(&Event::TxSign, &mut rng)
+            .expect("Execute sign");
+
+        let c_zero = match ring_signer.state {
+            RingState::Complete { c_zero, .. } => c_zero,
+            _ => panic!("unexpected state: {:?}", ring_signer.state),
+        };
+
+        let responses = (0..RESP_SIZE)
+            .map(|i| {
+                match ring_signer
+                    .update(&Event::TxGetResponse { index: i as u8 }, &mut rng)
+                    .expect("Fetch response")
+                {
+                    (_, Output::TxResponse { scalar, .. }) => scalar,
+                    (_, o) => panic!("unexpected output: {o:?}"),
+                }
+            })
+            .collect();
+
+        (c_zero, responses)
+    }
+
+    /// Signatures using a platform response buffer must match those
+    /// using owned storage, with the buffer reused between rings
+    #[test]
+    fn ring_sign_response_buffer() {
+        let seed = [2u8; 32];
+        let mut rng: RngType = SeedableRng::from_seed(seed);
+        let pseudo_output_blinding = Scalar::random(&mut rng);
+
+        let drv = SoftwareDriver::random();
+        let account = drv.account(0);
+
+        let params =
+            RingMLSAGParameters::random(&account, RING_SIZE - 1, pseudo_output_blinding, &mut rng);
+
+        let buff = alloc::boxed::Box::leak(alloc::boxed::Box::new(MaybeUninit::uninit()));
+        let buff = unsafe {
+            ResponseBuffer::init(buff.as_mut_ptr());
+            buff.assume_init_mut()
         };
+        let p = ResponseBufferPtr::new(buff);
+
+        let a = sign_ring(&account, &params, pseudo_output_blinding, seed, None);
+        let b = sign_ring(&account, &params, pseudo_output_blinding, seed, Some(p));
+        let c = sign_ring(&account, &params, pseudo_output_blinding, seed, Some(p));
+
+        assert_eq!(a, b, "buffered signature differs");
+        assert_eq!(a, c, "reused buffer signature differs");
+    }
+
+    /// Ring entries must be in range, unique, and start with the real input
+    #[test]
+    fn ring_index_checks() {
+        let seed = [0u8; 32];
+        let mut rng: RngType = SeedableRng::from_seed(seed);
+        let pseudo_output_blinding = Scalar::random(&mut rng);
 
-        let mut recomputed_c = [Scalar::default(); RING_SIZE];
-        verifier
-            .verify(&mut recomputed_c[..])
-            .expect("Failed to verify ring");
+        let drv = SoftwareDriver::random();
+        let account = drv.account(0);
+
+        let params =
+            RingMLSAGParameters::random(&account, RING_SIZE - 1, pseudo_output_blinding, &mut rng);
+        let real_index = params.real_index;
+        let other_index = (real_index + 1) % RING_SIZE;
+
+        // Setup ring signer and blindings ready for txouts
+        let signer = || {
+            let mut s = RingSigner::new(
+                RING_SIZE,
+                real_index,
+                account.view_private_key(),
+                account
+                    .subaddress(params.target_subaddress_index)
+                    .spend_private_key(),
+                params.value,
+                &params.message,
+                params.token_id,
+                None,
+            )
+            .unwrap();
+
+            s.update(
+                &Event::TxSetBlinding {
+                    blinding: params.blinding,
+                    output_blinding: pseudo_output_blinding,
+                },
+                OsRng {},
+            )
+            .expect("Set blinding");
+
+            s
+        };
+
+        let add = |s: &mut RingSigner, i: usize| {
+            s.update(
+                &Event::TxAddTxout(i as u8, params.ring[i].clone()),
+                OsRng {},
+            )
+            .map(|(state, _)| state)
+        };
+
+        // The first entry must be the real input
+        let mut s = signer();
+        assert_eq!(add(&mut s, other_index), Err(Error::InvalidRingIndex));
+        assert_eq!(s.state, RingState::Error);
+
+        // Indices must be within the ring
+        let mut s = signer();
+        assert_eq!(add(&mut s, real_index), Ok(RingState::BuildRing(1)));
+        let r = s.update(
+            &Event::TxAddTxout(RING_SIZE as u8, params.ring[other_index].clone()),
+            OsRng {},
+        );
+        assert_eq!(r.map(|(state, _)| state), Err(Error::InvalidRingIndex));
+        assert_eq!(s.state, RingState::Error);
+
+        // Indices may only be added once
+        let mut s = signer();
+        assert_eq!(add(&mut s, real_index), Ok(RingState::BuildRing(1)));
+        assert_eq!(add(&mut s, other_index), Ok(RingState::BuildRing(2)));
+        assert_eq!(add(&mut s, other_index), Err(Error::DuplicateRingIndex));
+        assert_eq!(s.state, RingState::Error);
     }
 }
@@ -0,0 +1,92 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Software [Driver] implementation, allowing the [Engine][super::Engine]
+//! to be executed on a host (for example, to emulate a device in CI
+//! without hardware or speculos).
+//!
+//! Keys are derived from a BIP39 mnemonic held in memory, this
+//! _MUST NOT_ be used for wallets holding real funds.
+
+use bip39::{Language, Mnemonic, MnemonicType, Seed};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use mc_core::{
+    account::Account,
+    slip10::{wallet_path, Slip10Key},
+};
+
+use super::Driver;
+
+/// Software [Driver], deriving keys from a BIP39 mnemonic seed
+pub struct SoftwareDriver {
+    /// BIP39 mnemonic derived seed
+    seed: [u8; 64],
+}
+
+impl SoftwareDriver {
+    /// Create a new [SoftwareDriver] from a BIP39 mnemonic and passphrase
+    pub fn from_mnemonic(mnemonic: &Mnemonic, passphrase: &str) -> Self {
+        let s = Seed::new(mnemonic, passphrase);
+
+        let mut seed = [0u8; 64];
+        seed.copy_from_slice(s.as_bytes());
+
+        Self { seed }
+    }
+
+    /// Create a new [SoftwareDriver] from a raw (BIP39 derived) seed
+    pub fn from_seed(seed: [u8; 64]) -> Self {
+        Self { seed }
+    }
+
+    /// Create a new [SoftwareDriver] with a randomly generated 24-word mnemonic
+    pub fn random() -> Self {
+        let m = Mnemonic::new(MnemonicType::Words24, Language::English);
+        Self::from_mnemonic(&m, "")
+    }
+
+    /// Fetch the account for the provided SLIP-0010 account index
+    pub fn account(&self, account_index: u32) -> Account {
+        let key = self.slip10_derive_ed25519(&wallet_path(account_index));
+        Account::from(&key)
+    }
+}
+
+impl Driver for SoftwareDriver {
+    /// SLIP-0010 ed25519 derivation from the mnemonic seed
+    fn slip10_derive_ed25519(&self, path: &[u32]) -> Slip10Key {
+        let d = slip10_ed25519::derive_ed25519_private_key(&self.seed, path);
+        Slip10Key::from_raw(d)
+    }
+}
+
+/// Clear mnemonic seed on drop
+impl Drop for SoftwareDriver {
+    fn drop(&mut self) {
+        self.seed.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SoftwareDriver {}
+
+#[cfg(test)]
+mod test {
+    use mc_core::{account::RingCtAddress, slip10::Slip10KeyGenerator, subaddress::Subaddress};
+
+    use super::*;
+
+    /// Ensure derived accounts match mc-core mnemonic derivation
+    #[test]
+    fn software_driver_accounts() {
+        let m = Mnemonic::new(MnemonicType::Words24, Language::English);
+        let d = SoftwareDriver::from_mnemonic(&m, "");
+
+        for i in 0..4 {
+            let expected = Account::from(&m.clone().derive_slip10_key(i)).subaddress(0);
+            let a = d.account(i).subaddress(0);
+
+            assert_eq!(a.spend_public_key(), expected.spend_public_key());
+            assert_eq!(a.view_public_key(), expected.view_public_key());
+        }
+    }
+}
@@ -4,11 +4,12 @@
 use alloc::string::ToString;
 use heapless::Vec;
 
-use ledger_mob_apdu::tx::FogId;
+use ledger_mob_apdu::tx::{FogId, FogUrl};
 use strum::{Display, EnumIter, EnumString, EnumVariantNames};
 
 use mc_core::{
-    account::{PublicSubaddress, ShortAddressHash},
+    account::{PublicSubaddress, RingCtAddress, ShortAddressHash},
+    consts::{CHANGE_SUBADDRESS_INDEX, DEFAULT_SUBADDRESS_INDEX, GIFT_CODE_SUBADDRESS_INDEX},
     keys::{RootViewPrivate, SubaddressViewPublic, TxOutPublic, TxOutTargetPublic},
 };
 use mc_crypto_digestible::{DigestTranscript, Digestible};
@@ -19,10 +20,15 @@ use mc_transaction_summary::{
     TransactionEntity, TxSummaryStreamingVerifierCtx, TxSummaryUnblindingReport,
 };
 use mc_transaction_types::{
-    Amount, BlockVersion, MaskedAmount, TxInSummary, TxOutSummary, UnmaskedAmount,
+    Amount, BlockVersion, MaskedAmount, TokenId, TxInSummary, TxOutSummary, UnmaskedAmount,
 };
 
-use crate::{apdu::tx::TxPrivateKey, helpers::digest_public_address};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{
+    apdu::tx::TxPrivateKey,
+    helpers::{digest_public_address, is_dust, zeroize_option},
+};
 
 use super::{Error, Event};
 
@@ -33,14 +39,41 @@ pub struct Summarizer<const MAX_RECORDS: usize = 16> {
     report: TxSummaryUnblindingReport<MAX_RECORDS>,
     addresses: Vec<OutputAddress, MAX_RECORDS>,
     tx_out_summary: Option<TxOutSummary>,
+    own_addresses: [(ReservedSubaddress, PublicSubaddress); 3],
+    tombstone_status: TombstoneStatus,
+    totals: Vec<TokenTotals, MAX_RECORDS>,
+    dust: Vec<DustTotal, MAX_RECORDS>,
+    inputs: Vec<(u64, u64), MAX_RECORDS>,
     num_outputs: usize,
     num_inputs: usize,
 }
 
+/// Per-token input and output sums, accumulated using 128-bit arithmetic
+/// to ensure report totals can not wrap
+#[derive(Clone, Debug, PartialEq)]
+struct TokenTotals {
+    token_id: u64,
+    inputs: u128,
+    outputs: u128,
+}
+
+/// Per-token dust outputs, folded from the report to avoid
+/// paging through many negligible outputs
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DustTotal {
+    /// Token ID for folded outputs
+    pub token_id: TokenId,
+    /// Number of outputs folded
+    pub count: usize,
+    /// Sum of folded output values
+    pub value: u64,
+}
+
 /// Summarizer state enumeration
 #[derive(
     Copy, Clone, PartialEq, Debug, Default, EnumString, Display, EnumVariantNames, EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SummaryState {
     #[default]
     Init,
@@ -50,6 +83,97 @@ pub enum SummaryState {
     Complete,
 }
 
+/// Maximum tombstone offset from the current block accepted by the network
+pub const MAX_TOMBSTONE_BLOCKS: u64 = 20_160;
+
+/// Tombstone block plausibility, checked against the current block
+/// index where this is provided by the host
+#[derive(
+    Copy, Clone, PartialEq, Debug, Default, EnumString, Display, EnumVariantNames, EnumIter,
+)]
+pub enum TombstoneStatus {
+    /// Current block not provided, tombstone unchecked
+    #[default]
+    Unknown,
+    /// Tombstone block is plausible
+    Valid,
+    /// Tombstone block has already passed
+    Expired,
+    /// Tombstone block exceeds [MAX_TOMBSTONE_BLOCKS] from the current block
+    FarFuture,
+}
+
+impl TombstoneStatus {
+    /// Check a tombstone block against the current block index
+    pub fn check(tombstone_block: u64, current_block: Option<u64>) -> Self {
+        let current_block = match current_block {
+            Some(v) => v,
+            None => return Self::Unknown,
+        };
+
+        if tombstone_block <= current_block {
+            Self::Expired
+        } else if tombstone_block - current_block > MAX_TOMBSTONE_BLOCKS {
+            Self::FarFuture
+        } else {
+            Self::Valid
+        }
+    }
+
+    /// Check whether the tombstone status should be flagged to the user
+    pub fn is_warning(&self) -> bool {
+        matches!(self, Self::Expired | Self::FarFuture)
+    }
+}
+
+/// Reserved subaddresses of the signing account, tagged in
+/// summary reports so these may be named when rendered
+#[derive(Copy, Clone, PartialEq, Debug, EnumString, Display, EnumVariantNames, EnumIter)]
+pub enum ReservedSubaddress {
+    /// Default subaddress
+    Default,
+    /// Change subaddress
+    Change,
+    /// Gift code subaddress
+    GiftCode,
+}
+
+impl ReservedSubaddress {
+    /// Fetch the subaddress index for a reserved subaddress
+    pub const fn index(&self) -> u64 {
+        match self {
+            Self::Default => DEFAULT_SUBADDRESS_INDEX,
+            Self::Change => CHANGE_SUBADDRESS_INDEX,
+            Self::GiftCode => GIFT_CODE_SUBADDRESS_INDEX,
+        }
+    }
+
+    /// Fetch a display label for a reserved subaddress
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::Default => "Default",
+            Self::Change => "Change",
+            Self::GiftCode => "Gift Code",
+        }
+    }
+}
+
+/// Clear verifier context, containing the account view private key (MOB-01.x)
+impl<const MAX_RECORDS: usize> Zeroize for Summarizer<MAX_RECORDS> {
+    fn zeroize(&mut self) {
+        zeroize_option(&mut self.verifier);
+        zeroize_option(&mut self.tx_out_summary);
+    }
+}
+
+impl<const MAX_RECORDS: usize> Drop for Summarizer<MAX_RECORDS> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<const MAX_RECORDS: usize> ZeroizeOnDrop for Summarizer<MAX_RECORDS> {}
+
 /// Cached output address for later rendering
 pub struct OutputAddress {
     /// Short address hash, matched against report entries
@@ -60,6 +184,18 @@ pub struct OutputAddress {
     pub fog_id: FogId,
     /// Fog signature
     pub fog_sig: Option<[u8; 64]>,
+    /// Fog report URL, for fog deployments without a [FogId]
+    pub fog_url: Option<FogUrl>,
+}
+
+impl OutputAddress {
+    /// Fetch fog report URL for address rendering
+    pub fn fog_report_url(&self) -> &str {
+        match &self.fog_url {
+            Some(f) => f.url(),
+            None => self.fog_id.url(),
+        }
+    }
 }
 
 impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
@@ -71,9 +207,11 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
         num_inputs: usize,
         view_private_key: &RootViewPrivate,
         change_address: &PublicSubaddress,
+        default_address: &PublicSubaddress,
+        gift_code_address: &PublicSubaddress,
     ) -> Result<Self, Error> {
-        // Check we have some inputs / outputs
-        if num_inputs == 0 || num_outputs == 0 {
+        // Check we have some inputs / outputs, and that inputs may be recorded
+        if num_inputs == 0 || num_outputs == 0 || num_inputs > MAX_RECORDS {
             return Err(Error::SummaryInitFailed);
         }
 
@@ -95,6 +233,15 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
             report,
             addresses: Vec::new(),
             tx_out_summary: None,
+            own_addresses: [
+                (ReservedSubaddress::Change, change_address.clone()),
+                (ReservedSubaddress::Default, default_address.clone()),
+                (ReservedSubaddress::GiftCode, gift_code_address.clone()),
+            ],
+            tombstone_status: TombstoneStatus::Unknown,
+            totals: Vec::new(),
+            dust: Vec::new(),
+            inputs: Vec::new(),
             num_outputs,
             num_inputs,
         })
@@ -112,9 +259,11 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
         num_inputs: usize,
         view_private_key: &RootViewPrivate,
         change_address: &PublicSubaddress,
+        default_address: &PublicSubaddress,
+        gift_code_address: &PublicSubaddress,
     ) -> Result<(), Error> {
-        // Check we have some inputs / outputs (MOB-06.3)
-        if num_inputs == 0 || num_outputs == 0 {
+        // Check we have some inputs / outputs, and that inputs may be recorded (MOB-06.3)
+        if num_inputs == 0 || num_outputs == 0 || num_inputs > MAX_RECORDS {
             return Err(Error::SummaryInitFailed);
         }
 
@@ -131,6 +280,15 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
             report: TxSummaryUnblindingReport::default(),
             addresses: Vec::new(),
             tx_out_summary: None,
+            own_addresses: [
+                (ReservedSubaddress::Change, change_address.clone()),
+                (ReservedSubaddress::Default, default_address.clone()),
+                (ReservedSubaddress::GiftCode, gift_code_address.clone()),
+            ],
+            tombstone_status: TombstoneStatus::Unknown,
+            totals: Vec::new(),
+            dust: Vec::new(),
+            inputs: Vec::new(),
             num_outputs,
             num_inputs,
         });
@@ -176,6 +334,7 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
         unmasked_amount: &UnmaskedAmount,
         address: Option<&PublicSubaddress>,
         fog_info: Option<(FogId, &[u8; 64])>,
+        fog_url: Option<&FogUrl>,
         tx_private_key: Option<&TxPrivateKey>,
     ) -> Result<SummaryState, Error> {
         // Check state
@@ -195,6 +354,9 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
             }
         };
 
+        // Accumulate output value
+        self.accumulate(unmasked_amount.token_id, unmasked_amount.value, false)?;
+
         let verifier = match &mut self.verifier {
             Some(v) => v,
             None => {
@@ -205,11 +367,17 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
             }
         };
 
-        // Regenerate short hash for address
-        let (fog_url, fog_sig) = fog_info
-            .map(|(f, s)| (f.url(), &s[..]))
+        // Fog URLs require an authority signature
+        if fog_url.is_some() && fog_info.is_none() {
+            return Err(Error::InvalidFogUrl);
+        }
+
+        // Regenerate short hash for address, using the provided
+        // fog URL for fog deployments without a [FogId]
+        let (fog_report_url, fog_sig) = fog_info
+            .map(|(f, s)| (fog_url.map(|u| u.url()).unwrap_or(f.url()), &s[..]))
             .unwrap_or(("", &[]));
-        let a = address.map(|a| (digest_public_address(a, fog_url, fog_sig), a));
+        let a = address.map(|a| (digest_public_address(a, fog_report_url, fog_sig), a));
 
         // Cache output address' for future display
         if let Some((h, _)) = &a {
@@ -219,6 +387,7 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
                     address: address.cloned().unwrap(),
                     fog_id: fog_info.map(|(f, _)| f).unwrap_or_default(),
                     fog_sig: fog_info.map(|(_, s)| *s),
+                    fog_url: fog_url.cloned(),
                 });
             }
         }
@@ -266,6 +435,18 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
             None => [].to_vec(),
         };
 
+        // Accumulate input value
+        self.accumulate(unmasked_amount.token_id, unmasked_amount.value, true)?;
+
+        // Record input value and token for checking against signed rings
+        if self
+            .inputs
+            .push((unmasked_amount.value, unmasked_amount.token_id))
+            .is_err()
+        {
+            return Err(Error::InvalidLength);
+        }
+
         // Build txin summary
         let tx_in_summary = TxInSummary {
             pseudo_output_commitment,
@@ -310,10 +491,27 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
         &mut self,
         fee: Amount,
         tombstone_block: u64,
+        current_block: Option<u64>,
         digest: &mut [u8; 32],
     ) -> Result<SummaryState, Error> {
         // TODO: check state
 
+        // Check tombstone block is plausible, warnings are displayed
+        // for user approval rather than rejecting the transaction
+        self.tombstone_status = TombstoneStatus::check(tombstone_block, current_block);
+        if self.tombstone_status.is_warning() {
+            #[cfg(feature = "log")]
+            log::warn!(
+                "tombstone block {} {} (current block: {:?})",
+                tombstone_block,
+                self.tombstone_status,
+                current_block
+            );
+        }
+
+        // Accumulate fee (paid from inputs)
+        self.accumulate(*fee.token_id, fee.value, false)?;
+
         let verifier = match self.verifier.take() {
             Some(v) => v,
             None => {
@@ -324,12 +522,55 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
             }
         };
 
+        // Wipe the vacated verifier slot
+        zeroize_option(&mut self.verifier);
+
         // Finalise verification report
         verifier.finalize(fee, tombstone_block, digest, &mut self.report);
 
-        // Elide SCIs from totals for rendering
-        // TODO: we may wish to revisit this when SCIs are widely used
-        self.report.elide_swap_totals();
+        // Check outputs to our own account target the change or default
+        // subaddress, so a host can not label sends as change
+        for (entity, ..) in self.report.outputs.iter() {
+            let h = match entity {
+                TransactionEntity::OurAddress(h) => h,
+                _ => continue,
+            };
+
+            if !self.is_own_address(h) {
+                #[cfg(feature = "log")]
+                log::error!("output to unexpected own subaddress: {:?}", h);
+
+                return Err(Error::UnexpectedOwnAddress);
+            }
+        }
+
+        // SCI totals are retained in the report so swap counterparty
+        // amounts can be displayed when signing MCIP-31 fills
+
+        // Fold dust outputs into per-token entries for display
+        let dust = &mut self.dust;
+        self.report.outputs.retain(|(entity, token_id, value)| {
+            if matches!(entity, TransactionEntity::Swap) || !is_dust(*value, *token_id) {
+                return true;
+            }
+
+            match dust.iter_mut().find(|d| d.token_id == *token_id) {
+                Some(d) => {
+                    d.count += 1;
+                    d.value = d.value.saturating_add(*value);
+                }
+                None => {
+                    // Capacity matches the report so this can not fail
+                    let _ = dust.push(DustTotal {
+                        token_id: *token_id,
+                        count: 1,
+                        value: *value,
+                    });
+                }
+            }
+
+            false
+        });
 
         // Set complete state
         self.state = SummaryState::Complete;
@@ -344,13 +585,26 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
         self.state
     }
 
+    /// Fetch tombstone block status (must be called after `finalize`)
+    #[inline]
+    pub fn tombstone_status(&self) -> TombstoneStatus {
+        self.tombstone_status
+    }
+
     /// Fetch summarizer progress (n / 100)
     pub fn progress(&self) -> usize {
-        let total = self.num_inputs + self.num_outputs + 1;
+        let (index, total) = self.steps();
 
         #[cfg(feature = "log")]
         log::debug!("progress: {:?} / {}", self.state, total);
 
+        index * 100 / total
+    }
+
+    /// Fetch summarizer step counts (entries loaded, total steps)
+    pub fn steps(&self) -> (usize, usize) {
+        let total = self.num_inputs + self.num_outputs + 1;
+
         let index = match self.state {
             SummaryState::Init => 0,
             SummaryState::AddTxOut(n) => n,
@@ -359,7 +613,7 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
             SummaryState::Complete => self.num_outputs + self.num_inputs + 1,
         };
 
-        index * 100 / total
+        (index, total)
     }
 
     /// Fetch report from summarizer (must be called after `finalize`)
@@ -368,11 +622,91 @@ impl<const MAX_RECORDS: usize> Summarizer<MAX_RECORDS> {
         &self.report
     }
 
+    /// Fetch dust outputs folded from the report (must be called after `finalize`)
+    #[inline]
+    pub fn dust(&self) -> &[DustTotal] {
+        &self.dust
+    }
+
+    /// Fetch (value, token_id) pairs for inputs added to the summary
+    #[inline]
+    pub fn inputs(&self) -> &[(u64, u64)] {
+        &self.inputs
+    }
+
     /// Fetch address from summarizer cache (must be called after `finalize`)
     #[inline]
     pub fn address(&self, h: &ShortAddressHash) -> Option<&OutputAddress> {
         self.addresses.iter().find(|v| &v.short_hash == h)
     }
+
+    /// Accumulate per-token input or output values, returning
+    /// [Error::TotalsOverflow] where sums exceed the range of report totals
+    fn accumulate(&mut self, token_id: u64, value: u64, input: bool) -> Result<(), Error> {
+        // Fetch or create totals for this token
+        let t = match self.totals.iter().position(|t| t.token_id == token_id) {
+            Some(i) => &mut self.totals[i],
+            None => {
+                let t = TokenTotals {
+                    token_id,
+                    inputs: 0,
+                    outputs: 0,
+                };
+                self.totals.push(t).map_err(|_| Error::TotalsOverflow)?;
+                self.totals.last_mut().unwrap()
+            }
+        };
+
+        let sum = match input {
+            true => &mut t.inputs,
+            false => &mut t.outputs,
+        };
+
+        // Report totals are signed 64-bit values, sums of inputs or
+        // outputs must remain within this range
+        match sum.checked_add(value as u128) {
+            Some(v) if v <= i64::MAX as u128 => *sum = v,
+            _ => {
+                #[cfg(feature = "log")]
+                log::error!("totals overflow for token {}", token_id);
+
+                return Err(Error::TotalsOverflow);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check whether a short address hash resolves to the change
+    /// or default subaddress for the configured account
+    fn is_own_address(&self, h: &ShortAddressHash) -> bool {
+        matches!(
+            self.reserved_subaddress(h),
+            Some(ReservedSubaddress::Change | ReservedSubaddress::Default)
+        )
+    }
+
+    /// Resolve a reserved subaddress of the signing account by short hash,
+    /// allowing report entries to be named when rendered
+    pub fn reserved_subaddress(&self, h: &ShortAddressHash) -> Option<ReservedSubaddress> {
+        // Resolve via cached output addresses where available
+        if let Some(a) = self.address(h) {
+            return self
+                .own_addresses
+                .iter()
+                .find(|(_, o)| {
+                    o.view_public_key() == a.address.view_public_key()
+                        && o.spend_public_key() == a.address.spend_public_key()
+                })
+                .map(|(r, _)| *r);
+        }
+
+        // Otherwise match against own addresses without fog info
+        self.own_addresses
+            .iter()
+            .find(|(_, o)| &digest_public_address(o, "", &[]) == h)
+            .map(|(r, _)| *r)
+    }
 }
 
 #[cfg(test)]
@@ -381,9 +715,10 @@ mod test {
     use core::str::FromStr;
 
     use log::*;
-    use mc_core::consts::CHANGE_SUBADDRESS_INDEX;
     use mc_core::{account::Account, keys::Key, subaddress::Subaddress};
+    use mc_crypto_keys::RistrettoPrivate;
     use mc_transaction_summary::verify_tx_summary;
+    use mc_util_from_random::FromRandom;
     use rand_core::OsRng;
 
     use ledger_mob_tests::transaction::{test, TRANSACTIONS};
@@ -440,6 +775,8 @@ mod test {
                 summary.inputs.len(),
                 account.view_private_key(),
                 &PublicSubaddress::from(&account.subaddress(CHANGE_SUBADDRESS_INDEX)),
+                &PublicSubaddress::from(&account.subaddress(DEFAULT_SUBADDRESS_INDEX)),
+                &PublicSubaddress::from(&account.subaddress(GIFT_CODE_SUBADDRESS_INDEX)),
             )
             .unwrap();
             s.assume_init()
@@ -463,22 +800,29 @@ mod test {
             let address = unblinding.address.as_ref();
             let k = unblinding.tx_private_key.map(Key::from);
 
-            let fog_info =
-                address.and_then(|a| match (a.fog_report_url(), a.fog_authority_sig()) {
-                    (Some(url), Some(s)) => {
-                        let fog_id = FogId::from_str(url).unwrap();
-
+            // Resolve fog IDs, falling back to fog URLs for unknown fogs
+            let (fog_info, fog_url) =
+                match address.map(|a| (a.fog_report_url(), a.fog_authority_sig())) {
+                    Some((Some(url), Some(s))) => {
                         let mut sig = [0u8; 64];
                         sig.copy_from_slice(s.as_ref());
-                        Some((fog_id, sig))
+
+                        match FogId::from_str(url) {
+                            Ok(fog_id) => (Some((fog_id, sig)), None),
+                            Err(_) => (
+                                Some((FogId::None, sig)),
+                                Some(FogUrl::new(url, [0u8; 32]).unwrap()),
+                            ),
+                        }
                     }
-                    _ => None,
-                });
+                    _ => (None, None),
+                };
 
             s.add_output_unblinding(
                 &unblinding.unmasked_amount,
                 address.map(PublicSubaddress::from).as_ref(),
                 fog_info.as_ref().map(|(f, s)| (*f, s)),
+                fog_url.as_ref(),
                 k.as_ref(),
             )
             .unwrap();
@@ -534,6 +878,7 @@ mod test {
                     token_id: summary.fee_token_id.into(),
                 },
                 summary.tombstone_block,
+                None,
                 &mut computed_digest,
             )
             .unwrap();
@@ -541,6 +886,14 @@ mod test {
         let progress = s.progress();
         assert_eq!(progress, 100);
 
+        // Check inputs are recorded for ring checks
+        let expected_inputs: Vec<(u64, u64), 16> = unblinding_data
+            .inputs
+            .iter()
+            .map(|i| (i.value, i.token_id))
+            .collect();
+        assert_eq!(s.inputs(), &expected_inputs[..]);
+
         // TODO: check report
 
         assert_eq!(
@@ -550,6 +903,109 @@ mod test {
         );
     }
 
+    #[test]
+    fn own_address_check() {
+        let account = Account::new(
+            RistrettoPrivate::from_random(&mut OsRng {}).into(),
+            RistrettoPrivate::from_random(&mut OsRng {}).into(),
+        );
+
+        let change = PublicSubaddress::from(&account.subaddress(CHANGE_SUBADDRESS_INDEX));
+        let default = PublicSubaddress::from(&account.subaddress(DEFAULT_SUBADDRESS_INDEX));
+        let gift_code = PublicSubaddress::from(&account.subaddress(GIFT_CODE_SUBADDRESS_INDEX));
+        let other = PublicSubaddress::from(&account.subaddress(7));
+
+        let s = Summarizer::<16>::new(
+            &[0u8; 32],
+            BlockVersion::THREE,
+            1,
+            1,
+            account.view_private_key(),
+            &change,
+            &default,
+            &gift_code,
+        )
+        .unwrap();
+
+        // Change and default subaddresses are accepted
+        assert!(s.is_own_address(&digest_public_address(&change, "", &[])));
+        assert!(s.is_own_address(&digest_public_address(&default, "", &[])));
+
+        // Other subaddresses are rejected
+        assert!(!s.is_own_address(&digest_public_address(&other, "", &[])));
+
+        // Reserved subaddresses are tagged for display
+        let tests = [
+            (&change, Some(ReservedSubaddress::Change)),
+            (&default, Some(ReservedSubaddress::Default)),
+            (&gift_code, Some(ReservedSubaddress::GiftCode)),
+            (&other, None),
+        ];
+        for (a, r) in tests {
+            let h = digest_public_address(a, "", &[]);
+            assert_eq!(s.reserved_subaddress(&h), r);
+        }
+    }
+
+    #[test]
+    fn totals_overflow() {
+        let account = Account::new(
+            RistrettoPrivate::from_random(&mut OsRng {}).into(),
+            RistrettoPrivate::from_random(&mut OsRng {}).into(),
+        );
+        let change = PublicSubaddress::from(&account.subaddress(CHANGE_SUBADDRESS_INDEX));
+
+        let mut s = Summarizer::<16>::new(
+            &[0u8; 32],
+            BlockVersion::THREE,
+            1,
+            1,
+            account.view_private_key(),
+            &change,
+            &change,
+            &change,
+        )
+        .unwrap();
+
+        // Sums within range are accepted, independently per token and direction
+        s.accumulate(0, i64::MAX as u64 - 1, true).unwrap();
+        s.accumulate(0, 1, true).unwrap();
+        s.accumulate(0, i64::MAX as u64, false).unwrap();
+        s.accumulate(1, i64::MAX as u64, true).unwrap();
+
+        // Sums exceeding the signed 64-bit range are rejected
+        assert_eq!(s.accumulate(0, 1, true), Err(Error::TotalsOverflow));
+        assert_eq!(s.accumulate(1, u64::MAX, false), Err(Error::TotalsOverflow));
+    }
+
+    #[test]
+    fn tombstone_status() {
+        let tests = &[
+            (100, None, TombstoneStatus::Unknown),
+            (100, Some(99), TombstoneStatus::Valid),
+            (100, Some(100), TombstoneStatus::Expired),
+            (100, Some(200), TombstoneStatus::Expired),
+            (
+                100 + MAX_TOMBSTONE_BLOCKS,
+                Some(100),
+                TombstoneStatus::Valid,
+            ),
+            (
+                101 + MAX_TOMBSTONE_BLOCKS,
+                Some(100),
+                TombstoneStatus::FarFuture,
+            ),
+        ];
+
+        for (tombstone_block, current_block, expected) in tests {
+            assert_eq!(
+                TombstoneStatus::check(*tombstone_block, *current_block),
+                *expected,
+                "tombstone: {tombstone_block} current: {current_block:?}"
+            );
+        }
+    }
+
     #[test]
     fn summarizer_size() {
         // TODO: check summarizer size is reasonable
@@ -0,0 +1,114 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Transaction watchdog, aborting in-progress transactions that are
+//! not completed within a configured timeout.
+//!
+//! Timing is driven by the platform tick count provided via
+//! [Engine::set_ticks][super::Engine::set_ticks].
+
+/// Transaction watchdog, tracking the deadline for the in-progress transaction
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TxWatchdog {
+    /// Transaction timeout in ticks (0 to disable)
+    timeout: u32,
+    /// Platform ticks per second, for reporting remaining time
+    ticks_per_s: u32,
+    /// Deadline (in ticks) for the running transaction
+    deadline: Option<u32>,
+}
+
+impl TxWatchdog {
+    /// Create a new (disabled) watchdog
+    pub const fn new() -> Self {
+        Self {
+            timeout: 0,
+            ticks_per_s: 1,
+            deadline: None,
+        }
+    }
+
+    /// Configure the transaction timeout in seconds (0 to disable),
+    /// with the platform tick rate used to convert to / from ticks
+    pub fn configure(&mut self, timeout_s: u32, ticks_per_s: u32) {
+        self.ticks_per_s = ticks_per_s.max(1);
+        self.timeout = timeout_s.saturating_mul(self.ticks_per_s);
+        self.deadline = None;
+    }
+
+    /// Start (or restart) the watchdog from the current tick count
+    pub fn start(&mut self, now: u32) {
+        self.deadline = match self.timeout {
+            0 => None,
+            t => Some(now.wrapping_add(t)),
+        };
+    }
+
+    /// Stop the watchdog
+    pub fn stop(&mut self) {
+        self.deadline = None;
+    }
+
+    /// Fetch ticks remaining until the deadline, if running
+    ///
+    /// Tick counts wrap, deadlines are resolved relative to `now`.
+    pub fn remaining(&self, now: u32) -> Option<u32> {
+        let d = self.deadline?;
+
+        match d.wrapping_sub(now) as i32 {
+            v if v > 0 => Some(v as u32),
+            _ => Some(0),
+        }
+    }
+
+    /// Fetch seconds remaining until the deadline (rounded up), if running
+    pub fn remaining_s(&self, now: u32) -> Option<u32> {
+        self.remaining(now)
+            .map(|t| t / self.ticks_per_s + (t % self.ticks_per_s != 0) as u32)
+    }
+
+    /// Check whether the watchdog has expired
+    pub fn expired(&self, now: u32) -> bool {
+        self.remaining(now) == Some(0)
+    }
+}
+
+impl Default for TxWatchdog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn watchdog_deadline() {
+        let mut w = TxWatchdog::new();
+
+        // Disabled watchdogs never run
+        w.start(0);
+        assert_eq!(w.remaining(0), None);
+        assert!(!w.expired(u32::MAX));
+
+        // Configure a 10s timeout at 10 ticks/s
+        w.configure(10, 10);
+        w.start(50);
+        assert_eq!(w.remaining(50), Some(100));
+        assert_eq!(w.remaining_s(50), Some(10));
+        assert_eq!(w.remaining_s(141), Some(1));
+        assert!(!w.expired(149));
+        assert!(w.expired(150));
+        assert!(w.expired(200));
+
+        // Deadlines are resolved across tick wraparound
+        w.start(u32::MAX - 10);
+        assert_eq!(w.remaining(u32::MAX), Some(90));
+        assert_eq!(w.remaining(5), Some(84));
+        assert!(w.expired(89));
+
+        // Stopped watchdogs report no deadline
+        w.stop();
+        assert_eq!(w.remaining(0), None);
+    }
+}
@@ -1,53 +1,94 @@
 // Copyright (c) 2022-2023 The MobileCoin Foundation
 
-use alloc::string::ToString;
 use core::{ops::Deref, str::from_utf8};
 
 use emstr::{helpers::Fractional, EncodeStr};
-use prost::{
-    bytes::{BufMut, BytesMut},
-    Message,
-};
 
-use mc_core::account::{RingCtAddress, ShortAddressHash};
+use mc_core::account::{PublicSubaddress, RingCtAddress, ShortAddressHash};
+use mc_crypto_keys::RistrettoPublic;
 
 use crate::engine::{Error, TokenId};
 
 mod schnorrkel;
-pub use schnorrkel::sign_authority;
+pub use schnorrkel::{sign_address, sign_authority, ADDRESS_CONTEXT};
 
-// Include generated protobuf types
-include!(concat!(env!("OUT_DIR"), "/mob.rs"));
+mod wipe;
+pub(crate) use wipe::{zeroize_option, zeroize_vec};
 
 /// Per-token information
 struct TokenInfo {
     pub id: u64,
-    pub scalar: i64,
+    /// Decimal places for display
+    pub decimals: u32,
+    /// Output values below this are considered dust for display
+    pub dust: u64,
+    /// Totals at or above this require additional confirmation to approve
+    pub high_value: u64,
 }
 
 /// Token information for rendering / display
 const TOKENS: &[TokenInfo] = &[
     TokenInfo {
         id: 0,
-        scalar: SCALAR_MOB,
+        decimals: 12,
+        dust: 1_000_000,
+        high_value: 1_000 * 1_000_000_000_000,
     },
     TokenInfo {
         id: 1,
-        scalar: 1_000_000,
+        decimals: 6,
+        dust: 1,
+        high_value: 1_000 * 1_000_000,
     },
 ];
 
-const SCALAR_MOB: i64 = 1_000_000_000_000;
 const MOB_MAX_SF: usize = 14;
 
+/// Maximum decimal places supported for display (10^18 fits in an i64)
+pub const DECIMALS_MAX: u32 = 18;
+
 fn get_token_info(token_id: TokenId) -> Option<&'static TokenInfo> {
     TOKENS.iter().find(|&t| t.id == *token_id.deref())
 }
 
-// Format helper for values and token types
+/// Check whether an output value is dust (zero or below the
+/// per-token dust threshold) and may be folded for display
+pub fn is_dust(value: u64, token_id: TokenId) -> bool {
+    let dust = get_token_info(token_id).map(|v| v.dust).unwrap_or(1);
+    value < dust
+}
+
+/// Check whether a total value (in either direction) meets the per-token
+/// high-value threshold, requiring additional confirmation to approve
+/// (unknown tokens have no threshold)
+pub fn is_high_value(value: i64, token_id: TokenId) -> bool {
+    match get_token_info(token_id) {
+        Some(t) => value.unsigned_abs() >= t.high_value,
+        None => false,
+    }
+}
+
+/// Fetch decimal places for a token from the registry
+/// (unknown tokens are displayed as integer values)
+pub fn token_decimals(token_id: TokenId) -> u32 {
+    get_token_info(token_id).map(|v| v.decimals).unwrap_or(0)
+}
+
+// Format helper for values and token types, using registry decimals
 pub fn fmt_token_val(value: i64, token_id: TokenId, buff: &mut [u8]) -> &str {
-    // Match token types
-    let scalar = get_token_info(token_id).map(|v| v.scalar).unwrap_or(1);
+    fmt_token_val_decimals(value, token_decimals(token_id), token_id, buff)
+}
+
+// Format helper for values with the provided decimal places
+// (clamped to [DECIMALS_MAX]) and token types
+pub fn fmt_token_val_decimals(
+    value: i64,
+    decimals: u32,
+    token_id: TokenId,
+    buff: &mut [u8],
+) -> &str {
+    // Compute scalar from decimals
+    let scalar = 10i64.pow(decimals.min(DECIMALS_MAX));
 
     // Compute and write value using scalar
     let mut n = match emstr::write!(&mut buff[..], Fractional::<i64>::new(value, scalar)) {
@@ -123,48 +164,138 @@ pub(crate) fn digest_public_address(
     ShortAddressHash::from(hash)
 }
 
-/// Helper to b58 encode [PublicAddress] equivalent types without
+/// Maximum length of an encoded printable wrapper (including the 4-byte
+/// checksum) prior to b58 encoding
+pub const B58_ADDRESS_PROTO_MAX: usize = 256;
+
+/// Helper to b58 encode `PublicAddress` equivalent types without
 /// pulling in no-std incompatible `mc_api` dependency.
+///
+/// The `PrintableWrapper { PublicAddress }` protobuf is encoded manually to a
+/// stack buffer (see `printable.proto` in `mc-api`), avoiding heap allocation.
 #[cfg_attr(feature = "noinline", inline(never))]
 pub fn b58_encode_public_address<const N: usize>(
     subaddress: impl RingCtAddress,
     fog_report_url: &str,
     fog_authority_sig: &[u8],
 ) -> Result<heapless::String<N>, Error> {
-    use printable_wrapper::*;
-
-    let view_public = subaddress.view_public_key();
-    let spend_public = subaddress.spend_public_key();
-
-    // Build printable protobuf wrapper
-    // NOTE: this uses prost / is heavily alloc'd under the hood
-    let p = PrintableWrapper {
-        wrapper: Some(Wrapper::PublicAddress(PublicAddress {
-            view_public_key: Some(CompressedRistretto {
-                data: view_public.to_bytes().to_vec(),
-            }),
-            spend_public_key: Some(CompressedRistretto {
-                data: spend_public.to_bytes().to_vec(),
-            }),
-            fog_report_url: fog_report_url.to_string(),
-            fog_report_id: "".to_string(),
-            fog_authority_sig: fog_authority_sig.to_vec(),
-        })),
-    };
+    let address_len = public_address_len(fog_report_url, fog_authority_sig);
+
+    // Check the encoded wrapper fits in our buffer
+    if 4 + proto_field_len(address_len) > B58_ADDRESS_PROTO_MAX {
+        return Err(Error::EncodingFailed);
+    }
+
+    // Encode printable wrapper following space for the checksum
+    let mut data = [0u8; B58_ADDRESS_PROTO_MAX];
+    let mut w = ProtoWriter::new(&mut data[4..]);
+
+    // PrintableWrapper.public_address
+    w.header(1, address_len)?;
+    write_public_address(&mut w, subaddress, fog_report_url, fog_authority_sig)?;
+
+    let n = 4 + w.index;
 
-    // Encode to temporary byte buffer
-    // TODO: prefer not to use alloc here but, BufMut seems to be broken for
-    // const generic types?!
-    let mut data = BytesMut::with_capacity(p.encoded_len() + 4);
-    // Pre-allocate space for checksum
-    data.put_bytes(0, 4);
-    // Encode proto to buffer
-    p.encode(&mut data).unwrap();
+    // Encode address to b58
+    let mut buff = HeaplessEncodeTarget::<N>(heapless::String::new());
+    b58_encode_wrapper(&mut data[..n], &mut buff)?;
+
+    Ok(buff.0)
+}
 
-    // Force drop `p` to free up heap memory
-    drop(p);
+/// URI prefix for b58 encoded payment requests
+pub const PAYMENT_URI_PREFIX: &str = "mob:///b58/";
 
-    // Compute checksum for encoded address
+/// Helper to build a `mob:///b58/` payment request URI (for QR display etc.)
+/// from `PublicAddress` equivalent types, with the requested value and memo.
+///
+/// As with [b58_encode_public_address] the `PrintableWrapper { PaymentRequest }`
+/// protobuf is encoded manually to a stack buffer.
+#[cfg_attr(feature = "noinline", inline(never))]
+pub fn payment_request_uri<const N: usize>(
+    subaddress: impl RingCtAddress,
+    fog_report_url: &str,
+    fog_authority_sig: &[u8],
+    value: u64,
+    token_id: TokenId,
+    memo: &str,
+) -> Result<heapless::String<N>, Error> {
+    // Compute nested message lengths, zero values and empty strings are omitted
+    let address_len = public_address_len(fog_report_url, fog_authority_sig);
+    let request_len = proto_field_len(address_len)
+        + proto_varint_field_len(value)
+        + proto_field_len(memo.len())
+        + proto_varint_field_len(*token_id);
+
+    // Check the encoded wrapper fits in our buffer
+    if 4 + proto_field_len(request_len) > B58_ADDRESS_PROTO_MAX {
+        return Err(Error::EncodingFailed);
+    }
+
+    // Encode printable wrapper following space for the checksum
+    let mut data = [0u8; B58_ADDRESS_PROTO_MAX];
+    let mut w = ProtoWriter::new(&mut data[4..]);
+
+    // PrintableWrapper.payment_request
+    w.header(2, request_len)?;
+    // PaymentRequest.public_address
+    w.header(1, address_len)?;
+    write_public_address(&mut w, subaddress, fog_report_url, fog_authority_sig)?;
+    // PaymentRequest.value
+    w.varint(2, value)?;
+    // PaymentRequest.memo
+    w.bytes(3, memo.as_bytes())?;
+    // PaymentRequest.token_id
+    w.varint(4, *token_id)?;
+
+    let n = 4 + w.index;
+
+    // Write URI prefix then b58 encoded request
+    let mut buff = HeaplessEncodeTarget::<N>(heapless::String::new());
+    buff.0
+        .push_str(PAYMENT_URI_PREFIX)
+        .map_err(|_| Error::EncodingFailed)?;
+    b58_encode_wrapper(&mut data[..n], &mut buff)?;
+
+    Ok(buff.0)
+}
+
+/// Compute the encoded length of a `PublicAddress` message, empty
+/// `fog_report_url`, `fog_report_id` and `fog_authority_sig` fields are omitted
+fn public_address_len(fog_report_url: &str, fog_authority_sig: &[u8]) -> usize {
+    proto_field_len(proto_field_len(32)) * 2
+        + proto_field_len(fog_report_url.len())
+        + proto_field_len(fog_authority_sig.len())
+}
+
+/// Write `PublicAddress` message fields (following the message header)
+fn write_public_address(
+    w: &mut ProtoWriter,
+    subaddress: impl RingCtAddress,
+    fog_report_url: &str,
+    fog_authority_sig: &[u8],
+) -> Result<(), Error> {
+    let key_len = proto_field_len(32);
+
+    // PublicAddress.view_public_key
+    w.header(1, key_len)?;
+    w.bytes(1, &subaddress.view_public_key().to_bytes())?;
+    // PublicAddress.spend_public_key
+    w.header(2, key_len)?;
+    w.bytes(1, &subaddress.spend_public_key().to_bytes())?;
+    // PublicAddress.fog_report_url
+    w.bytes(3, fog_report_url.as_bytes())?;
+    // PublicAddress.fog_authority_sig
+    w.bytes(5, fog_authority_sig)
+}
+
+/// Write the checksum for an encoded printable wrapper (following
+/// 4 bytes reserved for the checksum) then b58 encode to `out`
+fn b58_encode_wrapper<const N: usize>(
+    data: &mut [u8],
+    out: &mut HeaplessEncodeTarget<N>,
+) -> Result<(), Error> {
+    // Compute checksum for encoded wrapper
     let checksum = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC)
         .checksum(&data[4..])
         .to_le_bytes();
@@ -172,14 +303,262 @@ pub fn b58_encode_public_address<const N: usize>(
     // Write checksum to start of buffer
     data[0..4].copy_from_slice(&checksum);
 
-    // Encode address to b58
-    let mut buff = HeaplessEncodeTarget::<N>(heapless::String::new());
-    let _n = bs58::encode(&data).into(&mut buff).unwrap();
+    // Encode to b58
+    bs58::encode(&data[..])
+        .into(out)
+        .map_err(|_| Error::EncodingFailed)?;
 
-    Ok(buff.0)
+    Ok(())
+}
+
+/// Maximum fog report URL length for decoded addresses
+pub const FOG_URL_MAX: usize = 128;
+
+/// Fog authority signature length
+pub const FOG_SIG_LEN: usize = 64;
+
+/// Public address decoded from a b58 printable wrapper,
+/// see [b58_decode_public_address]
+#[derive(Clone, PartialEq, Debug)]
+pub struct B58Address {
+    /// Subaddress public keys
+    pub subaddress: PublicSubaddress,
+    /// Fog report URL (empty where fog is not in use)
+    pub fog_report_url: heapless::String<FOG_URL_MAX>,
+    /// Fog authority signature (empty where fog is not in use)
+    pub fog_authority_sig: heapless::Vec<u8, FOG_SIG_LEN>,
+}
+
+/// Helper to decode b58 `PublicAddress` printable wrappers, inverse of
+/// [b58_encode_public_address].
+///
+/// Returns [Error::InvalidAddress] for malformed or non-address wrappers,
+/// or [Error::InvalidKey] where public keys can not be decompressed.
+#[cfg_attr(feature = "noinline", inline(never))]
+pub fn b58_decode_public_address(s: &str) -> Result<B58Address, Error> {
+    // Decode b58 to temporary buffer
+    let mut data = [0u8; B58_ADDRESS_PROTO_MAX];
+    let n = bs58::decode(s)
+        .into(&mut data[..])
+        .map_err(|_| Error::InvalidAddress)?;
+
+    // Check checksum over encoded wrapper
+    if n < 4 {
+        return Err(Error::InvalidAddress);
+    }
+    let checksum = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC)
+        .checksum(&data[4..n])
+        .to_le_bytes();
+    if checksum != data[0..4] {
+        return Err(Error::InvalidAddress);
+    }
+
+    // Fetch PrintableWrapper.public_address, other wrapper kinds are not supported
+    let mut r = ProtoReader::new(&data[4..n]);
+    let address = match r.next()? {
+        Some((1, v)) => v,
+        _ => return Err(Error::InvalidAddress),
+    };
+
+    // Parse PublicAddress fields
+    let (mut view_public, mut spend_public) = (None, None);
+    let mut fog_report_url = heapless::String::new();
+    let mut fog_authority_sig = heapless::Vec::new();
+
+    let mut r = ProtoReader::new(address);
+    while let Some((field, v)) = r.next()? {
+        match field {
+            1 => view_public = Some(decode_proto_key(v)?),
+            2 => spend_public = Some(decode_proto_key(v)?),
+            3 => {
+                let url = from_utf8(v).map_err(|_| Error::InvalidAddress)?;
+                fog_report_url.clear();
+                fog_report_url
+                    .push_str(url)
+                    .map_err(|_| Error::InvalidAddress)?;
+            }
+            5 => {
+                fog_authority_sig =
+                    heapless::Vec::from_slice(v).map_err(|_| Error::InvalidAddress)?;
+            }
+            // Ignore fog_report_id and unknown fields
+            _ => (),
+        }
+    }
+
+    match (view_public, spend_public) {
+        (Some(view_public), Some(spend_public)) => Ok(B58Address {
+            subaddress: PublicSubaddress {
+                view_public: view_public.into(),
+                spend_public: spend_public.into(),
+            },
+            fog_report_url,
+            fog_authority_sig,
+        }),
+        _ => Err(Error::InvalidAddress),
+    }
 }
 
-/// Helper to support bs58 encoding to [heapless::String] types
+/// Decode a `CompressedRistretto` protobuf message to a public key
+fn decode_proto_key(buff: &[u8]) -> Result<RistrettoPublic, Error> {
+    let mut r = ProtoReader::new(buff);
+
+    match r.next()? {
+        Some((1, v)) => RistrettoPublic::try_from(v).map_err(|_| Error::InvalidKey),
+        _ => Err(Error::InvalidAddress),
+    }
+}
+
+/// Compute the length of a varint-encoded value
+const fn varint_len(mut v: u64) -> usize {
+    let mut n = 1;
+    while v >= 0x80 {
+        v >>= 7;
+        n += 1;
+    }
+    n
+}
+
+/// Compute the encoded length of a length-delimited protobuf field
+/// (omitted when empty)
+const fn proto_field_len(len: usize) -> usize {
+    match len {
+        0 => 0,
+        _ => 1 + varint_len(len as u64) + len,
+    }
+}
+
+/// Compute the encoded length of a varint protobuf field (omitted when zero)
+const fn proto_varint_field_len(v: u64) -> usize {
+    match v {
+        0 => 0,
+        _ => 1 + varint_len(v),
+    }
+}
+
+/// Minimal writer for length-delimited protobuf fields
+struct ProtoWriter<'a> {
+    buff: &'a mut [u8],
+    index: usize,
+}
+
+impl<'a> ProtoWriter<'a> {
+    fn new(buff: &'a mut [u8]) -> Self {
+        Self { buff, index: 0 }
+    }
+
+    /// Write a single byte
+    fn put(&mut self, b: u8) -> Result<(), Error> {
+        let v = self.buff.get_mut(self.index).ok_or(Error::EncodingFailed)?;
+        *v = b;
+        self.index += 1;
+        Ok(())
+    }
+
+    /// Write a raw varint value
+    fn put_varint(&mut self, mut v: u64) -> Result<(), Error> {
+        while v >= 0x80 {
+            self.put(v as u8 | 0x80)?;
+            v >>= 7;
+        }
+        self.put(v as u8)
+    }
+
+    /// Write a length-delimited field header (tag and length)
+    fn header(&mut self, field: u8, len: usize) -> Result<(), Error> {
+        self.put((field << 3) | 2)?;
+        self.put_varint(len as u64)
+    }
+
+    /// Write a varint field, omitting zero values per proto3
+    fn varint(&mut self, field: u8, v: u64) -> Result<(), Error> {
+        if v == 0 {
+            return Ok(());
+        }
+
+        self.put(field << 3)?;
+        self.put_varint(v)
+    }
+
+    /// Write a bytes / string field, omitting empty values per proto3
+    fn bytes(&mut self, field: u8, data: &[u8]) -> Result<(), Error> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        self.header(field, data.len())?;
+
+        let b = self
+            .buff
+            .get_mut(self.index..self.index + data.len())
+            .ok_or(Error::EncodingFailed)?;
+        b.copy_from_slice(data);
+        self.index += data.len();
+
+        Ok(())
+    }
+}
+
+/// Minimal reader for length-delimited protobuf fields
+struct ProtoReader<'a> {
+    buff: &'a [u8],
+    index: usize,
+}
+
+impl<'a> ProtoReader<'a> {
+    fn new(buff: &'a [u8]) -> Self {
+        Self { buff, index: 0 }
+    }
+
+    /// Read a varint value
+    fn varint(&mut self) -> Result<u64, Error> {
+        let mut v = 0u64;
+
+        for i in 0..10 {
+            let b = *self.buff.get(self.index).ok_or(Error::InvalidAddress)?;
+            self.index += 1;
+
+            v |= ((b & 0x7f) as u64) << (i * 7);
+            if b & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+
+        Err(Error::InvalidAddress)
+    }
+
+    /// Read the next length-delimited field, skipping varint fields
+    fn next(&mut self) -> Result<Option<(u64, &'a [u8])>, Error> {
+        while self.index < self.buff.len() {
+            let tag = self.varint()?;
+
+            match tag & 0x07 {
+                // Varint, skip value
+                0 => {
+                    self.varint()?;
+                }
+                // Length-delimited, return value
+                2 => {
+                    let len = self.varint()? as usize;
+                    let v = self
+                        .buff
+                        .get(self.index..self.index.saturating_add(len))
+                        .ok_or(Error::InvalidAddress)?;
+                    self.index += len;
+
+                    return Ok(Some((tag >> 3, v)));
+                }
+                // Other wire types are not used by address messages
+                _ => return Err(Error::InvalidAddress),
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Helper to support bs58 encoding to [heapless::String] types,
+/// appending to any existing content
 struct HeaplessEncodeTarget<const N: usize>(heapless::String<N>);
 
 impl<const N: usize> bs58::encode::EncodeTarget for HeaplessEncodeTarget<N> {
@@ -188,13 +567,14 @@ impl<const N: usize> bs58::encode::EncodeTarget for HeaplessEncodeTarget<N> {
         max_len: usize,
         f: impl for<'a> FnOnce(&'a mut [u8]) -> bs58::encode::Result<usize>,
     ) -> bs58::encode::Result<usize> {
-        // Fetch vec and resize
+        // Fetch vec and resize, appending to any existing content
         let v = unsafe { self.0.as_mut_vec() };
-        v.resize_default(max_len)
+        let start = v.len();
+        v.resize_default(start + max_len)
             .map_err(|_| bs58::encode::Error::BufferTooSmall)?;
 
         // Encode into resized vec
-        let n = match f(&mut v[..]) {
+        let n = match f(&mut v[start..]) {
             Ok(n) => n,
             Err(e) => {
                 // On encoding failure clear vec to avoid returning
@@ -205,7 +585,7 @@ impl<const N: usize> bs58::encode::EncodeTarget for HeaplessEncodeTarget<N> {
         };
 
         // Truncate down to encoded len
-        v.truncate(n);
+        v.truncate(start + n);
 
         Ok(n)
     }
@@ -222,6 +602,47 @@ mod test {
 
     pub(crate) const MAX_LINE_LEN: usize = 20;
 
+    #[test]
+    fn dust_values() {
+        let tests = &[
+            (0, TokenId::MOB, true),
+            (999_999, TokenId::MOB, true),
+            (1_000_000, TokenId::MOB, false),
+            (0, TokenId::from(1), true),
+            (1, TokenId::from(1), false),
+            (0, TokenId::from(7), true),
+            (1, TokenId::from(7), false),
+        ];
+
+        for (v, t, d) in tests {
+            assert_eq!(is_dust(*v, *t), *d, "dust mismatch for {v} ({t})");
+        }
+    }
+
+    #[test]
+    fn high_values() {
+        let tests = &[
+            (0, TokenId::MOB, false),
+            (999 * SCALAR_MOB, TokenId::MOB, false),
+            (1_000 * SCALAR_MOB, TokenId::MOB, true),
+            (-1_000 * SCALAR_MOB, TokenId::MOB, true),
+            (999_999_999, TokenId::from(1), false),
+            (-1_000_000_000, TokenId::from(1), true),
+            (i64::MAX, TokenId::from(7), false),
+            (i64::MIN, TokenId::from(7), false),
+        ];
+
+        for (v, t, h) in tests {
+            assert_eq!(
+                is_high_value(*v, *t),
+                *h,
+                "high value mismatch for {v} ({t})"
+            );
+        }
+    }
+
+    const SCALAR_MOB: i64 = 1_000_000_000_000;
+
     #[test]
     fn fmt_mob() {
         let tests = &[
@@ -254,6 +675,41 @@ mod test {
         }
     }
 
+    #[test]
+    fn fmt_decimals() {
+        let tests: &[(i64, u32, u64, &str)] = &[
+            (1, 6, 1, "0.000001 eUSD"),
+            (-1_500_000, 6, 1, "-1.5 eUSD"),
+            (42, 0, 7, "42 (7)"),
+            (-42, 0, 7, "-42 (7)"),
+            (12_345, 2, 7, "123.45 (7)"),
+            (-12_345, 3, 7, "-12.345 (7)"),
+            (1, 18, 7, "0.000000000000.. (7)"),
+            (-1, 18, 7, "-0.00000000000.. (7)"),
+            (1, 24, 7, "0.000000000000.. (7)"),
+            (123_456_789_012_345, 4, 7, "12345678901.23.. (7)"),
+        ];
+
+        for (v, d, t, s) in tests {
+            let mut buff = [0u8; 32];
+
+            let e = fmt_token_val_decimals(*v, *d, TokenId::from(*t), &mut buff);
+            assert_eq!(&e, s, "format mismatch for {v} ({d} decimals)");
+        }
+
+        // Registry decimals match explicit decimals
+        for (t, d) in [(0, 12), (1, 6), (7, 0)] {
+            let t = TokenId::from(t);
+            assert_eq!(token_decimals(t), d);
+
+            let (mut b1, mut b2) = ([0u8; 32], [0u8; 32]);
+            assert_eq!(
+                fmt_token_val(123_456_789, t, &mut b1),
+                fmt_token_val_decimals(123_456_789, d, t, &mut b2)
+            );
+        }
+    }
+
     const FOGS: &[FogId] = &[
         FogId::MobMain,
         FogId::MobTest,
@@ -347,4 +803,117 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn b58_address_decode() {
+        // Check addresses without fog, then with each fog
+        for i in 0..=FOGS.len() {
+            let a = match i {
+                0 => AccountKey::random(&mut OsRng {}),
+                _ => {
+                    let f = &FOGS[i - 1];
+                    AccountKey::random(&mut OsRng {}).with_fog(f.url(), "", f.spki())
+                }
+            };
+            let p = a.default_subaddress();
+
+            // API standard b58 encoding
+            let mut wrapper = mc_api::printable::PrintableWrapper::new();
+            wrapper.set_public_address((&p).into());
+            let s = wrapper.b58_encode().unwrap();
+
+            // Local b58 decoding
+            let d = b58_decode_public_address(&s).unwrap();
+
+            assert_eq!(d.subaddress.view_public_key(), p.view_public_key());
+            assert_eq!(d.subaddress.spend_public_key(), p.spend_public_key());
+            assert_eq!(d.fog_report_url.as_str(), p.fog_report_url().unwrap_or(""));
+            assert_eq!(
+                &d.fog_authority_sig[..],
+                p.fog_authority_sig().unwrap_or(&[])
+            );
+
+            // Round-trip via local encoding
+            let s1 = b58_encode_public_address::<B58_MAX_LEN>(
+                &d.subaddress,
+                &d.fog_report_url,
+                &d.fog_authority_sig,
+            )
+            .unwrap();
+            assert_eq!(s1.as_str(), s.as_str());
+        }
+    }
+
+    #[test]
+    fn b58_address_decode_invalid() {
+        let p = AccountKey::random(&mut OsRng {}).default_subaddress();
+        let s = b58_encode_public_address::<B58_MAX_LEN>(&p, "", &[]).unwrap();
+
+        // Corrupted payloads fail checksum validation
+        let mut data = [0u8; B58_ADDRESS_PROTO_MAX];
+        let n = bs58::decode(s.as_str()).into(&mut data[..]).unwrap();
+        data[10] ^= 0x01;
+
+        let mut s = HeaplessEncodeTarget::<B58_MAX_LEN>(heapless::String::new());
+        bs58::encode(&data[..n]).into(&mut s).unwrap();
+        assert_eq!(b58_decode_public_address(&s.0), Err(Error::InvalidAddress));
+
+        // As do invalid b58 strings
+        assert_eq!(
+            b58_decode_public_address("0OIl"),
+            Err(Error::InvalidAddress)
+        );
+        assert_eq!(b58_decode_public_address(""), Err(Error::InvalidAddress));
+    }
+
+    #[test]
+    fn payment_request_uri_encode() {
+        let tests: &[(u64, u64, &str)] = &[
+            (0, 0, ""),
+            (1_000_000_000_000, 0, "coffee"),
+            (5_000_000, 1, "invoice #1234"),
+            (u64::MAX, 7, ""),
+        ];
+
+        for (i, (value, token_id, memo)) in tests.iter().enumerate() {
+            let a = match FOGS.get(i) {
+                Some(f) => AccountKey::random(&mut OsRng {}).with_fog(f.url(), "", f.spki()),
+                None => AccountKey::random(&mut OsRng {}),
+            };
+            let p = a.default_subaddress();
+
+            // Local URI encoding
+            let s1 = payment_request_uri::<B58_MAX_LEN>(
+                &p,
+                p.fog_report_url().unwrap_or(""),
+                p.fog_authority_sig().unwrap_or(&[]),
+                *value,
+                TokenId::from(*token_id),
+                memo,
+            )
+            .unwrap();
+
+            // API standard b58 encoding
+            let mut req = mc_api::printable::PaymentRequest::new();
+            req.set_public_address((&p).into());
+            req.set_value(*value);
+            req.set_memo(alloc::string::String::from(*memo));
+            req.set_token_id(*token_id);
+
+            let mut wrapper = mc_api::printable::PrintableWrapper::new();
+            wrapper.set_payment_request(req);
+            let s2 = wrapper.b58_encode().unwrap();
+
+            assert_eq!(s1.strip_prefix(PAYMENT_URI_PREFIX), Some(s2.as_str()));
+        }
+    }
+
+    #[test]
+    fn b58_address_too_long() {
+        let a = AccountKey::random(&mut OsRng {});
+        let url = "fog://".repeat(B58_ADDRESS_PROTO_MAX / 6);
+
+        let r = b58_encode_public_address::<B58_MAX_LEN>(&a.default_subaddress(), &url, &[]);
+        assert_eq!(r, Err(Error::EncodingFailed));
+    }
 }
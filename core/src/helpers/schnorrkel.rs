@@ -4,7 +4,7 @@
 //! traits, with modifications to reduce stack use for execution on the ledger.
 //!
 
-use mc_core::keys::SubaddressViewPrivate;
+use mc_core::keys::{SubaddressSpendPrivate, SubaddressViewPrivate};
 use mc_crypto_digestible::MerlinTranscript;
 use mc_crypto_keys::{RistrettoPrivate, RistrettoSignature};
 
@@ -99,6 +99,16 @@ pub fn sign_authority(
     schnorrkel_sign(private_key.as_ref(), CONTEXT, spki_bytes)
 }
 
+/// Address verification signing context byte string
+pub const ADDRESS_CONTEXT: &[u8] = b"Address verification";
+
+/// Sign an address verification message using the subaddress spend private key,
+/// allowing hosts to check the displayed address matches the device derivation
+#[inline(never)]
+pub fn sign_address(private_key: &SubaddressSpendPrivate, message: &[u8]) -> RistrettoSignature {
+    schnorrkel_sign(private_key.as_ref(), ADDRESS_CONTEXT, message)
+}
+
 #[cfg(test)]
 mod test {
     use mc_account_keys::{AccountKey, DEFAULT_SUBADDRESS_INDEX};
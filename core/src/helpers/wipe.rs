@@ -0,0 +1,58 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Zeroization helpers for containers and upstream types that do not
+//! provide [Zeroize] implementations.
+
+use core::mem::size_of;
+
+use zeroize::Zeroize;
+
+/// Zeroize and clear a [heapless::Vec], wiping contents prior to
+/// truncation so stale values do not persist beyond the new length
+pub(crate) fn zeroize_vec<T: Zeroize, const N: usize>(v: &mut heapless::Vec<T, N>) {
+    v.iter_mut().for_each(Zeroize::zeroize);
+    v.clear();
+}
+
+/// Drop an optional value then zeroize the backing memory, for types
+/// holding private material without [Zeroize] implementations
+/// (or where the value has already been moved out via [Option::take])
+pub(crate) fn zeroize_option<T>(v: &mut Option<T>) {
+    // Drop any existing value, leaving a valid `None`
+    *v = None;
+
+    // SAFETY: `v` is exclusively borrowed and not read between the
+    // byte-wise wipe and the subsequent write restoring a valid `None`
+    unsafe {
+        let p = v as *mut Option<T> as *mut u8;
+        core::slice::from_raw_parts_mut(p, size_of::<Option<T>>()).zeroize();
+        core::ptr::write(v, None);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn wipe_vec() {
+        let mut v = heapless::Vec::<u8, 8>::from_slice(&[0xaa; 8]).unwrap();
+        zeroize_vec(&mut v);
+        assert!(v.is_empty());
+
+        // Wiped contents must not be visible on re-extension
+        unsafe { v.set_len(8) };
+        assert_eq!(v.as_slice(), &[0u8; 8]);
+    }
+
+    #[test]
+    fn wipe_option() {
+        let mut v = Some([0xaau8; 32]);
+        zeroize_option(&mut v);
+        assert_eq!(v, None);
+
+        let mut v: Option<[u8; 32]> = None;
+        zeroize_option(&mut v);
+        assert_eq!(v, None);
+    }
+}
@@ -14,6 +14,13 @@
 //! [`AppInfoReq`][ledger_mob_apdu::app_info::AppInfoReq] to fetch an
 //! [`AppInfoResp`][ledger_mob_apdu::app_info::AppInfoResp] containing application information
 //! including the applet version, protocol version, and flags for available features.
+//! Newer firmware also reports [`AppBuildInfo`][ledger_mob_apdu::app_info::AppBuildInfo]
+//! with the SDK target, git hash, enabled features and maximum ring size for support purposes.
+//!
+//! The client should then negotiate a protocol version via
+//! [`CapabilitiesReq`][ledger_mob_apdu::capabilities::CapabilitiesReq] and pin this with a
+//! [`SetProtocolVersionReq`][ledger_mob_apdu::capabilities::SetProtocolVersionReq],
+//! transactions are rejected until a supported protocol version has been pinned.
 //!
 //! ### Requesting wallet / subaddress keys
 //!
@@ -25,11 +32,34 @@
 //! APDU, returning a [`WalletKeyResp`][ledger_mob_apdu::subaddress_keys::SubaddressKeyResp] containing
 //! the subaddress spend public key and view private key for a given account index.
 //!
+//! Attested variants ([`WalletKeyAttestReq`][ledger_mob_apdu::wallet_keys::WalletKeyAttestReq],
+//! [`SubaddressKeyAttestReq`][ledger_mob_apdu::subaddress_keys::SubaddressKeyAttestReq])
+//! include a host challenge, with the response carrying a signature by the device attestation
+//! key over the challenge and returned keys (see [ledger_mob_apdu::attestation]).
+//!
+//! Path variants ([`WalletKeyPathReq`][ledger_mob_apdu::wallet_keys::WalletKeyPathReq],
+//! [`SubaddressKeyPathReq`][ledger_mob_apdu::subaddress_keys::SubaddressKeyPathReq])
+//! carry an explicit hardened derivation path of up to five levels in place of the account index,
+//! for custody integrations mandating specific path schemes (see [ledger_mob_apdu::key_path]).
+//! Paths must extend the application `m/44'/866'` prefix, derived accounts are not cached.
+//!
+//! [`SubaddressKeyFogReq`][ledger_mob_apdu::subaddress_keys::SubaddressKeyFogReq] additionally
+//! returns the fog id and fog authority signature for the subaddress, allowing hosts to construct
+//! fog-enabled public addresses without a further request or local fog signing.
+//!
 //! ### Key Image Scanning
 //!
 //! Key images can be recovered via [`KeyImageReq`][ledger_mob_apdu::key_image::KeyImageReq] request,
 //! returning a [`KeyImageResp`][ledger_mob_apdu::key_image::KeyImageResp] APDU containing the computed
-//! key image.
+//! key image and the subaddress spend public key derived by the device.
+//!
+//! ### Device presence
+//!
+//! Hosts may prove device presence by fetching a single-use nonce via
+//! [`DeviceNonceReq`][ledger_mob_apdu::device_nonce::DeviceNonceReq], then issuing a
+//! [`SessionChallengeReq`][ledger_mob_apdu::device_nonce::SessionChallengeReq] with a host challenge,
+//! returning an [`IdentResp`][ledger_mob_apdu::ident::IdentResp] signed by the device identity key
+//! over the nonce and challenge (see [ledger_mob_apdu::device_nonce]).
 //!
 //!
 //! ### Executing a transaction
@@ -51,13 +81,18 @@
 //! discarded if a mismatch is detected.
 //!
 //!
-//! 1. Issue [`TxInit`][ledger_mob_apdu::tx::TxInit] with transaction options to start a transaction operation
+//! 1. Issue [`TxInit`][ledger_mob_apdu::tx::TxInit] with transaction options to start a transaction operation,
+//!    optionally including the [`TxExpected`][ledger_mob_apdu::tx::TxExpected] fee and tombstone block
+//!    to be checked against the summary
 //! 2. Generate and sign memos
 //!     1. Issue [`TxMemoSign`][ledger_mob_apdu::tx::TxMemoSign] to fetch a [`TxMemoSig`][ledger_mob_apdu::tx::TxMemoSig]
 //!        APDU containing a signature for the provided memo
+//!     2. Payloads over 48 bytes are streamed using [`TxMemoFlags`][ledger_mob_apdu::tx::TxMemoFlags],
+//!        with [`TxInfo`][ledger_mob_apdu::tx::TxInfo] returned for intermediate chunks
 //! 3. Build transaction summary to generate message for signing (see: [MCIP#52](https://github.com/mobilecoinfoundation/mcips/pull/52))
 //!     1. Issue [`TxSummaryInit`][ledger_mob_apdu::tx::TxSummaryInit] to start summary generation
 //!     2. Add N outputs and unblinding information using [`TxSummaryAddTxOut`][ledger_mob_apdu::tx::TxSummaryAddTxOut] followed by [`TxSummaryAddTxOutUnblinding`][ledger_mob_apdu::tx::TxSummaryAddTxOutUnblinding]
+//!        (or compact encodings where [`Features::COMPACT_SUMMARY`][ledger_mob_apdu::capabilities::Features::COMPACT_SUMMARY] is supported)
 //!     3. Add M inputs via [`TxSummaryAddTxIn`][ledger_mob_apdu::tx::TxSummaryAddTxIn]
 //!     4. Issue [`TxSummaryBuild`][ledger_mob_apdu::tx::TxSummaryBuild] to build summary message
 //! 4. Sign N rings
@@ -70,8 +105,21 @@
 //!        APDU containing the key image and zeroth challenge for the signed ring
 //!     6. Issue [`TxGetResponse`][ledger_mob_apdu::tx::TxGetResponse] to fetch [`TxResponse`][ledger_mob_apdu::tx::TxResponse]
 //!        APDU containing the response scalar for each ring entry
-//! 5. Issue [`TxComplete`][ledger_mob_apdu::tx::TxComplete] to complete transaction
+//! 5. Issue [`TxComplete`][ledger_mob_apdu::tx::TxComplete] to complete transaction, returning a
+//!    [`TxCompleteResp`][ledger_mob_apdu::tx::TxCompleteResp] containing a digest over the signed transaction
+//!
+//! In-progress transactions may be cancelled at any step by issuing
+//! [`TxAbort`][ledger_mob_apdu::tx::TxAbort], clearing signing contexts
+//! and returning the final digest.
+//!
+//! Signed rings and the completion digest may be re-verified host-side
+//! using a [`TxVerifier`][verifier::TxVerifier].
 //!
+//! ### Host emulation
+//!
+//! With the `software` feature enabled a [`SoftwareDriver`][engine::SoftwareDriver]
+//! is provided, deriving keys from a BIP39 mnemonic so the [Engine][engine::Engine]
+//! may be executed on a host (for example, in CI without hardware or speculos).
 //!
 
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -85,4 +133,6 @@ pub mod engine;
 
 pub mod helpers;
 
+pub mod verifier;
+
 pub use mc_transaction_types::TokenId;
@@ -0,0 +1,188 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Host-side transaction verification, re-verifying ring signatures
+//! returned by the device against the extended message digest and
+//! computing the expected [SignedTxDigest] for comparison with the
+//! [`TxCompleteResp`][ledger_mob_apdu::tx::TxCompleteResp] digest.
+//!
+//! This allows callers to check device outputs without each reimplementing
+//! MLSAG verification.
+
+use ledger_mob_apdu::{ct::ct_eq, digest::SignedTxDigest};
+use mc_crypto_ring_signature::{
+    CompressedCommitment, CurveScalar, KeyImage, MlsagVerify, ReducedTxOut, Scalar,
+};
+
+use crate::engine::{Error, RING_SIZE};
+
+/// Verify a signed ring against the provided message (the extended message
+/// digest for transactions using summaries), returning [Error::SignError]
+/// if verification fails.
+pub fn verify_ring(
+    message: &[u8],
+    ring: &[ReducedTxOut],
+    output_commitment: &CompressedCommitment,
+    key_image: &KeyImage,
+    c_zero: &CurveScalar,
+    responses: &[CurveScalar],
+) -> Result<(), Error> {
+    // Check ring and response lengths
+    if ring.is_empty() || ring.len() > RING_SIZE || responses.len() != ring.len() * 2 {
+        return Err(Error::InvalidLength);
+    }
+
+    let verifier = MlsagVerify {
+        message,
+        c_zero,
+        responses,
+        key_image,
+        ring,
+        output_commitment,
+    };
+
+    let mut recomputed_c = [Scalar::default(); RING_SIZE];
+    verifier
+        .verify(&mut recomputed_c[..ring.len()])
+        .map_err(|_| Error::SignError)
+}
+
+/// Transaction verifier, checking each signed ring and accumulating
+/// the expected [SignedTxDigest] for the transaction
+pub struct TxVerifier {
+    message: [u8; 32],
+    digest: SignedTxDigest,
+}
+
+impl TxVerifier {
+    /// Create a new verifier for the provided message
+    /// (the extended message digest for transactions using summaries)
+    pub fn new(message: [u8; 32]) -> Self {
+        Self {
+            message,
+            digest: SignedTxDigest::new(),
+        }
+    }
+
+    /// Verify a signed ring, adding this to the transaction digest on success.
+    ///
+    /// Rings must be added in the order they were signed.
+    pub fn add_ring(
+        &mut self,
+        ring: &[ReducedTxOut],
+        output_commitment: &CompressedCommitment,
+        key_image: &KeyImage,
+        c_zero: &CurveScalar,
+        responses: &[CurveScalar],
+    ) -> Result<(), Error> {
+        verify_ring(
+            &self.message,
+            ring,
+            output_commitment,
+            key_image,
+            c_zero,
+            responses,
+        )?;
+
+        self.digest.add_ring(key_image, c_zero, responses);
+
+        Ok(())
+    }
+
+    /// Fetch the number of rings verified
+    pub fn num_rings(&self) -> usize {
+        self.digest.num_rings()
+    }
+
+    /// Finalise verification, returning the expected signed transaction digest
+    pub fn finalise(self) -> [u8; 32] {
+        self.digest.finalise(&self.message)
+    }
+
+    /// Finalise verification, checking the signed transaction digest
+    /// returned by the device on completion
+    pub fn check(self, signed_digest: &[u8; 32]) -> Result<(), Error> {
+        match ct_eq(&self.finalise(), signed_digest) {
+            true => Ok(()),
+            false => Err(Error::SignError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use mc_core::account::Account;
+    use mc_crypto_keys::RistrettoPrivate;
+    use mc_util_from_random::FromRandom;
+    use mc_util_test_helper::{RngType, SeedableRng};
+
+    use ledger_mob_tests::mlsag::RingMLSAGParameters;
+
+    use super::*;
+
+    #[test]
+    fn verify_rings() {
+        let mut rng: RngType = SeedableRng::from_seed([3u8; 32]);
+
+        let account = Account::new(
+            RistrettoPrivate::from_random(&mut rng).into(),
+            RistrettoPrivate::from_random(&mut rng).into(),
+        );
+
+        let params = RingMLSAGParameters::random(
+            &account,
+            RING_SIZE - 1,
+            Scalar::random(&mut rng),
+            &mut rng,
+        );
+        let signature = params.sign(&mut rng, true).unwrap();
+
+        let output_commitment = CompressedCommitment::new(
+            params.value,
+            params.pseudo_output_blinding,
+            &params.generator,
+        );
+
+        // Valid rings verify and are added to the digest
+        let mut v = TxVerifier::new(params.message);
+        v.add_ring(
+            &params.ring,
+            &output_commitment,
+            &signature.key_image,
+            &signature.c_zero,
+            &signature.responses,
+        )
+        .unwrap();
+        assert_eq!(v.num_rings(), 1);
+
+        let mut expected = SignedTxDigest::new();
+        expected.add_ring(
+            &signature.key_image,
+            &signature.c_zero,
+            &signature.responses,
+        );
+        assert_eq!(v.finalise(), expected.finalise(&params.message));
+
+        // Rings verified against the wrong message are rejected
+        let mut v = TxVerifier::new([0xaa; 32]);
+        let r = v.add_ring(
+            &params.ring,
+            &output_commitment,
+            &signature.key_image,
+            &signature.c_zero,
+            &signature.responses,
+        );
+        assert_eq!(r, Err(Error::SignError));
+        assert_eq!(v.num_rings(), 0);
+
+        // As are truncated responses
+        let r = verify_ring(
+            &params.message,
+            &params.ring,
+            &output_commitment,
+            &signature.key_image,
+            &signature.c_zero,
+            &signature.responses[1..],
+        );
+        assert_eq!(r, Err(Error::InvalidLength));
+    }
+}
@@ -0,0 +1,17 @@
+use ledger_mob_core::engine::{Engine, SoftwareDriver};
+
+mod helpers;
+use helpers::*;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn public_address() -> anyhow::Result<()> {
+    let _ = simplelog::SimpleLogger::init(log::LevelFilter::Debug, Default::default());
+
+    let e = TestEngine::new(Engine::new(SoftwareDriver::random()));
+
+    e.unlock();
+
+    ledger_mob_tests::address::test(e, 4).await.unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use ledger_mob_core::engine::{Engine, SoftwareDriver};
+
+mod helpers;
+use helpers::*;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn capabilities() -> anyhow::Result<()> {
+    let _ = simplelog::SimpleLogger::init(log::LevelFilter::Debug, Default::default());
+
+    let e = TestEngine::new(Engine::new(SoftwareDriver::random()));
+
+    ledger_mob_tests::capabilities::test(e).await.unwrap();
+
+    Ok(())
+}
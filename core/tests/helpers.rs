@@ -4,25 +4,128 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use async_trait::async_trait;
-use bip39::Seed;
-use ledger_proto::{ApduBase, ApduReq};
+use encdec::{Decode, Encode};
+use ledger_proto::{ApduBase, ApduReq, ApduStatic};
 use log::{debug, trace};
-
-use ledger_mob_core::engine::{Driver, Engine, Error, Event};
-use mc_core::slip10::Slip10Key;
+use rand::rngs::OsRng;
+
+use ledger_mob_core::{
+    apdu::{
+        batch::{BatchReq, BatchWriter},
+        chunk::{ChunkBuffer, ChunkGetReq, ChunkReq, ChunkResp, CHUNK_BUFF_LEN},
+        session::{DeviceSession, SessionInitReq, SessionReq},
+        ApduError,
+    },
+    engine::{Engine, Error, Event, SoftwareDriver},
+};
 
 pub const MNEMONIC: &str = "duck deal pretty pen thunder economy wide common goose fit engine main aisle curtain choose cube claim snake enroll detect brief history float unit";
 
 #[derive(Clone)]
 pub struct TestEngine {
-    pub engine: Arc<Mutex<Engine<TestDriver>>>,
+    pub engine: Arc<Mutex<Engine<SoftwareDriver>>>,
+    pub chunks: Arc<Mutex<ChunkBuffer>>,
+    pub session: Arc<Mutex<DeviceSession>>,
 }
 
 impl TestEngine {
-    pub fn new(engine: Engine<TestDriver>) -> Self {
+    pub fn new(engine: Engine<SoftwareDriver>) -> Self {
         Self {
             engine: Arc::new(Mutex::new(engine)),
+            chunks: Arc::new(Mutex::new(ChunkBuffer::new())),
+            session: Arc::new(Mutex::new(DeviceSession::new())),
+        }
+    }
+
+    /// Handle (possibly session wrapped or batched) requests, returning the encoded response length
+    fn handle(&self, ins: u8, data: &[u8], buff: &mut [u8]) -> usize {
+        // Handle batch entries as if issued directly
+        if ins == BatchReq::INS {
+            let (req, _) = BatchReq::decode(data).unwrap();
+
+            let mut w = BatchWriter::new(buff);
+            for e in req.entries() {
+                w.push_with(e.ins, |b| Ok(self.handle(e.ins, e.data, b)))
+                    .unwrap();
+            }
+
+            return w.finish().unwrap();
+        }
+
+        let mut engine = self.engine.lock().unwrap();
+
+        // Unwrap session requests and wrap responses
+        if ins == SessionReq::INS {
+            let mut session = self.session.lock().unwrap();
+
+            let (req, _) = SessionReq::decode(data).unwrap();
+            let (ins, data) = session.open(&req).unwrap();
+            let (evt, meta) = Event::parse_meta(ins, data).unwrap();
+
+            let r = engine.update_meta(meta, &evt).unwrap();
+
+            debug!("wrapped resp: {:?}", r);
+
+            let resp = session.seal(|b| r.encode(b)).unwrap();
+            return resp.encode(buff).unwrap();
+        }
+
+        // Decode APDU to event
+        let (evt, meta) = match Event::parse_meta(ins, data) {
+            Ok(v) => v,
+            Err(e) => {
+                panic!("Decode failed with {:?} for: {:02x?}", e, data);
+            }
+        };
+
+        // Handle event
+        let r = engine.update_meta(meta, &evt).unwrap();
+
+        debug!("resp: {:?}", r);
+
+        // Encode output to response APDU
+        r.encode(buff).unwrap()
+    }
+
+    /// Handle chunked request APDUs, returning the encoded response length
+    fn chunk(&self, ins: u8, buff: &mut [u8], n: usize) -> usize {
+        let mut chunks = self.chunks.lock().unwrap();
+
+        // Return response chunks
+        if ins == ChunkGetReq::INS {
+            let (req, _) = ChunkGetReq::decode(&buff[..n]).unwrap();
+            let r = chunks.get(req.offset).unwrap();
+            return r.encode(buff).unwrap();
         }
+
+        // Copy request chunk so buff can be reused for the response
+        let mut req_buff = [0u8; 256];
+        req_buff[..n].copy_from_slice(&buff[..n]);
+        let (req, _) = ChunkReq::decode(&req_buff[..n]).unwrap();
+
+        // Reassemble chunked request
+        let (ins, data) = match chunks.push(&req).unwrap() {
+            Some(v) => v,
+            None => {
+                let r = ChunkResp::new(chunks.received() as u16, 0, &[]);
+                return r.encode(buff).unwrap();
+            }
+        };
+
+        // Handle request
+        let mut resp = [0u8; CHUNK_BUFF_LEN];
+        let n = self.handle(ins, data, &mut resp);
+
+        debug!("chunked resp: {:02x?}", &resp[..n]);
+
+        // Copy response to chunk buffer and return the first chunk
+        chunks
+            .set_response(|b| {
+                b[..n].copy_from_slice(&resp[..n]);
+                Ok::<_, ApduError>(n)
+            })
+            .unwrap();
+        chunks.get(0).unwrap().encode(buff).unwrap()
     }
 
     pub fn unlock(&self) {
@@ -58,24 +161,39 @@ impl ledger_lib::Device for TestEngine {
 
         trace!("encoded: {:02x?}", &buff[..n]);
 
-        // Decode APDU to event
-        let evt = match Event::parse(h.ins, &buff[..n]) {
-            Ok(v) => v,
-            Err(e) => {
-                panic!("Decode failed with {:?} for: {:02x?}", e, &buff[..n]);
-            }
-        };
+        // Handle chunked requests
+        if h.ins == ChunkReq::INS || h.ins == ChunkGetReq::INS {
+            let n = self.chunk(h.ins, buff, n);
 
-        // Handle event
-        let mut engine = self.engine.lock().unwrap();
-        let r = engine.update(&evt).unwrap();
+            let (a, _) = RESP::decode(&buff[..n]).unwrap();
+            return Ok(a);
+        }
 
-        // Encode output to response APDU
-        let n = r.encode(buff).unwrap();
+        // Handle session handshakes
+        if h.ins == SessionInitReq::INS {
+            let (req, _) = SessionInitReq::decode(&buff[..n]).unwrap();
+            let r = self
+                .session
+                .lock()
+                .unwrap()
+                .handshake(&mut OsRng, &req)
+                .unwrap();
+            let n = r.encode(buff).unwrap();
+
+            let (a, _) = RESP::decode(&buff[..n]).unwrap();
+            return Ok(a);
+        }
+
+        // Copy request so buff can be reused for the response
+        let mut req_buff = [0u8; 256];
+        req_buff[..n].copy_from_slice(&buff[..n]);
+
+        // Handle request
+        let n = self.handle(h.ins, &req_buff[..n], buff);
 
         assert!(
             n < 250,
-            "encoded response maximum length exceeded for: {r:?} ({n} bytes)"
+            "encoded response maximum length exceeded for: {request:?} ({n} bytes)"
         );
 
         // Decode response APDU
@@ -88,27 +206,6 @@ impl ledger_lib::Device for TestEngine {
     }
 }
 
-/// Driver implementation for test use
-pub struct TestDriver {
-    /// BIP39 Mnemonic derived seed
-    pub seed: [u8; 64],
-}
-
-impl TestDriver {
-    pub fn new(seed: Seed) -> Self {
-        let mut b = [0u8; 64];
-        b.copy_from_slice(seed.as_bytes());
-        Self { seed: b }
-    }
-}
-
-impl Driver for TestDriver {
-    fn slip10_derive_ed25519(&self, path: &[u32]) -> Slip10Key {
-        let d = slip10_ed25519::derive_ed25519_private_key(&self.seed, path);
-        Slip10Key::from_raw(d)
-    }
-}
-
 pub async fn approve_tx(e: &TestEngine) {
     debug!("Approve transaction");
 
@@ -129,3 +226,10 @@ pub async fn approve_ident(e: &TestEngine) {
     let mut e = e.engine.lock().unwrap();
     e.ident_approve(true);
 }
+
+pub async fn approve_fog(e: &TestEngine) {
+    debug!("Approve fog change");
+
+    let mut e = e.engine.lock().unwrap();
+    e.fog_approve(true);
+}
@@ -1,6 +1,6 @@
 //! Test BIP-0013/17 ed25517 identity / challenge requests
 
-use ledger_mob_core::engine::Engine;
+use ledger_mob_core::engine::{Engine, SoftwareDriver};
 use ledger_mob_tests::ident::VECTORS;
 
 mod helpers;
@@ -14,7 +14,7 @@ async fn ident() -> anyhow::Result<()> {
     for v in VECTORS {
         // Setup engine with provided seed
         let seed = v.seed();
-        let e = TestEngine::new(Engine::new(TestDriver { seed }));
+        let e = TestEngine::new(Engine::new(SoftwareDriver::from_seed(seed)));
 
         ledger_mob_tests::ident::test(e.clone(), || approve_ident(&e), v)
             .await
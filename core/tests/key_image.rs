@@ -1,6 +1,6 @@
-use bip39::{Language, Seed};
+use bip39::Language;
 
-use ledger_mob_core::engine::Engine;
+use ledger_mob_core::engine::{Engine, SoftwareDriver};
 
 use mc_core::slip10::Mnemonic;
 
@@ -12,9 +12,7 @@ async fn key_image() -> anyhow::Result<()> {
     let _ = simplelog::SimpleLogger::init(log::LevelFilter::Debug, Default::default());
 
     let mnemonic = Mnemonic::from_phrase(MNEMONIC, Language::English)?;
-    let seed = Seed::new(&mnemonic, "");
-
-    let e = TestEngine::new(Engine::new(TestDriver::new(seed)));
+    let e = TestEngine::new(Engine::new(SoftwareDriver::from_mnemonic(&mnemonic, "")));
 
     e.unlock();
 
@@ -24,3 +22,19 @@ async fn key_image() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn key_image_checked() -> anyhow::Result<()> {
+    let _ = simplelog::SimpleLogger::init(log::LevelFilter::Debug, Default::default());
+
+    let mnemonic = Mnemonic::from_phrase(MNEMONIC, Language::English)?;
+    let e = TestEngine::new(Engine::new(SoftwareDriver::from_mnemonic(&mnemonic, "")));
+
+    e.unlock();
+
+    ledger_mob_tests::key_image::test_checked(e, mnemonic)
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
-use bip39::{Language, Seed};
+use bip39::Language;
 
-use ledger_mob_core::engine::{Engine, RING_SIZE};
+use ledger_mob_core::engine::{Engine, SoftwareDriver, RING_SIZE};
 
 use mc_core::slip10::Mnemonic;
 
@@ -12,11 +12,10 @@ async fn memo_sign() -> anyhow::Result<()> {
     let _ = simplelog::SimpleLogger::init(log::LevelFilter::Debug, Default::default());
 
     let mnemonic = Mnemonic::from_phrase(MNEMONIC, Language::English)?;
-    let seed = Seed::new(&mnemonic, "");
+    let e = TestEngine::new(Engine::new(SoftwareDriver::from_mnemonic(&mnemonic, "")));
 
-    let e = TestEngine::new(Engine::new(TestDriver::new(seed)));
-
-    ledger_mob_tests::memo::hmac(e, mnemonic, RING_SIZE)
+    // Memo review is disabled by default, so no approval is required
+    ledger_mob_tests::memo::hmac(e, || async {}, mnemonic, RING_SIZE)
         .await
         .unwrap();
 
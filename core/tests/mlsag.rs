@@ -1,6 +1,6 @@
-use bip39::{Language, Seed};
+use bip39::Language;
 
-use ledger_mob_core::engine::{Engine, RING_SIZE};
+use ledger_mob_core::engine::{Engine, SoftwareDriver, RING_SIZE};
 
 use mc_core::slip10::Mnemonic;
 
@@ -12,9 +12,7 @@ async fn mlsag() -> anyhow::Result<()> {
     let _ = simplelog::SimpleLogger::init(log::LevelFilter::Debug, Default::default());
 
     let mnemonic = Mnemonic::from_phrase(MNEMONIC, Language::English)?;
-    let seed = Seed::new(&mnemonic, "");
-
-    let e = TestEngine::new(Engine::new(TestDriver::new(seed)));
+    let e = TestEngine::new(Engine::new(SoftwareDriver::from_mnemonic(&mnemonic, "")));
 
     ledger_mob_tests::mlsag::test(e.clone(), || approve_tx(&e), mnemonic, RING_SIZE)
         .await
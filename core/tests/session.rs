@@ -0,0 +1,22 @@
+use bip39::Language;
+
+use ledger_mob_core::engine::{Engine, SoftwareDriver};
+
+use mc_core::slip10::Mnemonic;
+
+mod helpers;
+use helpers::*;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn session() -> anyhow::Result<()> {
+    let _ = simplelog::SimpleLogger::init(log::LevelFilter::Debug, Default::default());
+
+    let mnemonic = Mnemonic::from_phrase(MNEMONIC, Language::English)?;
+    let e = TestEngine::new(Engine::new(SoftwareDriver::from_mnemonic(&mnemonic, "")));
+
+    e.unlock();
+
+    ledger_mob_tests::session::test(e).await.unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+//! Test host-initiated settings changes
+
+use ledger_mob_core::{
+    apdu::tx::FogId,
+    engine::{Engine, SoftwareDriver},
+};
+
+mod helpers;
+use helpers::*;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn set_fog_id() -> anyhow::Result<()> {
+    let _ = simplelog::SimpleLogger::init(log::LevelFilter::Debug, Default::default());
+
+    let e = TestEngine::new(Engine::new(SoftwareDriver::random()));
+
+    ledger_mob_tests::settings::fog(e.clone(), || approve_fog(&e), FogId::SignalTest)
+        .await
+        .unwrap();
+
+    Ok(())
+}
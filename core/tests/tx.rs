@@ -1,6 +1,6 @@
-use bip39::{Language, Mnemonic, Seed};
+use bip39::{Language, Mnemonic};
 
-use ledger_mob_core::engine::Engine;
+use ledger_mob_core::engine::{Engine, SoftwareDriver};
 use ledger_mob_tests::transaction::{test, TRANSACTIONS};
 
 mod helpers;
@@ -12,9 +12,7 @@ async fn tx1() -> anyhow::Result<()> {
     let _ = simplelog::SimpleLogger::init(log::LevelFilter::Debug, Default::default());
 
     let mnemonic = Mnemonic::from_phrase(TRANSACTIONS[0].mnemonic, Language::English)?;
-    let seed = Seed::new(&mnemonic, "");
-
-    let e = TestEngine::new(Engine::new(TestDriver::new(seed)));
+    let e = TestEngine::new(Engine::new(SoftwareDriver::from_mnemonic(&mnemonic, "")));
 
     e.unlock();
 
@@ -30,9 +28,7 @@ async fn tx2() -> anyhow::Result<()> {
     let _ = simplelog::SimpleLogger::init(log::LevelFilter::Debug, Default::default());
 
     let mnemonic = Mnemonic::from_phrase(TRANSACTIONS[1].mnemonic, Language::English)?;
-    let seed = Seed::new(&mnemonic, "");
-
-    let e = TestEngine::new(Engine::new(TestDriver::new(seed)));
+    let e = TestEngine::new(Engine::new(SoftwareDriver::from_mnemonic(&mnemonic, "")));
 
     e.unlock();
 
@@ -53,9 +49,7 @@ async fn tx3() -> anyhow::Result<()> {
     );
 
     let mnemonic = Mnemonic::from_phrase(TRANSACTIONS[2].mnemonic, Language::English)?;
-    let seed = Seed::new(&mnemonic, "");
-
-    let e = TestEngine::new(Engine::new(TestDriver::new(seed)));
+    let e = TestEngine::new(Engine::new(SoftwareDriver::from_mnemonic(&mnemonic, "")));
 
     e.unlock();
 
@@ -76,9 +70,7 @@ async fn tx4() -> anyhow::Result<()> {
     );
 
     let mnemonic = Mnemonic::from_phrase(TRANSACTIONS[3].mnemonic, Language::English)?;
-    let seed = Seed::new(&mnemonic, "");
-
-    let e = TestEngine::new(Engine::new(TestDriver::new(seed)));
+    let e = TestEngine::new(Engine::new(SoftwareDriver::from_mnemonic(&mnemonic, "")));
 
     e.unlock();
 
@@ -0,0 +1,21 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Fuzz [Event::parse][ledger_mob_core::engine::Event::parse] with arbitrary
+//! APDU bytes, handling decoded events on an unlocked engine
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use ledger_mob_fuzz::{apdu, engine};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((ins, buff)) = data.split_first() else {
+        return;
+    };
+
+    let mut e = engine();
+    e.unlock();
+
+    let _ = apdu(&mut e, *ins, buff);
+});
@@ -0,0 +1,18 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Fuzz [Engine::update][ledger_mob_core::engine::Engine::update] with
+//! arbitrary sequences of APDUs and user interactions
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use ledger_mob_fuzz::{apply, engine, Op};
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut e = engine();
+
+    for op in &ops {
+        apply(&mut e, op);
+    }
+});
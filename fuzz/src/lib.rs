@@ -0,0 +1,174 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Shared helpers for [Engine] fuzz targets
+//!
+//! Targets drive the engine via [Engine::update] with events decoded by
+//! [Event::parse], checking for panics and state-machine invariants.
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use rand::{rngs::StdRng, SeedableRng};
+
+use ledger_mob_apdu::{Instruction, MOB_PROTO_VERSION};
+use ledger_mob_core::engine::{Approvals, Engine, Event, Output, SoftwareDriver, State};
+
+/// Response buffer length, matching the device APDU buffer
+pub const RESP_LEN: usize = 256;
+
+/// Instructions handled by [Event::parse]
+pub const INSTRUCTIONS: &[Instruction] = &[
+    Instruction::GetWalletKeys,
+    Instruction::GetSubaddressKeys,
+    Instruction::GetWalletKeysAttested,
+    Instruction::GetSubaddressKeysAttested,
+    Instruction::GetWalletKeysPath,
+    Instruction::GetSubaddressKeysPath,
+    Instruction::GetSubaddressKeysFog,
+    Instruction::GetKeyImage,
+    Instruction::GetRandom,
+    Instruction::GetDeviceNonce,
+    Instruction::SignSessionChallenge,
+    Instruction::IdentSignReq,
+    Instruction::IdentGetReq,
+    Instruction::GetKeyCounters,
+    Instruction::GetSubaddressRange,
+    Instruction::GetSubaddressKeysBatch,
+    Instruction::AddressVerify,
+    Instruction::AddressGet,
+    Instruction::GetPublicAddress,
+    Instruction::SetFogId,
+    Instruction::GetProgress,
+    Instruction::GetSettings,
+    Instruction::GetAuditLog,
+    Instruction::GetDiagnostics,
+    Instruction::GetKeyImageFromKey,
+    Instruction::InvalidateAccounts,
+    Instruction::GetCapabilities,
+    Instruction::SetProtocolVersion,
+    Instruction::TxInit,
+    Instruction::TxMemoSign,
+    Instruction::TxSetMessage,
+    Instruction::TxSummaryInit,
+    Instruction::TxSummaryAddTxOut,
+    Instruction::TxSummaryAddTxOutUnblinding,
+    Instruction::TxSummaryAddTxIn,
+    Instruction::TxSummaryBuild,
+    Instruction::TxSummaryReport,
+    Instruction::TxSummaryAddTxOutCompact,
+    Instruction::TxSummaryAddTxOutUnblindingCompact,
+    Instruction::TxRingInit,
+    Instruction::TxSetBlinding,
+    Instruction::TxAddTxOut,
+    Instruction::TxSign,
+    Instruction::TxGetKeyImage,
+    Instruction::TxGetResponse,
+    Instruction::TxGetResponses,
+    Instruction::TxComplete,
+    Instruction::TxAbort,
+    Instruction::TxGetInfo,
+];
+
+/// Engine instance used by fuzz targets
+pub type FuzzEngine = Engine<SoftwareDriver, StdRng>;
+
+/// Create a new engine with a fixed seed and deterministic RNG,
+/// so crashes are reproducible, pinning the protocol version so
+/// transactions are reachable
+pub fn engine() -> FuzzEngine {
+    let mut e = Engine::new_with_rng(
+        SoftwareDriver::from_seed([0x5a; 64]),
+        StdRng::seed_from_u64(0),
+    );
+
+    e.update(&Event::SetProtocolVersion {
+        version: MOB_PROTO_VERSION,
+    })
+    .expect("pin protocol version");
+
+    e
+}
+
+/// Structure-aware operation, either an incoming APDU or
+/// a user interaction with the device
+#[derive(Clone, Debug, Arbitrary)]
+pub enum Op {
+    /// APDU for a known instruction (index into [INSTRUCTIONS])
+    Apdu { ins: u8, data: Vec<u8> },
+    /// APDU with an arbitrary instruction code
+    RawApdu { ins: u8, data: Vec<u8> },
+    /// Unlock the device (permits key requests)
+    Unlock,
+    /// Lock the device
+    Lock,
+    /// Approve a subset of key request scopes
+    ApproveScope(u8),
+    /// Approve a pending transaction
+    Approve,
+    /// Deny a pending transaction
+    Deny,
+    /// Reset engine state
+    Reset,
+    /// Approve or deny a pending identity request
+    IdentApprove(bool),
+    /// Approve or deny a pending address verification
+    AddressApprove(bool),
+    /// Toggle blind signing support
+    BlindSigning(bool),
+    /// Toggle host-supplied onetime private key support
+    OnetimeKeys(bool),
+}
+
+/// Apply an [Op] to the engine, checking invariants
+pub fn apply(engine: &mut FuzzEngine, op: &Op) {
+    match op {
+        Op::Apdu { ins, data } => {
+            let ins = INSTRUCTIONS[*ins as usize % INSTRUCTIONS.len()] as u8;
+            let _ = apdu(engine, ins, data);
+        }
+        Op::RawApdu { ins, data } => {
+            let _ = apdu(engine, *ins, data);
+        }
+        Op::Unlock => engine.unlock(),
+        Op::Lock => {
+            engine.lock();
+            assert!(!engine.is_unlocked(), "engine unlocked after lock");
+        }
+        Op::ApproveScope(s) => engine.approve_scope(Approvals::from_bits_truncate(*s)),
+        Op::Approve => engine.approve(),
+        Op::Deny => engine.deny(),
+        Op::Reset => {
+            engine.reset();
+            assert_eq!(engine.state(), State::Init, "state not cleared on reset");
+            assert!(engine.message().is_none(), "message not cleared on reset");
+        }
+        Op::IdentApprove(v) => engine.ident_approve(*v),
+        Op::AddressApprove(v) => engine.address_approve(*v),
+        Op::BlindSigning(v) => engine.set_blind_signing(*v),
+        Op::OnetimeKeys(v) => engine.set_onetime_keys(*v),
+    }
+}
+
+/// Decode and handle an APDU, checking invariants on the result
+pub fn apdu(engine: &mut FuzzEngine, ins: u8, data: &[u8]) -> Option<Output> {
+    // Decoding must not panic for any input
+    let (evt, meta) = Event::parse_meta(ins, data).ok()?;
+
+    // Capture approval state prior to handling the event
+    let approved = evt.required_approval().map(|a| engine.is_approved(a));
+
+    let r = engine.update_meta(meta, &evt);
+
+    // Events requiring approval must be rejected if this is not granted
+    if approved == Some(false) {
+        assert!(r.is_err(), "unapproved event accepted: {evt:?}");
+    }
+
+    let o = r.ok()?;
+
+    // Outputs must always be encodable to a response APDU
+    let mut buff = [0u8; RESP_LEN];
+    if let Err(e) = o.encode(&mut buff) {
+        panic!("failed to encode output {o:?}: {e:?}");
+    }
+
+    Some(o)
+}
@@ -8,7 +8,17 @@ fn main() -> anyhow::Result<()> {
     println!("cargo:rerun-if-env-changed=VERSION");
     println!("cargo:rerun-if-env-changed=CI_SHA_SHORT");
 
-    let _target = std::env::var("TARGET").unwrap();
+    let target = std::env::var("TARGET").unwrap();
+
+    // Export SDK target for build identification
+    println!("cargo:rustc-env=BUILD_TARGET={target}");
+
+    // Select the NBGL (touchscreen) UI for Stax and Flex targets,
+    // Nano targets use the BAGL button-driven UI
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    if matches!(target_os.as_str(), "stax" | "flex") {
+        println!("cargo:rustc-cfg=nbgl");
+    }
 
     // Check if we have an injected app version
     let version_tag = match std::env::var("VERSION") {
@@ -30,6 +40,24 @@ fn main() -> anyhow::Result<()> {
     // Load git firmware description and export into environment
     println!("cargo:rustc-env=GIT_TAG={version_tag}");
 
+    // Check if we have an injected commit hash
+    let git_hash = match std::env::var("CI_SHA_SHORT") {
+        Ok(v) => v,
+        // Otherwise, run `git rev-parse`
+        _ => {
+            let output = std::process::Command::new("git")
+                .args(["rev-parse", "--short=8", "HEAD"])
+                .output()
+                .expect("git rev-parse failed");
+
+            std::str::from_utf8(&output.stdout)
+                .unwrap()
+                .trim()
+                .to_string()
+        }
+    };
+    println!("cargo:rustc-env=GIT_HASH={git_hash}");
+
     let build_time = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
     println!("cargo:rustc-env=BUILD_TIME={build_time}");
 
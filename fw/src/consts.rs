@@ -5,21 +5,51 @@
 #![allow(unused)]
 
 use ledger_device_sdk::ecc::make_bip32_path;
+#[cfg(not(nbgl))]
 use ledger_device_sdk::ui::bitmaps::Glyph;
 
-use ledger_mob_core::apdu::app_info::AppFlags;
+use ledger_mob_core::apdu::{
+    algorithm::Algorithm,
+    app_info::{AppFlags, BuildFeatures},
+};
+
+use crate::locale::Language;
 
 /// App Information
 pub const APP_NAME: &str = "MobileCoin";
 pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const GIT_VERSION: &str = env!("GIT_TAG");
 pub const BUILD_TIME: &str = env!("BUILD_TIME");
+pub const BUILD_TARGET: &str = env!("BUILD_TARGET");
+pub const GIT_HASH: &str = env!("GIT_HASH");
+
+/// Ledger standard quit application instruction (dashboard class `0xb0`)
+pub const LEDGER_INS_QUIT: u8 = 0xa7;
 
+/// Resolve static application flags from compiled features and device model,
+/// runtime state (approvals, settings) is applied when handling requests
 pub fn app_flags() -> AppFlags {
     let mut f = AppFlags::empty();
 
-    #[cfg(feature = "summary")]
-    f.set(AppFlags::HAS_TX_SUMMARY, true);
+    // Supported key / identity algorithms
+    f.insert(Algorithm::Ristretto255.flag());
+    #[cfg(feature = "ident")]
+    f.insert(Algorithm::Ed25519.flag());
+
+    f.set(AppFlags::HAS_TX_SUMMARY, cfg!(feature = "summary"));
+    f.set(AppFlags::HAS_PERSISTENT_SETTINGS, cfg!(feature = "nvm"));
+    f.set(AppFlags::TOUCHSCREEN, cfg!(nbgl));
+
+    f
+}
+
+pub fn build_features() -> BuildFeatures {
+    let mut f = BuildFeatures::empty();
+
+    f.set(BuildFeatures::SUMMARY, cfg!(feature = "summary"));
+    f.set(BuildFeatures::IDENT, cfg!(feature = "ident"));
+    f.set(BuildFeatures::ALLOC, cfg!(feature = "alloc"));
+    f.set(BuildFeatures::NVM, cfg!(feature = "nvm"));
 
     f
 }
@@ -27,9 +57,72 @@ pub fn app_flags() -> AppFlags {
 /// Application timeout (exit after no user input)
 pub const LOCK_TIMEOUT_S: u32 = 5 * 60;
 
+/// Lock timeouts selectable via settings (0 disables locking)
+pub const LOCK_TIMEOUTS_S: [u32; 4] = [60, 5 * 60, 15 * 60, 0];
+
+/// SLIP-0010 account index used for on-device operations
+pub const DEFAULT_ACCOUNT_INDEX: u32 = 0;
+
+/// Default UI language, for the persisted setting
+pub const DEFAULT_LANGUAGE: Language = Language::En;
+
+/// Key exports permitted per scope before re-approval is required (0 for unlimited),
+/// default for the persisted setting
+pub const KEY_REQUEST_LIMIT: u32 = 0;
+
+/// Key request limits selectable via settings (0 for unlimited)
+pub const KEY_REQUEST_LIMITS: [u32; 4] = [0, 5, 10, 25];
+
+/// Require sensitive requests (view keys, blindings) to be wrapped in an
+/// encrypted session, rather than only once a session is open.
+///
+/// Session handshakes are unauthenticated, so this protects against passive
+/// transport sniffing only (see [ledger_mob_core::apdu::session]).
+pub const SESSION_REQUIRED: bool = false;
+
+/// Permit blind signing (direct message setting) where TxSummary is supported,
+/// default for the persisted setting (see [crate::platform::PlatformSettings])
+pub const BLIND_SIGNING: bool = true;
+
+/// Require PIN confirmation for each wallet (view) key export,
+/// default for the persisted setting (see [crate::platform::PlatformSettings])
+pub const PIN_WALLET_KEYS: bool = false;
+
+/// Permit ring signing with host-supplied onetime private keys, disable to
+/// guarantee only seed-derived keys are used
+pub const ONETIME_KEYS: bool = true;
+
+/// Display payment request / intent ids when reviewing signed memos
+pub const SHOW_PAYMENT_IDS: bool = true;
+
+/// Hold the first signed memo of each transaction for on-device review.
+///
+/// Disabled by default as hosts predating memo review treat the held
+/// request as a failure and abort the transaction.
+pub const MEMO_REVIEW: bool = false;
+
+/// Confirmations required on the approve page for transactions where report
+/// totals meet the per-token high-value threshold (see
+/// [ledger_mob_core::helpers::is_high_value]), 1 for a single confirmation
+pub const HIGH_VALUE_CONFIRMATIONS: usize = 3;
+
+/// Transaction timeout (abort transactions not completed in this period)
+pub const TX_TIMEOUT_S: u32 = 10 * 60;
+
+/// Transaction idle timeout (abort transactions where no APDU is received
+/// in this period, 0 to disable), complementing [TX_TIMEOUT_S]
+pub const TX_IDLE_TIMEOUT_S: u32 = 60;
+
 /// Message timeout (return to home screen)
 pub const MESSAGE_TIMEOUT_S: u32 = 5;
 
+/// Chunked request / response buffer length, reduced for constrained builds
+/// (chunked requests exceeding this are rejected)
+#[cfg(not(feature = "constrained"))]
+pub const CHUNK_BUFF_SIZE: usize = ledger_mob_core::apdu::chunk::CHUNK_BUFF_LEN;
+#[cfg(feature = "constrained")]
+pub const CHUNK_BUFF_SIZE: usize = 512;
+
 /// Ticks per second for calculating application timeouts
 pub const TICKS_PER_S: u32 = 10;
 
@@ -37,10 +130,13 @@ pub const TICKS_PER_S: u32 = 10;
 pub const BIP32_PATH: [u32; 5] = make_bip32_path(b"m/44'/866'/0'/0/0");
 
 /// Mob logo in 14x14 (see build.rs for conversion)
+#[cfg(not(nbgl))]
 pub const MOB14X14: Glyph = include!(concat!(env!("OUT_DIR"), "/mob14x14.gif"));
 
 /// Mob logo in 16x16 (see build.rs for conversion)
+#[cfg(not(nbgl))]
 pub const MOB16X16: Glyph = include!(concat!(env!("OUT_DIR"), "/mob16x16.gif"));
 
 /// Mob logo in 32x32 (see build.rs for conversion)
+#[cfg(not(nbgl))]
 pub const MOB32X32: Glyph = include!(concat!(env!("OUT_DIR"), "/mob32x32.gif"));
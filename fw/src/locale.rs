@@ -0,0 +1,640 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! UI string tables, selected via the persisted [Language] setting
+//!
+//! Strings are resolved with `match` expressions rather than static arrays,
+//! as tables of string references contain absolute addresses that are not
+//! valid in position independent (PIC) app images. Translations are limited
+//! to printable ASCII as supported by the device fonts, and to ~20 characters
+//! per line to fit the smallest (Nano) displays.
+
+// NBGL targets only use message strings, the remaining entries are Nano UI
+#![cfg_attr(nbgl, allow(dead_code))]
+
+use crate::platform::platform_get_settings;
+
+/// UI languages, selectable via settings
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[repr(u8)]
+pub enum Language {
+    /// English
+    #[default]
+    En = 0,
+    /// Spanish
+    Es = 1,
+    /// German
+    De = 2,
+    /// French
+    Fr = 3,
+}
+
+/// Available languages, in selection order
+pub const LANGUAGES: &[Language] = &[Language::En, Language::Es, Language::De, Language::Fr];
+
+impl Language {
+    /// Language name for display (in the language itself)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::En => "English",
+            Language::Es => "Espanol",
+            Language::De => "Deutsch",
+            Language::Fr => "Francais",
+        }
+    }
+
+    /// Fetch the next language for selection
+    pub fn next(&self) -> Self {
+        LANGUAGES[(*self as usize + 1) % LANGUAGES.len()]
+    }
+}
+
+impl TryFrom<u32> for Language {
+    type Error = ();
+
+    fn try_from(v: u32) -> Result<Self, Self::Error> {
+        LANGUAGES.get(v as usize).copied().ok_or(())
+    }
+}
+
+/// UI string identifiers, see [text]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Text {
+    // Menu
+    IsReady,
+    Address,
+    Version,
+    Settings,
+    Exit,
+    KeyRequests,
+    CheckAddress,
+
+    // Settings
+    FogId,
+    LockTimeout,
+    BlindSigning,
+    KeyExportPin,
+    KeyExportLimit,
+    Language,
+    Enabled,
+    Disabled,
+    Never,
+    Unlimited,
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    Custom,
+    FogNone,
+
+    // Address verification
+    VerifyAddress,
+    Account,
+    Subaddress,
+    RejectAddress,
+    ConfirmAddress,
+    CompareAddress1,
+    CompareAddress2,
+    AddressMatches,
+    AddressDiffers,
+
+    // Identity requests
+    Identity,
+    Request,
+    Challenge,
+    RejectChallenge,
+    SignChallenge,
+
+    // Memo review
+    Memo,
+    Review,
+    RejectMemo,
+    SignMemo,
+
+    // Wallet sync, scope descriptions are split over three lines
+    SyncWallet,
+    WalletKeysInfo1,
+    WalletKeysInfo2,
+    WalletKeysInfo3,
+    SubaddressKeysInfo1,
+    SubaddressKeysInfo2,
+    SubaddressKeysInfo3,
+    OnetimeKeyImagesInfo1,
+    OnetimeKeyImagesInfo2,
+    OnetimeKeyImagesInfo3,
+    KeyImagesInfo1,
+    KeyImagesInfo2,
+    KeyImagesInfo3,
+    ApproveSync,
+    RejectSync,
+
+    // Transaction review
+    Transaction,
+    NoSummaryInfo1,
+    NoSummaryInfo2,
+    NoSummaryInfo3,
+    TransactionHash,
+    NoSummaryAvailable,
+    MemoUnavailable,
+    DustUnavailable,
+    TxExpired,
+    TombstoneFar,
+    HighValue,
+    ConfirmAgain,
+    Receive,
+    Send,
+    Swap,
+    Dust,
+    Outputs,
+    Fee,
+    FeeUnverified,
+    FeeNotProvided,
+    Rings,
+    Total,
+    SwapTotal,
+    ApproveTransaction,
+    RejectTransaction,
+
+    // Progress
+    LoadingSummary,
+    SigningRing,
+    ExportingResponses,
+
+    // Messages
+    TransactionTimeout,
+    TransactionComplete,
+    TransactionCancelled,
+    TransactionAborted,
+    SigningCancelled,
+    ChallengeApproved,
+    ChallengeRejected,
+    AddressConfirmed,
+    AddressRejected,
+    FogUpdated,
+    FogRejected,
+    SeedVerified,
+    SeedMismatch,
+}
+
+/// Resolve UI text for the configured language
+pub fn text(t: Text) -> &'static str {
+    t.localise(platform_get_settings().language)
+}
+
+impl Text {
+    /// Resolve UI text for the provided language
+    pub fn localise(self, lang: Language) -> &'static str {
+        match lang {
+            Language::En => en(self),
+            Language::Es => es(self),
+            Language::De => de(self),
+            Language::Fr => fr(self),
+        }
+    }
+}
+
+/// English strings
+fn en(t: Text) -> &'static str {
+    use Text::*;
+
+    match t {
+        IsReady => "is ready",
+        Address => "Address",
+        Version => "Version",
+        Settings => "Settings",
+        Exit => "Exit",
+        KeyRequests => "Key requests: ",
+        CheckAddress => "Check Address",
+
+        FogId => "Fog ID",
+        LockTimeout => "Lock Timeout",
+        BlindSigning => "Blind Signing",
+        KeyExportPin => "Key Export PIN",
+        KeyExportLimit => "Key Export Limit",
+        Language => "Language",
+        Enabled => "Enabled",
+        Disabled => "Disabled",
+        Never => "Never",
+        Unlimited => "Unlimited",
+        OneMinute => "1 minute",
+        FiveMinutes => "5 minutes",
+        FifteenMinutes => "15 minutes",
+        Custom => "Custom",
+        FogNone => "None",
+
+        VerifyAddress => "Verify Address",
+        Account => "Account ",
+        Subaddress => "Subaddr. ",
+        RejectAddress => "Reject address?",
+        ConfirmAddress => "Confirm address?",
+        CompareAddress1 => "Compare with your",
+        CompareAddress2 => "recorded address",
+        AddressMatches => "Address matches",
+        AddressDiffers => "Address differs",
+
+        Identity => "Identity",
+        Request => "Request",
+        Challenge => "Challenge",
+        RejectChallenge => "Reject challenge?",
+        SignChallenge => "Sign challenge?",
+
+        Memo => "Memo",
+        Review => "Review",
+        RejectMemo => "Reject memo?",
+        SignMemo => "Sign memo?",
+
+        SyncWallet => "Sync Wallet?",
+        WalletKeysInfo1 => "Allow the connected",
+        WalletKeysInfo2 => "application to export",
+        WalletKeysInfo3 => "account view keys",
+        SubaddressKeysInfo1 => "Allow the connected",
+        SubaddressKeysInfo2 => "application to export",
+        SubaddressKeysInfo3 => "subaddress view keys",
+        OnetimeKeyImagesInfo1 => "Allow key images for",
+        OnetimeKeyImagesInfo2 => "host-supplied onetime",
+        OnetimeKeyImagesInfo3 => "private keys",
+        KeyImagesInfo1 => "Allow the connected",
+        KeyImagesInfo2 => "application to retrieve",
+        KeyImagesInfo3 => "account balances",
+        ApproveSync => "Approve Sync",
+        RejectSync => "Reject Sync",
+
+        Transaction => "Transaction",
+        NoSummaryInfo1 => "No summary available,",
+        NoSummaryInfo2 => "please verify the",
+        NoSummaryInfo3 => "transaction hash",
+        TransactionHash => "Transaction hash:",
+        NoSummaryAvailable => "NO SUMMARY AVAILABLE",
+        MemoUnavailable => "MEMO UNAVAILABLE",
+        DustUnavailable => "DUST UNAVAILABLE",
+        TxExpired => "! TX EXPIRED !",
+        TombstoneFar => "! TOMBSTONE FAR !",
+        HighValue => "! HIGH VALUE !",
+        ConfirmAgain => "Confirm again",
+        Receive => "Receive",
+        Send => "Send",
+        Swap => "Swap",
+        Dust => "Dust",
+        Outputs => " outputs",
+        Fee => "Fee",
+        FeeUnverified => "Fee (unverified)",
+        FeeNotProvided => "Not provided",
+        Rings => " rings",
+        Total => "Total",
+        SwapTotal => "Swap Total",
+        ApproveTransaction => "Approve Transaction?",
+        RejectTransaction => "Reject Transaction?",
+
+        LoadingSummary => "Loading summary",
+        SigningRing => "Signing ring ",
+        ExportingResponses => "Exporting responses",
+
+        TransactionTimeout => "Transaction Timed Out",
+        TransactionComplete => "Transaction Complete",
+        TransactionCancelled => "Transaction Cancelled",
+        TransactionAborted => "Transaction Aborted",
+        SigningCancelled => "Signing Cancelled",
+        ChallengeApproved => "challenge approved",
+        ChallengeRejected => "challenge rejected",
+        AddressConfirmed => "address confirmed",
+        AddressRejected => "address rejected",
+        FogUpdated => "fog updated",
+        FogRejected => "fog rejected",
+        SeedVerified => "seed verified",
+        SeedMismatch => "seed mismatch",
+    }
+}
+
+/// Spanish strings
+fn es(t: Text) -> &'static str {
+    use Text::*;
+
+    match t {
+        IsReady => "esta lista",
+        Address => "Direccion",
+        Version => "Version",
+        Settings => "Ajustes",
+        Exit => "Salir",
+        KeyRequests => "Solicitudes: ",
+        CheckAddress => "Revisar direccion",
+
+        FogId => "ID de Fog",
+        LockTimeout => "Bloqueo auto.",
+        BlindSigning => "Firma ciega",
+        KeyExportPin => "PIN para claves",
+        KeyExportLimit => "Limite de claves",
+        Language => "Idioma",
+        Enabled => "Activado",
+        Disabled => "Desactivado",
+        Never => "Nunca",
+        Unlimited => "Ilimitado",
+        OneMinute => "1 minuto",
+        FiveMinutes => "5 minutos",
+        FifteenMinutes => "15 minutos",
+        Custom => "Personalizado",
+        FogNone => "Ninguno",
+
+        VerifyAddress => "Verificar direccion",
+        Account => "Cuenta ",
+        Subaddress => "Subdir. ",
+        RejectAddress => "Rechazar direccion?",
+        ConfirmAddress => "Confirmar direccion?",
+        CompareAddress1 => "Compare con su",
+        CompareAddress2 => "direccion anotada",
+        AddressMatches => "Direccion coincide",
+        AddressDiffers => "Direccion distinta",
+
+        Identity => "Identidad",
+        Request => "Solicitud",
+        Challenge => "Desafio",
+        RejectChallenge => "Rechazar desafio?",
+        SignChallenge => "Firmar desafio?",
+
+        Memo => "Memo",
+        Review => "Revisar",
+        RejectMemo => "Rechazar memo?",
+        SignMemo => "Firmar memo?",
+
+        SyncWallet => "Sincronizar?",
+        WalletKeysInfo1 => "Permitir a la app",
+        WalletKeysInfo2 => "conectada exportar",
+        WalletKeysInfo3 => "claves de cuenta",
+        SubaddressKeysInfo1 => "Permitir a la app",
+        SubaddressKeysInfo2 => "conectada exportar",
+        SubaddressKeysInfo3 => "claves de subdir.",
+        OnetimeKeyImagesInfo1 => "Permitir key images",
+        OnetimeKeyImagesInfo2 => "de claves privadas",
+        OnetimeKeyImagesInfo3 => "del host",
+        KeyImagesInfo1 => "Permitir a la app",
+        KeyImagesInfo2 => "conectada consultar",
+        KeyImagesInfo3 => "saldos de la cuenta",
+        ApproveSync => "Aprobar sinc.",
+        RejectSync => "Rechazar sinc.",
+
+        Transaction => "Transaccion",
+        NoSummaryInfo1 => "Sin resumen,",
+        NoSummaryInfo2 => "verifique el hash",
+        NoSummaryInfo3 => "de la transaccion",
+        TransactionHash => "Hash de transaccion:",
+        NoSummaryAvailable => "SIN RESUMEN",
+        MemoUnavailable => "MEMO NO DISPONIBLE",
+        DustUnavailable => "RESIDUOS NO DISP.",
+        TxExpired => "! TX CADUCADA !",
+        TombstoneFar => "! TOMBSTONE LEJANO !",
+        HighValue => "! VALOR ALTO !",
+        ConfirmAgain => "Confirmar de nuevo",
+        Receive => "Recibir",
+        Send => "Enviar",
+        Swap => "Swap",
+        Dust => "Residuos",
+        Outputs => " salidas",
+        Fee => "Comision",
+        FeeUnverified => "Comision (no verif.)",
+        FeeNotProvided => "No indicada",
+        Rings => " anillos",
+        Total => "Total",
+        SwapTotal => "Total swap",
+        ApproveTransaction => "Aprobar la tx?",
+        RejectTransaction => "Rechazar la tx?",
+
+        LoadingSummary => "Cargando resumen",
+        SigningRing => "Firmando anillo ",
+        ExportingResponses => "Exportando respuestas",
+
+        TransactionTimeout => "Tiempo agotado",
+        TransactionComplete => "Transaccion completa",
+        TransactionCancelled => "Tx cancelada",
+        TransactionAborted => "Tx abortada",
+        SigningCancelled => "Firma cancelada",
+        ChallengeApproved => "desafio aprobado",
+        ChallengeRejected => "desafio rechazado",
+        AddressConfirmed => "direccion confirmada",
+        AddressRejected => "direccion rechazada",
+        FogUpdated => "fog actualizado",
+        FogRejected => "fog rechazado",
+        SeedVerified => "semilla verificada",
+        SeedMismatch => "semilla distinta",
+    }
+}
+
+/// German strings
+fn de(t: Text) -> &'static str {
+    use Text::*;
+
+    match t {
+        IsReady => "ist bereit",
+        Address => "Adresse",
+        Version => "Version",
+        Settings => "Einstellungen",
+        Exit => "Beenden",
+        KeyRequests => "Anfragen: ",
+        CheckAddress => "Adresse pruefen",
+
+        FogId => "Fog-ID",
+        LockTimeout => "Auto-Sperre",
+        BlindSigning => "Blind Signing",
+        KeyExportPin => "PIN fuer Schluessel",
+        KeyExportLimit => "Schluessellimit",
+        Language => "Sprache",
+        Enabled => "Aktiviert",
+        Disabled => "Deaktiviert",
+        Never => "Nie",
+        Unlimited => "Unbegrenzt",
+        OneMinute => "1 Minute",
+        FiveMinutes => "5 Minuten",
+        FifteenMinutes => "15 Minuten",
+        Custom => "Benutzerdef.",
+        FogNone => "Keine",
+
+        VerifyAddress => "Adresse pruefen",
+        Account => "Konto ",
+        Subaddress => "Unteradr. ",
+        RejectAddress => "Adresse ablehnen?",
+        ConfirmAddress => "Adresse bestaetigen?",
+        CompareAddress1 => "Mit notierter Adresse",
+        CompareAddress2 => "vergleichen",
+        AddressMatches => "Adresse stimmt",
+        AddressDiffers => "Adresse abweichend",
+
+        Identity => "Identitaet",
+        Request => "Anfrage",
+        Challenge => "Challenge",
+        RejectChallenge => "Challenge ablehnen?",
+        SignChallenge => "Challenge signieren?",
+
+        Memo => "Memo",
+        Review => "Pruefen",
+        RejectMemo => "Memo ablehnen?",
+        SignMemo => "Memo signieren?",
+
+        SyncWallet => "Synchronisieren?",
+        WalletKeysInfo1 => "Der verbundenen App",
+        WalletKeysInfo2 => "den Export der Konto-",
+        WalletKeysInfo3 => "schluessel erlauben",
+        SubaddressKeysInfo1 => "Der verbundenen App",
+        SubaddressKeysInfo2 => "den Export der Unter-",
+        SubaddressKeysInfo3 => "adressschluessel erl.",
+        OnetimeKeyImagesInfo1 => "Key Images fuer vom",
+        OnetimeKeyImagesInfo2 => "Host gelieferte",
+        OnetimeKeyImagesInfo3 => "Einmalschluessel",
+        KeyImagesInfo1 => "Der verbundenen App",
+        KeyImagesInfo2 => "den Abruf der Konto-",
+        KeyImagesInfo3 => "staende erlauben",
+        ApproveSync => "Sync freigeben",
+        RejectSync => "Sync ablehnen",
+
+        Transaction => "Transaktion",
+        NoSummaryInfo1 => "Keine Uebersicht,",
+        NoSummaryInfo2 => "bitte den Hash der",
+        NoSummaryInfo3 => "Transaktion pruefen",
+        TransactionHash => "Transaktions-Hash:",
+        NoSummaryAvailable => "KEINE UEBERSICHT",
+        MemoUnavailable => "MEMO FEHLT",
+        DustUnavailable => "DUST FEHLT",
+        TxExpired => "! TX ABGELAUFEN !",
+        TombstoneFar => "! TOMBSTONE FERN !",
+        HighValue => "! HOHER BETRAG !",
+        ConfirmAgain => "Erneut bestaetigen",
+        Receive => "Empfangen",
+        Send => "Senden",
+        Swap => "Swap",
+        Dust => "Dust",
+        Outputs => " Ausgaben",
+        Fee => "Gebuehr",
+        FeeUnverified => "Gebuehr (ungeprueft)",
+        FeeNotProvided => "Nicht angegeben",
+        Rings => " Ringe",
+        Total => "Summe",
+        SwapTotal => "Swap-Summe",
+        ApproveTransaction => "Tx freigeben?",
+        RejectTransaction => "Tx ablehnen?",
+
+        LoadingSummary => "Lade Uebersicht",
+        SigningRing => "Signiere Ring ",
+        ExportingResponses => "Exportiere Antworten",
+
+        TransactionTimeout => "Zeitueberschreitung",
+        TransactionComplete => "Transaktion fertig",
+        TransactionCancelled => "Tx abgebrochen",
+        TransactionAborted => "Tx fehlgeschlagen",
+        SigningCancelled => "Signatur abgebrochen",
+        ChallengeApproved => "Challenge signiert",
+        ChallengeRejected => "Challenge abgelehnt",
+        AddressConfirmed => "Adresse bestaetigt",
+        AddressRejected => "Adresse abgelehnt",
+        FogUpdated => "Fog aktualisiert",
+        FogRejected => "Fog abgelehnt",
+        SeedVerified => "Seed bestaetigt",
+        SeedMismatch => "Seed abweichend",
+    }
+}
+
+/// French strings
+fn fr(t: Text) -> &'static str {
+    use Text::*;
+
+    match t {
+        IsReady => "est pret",
+        Address => "Adresse",
+        Version => "Version",
+        Settings => "Parametres",
+        Exit => "Quitter",
+        KeyRequests => "Requetes: ",
+        CheckAddress => "Verifier adresse",
+
+        FogId => "ID Fog",
+        LockTimeout => "Verrouillage",
+        BlindSigning => "Signature aveugle",
+        KeyExportPin => "PIN export de cles",
+        KeyExportLimit => "Limite export cles",
+        Language => "Langue",
+        Enabled => "Active",
+        Disabled => "Desactive",
+        Never => "Jamais",
+        Unlimited => "Illimite",
+        OneMinute => "1 minute",
+        FiveMinutes => "5 minutes",
+        FifteenMinutes => "15 minutes",
+        Custom => "Personnalise",
+        FogNone => "Aucun",
+
+        VerifyAddress => "Verifier l'adresse",
+        Account => "Compte ",
+        Subaddress => "Sous-adr. ",
+        RejectAddress => "Rejeter l'adresse?",
+        ConfirmAddress => "Confirmer l'adresse?",
+        CompareAddress1 => "Comparer avec votre",
+        CompareAddress2 => "adresse notee",
+        AddressMatches => "Adresse identique",
+        AddressDiffers => "Adresse differente",
+
+        Identity => "Identite",
+        Request => "Demande",
+        Challenge => "Defi",
+        RejectChallenge => "Rejeter le defi?",
+        SignChallenge => "Signer le defi?",
+
+        Memo => "Memo",
+        Review => "Verifier",
+        RejectMemo => "Rejeter le memo?",
+        SignMemo => "Signer le memo?",
+
+        SyncWallet => "Synchroniser?",
+        WalletKeysInfo1 => "Autoriser l'appli",
+        WalletKeysInfo2 => "connectee a exporter",
+        WalletKeysInfo3 => "les cles du compte",
+        SubaddressKeysInfo1 => "Autoriser l'appli",
+        SubaddressKeysInfo2 => "connectee a exporter",
+        SubaddressKeysInfo3 => "les cles sous-adr.",
+        OnetimeKeyImagesInfo1 => "Autoriser key images",
+        OnetimeKeyImagesInfo2 => "pour cles privees",
+        OnetimeKeyImagesInfo3 => "fournies par l'hote",
+        KeyImagesInfo1 => "Autoriser l'appli",
+        KeyImagesInfo2 => "connectee a lire",
+        KeyImagesInfo3 => "les soldes du compte",
+        ApproveSync => "Approuver sync",
+        RejectSync => "Rejeter sync",
+
+        Transaction => "Transaction",
+        NoSummaryInfo1 => "Aucun resume,",
+        NoSummaryInfo2 => "verifiez le hash",
+        NoSummaryInfo3 => "de la transaction",
+        TransactionHash => "Hash transaction:",
+        NoSummaryAvailable => "AUCUN RESUME",
+        MemoUnavailable => "MEMO INDISPONIBLE",
+        DustUnavailable => "POUSSIERE INDISPO.",
+        TxExpired => "! TX EXPIREE !",
+        TombstoneFar => "! TOMBSTONE LOIN !",
+        HighValue => "! MONTANT ELEVE !",
+        ConfirmAgain => "Confirmer a nouveau",
+        Receive => "Recevoir",
+        Send => "Envoyer",
+        Swap => "Swap",
+        Dust => "Poussiere",
+        Outputs => " sorties",
+        Fee => "Frais",
+        FeeUnverified => "Frais (non verifies)",
+        FeeNotProvided => "Non fournis",
+        Rings => " anneaux",
+        Total => "Total",
+        SwapTotal => "Total swap",
+        ApproveTransaction => "Approuver la tx?",
+        RejectTransaction => "Rejeter la tx?",
+
+        LoadingSummary => "Chargement resume",
+        SigningRing => "Signature anneau ",
+        ExportingResponses => "Export des reponses",
+
+        TransactionTimeout => "Delai depasse",
+        TransactionComplete => "Transaction terminee",
+        TransactionCancelled => "Transaction annulee",
+        TransactionAborted => "Transaction avortee",
+        SigningCancelled => "Signature annulee",
+        ChallengeApproved => "defi approuve",
+        ChallengeRejected => "defi rejete",
+        AddressConfirmed => "adresse confirmee",
+        AddressRejected => "adresse rejetee",
+        FogUpdated => "fog mis a jour",
+        FogRejected => "fog rejete",
+        SeedVerified => "seed verifiee",
+        SeedMismatch => "seed differente",
+    }
+}
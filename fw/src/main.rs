@@ -11,26 +11,37 @@ extern crate alloc;
 
 use core::mem::MaybeUninit;
 
-use encdec::Encode;
+use encdec::{Decode, Encode};
 use rand_core::{CryptoRng, RngCore};
 
-use ledger_device_sdk::ui::layout::{Layout, Location, StringPlace};
+#[cfg(not(nbgl))]
 use ledger_device_sdk::{
     buttons::ButtonEvent,
+    ui::layout::{Layout, Location},
+};
+use ledger_device_sdk::{
     io::{self, ApduHeader, Reply, SyscallError},
     random::LedgerRng,
 };
-use ledger_proto::apdus::{AppFlags, AppInfoReq, AppInfoResp, DeviceInfoReq};
+use ledger_proto::apdus::{AppInfoReq, AppInfoResp, DeviceInfoReq};
 
 use ledger_mob_core::{
     apdu::{
         self,
         app_info::{
-            AppFlags as MobAppFlags, AppInfoReq as MobAppInfoReq, AppInfoResp as MobAppInfoResp,
+            AppBuildInfo, AppFlags as MobAppFlags, AppInfoReq as MobAppInfoReq,
+            AppInfoResp as MobAppInfoResp,
         },
-        tx::FogId,
+        batch::{BatchReq, BatchWriter},
+        chunk::{ChunkBuffer, ChunkGetReq, ChunkReq, ChunkResp},
+        encoding::Encoding,
+        session::{requires_session, DeviceSession, SessionInitReq, SessionReq},
+        status::{StatusWord, SW_INS_NOT_SUPPORTED},
+    },
+    engine::{
+        AddressState, Approvals, Engine, Error, Event, FogState, Function, IdentState, Output,
+        ParseError, ResponseBuffer, State, RING_SIZE,
     },
-    engine::{Engine, Error, Event, IdentState, Output, State},
 };
 use mc_core::consts::DEFAULT_SUBADDRESS_INDEX;
 
@@ -40,9 +51,19 @@ use consts::*;
 mod platform;
 use platform::*;
 
+mod locale;
+use locale::{text, Text};
+
+#[cfg(not(nbgl))]
 mod ui;
+#[cfg(not(nbgl))]
 use ui::*;
 
+#[cfg(nbgl)]
+mod nbgl;
+#[cfg(nbgl)]
+use nbgl::*;
+
 const APDU_HEADER_LEN: usize = 5;
 
 /// Engine context is global to mitigate stack-related issues in current ledger OS.
@@ -58,6 +79,9 @@ struct AppCtx {
     ui: Ui,
     event: Event,
     output: Output,
+    chunks: ChunkBuffer<CHUNK_BUFF_SIZE>,
+    session: DeviceSession,
+    responses: ResponseBuffer,
 }
 
 // Setup ledger panic handler
@@ -75,6 +99,7 @@ pub fn app_getrandom(buff: &mut [u8]) -> Result<(), getrandom::Error> {
 }
 
 /// Error handler for allocation
+#[cfg(feature = "alloc")]
 #[alloc_error_handler]
 fn oom(_: core::alloc::Layout) -> ! {
     ledger_device_sdk::exit_app(250)
@@ -82,37 +107,81 @@ fn oom(_: core::alloc::Layout) -> ! {
 
 #[no_mangle]
 extern "C" fn sample_main() {
+    // Fill unused stack for memory instrumentation
+    #[cfg(any(debug_assertions, feature = "pre-release"))]
+    platform::stack::fill();
+
     // Setup comms and UI instances
     let mut comm = io::Comm::new();
 
+    // Bind comms for NBGL flows
+    #[cfg(nbgl)]
+    ledger_device_sdk::nbgl::init_comm(&mut comm);
+
+    // Load persisted settings
+    let settings = platform_get_settings();
+
     let mut ticks = 0u32;
-    let mut lock_timeout = LOCK_TIMEOUT_S * TICKS_PER_S;
+    let mut lock_timeout = settings.lock_timeout_s * TICKS_PER_S;
     let mut message_timeout = 0;
+    let mut tx_idle_timeout = 0;
 
     let mut redraw = true;
 
     #[cfg(feature = "local_alloc")]
     platform::allocator::init();
 
-    // non-nvm fog ID global must be pre-initialised
-    #[cfg(not(feature = "nvm"))]
-    platform::platform_set_fog_id(&FogId::MobMain);
-
     // Initialise and bind globally allocated contexts
-    let (engine, ui, event, output) = unsafe {
+    let (engine, ui, event, output, chunks, session) = unsafe {
         let p = &mut *APP_CTX.as_mut_ptr();
 
         Engine::init(&mut p.engine, LedgerDriver {}, LedgerRng {});
         Ui::init(&mut p.ui);
         Event::init(&mut p.event);
         Output::init(&mut p.output);
-
-        (&mut p.engine, &mut p.ui, &mut p.event, &mut p.output)
+        ChunkBuffer::init(&mut p.chunks);
+        DeviceSession::init(&mut p.session);
+        ResponseBuffer::init(&mut p.responses);
+
+        // Bind ring response storage to the engine
+        p.engine.set_response_buffer(&mut p.responses);
+
+        (
+            &mut p.engine,
+            &mut p.ui,
+            &mut p.event,
+            &mut p.output,
+            &mut p.chunks,
+            &mut p.session,
+        )
     };
 
+    // Configure key export re-approval limit
+    engine.set_key_request_limit(settings.key_request_limit);
+
+    // Configure blind signing (TxSummary verification bypass)
+    engine.set_blind_signing(settings.blind_signing);
+
+    // Configure host-supplied onetime private key policy
+    engine.set_onetime_keys(ONETIME_KEYS);
+
+    // Configure payment request / intent id display for signed memos
+    engine.set_show_payment_ids(SHOW_PAYMENT_IDS);
+
+    // Configure on-device review of signed memos
+    engine.set_memo_review(MEMO_REVIEW);
+
+    // Configure transaction watchdog
+    engine.set_tx_timeout(TX_TIMEOUT_S, TICKS_PER_S);
+
+    // Mirror device settings for reporting to the host
+    engine.set_fog_id(settings.fog_id);
+    engine.set_lock_timeout(settings.lock_timeout_s);
+    engine.set_default_account(settings.default_account);
+
     // Developer mode / pending review popup
     // must be cleared with user interaction
-    #[cfg(feature = "pre-release")]
+    #[cfg(all(feature = "pre-release", not(nbgl)))]
     {
         use ButtonEvent::*;
 
@@ -135,6 +204,21 @@ extern "C" fn sample_main() {
         }
     }
 
+    #[cfg(all(feature = "pre-release", nbgl))]
+    {
+        use ledger_device_sdk::nbgl::NbglChoice;
+
+        let accepted = NbglChoice::new().glyph(&MOB_GLYPH).show(
+            "Pending Review",
+            "This app has not yet been reviewed by Ledger",
+            "Continue",
+            "Exit",
+        );
+        if !accepted {
+            ledger_device_sdk::exit_app(0);
+        }
+    }
+
     // Run platform tests prior to init
     platform_tests(&mut comm);
 
@@ -147,6 +231,7 @@ extern "C" fn sample_main() {
         // Handle input events and update UI state
         match &evt {
             // Handle button presses
+            #[cfg(not(nbgl))]
             io::Event::Button(btn) => {
                 if handle_btn(engine, ui, btn) {
                     // Set redraw flag on changes
@@ -154,19 +239,48 @@ extern "C" fn sample_main() {
                 }
 
                 // Update timeout on button press
-                lock_timeout = ticks.wrapping_add(LOCK_TIMEOUT_S * TICKS_PER_S);
+                lock_timeout = ticks.wrapping_add(engine.lock_timeout() * TICKS_PER_S);
+            }
+            // Update timeout on touch, NBGL flows handle touch navigation
+            #[cfg(nbgl)]
+            io::Event::TouchEvent => {
+                lock_timeout = ticks.wrapping_add(engine.lock_timeout() * TICKS_PER_S);
             }
             // Handle incoming APDUs
             io::Event::Command(_hdr) => {
-                if handle_apdu(engine, &mut comm, ui, event, output) {
+                #[cfg(not(nbgl))]
+                let request_count = engine.request_count();
+
+                // Update transaction idle timeout on each APDU
+                tx_idle_timeout = ticks.wrapping_add(TX_IDLE_TIMEOUT_S * TICKS_PER_S);
+
+                if handle_apdu(engine, &mut comm, ui, event, output, chunks, session) {
                     // Set redraw flags on changes
                     redraw = true;
                 }
+
+                // Redraw menu to update the serviced key request count
+                #[cfg(not(nbgl))]
+                if matches!(ui.state, UiState::Menu) && engine.request_count() != request_count {
+                    redraw = true;
+                }
             }
             // Handle ticks
             io::Event::Ticker => {
                 // Update tick counter
                 ticks = ticks.wrapping_add(1);
+                let mut expired = engine.set_ticks(ticks);
+
+                // Abort stalled transactions where the host stops sending requests
+                if TX_IDLE_TIMEOUT_S != 0 && ticks == tx_idle_timeout {
+                    expired |= engine.tx_expire();
+                }
+
+                // Notify the user when the transaction watchdog aborts
+                if expired && (ui.state.is_tx_request() || ui.state.is_progress()) {
+                    ui.state = UiState::message(text(Text::TransactionTimeout));
+                    redraw = true;
+                }
 
                 // Return to menu state after message timeout
                 if ui.state.is_message() && ticks >= message_timeout {
@@ -178,16 +292,19 @@ extern "C" fn sample_main() {
                     engine.reset();
                 }
 
-                // Request pin entry after lock timeout
-                if ticks == lock_timeout {
+                // Request pin entry after lock timeout (where enabled)
+                if engine.lock_timeout() != 0 && ticks == lock_timeout {
                     // Clear engine approval flag
                     engine.lock();
 
+                    // Close encrypted session, requiring a new handshake
+                    session.reset();
+
                     // Execute lock syscall (blocks on pin entry)
                     request_pin_validation();
 
                     // Reset timeout and redraw on re-entry
-                    lock_timeout = ticks.wrapping_add(LOCK_TIMEOUT_S * TICKS_PER_S);
+                    lock_timeout = ticks.wrapping_add(engine.lock_timeout() * TICKS_PER_S);
                     redraw = true;
                 }
             }
@@ -200,6 +317,10 @@ extern "C" fn sample_main() {
 
         // Redraw UI on state change
         if redraw {
+            // NBGL reviews block on user input, applying results to the engine
+            #[cfg(nbgl)]
+            ui.render(engine);
+            #[cfg(not(nbgl))]
             ui.render(&*engine);
             redraw = false;
         }
@@ -207,6 +328,7 @@ extern "C" fn sample_main() {
 }
 
 /// Handle button events, returning true if UI should be redrawn
+#[cfg(not(nbgl))]
 #[cfg_attr(feature = "noinline", inline(never))]
 fn handle_btn<RNG: RngCore + CryptoRng>(
     engine: &mut Engine<LedgerDriver, RNG>,
@@ -222,19 +344,52 @@ fn handle_btn<RNG: RngCore + CryptoRng>(
                     MenuState::Address => {
                         // Fetch subaddress from engine
                         let fog_id = platform_get_fog_id();
-                        let s = engine.get_subaddress(0, DEFAULT_SUBADDRESS_INDEX, fog_id);
+                        let s = engine.get_subaddress(
+                            engine.default_account(),
+                            DEFAULT_SUBADDRESS_INDEX,
+                            fog_id,
+                        );
 
                         // Set UI state to display subaddress
                         ui.state = UiState::Address(Address::new(
                             &s.address,
-                            s.fog_id,
+                            s.fog_id.url(),
                             s.fog_sig.as_ref().map(|s| s.as_slice()).unwrap_or(&[]),
                         ));
                     }
+                    MenuState::CheckAddress => {
+                        // Re-derive default subaddress from the device seed
+                        let fog_id = platform_get_fog_id();
+                        let s = engine.get_subaddress(
+                            engine.default_account(),
+                            DEFAULT_SUBADDRESS_INDEX,
+                            fog_id,
+                        );
+
+                        // Set UI state to check subaddress against a recorded value
+                        ui.state = UiState::AddressCheck(AddressCheck::new(Address::new(
+                            &s.address,
+                            s.fog_id.url(),
+                            s.fog_sig.as_ref().map(|s| s.as_slice()).unwrap_or(&[]),
+                        )));
+                    }
                     MenuState::Version => ui.state = UiState::AppInfo(AppInfo::new()),
                     MenuState::Settings => {
                         let fog_id = platform_get_fog_id();
-                        ui.state = UiState::Settings(Settings::new(fog_id))
+                        let settings =
+                            Settings::new(fog_id).with_lock_timeout(engine.lock_timeout());
+
+                        // Blind signing may only be disabled where TxSummary is supported
+                        #[cfg(feature = "summary")]
+                        let settings = settings.with_blind_signing(engine.blind_signing());
+
+                        let s = platform_get_settings();
+                        let settings = settings
+                            .with_pin_wallet_keys(s.pin_wallet_keys)
+                            .with_key_request_limit(s.key_request_limit)
+                            .with_language(s.language);
+
+                        ui.state = UiState::Settings(settings)
                     }
                     MenuState::Exit => ledger_device_sdk::exit_app(0),
                     _ => (),
@@ -244,11 +399,36 @@ fn handle_btn<RNG: RngCore + CryptoRng>(
             UiResult::Update
         }
         UiState::Address(ref mut a) => a.update(btn),
+        UiState::AddressCheck(ref mut a) => match a.update(btn) {
+            // Show check result, returning to the menu on message timeout
+            UiResult::Exit(Some(matches)) => {
+                let msg = match matches {
+                    true => Text::SeedVerified,
+                    false => Text::SeedMismatch,
+                };
+                ui.state = UiState::message(text(msg));
+                UiResult::Update
+            }
+            r => r.map_exit(|_| ()),
+        },
+        UiState::AddressRequest(ref mut a) => {
+            a.update(btn).map_exit(|v| {
+                // Set address approval
+                engine.address_approve(*v)
+            })
+        }
+        UiState::MemoRequest(ref mut a) => {
+            a.update(btn).map_exit(|v| {
+                // Approve or deny memo, denial rejects the transaction
+                engine.memo_approve(*v)
+            })
+        }
         UiState::KeyRequest(ref mut a) => {
+            let scope = a.scope();
             a.update(btn).map_exit(|v| {
-                // Unlock engine on approval
+                // Approve requested capabilities
                 if *v {
-                    engine.unlock()
+                    engine.approve_scope(scope)
                 }
             })
         }
@@ -294,16 +474,50 @@ fn handle_btn<RNG: RngCore + CryptoRng>(
             })
         }
         UiState::AppInfo(ref mut a) => a.update(btn),
-        UiState::Settings(ref mut a) => a.update(btn).map_exit(|fog_id| {
-            // Update fog id
-            platform_set_fog_id(fog_id);
+        UiState::Settings(ref mut a) => a.update(btn).map_exit(|c| match *c {
+            SettingsChange::Fog(fog_id) => {
+                // Update fog id
+                platform_set_fog_id(&fog_id);
+                engine.set_fog_id(fog_id);
+
+                // Approve host fog requests where the requested fog is selected
+                if let Some(requested) = engine.fog_request() {
+                    engine.fog_approve(fog_id == requested);
+                }
+            }
+            SettingsChange::LockTimeout(timeout_s) => {
+                // Persist and apply lock timeout, read by the event loop
+                platform_set_lock_timeout(timeout_s);
+                engine.set_lock_timeout(timeout_s);
+            }
+            SettingsChange::BlindSigning(enabled) => {
+                // Persist and apply blind signing setting
+                platform_set_blind_signing(enabled);
+                engine.set_blind_signing(enabled);
+            }
+            SettingsChange::PinWalletKeys(enabled) => {
+                // Persist key export PIN setting, read on each export
+                platform_set_pin_wallet_keys(enabled);
+            }
+            SettingsChange::KeyRequestLimit(limit) => {
+                // Persist and apply key request limit
+                platform_set_key_request_limit(limit);
+                engine.set_key_request_limit(limit);
+            }
+            SettingsChange::Language(language) => {
+                // Persist UI language, read on each render
+                platform_set_language(language);
+            }
         }),
     };
 
     // Handle ui results
     match ui.state {
         UiState::Address(..)
+        | UiState::AddressCheck(..)
+        | UiState::AddressRequest(..)
         | UiState::KeyRequest(..)
+        | UiState::MemoRequest(..)
         | UiState::TxRequest(..)
         | UiState::Progress(..)
         | UiState::Message(..)
@@ -336,6 +550,8 @@ fn handle_apdu<RNG: RngCore + CryptoRng>(
     ui: &mut Ui,
     evt: &mut Event,
     output: &mut Output,
+    chunks: &mut ChunkBuffer<CHUNK_BUFF_SIZE>,
+    session: &mut DeviceSession,
 ) -> bool {
     use apdu::*;
 
@@ -353,15 +569,14 @@ fn handle_apdu<RNG: RngCore + CryptoRng>(
     match (cla, ins) {
         // Ledger standard application info
         (AppInfoReq::CLA | 0, AppInfoReq::INS) => {
-            let r = AppInfoResp::new(APP_NAME, APP_VERSION, AppFlags::empty());
+            let r = AppInfoResp::new(APP_NAME, APP_VERSION, os_app_flags());
             match r.encode(&mut comm.apdu_buffer) {
                 Ok(n) => {
                     comm.tx = n;
                     comm.reply_ok();
                 }
                 Err(_e) => {
-                    let r = 0x6d00 | (Error::EncodingFailed as u8) as u16;
-                    comm.reply(Reply(r));
+                    comm.reply(engine_error_reply(Error::EncodingFailed));
                 }
             }
 
@@ -375,31 +590,71 @@ fn handle_apdu<RNG: RngCore + CryptoRng>(
                     comm.reply_ok();
                 }
                 Err(_e) => {
-                    let r = 0x6d00 | (Error::EncodingFailed as u8) as u16;
-                    comm.reply(Reply(r));
+                    comm.reply(engine_error_reply(Error::EncodingFailed));
                 }
             }
 
             return false;
         }
-        // Ledger exit app command
-        (0xb0, 0xa7) => {
+        // Ledger exit app command, acknowledged prior to returning to the
+        // dashboard so host tooling does not observe a transport error
+        (AppInfoReq::CLA, LEDGER_INS_QUIT) => {
+            comm.reply_ok();
             ledger_device_sdk::exit_app(0);
         }
+        // Reject other Ledger standard commands as unsupported instructions
+        (AppInfoReq::CLA, _) => {
+            comm.tx = 0;
+            comm.reply(Reply(SW_INS_NOT_SUPPORTED));
+            return false;
+        }
+        // Test-only echo of text rendered on screen
+        #[cfg(all(feature = "speculos", not(nbgl)))]
+        (ui_echo::UiEchoReq::CLA, ui_echo::UiEchoReq::INS) => {
+            reply_encoded(comm, echo::screen());
+            return false;
+        }
+        // Debug-only memory usage reporting
+        #[cfg(any(debug_assertions, feature = "pre-release"))]
+        (memory::MemoryInfoReq::CLA, memory::MemoryInfoReq::INS) => {
+            reply_encoded(comm, &memory_info(engine));
+            return false;
+        }
         // MobileCoin application info
         (MobAppInfoReq::CLA, MobAppInfoReq::INS) => {
             let mut flags = app_flags();
             flags.set(MobAppFlags::UNLOCKED, engine.is_unlocked());
-
-            let r = MobAppInfoResp::new(MOB_PROTO_VERSION, APP_NAME, APP_VERSION, flags);
+            flags.set(
+                MobAppFlags::WALLET_KEYS_APPROVED,
+                engine.is_approved(Approvals::WALLET_KEYS),
+            );
+            flags.set(
+                MobAppFlags::SUBADDRESS_KEYS_APPROVED,
+                engine.is_approved(Approvals::SUBADDRESS_KEYS),
+            );
+            flags.set(
+                MobAppFlags::KEY_IMAGES_APPROVED,
+                engine.is_approved(Approvals::KEY_IMAGES),
+            );
+            flags.set(
+                MobAppFlags::ONETIME_KEY_IMAGES_APPROVED,
+                engine.is_approved(Approvals::ONETIME_KEY_IMAGES),
+            );
+            flags.set(MobAppFlags::BLIND_SIGNING_DISABLED, !engine.blind_signing());
+            flags.set(MobAppFlags::ONETIME_KEYS_DISABLED, !engine.onetime_keys());
+
+            let build =
+                AppBuildInfo::new(BUILD_TARGET, GIT_HASH, build_features(), RING_SIZE as u8);
+
+            let r = MobAppInfoResp::new(MOB_PROTO_VERSION, APP_NAME, APP_VERSION, flags)
+                .with_build(build);
             match r.encode(&mut comm.apdu_buffer) {
                 Ok(n) => {
                     comm.tx = n;
                     comm.reply_ok();
                 }
                 Err(_e) => {
-                    let r = 0x6d00 | (Error::EncodingFailed as u8) as u16;
-                    comm.reply(Reply(r));
+                    comm.reply(engine_error_reply(Error::EncodingFailed));
                 }
             }
 
@@ -408,34 +663,134 @@ fn handle_apdu<RNG: RngCore + CryptoRng>(
         _ => (),
     }
 
-    // Return error for other unhandled APDUs
-    if cla != MOB_APDU_CLA {
-        comm.tx = 0;
-        comm.reply(SyscallError::NotSupported);
+    // Resolve payload encoding from APDU class,
+    // returning an error for other unhandled APDUs
+    let enc = match Encoding::from_cla(cla) {
+        Some(v) => v,
+        None => {
+            comm.tx = 0;
+            comm.reply(SyscallError::NotSupported);
+            return false;
+        }
+    };
+
+    // Return response chunks following chunked requests
+    if ins == ChunkGetReq::INS {
+        let offset = match ChunkGetReq::decode(&comm.apdu_buffer[APDU_HEADER_LEN..]) {
+            Ok((req, _)) => req.offset,
+            Err(_e) => {
+                comm.reply(SyscallError::InvalidParameter);
+                return false;
+            }
+        };
+
+        match chunks.get(offset) {
+            Ok(r) => reply_encoded(comm, &r),
+            Err(_e) => comm.reply(SyscallError::InvalidParameter),
+        }
+
+        return false;
+    }
+
+    // Establish encrypted sessions, replacing any existing session
+    if ins == SessionInitReq::INS {
+        let r = match SessionInitReq::decode(&comm.apdu_buffer[APDU_HEADER_LEN..]) {
+            Ok((req, _)) => session.handshake(&mut LedgerRng {}, &req),
+            Err(e) => Err(e),
+        };
+
+        match r {
+            Ok(r) => reply_encoded(comm, &r),
+            Err(_e) => comm.reply(SyscallError::InvalidParameter),
+        }
+
         return false;
     }
 
     // Handle engine / transaction commands
 
-    // Decode APDUs to engine events
-    *evt = match Event::parse(ins, &comm.apdu_buffer[APDU_HEADER_LEN..]) {
+    // Reassemble chunked requests
+    let chunked = ins == ChunkReq::INS;
+    let r = match chunked {
+        true => match ChunkReq::decode(&comm.apdu_buffer[APDU_HEADER_LEN..]) {
+            Ok((req, _)) => match chunks.push(&req) {
+                Ok(Some((ins, data))) => Ok((ins, data)),
+                // Acknowledge intermediate chunks
+                Ok(None) => {
+                    let r = ChunkResp::new(chunks.received() as u16, 0, &[]);
+                    reply_encoded(comm, &r);
+                    return false;
+                }
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        },
+        false => Ok((ins, &comm.apdu_buffer[APDU_HEADER_LEN..])),
+    };
+
+    // Decrypt session wrapped requests
+    // (session state is fetched prior to unwrapping as decrypted data borrows the session)
+    let session_open = session.is_open();
+    let wrapped = matches!(r, Ok((i, _)) if i == SessionReq::INS);
+    let r = match r {
+        Ok((_, data)) if wrapped => match SessionReq::decode(data) {
+            Ok((req, _)) => session.open(&req),
+            Err(e) => Err(e),
+        },
+        r => r,
+    };
+
+    let (ins, data) = match r {
         Ok(v) => v,
         Err(_e) => {
+            chunks.reset();
+            comm.reply(SyscallError::InvalidParameter);
+            return false;
+        }
+    };
+
+    // Handle batched requests, which must be chunked and may not be session wrapped
+    if ins == BatchReq::INS {
+        if !chunked || wrapped {
+            chunks.reset();
             comm.reply(SyscallError::InvalidParameter);
             return false;
         }
+
+        return handle_batch(engine, comm, ui, evt, output, chunks, enc, session_open);
+    }
+
+    // Reject unwrapped sensitive requests where a session is required or open
+    if !wrapped && requires_session(ins) && (SESSION_REQUIRED || session_open) {
+        chunks.reset();
+        comm.reply(SyscallError::Security);
+        return false;
+    }
+
+    // Decode APDUs to engine events and request metadata
+    let meta = match Event::parse_meta_with(enc, ins, data) {
+        Ok((v, meta)) => {
+            *evt = v;
+            meta
+        }
+        Err(e) => {
+            chunks.reset();
+            comm.reply(parse_error_reply(&e));
+            return false;
+        }
     };
 
+    // Clear reassembled request data
+    if chunked {
+        chunks.reset();
+    }
+
     // WIP: user acknowledgement screens etc.
     // to be moved once i've worked out how to wire this best
-    match &evt {
-        Event::GetWalletKeys { .. }
-        | Event::GetSubaddressKeys { .. }
-        | Event::GetKeyImage { .. }
-            if !engine.is_unlocked() && !ui.state.is_key_request() =>
-        {
+    match evt.required_approval() {
+        Some(scope) if !engine.is_approved(scope) && !ui.state.is_key_request() => {
             // Update UI to key request acknowledge state
-            ui.state = UiState::KeyRequest(SyncApprover::new());
+            ui.state = UiState::KeyRequest(SyncApprover::new(scope, evt.key_path()));
 
             // Return empty APDU to signify late response
             // TODO: check on how other apps do this
@@ -447,17 +802,175 @@ fn handle_apdu<RNG: RngCore + CryptoRng>(
         _ => (),
     }
 
+    // Confirm PIN prior to wallet key export (where enabled)
+    if is_pin_request(evt) {
+        // Execute lock syscall (blocks on pin entry) and redraw on re-entry
+        request_pin_validation();
+        render = true;
+    }
+
     // Update engine
-    *output = match engine.update(evt) {
+    *output = match engine.update_meta(meta, evt) {
         Ok(v) => v,
         Err(e) => {
-            let r = 0x6d00 | (e as u8) as u16;
-            comm.reply(Reply(r));
+            reply_engine_error(comm, engine, e);
             return false;
         }
     };
 
     // Update UI based on engine state changes
+    if update_ui(engine, ui) {
+        render = true;
+    }
+
+    // Encrypt engine output for wrapped requests
+    if wrapped {
+        match session.seal(|b| output.encode_with(enc, b)) {
+            Ok(r) if chunked => reply_chunked(comm, chunks, |b| r.encode(b)),
+            Ok(r) => reply_encoded(comm, &r),
+            Err(_e) => comm.reply(SyscallError::Overflow),
+        }
+
+        return render;
+    }
+
+    // Encode engine output to chunk buffer for chunked requests
+    if chunked {
+        reply_chunked(comm, chunks, |b| output.encode_with(enc, b));
+
+        return render;
+    }
+
+    // Encode engine output to response APDU
+    let n = match output.encode_with(enc, &mut comm.apdu_buffer) {
+        Ok(v) => v,
+        Err(_e) => {
+            comm.reply(SyscallError::Overflow);
+            return false;
+        }
+    };
+
+    // Send response
+    comm.tx = n;
+    comm.reply_ok();
+
+    // Return render flag
+    render
+}
+
+/// Check whether a request requires PIN confirmation prior to servicing
+fn is_pin_request(evt: &Event) -> bool {
+    matches!(evt, Event::GetWalletKeys { .. }) && platform_get_settings().pin_wallet_keys
+}
+
+/// Handle batched requests (see [apdu::batch]), returning true if UI should be redrawn
+///
+/// Entry responses are staged in the APDU buffer as the reassembled
+/// request is held in the chunk buffer, then returned via the chunk buffer.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "noinline", inline(never))]
+fn handle_batch<RNG: RngCore + CryptoRng>(
+    engine: &mut Engine<LedgerDriver, RNG>,
+    comm: &mut io::Comm,
+    ui: &mut Ui,
+    evt: &mut Event,
+    output: &mut Output,
+    chunks: &mut ChunkBuffer<CHUNK_BUFF_SIZE>,
+    enc: Encoding,
+    session_open: bool,
+) -> bool {
+    // Handle batch entries
+    let r = match chunks.request().map(|(_, data)| BatchReq::decode(data)) {
+        Some(Ok((req, _))) => handle_batch_entries(
+            engine,
+            evt,
+            output,
+            enc,
+            session_open,
+            &req,
+            &mut comm.apdu_buffer,
+        ),
+        _ => Err(SyscallError::InvalidParameter.into()),
+    };
+
+    // Update UI for any applied entries
+    let render = update_ui(engine, ui);
+
+    let n = match r {
+        Ok(n) => n,
+        Err(e) => {
+            chunks.reset();
+            comm.reply(e);
+            return render;
+        }
+    };
+
+    // Copy staged response to chunk buffer and return the first chunk
+    let r = chunks.set_response(|b| {
+        b[..n].copy_from_slice(&comm.apdu_buffer[..n]);
+        Ok::<_, apdu::ApduError>(n)
+    });
+    match r.and_then(|_| chunks.get(0)) {
+        Ok(r) => reply_encoded(comm, &r),
+        Err(_e) => comm.reply(SyscallError::Overflow),
+    }
+
+    render
+}
+
+/// Handle batch entries in order, writing the [BatchResp][apdu::batch::BatchResp]
+/// to the provided buffer and returning the encoded length, or the status for
+/// the first failed entry
+fn handle_batch_entries<RNG: RngCore + CryptoRng>(
+    engine: &mut Engine<LedgerDriver, RNG>,
+    evt: &mut Event,
+    output: &mut Output,
+    enc: Encoding,
+    session_open: bool,
+    req: &BatchReq,
+    buff: &mut [u8],
+) -> Result<usize, Reply> {
+    let mut w = BatchWriter::new(buff);
+
+    for entry in req.entries() {
+        // Reject sensitive requests where a session is required or open
+        if requires_session(entry.ins) && (SESSION_REQUIRED || session_open) {
+            return Err(SyscallError::Security.into());
+        }
+
+        // Decode entry to engine event and request metadata
+        let meta;
+        (*evt, meta) = Event::parse_meta_with(enc, entry.ins, entry.data)
+            .map_err(|e| parse_error_reply(&e))?;
+
+        // Entries requiring approval must be issued directly
+        if matches!(evt.required_approval(), Some(scope) if !engine.is_approved(scope)) {
+            return Err(SyscallError::Security.into());
+        }
+
+        // Entries requiring PIN confirmation must also be issued directly
+        if is_pin_request(evt) {
+            return Err(SyscallError::Security.into());
+        }
+
+        // Update engine
+        *output = engine.update_meta(meta, evt).map_err(engine_error_reply)?;
+
+        // Append entry response
+        w.push_with(entry.ins, |b| output.encode_with(enc, b))
+            .map_err(|_e| Reply::from(SyscallError::Overflow))?;
+    }
+
+    w.finish().map_err(|_e| Reply::from(SyscallError::Overflow))
+}
+
+/// Update UI based on engine state changes, returning true if UI should be redrawn
+fn update_ui<RNG: RngCore + CryptoRng>(
+    engine: &mut Engine<LedgerDriver, RNG>,
+    ui: &mut Ui,
+) -> bool {
+    let mut render = false;
+
     match engine.state() {
         // Update to identity approver on request
         #[cfg(feature = "ident")]
@@ -469,14 +982,74 @@ fn handle_apdu<RNG: RngCore + CryptoRng>(
         #[cfg(feature = "ident")]
         State::Ident(IdentState::Approved) => {
             if !ui.state.is_message() {
-                ui.state = UiState::message("challenge approved");
+                ui.state = UiState::message(text(Text::ChallengeApproved));
                 render = true;
             }
         }
         #[cfg(feature = "ident")]
         State::Ident(IdentState::Denied) => {
             if !ui.state.is_message() {
-                ui.state = UiState::message("challenge rejected");
+                ui.state = UiState::message(text(Text::ChallengeRejected));
+                render = true;
+            }
+        }
+
+        // Update to address approver on verification request
+        State::Address(AddressState::Pending) if !ui.state.is_address_request() => {
+            if let Some(v) = engine.address_verify() {
+                let s = engine.get_subaddress(v.account_index, v.subaddress_index, v.fog_id);
+                let address = Address::new(
+                    &s.address,
+                    s.fog_id.url(),
+                    s.fog_sig.as_ref().map(|s| s.as_slice()).unwrap_or(&[]),
+                );
+                ui.state = UiState::AddressRequest(AddressApprover::new(
+                    address,
+                    v.account_index,
+                    v.subaddress_index,
+                ));
+                render = true;
+            }
+        }
+        // Show address verification state on changes
+        State::Address(AddressState::Approved) => {
+            if !ui.state.is_message() {
+                ui.state = UiState::message(text(Text::AddressConfirmed));
+                render = true;
+            }
+        }
+        State::Address(AddressState::Denied) => {
+            if !ui.state.is_message() {
+                ui.state = UiState::message(text(Text::AddressRejected));
+                render = true;
+            }
+        }
+
+        // Update to settings page on fog change request
+        State::Fog(FogState::Pending) if !ui.state.is_settings() => {
+            if let Some(fog_id) = engine.fog_request() {
+                ui.state = UiState::Settings(Settings::new(fog_id));
+                render = true;
+            }
+        }
+        // Show fog change state on changes
+        State::Fog(FogState::Approved) => {
+            if !ui.state.is_message() {
+                ui.state = UiState::message(text(Text::FogUpdated));
+                render = true;
+            }
+        }
+        State::Fog(FogState::Denied) => {
+            if !ui.state.is_message() {
+                ui.state = UiState::message(text(Text::FogRejected));
+                render = true;
+            }
+        }
+
+        // Update to memo approver when the first memo is held for review
+        State::MemoReview if !ui.state.is_memo_request() => {
+            if let Some(m) = engine.memo_request() {
+                ui.state = UiState::MemoRequest(MemoApprover::new(m.clone()));
                 render = true;
             }
         }
@@ -494,12 +1067,15 @@ fn handle_apdu<RNG: RngCore + CryptoRng>(
             Some(r) => {
                 ui.state = UiState::TxSummaryRequest(TxSummaryApprover::new(
                     r.outputs.len(),
+                    engine.dust().len(),
+                    engine.memos().len(),
                     r.totals.len(),
+                    engine.is_high_value(),
                 ));
                 render = true;
             }
             _ => {
-                ui.state = UiState::TxRequest(TxBlindApprover::new());
+                ui.state = UiState::TxRequest(TxBlindApprover::new(engine.memos().len()));
                 render = true;
             }
         },
@@ -512,13 +1088,25 @@ fn handle_apdu<RNG: RngCore + CryptoRng>(
 
         // Set complete message when transaction is complete
         State::Complete if !ui.state.is_message() => {
-            ui.state = UiState::message("Transaction Complete");
+            ui.state = UiState::message(text(Text::TransactionComplete));
             render = true;
         }
 
-        // Set cancelled message when transaction is aborted
+        // Set cancelled message when transaction is denied
         State::Deny if !ui.state.is_message() => {
-            ui.state = UiState::message("Transaction Cancelled");
+            ui.state = UiState::message(text(Text::TransactionCancelled));
+            render = true;
+        }
+
+        // Set cancelled message when signing is cancelled on-device
+        State::Cancelled if !ui.state.is_message() => {
+            ui.state = UiState::message(text(Text::SigningCancelled));
+            render = true;
+        }
+
+        // Set aborted message when the host aborts the transaction
+        State::Aborted if !ui.state.is_message() => {
+            ui.state = UiState::message(text(Text::TransactionAborted));
             render = true;
         }
 
@@ -530,28 +1118,116 @@ fn handle_apdu<RNG: RngCore + CryptoRng>(
         render = true;
     }
 
-    // Encode engine output to response APDU
-    let n = match output.encode(&mut comm.apdu_buffer) {
-        Ok(v) => v,
+    render
+}
+
+/// Encode response to the chunk buffer, sending the first response chunk
+fn reply_chunked<E>(
+    comm: &mut io::Comm,
+    chunks: &mut ChunkBuffer<CHUNK_BUFF_SIZE>,
+    encode: impl FnOnce(&mut [u8]) -> Result<usize, E>,
+) {
+    match chunks.set_response(encode) {
+        Ok(_) => match chunks.get(0) {
+            Ok(r) => reply_encoded(comm, &r),
+            Err(_e) => comm.reply(SyscallError::Overflow),
+        },
+        Err(_e) => comm.reply(SyscallError::Overflow),
+    }
+}
+
+/// Encode and send a response APDU
+fn reply_encoded<E: Encode<Error = apdu::ApduError>>(comm: &mut io::Comm, r: &E) {
+    match r.encode(&mut comm.apdu_buffer) {
+        Ok(n) => {
+            comm.tx = n;
+            comm.reply_ok();
+        }
         Err(_e) => {
             comm.reply(SyscallError::Overflow);
-            return false;
         }
-    };
+    }
+}
 
-    // Send response
-    comm.tx = n;
-    comm.reply_ok();
+/// Map engine errors to classified status words (see [apdu::status])
+fn engine_error_reply(e: Error) -> Reply {
+    Reply(StatusWord::from(e).into())
+}
 
-    // Return render flag
-    render
+/// Reply with classified engine error status words, prefixed by a short
+/// UTF-8 detail string (error and engine state) for debug and `pre-release`
+/// builds (see [apdu::status::error_detail])
+fn reply_engine_error<RNG: RngCore + CryptoRng>(
+    comm: &mut io::Comm,
+    engine: &Engine<LedgerDriver, RNG>,
+    e: Error,
+) {
+    #[cfg(any(debug_assertions, feature = "pre-release"))]
+    {
+        use core::fmt::Write;
+
+        // Details are truncated on overflow, the reply is sent regardless
+        let mut s = heapless::String::<{ apdu::status::ERROR_DETAIL_MAX }>::new();
+        let _ = write!(&mut s, "{:?} ({})", e, engine.state());
+
+        comm.apdu_buffer[..s.len()].copy_from_slice(s.as_bytes());
+        comm.tx = s.len();
+    }
+
+    #[cfg(not(any(debug_assertions, feature = "pre-release")))]
+    let _ = engine;
+
+    comm.reply(engine_error_reply(e));
+}
+
+/// Collect heap, stack and function context memory usage for debug and
+/// `pre-release` builds, heap usage is only available with `local_alloc`
+#[cfg(any(debug_assertions, feature = "pre-release"))]
+fn memory_info<RNG: RngCore + CryptoRng>(
+    engine: &Engine<LedgerDriver, RNG>,
+) -> apdu::memory::MemoryInfoResp {
+    #[cfg(feature = "local_alloc")]
+    let (heap_used, heap_free) = platform::allocator::usage();
+    #[cfg(not(feature = "local_alloc"))]
+    let (heap_used, heap_free) = (0, 0);
+
+    let (stack_used, stack_free) = platform::stack::usage();
+
+    apdu::memory::MemoryInfoResp {
+        heap_used: heap_used as u32,
+        heap_free: heap_free as u32,
+        stack_used: stack_used as u32,
+        stack_free: stack_free as u32,
+        function_size: engine.function_size() as u32,
+        function_max: Function::max_size() as u32,
+    }
+}
+
+/// Map APDU parsing errors to status words
+fn parse_error_reply(e: &ParseError) -> Reply {
+    match e {
+        ParseError::NotSupported(_) => SyscallError::NotSupported.into(),
+        ParseError::Apdu(_) => SyscallError::InvalidParameter.into(),
+    }
 }
 
 #[cfg_attr(feature = "noinline", inline(never))]
 fn platform_tests(comm: &mut io::Comm) {
+    // Ensure RNG is operating as expected
+    #[cfg(nbgl)]
+    if let Err(_e) = test_rng() {
+        let _ = comm;
+
+        ledger_device_sdk::nbgl::NbglStatus::new()
+            .text("RNG unavailable")
+            .show(false);
+        ledger_device_sdk::exit_app(30);
+    }
+
+    #[cfg(not(nbgl))]
     clear_screen();
 
-    // Ensure RNG is operating as expected
+    #[cfg(not(nbgl))]
     if let Err(_e) = test_rng() {
         "ERROR".place(Location::Top, Layout::Centered, true);
         "RNG UNAVAILABLE".place(Location::Middle, Layout::Centered, false);
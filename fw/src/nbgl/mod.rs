@@ -0,0 +1,261 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Ledger MobileCoin NBGL User Interface (Stax and Flex)
+//!
+//! This mirrors the [UiState] interface of the BAGL `ui` module used on
+//! Nano devices so APDU handling and engine state tracking are shared
+//! between targets, with the `nbgl` cfg set by `build.rs` for touchscreen
+//! targets. Request states are displayed using blocking NBGL review flows
+//! with touch navigation (NBGL handles layout for each screen size), and
+//! review results are applied to the engine on exit in place of button
+//! event handling.
+
+use rand_core::{CryptoRng, RngCore};
+
+use include_gif::include_gif;
+use ledger_device_sdk::nbgl::{NbglGlyph, NbglHomeAndSettings, NbglSpinner, NbglStatus};
+
+use ledger_mob_core::engine::{AddressState, Driver, Engine, FogState, IdentState, State};
+
+use crate::consts::{APP_NAME, APP_VERSION};
+
+mod review;
+pub use review::*;
+
+/// MobileCoin logo for Stax NBGL pages
+#[cfg(target_os = "stax")]
+pub const MOB_GLYPH: NbglGlyph = NbglGlyph::from_include(include_gif!("assets/mob32x32.gif", NBGL));
+
+/// MobileCoin logo for Flex NBGL pages
+#[cfg(target_os = "flex")]
+pub const MOB_GLYPH: NbglGlyph = NbglGlyph::from_include(include_gif!("assets/mob40x40.gif", NBGL));
+
+/// Application author shown on the home page info screen
+const APP_AUTHOR: &str = "MobileCoin Foundation";
+
+/// Top level User Interface implementation
+pub struct Ui {
+    /// Current top-level state of UI
+    pub state: UiState,
+
+    /// Home page instance, retained while displayed
+    home: Option<NbglHomeAndSettings>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UiState {
+    /// Showing home page
+    Menu,
+
+    /// Address verification request, awaiting user review
+    AddressRequest(AddressApprover),
+
+    /// Request for view keys, awaiting user review
+    KeyRequest(SyncApprover),
+
+    /// Signed memo review, awaiting user review
+    MemoRequest(MemoApprover),
+
+    /// Transaction request without summary, awaiting user review
+    TxRequest(TxBlindApprover),
+
+    /// Transaction request with summary, awaiting user review
+    #[cfg(feature = "summary")]
+    TxSummaryRequest(TxSummaryApprover),
+
+    #[cfg(feature = "ident")]
+    IdentRequest(IdentApprover),
+
+    /// Fog change request, awaiting user review
+    Settings(Settings),
+
+    /// Progress indicator
+    Progress(Progress),
+
+    /// Messages (transaction complete, rejected, etc.)
+    Message(Message),
+}
+
+impl UiState {
+    /// Create a new `Message` variant
+    pub fn message(value: &'static str) -> Self {
+        Self::Message(Message::new(value))
+    }
+
+    pub fn is_key_request(&self) -> bool {
+        matches!(self, UiState::KeyRequest(..))
+    }
+
+    pub fn is_address_request(&self) -> bool {
+        matches!(self, UiState::AddressRequest(..))
+    }
+
+    pub fn is_memo_request(&self) -> bool {
+        matches!(self, UiState::MemoRequest(..))
+    }
+
+    pub fn is_settings(&self) -> bool {
+        matches!(self, UiState::Settings(..))
+    }
+
+    pub fn is_tx_request(&self) -> bool {
+        match self {
+            UiState::TxRequest(..) => true,
+            #[cfg(feature = "summary")]
+            UiState::TxSummaryRequest(..) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_progress(&self) -> bool {
+        matches!(self, UiState::Progress(..))
+    }
+
+    pub fn is_message(&self) -> bool {
+        matches!(self, UiState::Message(..))
+    }
+
+    #[cfg(feature = "ident")]
+    pub fn is_ident_request(&self) -> bool {
+        matches!(self, UiState::IdentRequest(..))
+    }
+}
+
+impl Ui {
+    /// Create a new [Ui] instance
+    pub const fn new() -> Self {
+        Self {
+            state: UiState::Menu,
+            home: None,
+        }
+    }
+
+    /// Initialise a UI instance without double stack allocations
+    pub unsafe fn init(p: *mut Self) {
+        core::ptr::write(p, Self::new());
+    }
+
+    /// Render the [Ui] using the current state
+    ///
+    /// Review flows block until the user approves or rejects the request,
+    /// with the result applied to the engine before returning.
+    #[inline(never)]
+    pub fn render<D: Driver, R: RngCore + CryptoRng>(&mut self, engine: &mut Engine<D, R>) {
+        // Take the current state, reviews return to the home page on exit
+        let state = core::mem::replace(&mut self.state, UiState::Menu);
+
+        match state {
+            UiState::Menu => (),
+            UiState::AddressRequest(a) => {
+                // Set address approval
+                engine.address_approve(a.show())
+            }
+            UiState::KeyRequest(a) => {
+                // Approve requested capabilities
+                if a.show() {
+                    engine.approve_scope(a.scope())
+                }
+            }
+            UiState::MemoRequest(a) => {
+                // Approve or deny memo, denial rejects the transaction
+                engine.memo_approve(a.show(engine))
+            }
+            UiState::TxRequest(a) => {
+                // Approve or deny transaction
+                match a.show(engine) {
+                    true => engine.approve(),
+                    false => engine.deny(),
+                }
+
+                self.state = UiState::Progress(Progress::new());
+            }
+            #[cfg(feature = "summary")]
+            UiState::TxSummaryRequest(a) => {
+                // Approve or deny transaction
+                match a.show(engine) {
+                    true => engine.approve(),
+                    false => engine.deny(),
+                }
+
+                self.state = UiState::Progress(Progress::new());
+            }
+            #[cfg(feature = "ident")]
+            UiState::IdentRequest(a) => {
+                // Set ident approval
+                engine.ident_approve(a.show(engine))
+            }
+            UiState::Settings(a) => {
+                // Approve or deny fog change
+                a.show(engine)
+            }
+            s @ (UiState::Progress(..) | UiState::Message(..)) => self.state = s,
+        }
+
+        // Display resulting state
+        match &self.state {
+            UiState::Progress(p) => p.show(),
+            UiState::Message(m) => m.show(is_success(engine.state())),
+            _ => self.home(),
+        }
+    }
+
+    /// Show the home page, returning to the event loop
+    fn home(&mut self) {
+        self.home
+            .get_or_insert_with(|| {
+                NbglHomeAndSettings::new().glyph(&MOB_GLYPH).infos(
+                    APP_NAME,
+                    APP_VERSION,
+                    APP_AUTHOR,
+                )
+            })
+            .show_and_return();
+    }
+}
+
+/// Progress indicator, shown while loading or signing transactions
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Progress {}
+
+impl Progress {
+    /// Create a new [Progress] instance
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Show progress spinner
+    pub fn show(&self) {
+        NbglSpinner::new().text("Processing").show();
+    }
+}
+
+/// Status message (transaction complete, rejected, etc.)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Message {
+    value: &'static str,
+}
+
+impl Message {
+    pub fn new(value: &'static str) -> Self {
+        Self { value }
+    }
+
+    /// Show status page with success or failure icon
+    pub fn show(&self, success: bool) {
+        NbglStatus::new().text(self.value).show(success);
+    }
+}
+
+/// Resolve whether an engine state is a successful outcome for status display
+fn is_success(state: State) -> bool {
+    !matches!(
+        state,
+        State::Deny
+            | State::Cancelled
+            | State::Aborted
+            | State::Error
+            | State::Address(AddressState::Denied)
+            | State::Fog(FogState::Denied)
+            | State::Ident(IdentState::Denied)
+    )
+}
@@ -0,0 +1,540 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! NBGL review flows for engine approval requests
+//!
+//! Approvers are constructed with the same arguments as their BAGL
+//! counterparts, with `show` blocking on the NBGL review and returning
+//! the user's selection.
+
+use core::fmt::Write;
+
+use heapless::{String, Vec};
+use rand_core::{CryptoRng, RngCore};
+
+use ledger_device_sdk::nbgl::{Field, NbglAddressReview, NbglChoice, NbglReview};
+
+use ledger_mob_core::{
+    apdu::{
+        key_path::{KeyPath, KEY_PATH_HARDENED},
+        tx::FogId,
+    },
+    engine::{Approvals, Driver, Engine, MemoInfo, TokenId},
+    helpers::{b58_encode_public_address, fmt_token_val},
+};
+use mc_core::account::PublicSubaddress;
+
+use super::MOB_GLYPH;
+use crate::platform::platform_set_fog_id;
+
+/// Maximum number of memos displayed in blind transaction reviews
+const MEMOS_MAX: usize = 16;
+
+/// Wallet sync approval
+///
+/// Used for user-confirmation of wallet syncing (view key and key image requests),
+/// approval applies only to the requested [Approvals] scope.
+///
+/// The account index and derivation path of the triggering request are shown
+/// where available.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SyncApprover {
+    scope: Approvals,
+    path: Option<KeyPath>,
+}
+
+impl SyncApprover {
+    /// Create a new [SyncApprover] for the provided approval scope
+    /// and request derivation path
+    pub fn new(scope: Approvals, path: Option<KeyPath>) -> Self {
+        Self { scope, path }
+    }
+
+    /// Fetch the approval scope for this request
+    pub fn scope(&self) -> Approvals {
+        self.scope
+    }
+
+    /// Show sync choice, returning true on approval
+    pub fn show(&self) -> bool {
+        let info = match self.scope {
+            s if s.contains(Approvals::WALLET_KEYS) => {
+                "Allow the connected application to export account view keys"
+            }
+            s if s.contains(Approvals::SUBADDRESS_KEYS) => {
+                "Allow the connected application to export subaddress view keys"
+            }
+            s if s.contains(Approvals::ONETIME_KEY_IMAGES) => {
+                "Allow key images for host-supplied onetime private keys"
+            }
+            _ => "Allow the connected application to retrieve account balances",
+        };
+
+        // Append account index and derivation path where available
+        let mut message = String::<160>::new();
+        let _ = message.push_str(info);
+        if let Some(p) = &self.path {
+            let _ = write!(&mut message, "\n\nAccount {} (m", p.account_index());
+            for v in p.as_slice() {
+                let _ = write!(&mut message, "/{}", v & !KEY_PATH_HARDENED);
+                if v & KEY_PATH_HARDENED != 0 {
+                    let _ = message.push('\'');
+                }
+            }
+            let _ = message.push(')');
+        }
+
+        NbglChoice::new().glyph(&MOB_GLYPH).show(
+            "Sync wallet?",
+            message.as_str(),
+            "Approve sync",
+            "Reject sync",
+        )
+    }
+}
+
+/// b58 encoded address for display
+#[derive(Clone, Debug, PartialEq)]
+pub struct Address<const N: usize> {
+    value: String<N>,
+}
+
+impl<const N: usize> Address<N> {
+    #[cfg_attr(feature = "noinline", inline(never))]
+    pub fn new(address: &PublicSubaddress, fog_report_url: &str, fog_authority_sig: &[u8]) -> Self {
+        let value =
+            b58_encode_public_address::<N>(address, fog_report_url, fog_authority_sig).unwrap();
+
+        Self { value }
+    }
+
+    /// Fetch encoded address
+    pub fn as_str(&self) -> &str {
+        self.value.as_str()
+    }
+}
+
+/// Address verification approval
+///
+/// Displays the requested account and subaddress indices and the full
+/// derived address for user comparison prior to returning a signed
+/// acknowledgement to the host
+#[derive(Clone, Debug, PartialEq)]
+pub struct AddressApprover {
+    address: Address<512>,
+    account_index: u32,
+    subaddress_index: u64,
+}
+
+impl AddressApprover {
+    /// Create a new [AddressApprover] for the provided address
+    pub fn new(address: Address<512>, account_index: u32, subaddress_index: u64) -> Self {
+        Self {
+            address,
+            account_index,
+            subaddress_index,
+        }
+    }
+
+    /// Show address review, returning true on confirmation
+    pub fn show(&self) -> bool {
+        let mut title = String::<96>::new();
+        let _ = write!(
+            &mut title,
+            "Verify MobileCoin address\nAccount {}, subaddress {}",
+            self.account_index, self.subaddress_index
+        );
+
+        NbglAddressReview::new()
+            .glyph(&MOB_GLYPH)
+            .verify_str(title.as_str())
+            .show(self.address.as_str())
+    }
+}
+
+/// Transaction approval without summary, displaying the transaction hash
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TxBlindApprover {
+    num_memos: usize,
+}
+
+impl TxBlindApprover {
+    /// Create a new [TxBlindApprover] with the provided number of memos
+    pub fn new(num_memos: usize) -> Self {
+        Self { num_memos }
+    }
+
+    /// Show blind signing review, returning true on approval
+    #[cfg_attr(feature = "noinline", inline(never))]
+    pub fn show<D: Driver, R: RngCore + CryptoRng>(&self, engine: &Engine<D, R>) -> bool {
+        let mut hash = String::<64>::new();
+        match engine.message() {
+            Some(m) => write_hex(&mut hash, m),
+            None => {
+                let _ = hash.push_str("NO_MSG");
+            }
+        }
+
+        // Format signed memos
+        let mut memos = Vec::<(String<16>, String<64>), MEMOS_MAX>::new();
+        for (i, m) in engine.memos().iter().enumerate().take(MEMOS_MAX) {
+            let mut title = String::new();
+            let _ = write!(&mut title, "Memo ({}/{})", i + 1, self.num_memos);
+
+            let _ = memos.push((title, memo_value(m, engine.show_payment_ids())));
+        }
+
+        // Fee is provided by the host and can not be checked without a summary
+        let mut fee_buff = [0u8; 32];
+        let fee = match engine.tx_expected() {
+            Some(e) => fmt_token_val(
+                e.fee_value as i64,
+                TokenId::from(e.fee_token_id),
+                &mut fee_buff,
+            ),
+            None => "Not provided",
+        };
+
+        let mut rings = String::<8>::new();
+        let _ = write!(&mut rings, "{}", engine.num_rings());
+
+        let mut fields = Vec::<Field, { MEMOS_MAX + 3 }>::new();
+        let _ = fields.push(Field {
+            name: "Transaction hash",
+            value: hash.as_str(),
+        });
+        for (name, value) in &memos {
+            let _ = fields.push(Field {
+                name: name.as_str(),
+                value: value.as_str(),
+            });
+        }
+        let _ = fields.push(Field {
+            name: "Fee (unverified)",
+            value: fee,
+        });
+        let _ = fields.push(Field {
+            name: "Rings",
+            value: rings.as_str(),
+        });
+
+        NbglReview::new()
+            .titles(
+                "Review transaction",
+                "No summary available, verify the transaction hash",
+                "Sign transaction",
+            )
+            .glyph(&MOB_GLYPH)
+            .blind()
+            .show(&fields)
+    }
+}
+
+/// Signed memo review, displaying the first memo of a transaction
+/// prior to memo signing
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemoApprover {
+    memo: MemoInfo,
+}
+
+impl MemoApprover {
+    /// Create a new [MemoApprover] for the provided memo
+    pub fn new(memo: MemoInfo) -> Self {
+        Self { memo }
+    }
+
+    /// Show memo review, returning true on approval
+    pub fn show<D: Driver, R: RngCore + CryptoRng>(&self, engine: &Engine<D, R>) -> bool {
+        let mut hash = String::<32>::new();
+        write_hex(&mut hash, self.memo.address_hash.as_ref());
+
+        let mut id = String::<20>::new();
+        if let Some(v) = self.memo.payment_id.filter(|_| engine.show_payment_ids()) {
+            let _ = write!(&mut id, "{}", v);
+        }
+
+        let mut fields = Vec::<Field, 3>::new();
+        let _ = fields.push(Field {
+            name: "Memo type",
+            value: self.memo.kind.label(),
+        });
+        let _ = fields.push(Field {
+            name: "Address hash",
+            value: hash.as_str(),
+        });
+        if !id.is_empty() {
+            let _ = fields.push(Field {
+                name: "Payment ID",
+                value: id.as_str(),
+            });
+        }
+
+        NbglReview::new()
+            .titles(
+                "Review memo",
+                "Check the memo matches the intended payment",
+                "Sign memo",
+            )
+            .glyph(&MOB_GLYPH)
+            .show(&fields)
+    }
+}
+
+/// Transaction approval with summary, listing outputs, fees and totals
+#[cfg(feature = "summary")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TxSummaryApprover {
+    num_outputs: usize,
+    num_dust: usize,
+    num_memos: usize,
+    num_totals: usize,
+    high_value: bool,
+}
+
+#[cfg(feature = "summary")]
+impl TxSummaryApprover {
+    /// Create a new [TxSummaryApprover] with the provided report entry counts
+    ///
+    /// NBGL reviews are always approved with a long-press, high-value
+    /// transactions are additionally flagged prior to the outputs.
+    pub fn new(
+        num_outputs: usize,
+        num_dust: usize,
+        num_memos: usize,
+        num_totals: usize,
+        high_value: bool,
+    ) -> Self {
+        Self {
+            num_outputs,
+            num_dust,
+            num_memos,
+            num_totals,
+            high_value,
+        }
+    }
+
+    /// Show transaction summary review, returning true on approval
+    #[cfg_attr(feature = "noinline", inline(never))]
+    pub fn show<D: Driver, R: RngCore + CryptoRng>(&self, engine: &Engine<D, R>) -> bool {
+        use alloc::{string::String, vec::Vec};
+
+        use ledger_mob_core::engine::{
+            ReservedSubaddress, TombstoneStatus, TotalKind, TransactionEntity,
+        };
+
+        let mut buff = [0u8; 32];
+
+        // Fetch transaction report
+        let report = match engine.report() {
+            Some(r) => r,
+            None => return false,
+        };
+
+        let mut entries: Vec<(String, String)> = Vec::new();
+        let mut push = |name: &str, value: &str| entries.push((name.into(), value.into()));
+
+        // Flag implausible tombstone blocks prior to outputs
+        match engine.tombstone_status() {
+            Some(TombstoneStatus::Expired) => push("Warning", "Transaction expired"),
+            Some(TombstoneStatus::FarFuture) => push("Warning", "Tombstone block far in future"),
+            _ => (),
+        }
+        if self.high_value {
+            push("Warning", "High value transaction");
+        }
+
+        // Balance changes
+        for (i, (entity, token_id, value)) in report.outputs.iter().enumerate() {
+            let value_str = fmt_token_val(*value as i64, *token_id, &mut buff);
+
+            let (heading, a) = match entity {
+                TransactionEntity::OurAddress(a) => ("Receive", a),
+                TransactionEntity::OtherAddress(a) => ("Send", a),
+                TransactionEntity::Swap => {
+                    push(&page("Swap", i, self.num_outputs), value_str);
+                    continue;
+                }
+            };
+            push(&page(heading, i, self.num_outputs), value_str);
+
+            // Name reserved subaddresses, or show the full b58 address
+            let reserved = engine
+                .reserved_subaddress(a)
+                .filter(|r| *r != ReservedSubaddress::Default);
+            match (reserved, engine.address(a)) {
+                (Some(r), _) => push("Address", r.label()),
+                (_, Some(c)) => {
+                    let b58 = b58_encode_public_address::<512>(
+                        &c.address,
+                        c.fog_report_url(),
+                        c.fog_sig.as_ref().map(|v| &v[..]).unwrap_or(&[]),
+                    );
+                    match b58 {
+                        Ok(v) => push("Address", v.as_str()),
+                        Err(_) => push("Address", "B58 ENCODE ERROR"),
+                    }
+                }
+                (_, None) => {
+                    let mut h = heapless::String::<64>::new();
+                    write_hex(&mut h, a.as_ref());
+                    push("Address hash", h.as_str());
+                }
+            }
+        }
+
+        // Folded dust outputs
+        for (i, d) in engine.dust().iter().enumerate() {
+            let value_str = fmt_token_val(d.value as i64, d.token_id, &mut buff);
+            let value = alloc::format!("{} ({} outputs)", value_str, d.count);
+            push(&page("Dust", i, self.num_dust), &value);
+        }
+
+        // Signed memos
+        for (i, m) in engine.memos().iter().enumerate() {
+            let value = memo_value(m, engine.show_payment_ids());
+            push(&page("Memo", i, self.num_memos), value.as_str());
+        }
+
+        // Fees
+        let fee_str = fmt_token_val(
+            report.network_fee.value as i64,
+            report.network_fee.token_id,
+            &mut buff,
+        );
+        push("Fee", fee_str);
+
+        // Totals, showing swap counterparty totals distinctly from our own
+        for (i, (token_id, total_kind, value)) in report.totals.iter().enumerate() {
+            let heading = match total_kind {
+                TotalKind::Ours => "Total",
+                TotalKind::Sci => "Swap Total",
+            };
+            let value_str = fmt_token_val(*value, *token_id, &mut buff);
+            push(&page(heading, i, self.num_totals), value_str);
+        }
+
+        let fields: Vec<Field> = entries
+            .iter()
+            .map(|(name, value)| Field {
+                name: name.as_str(),
+                value: value.as_str(),
+            })
+            .collect();
+
+        NbglReview::new()
+            .titles("Review transaction", "", "Sign transaction")
+            .glyph(&MOB_GLYPH)
+            .show(&fields)
+    }
+}
+
+/// Identity challenge approval
+#[cfg(feature = "ident")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct IdentApprover {}
+
+#[cfg(feature = "ident")]
+impl IdentApprover {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Show identity review, returning true on approval
+    pub fn show<D: Driver, R: RngCore + CryptoRng>(&self, engine: &Engine<D, R>) -> bool {
+        let ident = match engine.ident() {
+            Some(v) => v,
+            None => return false,
+        };
+
+        let mut challenge = String::<128>::new();
+        write_hex(&mut challenge, ident.challenge());
+
+        let fields = [
+            Field {
+                name: "Derivation",
+                value: ident.derivation().into(),
+            },
+            Field {
+                name: "URI",
+                value: ident.uri(),
+            },
+            Field {
+                name: "Challenge",
+                value: challenge.as_str(),
+            },
+        ];
+
+        NbglReview::new()
+            .titles("Identity request", "", "Sign challenge")
+            .glyph(&MOB_GLYPH)
+            .show(&fields)
+    }
+}
+
+/// Fog change approval, at the moment this only handles host fog requests
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Settings {
+    fog_id: FogId,
+}
+
+impl Settings {
+    pub fn new(fog_id: FogId) -> Self {
+        Self { fog_id }
+    }
+
+    /// Show fog change choice, updating the device fog on approval
+    pub fn show<D: Driver, R: RngCore + CryptoRng>(&self, engine: &mut Engine<D, R>) {
+        let approved = NbglChoice::new().glyph(&MOB_GLYPH).show(
+            "Update fog?",
+            fog_name(self.fog_id),
+            "Update fog",
+            "Reject",
+        );
+
+        if approved {
+            platform_set_fog_id(&self.fog_id);
+            engine.set_fog_id(self.fog_id);
+        }
+
+        engine.fog_approve(approved);
+    }
+}
+
+/// Resolve fog_id to string for display
+fn fog_name(fog_id: FogId) -> &'static str {
+    match fog_id {
+        FogId::None => "None",
+        FogId::MobMain => "MobileCoin MainNet",
+        FogId::MobTest => "MobileCoin TestNet",
+        FogId::SignalMain => "Signal MainNet",
+        FogId::SignalTest => "Signal TestNet",
+    }
+}
+
+/// Format memo kind, address hash and payment id for display
+fn memo_value(memo: &MemoInfo, show_id: bool) -> String<64> {
+    let mut s = String::new();
+
+    let _ = writeln!(&mut s, "{}", memo.kind.label());
+    write_hex(&mut s, memo.address_hash.as_ref());
+
+    if let Some(id) = memo.payment_id.filter(|_| show_id) {
+        let _ = write!(&mut s, "\nID: {}", id);
+    }
+
+    s
+}
+
+/// Format paged review entry names
+#[cfg(feature = "summary")]
+fn page(name: &str, index: usize, total: usize) -> alloc::string::String {
+    alloc::format!("{} ({}/{})", name, index + 1, total)
+}
+
+/// Write hex encoded data, truncating on overflow
+fn write_hex<const N: usize>(s: &mut String<N>, data: &[u8]) {
+    for b in data {
+        if write!(s, "{:02x}", b).is_err() {
+            break;
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 //! Ledger MobileCoin Platform Support
 
-use core::{ffi::CStr, mem::MaybeUninit};
+use core::ffi::CStr;
 
 use encdec::Encode;
 
@@ -12,7 +12,10 @@ use ledger_device_sdk::{
     nvm::{AtomicStorage, SingleStorage},
     Pic,
 };
-use ledger_proto::{apdus::DeviceInfoResp, ApduError};
+use ledger_proto::{
+    apdus::{AppFlags, DeviceInfoResp},
+    ApduError,
+};
 use ledger_secure_sdk_sys::{os_perso_derive_node_with_seed_key, HDW_ED25519_SLIP10};
 
 #[cfg(feature = "nvm")]
@@ -20,14 +23,121 @@ use ledger_mob_core::apdu::tx::FOG_IDS;
 use ledger_mob_core::{apdu::tx::FogId, engine::Driver};
 use mc_core::slip10::Slip10Key;
 
-/// Fog ID for address display
-/// Note NVM is not available under speculos so accessing this page will fault.
+use crate::consts::{
+    BLIND_SIGNING, DEFAULT_ACCOUNT_INDEX, DEFAULT_LANGUAGE, KEY_REQUEST_LIMIT, LOCK_TIMEOUT_S,
+    PIN_WALLET_KEYS,
+};
+#[cfg(feature = "nvm")]
+use crate::consts::{KEY_REQUEST_LIMITS, LOCK_TIMEOUTS_S};
+use crate::locale::Language;
+
+/// Persistent device settings
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PlatformSettings {
+    /// Fog ID for address display
+    pub fog_id: FogId,
+    /// Lock timeout in seconds (0 disables locking)
+    pub lock_timeout_s: u32,
+    /// SLIP-0010 account index used for on-device operations
+    pub default_account: u32,
+    /// Permit blind signing where TxSummary is supported
+    pub blind_signing: bool,
+    /// Require PIN confirmation for each wallet key export
+    pub pin_wallet_keys: bool,
+    /// UI language
+    pub language: Language,
+    /// Key exports permitted before re-approval is required (0 for unlimited)
+    pub key_request_limit: u32,
+}
+
+impl PlatformSettings {
+    /// Default settings, applied on install
+    pub const DEFAULT: Self = Self {
+        fog_id: FogId::MobMain,
+        lock_timeout_s: LOCK_TIMEOUT_S,
+        default_account: DEFAULT_ACCOUNT_INDEX,
+        blind_signing: BLIND_SIGNING,
+        pin_wallet_keys: PIN_WALLET_KEYS,
+        language: DEFAULT_LANGUAGE,
+        key_request_limit: KEY_REQUEST_LIMIT,
+    };
+}
+
+/// Settings storage format, versioned to allow settings to be
+/// extended without misinterpreting previously stored values
+#[cfg(feature = "nvm")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(C)]
+struct StoredSettings {
+    version: u32,
+    fog_id: u32,
+    lock_timeout_s: u32,
+    default_account: u32,
+    blind_signing: u32,
+    pin_wallet_keys: u32,
+    language: u32,
+    key_request_limit: u32,
+}
+
+/// Current settings storage version
+#[cfg(feature = "nvm")]
+const SETTINGS_VERSION: u32 = 5;
+
+#[cfg(feature = "nvm")]
+impl StoredSettings {
+    const DEFAULT: Self = Self::from_settings(&PlatformSettings::DEFAULT);
+
+    const fn from_settings(s: &PlatformSettings) -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            fog_id: s.fog_id as u32,
+            lock_timeout_s: s.lock_timeout_s,
+            default_account: s.default_account,
+            blind_signing: s.blind_signing as u32,
+            pin_wallet_keys: s.pin_wallet_keys as u32,
+            language: s.language as u32,
+            key_request_limit: s.key_request_limit,
+        }
+    }
+
+    /// Convert stored settings, falling back to defaults for
+    /// unrecognised versions or invalid values
+    fn to_settings(&self) -> PlatformSettings {
+        let mut s = PlatformSettings::DEFAULT;
+
+        if self.version != SETTINGS_VERSION {
+            return s;
+        }
+
+        if let Some(f) = FOG_IDS.get(self.fog_id as usize) {
+            s.fog_id = *f;
+        }
+        if LOCK_TIMEOUTS_S.contains(&self.lock_timeout_s) {
+            s.lock_timeout_s = self.lock_timeout_s;
+        }
+        s.default_account = self.default_account;
+        s.blind_signing = self.blind_signing != 0;
+        s.pin_wallet_keys = self.pin_wallet_keys != 0;
+        if let Ok(l) = Language::try_from(self.language) {
+            s.language = l;
+        }
+        if KEY_REQUEST_LIMITS.contains(&self.key_request_limit) {
+            s.key_request_limit = self.key_request_limit;
+        }
+
+        s
+    }
+}
+
+/// Device settings, persisted in NVM
 #[cfg(feature = "nvm")]
 #[cfg_attr(feature = "nvm", link_section = ".nvm_data")]
-static mut FOG: Pic<AtomicStorage<u32>> = Pic::new(AtomicStorage::new(&(FogId::MobMain as u32)));
+static mut SETTINGS: Pic<AtomicStorage<StoredSettings>> =
+    Pic::new(AtomicStorage::new(&StoredSettings::DEFAULT));
 
+/// Device settings, reset on each launch
 #[cfg(not(feature = "nvm"))]
-static mut FOG: MaybeUninit<FogId> = MaybeUninit::uninit();
+static mut SETTINGS: PlatformSettings = PlatformSettings::DEFAULT;
 
 /// Ledger platform driver
 pub struct LedgerDriver {}
@@ -55,39 +165,93 @@ impl Driver for LedgerDriver {
     }
 }
 
-/// Fetch fog ID from platform persistent storage
+/// Fetch settings from platform persistent storage
 #[cfg(feature = "nvm")]
-pub fn platform_get_fog_id() -> FogId {
-    let i = unsafe { *FOG.get_ref().get_ref() } as usize;
-    if i < FOG_IDS.len() {
-        FOG_IDS[i]
-    } else {
-        FogId::MobMain
-    }
+pub fn platform_get_settings() -> PlatformSettings {
+    unsafe { SETTINGS.get_ref().get_ref().to_settings() }
 }
 
-/// Update fog ID in platform persistent storage
+/// Update settings in platform persistent storage
 #[cfg(feature = "nvm")]
-pub fn platform_set_fog_id(fog_id: &FogId) {
+pub fn platform_set_settings(settings: &PlatformSettings) {
     unsafe {
-        let f = FOG.get_mut();
-        f.update(&(*fog_id as u32));
+        let s = SETTINGS.get_mut();
+        s.update(&StoredSettings::from_settings(settings));
     };
 }
 
-// `nvm` feature gate exists due to fault with nvm under
-// speculos _and_ currently on hw
+// `nvm` feature gate allows builds without persistent storage,
+// non-nvm builds reset settings to defaults on each launch
 
-/// Fetch fog ID from local variable
+/// Fetch settings from local variable
 #[cfg(not(feature = "nvm"))]
-pub fn platform_get_fog_id() -> FogId {
-    unsafe { FOG.assume_init() }
+pub fn platform_get_settings() -> PlatformSettings {
+    unsafe { SETTINGS }
 }
 
-/// Update fog ID in local variable
+/// Update settings in local variable
 #[cfg(not(feature = "nvm"))]
+pub fn platform_set_settings(settings: &PlatformSettings) {
+    unsafe { SETTINGS = *settings };
+}
+
+/// Fetch fog ID from platform settings
+pub fn platform_get_fog_id() -> FogId {
+    platform_get_settings().fog_id
+}
+
+/// Update fog ID in platform settings
 pub fn platform_set_fog_id(fog_id: &FogId) {
-    unsafe { FOG.write(*fog_id) };
+    let mut s = platform_get_settings();
+    if s.fog_id != *fog_id {
+        s.fog_id = *fog_id;
+        platform_set_settings(&s);
+    }
+}
+
+/// Update lock timeout in platform settings
+pub fn platform_set_lock_timeout(timeout_s: u32) {
+    let mut s = platform_get_settings();
+    if s.lock_timeout_s != timeout_s {
+        s.lock_timeout_s = timeout_s;
+        platform_set_settings(&s);
+    }
+}
+
+/// Update blind signing permission in platform settings
+pub fn platform_set_blind_signing(enabled: bool) {
+    let mut s = platform_get_settings();
+    if s.blind_signing != enabled {
+        s.blind_signing = enabled;
+        platform_set_settings(&s);
+    }
+}
+
+/// Update wallet key export PIN confirmation in platform settings
+pub fn platform_set_pin_wallet_keys(enabled: bool) {
+    let mut s = platform_get_settings();
+    if s.pin_wallet_keys != enabled {
+        s.pin_wallet_keys = enabled;
+        platform_set_settings(&s);
+    }
+}
+
+/// Update UI language in platform settings
+pub fn platform_set_language(language: Language) {
+    let mut s = platform_get_settings();
+    if s.language != language {
+        s.language = language;
+        platform_set_settings(&s);
+    }
+}
+
+/// Update key request limit in platform settings
+pub fn platform_set_key_request_limit(limit: u32) {
+    let mut s = platform_get_settings();
+    if s.key_request_limit != limit {
+        s.key_request_limit = limit;
+        platform_set_settings(&s);
+    }
 }
 
 // Global allocator configuration
@@ -118,6 +282,12 @@ pub(crate) mod allocator {
         unsafe { HEAP.init(HEAP_MEM.as_ptr() as usize, HEAP_SIZE) }
     }
 
+    /// Fetch heap usage as `(used, free)` bytes
+    #[cfg(any(debug_assertions, feature = "pre-release"))]
+    pub fn usage() -> (usize, usize) {
+        (HEAP.used(), HEAP.free())
+    }
+
     /// Noop critical section
     /// (_should_ okay as we only -have- one thread)
     struct MyCriticalSection;
@@ -134,11 +304,81 @@ pub(crate) mod allocator {
     }
 }
 
+/// Stack instrumentation for debug and `pre-release` builds
+///
+/// Unused stack is filled with a canary pattern at app start, with the
+/// high-water mark found by scanning for the first overwritten word.
+#[cfg(any(debug_assertions, feature = "pre-release"))]
+pub(crate) mod stack {
+    use core::ptr::{addr_of, read_volatile, write_volatile};
+
+    /// Pattern written to unused stack
+    const CANARY: u32 = 0xa5a5_a5a5;
+
+    /// Bytes below the current stack pointer left untouched by [fill],
+    /// covering the frame of the fill call itself
+    const FILL_MARGIN: usize = 256;
+
+    extern "C" {
+        /// SDK stack canary (bottom of stack), provided by the linker script
+        static app_stack_canary: u32;
+    }
+
+    /// Stack pointer at fill, used as the top of the measured region
+    static mut STACK_TOP: usize = 0;
+
+    /// Fetch the lowest stack address available for instrumentation,
+    /// skipping the SDK canary word
+    fn bottom() -> usize {
+        unsafe { addr_of!(app_stack_canary) as usize + 4 }
+    }
+
+    /// Fill unused stack with the canary pattern, must be called once
+    /// at app start
+    #[inline(never)]
+    pub fn fill() {
+        let marker = 0u32;
+        let top = addr_of!(marker) as usize & !3;
+
+        let mut p = bottom();
+        while p < top - FILL_MARGIN {
+            unsafe { write_volatile(p as *mut u32, CANARY) };
+            p += 4;
+        }
+
+        unsafe { STACK_TOP = top };
+    }
+
+    /// Fetch stack usage as `(used, free)` bytes, where `used` is the
+    /// high-water mark since [fill] was called
+    pub fn usage() -> (usize, usize) {
+        let top = unsafe { STACK_TOP };
+        if top == 0 {
+            return (0, 0);
+        }
+
+        let start = bottom();
+        let mut p = start;
+        while p < top && unsafe { read_volatile(p as *const u32) } == CANARY {
+            p += 4;
+        }
+
+        (top - p, p - start)
+    }
+}
+
 /// Blocking request for pin validation to unlock
 pub fn request_pin_validation() {
     UxEvent::ValidatePIN.request();
 }
 
+/// Fetch Ledger standard application flags from the OS, as reported by
+/// C SDK applications in response to the standard app info request
+pub fn os_app_flags() -> AppFlags {
+    let flags = unsafe { ledger_secure_sdk_sys::os_flags() };
+    AppFlags::from_bits_truncate(flags as u8)
+}
+
 pub fn fetch_encode_device_info(buff: &mut [u8]) -> Result<usize, ApduError> {
     // Fetch information from OS
     let mut mcu_version_raw = [0u8; 32];
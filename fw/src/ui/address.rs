@@ -7,17 +7,18 @@ use rand_core::{CryptoRng, RngCore};
 use ledger_device_sdk::buttons::ButtonEvent;
 use ledger_device_sdk::ui::{
     bagls::*,
-    layout::{Draw, Layout, Location, StringPlace},
+    layout::{Draw, Layout, Location},
     screen_util,
 };
 
 use ledger_mob_core::{
-    engine::{Driver, Engine, FogId},
+    engine::{Driver, Engine},
     helpers::b58_encode_public_address,
 };
 use mc_core::account::PublicSubaddress;
 
-use super::{clear_screen, UiResult};
+use super::{clear_screen, StringPlace, UiResult};
+use crate::locale::{text, Text};
 
 /// Pager for rendering b58 encoded addresses
 #[derive(Clone, Debug, PartialEq)]
@@ -25,6 +26,7 @@ pub struct Address<const N: usize> {
     value: String<N>,
     page: usize,
     num_pages: usize,
+    arrows: bool,
 }
 
 const NUM_LINES: usize = 4;
@@ -34,10 +36,10 @@ const PAGE_LEN: usize = LINE_LEN * NUM_LINES;
 
 impl<const N: usize> Address<N> {
     #[cfg_attr(feature = "noinline", inline(never))]
-    pub fn new(address: &PublicSubaddress, fog_id: FogId, fog_authority_sig: &[u8]) -> Self {
+    pub fn new(address: &PublicSubaddress, fog_report_url: &str, fog_authority_sig: &[u8]) -> Self {
         // Encode address to string
         let value =
-            b58_encode_public_address::<N>(address, fog_id.url(), fog_authority_sig).unwrap();
+            b58_encode_public_address::<N>(address, fog_report_url, fog_authority_sig).unwrap();
 
         // Compute number of pages for display
         let num_pages = value.as_bytes().chunks(PAGE_LEN).count();
@@ -47,9 +49,27 @@ impl<const N: usize> Address<N> {
             value,
             num_pages,
             page: 0,
+            arrows: false,
         }
     }
 
+    /// Always display paging arrows, for use within flows continuing
+    /// before the first or after the last page
+    pub fn with_arrows(mut self) -> Self {
+        self.arrows = true;
+        self
+    }
+
+    /// Check whether the first page is displayed
+    pub fn is_first_page(&self) -> bool {
+        self.page == 0
+    }
+
+    /// Check whether the last page is displayed
+    pub fn is_last_page(&self) -> bool {
+        self.page + 1 >= self.num_pages
+    }
+
     pub fn update(&mut self, btn: &ButtonEvent) -> UiResult<()> {
         // Update paging based on button inputs
         match btn {
@@ -74,10 +94,10 @@ impl<const N: usize> Address<N> {
         clear_screen();
 
         // Show paging arrows
-        if self.page != 0 {
+        if self.arrows || self.page != 0 {
             LEFT_ARROW.display();
         }
-        if self.page < self.num_pages - 1 {
+        if self.arrows || self.page < self.num_pages - 1 {
             RIGHT_ARROW.display();
         }
 
@@ -86,7 +106,12 @@ impl<const N: usize> Address<N> {
 
         // Set title
         let mut title_buff = [0u8; 20];
-        line_buff[0] = fmt_title("Address", self.page, self.num_pages, &mut title_buff);
+        line_buff[0] = fmt_title(
+            text(Text::Address),
+            self.page,
+            self.num_pages,
+            &mut title_buff,
+        );
 
         // Write address line by line
         let page = &self.value[self.page * PAGE_LEN..];
@@ -0,0 +1,149 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+use core::str::from_utf8;
+
+use emstr::EncodeStr;
+use rand_core::{CryptoRng, RngCore};
+
+use ledger_device_sdk::buttons::ButtonEvent;
+
+use ledger_device_sdk::ui::{
+    bagls::*,
+    layout::{Draw, Layout, Location},
+    screen_util,
+};
+
+use ledger_mob_core::engine::{Driver, Engine};
+
+use super::{clear_screen, Address, StringPlace, UiResult};
+use crate::locale::{text, Text};
+
+/// Address verification approval element
+///
+/// Displays the requested account and subaddress indices and the derived
+/// address for user comparison prior to returning a signed acknowledgement
+/// to the host
+#[derive(Clone, Debug, PartialEq)]
+pub struct AddressApprover {
+    state: ApproverState,
+    address: Address<512>,
+    account_index: u32,
+    subaddress_index: u64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ApproverState {
+    Init,
+    ShowAddress,
+    Allow,
+    Deny,
+}
+
+impl AddressApprover {
+    /// Create a new [AddressApprover] for the provided address
+    pub fn new(address: Address<512>, account_index: u32, subaddress_index: u64) -> Self {
+        Self {
+            state: ApproverState::Init,
+            address,
+            account_index,
+            subaddress_index,
+        }
+    }
+
+    /// Update [AddressApprover] state, handling button events and returning the
+    /// approval state on exit
+    pub fn update(&mut self, btn: &ButtonEvent) -> UiResult<bool> {
+        use ApproverState::*;
+        use ButtonEvent::*;
+
+        let state = match (self.state, btn) {
+            (Init, RightButtonRelease) => ApproverState::ShowAddress,
+
+            // Page through address, moving on at either end
+            (ShowAddress, LeftButtonRelease | RightButtonRelease) => match self.address.update(btn)
+            {
+                UiResult::Update => return UiResult::Update,
+                _ if matches!(btn, LeftButtonRelease) => ApproverState::Init,
+                _ => ApproverState::Allow,
+            },
+
+            (Allow, LeftButtonRelease) => ApproverState::ShowAddress,
+            (Allow, BothButtonsRelease) => return UiResult::Exit(true),
+            (Allow, RightButtonRelease) => ApproverState::Deny,
+
+            (Deny, LeftButtonRelease) => ApproverState::Allow,
+            (Deny, BothButtonsRelease) => return UiResult::Exit(false),
+
+            _ => self.state,
+        };
+
+        if state != self.state {
+            self.state = state;
+            UiResult::Update
+        } else {
+            UiResult::None
+        }
+    }
+
+    /// Render the [AddressApprover] based on it's current internal state
+    pub fn render<D: Driver, R: RngCore + CryptoRng>(&self, engine: &Engine<D, R>) {
+        use ApproverState::*;
+
+        clear_screen();
+
+        // Display arrows
+        if self.state != ApproverState::Init {
+            LEFT_ARROW.shift_v(0).display();
+        }
+        if self.state != ApproverState::Deny {
+            RIGHT_ARROW.shift_v(0).display();
+        }
+
+        // Display information
+        match self.state {
+            Init => {
+                let mut account_buff = [0u8; 24];
+                let mut subaddress_buff = [0u8; 32];
+
+                [
+                    text(Text::VerifyAddress),
+                    fmt_index(
+                        text(Text::Account),
+                        self.account_index as u64,
+                        &mut account_buff,
+                    ),
+                    fmt_index(
+                        text(Text::Subaddress),
+                        self.subaddress_index,
+                        &mut subaddress_buff,
+                    ),
+                ]
+                .place(Location::Middle, Layout::Centered, false);
+            }
+            Deny => {
+                text(Text::RejectAddress).place(Location::Custom(16), Layout::Centered, false);
+                CROSS_ICON.shift_v(8).shift_h((128 - 16) / 2).display();
+            }
+            Allow => {
+                text(Text::ConfirmAddress).place(Location::Custom(16), Layout::Centered, false);
+                CHECKMARK_ICON.shift_v(8).shift_h((128 - 16) / 2).display();
+            }
+            // Address pager handles its own rendering
+            ShowAddress => self.address.render(engine),
+        }
+
+        screen_util::screen_update();
+    }
+}
+
+fn fmt_index<'a>(name: &str, index: u64, buff: &'a mut [u8]) -> &'a str {
+    let n = match emstr::write!(&mut buff[..], name, index) {
+        Ok(v) => v,
+        Err(_) => return "ENCODE_ERR",
+    };
+
+    match from_utf8(&buff[..n]) {
+        Ok(v) => v,
+        Err(_) => "INVALID_UTF8",
+    }
+}
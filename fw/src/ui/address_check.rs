@@ -0,0 +1,122 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+use rand_core::{CryptoRng, RngCore};
+
+use ledger_device_sdk::buttons::ButtonEvent;
+
+use ledger_device_sdk::ui::{
+    bagls::*,
+    layout::{Draw, Layout, Location},
+    screen_util,
+};
+
+use ledger_mob_core::engine::{Driver, Engine};
+
+use super::{clear_screen, Address, StringPlace, UiResult};
+use crate::locale::{text, Text};
+
+/// Address check (recovery check) element
+///
+/// Displays the default public address re-derived from the device seed
+/// for comparison against a previously recorded value, confirming the seed
+/// matches the wallet without exporting keys. Exits with `Some(true)` where
+/// the address matches, `Some(false)` where it differs, or `None` if the
+/// check is cancelled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AddressCheck {
+    state: CheckState,
+    address: Address<512>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum CheckState {
+    Init,
+    ShowAddress,
+    Matches,
+    Differs,
+}
+
+impl AddressCheck {
+    /// Create a new [AddressCheck] for the provided (default) address
+    pub fn new(address: Address<512>) -> Self {
+        Self {
+            state: CheckState::Init,
+            address: address.with_arrows(),
+        }
+    }
+
+    /// Update [AddressCheck] state, handling button events and returning
+    /// the check result on exit
+    pub fn update(&mut self, btn: &ButtonEvent) -> UiResult<Option<bool>> {
+        use ButtonEvent::*;
+        use CheckState::*;
+
+        let state = match (self.state, btn) {
+            (Init, RightButtonRelease) => ShowAddress,
+            (Init, BothButtonsRelease) => return UiResult::Exit(None),
+
+            // Page through address, continuing to the result pages
+            (ShowAddress, LeftButtonRelease) if self.address.is_first_page() => Init,
+            (ShowAddress, RightButtonRelease) if self.address.is_last_page() => Matches,
+            (ShowAddress, LeftButtonRelease | RightButtonRelease) => {
+                return self.address.update(btn).map_exit(|_| None);
+            }
+
+            (Matches, LeftButtonRelease) => ShowAddress,
+            (Matches, BothButtonsRelease) => return UiResult::Exit(Some(true)),
+            (Matches, RightButtonRelease) => Differs,
+
+            (Differs, LeftButtonRelease) => Matches,
+            (Differs, BothButtonsRelease) => return UiResult::Exit(Some(false)),
+
+            _ => self.state,
+        };
+
+        if state != self.state {
+            self.state = state;
+            UiResult::Update
+        } else {
+            UiResult::None
+        }
+    }
+
+    /// Render the [AddressCheck] based on it's current internal state
+    pub fn render<D: Driver, R: RngCore + CryptoRng>(&self, engine: &Engine<D, R>) {
+        use CheckState::*;
+
+        clear_screen();
+
+        // Display arrows
+        if self.state != Init {
+            LEFT_ARROW.shift_v(0).display();
+        }
+        if self.state != Differs {
+            RIGHT_ARROW.shift_v(0).display();
+        }
+
+        // Display information
+        match self.state {
+            Init => {
+                [
+                    text(Text::CheckAddress),
+                    "",
+                    text(Text::CompareAddress1),
+                    text(Text::CompareAddress2),
+                ]
+                .place(Location::Middle, Layout::Centered, false);
+            }
+            Matches => {
+                text(Text::AddressMatches).place(Location::Custom(16), Layout::Centered, false);
+                CHECKMARK_ICON.shift_v(8).shift_h((128 - 16) / 2).display();
+            }
+            Differs => {
+                text(Text::AddressDiffers).place(Location::Custom(16), Layout::Centered, false);
+                CROSS_ICON.shift_v(8).shift_h((128 - 16) / 2).display();
+            }
+            // Address pages are rendered by the address pager
+            ShowAddress => self.address.render(engine),
+        }
+
+        screen_util::screen_update();
+    }
+}
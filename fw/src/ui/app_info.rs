@@ -4,13 +4,13 @@ use rand_core::{CryptoRng, RngCore};
 
 use ledger_device_sdk::buttons::ButtonEvent;
 use ledger_device_sdk::ui::{
-    layout::{Layout, Location, StringPlace},
+    layout::{Layout, Location},
     screen_util,
 };
 
 use ledger_mob_core::engine::{Driver, Engine};
 
-use super::{clear_screen, UiResult};
+use super::{clear_screen, StringPlace, UiResult};
 use crate::consts::{BUILD_TIME, GIT_VERSION};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
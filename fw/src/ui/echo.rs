@@ -0,0 +1,59 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Screen text echo for automated tests (`speculos` builds only)
+//!
+//! Wraps SDK string placement to record text drawn since the last
+//! [clear_screen][super::clear_screen], returned to the host via
+//! [UiEchoReq][ledger_mob_core::apdu::ui_echo::UiEchoReq] so tests can
+//! check approvers show the intended values without image diffing.
+
+use core::ptr::addr_of_mut;
+
+use ledger_device_sdk::ui::layout::{self, Layout, Location};
+
+use ledger_mob_core::apdu::ui_echo::UiEchoResp;
+
+/// Text rendered on the current page
+static mut SCREEN: UiEchoResp = UiEchoResp::new();
+
+/// Fetch text rendered on the current page
+pub fn screen() -> &'static UiEchoResp {
+    // Safe as the UI and APDU handling are single threaded
+    unsafe { &*addr_of_mut!(SCREEN) }
+}
+
+/// Reset recorded text, called on screen clear
+pub fn reset() {
+    unsafe { (*addr_of_mut!(SCREEN)).clear() }
+}
+
+/// Record a rendered line, skipping empty spacer lines
+fn record(line: &str) {
+    if line.is_empty() {
+        return;
+    }
+
+    unsafe { (*addr_of_mut!(SCREEN)).push(line) }
+}
+
+/// Recording replacement for [layout::StringPlace], used in place of the
+/// SDK trait for `speculos` builds
+pub trait StringPlace {
+    fn place(&self, loc: Location, layout: Layout, bold: bool);
+}
+
+impl StringPlace for &str {
+    fn place(&self, loc: Location, layout: Layout, bold: bool) {
+        record(self);
+        layout::StringPlace::place(self, loc, layout, bold)
+    }
+}
+
+impl StringPlace for [&str] {
+    fn place(&self, loc: Location, layout: Layout, bold: bool) {
+        for l in self {
+            record(l);
+        }
+        layout::StringPlace::place(self, loc, layout, bold)
+    }
+}
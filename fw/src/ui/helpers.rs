@@ -4,18 +4,27 @@
 use ledger_device_sdk::ui::{
     bagls::*,
     bitmaps, gadgets,
-    layout::{Draw, Layout, Location, StringPlace},
+    layout::{Draw, Layout, Location},
     screen_util,
 };
 
-const TX_REQ_APPROVE: &str = "Approve Transaction?";
-const TX_REQ_DENY: &str = "Reject Transaction?";
+use super::StringPlace;
+use crate::locale::{text, Text};
 
 /// Clear screen wrapper that works both on hardware and speculos
 /// (required as speculos doesn't support the full screen clear syscall,
 /// and we want to run _exactly_ the same code on both)
 pub fn clear_screen() {
     gadgets::clear_screen();
+    echo_reset();
+}
+
+/// Reset text recorded for the current page (see [super::echo]),
+/// a no-op outside `speculos` builds
+#[inline]
+pub fn echo_reset() {
+    #[cfg(feature = "speculos")]
+    super::echo::reset();
 }
 
 /// Convert to hex. Returns a static buffer of N bytes
@@ -87,12 +96,12 @@ pub fn show_var(loc: Location, kind: &str, val: u16) {
 
 /// Tx approve page
 pub fn tx_approve_page() {
-    TX_REQ_APPROVE.place(Location::Custom(14), Layout::Centered, false);
+    text(Text::ApproveTransaction).place(Location::Custom(14), Layout::Centered, false);
     CHECKMARK_ICON.shift_v(6).shift_h((128 - 16) / 2).display();
 }
 
 /// Tx deny page
 pub fn tx_deny_page() {
-    TX_REQ_DENY.place(Location::Custom(14), Layout::Centered, false);
+    text(Text::RejectTransaction).place(Location::Custom(14), Layout::Centered, false);
     CROSS_ICON.shift_v(6).shift_h((128 - 16) / 2).display();
 }
@@ -7,14 +7,15 @@ use ledger_device_sdk::buttons::ButtonEvent;
 
 use ledger_device_sdk::ui::{
     bagls::*,
-    layout::{Draw, Layout, Location, StringPlace},
+    layout::{Draw, Layout, Location},
     screen_util,
 };
 
-use super::clear_screen;
+use super::{clear_screen, StringPlace};
 use ledger_mob_core::engine::{Driver, Engine};
 
 use super::UiResult;
+use crate::locale::{text, Text};
 
 /// UI Approval Element
 ///
@@ -93,7 +94,14 @@ impl IdentApprover {
         // Display information
         match self.state {
             Init => {
-                ["Identity", "Request"].place(Location::Middle, Layout::Centered, false);
+                let ident = engine.ident().unwrap();
+                let derivation: &str = ident.derivation().into();
+
+                [text(Text::Identity), text(Text::Request), derivation].place(
+                    Location::Middle,
+                    Layout::Centered,
+                    false,
+                );
             }
             Uri => {
                 let ident = engine.ident().unwrap();
@@ -118,14 +126,18 @@ impl IdentApprover {
             Challenge => {
                 let ident = engine.ident().unwrap();
                 let challenge_str = fmt_challenge(ident.challenge(), &mut buff);
-                ["Challenge", challenge_str].place(Location::Middle, Layout::Centered, false);
+                [text(Text::Challenge), challenge_str].place(
+                    Location::Middle,
+                    Layout::Centered,
+                    false,
+                );
             }
             Deny => {
-                "Reject challenge?".place(Location::Custom(16), Layout::Centered, false);
+                text(Text::RejectChallenge).place(Location::Custom(16), Layout::Centered, false);
                 CROSS_ICON.shift_v(8).shift_h((128 - 16) / 2).display();
             }
             Allow => {
-                "Sign challenge?".place(Location::Custom(16), Layout::Centered, false);
+                text(Text::SignChallenge).place(Location::Custom(16), Layout::Centered, false);
                 CHECKMARK_ICON.shift_v(8).shift_h((128 - 16) / 2).display();
             }
         }
@@ -0,0 +1,68 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+use core::str::from_utf8;
+
+use emstr::{helpers::Hex, EncodeStr};
+
+use ledger_device_sdk::ui::layout::{Layout, Location};
+
+use super::StringPlace;
+use crate::locale::{text, Text};
+
+use ledger_mob_core::engine::MemoInfo;
+
+/// Render a page describing a signed memo, for display during transaction approval
+///
+/// Payment request / intent ids are shown where `show_id` is set.
+pub fn memo_page(memo: &MemoInfo, index: usize, total: usize, show_id: bool) {
+    let mut title_buff = [0u8; 20];
+    let mut hash_buff = [0u8; 20];
+    let mut id_buff = [0u8; 24];
+
+    let title_str = fmt_str(
+        emstr::write!(
+            &mut title_buff[..],
+            text(Text::Memo),
+            "  (",
+            index + 1,
+            '/',
+            total,
+            ')'
+        ),
+        &title_buff,
+    );
+
+    let h: &[u8] = memo.address_hash.as_ref();
+    let hash_str = fmt_str(
+        emstr::write!(
+            &mut hash_buff[..],
+            Hex(&h[..4]),
+            "...",
+            Hex(&h[h.len() - 4..])
+        ),
+        &hash_buff,
+    );
+
+    let id_str = match memo.payment_id.filter(|_| show_id) {
+        Some(id) => fmt_str(emstr::write!(&mut id_buff[..], "ID: ", id), &id_buff),
+        None => "",
+    };
+
+    [title_str, memo.kind.label(), hash_str, id_str].place(
+        Location::Middle,
+        Layout::Centered,
+        false,
+    );
+}
+
+fn fmt_str<E>(r: Result<usize, E>, buff: &[u8]) -> &str {
+    let n = match r {
+        Ok(v) => v,
+        Err(_) => return "ENCODE_ERR",
+    };
+
+    match from_utf8(&buff[..n]) {
+        Ok(v) => v,
+        Err(_) => "INVALID_UTF8",
+    }
+}
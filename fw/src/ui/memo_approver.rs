@@ -0,0 +1,111 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+use rand_core::{CryptoRng, RngCore};
+
+use ledger_device_sdk::buttons::ButtonEvent;
+
+use ledger_device_sdk::ui::{
+    bagls::*,
+    layout::{Draw, Layout, Location},
+    screen_util,
+};
+
+use ledger_mob_core::engine::{Driver, Engine, MemoInfo};
+
+use super::{clear_screen, memo_page, StringPlace, UiResult};
+use crate::locale::{text, Text};
+
+/// Signed memo review element
+///
+/// Displays the first memo of a transaction (kind, address short hash
+/// and payment id) for approval prior to memo signing
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemoApprover {
+    state: ApproverState,
+    memo: MemoInfo,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ApproverState {
+    Init,
+    ShowMemo,
+    Allow,
+    Deny,
+}
+
+impl MemoApprover {
+    /// Create a new [MemoApprover] for the provided memo
+    pub fn new(memo: MemoInfo) -> Self {
+        Self {
+            state: ApproverState::Init,
+            memo,
+        }
+    }
+
+    /// Update [MemoApprover] state, handling button events and returning the
+    /// approval state on exit
+    pub fn update(&mut self, btn: &ButtonEvent) -> UiResult<bool> {
+        use ApproverState::*;
+        use ButtonEvent::*;
+
+        let state = match (self.state, btn) {
+            (Init, RightButtonRelease) => ShowMemo,
+
+            (ShowMemo, LeftButtonRelease) => Init,
+            (ShowMemo, RightButtonRelease) => Allow,
+
+            (Allow, LeftButtonRelease) => ShowMemo,
+            (Allow, BothButtonsRelease) => return UiResult::Exit(true),
+            (Allow, RightButtonRelease) => Deny,
+
+            (Deny, LeftButtonRelease) => Allow,
+            (Deny, BothButtonsRelease) => return UiResult::Exit(false),
+
+            _ => self.state,
+        };
+
+        if state != self.state {
+            self.state = state;
+            UiResult::Update
+        } else {
+            UiResult::None
+        }
+    }
+
+    /// Render the [MemoApprover] based on it's current internal state
+    pub fn render<D: Driver, R: RngCore + CryptoRng>(&self, engine: &Engine<D, R>) {
+        use ApproverState::*;
+
+        clear_screen();
+
+        // Display arrows
+        if self.state != Init {
+            LEFT_ARROW.shift_v(0).display();
+        }
+        if self.state != Deny {
+            RIGHT_ARROW.shift_v(0).display();
+        }
+
+        // Display information
+        match self.state {
+            Init => {
+                [text(Text::Review), text(Text::Memo)].place(
+                    Location::Middle,
+                    Layout::Centered,
+                    false,
+                );
+            }
+            ShowMemo => memo_page(&self.memo, 0, 1, engine.show_payment_ids()),
+            Deny => {
+                text(Text::RejectMemo).place(Location::Custom(16), Layout::Centered, false);
+                CROSS_ICON.shift_v(8).shift_h((128 - 16) / 2).display();
+            }
+            Allow => {
+                text(Text::SignMemo).place(Location::Custom(16), Layout::Centered, false);
+                CHECKMARK_ICON.shift_v(8).shift_h((128 - 16) / 2).display();
+            }
+        }
+
+        screen_util::screen_update();
+    }
+}
@@ -1,24 +1,29 @@
 // Copyright (c) 2022-2023 The MobileCoin Foundation
 
+use core::str::from_utf8;
+
+use emstr::EncodeStr;
 use rand_core::{CryptoRng, RngCore};
 use strum::EnumCount;
 
 use ledger_device_sdk::buttons::ButtonEvent;
 use ledger_device_sdk::ui::{
     bagls::*,
-    layout::{Draw, Layout, Location, StringPlace},
+    layout::{Draw, Layout, Location},
     screen_util,
 };
 
 use ledger_mob_core::engine::{Driver, Engine};
 
-use super::{clear_screen, UiResult};
+use super::{clear_screen, StringPlace, UiResult};
 use crate::consts::{APP_VERSION, MOB32X32};
+use crate::locale::{text, Text};
 
 #[derive(Copy, Clone, Debug, PartialEq, EnumCount)]
 pub enum MenuState {
     Hello,
     Address,
+    CheckAddress,
     Version,
     Settings,
     Exit,
@@ -33,6 +38,7 @@ pub struct UiMenu {
 pub const MENU_STATES: &[MenuState] = &[
     MenuState::Hello,
     MenuState::Address,
+    MenuState::CheckAddress,
     MenuState::Version,
     MenuState::Settings,
     MenuState::Exit,
@@ -74,7 +80,7 @@ impl UiMenu {
         UiResult::Update
     }
 
-    pub fn render<D: Driver, R: RngCore + CryptoRng>(&self, _engine: &Engine<D, R>) {
+    pub fn render<D: Driver, R: RngCore + CryptoRng>(&self, engine: &Engine<D, R>) {
         let state = self.state();
 
         // Clear screen
@@ -89,17 +95,36 @@ impl UiMenu {
             MenuState::Hello => {
                 MOB32X32.draw((128 - 32) / 2, 2);
                 "MobileCoin".place(Location::Custom(38), Layout::Centered, true);
-                "is ready".place(Location::Custom(50), Layout::Centered, false);
+
+                // Show key requests serviced since unlock, so background
+                // wallet scanning is visible to the user
+                let mut buff = [0u8; 24];
+                let status = match engine.request_count() {
+                    0 => text(Text::IsReady),
+                    n => fmt_requests(n, &mut buff),
+                };
+                status.place(Location::Custom(50), Layout::Centered, false);
             }
             MenuState::Address => {
                 CERTIFICATE_ICON
                     .shift_v(ICON_OFFSET)
                     .shift_h((128 - 16) / 2)
                     .display();
-                "Address".place(Location::Custom(TEXT_OFFSET), Layout::Centered, true);
+                text(Text::Address).place(Location::Custom(TEXT_OFFSET), Layout::Centered, true);
+            }
+            MenuState::CheckAddress => {
+                CHECKMARK_ICON
+                    .shift_v(ICON_OFFSET)
+                    .shift_h((128 - 16) / 2)
+                    .display();
+                text(Text::CheckAddress).place(
+                    Location::Custom(TEXT_OFFSET),
+                    Layout::Centered,
+                    true,
+                );
             }
             MenuState::Version => {
-                "Version".place(Location::Custom(20), Layout::Centered, true);
+                text(Text::Version).place(Location::Custom(20), Layout::Centered, true);
                 APP_VERSION.place(Location::Custom(36), Layout::Centered, false);
             }
             MenuState::Settings => {
@@ -107,14 +132,14 @@ impl UiMenu {
                     .shift_v(ICON_OFFSET)
                     .shift_h((128 - 16) / 2)
                     .display();
-                "Settings".place(Location::Custom(TEXT_OFFSET), Layout::Centered, true);
+                text(Text::Settings).place(Location::Custom(TEXT_OFFSET), Layout::Centered, true);
             }
             MenuState::Exit => {
                 DASHBOARD_X_ICON
                     .shift_v(ICON_OFFSET)
                     .shift_h((128 - 16) / 2)
                     .display();
-                "Exit".place(Location::Custom(TEXT_OFFSET), Layout::Centered, true)
+                text(Text::Exit).place(Location::Custom(TEXT_OFFSET), Layout::Centered, true)
             }
         }
 
@@ -122,3 +147,16 @@ impl UiMenu {
         screen_util::screen_update();
     }
 }
+
+/// Format serviced key request count for display
+fn fmt_requests(count: u32, buff: &mut [u8]) -> &str {
+    let n = match emstr::write!(&mut buff[..], text(Text::KeyRequests), count) {
+        Ok(v) => v,
+        Err(_) => return "ENCODE_ERR",
+    };
+
+    match from_utf8(&buff[..n]) {
+        Ok(v) => v,
+        Err(_) => "INVALID_UTF8",
+    }
+}
@@ -22,12 +22,24 @@ pub use progress::*;
 mod message;
 pub use message::*;
 
+mod memo;
+pub use memo::*;
+
+mod memo_approver;
+pub use memo_approver::*;
+
 mod tx_blind_approver;
 pub use tx_blind_approver::*;
 
 mod address;
 pub use address::*;
 
+mod address_approver;
+pub use address_approver::*;
+
+mod address_check;
+pub use address_check::*;
+
 mod app_info;
 pub use app_info::*;
 
@@ -44,6 +56,15 @@ mod ident_approver;
 #[cfg(feature = "ident")]
 pub use ident_approver::*;
 
+#[cfg(feature = "speculos")]
+pub mod echo;
+
+// Record placed text for speculos builds (see [echo])
+#[cfg(feature = "speculos")]
+pub use echo::StringPlace;
+#[cfg(not(feature = "speculos"))]
+pub use ledger_device_sdk::ui::layout::StringPlace;
+
 /// Top level User Interface implementation
 pub struct Ui {
     /// Current top-level state of UI
@@ -61,9 +82,18 @@ pub enum UiState {
     /// Showing a b58 address
     Address(Address<512>),
 
+    /// Checking the default address against a recorded value
+    AddressCheck(AddressCheck),
+
+    /// Address verification request, awaiting user input
+    AddressRequest(AddressApprover),
+
     /// Request for view keys, awaiting user input
     KeyRequest(SyncApprover),
 
+    /// Signed memo review, awaiting user input
+    MemoRequest(MemoApprover),
+
     /// Transaction request without summary, awaiting user input
     TxRequest(TxBlindApprover),
 
@@ -97,6 +127,18 @@ impl UiState {
         matches!(self, UiState::KeyRequest(..))
     }
 
+    pub fn is_address_request(&self) -> bool {
+        matches!(self, UiState::AddressRequest(..))
+    }
+
+    pub fn is_memo_request(&self) -> bool {
+        matches!(self, UiState::MemoRequest(..))
+    }
+
+    pub fn is_settings(&self) -> bool {
+        matches!(self, UiState::Settings(..))
+    }
+
     pub fn is_tx_request(&self) -> bool {
         match self {
             UiState::TxRequest(..) => true,
@@ -140,7 +182,10 @@ impl Ui {
         match &mut self.state {
             UiState::Menu => self.menu.render(engine),
             UiState::Address(a) => a.render(engine),
+            UiState::AddressCheck(a) => a.render(engine),
+            UiState::AddressRequest(a) => a.render(engine),
             UiState::KeyRequest(a) => a.render(engine),
+            UiState::MemoRequest(a) => a.render(engine),
             UiState::TxRequest(a) => a.render(engine),
             #[cfg(feature = "summary")]
             UiState::TxSummaryRequest(a) => a.render(engine),
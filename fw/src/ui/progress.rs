@@ -1,18 +1,25 @@
 // Copyright (c) 2022-2023 The MobileCoin Foundation
 
+#[cfg(feature = "mlsag")]
+use emstr::EncodeStr;
 use rand_core::{CryptoRng, RngCore};
 
 use ledger_device_sdk::buttons::ButtonEvent;
 
 use ledger_device_sdk::ui::{
     bagls::*,
-    layout::{Draw, Layout, Location, StringPlace},
+    layout::{Draw, Layout, Location},
     screen_util,
 };
 
-use super::{clear_screen, UiResult};
+use super::{clear_screen, echo_reset, StringPlace, UiResult};
+use crate::locale::{text, Text};
+#[cfg(feature = "mlsag")]
+use ledger_mob_core::engine::RingState;
 use ledger_mob_core::engine::{Driver, Engine, State};
 
+/// Progress indicator, labelled with the current transaction phase
+/// (summary loading, ring signing step, response export)
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Progress {
     init: bool,
@@ -34,12 +41,20 @@ impl Progress {
     }
 
     pub fn render<D: Driver, R: RngCore + CryptoRng>(&mut self, engine: &Engine<D, R>) {
+        #[cfg(feature = "mlsag")]
+        let mut buff = [0u8; 32];
+
         // Resolve message based on engine state
         let message = match engine.state() {
             #[cfg(feature = "summary")]
-            State::Summary(_) => "Loading Transaction",
+            State::Summary(_) => text(Text::LoadingSummary),
+            #[cfg(feature = "mlsag")]
+            State::SignRing(RingState::Complete { .. }) => text(Text::ExportingResponses),
             #[cfg(feature = "mlsag")]
-            State::SignRing(_) => "Signing Transaction",
+            State::SignRing(_) => match engine.progress_steps() {
+                Some((n, total)) => fmt_ring(n, total, &mut buff),
+                None => text(Text::SigningRing),
+            },
             _ => "UNKNOWN",
         };
 
@@ -54,7 +69,8 @@ impl Progress {
             self.init = true;
         }
 
-        // Render progress information
+        // Render progress information (replacing previously recorded text)
+        echo_reset();
         match engine.progress() {
             Some(v) => {
                 let v = v as u32;
@@ -78,3 +94,23 @@ impl Progress {
         screen_util::screen_update();
     }
 }
+
+/// Format ring signing step for display (current ring is 1-indexed)
+#[cfg(feature = "mlsag")]
+fn fmt_ring(index: usize, total: usize, buff: &mut [u8]) -> &str {
+    let n = match emstr::write!(
+        &mut buff[..],
+        text(Text::SigningRing),
+        index + 1,
+        '/',
+        total
+    ) {
+        Ok(v) => v,
+        Err(_) => return "ENCODE_ERR",
+    };
+
+    match core::str::from_utf8(&buff[..n]) {
+        Ok(v) => v,
+        Err(_) => "INVALID_UTF8",
+    }
+}
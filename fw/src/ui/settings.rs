@@ -6,7 +6,7 @@ use rand_core::{CryptoRng, RngCore};
 use ledger_device_sdk::buttons::ButtonEvent;
 use ledger_device_sdk::ui::{
     bagls::*,
-    layout::{Draw, Layout, Location, StringPlace},
+    layout::{Draw, Layout, Location},
     screen_util,
 };
 
@@ -15,32 +15,161 @@ use ledger_mob_core::{
     engine::{Driver, Engine},
 };
 
-use super::{clear_screen, UiResult};
+use super::{clear_screen, StringPlace, UiResult};
+use crate::consts::{KEY_REQUEST_LIMITS, LOCK_TIMEOUTS_S};
+use crate::locale::{text, Language, Text};
 
-/// [Settings] page, at the moment this only provides Fog configuration
+/// [Settings] page, providing Fog configuration and (where enabled)
+/// lock timeout, blind signing, key export PIN, key export limit and
+/// language entries following the list of fogs
 #[derive(PartialEq, Clone, Debug)]
 pub struct Settings {
-    fog_id_index: usize,
+    index: usize,
+    lock_timeout_s: Option<u32>,
+    blind_signing: Option<bool>,
+    pin_wallet_keys: Option<bool>,
+    key_request_limit: Option<u32>,
+    language: Option<Language>,
+}
+
+/// Setting changed on [Settings] exit
+#[derive(Copy, PartialEq, Clone, Debug)]
+pub enum SettingsChange {
+    /// Fog ID selected
+    Fog(FogId),
+    /// Lock timeout changed (seconds, 0 to disable locking)
+    LockTimeout(u32),
+    /// Blind signing toggled
+    BlindSigning(bool),
+    /// Wallet key export PIN confirmation toggled
+    PinWalletKeys(bool),
+    /// Key request limit changed (0 for unlimited)
+    KeyRequestLimit(u32),
+    /// UI language changed
+    Language(Language),
+}
+
+/// Settings entry at a given index
+enum Entry {
+    Fog(FogId),
+    LockTimeout(u32),
+    BlindSigning(bool),
+    PinWalletKeys(bool),
+    KeyRequestLimit(u32),
+    Language(Language),
 }
 
 impl Settings {
     pub fn new(fog_id: FogId) -> Self {
-        let fog_id_index = fog_id as usize;
-        Self { fog_id_index }
+        Self {
+            index: fog_id as usize,
+            lock_timeout_s: None,
+            blind_signing: None,
+            pin_wallet_keys: None,
+            key_request_limit: None,
+            language: None,
+        }
+    }
+
+    /// Show the lock timeout entry with the current setting
+    /// (omitted for host fog requests)
+    pub fn with_lock_timeout(mut self, timeout_s: u32) -> Self {
+        self.lock_timeout_s = Some(timeout_s);
+        self
+    }
+
+    /// Show the blind signing toggle with the current setting
+    /// (omitted for host fog requests)
+    pub fn with_blind_signing(mut self, enabled: bool) -> Self {
+        self.blind_signing = Some(enabled);
+        self
+    }
+
+    /// Show the wallet key export PIN toggle with the current setting
+    /// (omitted for host fog requests)
+    pub fn with_pin_wallet_keys(mut self, enabled: bool) -> Self {
+        self.pin_wallet_keys = Some(enabled);
+        self
+    }
+
+    /// Show the key request limit entry with the current setting
+    /// (omitted for host fog requests)
+    pub fn with_key_request_limit(mut self, limit: u32) -> Self {
+        self.key_request_limit = Some(limit);
+        self
+    }
+
+    /// Show the language selector with the current setting
+    /// (omitted for host fog requests)
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Optional entries following the list of fogs
+    fn options(&self) -> impl Iterator<Item = Entry> {
+        let lock = self.lock_timeout_s.map(Entry::LockTimeout);
+        let blind = self.blind_signing.map(Entry::BlindSigning);
+        let pin = self.pin_wallet_keys.map(Entry::PinWalletKeys);
+        let limit = self.key_request_limit.map(Entry::KeyRequestLimit);
+        let language = self.language.map(Entry::Language);
+
+        lock.into_iter()
+            .chain(blind)
+            .chain(pin)
+            .chain(limit)
+            .chain(language)
+    }
+
+    /// Number of selectable entries
+    fn num_entries(&self) -> usize {
+        FOG_IDS.len() + self.options().count()
     }
 
-    pub fn update(&mut self, btn: &ButtonEvent) -> UiResult<FogId> {
+    /// Resolve the entry at the current index
+    fn entry(&self) -> Entry {
+        match FOG_IDS.get(self.index) {
+            Some(f) => Entry::Fog(*f),
+            None => self
+                .options()
+                .nth(self.index - FOG_IDS.len())
+                .unwrap_or(Entry::Fog(FogId::None)),
+        }
+    }
+
+    pub fn update(&mut self, btn: &ButtonEvent) -> UiResult<SettingsChange> {
         match btn {
-            // Exit on both buttons pressed/released
-            ButtonEvent::BothButtonsRelease => UiResult::Exit(FOG_IDS[self.fog_id_index]),
+            // Exit on both buttons pressed/released, selecting the current fog,
+            // moving to the next lock timeout / key request limit / language
+            // or toggling blind signing / PIN
+            ButtonEvent::BothButtonsRelease => match self.entry() {
+                Entry::Fog(f) => UiResult::Exit(SettingsChange::Fog(f)),
+                Entry::LockTimeout(t) => {
+                    let i = LOCK_TIMEOUTS_S.iter().position(|v| *v == t).unwrap_or(0);
+                    let next = LOCK_TIMEOUTS_S[(i + 1) % LOCK_TIMEOUTS_S.len()];
+                    UiResult::Exit(SettingsChange::LockTimeout(next))
+                }
+                Entry::BlindSigning(enabled) => {
+                    UiResult::Exit(SettingsChange::BlindSigning(!enabled))
+                }
+                Entry::PinWalletKeys(enabled) => {
+                    UiResult::Exit(SettingsChange::PinWalletKeys(!enabled))
+                }
+                Entry::KeyRequestLimit(l) => {
+                    let i = KEY_REQUEST_LIMITS.iter().position(|v| *v == l).unwrap_or(0);
+                    let next = KEY_REQUEST_LIMITS[(i + 1) % KEY_REQUEST_LIMITS.len()];
+                    UiResult::Exit(SettingsChange::KeyRequestLimit(next))
+                }
+                Entry::Language(l) => UiResult::Exit(SettingsChange::Language(l.next())),
+            },
 
-            // Otherwise move through fogs
-            ButtonEvent::LeftButtonRelease if self.fog_id_index > 0 => {
-                self.fog_id_index -= 1;
+            // Otherwise move through entries
+            ButtonEvent::LeftButtonRelease if self.index > 0 => {
+                self.index -= 1;
                 UiResult::Update
             }
-            ButtonEvent::RightButtonRelease if self.fog_id_index < FOG_IDS.len() - 1 => {
-                self.fog_id_index += 1;
+            ButtonEvent::RightButtonRelease if self.index < self.num_entries() - 1 => {
+                self.index += 1;
                 UiResult::Update
             }
 
@@ -54,30 +183,86 @@ impl Settings {
         clear_screen();
 
         // Show arrows
-        if self.fog_id_index > 0 {
+        if self.index > 0 {
             LEFT_ARROW.shift_v(0).display();
         }
-        if self.fog_id_index < FOG_IDS.len() - 1 {
+        if self.index < self.num_entries() - 1 {
             RIGHT_ARROW.shift_v(0).display();
         }
 
-        // Resolve index to fog ID and string
-        let fog = FOG_IDS[self.fog_id_index];
-        let name = fog_name(fog);
-
-        // Display current selection
-        "Fog ID".place(Location::Custom(8), Layout::Centered, true);
-        name.place(Location::Custom(26), Layout::Centered, false);
+        match self.entry() {
+            // Resolve index to fog ID and display current selection
+            Entry::Fog(fog) => {
+                text(Text::FogId).place(Location::Custom(8), Layout::Centered, true);
+                fog_name(fog).place(Location::Custom(26), Layout::Centered, false);
+            }
+            // Display lock timeout
+            Entry::LockTimeout(t) => {
+                text(Text::LockTimeout).place(Location::Custom(8), Layout::Centered, true);
+                lock_timeout_name(t).place(Location::Custom(26), Layout::Centered, false);
+            }
+            // Display blind signing state
+            Entry::BlindSigning(enabled) => {
+                text(Text::BlindSigning).place(Location::Custom(8), Layout::Centered, true);
+                enabled_name(enabled).place(Location::Custom(26), Layout::Centered, false);
+            }
+            // Display wallet key export PIN state
+            Entry::PinWalletKeys(enabled) => {
+                text(Text::KeyExportPin).place(Location::Custom(8), Layout::Centered, true);
+                enabled_name(enabled).place(Location::Custom(26), Layout::Centered, false);
+            }
+            // Display key request limit
+            Entry::KeyRequestLimit(l) => {
+                text(Text::KeyExportLimit).place(Location::Custom(8), Layout::Centered, true);
+                key_request_limit_name(l).place(Location::Custom(26), Layout::Centered, false);
+            }
+            // Display current language
+            Entry::Language(l) => {
+                text(Text::Language).place(Location::Custom(8), Layout::Centered, true);
+                l.name()
+                    .place(Location::Custom(26), Layout::Centered, false);
+            }
+        }
 
         // Update screen
         screen_util::screen_update();
     }
 }
 
+/// Resolve toggle state to string for display
+fn enabled_name(enabled: bool) -> &'static str {
+    match enabled {
+        true => text(Text::Enabled),
+        false => text(Text::Disabled),
+    }
+}
+
+/// Resolve lock timeout to string for display
+fn lock_timeout_name(timeout_s: u32) -> &'static str {
+    match timeout_s {
+        0 => text(Text::Never),
+        60 => text(Text::OneMinute),
+        300 => text(Text::FiveMinutes),
+        900 => text(Text::FifteenMinutes),
+        _ => text(Text::Custom),
+    }
+}
+
+/// Resolve key request limit to string for display
+fn key_request_limit_name(limit: u32) -> &'static str {
+    match limit {
+        0 => text(Text::Unlimited),
+        5 => "5",
+        10 => "10",
+        25 => "25",
+        _ => text(Text::Custom),
+    }
+}
+
 /// Resolve fog_id to string for display
 fn fog_name(fog_id: FogId) -> &'static str {
     match fog_id {
-        FogId::None => "None",
+        FogId::None => text(Text::FogNone),
         FogId::MobMain => "MobileCoin MainNet",
         FogId::MobTest => "MobileCoin TestNet",
         FogId::SignalMain => "Signal MainNet",
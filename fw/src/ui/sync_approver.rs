@@ -1,57 +1,97 @@
 // Copyright (c) 2022-2023 The MobileCoin Foundation
 
+use core::str::from_utf8;
+
+use emstr::EncodeStr;
 use rand_core::{CryptoRng, RngCore};
 
 use ledger_device_sdk::buttons::ButtonEvent;
 
 use ledger_device_sdk::ui::{
     bagls::*,
-    layout::{Draw, Layout, Location, StringPlace},
+    layout::{Draw, Layout, Location},
     screen_util,
 };
 
-use super::clear_screen;
-use ledger_mob_core::engine::{Driver, Engine};
+use super::{clear_screen, StringPlace};
+use ledger_mob_core::{
+    apdu::key_path::{KeyPath, KEY_PATH_HARDENED},
+    engine::{Approvals, Driver, Engine},
+};
 
 use super::UiResult;
+use crate::locale::{text, Text};
 
 /// Wallet sync approval element
 ///
-/// Used for user-confirmation of wallet syncing (view key and key image requests)
+/// Used for user-confirmation of wallet syncing (view key and key image requests),
+/// approval applies only to the requested [Approvals] scope.
+///
+/// The account index and derivation path of the triggering request are shown
+/// where available.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct SyncApprover {
     state: ApproverState,
+    scope: Approvals,
+    path: Option<KeyPath>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum ApproverState {
     Info,
+    Account,
     Allow,
     Deny,
 }
 
 impl SyncApprover {
-    /// Create a new [SyncApprover] with the provided message
-    pub fn new() -> Self {
+    /// Create a new [SyncApprover] for the provided approval scope
+    /// and request derivation path
+    pub fn new(scope: Approvals, path: Option<KeyPath>) -> Self {
         Self {
             state: ApproverState::Info,
+            scope,
+            path,
         }
     }
 
+    /// Fetch the approval scope for this request
+    pub fn scope(&self) -> Approvals {
+        self.scope
+    }
+
     /// Update [Approver] state, handling button events and returning the
     /// approval state on exit
     pub fn update(&mut self, btn: &ButtonEvent) -> UiResult<bool> {
         match (self.state, btn) {
-            // Info, right button to Allow
+            // Info, right button to Account where a path is available
+            (ApproverState::Info, ButtonEvent::RightButtonRelease) if self.path.is_some() => {
+                self.state = ApproverState::Account;
+                UiResult::Update
+            }
+            // .. otherwise to Allow
             (ApproverState::Info, ButtonEvent::RightButtonRelease) => {
                 self.state = ApproverState::Allow;
                 UiResult::Update
             }
+            // Account, right button to Allow
+            (ApproverState::Account, ButtonEvent::RightButtonRelease) => {
+                self.state = ApproverState::Allow;
+                UiResult::Update
+            }
+            // .. left button back to Info
+            (ApproverState::Account, ButtonEvent::LeftButtonRelease) => {
+                self.state = ApproverState::Info;
+                UiResult::Update
+            }
             // Allow, both buttons to approve
             (ApproverState::Allow, ButtonEvent::BothButtonsRelease) => UiResult::Exit(true),
-            // .. left button back to Info
+            // .. left button back to Account or Info
             (ApproverState::Allow, ButtonEvent::LeftButtonRelease) => {
-                self.state = ApproverState::Info;
+                self.state = match self.path {
+                    Some(_) => ApproverState::Account,
+                    None => ApproverState::Info,
+                };
                 UiResult::Update
             }
             // .. right button to Deny
@@ -86,27 +126,96 @@ impl SyncApprover {
 
         match self.state {
             Info => {
-                "Sync Wallet?".place(Location::Custom(8), Layout::Centered, true);
-                let lines = [
-                    "Allow the connected",
-                    "application to retrieve",
-                    "account balances",
-                ];
+                text(Text::SyncWallet).place(Location::Custom(8), Layout::Centered, true);
+                let lines = match self.scope {
+                    s if s.contains(Approvals::WALLET_KEYS) => [
+                        Text::WalletKeysInfo1,
+                        Text::WalletKeysInfo2,
+                        Text::WalletKeysInfo3,
+                    ],
+                    s if s.contains(Approvals::SUBADDRESS_KEYS) => [
+                        Text::SubaddressKeysInfo1,
+                        Text::SubaddressKeysInfo2,
+                        Text::SubaddressKeysInfo3,
+                    ],
+                    s if s.contains(Approvals::ONETIME_KEY_IMAGES) => [
+                        Text::OnetimeKeyImagesInfo1,
+                        Text::OnetimeKeyImagesInfo2,
+                        Text::OnetimeKeyImagesInfo3,
+                    ],
+                    _ => [
+                        Text::KeyImagesInfo1,
+                        Text::KeyImagesInfo2,
+                        Text::KeyImagesInfo3,
+                    ],
+                };
 
                 for (i, l) in lines.iter().enumerate() {
-                    l.place(Location::Custom(22 + i * 10), Layout::Centered, false);
+                    text(*l).place(Location::Custom(22 + i * 10), Layout::Centered, false);
+                }
+            }
+            Account => {
+                let mut account_buff = [0u8; 24];
+                let mut path_buff = [0u8; 64];
+
+                if let Some(p) = &self.path {
+                    fmt_account(p.account_index(), &mut account_buff).place(
+                        Location::Custom(8),
+                        Layout::Centered,
+                        true,
+                    );
+                    fmt_path(p, &mut path_buff).place(
+                        Location::Custom(28),
+                        Layout::Centered,
+                        false,
+                    );
                 }
             }
             Allow => {
                 CHECKMARK_ICON.shift_v(-8).shift_h((128 - 16) / 2).display();
-                "Approve Sync".place(Location::Custom(34), Layout::Centered, false);
+                text(Text::ApproveSync).place(Location::Custom(34), Layout::Centered, false);
             }
             Deny => {
                 CROSS_ICON.shift_v(-8).shift_h((128 - 16) / 2).display();
-                "Reject Sync".place(Location::Custom(34), Layout::Centered, false);
+                text(Text::RejectSync).place(Location::Custom(34), Layout::Centered, false);
             }
         }
 
         screen_util::screen_update();
     }
 }
+
+fn fmt_account(account_index: u32, buff: &mut [u8]) -> &str {
+    let n = match emstr::write!(&mut buff[..], text(Text::Account), account_index) {
+        Ok(v) => v,
+        Err(_) => return "ENCODE_ERR",
+    };
+
+    match from_utf8(&buff[..n]) {
+        Ok(v) => v,
+        Err(_) => "INVALID_UTF8",
+    }
+}
+
+/// Format a derivation path as `m/44'/866'/N'`
+fn fmt_path<'a>(path: &KeyPath, buff: &'a mut [u8]) -> &'a str {
+    buff[0] = b'm';
+    let mut n = 1;
+
+    for p in path.as_slice() {
+        let hardened = match p & KEY_PATH_HARDENED != 0 {
+            true => "'",
+            false => "",
+        };
+
+        n += match emstr::write!(&mut buff[n..], '/', p & !KEY_PATH_HARDENED, hardened) {
+            Ok(v) => v,
+            Err(_) => return "ENCODE_ERR",
+        };
+    }
+
+    match from_utf8(&buff[..n]) {
+        Ok(v) => v,
+        Err(_) => "INVALID_UTF8",
+    }
+}
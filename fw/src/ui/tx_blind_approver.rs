@@ -8,23 +8,28 @@ use ledger_device_sdk::buttons::ButtonEvent;
 
 use ledger_device_sdk::ui::{
     bagls::*,
-    layout::{Draw, Layout, Location, StringPlace},
+    layout::{Draw, Layout, Location},
     screen_util,
 };
 
-use ledger_mob_core::engine::{Driver, Engine};
+use ledger_mob_core::{
+    engine::{Driver, Engine, TokenId},
+    helpers::fmt_token_val,
+};
 
 use super::{
     clear_screen,
     helpers::{tx_approve_page, tx_deny_page},
-    UiResult,
+    memo_page, StringPlace, UiResult,
 };
+use crate::locale::{text, Text};
 
 /// UI Approval Element
 ///
 /// Used for user-confirmation of key requests (and transactions, pending TxSummary availability)
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct TxBlindApprover {
+    num_memos: usize,
     state: ApproverState,
 }
 
@@ -33,14 +38,17 @@ enum ApproverState {
     Init,
     Warn,
     Hash,
+    Memo(usize),
+    Fee,
     Allow,
     Deny,
 }
 
 impl TxBlindApprover {
-    /// Create a new Approver with the provided message
-    pub fn new() -> Self {
+    /// Create a new Approver with the number of signed memos to display
+    pub fn new(num_memos: usize) -> Self {
         Self {
+            num_memos,
             state: ApproverState::Init,
         }
     }
@@ -62,17 +70,45 @@ impl TxBlindApprover {
                 self.state = ApproverState::Hash
             }
 
-            // Hash display, left back to warning, right to allow
+            // Hash display, left back to warning, right to memos or fee
             (ApproverState::Hash, ButtonEvent::LeftButtonRelease) => {
                 self.state = ApproverState::Warn
             }
+            (ApproverState::Hash, ButtonEvent::RightButtonRelease) if self.num_memos > 0 => {
+                self.state = ApproverState::Memo(0)
+            }
             (ApproverState::Hash, ButtonEvent::RightButtonRelease) => {
+                self.state = ApproverState::Fee
+            }
+
+            // Memo display, left / right to step through memos
+            (ApproverState::Memo(n), ButtonEvent::LeftButtonRelease) if n == 0 => {
+                self.state = ApproverState::Hash
+            }
+            (ApproverState::Memo(n), ButtonEvent::LeftButtonRelease) => {
+                self.state = ApproverState::Memo(n - 1)
+            }
+            (ApproverState::Memo(n), ButtonEvent::RightButtonRelease) if n + 1 < self.num_memos => {
+                self.state = ApproverState::Memo(n + 1)
+            }
+            (ApproverState::Memo(_n), ButtonEvent::RightButtonRelease) => {
+                self.state = ApproverState::Fee
+            }
+
+            // Fee display, left back to memos or hash, right to allow
+            (ApproverState::Fee, ButtonEvent::LeftButtonRelease) if self.num_memos > 0 => {
+                self.state = ApproverState::Memo(self.num_memos - 1)
+            }
+            (ApproverState::Fee, ButtonEvent::LeftButtonRelease) => {
+                self.state = ApproverState::Hash
+            }
+            (ApproverState::Fee, ButtonEvent::RightButtonRelease) => {
                 self.state = ApproverState::Allow
             }
 
-            // Allow state, left back to hash, both to approve, right to deny
+            // Allow state, left back to fee, both to approve, right to deny
             (ApproverState::Allow, ButtonEvent::LeftButtonRelease) => {
-                self.state = ApproverState::Hash
+                self.state = ApproverState::Fee
             }
             (ApproverState::Allow, ButtonEvent::BothButtonsRelease) => return UiResult::Exit(true),
             (ApproverState::Allow, ButtonEvent::RightButtonRelease) => {
@@ -113,13 +149,17 @@ impl TxBlindApprover {
         // Display pages
         match self.state {
             Init => {
-                ["Transaction", "Request"].place(Location::Middle, Layout::Centered, false);
+                [text(Text::Transaction), text(Text::Request)].place(
+                    Location::Middle,
+                    Layout::Centered,
+                    false,
+                );
             }
             Warn => {
                 [
-                    "No summary available,",
-                    "please verify the",
-                    "transaction hash",
+                    text(Text::NoSummaryInfo1),
+                    text(Text::NoSummaryInfo2),
+                    text(Text::NoSummaryInfo3),
                 ]
                 .place(Location::Middle, Layout::Centered, false);
             }
@@ -129,7 +169,37 @@ impl TxBlindApprover {
                 let msg_str = fmt_msg(msg, &mut buff);
 
                 // Display message
-                ["Transaction hash:", msg_str].place(Location::Middle, Layout::Centered, false);
+                [text(Text::TransactionHash), msg_str].place(
+                    Location::Middle,
+                    Layout::Centered,
+                    false,
+                );
+            }
+            Memo(n) => match engine.memos().get(n) {
+                Some(m) => memo_page(m, n, self.num_memos, engine.show_payment_ids()),
+                None => {
+                    text(Text::MemoUnavailable).place(Location::Middle, Layout::Centered, false)
+                }
+            },
+            Fee => {
+                let mut value_buff = [0u8; 32];
+
+                // Fee is provided by the host and can not be checked without a summary
+                let value_str = match engine.tx_expected() {
+                    Some(e) => fmt_token_val(
+                        e.fee_value as i64,
+                        TokenId::from(e.fee_token_id),
+                        &mut value_buff,
+                    ),
+                    None => text(Text::FeeNotProvided),
+                };
+                let rings_str = fmt_rings(engine.num_rings(), &mut buff);
+
+                [text(Text::FeeUnverified), value_str, rings_str].place(
+                    Location::Middle,
+                    Layout::Centered,
+                    false,
+                );
             }
             Deny => {
                 tx_deny_page();
@@ -165,3 +235,15 @@ fn fmt_msg<'a>(msg: Option<&[u8]>, buff: &'a mut [u8]) -> &'a str {
         Err(_) => "INVALID_UTF8",
     }
 }
+
+fn fmt_rings(num_rings: usize, buff: &mut [u8]) -> &str {
+    let n = match emstr::write!(&mut buff[..], num_rings, text(Text::Rings)) {
+        Ok(v) => v,
+        Err(_) => return "ENCODE_ERR",
+    };
+
+    match core::str::from_utf8(&buff[..n]) {
+        Ok(v) => v,
+        Err(_) => "INVALID_UTF8",
+    }
+}
@@ -11,19 +11,23 @@ use ledger_secure_sdk_sys::buttons::ButtonEvent;
 
 use ledger_device_sdk::ui::{
     bagls::*,
-    layout::{Draw, Layout, Location, StringPlace},
+    layout::{Draw, Layout, Location},
     screen_util,
 };
 
 use ledger_mob_core::{
-    engine::{Driver, Engine, TransactionEntity},
+    engine::{Driver, Engine, ReservedSubaddress, TombstoneStatus, TotalKind, TransactionEntity},
     helpers::{b58_encode_public_address, fmt_token_val},
 };
 
 use super::{
     clear_screen,
     helpers::{tx_approve_page, tx_deny_page},
-    Address, UiResult,
+    memo_page, Address, StringPlace, UiResult,
+};
+use crate::{
+    consts::HIGH_VALUE_CONFIRMATIONS,
+    locale::{text, Text},
 };
 
 /// UI Approval Element
@@ -32,16 +36,24 @@ use super::{
 #[derive(Clone, Debug, PartialEq)]
 pub struct TxSummaryApprover {
     num_outputs: usize,
+    num_dust: usize,
+    num_memos: usize,
     num_totals: usize,
     state: TxSummaryApproverState,
     selected: bool,
     address: Option<Address<512>>,
+    /// Confirmations required to approve (more than one for high-value transactions)
+    confirmations: usize,
+    /// Confirmations received on the current visit to the approve page
+    confirmed: usize,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Display, EnumCount)]
 enum TxSummaryApproverState {
     Init,
     Op(usize),
+    Dust(usize),
+    Memo(usize),
     Fee,
     Total(usize),
     Allow,
@@ -49,14 +61,28 @@ enum TxSummaryApproverState {
 }
 
 impl TxSummaryApprover {
-    /// Create a new Approver with the provided message
-    pub fn new(num_outputs: usize, num_totals: usize) -> Self {
+    /// Create a new Approver with the provided message, high-value
+    /// transactions require [HIGH_VALUE_CONFIRMATIONS] to approve
+    pub fn new(
+        num_outputs: usize,
+        num_dust: usize,
+        num_memos: usize,
+        num_totals: usize,
+        high_value: bool,
+    ) -> Self {
         Self {
             num_outputs,
+            num_dust,
+            num_memos,
             num_totals,
             state: TxSummaryApproverState::Init,
             selected: false,
             address: None,
+            confirmations: match high_value {
+                true => HIGH_VALUE_CONFIRMATIONS,
+                false => 1,
+            },
+            confirmed: 0,
         }
     }
 
@@ -72,7 +98,8 @@ impl TxSummaryApprover {
 
         match (self.state, btn) {
             // Transaction overview (first page)
-            (Init, ButtonEvent::RightButtonRelease) => self.state = Op(0),
+            (Init, ButtonEvent::RightButtonRelease) if self.num_outputs > 0 => self.state = Op(0),
+            (Init, ButtonEvent::RightButtonRelease) => self.state = self.after_outputs(),
 
             // Passthrough to address renderer if available
             (Op(_), ButtonEvent::BothButtonsRelease) if self.address.is_some() => {
@@ -89,7 +116,7 @@ impl TxSummaryApprover {
             (Op(n), ButtonEvent::RightButtonRelease) if n + 1 < self.num_outputs => {
                 self.state = Op(n + 1)
             }
-            (Op(_n), ButtonEvent::RightButtonRelease) => self.state = Fee,
+            (Op(_n), ButtonEvent::RightButtonRelease) => self.state = self.after_outputs(),
 
             // Select for operations with addresses
             (Op(n), ButtonEvent::BothButtonsRelease) if self.address.is_none() => {
@@ -113,15 +140,37 @@ impl TxSummaryApprover {
                 // Setup address for rendering
                 self.address = Some(Address::new(
                     &s.address,
-                    s.fog_id,
+                    s.fog_report_url(),
                     s.fog_sig.as_ref().map(|s| s.as_slice()).unwrap_or(&[]),
                 ));
 
                 return UiResult::Update;
             }
 
+            // Folded dust outputs
+            (Dust(n), ButtonEvent::LeftButtonRelease) if n == 0 => self.state = self.last_output(),
+            (Dust(n), ButtonEvent::LeftButtonRelease) => self.state = Dust(n - 1),
+            (Dust(n), ButtonEvent::RightButtonRelease) if n + 1 < self.num_dust => {
+                self.state = Dust(n + 1)
+            }
+            (Dust(_n), ButtonEvent::RightButtonRelease) if self.num_memos > 0 => {
+                self.state = Memo(0)
+            }
+            (Dust(_n), ButtonEvent::RightButtonRelease) => self.state = Fee,
+
+            // List of signed memos
+            (Memo(n), ButtonEvent::LeftButtonRelease) if n == 0 => self.state = self.before_memos(),
+            (Memo(n), ButtonEvent::LeftButtonRelease) => self.state = Memo(n - 1),
+            (Memo(n), ButtonEvent::RightButtonRelease) if n + 1 < self.num_memos => {
+                self.state = Memo(n + 1)
+            }
+            (Memo(_n), ButtonEvent::RightButtonRelease) => self.state = Fee,
+
             // Fee information
-            (Fee, ButtonEvent::LeftButtonRelease) => self.state = Op(self.num_outputs - 1),
+            (Fee, ButtonEvent::LeftButtonRelease) if self.num_memos > 0 => {
+                self.state = Memo(self.num_memos - 1)
+            }
+            (Fee, ButtonEvent::LeftButtonRelease) => self.state = self.before_memos(),
             (Fee, ButtonEvent::RightButtonRelease) => self.state = Total(0),
 
             // List of totals
@@ -132,10 +181,20 @@ impl TxSummaryApprover {
             }
             (Total(_n), ButtonEvent::RightButtonRelease) => self.state = Allow,
 
-            // Approve page
-            (Allow, ButtonEvent::LeftButtonRelease) => self.state = Total(self.num_totals - 1),
+            // Approve page, high-value transactions require repeated confirmation
+            // (reset on leaving the page) to mitigate accidental approval
+            (Allow, ButtonEvent::LeftButtonRelease) => {
+                self.confirmed = 0;
+                self.state = Total(self.num_totals - 1)
+            }
+            (Allow, ButtonEvent::BothButtonsRelease) if self.confirmed + 1 < self.confirmations => {
+                self.confirmed += 1
+            }
             (Allow, ButtonEvent::BothButtonsRelease) => return UiResult::Exit(true),
-            (Allow, ButtonEvent::RightButtonRelease) => self.state = Deny,
+            (Allow, ButtonEvent::RightButtonRelease) => {
+                self.confirmed = 0;
+                self.state = Deny
+            }
 
             // Deny page
             (Deny, ButtonEvent::LeftButtonRelease) => self.state = Allow,
@@ -150,6 +209,39 @@ impl TxSummaryApprover {
         UiResult::Update
     }
 
+    /// Page following the list of outputs
+    fn after_outputs(&self) -> TxSummaryApproverState {
+        use TxSummaryApproverState::*;
+
+        if self.num_dust > 0 {
+            Dust(0)
+        } else if self.num_memos > 0 {
+            Memo(0)
+        } else {
+            Fee
+        }
+    }
+
+    /// Last output page, or the overview if all outputs are folded as dust
+    fn last_output(&self) -> TxSummaryApproverState {
+        use TxSummaryApproverState::*;
+
+        match self.num_outputs {
+            0 => Init,
+            n => Op(n - 1),
+        }
+    }
+
+    /// Page preceding the list of memos
+    fn before_memos(&self) -> TxSummaryApproverState {
+        use TxSummaryApproverState::*;
+
+        match self.num_dust {
+            0 => self.last_output(),
+            n => Dust(n - 1),
+        }
+    }
+
     /// Render the [Approver] based on it's current internal state
     pub fn render<D: Driver, R: RngCore + CryptoRng>(&self, engine: &Engine<D, R>) {
         use TxSummaryApproverState::*;
@@ -165,7 +257,7 @@ impl TxSummaryApprover {
         let report = match engine.report() {
             Some(r) => r,
             None => {
-                "NO SUMMARY AVAILABLE".place(Location::Middle, Layout::Centered, false);
+                text(Text::NoSummaryAvailable).place(Location::Middle, Layout::Centered, false);
                 screen_util::screen_update();
                 return;
             }
@@ -180,9 +272,34 @@ impl TxSummaryApprover {
         }
 
         match self.state {
-            Init => {
-                ["Transaction", "Request"].place(Location::Middle, Layout::Centered, false);
-            }
+            Init => match engine.tombstone_status() {
+                // Flag implausible tombstone blocks on the first page
+                Some(TombstoneStatus::Expired) => {
+                    [
+                        text(Text::Transaction),
+                        text(Text::Request),
+                        "",
+                        text(Text::TxExpired),
+                    ]
+                    .place(Location::Middle, Layout::Centered, false);
+                }
+                Some(TombstoneStatus::FarFuture) => {
+                    [
+                        text(Text::Transaction),
+                        text(Text::Request),
+                        "",
+                        text(Text::TombstoneFar),
+                    ]
+                    .place(Location::Middle, Layout::Centered, false);
+                }
+                _ => {
+                    [text(Text::Transaction), text(Text::Request)].place(
+                        Location::Middle,
+                        Layout::Centered,
+                        false,
+                    );
+                }
+            },
             Op(_n) if self.address.is_some() => {
                 let address = self.address.as_ref().unwrap();
                 address.render(engine);
@@ -202,22 +319,25 @@ impl TxSummaryApprover {
                         // Switch heading depending on whether this is
                         // to an address we control
                         let heading = match &entity {
-                            TransactionEntity::OurAddress(_) => "Receive",
-                            TransactionEntity::OtherAddress(_) => "Send",
-                            _ => unreachable!(),
+                            TransactionEntity::OurAddress(_) => text(Text::Receive),
+                            _ => text(Text::Send),
                         };
 
                         DOWN_ARROW.shift_h(-60).shift_v(24).display();
 
                         let title_str = fmt_page(heading, n, self.num_outputs, &mut title_buff);
 
-                        // Lookup address from cache
-                        let addr_str = match engine.address(a) {
-                            Some(c) => {
+                        // Name reserved subaddresses, or lookup address from cache
+                        let reserved = engine
+                            .reserved_subaddress(a)
+                            .filter(|r| *r != ReservedSubaddress::Default);
+                        let addr_str = match (reserved, engine.address(a)) {
+                            (Some(r), _) => r.label(),
+                            (_, Some(c)) => {
                                 // Encode in b58 form for display
                                 let b58 = b58_encode_public_address::<512>(
                                     &c.address,
-                                    c.fog_id.url(),
+                                    c.fog_report_url(),
                                     c.fog_sig.as_ref().map(|v| &v[..]).unwrap_or(&[]),
                                 );
 
@@ -230,7 +350,7 @@ impl TxSummaryApprover {
                             // If we don't have a cache match, display short hash
                             // NOTE: this _shouldn't_ be possible so long
                             // as the cache size is the same as the report size
-                            None => fmt_short_hash(a.as_ref(), &mut buff),
+                            (_, None) => fmt_short_hash(a.as_ref(), &mut buff),
                         };
 
                         // Display title / value / address
@@ -242,11 +362,39 @@ impl TxSummaryApprover {
                     }
                     // Swap outputs
                     TransactionEntity::Swap => {
-                        let title_str = fmt_page("Swap", n, self.num_outputs, &mut buff);
+                        let title_str = fmt_page(text(Text::Swap), n, self.num_outputs, &mut buff);
                         [title_str, value_str].place(Location::Middle, Layout::Centered, false);
                     }
                 }
             }
+            // Folded dust outputs
+            Dust(n) => match engine.dust().get(n) {
+                Some(d) => {
+                    let title_str = fmt_page(text(Text::Dust), n, self.num_dust, &mut title_buff);
+                    let value_str = fmt_token_val(d.value as i64, d.token_id, &mut value_buff);
+                    let count_str = match emstr::write!(&mut buff[..], d.count, text(Text::Outputs))
+                    {
+                        Ok(n) => from_utf8(&buff[..n]).unwrap_or("INVALID_UTF8"),
+                        Err(_) => "ENCODE_ERR",
+                    };
+
+                    [title_str, value_str, count_str].place(
+                        Location::Middle,
+                        Layout::Centered,
+                        false,
+                    );
+                }
+                None => {
+                    text(Text::DustUnavailable).place(Location::Middle, Layout::Centered, false)
+                }
+            },
+            // Signed memos
+            Memo(n) => match engine.memos().get(n) {
+                Some(m) => memo_page(m, n, self.num_memos, engine.show_payment_ids()),
+                None => {
+                    text(Text::MemoUnavailable).place(Location::Middle, Layout::Centered, false)
+                }
+            },
             // Fees
             Fee => {
                 let value_str = fmt_token_val(
@@ -254,20 +402,34 @@ impl TxSummaryApprover {
                     report.network_fee.token_id,
                     &mut buff[..],
                 );
-                ["Fee", value_str].place(Location::Middle, Layout::Centered, false);
+                [text(Text::Fee), value_str].place(Location::Middle, Layout::Centered, false);
             }
             // Totals
             Total(n) => {
                 // Fetch total information
-                let (token_id, _total_kind, value) = &report.totals[n];
+                let (token_id, total_kind, value) = &report.totals[n];
+
+                // Show swap counterparty totals distinctly from our own
+                let heading = match total_kind {
+                    TotalKind::Ours => text(Text::Total),
+                    TotalKind::Sci => text(Text::SwapTotal),
+                };
 
                 let value_str = fmt_token_val(*value, *token_id, &mut value_buff);
-                let title_str = fmt_page("Total", n, self.num_totals, &mut title_buff);
+                let title_str = fmt_page(heading, n, self.num_totals, &mut title_buff);
                 [title_str, value_str].place(Location::Middle, Layout::Centered, false);
             }
             Deny => {
                 tx_deny_page();
             }
+            Allow if self.confirmed > 0 => {
+                let count_str = fmt_count(self.confirmed, self.confirmations, &mut buff);
+                [text(Text::HighValue), text(Text::ConfirmAgain), count_str].place(
+                    Location::Middle,
+                    Layout::Centered,
+                    false,
+                );
+            }
             Allow => {
                 tx_approve_page();
             }
@@ -289,6 +451,18 @@ fn fmt_page<'a>(name: &str, index: usize, total: usize, buff: &'a mut [u8]) -> &
     }
 }
 
+fn fmt_count<'a>(index: usize, total: usize, buff: &'a mut [u8]) -> &'a str {
+    let n = match emstr::write!(&mut buff[..], '(', index + 1, '/', total, ')') {
+        Ok(v) => v,
+        Err(_) => return "ENCODE_ERR",
+    };
+
+    match from_utf8(&buff[..n]) {
+        Ok(v) => v,
+        Err(_) => "INVALID_UTF8",
+    }
+}
+
 fn fmt_b58_addr<'a>(addr: &str, buff: &'a mut [u8]) -> &'a str {
     let n = match emstr::write!(&mut buff[..], &addr[..8], "...", &addr[addr.len() - 8..]) {
         Ok(v) => v,
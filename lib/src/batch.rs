@@ -0,0 +1,66 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Batched APDU exchange helpers
+
+use std::time::Duration;
+
+use encdec::Decode;
+use ledger_lib::Device;
+use ledger_proto::{ApduError, ApduReq};
+use log::debug;
+
+use ledger_mob_apdu::{
+    batch::{BatchReq, BatchResp, BatchWriter},
+    chunk::CHUNK_BUFF_LEN,
+};
+
+use crate::{chunk, Error};
+
+/// Issue a batch of requests (see [ledger_mob_apdu::batch]) using the
+/// chunked exchange, returning the decoded response for each request.
+///
+/// Batched requests share a response type, as when streaming rings.
+pub(crate) async fn request_batch<'a, D, REQ, RESP>(
+    d: &mut D,
+    requests: &[REQ],
+    timeout: Duration,
+) -> Result<Vec<RESP>, Error>
+where
+    D: Device + Send,
+    REQ: ApduReq<'a>,
+    RESP: for<'b> Decode<'b, Output = RESP, Error = ApduError>,
+{
+    // Build batch request
+    let mut req_buff = [0u8; CHUNK_BUFF_LEN];
+    let mut w = BatchWriter::new(&mut req_buff);
+    for r in requests {
+        w.push(r)?;
+    }
+    let n = w.finish()?;
+
+    let (req, _) = BatchReq::decode(&req_buff[..n])?;
+
+    debug!("Sending batch ({} entries, {n} bytes)", req.len());
+
+    // Batches are always sent using the chunked exchange
+    let mut resp_buff = [0u8; CHUNK_BUFF_LEN];
+    let resp: BatchResp = chunk::request_chunked(d, req, &mut resp_buff, timeout).await?;
+
+    // Check responses match requests
+    if resp.len() != requests.len() {
+        return Err(Error::UnexpectedResponse);
+    }
+
+    // Decode entry responses
+    let mut decoded = Vec::with_capacity(resp.len());
+    for (e, r) in resp.entries().zip(requests) {
+        if e.ins != r.header().ins {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        let (v, _) = RESP::decode(e.data)?;
+        decoded.push(v);
+    }
+
+    Ok(decoded)
+}
@@ -0,0 +1,106 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Chunked APDU exchange helpers
+
+use std::time::Duration;
+
+use ledger_lib::Device;
+use ledger_proto::{ApduBase, ApduReq};
+use log::debug;
+
+use ledger_mob_apdu::chunk::{
+    ChunkFlags, ChunkGetReq, ChunkReq, ChunkResp, CHUNK_BUFF_LEN, CHUNK_DATA_MAX,
+};
+
+use crate::Error;
+
+/// Issue a request using the chunked exchange protocol
+/// (see [ledger_mob_apdu::chunk]), for requests or responses
+/// exceeding the maximum APDU payload.
+///
+/// The response is reassembled into `buff` prior to decoding.
+/// This is generic over [Device] to support use by [DeviceHandle][crate::DeviceHandle]
+/// and wrapping devices such as [SessionDevice][crate::SessionDevice].
+pub(crate) async fn request_chunked<'a, 'b, D: Device + Send, RESP: ApduBase<'b>>(
+    d: &mut D,
+    request: impl ApduReq<'a> + Send,
+    buff: &'b mut [u8],
+    timeout: Duration,
+) -> Result<RESP, Error> {
+    let ins = request.header().ins;
+
+    // Encode request
+    let mut req_buff = [0u8; CHUNK_BUFF_LEN];
+    let n = request.encode(&mut req_buff)?;
+
+    let mut chunk_buff = [0u8; 256];
+    let mut offset = 0;
+
+    // Send request chunks, the final chunk returns the first response chunk
+    let (total_len, mut received) = loop {
+        let len = (n - offset).min(CHUNK_DATA_MAX);
+
+        let mut flags = ChunkFlags::empty();
+        flags.set(ChunkFlags::FIRST, offset == 0);
+        flags.set(ChunkFlags::LAST, offset + len == n);
+
+        debug!("Sending request chunk (offset: {offset} length: {len} total: {n})");
+
+        let req = ChunkReq::new(
+            ins,
+            flags,
+            offset as u16,
+            n as u16,
+            &req_buff[offset..][..len],
+        );
+        let resp = d
+            .request::<ChunkResp>(req, &mut chunk_buff, timeout)
+            .await?;
+
+        offset += len;
+
+        // Check intermediate chunks are acknowledged
+        if offset < n {
+            if resp.offset as usize != offset {
+                return Err(Error::UnexpectedResponse);
+            }
+            continue;
+        }
+
+        // Check first response chunk
+        let total_len = resp.total_len as usize;
+        if resp.offset != 0 || total_len > buff.len() || resp.data.len() > total_len {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        buff[..resp.data.len()].copy_from_slice(resp.data);
+
+        break (total_len, resp.data.len());
+    };
+
+    // Fetch remaining response chunks
+    while received < total_len {
+        debug!("Fetching response chunk (offset: {received} total: {total_len})");
+
+        let resp = d
+            .request::<ChunkResp>(ChunkGetReq::new(received as u16), &mut chunk_buff, timeout)
+            .await?;
+
+        if resp.offset as usize != received
+            || resp.total_len as usize != total_len
+            || resp.data.is_empty()
+            || received + resp.data.len() > total_len
+        {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        buff[received..][..resp.data.len()].copy_from_slice(resp.data);
+        received += resp.data.len();
+    }
+
+    // Decode reassembled response
+    let buff: &'b [u8] = buff;
+    let (resp, _) = RESP::decode(&buff[..total_len])?;
+
+    Ok(resp)
+}
@@ -29,6 +29,23 @@ impl<const N: usize> std::string::ToString for HexData<N> {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
+pub struct HexVec(pub Vec<u8>);
+
+impl std::str::FromStr for HexVec {
+    type Err = hex::FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        hex::decode(s).map(HexVec)
+    }
+}
+
+impl AsRef<[u8]> for HexVec {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct B64Data<const N: usize = 32>(pub [u8; N]);
 
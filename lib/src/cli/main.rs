@@ -2,7 +2,7 @@
 
 //! Command line utility for interacting with the Ledger MobileCoin NanoApp
 
-use std::{path::Path, time::Duration};
+use std::{path::Path, str::FromStr, time::Duration};
 
 use clap::Parser;
 use ledger_lib::{Device, Filters, LedgerProvider, Transport};
@@ -17,7 +17,11 @@ use mc_transaction_signer::{
 };
 
 use ledger_mob::DeviceHandle;
-use ledger_mob_apdu::random::{RandomReq, RandomResp};
+use ledger_mob_apdu::{
+    ident::{IdentDerivation, IDENT_CHALLENGE_MAX},
+    random::{RandomReq, RandomResp},
+    tx::FogId,
+};
 
 mod helpers;
 use helpers::*;
@@ -69,6 +73,31 @@ enum Actions {
         subaddress: u64,
     },
 
+    /// Fetch b58 encoded public address
+    Address {
+        /// SLIP-0010 account index for SLIP-010 derivation
+        #[clap(long, default_value = "0")]
+        account: u32,
+
+        /// Subaddress index
+        #[clap(long, default_value = "0")]
+        subaddress: u64,
+
+        /// Fog report URL (empty for no fog)
+        #[clap(long, default_value = "")]
+        fog_url: String,
+    },
+
+    /// Fetch current device settings
+    Settings,
+
+    /// Set the device's active fog (requires on-device confirmation)
+    SetFog {
+        /// Fog report URL (empty for no fog)
+        #[clap(long, default_value = "")]
+        fog_url: String,
+    },
+
     /// Resolve key images for transaction public keys
     KeyImage {
         /// SLIP-0010 account index for SLIP-010 derivation
@@ -85,7 +114,11 @@ enum Actions {
     },
 
     /// Fetch a random value from the device
-    GetRandom,
+    GetRandom {
+        /// Random value length in bytes
+        #[clap(long, default_value = "32")]
+        len: u8,
+    },
 
     /// Fetch BIP0013/17 derived ed25519 public key (and optionally sign the provided challenge)
     Ident {
@@ -97,9 +130,13 @@ enum Actions {
         #[clap(long, default_value = "0")]
         index: u32,
 
-        /// hex-encoded challenge to be signed
+        /// hex-encoded challenge to be signed (random 32-byte challenge if unset)
         #[clap(long)]
-        challenge: Option<HexData<32>>,
+        challenge: Option<HexVec>,
+
+        /// identity derivation (slip13 or slip17)
+        #[clap(long, default_value = "slip13")]
+        derivation: IdentDerivation,
     },
 
     // Implement shared signer operations
@@ -186,6 +223,10 @@ where
             let i = t.app_info().await?;
 
             info!("app info: {:#?}", i);
+
+            let c = t.negotiate().await?;
+
+            info!("capabilities: {:#?}", c);
         }
         Actions::Account { account } => {
             info!("requesting root keys for wallet: {}", account);
@@ -209,6 +250,42 @@ where
             info!("subaddress view private key: {}", r.view_private);
             info!("subaddress spend public key:  {}", r.spend_public);
         }
+        Actions::Address {
+            account,
+            subaddress,
+            fog_url,
+        } => {
+            let fog_id = FogId::from_str(&fog_url)
+                .map_err(|_| anyhow::anyhow!("unsupported fog url: '{}'", fog_url))?;
+
+            info!(
+                "requesting public address for wallet: {} subaddress: {} fog: {:?}",
+                account, subaddress, fog_id
+            );
+
+            let r = t.public_address(account, subaddress, fog_id).await?;
+
+            info!("public address: {}", r.b58);
+        }
+        Actions::Settings => {
+            let s = t.settings().await?;
+
+            info!("fog: {:?}", s.fog_id);
+            info!("blind signing: {}", s.blind_signing());
+            info!("onetime keys: {}", s.onetime_keys());
+            info!("lock timeout: {}s", s.lock_timeout_s);
+            info!("default account: {}", s.account_index);
+        }
+        Actions::SetFog { fog_url } => {
+            let fog_id = FogId::from_str(&fog_url)
+                .map_err(|_| anyhow::anyhow!("unsupported fog url: '{}'", fog_url))?;
+
+            info!("requesting fog change: {:?} (confirm on device)", fog_id);
+
+            t.set_fog_id(fog_id).await?;
+
+            info!("fog updated");
+        }
         Actions::KeyImage {
             account,
             subaddress,
@@ -224,15 +301,16 @@ where
             let tx_public_key = RistrettoPublic::try_from(tx_public_key.as_ref())
                 .map_err(|_| anyhow::anyhow!("failed to parse ristretto public key from hex"))?;
 
-            let key_image = t.key_image(account, subaddress, tx_public_key).await?;
+            let r = t.key_image_resp(account, subaddress, tx_public_key).await?;
 
-            info!("key image: {}", key_image);
+            info!("key image: {}", r.key_image);
+            info!("subaddress spend public: {}", r.spend_public.as_ref());
         }
-        Actions::GetRandom => {
-            info!("requesting random value");
+        Actions::GetRandom { len } => {
+            info!("requesting random value ({} bytes)", len);
 
             let r = t
-                .request::<RandomResp>(RandomReq {}, &mut buff, Duration::from_secs(2))
+                .request::<RandomResp>(RandomReq::new(len), &mut buff, Duration::from_secs(2))
                 .await?;
 
             info!("value: {:x?}", r.value);
@@ -241,19 +319,28 @@ where
             uri,
             index,
             challenge,
+            derivation,
         } => {
-            info!("Requesting identity for uri: '{}' (index: {})", uri, index);
+            info!(
+                "Requesting {} identity for uri: '{}' (index: {})",
+                derivation, uri, index
+            );
 
             // Setup challenge
-            let mut c: [u8; 32] = rand::random();
-            if let Some(v) = challenge {
-                c.copy_from_slice(v.as_ref());
-            }
+            let c = match challenge {
+                Some(v) if v.0.len() > IDENT_CHALLENGE_MAX => {
+                    return Err(anyhow::anyhow!(
+                        "challenge exceeds maximum length ({IDENT_CHALLENGE_MAX} bytes)"
+                    ));
+                }
+                Some(v) => v.0,
+                None => rand::random::<[u8; 32]>().to_vec(),
+            };
 
-            info!("Using challenge: {}", hex::encode(c));
+            info!("Using challenge: {}", hex::encode(&c));
 
             // Execute identity request
-            let (key, sig) = t.identity(index, &uri, &c).await?;
+            let (key, sig) = t.identity(index, &uri, &c, derivation).await?;
 
             // Display response
             info!("public key: {}", hex::encode(key.as_bytes()));
@@ -2,7 +2,10 @@
 
 use core::fmt::Debug;
 
-use ledger_mob_apdu::state::TxState;
+use ledger_mob_apdu::{
+    state::TxState,
+    status::{error_detail, split_reply, StatusClass, StatusWord},
+};
 use mc_crypto_ring_signature_signer::Error as SignerError;
 use tokio::time::error::Elapsed;
 
@@ -42,6 +45,10 @@ pub enum Error {
     #[error("Mismatch in rolling transaction digest")]
     DigestMismatch,
 
+    /// Mismatch in transaction request sequence number
+    #[error("Mismatch in request sequence (actual: {0}, expected: {1})")]
+    SequenceMismatch(u8, u8),
+
     /// Error signing ring
     #[error("Ring signing failed: {0}")]
     Ring(SignerError),
@@ -54,10 +61,15 @@ pub enum Error {
     #[error("Timeout waiting for device response")]
     RequestTimeout,
 
-    /// Transaction engine error
-    #[error("Engine operation failed: {0}")]
+    /// Transaction engine error, see [ledger_mob_apdu::status] for status word classes
+    #[error("Engine operation failed: {0:04x}")]
     Engine(u16),
 
+    /// Transaction engine error with detail string,
+    /// returned by debug and `pre-release` firmware builds
+    #[error("Engine operation failed: {0:04x} ({1})")]
+    EngineDetail(u16, String),
+
     /// User denied operation
     #[error("Operation rejected by user")]
     UserDenied,
@@ -66,10 +78,18 @@ pub enum Error {
     #[error("Invalid key object")]
     InvalidKey,
 
+    /// Invalid signature in response
+    #[error("Invalid signature")]
+    InvalidSignature,
+
     /// Invalid length
     #[error("Invalid length")]
     InvalidLength,
 
+    /// No protocol version in common with the device
+    #[error("Unsupported protocol version (device supports {0} to {1})")]
+    UnsupportedProtocol(u8, u8),
+
     /// UTF8 encoding error
     #[error("UTF8 encoding error")]
     Utf8,
@@ -92,6 +112,32 @@ pub enum Error {
     Unknown,
 }
 
+impl Error {
+    /// Fetch the [StatusClass] for engine errors
+    pub fn status_class(&self) -> Option<StatusClass> {
+        match self {
+            Error::Engine(sw) | Error::EngineDetail(sw, _) => StatusWord(*sw).class(),
+            _ => None,
+        }
+    }
+
+    /// Decode an engine error from a raw reply (data and status word),
+    /// including any detail string returned by debug firmware builds.
+    ///
+    /// Returns `None` for successful or truncated replies.
+    pub fn from_reply(resp: &[u8]) -> Option<Self> {
+        let (_, sw) = split_reply(resp)?;
+        if sw.is_ok() {
+            return None;
+        }
+
+        match error_detail(resp) {
+            Some(d) => Some(Error::EngineDetail(sw.0, d.to_string())),
+            None => Some(Error::Engine(sw.0)),
+        }
+    }
+}
+
 impl From<Error> for SignerError {
     fn from(value: Error) -> Self {
         match value {
@@ -5,10 +5,11 @@
 //! This provides methods for interacting with the device
 //! and is generic over [ledger_lib::Device]
 
-use std::{sync::Arc, time::Duration};
+use std::{ops::Range, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
-use ed25519_dalek::VerifyingKey;
+use ed25519_dalek::{Signature, VerifyingKey};
+use encdec::Encode;
 use ledger_lib::Device;
 use ledger_proto::{ApduBase, ApduReq};
 use log::debug;
@@ -16,21 +17,45 @@ use rand_core::OsRng;
 use tokio::sync::Mutex;
 
 use ledger_mob_apdu::{
-    app_info::AppFlags,
-    ident::{IdentGetReq, IdentResp, IdentSignReq},
-    key_image::{KeyImageReq, KeyImageResp},
+    address::{AddressGetReq, AddressResp, AddressVerifyReq, PublicAddressReq, PublicAddressResp},
+    app_info::{AppBuildInfo, AppFlags, BuildFeatures},
+    attestation::KeyAttestation,
+    audit::{AuditEntry, AuditLogReq, AuditLogResp},
+    capabilities::{negotiate, CapabilitiesReq, CapabilitiesResp, Features, SetProtocolVersionReq},
+    chunk::{CHUNK_BUFF_LEN, CHUNK_DATA_MAX},
+    device_nonce::{DeviceNonceReq, DeviceNonceResp, SessionChallengeReq, DEVICE_NONCE_LEN},
+    diagnostics::{DiagnosticsReq, DiagnosticsResp},
+    digest::{
+        digest_session_challenge, digest_subaddress_key_attestation, digest_wallet_key_attestation,
+    },
+    ident::{IdentDerivation, IdentGetReq, IdentResp, IdentSignReq},
+    key_counters::{KeyCountersReq, KeyCountersResp},
+    key_image::{KeyImageFromKeyReq, KeyImageReq, KeyImageResp},
+    key_path::KeyPath,
+    memory::{MemoryInfoReq, MemoryInfoResp},
     prelude::{AppInfoReq, AppInfoResp},
+    progress::{ProgressReq, ProgressResp},
+    settings::{SetFogIdReq, SettingsReq, SettingsResp},
     state::TxState,
-    subaddress_keys::{SubaddressKeyReq, SubaddressKeyResp},
-    tx::{TxInfo, TxInfoReq},
-    wallet_keys::{WalletKeyReq, WalletKeyResp},
+    subaddress_keys::{
+        SubaddressKeyAttestReq, SubaddressKeyFogReq, SubaddressKeyFogResp, SubaddressKeyPathReq,
+        SubaddressKeyReq, SubaddressKeyResp, SubaddressKeysBatchReq, SubaddressKeysBatchResp,
+        SubaddressRangeReq, SubaddressRangeResp, SUBADDRESS_KEYS_BATCH_MAX, SUBADDRESS_RANGE_MAX,
+    },
+    tx::{FogId, TxExpected, TxInfo, TxInfoReq, TX_RESPONSES_MAX},
+    ui_echo::{UiEchoReq, UiEchoResp},
+    wallet_keys::{
+        InvalidateAccountsReq, WalletKeyAttestReq, WalletKeyPathReq, WalletKeyReq, WalletKeyResp,
+    },
+    MOB_PROTO_VERSION, MOB_PROTO_VERSION_MIN,
 };
 
+use mc_account_keys::PublicAddress;
 use mc_core::{
-    account::{ViewAccount, ViewSubaddress},
+    account::{PublicSubaddress, ShortAddressHash, ViewAccount, ViewSubaddress},
     keys::TxOutPublic,
 };
-use mc_crypto_keys::RistrettoPublic;
+use mc_crypto_keys::{RistrettoPrivate, RistrettoPublic, RistrettoSignature};
 use mc_crypto_ring_signature::KeyImage;
 use mc_transaction_core::{ring_ct::InputRing, tx::Tx};
 use mc_transaction_extra::UnsignedTx;
@@ -38,7 +63,8 @@ use mc_transaction_signer::types::TxoSynced;
 
 use crate::{
     account::AccountHandle,
-    tx::{TransactionHandle, TxConfig},
+    chunk,
+    tx::{TransactionHandle, TxConfig, RING_BATCH_LEN},
     Error,
 };
 
@@ -54,6 +80,10 @@ pub struct DeviceHandle<T: Device> {
     user_timeout_s: usize,
     /// Timeout for APDU requests
     request_timeout_s: usize,
+    /// Capabilities negotiated with the device
+    capabilities: Option<MobCapabilities>,
+    /// Protocol version pinned on the device
+    protocol_version: Option<u8>,
 }
 
 /// Create a [DeviceHandle] wrapper from a type implementing [Device]
@@ -63,6 +93,8 @@ impl<T: Device> From<T> for DeviceHandle<T> {
             t: Arc::new(Mutex::new(t)),
             user_timeout_s: 10,
             request_timeout_s: 2,
+            capabilities: None,
+            protocol_version: None,
         }
     }
 }
@@ -75,6 +107,67 @@ pub struct MobAppInfo {
     pub app_version: String,
     pub protocol_version: u8,
     pub flags: AppFlags,
+    /// Build information (not reported by earlier firmware)
+    pub build: Option<MobBuildInfo>,
+}
+
+/// Application build information, identifying the exact binary running
+/// on a device, see [MobAppInfo]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MobBuildInfo {
+    /// SDK build target
+    pub target: String,
+    /// Git hash of the application build
+    pub git_hash: String,
+    /// Enabled cargo features
+    pub features: BuildFeatures,
+    /// Maximum supported ring size
+    pub ring_size_max: u8,
+}
+
+impl<'a> From<AppBuildInfo<'a>> for MobBuildInfo {
+    fn from(b: AppBuildInfo<'a>) -> Self {
+        Self {
+            target: b.target.to_string(),
+            git_hash: b.git_hash.to_string(),
+            features: b.features,
+            ring_size_max: b.ring_size_max,
+        }
+    }
+}
+
+/// b58 encoded public address with fog information,
+/// see [DeviceHandle::public_address]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MobPublicAddress {
+    /// b58 encoded `PrintableWrapper { PublicAddress }`
+    pub b58: String,
+    /// Fog ID for address
+    pub fog_id: FogId,
+    /// Fog authority signature (empty if no fog is set)
+    pub fog_sig: Vec<u8>,
+}
+
+impl<'a> From<PublicAddressResp<'a>> for MobPublicAddress {
+    fn from(r: PublicAddressResp<'a>) -> Self {
+        Self {
+            b58: r.address.to_string(),
+            fog_id: r.fog_id,
+            fog_sig: match r.fog_id {
+                FogId::None => vec![],
+                _ => r.fog_sig.to_vec(),
+            },
+        }
+    }
+}
+
+/// Capabilities negotiated with the device
+#[derive(Clone, Debug, PartialEq)]
+pub struct MobCapabilities {
+    /// Negotiated protocol version
+    pub protocol_version: u8,
+    /// Features supported by the device
+    pub features: Features,
 }
 
 impl<T: Device + Send> DeviceHandle<T> {
@@ -89,6 +182,9 @@ impl<T: Device + Send> DeviceHandle<T> {
     }
 
     /// Fetch ledger application info
+    ///
+    /// On first connection the reported protocol version is pinned where
+    /// supported by the host, as required prior to transactions.
     pub async fn app_info(&mut self) -> Result<MobAppInfo, Error> {
         let mut buff = [0u8; 256];
 
@@ -98,12 +194,225 @@ impl<T: Device + Send> DeviceHandle<T> {
             .request::<AppInfoResp>(AppInfoReq {}, &mut buff, self.request_timeout())
             .await?;
 
-        Ok(MobAppInfo {
+        let info = MobAppInfo {
             app_name: resp.name.to_string(),
             app_version: resp.version.to_string(),
             protocol_version: resp.proto,
             flags: resp.flags,
-        })
+            build: resp.build.map(MobBuildInfo::from),
+        };
+
+        // Pin protocol version, skipping devices without pinning support
+        let supported = MOB_PROTO_VERSION_MIN..=MOB_PROTO_VERSION;
+        if self.protocol_version.is_none() && supported.contains(&info.protocol_version) {
+            if let Err(e) = self.set_protocol_version(info.protocol_version).await {
+                debug!("Protocol pinning failed ({e}), device may not support pinning");
+            }
+        }
+
+        Ok(info)
+    }
+
+    /// Fetch supported protocol versions and features
+    pub async fn capabilities(&mut self) -> Result<CapabilitiesResp, Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Requesting capabilities");
+
+        let resp = self
+            .request::<CapabilitiesResp>(CapabilitiesReq {}, &mut buff, self.request_timeout())
+            .await?;
+
+        Ok(resp)
+    }
+
+    /// Negotiate protocol version and features with the device, falling back
+    /// to the [app_info][Self::app_info] protocol version where capabilities
+    /// are not supported.
+    ///
+    /// The negotiated version is pinned on devices supporting
+    /// [Features::PROTOCOL_PIN], as required prior to transactions.
+    ///
+    /// Negotiated capabilities are cached for subsequent calls.
+    pub async fn negotiate(&mut self) -> Result<MobCapabilities, Error> {
+        if let Some(c) = &self.capabilities {
+            return Ok(c.clone());
+        }
+
+        let (proto_min, proto_max, features) = match self.capabilities().await {
+            Ok(r) => (r.proto_min, r.proto_max, r.features),
+            Err(e) => {
+                debug!("Capabilities request failed ({e}), using app info");
+
+                let i = self.app_info().await?;
+                (i.protocol_version, i.protocol_version, Features::empty())
+            }
+        };
+
+        let protocol_version = negotiate(
+            (MOB_PROTO_VERSION_MIN, MOB_PROTO_VERSION),
+            (proto_min, proto_max),
+        )
+        .ok_or(Error::UnsupportedProtocol(proto_min, proto_max))?;
+
+        debug!("Negotiated protocol version {protocol_version} (features: {features:?})");
+
+        // Pin negotiated version where supported (and not already pinned)
+        if features.contains(Features::PROTOCOL_PIN)
+            && self.protocol_version != Some(protocol_version)
+        {
+            self.set_protocol_version(protocol_version).await?;
+        }
+
+        let c = MobCapabilities {
+            protocol_version,
+            features,
+        };
+        self.capabilities = Some(c.clone());
+
+        Ok(c)
+    }
+
+    /// Pin the protocol version used for subsequent requests,
+    /// see [negotiate][Self::negotiate]
+    pub async fn set_protocol_version(&mut self, version: u8) -> Result<(), Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Pinning protocol version {version}");
+
+        let _ = self
+            .request::<TxInfo>(
+                SetProtocolVersionReq::new(version),
+                &mut buff,
+                self.request_timeout(),
+            )
+            .await?;
+
+        self.protocol_version = Some(version);
+
+        Ok(())
+    }
+
+    /// Fetch key export counters since the device was last unlocked
+    pub async fn key_counters(&mut self) -> Result<KeyCountersResp, Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Requesting key export counters");
+
+        let resp = self
+            .request::<KeyCountersResp>(KeyCountersReq {}, &mut buff, self.request_timeout())
+            .await?;
+
+        Ok(resp)
+    }
+
+    /// Fetch current device settings, for checking against transactions
+    /// prior to signing
+    pub async fn settings(&mut self) -> Result<SettingsResp, Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Requesting device settings");
+
+        let resp = self
+            .request::<SettingsResp>(SettingsReq {}, &mut buff, self.request_timeout())
+            .await?;
+
+        Ok(resp)
+    }
+
+    /// Fetch summary loading / ring signing progress, for host UIs
+    pub async fn progress(&mut self) -> Result<ProgressResp, Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Requesting progress");
+
+        let resp = self
+            .request::<ProgressResp>(ProgressReq {}, &mut buff, self.request_timeout())
+            .await?;
+
+        Ok(resp)
+    }
+
+    /// Fetch engine diagnostic counters, for debugging transport issues
+    pub async fn diagnostics(&mut self) -> Result<DiagnosticsResp, Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Requesting engine diagnostics");
+
+        let resp = self
+            .request::<DiagnosticsResp>(DiagnosticsReq {}, &mut buff, self.request_timeout())
+            .await?;
+
+        Ok(resp)
+    }
+
+    /// Fetch text currently rendered on the device screen, for asserting
+    /// approver contents in automated tests
+    ///
+    /// (test-only, requires firmware built with the `speculos` feature)
+    pub async fn ui_echo(&mut self) -> Result<UiEchoResp, Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Requesting UI echo");
+
+        let resp = self
+            .request::<UiEchoResp>(UiEchoReq {}, &mut buff, self.request_timeout())
+            .await?;
+
+        Ok(resp)
+    }
+
+    /// Fetch heap, stack and function context memory usage, for catching
+    /// memory regressions on hardware
+    ///
+    /// (debug-only, requires a debug or `pre-release` firmware build)
+    pub async fn memory_info(&mut self) -> Result<MemoryInfoResp, Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Requesting memory info");
+
+        let resp = self
+            .request::<MemoryInfoResp>(MemoryInfoReq {}, &mut buff, self.request_timeout())
+            .await?;
+
+        Ok(resp)
+    }
+
+    /// Zeroize account roots cached on the device during the current
+    /// session, for use once multi-account sync is complete
+    pub async fn invalidate_accounts(&mut self) -> Result<(), Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Invalidating cached accounts");
+
+        let _ = self
+            .request::<TxInfo>(InvalidateAccountsReq {}, &mut buff, self.request_timeout())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fetch the approval audit log, most recent first
+    pub async fn audit_log(&mut self) -> Result<Vec<AuditEntry>, Error> {
+        let mut buff = [0u8; 256];
+        let mut entries = vec![];
+
+        debug!("Requesting approval audit log");
+
+        loop {
+            let req = AuditLogReq::new(entries.len() as u8);
+            let resp = self
+                .request::<AuditLogResp>(req, &mut buff, self.request_timeout())
+                .await?;
+
+            entries.extend_from_slice(&resp.entries);
+
+            if resp.entries.is_empty() || entries.len() >= resp.num_held as usize {
+                break;
+            }
+        }
+
+        Ok(entries)
     }
 
     /// Fetch root keys for the provided account index
@@ -114,7 +423,12 @@ impl<T: Device + Send> DeviceHandle<T> {
 
         let req = WalletKeyReq::new(account_index);
         let resp = self
-            .retry::<WalletKeyResp>(req, &mut buff_a, &mut buff_b)
+            .retry::<WalletKeyResp>(
+                req,
+                AppFlags::WALLET_KEYS_APPROVED,
+                &mut buff_a,
+                &mut buff_b,
+            )
             .await?;
 
         Ok(ViewAccount::new(resp.view_private, resp.spend_public))
@@ -135,7 +449,12 @@ impl<T: Device + Send> DeviceHandle<T> {
 
         let req = SubaddressKeyReq::new(account_index, subaddress_index);
         let resp = self
-            .retry::<SubaddressKeyResp>(req, &mut buff_a, &mut buff_b)
+            .retry::<SubaddressKeyResp>(
+                req,
+                AppFlags::SUBADDRESS_KEYS_APPROVED,
+                &mut buff_a,
+                &mut buff_b,
+            )
             .await?;
 
         Ok(ViewSubaddress {
@@ -144,6 +463,294 @@ impl<T: Device + Send> DeviceHandle<T> {
         })
     }
 
+    /// Fetch root keys for the account at an explicit derivation path
+    ///
+    /// (requires [Features::KEY_PATH])
+    pub async fn account_keys_path(&mut self, path: &KeyPath) -> Result<ViewAccount, Error> {
+        let (mut buff_a, mut buff_b) = ([0u8; 256], [0u8; 256]);
+
+        debug!("Requesting root keys for path: {:x?}", path.as_slice());
+
+        let req = WalletKeyPathReq::new(*path);
+        let resp = self
+            .retry::<WalletKeyResp>(
+                req,
+                AppFlags::WALLET_KEYS_APPROVED,
+                &mut buff_a,
+                &mut buff_b,
+            )
+            .await?;
+
+        if resp.account_index != path.account_index() {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        Ok(ViewAccount::new(resp.view_private, resp.spend_public))
+    }
+
+    /// Fetch subaddress keys for the account at an explicit derivation path
+    ///
+    /// (requires [Features::KEY_PATH])
+    pub async fn subaddress_keys_path(
+        &mut self,
+        path: &KeyPath,
+        subaddress_index: u64,
+    ) -> Result<ViewSubaddress, Error> {
+        let (mut buff_a, mut buff_b) = ([0u8; 256], [0u8; 256]);
+
+        debug!(
+            "Requesting subaddress keys for path: {:x?}, subaddress: {}",
+            path.as_slice(),
+            subaddress_index
+        );
+
+        let req = SubaddressKeyPathReq::new(*path, subaddress_index);
+        let resp = self
+            .retry::<SubaddressKeyResp>(
+                req,
+                AppFlags::SUBADDRESS_KEYS_APPROVED,
+                &mut buff_a,
+                &mut buff_b,
+            )
+            .await?;
+
+        if resp.account_index != path.account_index() || resp.subaddress_index != subaddress_index {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        Ok(ViewSubaddress {
+            view_private: resp.view_private,
+            spend_public: resp.spend_public,
+        })
+    }
+
+    /// Fetch subaddress keys with the fog authority signature for the
+    /// provided fog, for constructing fog-enabled public addresses
+    /// (the report URL is available via [FogId::url])
+    ///
+    /// (requires [Features::SUBADDRESS_KEYS_FOG])
+    pub async fn subaddress_keys_fog(
+        &mut self,
+        account_index: u32,
+        subaddress_index: u64,
+        fog_id: FogId,
+    ) -> Result<SubaddressKeyFogResp, Error> {
+        let (mut buff_a, mut buff_b) = ([0u8; 256], [0u8; 256]);
+
+        debug!(
+            "Requesting subaddress keys for account: {}, subaddress: {}, fog: {:?}",
+            account_index, subaddress_index, fog_id
+        );
+
+        let req = SubaddressKeyFogReq::new(account_index, subaddress_index, fog_id);
+        let resp = self
+            .retry::<SubaddressKeyFogResp>(
+                req,
+                AppFlags::SUBADDRESS_KEYS_APPROVED,
+                &mut buff_a,
+                &mut buff_b,
+            )
+            .await?;
+
+        // Check the device returned the requested keys and fog
+        if resp.account_index != account_index
+            || resp.subaddress_index != subaddress_index
+            || resp.fog_id != fog_id
+        {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        Ok(resp)
+    }
+
+    /// Fetch root keys for the provided account index with a device
+    /// attestation over the returned keys and provided challenge,
+    /// returning the keys and verified attestation public key
+    ///
+    /// (requires [Features::KEY_ATTESTATION])
+    pub async fn account_keys_attested(
+        &mut self,
+        account_index: u32,
+        challenge: [u8; 32],
+    ) -> Result<(WalletKeyResp, VerifyingKey), Error> {
+        let (mut buff_a, mut buff_b) = ([0u8; 256], [0u8; 256]);
+
+        debug!(
+            "Requesting attested root keys for account: {}",
+            account_index
+        );
+
+        let req = WalletKeyAttestReq::new(account_index, challenge);
+        let resp = self
+            .retry::<WalletKeyResp>(
+                req,
+                AppFlags::WALLET_KEYS_APPROVED,
+                &mut buff_a,
+                &mut buff_b,
+            )
+            .await?;
+
+        if resp.account_index != account_index {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        let d = digest_wallet_key_attestation(
+            &challenge,
+            account_index,
+            resp.view_private.as_ref(),
+            resp.spend_public.as_ref(),
+        );
+        let public_key = verify_attestation(resp.attestation.as_ref(), &d)?;
+
+        Ok((resp, public_key))
+    }
+
+    /// Fetch subaddress keys for the provided account and subaddress index
+    /// with a device attestation over the returned keys and provided challenge,
+    /// returning the keys and verified attestation public key
+    ///
+    /// (requires [Features::KEY_ATTESTATION])
+    pub async fn subaddress_keys_attested(
+        &mut self,
+        account_index: u32,
+        subaddress_index: u64,
+        challenge: [u8; 32],
+    ) -> Result<(SubaddressKeyResp, VerifyingKey), Error> {
+        let (mut buff_a, mut buff_b) = ([0u8; 256], [0u8; 256]);
+
+        debug!(
+            "Requesting attested subaddress keys for account: {}, subaddress: {}",
+            account_index, subaddress_index
+        );
+
+        let req = SubaddressKeyAttestReq::new(account_index, subaddress_index, challenge);
+        let resp = self
+            .retry::<SubaddressKeyResp>(
+                req,
+                AppFlags::SUBADDRESS_KEYS_APPROVED,
+                &mut buff_a,
+                &mut buff_b,
+            )
+            .await?;
+
+        if resp.account_index != account_index || resp.subaddress_index != subaddress_index {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        let d = digest_subaddress_key_attestation(
+            &challenge,
+            account_index,
+            subaddress_index,
+            resp.view_private.as_ref(),
+            resp.spend_public.as_ref(),
+        );
+        let public_key = verify_attestation(resp.attestation.as_ref(), &d)?;
+
+        Ok((resp, public_key))
+    }
+
+    /// Fetch public keys for a contiguous range of subaddresses,
+    /// batching requests to minimise round-trips
+    pub async fn subaddress_range(
+        &mut self,
+        account_index: u32,
+        range: Range<u64>,
+    ) -> Result<Vec<PublicSubaddress>, Error> {
+        debug!(
+            "Requesting subaddress public keys for account: {}, subaddresses: {:?}",
+            account_index, range
+        );
+
+        let mut subaddresses = Vec::with_capacity((range.end - range.start) as usize);
+
+        let mut index = range.start;
+        while index < range.end {
+            let (mut buff_a, mut buff_b) = ([0u8; 256], [0u8; 256]);
+
+            let count = (range.end - index).min(SUBADDRESS_RANGE_MAX as u64);
+            let req = SubaddressRangeReq::new(account_index, index, count as u8);
+
+            let resp = self
+                .retry::<SubaddressRangeResp>(
+                    req,
+                    AppFlags::SUBADDRESS_KEYS_APPROVED,
+                    &mut buff_a,
+                    &mut buff_b,
+                )
+                .await?;
+
+            // Check the device returned the requested range
+            if resp.account_index != account_index
+                || resp.start != index
+                || resp.keys.len() != count as usize
+            {
+                return Err(Error::UnexpectedResponse);
+            }
+
+            subaddresses.extend(resp.keys.into_iter().map(|(view_public, spend_public)| {
+                PublicSubaddress {
+                    view_public,
+                    spend_public,
+                }
+            }));
+
+            index += count;
+        }
+
+        Ok(subaddresses)
+    }
+
+    /// Fetch keys for a contiguous range of subaddresses (for scanning),
+    /// batching requests to minimise round-trips
+    pub async fn subaddress_keys_batch(
+        &mut self,
+        account_index: u32,
+        range: Range<u64>,
+    ) -> Result<Vec<ViewSubaddress>, Error> {
+        debug!(
+            "Requesting subaddress keys for account: {}, subaddresses: {:?}",
+            account_index, range
+        );
+
+        let mut subaddresses = Vec::with_capacity((range.end - range.start) as usize);
+
+        let mut index = range.start;
+        while index < range.end {
+            let (mut buff_a, mut buff_b) = ([0u8; 256], [0u8; 256]);
+
+            let count = (range.end - index).min(SUBADDRESS_KEYS_BATCH_MAX as u64);
+            let req = SubaddressKeysBatchReq::new(account_index, index, count as u8);
+
+            let resp = self
+                .retry::<SubaddressKeysBatchResp>(
+                    req,
+                    AppFlags::SUBADDRESS_KEYS_APPROVED,
+                    &mut buff_a,
+                    &mut buff_b,
+                )
+                .await?;
+
+            // Check the device returned the requested batch
+            if resp.account_index != account_index
+                || resp.start != index
+                || resp.keys.len() != count as usize
+            {
+                return Err(Error::UnexpectedResponse);
+            }
+
+            subaddresses.extend(resp.keys.into_iter().map(|(spend_public, view_private)| {
+                ViewSubaddress {
+                    view_private,
+                    spend_public,
+                }
+            }));
+
+            index += count;
+        }
+
+        Ok(subaddresses)
+    }
+
     /// Resolve a key image for a given tx_out
     pub async fn key_image(
         &mut self,
@@ -151,6 +758,39 @@ impl<T: Device + Send> DeviceHandle<T> {
         subaddress_index: u64,
         tx_public_key: RistrettoPublic,
     ) -> Result<KeyImage, Error> {
+        self.key_image_resp(account_index, subaddress_index, tx_public_key)
+            .await
+            .map(|r| r.key_image)
+    }
+
+    /// Resolve a key image for a given tx_out, checking the subaddress
+    /// spend public key derived by the device matches the expected `spend_public`
+    pub async fn key_image_checked(
+        &mut self,
+        account_index: u32,
+        subaddress_index: u64,
+        tx_public_key: RistrettoPublic,
+        spend_public: &RistrettoPublic,
+    ) -> Result<KeyImage, Error> {
+        let resp = self
+            .key_image_resp(account_index, subaddress_index, tx_public_key)
+            .await?;
+
+        if resp.spend_public.as_ref() != spend_public {
+            return Err(Error::InvalidKey);
+        }
+
+        Ok(resp.key_image)
+    }
+
+    /// Resolve a key image for a given tx_out, returning the full
+    /// [KeyImageResp] including the device-derived subaddress spend public key
+    pub async fn key_image_resp(
+        &mut self,
+        account_index: u32,
+        subaddress_index: u64,
+        tx_public_key: RistrettoPublic,
+    ) -> Result<KeyImageResp, Error> {
         let (mut buff_a, mut buff_b) = ([0u8; 256], [0u8; 256]);
 
         debug!(
@@ -159,18 +799,94 @@ impl<T: Device + Send> DeviceHandle<T> {
         );
 
         let req = KeyImageReq::new(account_index, subaddress_index, tx_public_key.into());
+        self.retry::<KeyImageResp>(req, AppFlags::KEY_IMAGES_APPROVED, &mut buff_a, &mut buff_b)
+            .await
+    }
+
+    /// Compute a key image from a onetime private key recovered by the host
+    /// (for view-only-signer workflows), requiring explicit user approval
+    pub async fn key_image_from_key(
+        &mut self,
+        account_index: u32,
+        subaddress_index: u64,
+        onetime_private_key: RistrettoPrivate,
+    ) -> Result<KeyImage, Error> {
+        let (mut buff_a, mut buff_b) = ([0u8; 256], [0u8; 256]);
+
+        debug!(
+            "Resolving key image from onetime private key for account: {}, subaddress: {}",
+            account_index, subaddress_index
+        );
+
+        let req =
+            KeyImageFromKeyReq::new(account_index, subaddress_index, onetime_private_key.into());
         let resp = self
-            .retry::<KeyImageResp>(req, &mut buff_a, &mut buff_b)
+            .retry::<KeyImageResp>(
+                req,
+                AppFlags::ONETIME_KEY_IMAGES_APPROVED,
+                &mut buff_a,
+                &mut buff_b,
+            )
             .await?;
 
         Ok(resp.key_image)
     }
 
-    /// Helper to retry for requests requiring user approval
+    /// Fetch the b58 encoded public address for a given account,
+    /// subaddress and fog (requires subaddress key approval)
+    pub async fn public_address(
+        &mut self,
+        account_index: u32,
+        subaddress_index: u64,
+        fog_id: FogId,
+    ) -> Result<MobPublicAddress, Error> {
+        debug!(
+            "Requesting public address for account: {}, subaddress: {}, fog: {:?}",
+            account_index, subaddress_index, fog_id
+        );
+
+        let mut buff = [0u8; CHUNK_BUFF_LEN];
+        let req = PublicAddressReq::new(account_index, subaddress_index, fog_id);
+
+        // Encoded addresses may exceed the maximum APDU payload,
+        // so are always fetched using the chunked exchange
+        let r = self
+            .request_chunked::<PublicAddressResp>(req, &mut buff, self.request_timeout())
+            .await
+            .map(MobPublicAddress::from);
+
+        let resp = match r {
+            Ok(v) => v,
+            // Await approval and re-issue request
+            Err(_) => {
+                self.wait_approval(AppFlags::SUBADDRESS_KEYS_APPROVED)
+                    .await?;
+
+                let mut buff = [0u8; CHUNK_BUFF_LEN];
+                let timeout = self.request_timeout();
+                let resp = self
+                    .request_chunked::<PublicAddressResp>(req, &mut buff, timeout)
+                    .await?;
+
+                MobPublicAddress::from(resp)
+            }
+        };
+
+        // Check the device returned the requested fog
+        if resp.fog_id != fog_id {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        Ok(resp)
+    }
+
+    /// Helper to retry for requests requiring user approval,
+    /// polling on the provided approval flag
     // TODO: fix apdu lifetimes so we don't need multiple buffers here / can return immediate errors
     async fn retry<'a, ANS: ApduBase<'a>>(
         &mut self,
         req: impl ApduReq<'_> + Clone + Send,
+        approval: AppFlags,
         buff_a: &'a mut [u8],
         buff_b: &'a mut [u8],
     ) -> Result<ANS, Error> {
@@ -182,11 +898,23 @@ impl<T: Device + Send> DeviceHandle<T> {
             return Ok(v);
         };
 
-        // Poll on app unlock state
+        // Await approval
+        self.wait_approval(approval).await?;
+
+        // Re-issue request
+        let resp = self
+            .request::<ANS>(req.clone(), buff_b, self.request_timeout())
+            .await?;
+
+        Ok(resp)
+    }
+
+    /// Helper to poll app state until the provided approval flag is set
+    async fn wait_approval(&mut self, approval: AppFlags) -> Result<(), Error> {
         for i in 0..self.user_timeout_s {
             let info = self.app_info().await?;
-            match info.flags.contains(AppFlags::UNLOCKED) {
-                true => break,
+            match info.flags.contains(approval) {
+                true => return Ok(()),
                 false if i == self.user_timeout_s - 1 => return Err(Error::UserTimeout),
                 false => {
                     debug!("Waiting for user approval: {}s", i);
@@ -195,12 +923,21 @@ impl<T: Device + Send> DeviceHandle<T> {
             }
         }
 
-        // Re-issue request
-        let resp = self
-            .request::<ANS>(req.clone(), buff_b, self.request_timeout())
-            .await?;
+        Ok(())
+    }
 
-        Ok(resp)
+    /// Issue a request using the chunked exchange protocol
+    /// (see [ledger_mob_apdu::chunk]), for requests or responses
+    /// exceeding the maximum APDU payload.
+    ///
+    /// The response is reassembled into `buff` prior to decoding.
+    pub async fn request_chunked<'a, 'b, RESP: ApduBase<'b>>(
+        &mut self,
+        request: impl ApduReq<'a> + Send,
+        buff: &'b mut [u8],
+        timeout: Duration,
+    ) -> Result<RESP, Error> {
+        chunk::request_chunked(self, request, buff, timeout).await
     }
 
     /// Fetch a handle to a specific on-device account by SLIP-0010 index
@@ -222,6 +959,9 @@ impl<T: Device + Send> DeviceHandle<T> {
         approval_timeout_s: u32,
         unsigned: UnsignedTx,
     ) -> Result<(Tx, Vec<TxoSynced>), Error> {
+        // Check protocol compatibility
+        let caps = self.negotiate().await?;
+
         // Start device transaction
         debug!("Starting transaction");
         let mut signer = TransactionHandle::new(
@@ -231,6 +971,24 @@ impl<T: Device + Send> DeviceHandle<T> {
                 num_rings: unsigned.rings.len(),
                 request_timeout: self.request_timeout(),
                 user_timeout: Duration::from_secs(approval_timeout_s as u64),
+                current_block: None,
+                ring_batch: match caps.features.contains(Features::BATCH) {
+                    true => RING_BATCH_LEN,
+                    false => 0,
+                },
+                response_batch: match caps.features.contains(Features::TX_GET_RESPONSES) {
+                    true => TX_RESPONSES_MAX,
+                    false => 0,
+                },
+                compact_summary: caps.features.contains(Features::COMPACT_SUMMARY),
+                expected: match caps.features.contains(Features::TX_EXPECTED) {
+                    true => Some(TxExpected {
+                        fee_value: unsigned.tx_prefix.fee,
+                        fee_token_id: unsigned.tx_prefix.fee_token_id,
+                        tombstone_block: unsigned.tx_prefix.tombstone_block,
+                    }),
+                    false => None,
+                },
             },
             self.t.clone(),
         )
@@ -248,18 +1006,37 @@ impl<T: Device + Send> DeviceHandle<T> {
             signing_data.mlsag_signing_digest
         );
 
-        // Load transaction summary
-        debug!("Loading tx summary");
-        signer
-            .set_tx_summary(unsigned.block_version, &digest.0, &summary, &unblinding)
-            .await?;
+        let r = async {
+            // Load transaction summary
+            debug!("Loading tx summary");
+            signer
+                .set_tx_summary(unsigned.block_version, &digest.0, &summary, &unblinding)
+                .await?;
+
+            // Await transaction approval
+            signer.await_approval(approval_timeout_s).await?;
 
-        // Await transaction approval
-        signer.await_approval(approval_timeout_s).await?;
+            // Sign rings
+            debug!("Executing signing operation");
+            let signature = signing_data.sign(&unsigned.rings, &signer, &mut OsRng {})?;
 
-        // Sign rings
-        debug!("Executing signing operation");
-        let signature = signing_data.sign(&unsigned.rings, &signer, &mut OsRng {})?;
+            Ok::<_, Error>(signature)
+        }
+        .await;
+
+        // Abort the device transaction on failure
+        let signature = match r {
+            Ok(v) => v,
+            Err(e) => {
+                debug!("Aborting transaction: {e}");
+
+                if caps.features.contains(Features::TX_ABORT) {
+                    let _ = signer.abort().await;
+                }
+
+                return Err(e);
+            }
+        };
 
         debug!("Signing complete");
 
@@ -293,22 +1070,34 @@ impl<T: Device + Send> DeviceHandle<T> {
         Ok((tx, txos))
     }
 
-    /// Execute and identity challenge and response
+    /// Execute and identity challenge and response using the provided
+    /// [IdentDerivation]
     pub async fn identity(
         &mut self,
         index: u32,
         uri: &str,
         challenge: &[u8],
+        derivation: IdentDerivation,
     ) -> Result<(VerifyingKey, [u8; 64]), Error> {
         let mut buff = [0u8; 256];
 
-        debug!("Executing identity challenge");
+        debug!("Executing identity challenge ({derivation})");
 
-        // Issue signing request
-        let req = IdentSignReq::new(index, uri, challenge);
-        let resp = self
-            .request::<TxInfo>(req, &mut buff, self.user_timeout())
-            .await?;
+        // Issue signing request, long challenges exceeding the
+        // maximum APDU payload are sent using the chunked exchange
+        let mut req = IdentSignReq::new(index, uri, challenge);
+        req.derivation = derivation;
+
+        let resp = match req.encode_len()? > CHUNK_DATA_MAX {
+            true => {
+                self.request_chunked::<TxInfo>(req, &mut buff, self.user_timeout())
+                    .await?
+            }
+            false => {
+                self.request::<TxInfo>(req, &mut buff, self.user_timeout())
+                    .await?
+            }
+        };
 
         if resp.state != TxState::IdentPending {
             return Err(Error::InvalidState(resp.state, TxState::IdentPending));
@@ -341,6 +1130,182 @@ impl<T: Device + Send> DeviceHandle<T> {
 
         Ok((public_key, resp.signature))
     }
+
+    /// Fetch a fresh single-use device nonce, for binding a subsequent
+    /// [session challenge][Self::session_challenge] to device presence
+    ///
+    /// (requires [Features::DEVICE_NONCE])
+    pub async fn device_nonce(&mut self) -> Result<[u8; DEVICE_NONCE_LEN], Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Requesting device nonce");
+
+        let resp = self
+            .request::<DeviceNonceResp>(DeviceNonceReq {}, &mut buff, self.request_timeout())
+            .await?;
+
+        Ok(resp.nonce)
+    }
+
+    /// Sign a host challenge bound to the device nonce returned by
+    /// [Self::device_nonce], returning the verified device identity
+    /// public key and signature
+    ///
+    /// (requires [Features::DEVICE_NONCE])
+    pub async fn session_challenge(
+        &mut self,
+        nonce: &[u8; DEVICE_NONCE_LEN],
+        challenge: [u8; 32],
+    ) -> Result<(VerifyingKey, [u8; 64]), Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Requesting session challenge signature");
+
+        let resp = self
+            .request::<IdentResp>(
+                SessionChallengeReq::new(challenge),
+                &mut buff,
+                self.request_timeout(),
+            )
+            .await?;
+
+        let public_key =
+            VerifyingKey::from_bytes(&resp.public_key).map_err(|_| Error::InvalidKey)?;
+        public_key
+            .verify_strict(
+                &digest_session_challenge(nonce, &challenge),
+                &Signature::from_bytes(&resp.signature),
+            )
+            .map_err(|_| Error::InvalidSignature)?;
+
+        Ok((public_key, resp.signature))
+    }
+
+    /// Display an address on the device for user verification, returning
+    /// the confirmed [PublicAddress] once the device acknowledgement is
+    /// checked against a random challenge
+    pub async fn verify_address(
+        &mut self,
+        account_index: u32,
+        subaddress_index: u64,
+        fog_id: FogId,
+    ) -> Result<PublicAddress, Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Executing address verification");
+
+        // Issue verification request with a fresh challenge
+        let challenge: [u8; 32] = rand::random();
+        let req = AddressVerifyReq::new(account_index, subaddress_index, fog_id, challenge);
+        let resp = self
+            .request::<TxInfo>(req, &mut buff, self.user_timeout())
+            .await?;
+
+        if resp.state != TxState::AddressPending {
+            return Err(Error::InvalidState(resp.state, TxState::AddressPending));
+        }
+
+        // Await user approval
+        let n = self.user_timeout_s;
+        for i in 0..n {
+            let resp = self
+                .request::<TxInfo>(TxInfoReq, &mut buff, self.user_timeout())
+                .await?;
+
+            match resp.state {
+                TxState::AddressApproved => break,
+                TxState::AddressPending if i + 1 < n => {
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                }
+                TxState::AddressPending => return Err(Error::UserTimeout),
+                _ => return Err(Error::UserDenied),
+            }
+        }
+
+        // Fetch signed address
+        let resp = self
+            .request::<AddressResp>(AddressGetReq, &mut buff, self.user_timeout())
+            .await?;
+
+        let fog_sig = match resp.fog_id {
+            FogId::None => vec![],
+            _ => resp.fog_sig.to_vec(),
+        };
+        let address = PublicAddress::new_with_fog(
+            resp.spend_public.as_ref(),
+            resp.view_public.as_ref(),
+            resp.fog_id.url(),
+            String::new(),
+            fog_sig,
+        );
+
+        // Check acknowledgement over address hash and challenge
+        let short_hash = ShortAddressHash::from(&address);
+        let mut message = [0u8; 48];
+        message[..16].copy_from_slice(short_hash.as_ref());
+        message[16..].copy_from_slice(&challenge);
+
+        let signature = RistrettoSignature::try_from(&resp.signature[..])
+            .map_err(|_| Error::InvalidSignature)?;
+        let spend_public: &RistrettoPublic = resp.spend_public.as_ref();
+        spend_public
+            .verify_schnorrkel(b"Address verification", &message, &signature)
+            .map_err(|_| Error::InvalidSignature)?;
+
+        Ok(address)
+    }
+
+    /// Request a change of the device's active fog id,
+    /// returning once the change is confirmed on the device
+    pub async fn set_fog_id(&mut self, fog_id: FogId) -> Result<(), Error> {
+        let mut buff = [0u8; 256];
+
+        debug!("Requesting fog id change: {fog_id:?}");
+
+        // Issue fog change request
+        let resp = self
+            .request::<TxInfo>(SetFogIdReq::new(fog_id), &mut buff, self.user_timeout())
+            .await?;
+
+        if resp.state != TxState::FogPending {
+            return Err(Error::InvalidState(resp.state, TxState::FogPending));
+        }
+
+        // Await user approval
+        let n = self.user_timeout_s;
+        for i in 0..n {
+            let resp = self
+                .request::<TxInfo>(TxInfoReq, &mut buff, self.user_timeout())
+                .await?;
+
+            match resp.state {
+                TxState::FogApproved => return Ok(()),
+                TxState::FogPending if i + 1 < n => {
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                }
+                TxState::FogPending => return Err(Error::UserTimeout),
+                _ => return Err(Error::UserDenied),
+            }
+        }
+
+        Err(Error::UserTimeout)
+    }
+}
+
+/// Verify a [KeyAttestation] over the provided digest,
+/// returning the attestation public key
+fn verify_attestation(
+    attestation: Option<&KeyAttestation>,
+    digest: &[u8; 32],
+) -> Result<VerifyingKey, Error> {
+    let a = attestation.ok_or(Error::UnexpectedResponse)?;
+
+    let public_key = VerifyingKey::from_bytes(&a.public_key).map_err(|_| Error::InvalidKey)?;
+    public_key
+        .verify_strict(digest, &Signature::from_bytes(&a.signature))
+        .map_err(|_| Error::InvalidSignature)?;
+
+    Ok(public_key)
 }
 
 /// Re-export [Device] trait for MobileCoin [DeviceHandle]
@@ -15,11 +15,17 @@ pub use ledger_lib::{
 pub use ledger_mob_apdu::{self as apdu};
 
 mod handle;
-pub use handle::DeviceHandle;
+pub use handle::{DeviceHandle, MobAppInfo, MobBuildInfo, MobCapabilities, MobPublicAddress};
 
 mod error;
 pub use error::Error;
 
+mod batch;
+mod chunk;
+
+mod session;
+pub use session::SessionDevice;
+
 pub mod tx;
 
 pub mod account;
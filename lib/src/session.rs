@@ -0,0 +1,157 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Encrypted APDU sessions
+//!
+//! [SessionDevice] wraps a [Device] to establish an encrypted session
+//! (see [ledger_mob_apdu::session]), transparently wrapping requests
+//! carrying sensitive material once the session is open.
+//!
+//! Sessions should be opened prior to wrapping the [SessionDevice]
+//! in a [DeviceHandle][crate::DeviceHandle].
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ledger_lib::Device;
+use ledger_proto::{ApduBase, ApduError, ApduReq};
+use log::debug;
+use rand_core::OsRng;
+
+use ledger_mob_apdu::{
+    chunk::CHUNK_BUFF_LEN,
+    session::{
+        requires_session, HostHandshake, HostSession, SessionInitResp, SessionResp,
+        SESSION_BUFF_LEN, SESSION_TAG_LEN,
+    },
+    MOB_APDU_CLA,
+};
+
+use crate::{chunk, Error};
+
+/// [Device] wrapper, encrypting sensitive requests and responses
+/// once a session is established
+pub struct SessionDevice<T: Device> {
+    t: T,
+    session: Option<HostSession>,
+}
+
+impl<T: Device + Send> SessionDevice<T> {
+    /// Wrap a [Device], sessions must be established using [SessionDevice::open]
+    pub fn new(t: T) -> Self {
+        Self { t, session: None }
+    }
+
+    /// Establish a new session, replacing any existing session
+    pub async fn open(&mut self, timeout: Duration) -> Result<(), Error> {
+        self.session = None;
+
+        let h = HostHandshake::new(&mut OsRng);
+
+        let mut buff = [0u8; 256];
+        let resp = self
+            .t
+            .request::<SessionInitResp>(h.request(), &mut buff, timeout)
+            .await?;
+
+        self.session = Some(h.finish(&resp)?);
+
+        debug!("Session established");
+
+        Ok(())
+    }
+
+    /// Close the current session
+    ///
+    /// Devices retain session state until the next handshake
+    /// or lock, at which point sensitive requests are rejected.
+    pub fn close(&mut self) {
+        self.session = None;
+    }
+
+    /// Check whether a session is open
+    pub fn is_open(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Fetch the underlying device
+    pub fn inner(&mut self) -> &mut T {
+        &mut self.t
+    }
+
+    /// Wrap a request in the current session, returning the
+    /// decrypted response length in `buff`
+    async fn wrapped<'a>(
+        &mut self,
+        request: impl ApduReq<'a> + Send,
+        buff: &mut [u8],
+        timeout: Duration,
+    ) -> Result<usize, Error> {
+        let session = match &mut self.session {
+            Some(s) => s,
+            None => return Err(Error::UnexpectedResponse),
+        };
+
+        let ins = request.header().ins;
+
+        // Encode and encrypt request
+        let mut req_buff = [0u8; SESSION_BUFF_LEN];
+        let n = request.encode(&mut req_buff[..SESSION_BUFF_LEN - SESSION_TAG_LEN])?;
+        let req = session.seal(ins, &mut req_buff, n)?;
+
+        debug!("Wrapped request (ins: {ins:02x} counter: {})", req.counter);
+
+        // Wrapped requests may exceed the maximum APDU payload,
+        // so are always sent using the chunked exchange
+        let mut resp_buff = [0u8; CHUNK_BUFF_LEN];
+        let resp: SessionResp =
+            chunk::request_chunked(&mut self.t, req, &mut resp_buff, timeout).await?;
+
+        // Decrypt response
+        let n = session.open(ins, &resp, buff)?;
+
+        Ok(n)
+    }
+}
+
+impl<T: Device> From<T> for SessionDevice<T> {
+    fn from(t: T) -> Self {
+        Self { t, session: None }
+    }
+}
+
+/// Map library errors to transport errors for [Device] responses
+fn transport_err(e: Error) -> ledger_lib::Error {
+    match e {
+        Error::Transport(e) => e,
+        Error::Apdu(e) => e.into(),
+        _ => ApduError::InvalidEncoding.into(),
+    }
+}
+
+#[async_trait]
+impl<T: Device + Send> Device for SessionDevice<T> {
+    async fn request<'a, 'b, RESP: ApduBase<'b>>(
+        &mut self,
+        request: impl ApduReq<'a> + Send,
+        buff: &'b mut [u8],
+        timeout: Duration,
+    ) -> Result<RESP, ledger_lib::Error> {
+        let h = request.header();
+
+        // Pass through requests not requiring a session
+        if self.session.is_none() || h.cla != MOB_APDU_CLA || !requires_session(h.ins) {
+            return self.t.request(request, buff, timeout).await;
+        }
+
+        // Wrap sensitive requests
+        let n = self
+            .wrapped(request, buff, timeout)
+            .await
+            .map_err(transport_err)?;
+
+        let buff: &'b [u8] = buff;
+        let (resp, _) = RESP::decode(&buff[..n])?;
+
+        Ok(resp)
+    }
+}
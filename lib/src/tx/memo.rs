@@ -2,14 +2,22 @@
 
 //! Memo signing API
 //!
-//!
+//! Devices may hold the first memo of a transaction for on-device review
+//! ([TxState::MemoPending]), in which case the request is re-issued once
+//! the memo has been approved.
+
+use std::time::Duration;
 
 use futures::executor::block_on;
 use ledger_lib::Device;
 use ledger_mob_apdu::{
     state::{Digest, TxState},
-    tx::{TxMemoSig, TxMemoSign},
+    tx::{
+        TxInfo, TxInfoReq, TxMemoFlags, TxMemoSig, TxMemoSign, TxSequenced, MEMO_CHUNK_LEN,
+        MEMO_PAYLOAD_MAX,
+    },
 };
+use log::debug;
 
 use mc_core::{account::PublicSubaddress, keys::TxOutPublic};
 use mc_transaction_signer::traits::MemoHmacSigner;
@@ -76,7 +84,8 @@ impl<T: Device> TransactionHandle<T> {
             &target_subaddress,
             *memo_type,
             *memo_data_sans_hmac,
-        );
+        )
+        .with_seq(self.next_seq());
 
         // Update transaction digest
         let digest = {
@@ -84,10 +93,20 @@ impl<T: Device> TransactionHandle<T> {
             Digest::update(&mut state.digest, &tx_memo_sign.hash()).clone()
         };
 
-        // Execute memo signing
-        let r = t
-            .request::<TxMemoSig>(tx_memo_sign, &mut buff, self.info.request_timeout)
-            .await?;
+        // Execute memo signing, re-issuing the request following review
+        let r = match t
+            .request::<TxMemoSig>(tx_memo_sign.clone(), &mut buff, self.info.request_timeout)
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => match self.await_memo_review(&mut t).await? {
+                true => {
+                    t.request::<TxMemoSig>(tx_memo_sign, &mut buff, self.info.request_timeout)
+                        .await?
+                }
+                false => return Err(e.into()),
+            },
+        };
 
         // Check state and expected digest
         check_state(r.state, TxState::SignMemos)?;
@@ -101,4 +120,137 @@ impl<T: Device> TransactionHandle<T> {
 
         Ok(r.hmac)
     }
+
+    /// Asynchronously compute the HMAC signature for a memo payload of up to
+    /// [MEMO_PAYLOAD_MAX] bytes, streaming payloads larger than
+    /// [MEMO_CHUNK_LEN] across chunked requests.
+    ///
+    /// The first chunk must contain a complete standard memo payload.
+    pub async fn memo_sign_payload(
+        &self,
+        sender_subaddress_index: u64,
+        tx_public_key: &TxOutPublic,
+        target_subaddress: PublicSubaddress,
+        memo_type: &[u8; 2],
+        payload: &[u8],
+    ) -> Result<[u8; 16], Error> {
+        // Standard payloads use a single request
+        if payload.len() == MEMO_CHUNK_LEN {
+            let mut p = [0u8; MEMO_CHUNK_LEN];
+            p.copy_from_slice(payload);
+
+            return self
+                .memo_sign(
+                    sender_subaddress_index,
+                    tx_public_key,
+                    target_subaddress,
+                    memo_type,
+                    &p,
+                )
+                .await;
+        }
+
+        // Leading chunk must be a complete memo payload
+        if payload.len() < MEMO_CHUNK_LEN || payload.len() > MEMO_PAYLOAD_MAX {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut buff = [0u8; 256];
+        let mut t = self.t.lock().await;
+
+        let chunks = payload.chunks(MEMO_CHUNK_LEN);
+        let n = chunks.len();
+
+        for (i, c) in chunks.enumerate() {
+            let mut flags = TxMemoFlags::empty();
+            flags.set(TxMemoFlags::CONTINUATION, i > 0);
+            flags.set(TxMemoFlags::MORE, i + 1 < n);
+
+            // Build memo chunk request
+            let tx_memo_sign = TxMemoSign::new_chunk(
+                sender_subaddress_index,
+                tx_public_key.clone(),
+                &target_subaddress,
+                *memo_type,
+                c,
+                flags,
+            )
+            .with_seq(self.next_seq());
+
+            // Update transaction digest
+            let digest = self.update_digest(&tx_memo_sign.hash());
+
+            // Intermediate chunks return transaction state only,
+            // with the leading chunk re-issued following review
+            if flags.contains(TxMemoFlags::MORE) {
+                let r = match t
+                    .request::<TxInfo>(tx_memo_sign.clone(), &mut buff, self.info.request_timeout)
+                    .await
+                {
+                    Ok(v) => v,
+                    Err(e) if i > 0 => return Err(e.into()),
+                    Err(e) => match self.await_memo_review(&mut t).await? {
+                        true => {
+                            t.request::<TxInfo>(tx_memo_sign, &mut buff, self.info.request_timeout)
+                                .await?
+                        }
+                        false => return Err(e.into()),
+                    },
+                };
+
+                check_state(r.state, TxState::SignMemos)?;
+                check_digest(&r.digest, &digest)?;
+
+                continue;
+            }
+
+            // Final chunk returns the memo HMAC
+            let r = t
+                .request::<TxMemoSig>(tx_memo_sign, &mut buff, self.info.request_timeout)
+                .await?;
+
+            check_state(r.state, TxState::SignMemos)?;
+            check_digest(&r.digest, &digest)?;
+
+            // Update submitted memo count
+            {
+                let mut state = self.state.borrow_mut();
+                state.memo_count += 1;
+            }
+
+            return Ok(r.hmac);
+        }
+
+        Err(Error::InvalidLength)
+    }
+
+    /// Await on-device review where a memo is held pending approval,
+    /// returning true once approved or false where no review is pending
+    async fn await_memo_review(&self, t: &mut T) -> Result<bool, Error> {
+        let mut buff = [0u8; 256];
+
+        // Only the first memo of each transaction is reviewed
+        if self.state.borrow().memo_count > 0 {
+            return Ok(false);
+        }
+
+        for i in 0..self.info.user_timeout.as_secs() {
+            let r = t
+                .request::<TxInfo>(TxInfoReq {}, &mut buff, self.info.request_timeout)
+                .await?;
+
+            debug!("awaiting memo review (state: {:?})", r.state);
+
+            match r.state {
+                TxState::MemoPending => (),
+                TxState::SignMemos if i > 0 => return Ok(true),
+                TxState::TxDenied => return Err(Error::UserDenied),
+                _ => return Ok(false),
+            }
+
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+
+        Err(Error::UserTimeout)
+    }
 }
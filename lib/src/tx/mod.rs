@@ -13,8 +13,12 @@ use ledger_lib::Device;
 use ledger_proto::{ApduBase, ApduReq};
 
 use ledger_mob_apdu::{
+    ct::ct_eq,
     state::{Digest, TxState},
-    tx::{TxComplete, TxInfo, TxInfoReq, TxInit, TxSetMessage},
+    tx::{
+        next_seq, TxAbort, TxAuthenticated, TxComplete, TxCompleteResp, TxExpected, TxInfo,
+        TxInfoReq, TxInit, TxSequenced, TxSetMessage,
+    },
 };
 
 use crate::Error;
@@ -25,6 +29,9 @@ mod ring;
 mod subaddress;
 mod summary;
 
+/// Default number of txouts loaded per batched request when signing rings
+pub const RING_BATCH_LEN: usize = 4;
+
 /// Configuration for a transaction operation
 #[derive(Clone, Debug, PartialEq)]
 pub struct TxConfig {
@@ -42,6 +49,29 @@ pub struct TxConfig {
 
     /// User interaction timeout
     pub user_timeout: Duration,
+
+    /// Current block index, used by the device to check the
+    /// tombstone block is plausible
+    pub current_block: Option<u64>,
+
+    /// Number of txouts loaded per request when signing rings,
+    /// batching is disabled where this is 0 or 1
+    /// (requires [Features::BATCH][ledger_mob_apdu::capabilities::Features::BATCH])
+    pub ring_batch: usize,
+
+    /// Number of response scalars fetched per request when signing rings,
+    /// multi-scalar fetches are disabled where this is 0 or 1
+    /// (requires [Features::TX_GET_RESPONSES][ledger_mob_apdu::capabilities::Features::TX_GET_RESPONSES])
+    pub response_batch: usize,
+
+    /// Use compact encodings for TxOut summary requests
+    /// (requires [Features::COMPACT_SUMMARY][ledger_mob_apdu::capabilities::Features::COMPACT_SUMMARY])
+    pub compact_summary: bool,
+
+    /// Expected fee and tombstone block, checked by the device against
+    /// the transaction summary
+    /// (requires [Features::TX_EXPECTED][ledger_mob_apdu::capabilities::Features::TX_EXPECTED])
+    pub expected: Option<TxExpected>,
 }
 
 /// Handle to a hardware wallet configured for transaction execution
@@ -68,6 +98,9 @@ struct TransactionState {
 
     /// Number of rings
     ring_count: usize,
+
+    /// Last issued request sequence number
+    seq: u8,
 }
 
 impl<T: Device + Send> TransactionHandle<T> {
@@ -76,7 +109,8 @@ impl<T: Device + Send> TransactionHandle<T> {
         let mut buff = [0u8; 256];
 
         // Setup transaction
-        let tx_init = TxInit::new(info.account_index, info.num_rings as u8);
+        let mut tx_init = TxInit::new(info.account_index, info.num_rings as u8).with_seq(1);
+        tx_init.expected = info.expected;
         let mut t = transport.lock().await;
 
         let r = t
@@ -86,6 +120,7 @@ impl<T: Device + Send> TransactionHandle<T> {
 
         // TODO: Check the device has entered the Init state
         //Self::check_state(r.state, TxState::Init)?;
+        check_seq(r.seq, 1)?;
 
         // Return transaction handle
         Ok(Self {
@@ -95,6 +130,7 @@ impl<T: Device + Send> TransactionHandle<T> {
                 digest: r.digest,
                 memo_count: 0,
                 ring_count: 0,
+                seq: 1,
             }),
         })
     }
@@ -104,7 +140,8 @@ impl<T: Device + Send> TransactionHandle<T> {
         let mut buff = [0u8; 256];
 
         // Build request
-        let req = TxSetMessage::new(m);
+        let seq = self.next_seq();
+        let req = TxSetMessage::new(m).with_seq(seq);
 
         // Update transaction digest
 
@@ -119,13 +156,36 @@ impl<T: Device + Send> TransactionHandle<T> {
             .request::<TxInfo>(req, &mut buff, self.info.request_timeout)
             .await?;
 
-        // Check state and expected digest
+        // Check state, sequence, and expected digest
         check_state(resp.state, TxState::Pending)?;
+        check_seq(resp.seq, seq)?;
         check_digest(&resp.digest, &digest)?;
 
         Ok(())
     }
 
+    /// Fetch the sequence number for the next mutating request
+    pub(crate) fn next_seq(&self) -> u8 {
+        let mut state = self.state.borrow_mut();
+        state.seq = next_seq(state.seq);
+        state.seq
+    }
+
+    /// Update the transaction digest with a state-mutating request
+    pub(crate) fn update_digest(&self, h: &[u8; 32]) -> Digest {
+        let mut state = self.state.borrow_mut();
+        Digest::update(&mut state.digest, h).clone()
+    }
+
+    /// Set the payload MAC for a streamed request using the current
+    /// transaction digest, then update the digest with the request
+    pub(crate) fn authenticate<R: TxAuthenticated>(&self, req: R) -> R {
+        let mut state = self.state.borrow_mut();
+        let req = req.authenticate(&state.digest);
+        Digest::update(&mut state.digest, &req.event_hash());
+        req
+    }
+
     /// Await on-device transaction approval
     pub async fn await_approval(&mut self, timeout_s: u32) -> Result<(), Error> {
         let mut buff = [0u8; 256];
@@ -155,15 +215,53 @@ impl<T: Device + Send> TransactionHandle<T> {
         Err(Error::UserTimeout)
     }
 
-    /// Signal transaction completion
-    pub async fn complete(mut self) -> Result<(), Error> {
+    /// Fetch time remaining before the device aborts the in-progress
+    /// transaction, `None` where no transaction watchdog is running
+    pub async fn time_remaining(&self) -> Result<Option<Duration>, Error> {
         let mut buff = [0u8; 256];
 
-        let _r = self
-            .request::<TxInfo>(TxComplete, &mut buff, self.info.request_timeout)
+        let mut t = self.t.lock().await;
+
+        let r = t
+            .request::<TxInfo>(TxInfoReq {}, &mut buff, self.info.request_timeout)
             .await?;
 
-        Ok(())
+        debug!("transaction timeout: {}s", r.timeout_s);
+
+        Ok(match r.timeout_s {
+            0 => None,
+            v => Some(Duration::from_secs(v as u64)),
+        })
+    }
+
+    /// Signal transaction completion, returning the device digest over the
+    /// signed transaction (see [SignedTxDigest][ledger_mob_apdu::digest::SignedTxDigest]) for archival
+    pub async fn complete(mut self) -> Result<[u8; 32], Error> {
+        let mut buff = [0u8; 256];
+
+        let r = self
+            .request::<TxCompleteResp>(TxComplete, &mut buff, self.info.request_timeout)
+            .await?;
+
+        debug!("signed transaction digest: {:02x?}", r.signed_digest);
+
+        Ok(r.signed_digest)
+    }
+
+    /// Abort the in-progress transaction, clearing on-device signing
+    /// contexts and returning the final transaction digest
+    pub async fn abort(mut self) -> Result<Digest, Error> {
+        let mut buff = [0u8; 256];
+
+        let r = self
+            .request::<TxInfo>(TxAbort, &mut buff, self.info.request_timeout)
+            .await?;
+
+        check_state(r.state, TxState::TxAborted)?;
+
+        debug!("aborted transaction digest: {:02x?}", r.digest.as_bytes());
+
+        Ok(r.digest)
     }
 }
 
@@ -176,9 +274,18 @@ pub(crate) fn check_state(actual: TxState, expected: TxState) -> Result<(), Erro
     }
 }
 
+/// Helper to check request sequence numbers when executing transactions
+pub(crate) fn check_seq(actual: u8, expected: u8) -> Result<(), Error> {
+    if actual != expected {
+        Err(Error::SequenceMismatch(actual, expected))
+    } else {
+        Ok(())
+    }
+}
+
 /// Helper to check digest when executing transactions
 pub(crate) fn check_digest(actual: &Digest, expected: &Digest) -> Result<(), Error> {
-    if expected != actual {
+    if !ct_eq(expected.as_bytes(), actual.as_bytes()) {
         Err(Error::DigestMismatch)
     } else {
         Ok(())
@@ -4,6 +4,8 @@
 //!
 //!
 
+use std::{ops::Range, time::Duration};
+
 use futures::executor::block_on;
 use log::{debug, info};
 use rand_core::CryptoRngCore;
@@ -18,14 +20,17 @@ use mc_crypto_ring_signature_signer::{
 
 use ledger_mob_apdu::{state::TxState, tx::*};
 
-use crate::tx::check_state;
+use crate::{
+    batch,
+    tx::{check_seq, check_state},
+};
 
 use super::{Error, TransactionHandle};
 
 /// Sync [RingSigner] implementation for [TransactionHandle]
 ///
 /// Note: this MUST be called from a tokio context
-impl<T: Device> RingSigner for TransactionHandle<T> {
+impl<T: Device + Send> RingSigner for TransactionHandle<T> {
     /// Execute ring signing operation on ledger hw
     fn sign(
         &self,
@@ -49,7 +54,7 @@ impl<T: Device> RingSigner for TransactionHandle<T> {
     }
 }
 
-impl<T: Device> TransactionHandle<T> {
+impl<T: Device + Send> TransactionHandle<T> {
     /// Asynchronously execute a ring signing operation on ledger hardware.
     ///  
     /// See [RingSigner] trait for public / blocking API
@@ -65,7 +70,6 @@ impl<T: Device> TransactionHandle<T> {
         let mut t = self.t.lock().await;
 
         let ring_size = signable_ring.members.len();
-        let real_index = signable_ring.real_input_index;
 
         // Handle unsigned and pre-signed rings
         let (subaddress_index, onetime_key) =
@@ -79,6 +83,7 @@ impl<T: Device> TransactionHandle<T> {
         debug!("Start ring signing...");
 
         // Start ring signing
+        let seq = self.next_seq();
         let tx_init = TxRingInit::new(
             signable_ring.members.len() as u8,
             signable_ring.real_input_index as u8,
@@ -86,10 +91,14 @@ impl<T: Device> TransactionHandle<T> {
             signable_ring.input_secret.amount.value,
             *signable_ring.input_secret.amount.token_id,
             onetime_key,
-        );
+        )
+        .with_seq(seq);
+        self.update_digest(&tx_init.hash());
+
         let r = t
             .request::<TxInfo>(tx_init, &mut buff, self.info.request_timeout)
             .await?;
+        check_seq(r.seq, seq)?;
 
         // TODO: onetime_private_key looks to be per-ring?
         // (must be to correlate with real_input.target_key..?)
@@ -99,44 +108,40 @@ impl<T: Device> TransactionHandle<T> {
         debug!("Set blindings");
 
         // Set blindings
-        let tx_set_blinding = TxSetBlinding {
-            blinding: signable_ring.input_secret.blinding,
-            output_blinding: pseudo_output_blinding,
-        };
+        let seq = self.next_seq();
+        let tx_set_blinding = self.authenticate(
+            TxSetBlinding::new(signable_ring.input_secret.blinding, pseudo_output_blinding)
+                .with_seq(seq),
+        );
         let r = t
             .request::<TxInfo>(tx_set_blinding, &mut buff, self.info.request_timeout)
             .await?;
+        check_seq(r.seq, seq)?;
 
         debug!("Ring state: {:?}", r);
 
         info!("Loading {} txouts", ring_size);
 
-        // Load txouts into ring
-        for n in 0..ring_size {
-            let i = (real_index + n) % ring_size;
-            let tx_out = &signable_ring.members[i];
-
-            let tx_add_txout = TxAddTxOut::new(
-                i as u8,
-                tx_out.public_key,
-                tx_out.target_key,
-                CompressedRistrettoPublic::from(tx_out.commitment.point),
-            );
-
-            let r = t
-                .request::<TxInfo>(tx_add_txout, &mut buff, self.info.request_timeout)
+        // Load txouts into ring, batching requests where enabled
+        let step = self.info.ring_batch.max(1);
+        for n in (0..ring_size).step_by(step) {
+            let end = (n + step).min(ring_size);
+            self.add_txouts(&mut t, &mut buff, signable_ring, n..end)
                 .await?;
-
-            debug!("State: {:?}", r);
         }
 
         info!("Signing ring");
 
         // Generate signature
+        let seq = self.next_seq();
+        let tx_ring_sign = TxRingSign::default().with_seq(seq);
+        self.update_digest(&tx_ring_sign.hash());
+
         let r = t
-            .request::<TxInfo>(TxRingSign, &mut buff, self.info.request_timeout)
+            .request::<TxInfo>(tx_ring_sign, &mut buff, self.info.request_timeout)
             .await?;
         check_state(r.state, TxState::RingComplete)?;
+        check_seq(r.seq, seq)?;
 
         debug!("Requesting key image");
 
@@ -153,26 +158,8 @@ impl<T: Device> TransactionHandle<T> {
 
         // Fetch responses
         let mut responses = Vec::<CurveScalar>::new();
-        for i in 0..ring_size * 2 {
-            debug!("Requesting response {}", i);
-
-            let resp = t
-                .request::<TxResponse>(
-                    TxGetResponse::new(i as u8),
-                    &mut buff,
-                    self.info.request_timeout,
-                )
-                .await
-                .unwrap();
-
-            debug!("Response {}: {:?}", resp.ring_index, resp.scalar);
-
-            if resp.ring_index as usize != i {
-                return Err(Error::UnexpectedResponse);
-            }
-
-            responses.push(CurveScalar::from(resp.scalar));
-        }
+        self.fetch_responses(&mut t, &mut buff, ring_size * 2, false, &mut responses)
+            .await?;
 
         // Reconstruct signed ring
         let ring = RingMLSAG {
@@ -189,4 +176,270 @@ impl<T: Device> TransactionHandle<T> {
 
         Ok(ring)
     }
+
+    /// Asynchronously execute a batch of ring signing operations on ledger
+    /// hardware, fetching responses for each completed ring while the
+    /// following ring is loaded to reduce round-trips.
+    ///
+    /// Rings are provided with their associated pseudo output blinding,
+    /// signed rings are returned in the same order.
+    pub async fn ring_sign_pipelined(
+        &self,
+        rings: &[(&SignableInputRing, Scalar)],
+    ) -> Result<Vec<RingMLSAG>, Error> {
+        let mut buff = [0u8; 256];
+
+        let mut t = self.t.lock().await;
+
+        let mut signed = Vec::<RingMLSAG>::with_capacity(rings.len());
+
+        // Key image, c_zero and response count for the last signed ring
+        let mut prior: Option<(TxKeyImage, usize)> = None;
+
+        for (signable_ring, pseudo_output_blinding) in rings {
+            let ring_size = signable_ring.members.len();
+            let real_index = signable_ring.real_input_index;
+
+            // Handle unsigned and pre-signed rings
+            let (subaddress_index, onetime_key) =
+                match signable_ring.input_secret.onetime_key_derive_data {
+                    OneTimeKeyDeriveData::OneTimeKey(key) => (0, Some(key.into())),
+                    OneTimeKeyDeriveData::SubaddressIndex(i) => (i, None),
+                };
+
+            debug!("Start ring signing...");
+
+            // Start ring signing, the device retains responses for the prior ring
+            let seq = self.next_seq();
+            let tx_init = TxRingInit::new(
+                ring_size as u8,
+                real_index as u8,
+                subaddress_index,
+                signable_ring.input_secret.amount.value,
+                *signable_ring.input_secret.amount.token_id,
+                onetime_key,
+            )
+            .with_seq(seq);
+            self.update_digest(&tx_init.hash());
+
+            let r = t
+                .request::<TxInfo>(tx_init, &mut buff, self.info.request_timeout)
+                .await?;
+            check_seq(r.seq, seq)?;
+
+            debug!("Ring state: {:?}", r);
+
+            // Set blindings
+            let seq = self.next_seq();
+            let tx_set_blinding = self.authenticate(
+                TxSetBlinding::new(signable_ring.input_secret.blinding, *pseudo_output_blinding)
+                    .with_seq(seq),
+            );
+            let r = t
+                .request::<TxInfo>(tx_set_blinding, &mut buff, self.info.request_timeout)
+                .await?;
+            check_seq(r.seq, seq)?;
+
+            debug!("Ring state: {:?}", r);
+
+            info!("Loading {} txouts", ring_size);
+
+            // Fetch prior responses alongside txouts for the current ring
+            let mut prior_responses = Vec::<CurveScalar>::new();
+            let prior_count = prior.as_ref().map(|(_, n)| *n).unwrap_or(0);
+
+            let step = self.info.ring_batch.max(1);
+            for n in (0..ring_size).step_by(step) {
+                let end = (n + step).min(ring_size);
+                self.add_txouts(&mut t, &mut buff, signable_ring, n..end)
+                    .await?;
+
+                // Interleave two prior responses per txout
+                let n = prior_count.min(end * 2);
+                self.fetch_responses(&mut t, &mut buff, n, true, &mut prior_responses)
+                    .await?;
+            }
+
+            // Fetch any remaining prior responses
+            self.fetch_responses(&mut t, &mut buff, prior_count, true, &mut prior_responses)
+                .await?;
+
+            // Reconstruct prior signed ring
+            if let Some((TxKeyImage { key_image, c_zero }, _)) = prior.take() {
+                signed.push(RingMLSAG {
+                    c_zero: CurveScalar::from(c_zero),
+                    responses: prior_responses,
+                    key_image,
+                });
+            }
+
+            info!("Signing ring");
+
+            // Generate signature
+            let seq = self.next_seq();
+            let tx_ring_sign = TxRingSign::default().with_seq(seq);
+            self.update_digest(&tx_ring_sign.hash());
+
+            let r = t
+                .request::<TxInfo>(tx_ring_sign, &mut buff, self.info.request_timeout)
+                .await?;
+            check_state(r.state, TxState::RingComplete)?;
+            check_seq(r.seq, seq)?;
+
+            // Retrieve key image
+            let key_image = t
+                .request::<TxKeyImage>(TxGetKeyImage {}, &mut buff, self.info.request_timeout)
+                .await?;
+
+            debug!(
+                "Key image: {} c_zero: {:?}",
+                key_image.key_image, key_image.c_zero
+            );
+
+            prior = Some((key_image, ring_size * 2));
+        }
+
+        // Fetch responses for the final ring
+        if let Some((TxKeyImage { key_image, c_zero }, count)) = prior.take() {
+            let mut responses = Vec::<CurveScalar>::new();
+            self.fetch_responses(&mut t, &mut buff, count, false, &mut responses)
+                .await?;
+
+            signed.push(RingMLSAG {
+                c_zero: CurveScalar::from(c_zero),
+                responses,
+                key_image,
+            });
+        }
+
+        // Update ring count
+        {
+            let mut state = self.state.borrow_mut();
+            state.ring_count += signed.len();
+        }
+
+        Ok(signed)
+    }
+
+    /// Load txouts for the provided range of ring entries (offset from the
+    /// real input), using a batched request where more than one txout is provided
+    async fn add_txouts(
+        &self,
+        t: &mut T,
+        buff: &mut [u8],
+        signable_ring: &SignableInputRing,
+        entries: Range<usize>,
+    ) -> Result<(), Error> {
+        let ring_size = signable_ring.members.len();
+        let real_index = signable_ring.real_input_index;
+
+        let txouts: Vec<_> = entries
+            .map(|n| {
+                let i = (real_index + n) % ring_size;
+                let tx_out = &signable_ring.members[i];
+
+                self.authenticate(
+                    TxAddTxOut::new(
+                        i as u8,
+                        tx_out.public_key,
+                        tx_out.target_key,
+                        CompressedRistrettoPublic::from(tx_out.commitment.point),
+                    )
+                    .with_seq(self.next_seq()),
+                )
+            })
+            .collect();
+
+        // Send single txouts directly
+        if let [tx_add_txout] = &txouts[..] {
+            let r = t
+                .request::<TxInfo>(tx_add_txout.clone(), buff, self.info.request_timeout)
+                .await?;
+
+            debug!("State: {:?}", r);
+            check_seq(r.seq, tx_add_txout.seq())?;
+
+            return Ok(());
+        }
+
+        debug!("Loading {} txouts (batched)", txouts.len());
+
+        let r = batch::request_batch::<_, _, TxInfo>(t, &txouts, self.info.request_timeout).await?;
+
+        for (s, req) in r.iter().zip(&txouts) {
+            debug!("State: {:?}", s);
+            check_seq(s.seq, req.seq())?;
+        }
+
+        Ok(())
+    }
+
+    /// Fetch responses until `responses` contains `count` entries, using
+    /// multi-scalar requests where enabled by [TxConfig::response_batch][super::TxConfig]
+    async fn fetch_responses(
+        &self,
+        t: &mut T,
+        buff: &mut [u8],
+        count: usize,
+        prior: bool,
+        responses: &mut Vec<CurveScalar>,
+    ) -> Result<(), Error> {
+        let timeout = self.info.request_timeout;
+        let step = self.info.response_batch.min(TX_RESPONSES_MAX);
+
+        while responses.len() < count {
+            let i = responses.len();
+
+            // Fetch single responses where batching is disabled
+            if step <= 1 {
+                let req = match prior {
+                    true => TxGetResponse::prior(i as u8),
+                    false => TxGetResponse::new(i as u8),
+                };
+
+                let resp = Self::fetch_response(t, buff, timeout, req).await?;
+                responses.push(resp);
+
+                continue;
+            }
+
+            let n = step.min(count - i);
+            let req = match prior {
+                true => TxGetResponses::prior(i as u8, n as u8),
+                false => TxGetResponses::new(i as u8, n as u8),
+            };
+
+            debug!("Requesting responses {}..{} ({:?})", i, i + n, req.flags);
+
+            let resp = t.request::<TxResponses>(req, buff, timeout).await?;
+
+            if resp.start as usize != i || resp.scalars.len() != n {
+                return Err(Error::UnexpectedResponse);
+            }
+
+            responses.extend(resp.scalars.iter().map(|s| CurveScalar::from(*s)));
+        }
+
+        Ok(())
+    }
+
+    /// Fetch a single response scalar, checking the returned ring index
+    async fn fetch_response(
+        t: &mut T,
+        buff: &mut [u8],
+        timeout: Duration,
+        req: TxGetResponse,
+    ) -> Result<CurveScalar, Error> {
+        let i = req.ring_index;
+
+        debug!("Requesting response {} ({:?})", i, req.flags);
+
+        let resp = t.request::<TxResponse>(req, buff, timeout).await?;
+
+        if resp.ring_index != i {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        Ok(CurveScalar::from(resp.scalar))
+    }
 }
@@ -3,16 +3,18 @@
 use log::warn;
 use std::ops::Deref;
 
+use encdec::Encode;
 use ledger_lib::Device;
+use ledger_proto::ApduReq;
 
 use mc_core::account::ShortAddressHash;
 use mc_transaction_core::{BlockVersion, TxSummary};
 use mc_transaction_summary::TxSummaryUnblindingData;
 
-use ledger_mob_apdu::{state::TxState, tx::*};
+use ledger_mob_apdu::{chunk::CHUNK_DATA_MAX, state::TxState, tx::*};
 
-use super::{check_state, TransactionHandle};
-use crate::Error;
+use super::{check_seq, check_state, TransactionHandle};
+use crate::{chunk, Error};
 
 impl<T: Device + Send> TransactionHandle<T> {
     /// Load tx summary for signing operation, alternative to `set_message` for block versions > 3
@@ -33,18 +35,23 @@ impl<T: Device + Send> TransactionHandle<T> {
         let mut m = [0u8; 32];
         m[..message.len()].copy_from_slice(message);
 
-        let init = TxSummaryInit {
-            message: m,
-            block_version: *block_version.deref(),
-            num_inputs: summary.inputs.len() as u32,
-            num_outputs: summary.outputs.len() as u32,
-        };
+        let seq = self.next_seq();
+        let init = TxSummaryInit::new(
+            m,
+            *block_version.deref(),
+            summary.inputs.len() as u32,
+            summary.outputs.len() as u32,
+        )
+        .with_seq(seq);
+        self.update_digest(&init.hash());
+
         let resp = self
             .request::<TxInfo>(init, &mut buff, self.info.request_timeout)
             .await?;
 
-        // Check state and expected digest
+        // Check state, sequence, and expected digest
         check_state(resp.state, TxState::SummaryInit)?;
+        check_seq(resp.seq, seq)?;
         //check_digest::<T>(&resp.digest, &ctx.digest)?;
 
         warn!("Write {} TxOuts", summary.outputs.len());
@@ -59,21 +66,30 @@ impl<T: Device + Send> TransactionHandle<T> {
             let u = &unblinding.outputs[n];
 
             // Build tx out summary
-            let tx_out_summary = TxSummaryAddTxOut::new(
-                n as u8,
-                o.masked_amount.as_ref(),
-                o.target_key,
-                o.public_key,
-                o.associated_to_input_rules,
+            let seq = self.next_seq();
+            let tx_out_summary = self.authenticate(
+                TxSummaryAddTxOut::new(
+                    n as u8,
+                    o.masked_amount.as_ref(),
+                    o.target_key,
+                    o.public_key,
+                    o.associated_to_input_rules,
+                )
+                .with_seq(seq),
             );
 
-            // Submit tx out summary
-            let resp = self
-                .request::<TxInfo>(tx_out_summary, &mut buff, self.info.request_timeout)
-                .await?;
+            // Submit tx out summary, using the compact encoding where supported
+            let resp = match self.info.compact_summary {
+                true => {
+                    let req = TxSummaryAddTxOutCompact::from(tx_out_summary);
+                    self.request_summary(req, &mut buff).await?
+                }
+                false => self.request_summary(tx_out_summary, &mut buff).await?,
+            };
 
-            // Check state and expected digest
+            // Check state, sequence, and expected digest
             check_state(resp.state, TxState::SummaryAddTxOut)?;
+            check_seq(resp.seq, seq)?;
             //check_digest::<T>(&resp.digest, &ctx.digest)?;
 
             log::debug!("Address: {:?}", u.address);
@@ -93,18 +109,26 @@ impl<T: Device + Send> TransactionHandle<T> {
             };
 
             // Build tx out unblinding
-            let tx_out_unblinding = TxSummaryAddTxOutUnblinding::new(
-                n as u8,
-                &u.unmasked_amount,
-                u.address.as_ref().map(|a| (a, ShortAddressHash::from(a))),
-                fog_info,
-                u.tx_private_key.map(|k| k.into()),
-            )?;
+            let seq = self.next_seq();
+            let tx_out_unblinding = self.authenticate(
+                TxSummaryAddTxOutUnblinding::new(
+                    n as u8,
+                    &u.unmasked_amount,
+                    u.address.as_ref().map(|a| (a, ShortAddressHash::from(a))),
+                    fog_info,
+                    u.tx_private_key.map(|k| k.into()),
+                )?
+                .with_seq(seq),
+            );
 
-            // Submit tx out unblinding
-            let resp = self
-                .request::<TxInfo>(tx_out_unblinding, &mut buff, self.info.request_timeout)
-                .await?;
+            // Submit tx out unblinding, using the compact encoding where supported
+            let resp = match self.info.compact_summary {
+                true => {
+                    let req = TxSummaryAddTxOutUnblindingCompact::from(tx_out_unblinding);
+                    self.request_summary(req, &mut buff).await?
+                }
+                false => self.request_summary(tx_out_unblinding, &mut buff).await?,
+            };
 
             // Check state and expected digest
             let expected_state = match n < summary.outputs.len() - 1 {
@@ -112,6 +136,7 @@ impl<T: Device + Send> TransactionHandle<T> {
                 false => TxState::SummaryAddTxIn,
             };
             check_state(resp.state, expected_state)?;
+            check_seq(resp.seq, seq)?;
             //check_digest::<T>(&resp.digest, &ctx.digest)?;
         }
 
@@ -131,11 +156,15 @@ impl<T: Device + Send> TransactionHandle<T> {
             };
 
             // Build TxIn summary
-            let tx_in_summary = TxSummaryAddTxIn::new(
-                n as u8,
-                i.pseudo_output_commitment,
-                u.clone(),
-                input_rules_digest.as_ref(),
+            let seq = self.next_seq();
+            let tx_in_summary = self.authenticate(
+                TxSummaryAddTxIn::new(
+                    n as u8,
+                    i.pseudo_output_commitment,
+                    u.clone(),
+                    input_rules_digest.as_ref(),
+                )
+                .with_seq(seq),
             );
 
             // Submit tx out unblinding
@@ -149,16 +178,21 @@ impl<T: Device + Send> TransactionHandle<T> {
                 false => TxState::SummaryReady,
             };
             check_state(resp.state, expected_state)?;
+            check_seq(resp.seq, seq)?;
             //check_digest::<T>(&resp.digest, &ctx.digest)?;
         }
 
         warn!("Complete Tx Summary");
 
+        let seq = self.next_seq();
         let b = TxSummaryBuild {
+            seq,
             fee_value: summary.fee,
             fee_token_id: summary.fee_token_id,
             tombstone_block: summary.tombstone_block,
+            current_block: self.info.current_block,
         };
+        self.update_digest(&b.hash());
 
         // Submit summary build request
         let resp = self
@@ -166,8 +200,54 @@ impl<T: Device + Send> TransactionHandle<T> {
             .await?;
 
         check_state(resp.state, TxState::Pending)?;
+        check_seq(resp.seq, seq)?;
         //check_digest::<T>(&resp.digest, &ctx.digest)?;
 
         Ok(())
     }
+
+    /// Fetch the TxSummary report entries computed by the device,
+    /// following [TransactionHandle::set_tx_summary]
+    pub async fn tx_summary_report(&mut self) -> Result<Vec<ReportEntry>, Error> {
+        let mut buff = [0u8; 256];
+        let mut entries = Vec::new();
+
+        loop {
+            let resp = self
+                .request::<TxSummaryReportResp>(
+                    TxSummaryReportReq::new(entries.len() as u8),
+                    &mut buff,
+                    self.info.request_timeout,
+                )
+                .await?;
+
+            // Stop on empty pages to avoid spinning on malformed responses
+            if resp.entries.is_empty() {
+                break;
+            }
+
+            entries.extend_from_slice(&resp.entries);
+
+            if entries.len() >= resp.num_held as usize {
+                break;
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Submit a TxOut summary request, requests with fog URLs exceeding
+    /// the maximum APDU payload are sent using the chunked exchange
+    async fn request_summary<'a>(
+        &mut self,
+        req: impl ApduReq<'a> + Send,
+        buff: &mut [u8],
+    ) -> Result<TxInfo, Error> {
+        let timeout = self.info.request_timeout;
+
+        match req.encode_len()? > CHUNK_DATA_MAX {
+            true => chunk::request_chunked(self, req, buff, timeout).await,
+            false => Ok(self.request(req, buff, timeout).await?),
+        }
+    }
 }
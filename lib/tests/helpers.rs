@@ -55,7 +55,7 @@ pub async fn setup(seed: Option<String>) -> (GenericDriver, GenericHandle, Gener
     let api_level = match model {
         Model::NanoSP => "5".to_string(),
         Model::NanoX => "5".to_string(),
-        Model::NanoS => panic!("unsupported model"),
+        Model::NanoS => "1".to_string(),
     };
 
     println!("Using model: {model} ({driver_mode} driver)");
@@ -104,6 +104,8 @@ pub async fn setup(seed: Option<String>) -> (GenericDriver, GenericHandle, Gener
     println!("Launching speculos (http port: {http_port} apdu port: {apdu_port})");
 
     // Start simulator and wait for a moment for launch
+    // (a fresh instance per test, as seeds differ and ledger-sim does
+    // not support reattaching to existing containers)
     let driver = match driver_mode {
         DriverMode::Local => GenericDriver::Local(LocalDriver::new()),
         DriverMode::Docker => {
@@ -166,7 +168,28 @@ pub async fn approve_wallet_sync(h: &GenericHandle) {
     debug!("UI: Unlock");
 
     let buttons = &[
-        // Right button to move from info to allow
+        // Right button to move from info to account
+        Button::Right,
+        // Right button to move from account to allow
+        Button::Right,
+        // Both buttons to select allow
+        Button::Both,
+    ];
+
+    for b in buttons {
+        h.button(*b, Action::PressAndRelease).await.unwrap();
+    }
+}
+
+/// Run memo review UI where required for tests
+#[allow(unused)]
+pub async fn approve_memo(h: &GenericHandle) {
+    debug!("UI: Approve memo");
+
+    let buttons = &[
+        // Right button to move to memo screen
+        Button::Right,
+        // Right button to move to allow screen
         Button::Right,
         // Both buttons to select allow
         Button::Both,
@@ -190,6 +213,8 @@ pub async fn approve_tx_blind(h: &GenericHandle) {
         Button::Right,
         // Right button to move to hash screen
         Button::Right,
+        // Right button to move to fee screen
+        Button::Right,
         // Right button to move to allow screen
         Button::Right,
         // Both buttons to select allow
@@ -6,7 +6,7 @@ use ledger_mob_tests::memo;
 use ledger_sim::*;
 
 mod helpers;
-use helpers::setup;
+use helpers::{approve_memo, setup};
 
 const MNEMONIC: &str = "duck deal pretty pen thunder economy wide common goose fit engine main aisle curtain choose cube claim snake enroll detect brief history float unit";
 
@@ -21,7 +21,7 @@ async fn memo_hmac() -> anyhow::Result<()> {
     let (d, s, t) = setup(Some(format!("hex:{}", hex::encode(&seed)))).await;
 
     // Run memo signing test
-    memo::hmac(t, mnemonic, 11).await?;
+    memo::hmac(t, || approve_memo(&s), mnemonic, 11).await?;
 
     // Exit simulator
     d.exit(s).await?;
@@ -0,0 +1,52 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Public address tests
+
+use std::collections::HashSet;
+
+use tracing::info;
+
+use ledger_lib::Device;
+use ledger_mob_apdu::tx::FogId;
+
+use ledger_mob::DeviceHandle;
+
+/// b58 alphabet (bitcoin) used for printable addresses
+const B58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Fetch b58 encoded public addresses for the first `n` subaddresses,
+/// with and without fog
+pub async fn test<T>(t: T, n: u64) -> anyhow::Result<()>
+where
+    T: Device + Send,
+{
+    let mut d = DeviceHandle::from(t);
+
+    let mut addresses = HashSet::new();
+
+    for fog_id in [FogId::None, FogId::MobMain] {
+        for index in 0..n {
+            info!("fetch public address {} (fog: {:?})", index, fog_id);
+
+            let r = d.public_address(0, index, fog_id).await?;
+
+            info!("received address: '{}'", r.b58);
+
+            // Check fog information matches the request
+            assert_eq!(r.fog_id, fog_id);
+            match fog_id {
+                FogId::None => assert!(r.fog_sig.is_empty()),
+                _ => assert_eq!(r.fog_sig.len(), 64),
+            }
+
+            // Check address is b58 encoded
+            assert!(!r.b58.is_empty());
+            assert!(r.b58.chars().all(|c| B58_ALPHABET.contains(c)));
+
+            // Addresses should be unique per subaddress and fog
+            assert!(addresses.insert(r.b58), "duplicate address");
+        }
+    }
+
+    Ok(())
+}
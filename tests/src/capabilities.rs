@@ -0,0 +1,36 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Capability / protocol version negotiation tests
+
+use tracing::info;
+
+use ledger_lib::Device;
+use ledger_mob_apdu::{capabilities::Features, MOB_PROTO_VERSION};
+
+use ledger_mob::DeviceHandle;
+
+/// Negotiate protocol version and features with the device
+pub async fn test<T>(t: T) -> anyhow::Result<()>
+where
+    T: Device + Send,
+{
+    let mut d = DeviceHandle::from(t);
+
+    let c = d.negotiate().await?;
+
+    info!("negotiated capabilities: {:?}", c);
+
+    // Current protocol version should be selected
+    assert_eq!(c.protocol_version, MOB_PROTO_VERSION);
+
+    // Chunked exchange should be supported
+    assert!(c.features.contains(Features::CHUNKED_APDU));
+
+    // Protocol version pinning should be supported (and applied on negotiation)
+    assert!(c.features.contains(Features::PROTOCOL_PIN));
+
+    // Negotiated capabilities are cached
+    assert_eq!(d.negotiate().await?, c);
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Chunked APDU exchange tests
+
+use std::time::Duration;
+
+use tracing::info;
+
+use ledger_lib::Device;
+use ledger_mob_apdu::{
+    chunk::CHUNK_BUFF_LEN,
+    wallet_keys::{WalletKeyReq, WalletKeyResp},
+};
+
+use ledger_mob::DeviceHandle;
+
+/// Fetch account keys via a chunked exchange, checking these match
+/// keys fetched via a direct request.
+///
+/// The device must be unlocked prior to executing this test.
+pub async fn test<T>(t: T) -> anyhow::Result<()>
+where
+    T: Device + Send,
+{
+    let mut d = DeviceHandle::from(t);
+    let timeout = Duration::from_secs(2);
+
+    // Fetch account keys directly
+    let mut buff = [0u8; 256];
+    let expected = d
+        .request::<WalletKeyResp>(WalletKeyReq::new(0), &mut buff, timeout)
+        .await?;
+
+    // Fetch account keys via chunked exchange
+    let mut chunk_buff = [0u8; CHUNK_BUFF_LEN];
+    let resp = d
+        .request_chunked::<WalletKeyResp>(WalletKeyReq::new(0), &mut chunk_buff, timeout)
+        .await?;
+
+    info!("chunked response: {:?}", resp);
+
+    assert_eq!(resp, expected);
+
+    Ok(())
+}
@@ -20,7 +20,7 @@ use mc_util_from_random::FromRandom;
 
 use ledger_lib::Device;
 
-use ledger_mob::DeviceHandle;
+use ledger_mob::{DeviceHandle, Error};
 
 /// Test key image recovery via subaddress and tx_out_public_key
 pub async fn test<T>(t: T, mnemonic: Mnemonic) -> anyhow::Result<()>
@@ -70,3 +70,63 @@ where
 
     Ok(())
 }
+
+/// Test key image recovery checked against the expected subaddress spend public key
+pub async fn test_checked<T>(t: T, mnemonic: Mnemonic) -> anyhow::Result<()>
+where
+    T: Device + Send,
+{
+    info!("using mnemonic: '{}'", mnemonic.phrase());
+
+    // Derive base key from seed
+    let slip10key = mnemonic.derive_slip10_key(0);
+    let account_key = Account::from(&slip10key);
+
+    let subaddress_index = 102;
+    let target_subaddr = account_key.subaddress(subaddress_index);
+
+    let mut d = DeviceHandle::from(t);
+
+    // Synthesize transaction for key image recovery
+    let tx_private_key = RistrettoPrivate::from_random(&mut OsRng {});
+
+    let tx_out_public =
+        create_tx_out_public_key(&tx_private_key, target_subaddr.spend_public_key().as_ref());
+
+    let onetime_private_key = recover_onetime_private_key(
+        &tx_out_public,
+        account_key.view_private_key().as_ref(),
+        target_subaddr.spend_private_key().as_ref(),
+    );
+
+    // Resolve key image, checking the device derived the expected subaddress
+    let key_image = d
+        .key_image_checked(
+            0,
+            subaddress_index,
+            tx_out_public,
+            target_subaddr.spend_public_key().as_ref(),
+        )
+        .await?;
+
+    info!("received key_image: '{:?}'", key_image);
+
+    assert_eq!(key_image, KeyImage::from(&onetime_private_key),);
+
+    // Mismatched spend public keys are rejected
+    let other_subaddr = account_key.subaddress(subaddress_index + 1);
+    let r = d
+        .key_image_checked(
+            0,
+            subaddress_index,
+            tx_out_public,
+            other_subaddr.spend_public_key().as_ref(),
+        )
+        .await;
+    assert!(
+        matches!(r, Err(Error::InvalidKey)),
+        "unexpected result: {r:?}"
+    );
+
+    Ok(())
+}
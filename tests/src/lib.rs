@@ -9,6 +9,8 @@ pub mod wallet;
 
 pub mod subaddress;
 
+pub mod address;
+
 pub mod key_image;
 
 pub mod mlsag;
@@ -19,4 +21,12 @@ pub mod transaction;
 
 pub mod ident;
 
+pub mod chunk;
+
+pub mod capabilities;
+
+pub mod session;
+
+pub mod settings;
+
 pub use ledger_mob::Error;
@@ -1,5 +1,7 @@
 // Copyright (c) 2022-2023 The MobileCoin Foundation
 
+use std::str::FromStr;
+
 use clap::Parser;
 use strum::{Display, EnumString, EnumVariantNames};
 use tracing::{debug, error, info, metadata::LevelFilter};
@@ -7,6 +9,7 @@ use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
 use ledger_lib::{Device, Filters, LedgerProvider, Transport};
 
+use ledger_mob_apdu::tx::FogId;
 use ledger_mob_tests::transaction::TransactionExpectation;
 use mc_core::slip10::{Language, Mnemonic};
 
@@ -49,12 +52,26 @@ pub enum Tests {
     List,
     /// Test wallet key derivation
     WalletKeys,
+    /// Test attested wallet / subaddress key requests
+    WalletKeysAttested,
     /// Test subaddress key derivation
     SubaddressKeys {
         /// Number of subaddresses to derive
         #[clap(long, default_value = "16")]
         n: u64,
     },
+    /// Test batched subaddress key requests
+    SubaddressKeysBatch {
+        /// Number of subaddresses to fetch
+        #[clap(long, default_value = "16")]
+        n: u64,
+    },
+    /// Test b58 public address requests
+    PublicAddress {
+        /// Number of subaddresses to fetch
+        #[clap(long, default_value = "4")]
+        n: u64,
+    },
     /// Test Memo HMAC / signing
     MemoSign,
     /// Test MLSAG signing
@@ -70,6 +87,18 @@ pub enum Tests {
         #[clap(long)]
         input: String,
     },
+    /// Test chunked APDU exchange
+    Chunk,
+    /// Test capability negotiation
+    Capabilities,
+    /// Test encrypted APDU sessions
+    Session,
+    /// Test fog id change requests (requires on-device confirmation)
+    SetFog {
+        /// Fog report URL (empty for no fog)
+        #[clap(long, default_value = "")]
+        fog_url: String,
+    },
 }
 
 #[tokio::main]
@@ -160,10 +189,13 @@ where
 
     match opts.test {
         Tests::WalletKeys => wallet::test(target, || async {}, opts.mnemonic).await?,
+        Tests::WalletKeysAttested => wallet::attested(target, opts.mnemonic).await?,
         Tests::SubaddressKeys { n } => {
             subaddress::test(target, || async {}, opts.mnemonic, n).await?
         }
-        Tests::MemoSign => memo::hmac(target, opts.mnemonic, 0).await?,
+        Tests::SubaddressKeysBatch { n } => subaddress::batch(target, opts.mnemonic, n).await?,
+        Tests::PublicAddress { n } => address::test(target, n).await?,
+        Tests::MemoSign => memo::hmac(target, || async {}, opts.mnemonic, 0).await?,
         Tests::Mlsag { ring_size } => {
             mlsag::test(target, || async {}, opts.mnemonic, ring_size).await?
         }
@@ -179,6 +211,14 @@ where
             )
             .await?
         }
+        Tests::Chunk => chunk::test(target).await?,
+        Tests::Capabilities => capabilities::test(target).await?,
+        Tests::Session => session::test(target).await?,
+        Tests::SetFog { fog_url } => {
+            let fog_id = FogId::from_str(&fog_url)
+                .map_err(|_| anyhow::anyhow!("unsupported fog url: '{}'", fog_url))?;
+            settings::fog(target, || async {}, fog_id).await?
+        }
         Tests::List => unreachable!(),
     }
 
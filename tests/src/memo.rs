@@ -2,7 +2,7 @@
 
 //! Memo signing tests
 
-use std::time::Duration;
+use std::{future::Future, time::Duration};
 
 use base64::{engine::general_purpose::STANDARD, Engine as _};
 use mc_crypto_memo_mac::compute_category1_hmac;
@@ -21,15 +21,27 @@ use mc_util_from_random::FromRandom;
 
 use ledger_lib::Device;
 
-use ledger_mob_apdu::tx::*;
-
-/// Test memo HMAC signing and verification
-pub async fn hmac<T>(mut t: T, mnemonic: Mnemonic, _ring_size: usize) -> anyhow::Result<()>
+use ledger_mob::DeviceHandle;
+use ledger_mob_apdu::{state::TxState, tx::*};
+
+/// Test memo HMAC signing and verification, with `approve` called
+/// where the first memo is held for on-device review
+pub async fn hmac<T, F>(
+    t: T,
+    approve: impl Fn() -> F,
+    mnemonic: Mnemonic,
+    _ring_size: usize,
+) -> anyhow::Result<()>
 where
-    T: Device,
+    T: Device + Send,
+    F: Future<Output = ()>,
 {
     let mut buff = [0u8; 256];
 
+    // Connect via device handle, pinning the protocol version
+    let mut t = DeviceHandle::from(t);
+    t.app_info().await?;
+
     debug!("using mnemonic: '{}'", mnemonic.phrase());
 
     // Derive base key from seed
@@ -65,39 +77,65 @@ where
 
     debug!("State: {:?}", r);
 
-    // Sign memo
-    let mut payload = [0u8; 48];
-    OsRng {}.fill_bytes(&mut payload);
-
-    let tx_memo_sign = TxMemoSign::new(
-        DEFAULT_SUBADDRESS_INDEX,
-        tx_out_public_key.into(),
-        &target_subaddr,
-        [0, 1],
-        payload,
-    );
-
-    debug!("Request memo sign");
-    let r = t
-        .request::<TxMemoSig>(tx_memo_sign, &mut buff, Duration::from_secs(1))
-        .await
-        .unwrap();
-
     // Reverse KX using receiver subaddress spend private and sender subaddress view public
     let target_view_private: &RistrettoPrivate = target_subaddr.view_private_key().as_ref();
     let sender_spend_public = sender_subaddr.spend_public_key();
     let shared_secret = target_view_private.key_exchange(sender_spend_public.as_ref());
 
-    // Re-compute HMAC
-    let hmac_value = compute_category1_hmac(
-        shared_secret.as_ref(),
-        &CompressedRistrettoPublic::from(&tx_out_public_key),
-        [0, 1],
-        &payload,
-    );
-
-    // Check values match
-    assert_eq!(hmac_value, r.hmac);
+    // Sign authenticated sender memos, with and without payment request / intent ids
+    for (kind, payment_id) in [
+        ([0x01, 0x00], None),
+        ([0x01, 0x01], Some(OsRng {}.next_u64() | 1)),
+        ([0x01, 0x02], Some(OsRng {}.next_u64() | 1)),
+    ] {
+        // Build memo payload (address hash, optional id, zeroed unused bytes)
+        let mut payload = [0u8; 48];
+        OsRng {}.fill_bytes(&mut payload[..16]);
+        if let Some(id) = payment_id {
+            payload[16..24].copy_from_slice(&id.to_be_bytes());
+        }
+
+        let tx_memo_sign = TxMemoSign::new(
+            DEFAULT_SUBADDRESS_INDEX,
+            tx_out_public_key.into(),
+            &target_subaddr,
+            kind,
+            payload,
+        );
+
+        debug!("Request memo sign (kind: {:02x?})", kind);
+        let r = match t
+            .request::<TxMemoSig>(tx_memo_sign.clone(), &mut buff, Duration::from_secs(1))
+            .await
+        {
+            Ok(v) => v,
+            // Approve memo review and re-issue the request
+            Err(_) => {
+                let s = t
+                    .request::<TxInfo>(TxInfoReq {}, &mut buff, Duration::from_secs(1))
+                    .await
+                    .unwrap();
+                assert_eq!(s.state, TxState::MemoPending);
+
+                approve().await;
+
+                t.request::<TxMemoSig>(tx_memo_sign, &mut buff, Duration::from_secs(1))
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // Re-compute HMAC
+        let hmac_value = compute_category1_hmac(
+            shared_secret.as_ref(),
+            &CompressedRistrettoPublic::from(&tx_out_public_key),
+            kind,
+            &payload,
+        );
+
+        // Check values match
+        assert_eq!(hmac_value, r.hmac);
+    }
 
     Ok(())
 }
@@ -27,22 +27,26 @@ use mc_util_from_random::FromRandom;
 
 use ledger_lib::Device;
 
-use ledger_mob::Error;
+use ledger_mob::{DeviceHandle, Error};
 use ledger_mob_apdu::{state::TxState, tx::*};
 
 /// Start a transaction and sign a ring via [RingMLSAGParameters] object
 pub async fn test<T, F>(
-    mut t: T,
+    t: T,
     approve: impl Fn() -> F,
     mnemonic: Mnemonic,
     ring_size: usize,
 ) -> anyhow::Result<()>
 where
-    T: Device,
+    T: Device + Send,
     F: Future<Output = ()>,
 {
     let mut buff = [0u8; 256];
 
+    // Connect via device handle, pinning the protocol version
+    let mut t = DeviceHandle::from(t);
+    t.app_info().await?;
+
     debug!("using mnemonic: '{}'", mnemonic.phrase());
 
     // Derive base key from seed
@@ -136,10 +140,7 @@ where
     debug!("State: {:?}", r);
 
     // Set blindings
-    let tx_set_blinding = TxSetBlinding {
-        blinding: params.blinding,
-        output_blinding: pseudo_output_blinding,
-    };
+    let tx_set_blinding = TxSetBlinding::new(params.blinding, pseudo_output_blinding);
     info!("Set blindings: {:?}", tx_set_blinding);
     let r = t
         .request::<TxInfo>(tx_set_blinding, &mut buff, Duration::from_secs(1))
@@ -176,7 +177,7 @@ where
 
     // Generate signature
     let r = t
-        .request::<TxInfo>(TxRingSign, &mut buff, Duration::from_secs(1))
+        .request::<TxInfo>(TxRingSign::default(), &mut buff, Duration::from_secs(1))
         .await
         .unwrap();
     assert_eq!(
@@ -0,0 +1,49 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Encrypted APDU session tests
+
+use std::time::Duration;
+
+use tracing::info;
+
+use ledger_lib::Device;
+use ledger_mob_apdu::wallet_keys::{WalletKeyReq, WalletKeyResp};
+
+use ledger_mob::{DeviceHandle, SessionDevice};
+
+/// Fetch account keys via an encrypted session, checking these match
+/// keys fetched via a direct request.
+///
+/// The device must be unlocked prior to executing this test.
+pub async fn test<T>(t: T) -> anyhow::Result<()>
+where
+    T: Device + Send,
+{
+    let mut s = SessionDevice::new(t);
+    let timeout = Duration::from_secs(2);
+
+    // Fetch account keys directly (prior to session establishment)
+    let mut buff = [0u8; 256];
+    let expected = s
+        .request::<WalletKeyResp>(WalletKeyReq::new(0), &mut buff, timeout)
+        .await?;
+
+    // Establish session
+    s.open(timeout).await?;
+    assert!(s.is_open());
+
+    // Fetch account keys via session, wrapped by the handle
+    let mut d = DeviceHandle::from(s);
+    for _i in 0..2 {
+        let mut buff = [0u8; 256];
+        let resp = d
+            .request::<WalletKeyResp>(WalletKeyReq::new(0), &mut buff, timeout)
+            .await?;
+
+        info!("session response: {:?}", resp);
+
+        assert_eq!(resp, expected);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// Copyright (c) 2022-2023 The MobileCoin Foundation
+
+//! Tests for host-initiated settings changes
+//!
+
+use std::{future::Future, time::Duration};
+
+use ledger_lib::Device;
+
+use ledger_mob_apdu::{
+    settings::SetFogIdReq,
+    state::TxState,
+    tx::{FogId, TxInfo, TxInfoReq},
+};
+
+/// Test fog id change requests
+///
+/// Approval is polled to allow for manual confirmation on physical devices
+pub async fn fog<T, F>(mut t: T, approve: impl Fn() -> F, fog_id: FogId) -> anyhow::Result<()>
+where
+    T: Device,
+    F: Future<Output = ()>,
+{
+    let mut buff = [0u8; 256];
+
+    // Issue fog change request
+    let resp = t
+        .request::<TxInfo>(SetFogIdReq::new(fog_id), &mut buff, Duration::from_secs(1))
+        .await
+        .expect("TxInfo APDU exchange failed");
+
+    // Check pending state
+    assert_eq!(resp.state, TxState::FogPending, "expected fog pending");
+
+    // Execute approver
+    approve().await;
+
+    // Await approval
+    for _i in 0..30 {
+        let resp = t
+            .request::<TxInfo>(TxInfoReq, &mut buff, Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        match resp.state {
+            TxState::FogApproved => return Ok(()),
+            TxState::FogPending => tokio::time::sleep(Duration::from_secs(1)).await,
+            s => return Err(anyhow::anyhow!("unexpected state: {s}")),
+        }
+    }
+
+    Err(anyhow::anyhow!("fog change approval timeout"))
+}
@@ -75,3 +75,38 @@ where
 
     Ok(())
 }
+
+/// Fetch batched subaddress keys for the provided mnemonic
+pub async fn batch<T>(t: T, mnemonic: Mnemonic, n: u64) -> anyhow::Result<()>
+where
+    T: Device + Send,
+{
+    info!("using mnemonic: '{}'", mnemonic.phrase());
+
+    let slip10key = mnemonic.derive_slip10_key(0);
+    let account_key = Account::from(&slip10key);
+
+    let mut d = DeviceHandle::from(t);
+
+    // Fetch keys for a range not aligned to the batch size
+    let start = 5;
+    let keys = d.subaddress_keys_batch(0, start..start + n).await?;
+
+    assert_eq!(keys.len() as u64, n);
+
+    for (i, k) in keys.iter().enumerate() {
+        let subaddr = account_key.subaddress(start + i as u64);
+
+        info!(
+            "received subaddress {} spend_public: '{}'",
+            start + i as u64,
+            k.spend_public
+        );
+
+        // Check subaddress keys match
+        assert_eq!(k.view_private, subaddr.view_private);
+        assert_eq!(k.spend_public, subaddr.spend_public_key());
+    }
+
+    Ok(())
+}
@@ -21,8 +21,13 @@ use mc_transaction_core::validation::validate_signature;
 use mc_transaction_signer::types::{TxSignReq, TxSignResp};
 use mc_transaction_summary::verify_tx_summary;
 
+use ledger_mob_apdu::{
+    digest::SignedTxDigest,
+    tx::{TxExpected, TX_RESPONSES_MAX},
+};
+
 use ledger_mob::{
-    tx::{TransactionHandle, TxConfig},
+    tx::{TransactionHandle, TxConfig, RING_BATCH_LEN},
     DeviceHandle,
 };
 
@@ -89,8 +94,9 @@ where
 
     trace!("Request: {:?}", req);
 
-    // Setup device handle
-    let d = DeviceHandle::from(t);
+    // Setup device handle and pin protocol version
+    let mut d = DeviceHandle::from(t);
+    d.negotiate().await?;
 
     info!("Starting transaction");
 
@@ -102,6 +108,15 @@ where
             num_rings: req.rings.len(),
             request_timeout: Duration::from_millis(500),
             user_timeout: Duration::from_secs(3),
+            current_block: None,
+            ring_batch: RING_BATCH_LEN,
+            response_batch: TX_RESPONSES_MAX,
+            compact_summary: true,
+            expected: Some(TxExpected {
+                fee_value: req.tx_prefix.fee,
+                fee_token_id: req.tx_prefix.fee_token_id,
+                tombstone_block: req.tx_prefix.tombstone_block,
+            }),
         },
         Arc::new(Mutex::new(d)),
     )
@@ -134,7 +149,7 @@ where
             let mut m = [0u8; 32];
             m.copy_from_slice(&digest.0[..]);
 
-            let (expected_digest, _report) = verify_tx_summary(
+            let (expected_digest, report) = verify_tx_summary(
                 &m,
                 &summary,
                 &unblinding,
@@ -148,6 +163,19 @@ where
                 &signing_data.mlsag_signing_digest[..],
                 "summary generated digest mismatch"
             );
+
+            // Check the device classified outputs the same way (dust may be folded)
+            debug!("Fetching tx summary report");
+            let entries = signer.tx_summary_report().await?;
+            assert!(entries.len() <= report.outputs.len());
+
+            for e in &entries {
+                let found = report
+                    .outputs
+                    .iter()
+                    .any(|(_, token_id, value)| **token_id == e.token_id && *value == e.value);
+                assert!(found, "device report entry {e:?} not found in host report");
+            }
         }
     }
 
@@ -180,8 +208,19 @@ where
 
     trace!("Response: {:?}", resp);
 
+    // Compute expected digest over the signed transaction
+    let mut expected = SignedTxDigest::new();
+    for r in &resp.tx.signature.ring_signatures {
+        expected.add_ring(&r.key_image, &r.c_zero, &r.responses);
+    }
+    let expected = expected.finalise(&signing_data.mlsag_signing_digest[..]);
+
     // Signal transaction is complete
-    signer.complete().await?;
+    let signed_digest = signer.complete().await?;
+    assert_eq!(
+        signed_digest, expected,
+        "signed transaction digest mismatch"
+    );
 
     info!("Transaction complete! validating signature");
 
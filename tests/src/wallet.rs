@@ -5,6 +5,7 @@
 use std::future::Future;
 
 use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rand::random;
 use tracing::info;
 
 use mc_core::{
@@ -69,3 +70,34 @@ where
 
     Ok(())
 }
+
+/// Fetch attested account and subaddress keys for the provided mnemonic,
+/// checking returned keys and attestations (requires prior approval)
+pub async fn attested<T>(t: T, mnemonic: Mnemonic) -> anyhow::Result<()>
+where
+    T: Device + Send,
+{
+    let account_key = Account::from(&mnemonic.derive_slip10_key(0));
+
+    let mut d = DeviceHandle::from(t);
+
+    // Fetch attested account keys, verifying attestation
+    let (a, k) = d.account_keys_attested(0, random()).await?;
+
+    info!("attestation key: '{}'", STANDARD.encode(k.as_bytes()));
+
+    assert_eq!(&a.view_private, account_key.view_private_key());
+    assert_eq!(a.spend_public, account_key.spend_public_key());
+
+    // Fetch attested subaddress keys, verifying attestation
+    let (s, k1) = d.subaddress_keys_attested(0, 1, random()).await?;
+
+    let subaddress = account_key.subaddress(1);
+    assert_eq!(s.view_private, subaddress.view_private);
+    assert_eq!(s.spend_public, subaddress.spend_public_key());
+
+    // Attestation key is stable across requests
+    assert_eq!(k, k1);
+
+    Ok(())
+}